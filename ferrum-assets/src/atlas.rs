@@ -0,0 +1,125 @@
+use crate::{AssetError, AssetManager, AssetResult};
+use ferrum_core::BlockId;
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// Tile size used for an atlas with no textures.
+const DEFAULT_TILE_SIZE: u32 = 16;
+
+/// Area of an atlas holding one texture, in UV coordinates (0 to 1, origin
+/// at the top left).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UvRect {
+    pub min: [f32; 2],
+    pub max: [f32; 2],
+}
+
+/// Block textures packed into one square, power-of-two sized image.
+#[derive(Debug, Clone)]
+pub struct AtlasImage {
+    pub image: RgbaImage,
+    /// Side length in pixels of every tile.
+    pub tile_size: u32,
+    /// Where each block's texture is in the image.
+    pub uvs: HashMap<BlockId, UvRect>,
+}
+
+impl AtlasImage {
+    pub fn uv(&self, block: BlockId) -> Option<UvRect> {
+        self.uvs.get(&block).copied()
+    }
+}
+
+impl AssetManager {
+    /// Pack the cached textures of `entries` into an atlas. Textures must
+    /// have been loaded with [`load_texture`](Self::load_texture) first.
+    ///
+    /// Textures are decoded in parallel and scaled to the largest size among
+    /// them, so mixed 16px and 32px textures share one tile size.
+    /// Animated textures, frames stacked vertically, keep their first frame.
+    /// Blocks sharing a texture share a tile.
+    pub fn build_atlas(&self, entries: &[(BlockId, String)]) -> AssetResult<AtlasImage> {
+        let mut paths: Vec<&str> = entries.iter().map(|(_, path)| path.as_str()).collect();
+        paths.sort_unstable();
+        paths.dedup();
+
+        let decoded = paths
+            .par_iter()
+            .map(|path| self.decode_cached(path))
+            .collect::<AssetResult<Vec<_>>>()?;
+
+        let tile_size = decoded
+            .iter()
+            .map(|texture| texture.width().min(texture.height()))
+            .max()
+            .unwrap_or(DEFAULT_TILE_SIZE);
+        let tiles: Vec<RgbaImage> = decoded
+            .into_par_iter()
+            .map(|texture| to_tile(texture, tile_size))
+            .collect();
+
+        // Smallest power-of-two square with room for every tile
+        let mut columns = 1;
+        while columns * columns < tiles.len() as u32 {
+            columns += 1;
+        }
+        let size = (columns * tile_size).next_power_of_two();
+        let columns = size / tile_size;
+
+        let mut image = RgbaImage::new(size, size);
+        let mut tile_uvs = HashMap::with_capacity(tiles.len());
+        for (index, (path, tile)) in paths.iter().zip(&tiles).enumerate() {
+            let x = index as u32 % columns * tile_size;
+            let y = index as u32 / columns * tile_size;
+            imageops::replace(&mut image, tile, x.into(), y.into());
+            let scale = 1.0 / size as f32;
+            tile_uvs.insert(
+                *path,
+                UvRect {
+                    min: [x as f32 * scale, y as f32 * scale],
+                    max: [
+                        (x + tile_size) as f32 * scale,
+                        (y + tile_size) as f32 * scale,
+                    ],
+                },
+            );
+        }
+
+        let uvs = entries
+            .iter()
+            .map(|(block, path)| (*block, tile_uvs[path.as_str()]))
+            .collect();
+        Ok(AtlasImage {
+            image,
+            tile_size,
+            uvs,
+        })
+    }
+
+    fn decode_cached(&self, path: &str) -> AssetResult<RgbaImage> {
+        let bytes = std::fs::read(self.cache_dir().join(path)).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                AssetError::NotCached(path.to_string())
+            } else {
+                e.into()
+            }
+        })?;
+        Ok(image::load_from_memory(&bytes)?.to_rgba8())
+    }
+}
+
+/// The first `width` × `width` frame of `texture`, scaled to `tile_size`.
+fn to_tile(mut texture: RgbaImage, tile_size: u32) -> RgbaImage {
+    let frame = texture.width().min(texture.height());
+    if texture.height() != frame || texture.width() != frame {
+        texture = imageops::crop_imm(&texture, 0, 0, frame, frame).to_image();
+    }
+    if frame == tile_size {
+        texture
+    } else {
+        // Nearest keeps pixel art crisp
+        imageops::resize(&texture, tile_size, tile_size, FilterType::Nearest)
+    }
+}
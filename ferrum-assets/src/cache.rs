@@ -0,0 +1,183 @@
+//! Size limit for the on-disk asset cache, evicting the least recently used
+//! files first.
+
+use crate::{AssetManager, AssetResult};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Cache files being written, which eviction must leave alone.
+#[derive(Default)]
+pub(crate) struct InFlightWrites(Mutex<HashSet<PathBuf>>);
+
+impl InFlightWrites {
+    /// Mark `path` as being written until the guard is dropped.
+    pub(crate) fn start(&self, path: PathBuf) -> WriteGuard<'_> {
+        if let Ok(mut writes) = self.0.lock() {
+            writes.insert(path.clone());
+        }
+        WriteGuard { writes: self, path }
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        self.0.lock().is_ok_and(|writes| writes.contains(path))
+    }
+}
+
+pub(crate) struct WriteGuard<'a> {
+    writes: &'a InFlightWrites,
+    path: PathBuf,
+}
+
+impl Drop for WriteGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut writes) = self.writes.0.lock() {
+            writes.remove(&self.path);
+        }
+    }
+}
+
+/// A cached file together with its checksum sidecar, evicted as one.
+struct CacheEntry {
+    files: Vec<PathBuf>,
+    size: u64,
+    last_used: SystemTime,
+}
+
+impl AssetManager {
+    /// Total size in bytes of this version's cache, checksum sidecars
+    /// included.
+    pub fn cache_size(&self) -> AssetResult<u64> {
+        Ok(cache_files(&self.cache_dir)?
+            .iter()
+            .map(|(_, metadata)| metadata.len())
+            .sum())
+    }
+
+    /// Delete the least recently used files of this version's cache until it
+    /// is no larger than `max_bytes`, returning the bytes freed.
+    ///
+    /// Files are ordered by last access, or by last modification where the
+    /// filesystem does not record access. A checksum sidecar counts towards
+    /// its file and is deleted with it. Files still being written are never
+    /// deleted, so the cache can stay over the limit while a download runs.
+    pub fn enforce_cache_limit(&self, max_bytes: u64) -> AssetResult<u64> {
+        let mut entries: HashMap<PathBuf, CacheEntry> = HashMap::new();
+        for (path, metadata) in cache_files(&self.cache_dir)? {
+            let is_sidecar = path
+                .extension()
+                .is_some_and(|extension| extension == "sha1");
+            let asset = if is_sidecar {
+                path.with_extension("")
+            } else {
+                path.clone()
+            };
+            let last_used = metadata
+                .accessed()
+                .or_else(|_| metadata.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            let entry = entries.entry(asset).or_insert(CacheEntry {
+                files: Vec::new(),
+                size: 0,
+                last_used,
+            });
+            entry.files.push(path);
+            entry.size += metadata.len();
+            // A file's own use decides when it goes, not its sidecar's
+            if !is_sidecar {
+                entry.last_used = last_used;
+            }
+        }
+
+        let mut total: u64 = entries.values().map(|entry| entry.size).sum();
+        let mut entries: Vec<_> = entries.into_iter().collect();
+        entries
+            .sort_by(|(a_path, a), (b_path, b)| (a.last_used, a_path).cmp(&(b.last_used, b_path)));
+
+        let mut freed = 0;
+        for (asset, entry) in entries {
+            if total <= max_bytes {
+                break;
+            }
+            if self.writes.contains(&asset) {
+                continue;
+            }
+            for file in &entry.files {
+                match fs::remove_file(file) {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                    _ => {}
+                }
+            }
+            total -= entry.size;
+            freed += entry.size;
+        }
+        Ok(freed)
+    }
+}
+
+/// Record a cache hit on `path` for eviction, since reads do not update the
+/// access time on every filesystem. Best effort.
+pub(crate) fn mark_used(path: &Path) {
+    if let Ok(file) = fs::File::open(path) {
+        let _ = file.set_times(fs::FileTimes::new().set_accessed(SystemTime::now()));
+    }
+}
+
+/// Every file under `dir` with its metadata. A missing directory is empty.
+fn cache_files(dir: &Path) -> AssetResult<Vec<(PathBuf, fs::Metadata)>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let listing = match fs::read_dir(&dir) {
+            Ok(listing) => listing,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        for entry in listing {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                dirs.push(entry.path());
+            } else if metadata.is_file() {
+                files.push((entry.path(), metadata));
+            }
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checksum;
+
+    #[tokio::test]
+    async fn test_files_being_written_are_kept() {
+        let cache = tempfile::TempDir::new().unwrap();
+        let manager = AssetManager::with_cache_root("1.20.1", cache.path())
+            .await
+            .unwrap();
+        let old = manager.cache_dir().join("old.png");
+        let writing = manager.cache_dir().join("writing.png");
+        fs::write(&writing, [0; 100]).unwrap();
+        fs::write(&old, [0; 100]).unwrap();
+        fs::write(checksum::sidecar_path(&old), "0".repeat(40)).unwrap();
+        fs::File::open(&writing)
+            .unwrap()
+            .set_times(fs::FileTimes::new().set_accessed(SystemTime::UNIX_EPOCH))
+            .unwrap();
+
+        // The oldest file is mid-write, so the next one goes instead
+        let guard = manager.writes.start(writing.clone());
+        assert_eq!(manager.enforce_cache_limit(0).unwrap(), 140);
+        assert!(writing.exists());
+        assert!(!old.exists());
+        assert!(!checksum::sidecar_path(&old).exists());
+
+        drop(guard);
+        assert_eq!(manager.enforce_cache_limit(0).unwrap(), 100);
+        assert_eq!(manager.cache_size().unwrap(), 0);
+    }
+}
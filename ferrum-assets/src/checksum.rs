@@ -0,0 +1,32 @@
+use crate::{AssetError, AssetResult};
+use sha1::{Digest, Sha1};
+use std::path::{Path, PathBuf};
+
+/// Lowercase hex SHA-1 of `data`, as Mojang's asset index writes it.
+pub fn sha1_hex(data: &[u8]) -> String {
+    Sha1::digest(data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Check `data` fetched for `path` against its `expected` hash.
+pub fn verify(path: &str, data: &[u8], expected: &str) -> AssetResult<()> {
+    let got = sha1_hex(data);
+    if got.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(AssetError::ChecksumMismatch {
+            path: path.to_string(),
+            expected: expected.to_string(),
+            got,
+        })
+    }
+}
+
+/// The file next to `cache_path` holding its expected hash.
+pub fn sidecar_path(cache_path: &Path) -> PathBuf {
+    let mut name = cache_path.as_os_str().to_owned();
+    name.push(".sha1");
+    PathBuf::from(name)
+}
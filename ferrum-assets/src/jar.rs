@@ -1,48 +1,200 @@
 use crate::{AssetError, AssetResult};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use zip::ZipArchive;
 
-pub async fn extract_asset(version: &str, path: &str) -> AssetResult<Vec<u8>> {
-    let jar_path = find_minecraft_jar(version)?;
-    
+pub async fn extract_asset(
+    locator: &JarLocator,
+    version: &str,
+    path: &str,
+) -> AssetResult<Vec<u8>> {
+    let jar_path = locator.locate(version)?;
+
     let jar_file = std::fs::File::open(&jar_path)?;
     let mut archive = ZipArchive::new(jar_file)?;
-    
+
     let asset_path = format!("assets/{}", path.strip_prefix("minecraft/").unwrap_or(path));
-    
+
     let mut file = archive.by_name(&asset_path).map_err(|_| {
         AssetError::Io(std::io::Error::new(
             std::io::ErrorKind::NotFound,
-            format!("Asset {} not found in JAR", asset_path)
+            format!("Asset {} not found in JAR", asset_path),
         ))
     })?;
-    
+
     let mut data = Vec::new();
     std::io::Read::read_to_end(&mut file, &mut data)?;
     Ok(data)
 }
 
-fn find_minecraft_jar(version: &str) -> AssetResult<PathBuf> {
-    let home = std::env::var("HOME")
-        .or_else(|_| std::env::var("USERPROFILE"))
-        .map_err(|_| AssetError::Io(std::io::Error::new(
-            std::io::ErrorKind::NotFound,
-            "Could not find home directory"
-        )))?;
-    
-    let minecraft_dir = PathBuf::from(home).join(".minecraft");
-    
-    let jar_path = minecraft_dir
-        .join("versions")
-        .join(version)
-        .join(format!("{}.jar", version));
-    
-    if jar_path.exists() {
-        Ok(jar_path)
-    } else {
-        Err(AssetError::Io(std::io::Error::new(
-            std::io::ErrorKind::NotFound,
-            format!("Minecraft JAR not found at {:?}", jar_path)
-        )))
+/// Directory layout used by a launcher to store client jars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LauncherLayout {
+    /// Official launcher: `versions/<v>/<v>.jar`
+    Vanilla,
+    /// MultiMC and Prism Launcher:
+    /// `libraries/com/mojang/minecraft/<v>/minecraft-<v>-client.jar`
+    MultiMc,
+    /// Modrinth App: `meta/versions/<v>/<v>.jar`
+    Modrinth,
+}
+
+impl LauncherLayout {
+    pub fn all() -> [LauncherLayout; 3] {
+        [
+            LauncherLayout::Vanilla,
+            LauncherLayout::MultiMc,
+            LauncherLayout::Modrinth,
+        ]
+    }
+
+    /// Path the jar for `version` would have under a launcher root of this
+    /// layout.
+    pub fn jar_path(self, root: &Path, version: &str) -> PathBuf {
+        match self {
+            LauncherLayout::Vanilla => root
+                .join("versions")
+                .join(version)
+                .join(format!("{}.jar", version)),
+            LauncherLayout::MultiMc => root
+                .join("libraries")
+                .join("com")
+                .join("mojang")
+                .join("minecraft")
+                .join(version)
+                .join(format!("minecraft-{}-client.jar", version)),
+            LauncherLayout::Modrinth => root
+                .join("meta")
+                .join("versions")
+                .join(version)
+                .join(format!("{}.jar", version)),
+        }
+    }
+}
+
+/// Finds the client jar for a game version across common launcher installs.
+///
+/// An explicit override is checked first. A file override is used as-is; a
+/// directory override is searched with every known layout. Resolved paths are
+/// cached per version and re-validated on each lookup.
+pub struct JarLocator {
+    override_path: Option<PathBuf>,
+    roots: Vec<(LauncherLayout, PathBuf)>,
+    resolved: Mutex<HashMap<String, PathBuf>>,
+}
+
+impl JarLocator {
+    /// Locator searching the default launcher directories for this platform.
+    pub fn new() -> Self {
+        Self::with_roots(default_roots())
+    }
+
+    /// Locator searching only the given launcher roots.
+    pub fn with_roots(roots: Vec<(LauncherLayout, PathBuf)>) -> Self {
+        Self {
+            override_path: None,
+            roots,
+            resolved: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_override(mut self, path: impl Into<PathBuf>) -> Self {
+        self.override_path = Some(path.into());
+        self
+    }
+
+    pub fn override_path(&self) -> Option<&Path> {
+        self.override_path.as_deref()
+    }
+
+    /// Every path that would be checked for `version`, in search order.
+    pub fn candidates(&self, version: &str) -> Vec<PathBuf> {
+        let mut candidates = Vec::new();
+
+        if let Some(path) = &self.override_path {
+            if path.is_dir() {
+                for layout in LauncherLayout::all() {
+                    candidates.push(layout.jar_path(path, version));
+                }
+            } else {
+                candidates.push(path.clone());
+            }
+        }
+
+        for (layout, root) in &self.roots {
+            candidates.push(layout.jar_path(root, version));
+        }
+
+        candidates
+    }
+
+    /// Resolve the jar path for `version`, using the cache when still valid.
+    pub fn locate(&self, version: &str) -> AssetResult<PathBuf> {
+        if let Ok(resolved) = self.resolved.lock() {
+            if let Some(path) = resolved.get(version) {
+                if path.is_file() {
+                    return Ok(path.clone());
+                }
+            }
+        }
+
+        let candidates = self.candidates(version);
+        match candidates.iter().find(|path| path.is_file()) {
+            Some(path) => {
+                if let Ok(mut resolved) = self.resolved.lock() {
+                    resolved.insert(version.to_string(), path.clone());
+                }
+                Ok(path.clone())
+            }
+            None => Err(AssetError::JarNotFound {
+                version: version.to_string(),
+                searched: candidates,
+            }),
+        }
+    }
+}
+
+impl Default for JarLocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn default_roots() -> Vec<(LauncherLayout, PathBuf)> {
+    let mut roots = Vec::new();
+
+    if let Ok(home) = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")) {
+        let home = PathBuf::from(home);
+        let data_dir = std::env::var("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| home.join(".local").join("share"));
+        let mac_support = home.join("Library").join("Application Support");
+
+        roots.push((LauncherLayout::Vanilla, home.join(".minecraft")));
+        roots.push((LauncherLayout::Vanilla, mac_support.join("minecraft")));
+        roots.push((LauncherLayout::MultiMc, data_dir.join("PrismLauncher")));
+        roots.push((LauncherLayout::MultiMc, data_dir.join("multimc")));
+        roots.push((LauncherLayout::MultiMc, mac_support.join("PrismLauncher")));
+        roots.push((LauncherLayout::Modrinth, data_dir.join("ModrinthApp")));
+        roots.push((
+            LauncherLayout::Modrinth,
+            data_dir.join("com.modrinth.theseus"),
+        ));
+        roots.push((LauncherLayout::Modrinth, mac_support.join("ModrinthApp")));
     }
+
+    if let Ok(appdata) = std::env::var("APPDATA") {
+        let appdata = PathBuf::from(appdata);
+        roots.push((LauncherLayout::Vanilla, appdata.join(".minecraft")));
+        roots.push((LauncherLayout::MultiMc, appdata.join("PrismLauncher")));
+        roots.push((LauncherLayout::MultiMc, appdata.join("MultiMC")));
+        roots.push((LauncherLayout::Modrinth, appdata.join("ModrinthApp")));
+        roots.push((
+            LauncherLayout::Modrinth,
+            appdata.join("com.modrinth.theseus"),
+        ));
+    }
+
+    roots
 }
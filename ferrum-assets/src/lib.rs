@@ -1,10 +1,23 @@
+mod atlas;
+mod cache;
+mod checksum;
 mod mojang;
 mod jar;
+mod model;
 mod prismarine;
+mod resource_pack;
 
+use ferrum_config::AssetsConfig;
+use futures_util::stream::{self, StreamExt};
+use std::future::Future;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+pub use atlas::{AtlasImage, UvRect};
+pub use jar::{JarLocator, LauncherLayout};
+pub use model::{BlockModel, Face, ModelElement, ModelFace};
+pub use resource_pack::ResourcePack;
+
 #[derive(Debug, Error)]
 pub enum AssetError {
     #[error("All asset sources failed: {0}")]
@@ -21,6 +34,36 @@ pub enum AssetError {
     
     #[error("ZIP error: {0}")]
     Zip(#[from] zip::result::ZipError),
+
+    #[error("Image error: {0}")]
+    Image(#[from] image::ImageError),
+
+    #[error("Checksum mismatch for {path}: expected {expected}, got {got}")]
+    ChecksumMismatch {
+        path: String,
+        expected: String,
+        got: String,
+    },
+
+    #[error("Block model parents form a cycle: {0}")]
+    ModelCycle(String),
+
+    #[error("Texture {0} is not cached, load it first")]
+    NotCached(String),
+
+    #[error("Minecraft {version} JAR not found, searched: {}", display_paths(.searched))]
+    JarNotFound {
+        version: String,
+        searched: Vec<PathBuf>,
+    },
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 pub type AssetResult<T> = Result<T, AssetError>;
@@ -29,52 +72,142 @@ pub struct AssetManager {
     version: String,
     cache_dir: PathBuf,
     client: reqwest::Client,
+    jar_locator: JarLocator,
+    writes: cache::InFlightWrites,
+    /// Serve only from the cache, never trying a source.
+    offline: bool,
+    /// Overlays checked before the cache, lowest first.
+    resource_packs: Vec<ResourcePack>,
 }
 
 impl AssetManager {
     pub async fn new(version: &str) -> AssetResult<Self> {
-        let cache_dir = Self::get_cache_dir(version)?;
+        Self::with_cache_root(version, Self::default_cache_root()?).await
+    }
+    
+    /// A manager caching under `root` instead of `~/.ferrum/cache/assets`,
+    /// each version in its own directory.
+    pub async fn with_cache_root(version: &str, root: impl Into<PathBuf>) -> AssetResult<Self> {
+        let cache_dir = root.into().join(version);
         tokio::fs::create_dir_all(&cache_dir).await?;
         
         Ok(Self {
             version: version.to_string(),
             cache_dir,
             client: reqwest::Client::new(),
+            jar_locator: JarLocator::new(),
+            writes: cache::InFlightWrites::default(),
+            offline: false,
+            resource_packs: Vec::new(),
         })
     }
     
+    /// A manager set up from the `[assets]` config: caching under its
+    /// `cache_dir` and extracting from its `jar_path`, if one is set.
+    pub async fn from_config(version: &str, config: &AssetsConfig) -> AssetResult<Self> {
+        let root = expand_home(&config.cache_dir)?.join("assets");
+        let manager = Self::with_cache_root(version, root).await?;
+        Ok(match &config.jar_path {
+            Some(jar_path) => manager.with_jar_path(jar_path),
+            None => manager,
+        })
+    }
+    
+    /// A manager that only serves cached assets, failing at once on anything
+    /// not cached instead of trying the network or a jar.
+    pub async fn offline(version: &str) -> AssetResult<Self> {
+        let mut manager = Self::new(version).await?;
+        manager.set_offline(true);
+        Ok(manager)
+    }
+    
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+    
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+    
+    /// Use `path` (a jar file or launcher directory) before searching launcher defaults.
+    pub fn with_jar_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.jar_locator = self.jar_locator.with_override(path);
+        self
+    }
+    
+    /// Layer the pack at `dir_or_zip` over every source. Packs added later
+    /// win over earlier ones, and what they serve is never cached.
+    pub fn add_resource_pack(&mut self, dir_or_zip: PathBuf) {
+        self.resource_packs.push(ResourcePack::new(dir_or_zip));
+    }
+    
+    pub fn resource_packs(&self) -> &[ResourcePack] {
+        &self.resource_packs
+    }
+    
     pub fn cache_dir(&self) -> &Path {
         &self.cache_dir
     }
     
+    pub fn jar_locator(&self) -> &JarLocator {
+        &self.jar_locator
+    }
+    
+    /// Load `path` from the newest resource pack that has it, then the
+    /// cache, or fetch and cache it. A cached file that no longer matches
+    /// the hash stored beside it is fetched again.
     pub async fn load_texture(&self, path: &str) -> AssetResult<Vec<u8>> {
-        let cache_path = self.cache_dir.join(path);
+        self.load_texture_with_progress(path, |_, _| {}).await
+    }
+    
+    /// Like [`load_texture`](Self::load_texture), calling `progress` with the
+    /// bytes received so far and the total size as a download streams in.
+    /// The total is `None` when the server does not send a
+    /// `Content-Length`. Files from a resource pack, the cache or the jar
+    /// report once, with their full size.
+    pub async fn load_texture_with_progress(
+        &self,
+        path: &str,
+        progress: impl Fn(u64, Option<u64>),
+    ) -> AssetResult<Vec<u8>> {
+        let report_whole = |data: &[u8]| progress(data.len() as u64, Some(data.len() as u64));
         
-        if cache_path.exists() {
-            return Ok(tokio::fs::read(&cache_path).await?);
+        for pack in self.resource_packs.iter().rev() {
+            if let Some(data) = pack.read(path)? {
+                report_whole(&data);
+                return Ok(data);
+            }
+        }
+        if let Some(data) = self.read_cached(path).await? {
+            report_whole(&data);
+            return Ok(data);
+        }
+        if self.offline {
+            return Err(AssetError::AllSourcesFailed("offline mode".to_string()));
         }
         
         let mut errors = Vec::new();
         
-        match mojang::fetch_asset(&self.client, &self.version, path).await {
-            Ok(data) => {
-                self.cache_asset(path, &data).await?;
+        match mojang::fetch_asset(&self.client, &self.version, path, &progress).await {
+            Ok((data, sha1)) => {
+                self.cache_asset(path, &data, Some(&sha1)).await?;
                 return Ok(data);
             }
             Err(e) => errors.push(format!("Mojang: {}", e)),
         }
         
-        match jar::extract_asset(&self.version, path).await {
+        match jar::extract_asset(&self.jar_locator, &self.version, path).await {
             Ok(data) => {
-                self.cache_asset(path, &data).await?;
+                report_whole(&data);
+                self.cache_asset(path, &data, None).await?;
                 return Ok(data);
             }
             Err(e) => errors.push(format!("JAR: {}", e)),
         }
         
-        match prismarine::fetch_asset(&self.client, &self.version, path).await {
+        match prismarine::fetch_asset(&self.client, &self.version, path, &progress).await {
             Ok(data) => {
-                self.cache_asset(path, &data).await?;
+                self.cache_asset(path, &data, None).await?;
                 return Ok(data);
             }
             Err(e) => errors.push(format!("PrismarineJS: {}", e)),
@@ -83,27 +216,230 @@ impl AssetManager {
         Err(AssetError::AllSourcesFailed(errors.join(", ")))
     }
     
-    async fn cache_asset(&self, path: &str, data: &[u8]) -> AssetResult<()> {
+    /// Load every path into the cache with at most `concurrency` loads in
+    /// flight. Paths already cached are skipped without fetching anything.
+    /// Results come back in the order of `paths`, and a failed path does not
+    /// stop the others.
+    pub async fn prefetch(
+        &self,
+        paths: &[&str],
+        concurrency: usize,
+    ) -> Vec<(String, AssetResult<()>)> {
+        prefetch_with(paths, concurrency, |path| self.prefetch_one(path)).await
+    }
+    
+    async fn prefetch_one(&self, path: &str) -> AssetResult<()> {
+        if self.read_cached(path).await?.is_some() {
+            return Ok(());
+        }
+        self.load_texture(path).await.map(|_| ())
+    }
+    
+    /// The cached bytes of `path`, or `None` if it is not cached or fails
+    /// its stored checksum. Files cached without a hash are trusted.
+    async fn read_cached(&self, path: &str) -> AssetResult<Option<Vec<u8>>> {
+        let cache_path = self.cache_dir.join(path);
+        if !cache_path.exists() {
+            return Ok(None);
+        }
+        let data = tokio::fs::read(&cache_path).await?;
+        
+        match tokio::fs::read_to_string(checksum::sidecar_path(&cache_path)).await {
+            Ok(expected) if checksum::verify(path, &data, expected.trim()).is_err() => {
+                return Ok(None)
+            }
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        cache::mark_used(&cache_path);
+        Ok(Some(data))
+    }
+    
+    /// Write `data` to the cache, with its `sha1` in a sidecar file when the
+    /// source gave one. The sidecar goes first, so a write cut short leaves
+    /// a file that fails its check rather than one that is trusted.
+    async fn cache_asset(&self, path: &str, data: &[u8], sha1: Option<&str>) -> AssetResult<()> {
         let cache_path = self.cache_dir.join(path);
+        let _writing = self.writes.start(cache_path.clone());
         if let Some(parent) = cache_path.parent() {
             tokio::fs::create_dir_all(parent).await?;
         }
+        let sidecar = checksum::sidecar_path(&cache_path);
+        match sha1 {
+            Some(sha1) => tokio::fs::write(&sidecar, sha1).await?,
+            None => match tokio::fs::remove_file(&sidecar).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            },
+        }
         tokio::fs::write(&cache_path, data).await?;
         Ok(())
     }
     
-    fn get_cache_dir(version: &str) -> AssetResult<PathBuf> {
-        let home = std::env::var("HOME")
-            .or_else(|_| std::env::var("USERPROFILE"))
-            .map_err(|_| AssetError::Io(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "Could not find home directory"
-            )))?;
-        
-        Ok(PathBuf::from(home)
+    fn default_cache_root() -> AssetResult<PathBuf> {
+        Ok(home_dir()?
             .join(".ferrum")
             .join("cache")
-            .join("assets")
-            .join(version))
+            .join("assets"))
+    }
+}
+
+fn home_dir() -> AssetResult<PathBuf> {
+    let home = std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .map_err(|_| AssetError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "Could not find home directory"
+        )))?;
+    Ok(PathBuf::from(home))
+}
+
+/// `path` with a leading `~` replaced by the home directory.
+fn expand_home(path: &str) -> AssetResult<PathBuf> {
+    if path == "~" {
+        return home_dir();
+    }
+    match path.strip_prefix("~/") {
+        Some(rest) => Ok(home_dir()?.join(rest)),
+        None => Ok(PathBuf::from(path)),
+    }
+}
+
+/// Read the body of `response` chunk by chunk, calling `progress` with the
+/// bytes received so far and the `Content-Length`, if the server sent one.
+pub(crate) async fn download(
+    response: reqwest::Response,
+    progress: &dyn Fn(u64, Option<u64>),
+) -> AssetResult<Vec<u8>> {
+    let total = response
+        .headers()
+        .get(reqwest::header::CONTENT_LENGTH)
+        .and_then(|length| length.to_str().ok())
+        .and_then(|length| length.parse::<u64>().ok());
+
+    let mut data = Vec::new();
+    let mut body = response.bytes_stream();
+    while let Some(chunk) = body.next().await {
+        data.extend_from_slice(&chunk?);
+        progress(data.len() as u64, total);
+    }
+    Ok(data)
+}
+
+/// Run `load` over `paths`, keeping at most `concurrency` of them in flight.
+async fn prefetch_with<'a, F, Fut>(
+    paths: &[&'a str],
+    concurrency: usize,
+    load: F,
+) -> Vec<(String, AssetResult<()>)>
+where
+    F: Fn(&'a str) -> Fut,
+    Fut: Future<Output = AssetResult<()>>,
+{
+    let load = &load;
+    stream::iter(paths.iter().copied())
+        .map(|path| async move { (path.to_string(), load(path).await) })
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve one request on a local port with `head` and then `chunks`
+    /// written separately, returning the URL.
+    async fn serve_once(head: &'static str, chunks: &'static [&'static str]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0; 1024];
+            let _ = socket.read(&mut request).await.unwrap();
+            socket.write_all(head.as_bytes()).await.unwrap();
+            for chunk in chunks {
+                socket.flush().await.unwrap();
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                socket.write_all(chunk.as_bytes()).await.unwrap();
+            }
+        });
+        url
+    }
+
+    /// Download `url`, returning the body and every progress report.
+    async fn download_reporting(url: &str) -> (Vec<u8>, Vec<(u64, Option<u64>)>) {
+        let reports = Mutex::new(Vec::new());
+        let response = reqwest::get(url).await.unwrap();
+        let data = download(response, &|done, total| {
+            reports.lock().unwrap().push((done, total))
+        })
+        .await
+        .unwrap();
+        (data, reports.into_inner().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_download_reports_progress_against_content_length() {
+        let url = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Length: 10\r\nConnection: close\r\n\r\n",
+            &["hello", "world"],
+        )
+        .await;
+        let (data, reports) = download_reporting(&url).await;
+
+        assert_eq!(data, b"helloworld");
+        assert_eq!(reports.last(), Some(&(10, Some(10))));
+        assert!(reports.len() >= 2, "{reports:?}");
+        assert!(reports.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[tokio::test]
+    async fn test_download_without_content_length_has_no_total() {
+        let url = serve_once(
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n",
+            &["5\r\nhello\r\n", "5\r\nworld\r\n", "0\r\n\r\n"],
+        )
+        .await;
+        let (data, reports) = download_reporting(&url).await;
+
+        assert_eq!(data, b"helloworld");
+        assert_eq!(reports.last(), Some(&(10, None)));
+        assert!(reports.iter().all(|(_, total)| total.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_respects_concurrency() {
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let paths: Vec<String> = (0..10).map(|i| format!("texture_{}.png", i)).collect();
+        let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+
+        let results = prefetch_with(&paths, 3, |path| {
+            let (in_flight, peak) = (&in_flight, &peak);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                match path {
+                    "texture_4.png" => Err(AssetError::NotCached(path.to_string())),
+                    _ => Ok(()),
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        assert_eq!(results.len(), 10);
+        for (i, (path, result)) in results.iter().enumerate() {
+            assert_eq!(path, &format!("texture_{}.png", i));
+            assert_eq!(result.is_err(), i == 4, "{}", path);
+        }
     }
 }
@@ -0,0 +1,242 @@
+use crate::{AssetError, AssetManager, AssetResult};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Side of a block or model element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Face {
+    Down,
+    Up,
+    North,
+    South,
+    West,
+    East,
+}
+
+impl Face {
+    pub fn all() -> [Face; 6] {
+        [
+            Face::Down,
+            Face::Up,
+            Face::North,
+            Face::South,
+            Face::West,
+            Face::East,
+        ]
+    }
+}
+
+/// One textured face of a model element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelFace {
+    /// Texture location such as `minecraft:block/stone`, or a `#variable`
+    /// that no model in the parent chain defines.
+    pub texture: String,
+    /// Area of the texture drawn on the face, in pixels of a 16x16 texture:
+    /// `[u1, v1, u2, v2]`. Faces without one in the JSON get the area
+    /// matching the element's position, as in vanilla.
+    pub uv: [f32; 4],
+    /// Side whose neighbour, when solid, hides this face.
+    pub cullface: Option<Face>,
+    /// Clockwise texture rotation in degrees, a multiple of 90.
+    pub rotation: u32,
+    pub tint_index: Option<i32>,
+}
+
+/// A box of a model, in 1/16ths of a block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelElement {
+    pub from: [f32; 3],
+    pub to: [f32; 3],
+    pub faces: HashMap<Face, ModelFace>,
+    pub shade: bool,
+}
+
+impl ModelElement {
+    /// The element's corners as `[from, to]`.
+    pub fn bounds(&self) -> [[f32; 3]; 2] {
+        [self.from, self.to]
+    }
+}
+
+/// A block model with its parents merged in and texture variables resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockModel {
+    /// Every texture variable in the chain, resolved as far as the chain
+    /// allows.
+    pub textures: HashMap<String, String>,
+    /// The elements of the nearest model in the chain that has any.
+    pub elements: Vec<ModelElement>,
+    pub ambient_occlusion: bool,
+}
+
+impl BlockModel {
+    /// The texture bound to `variable`, with or without its leading `#`.
+    pub fn texture(&self, variable: &str) -> Option<&str> {
+        self.textures
+            .get(variable.strip_prefix('#').unwrap_or(variable))
+            .map(String::as_str)
+            .filter(|texture| !texture.starts_with('#'))
+    }
+}
+
+#[derive(Deserialize)]
+struct RawModel {
+    parent: Option<String>,
+    #[serde(default)]
+    textures: HashMap<String, String>,
+    elements: Option<Vec<RawElement>>,
+    #[serde(rename = "ambientocclusion")]
+    ambient_occlusion: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct RawElement {
+    from: [f32; 3],
+    to: [f32; 3],
+    #[serde(default)]
+    faces: HashMap<Face, RawFace>,
+    #[serde(default = "default_shade")]
+    shade: bool,
+}
+
+#[derive(Deserialize)]
+struct RawFace {
+    texture: String,
+    uv: Option<[f32; 4]>,
+    cullface: Option<Face>,
+    #[serde(default)]
+    rotation: u32,
+    #[serde(rename = "tintindex")]
+    tint_index: Option<i32>,
+}
+
+fn default_shade() -> bool {
+    true
+}
+
+impl AssetManager {
+    /// Load the block model `name` (`block/stairs`, optionally with a
+    /// `minecraft:` namespace) and every parent it inherits from.
+    ///
+    /// Texture variables set lower in the chain override those set by
+    /// parents, and the model's elements are those of the nearest model
+    /// defining any. Parents under `builtin/` have no file and end the
+    /// chain. A model that is its own ancestor is an error.
+    pub async fn load_block_model(&self, name: &str) -> AssetResult<BlockModel> {
+        let mut chain: Vec<(String, RawModel)> = Vec::new();
+        let mut next = Some(model_name(name).to_string());
+        while let Some(name) = next.take() {
+            if chain.iter().any(|(seen, _)| *seen == name) {
+                let mut names: Vec<&str> = chain.iter().map(|(name, _)| name.as_str()).collect();
+                names.push(&name);
+                return Err(AssetError::ModelCycle(names.join(" -> ")));
+            }
+            let data = self
+                .load_texture(&format!("minecraft/models/{}.json", name))
+                .await?;
+            let model: RawModel = serde_json::from_slice(&data)?;
+            next = model
+                .parent
+                .as_deref()
+                .map(model_name)
+                .filter(|parent| !parent.starts_with("builtin/"))
+                .map(str::to_string);
+            chain.push((name, model));
+        }
+        Ok(resolve(chain.into_iter().map(|(_, model)| model).collect()))
+    }
+}
+
+/// `name` without its `minecraft:` namespace.
+fn model_name(name: &str) -> &str {
+    name.strip_prefix("minecraft:").unwrap_or(name)
+}
+
+/// Merge `chain`, the model first and its root ancestor last.
+fn resolve(chain: Vec<RawModel>) -> BlockModel {
+    let mut textures = HashMap::new();
+    for model in chain.iter().rev() {
+        textures.extend(model.textures.clone());
+    }
+    let resolved: HashMap<String, String> = textures
+        .keys()
+        .map(|variable| (variable.clone(), resolve_texture(&textures, variable)))
+        .collect();
+
+    let ambient_occlusion = chain
+        .iter()
+        .find_map(|model| model.ambient_occlusion)
+        .unwrap_or(true);
+    let elements = chain
+        .into_iter()
+        .find_map(|model| model.elements)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|element| ModelElement {
+            faces: element
+                .faces
+                .into_iter()
+                .map(|(face, raw)| {
+                    let texture = match raw.texture.strip_prefix('#') {
+                        Some(variable) => resolved
+                            .get(variable)
+                            .cloned()
+                            .unwrap_or_else(|| raw.texture.clone()),
+                        None => raw.texture.clone(),
+                    };
+                    let resolved_face = ModelFace {
+                        texture,
+                        uv: raw
+                            .uv
+                            .unwrap_or_else(|| default_uv(face, element.from, element.to)),
+                        cullface: raw.cullface,
+                        rotation: raw.rotation,
+                        tint_index: raw.tint_index,
+                    };
+                    (face, resolved_face)
+                })
+                .collect(),
+            from: element.from,
+            to: element.to,
+            shade: element.shade,
+        })
+        .collect();
+
+    BlockModel {
+        textures: resolved,
+        elements,
+        ambient_occlusion,
+    }
+}
+
+/// Follow `variable` through `#references` to a texture, stopping at a
+/// variable nothing defines or after as many steps as there are variables,
+/// which only a reference loop takes.
+fn resolve_texture(textures: &HashMap<String, String>, variable: &str) -> String {
+    let mut value = format!("#{}", variable);
+    for _ in 0..=textures.len() {
+        match value
+            .strip_prefix('#')
+            .and_then(|variable| textures.get(variable))
+        {
+            Some(next) => value = next.clone(),
+            None => break,
+        }
+    }
+    value
+}
+
+/// The texture area vanilla uses for `face` of the element from `from` to
+/// `to` when the model gives none.
+fn default_uv(face: Face, from: [f32; 3], to: [f32; 3]) -> [f32; 4] {
+    match face {
+        Face::Down => [from[0], 16.0 - to[2], to[0], 16.0 - from[2]],
+        Face::Up => [from[0], from[2], to[0], to[2]],
+        Face::North => [16.0 - to[0], 16.0 - to[1], 16.0 - from[0], 16.0 - from[1]],
+        Face::South => [from[0], 16.0 - to[1], to[0], 16.0 - from[1]],
+        Face::West => [from[2], 16.0 - to[1], to[2], 16.0 - from[1]],
+        Face::East => [16.0 - to[2], 16.0 - to[1], 16.0 - from[2], 16.0 - from[1]],
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{AssetError, AssetResult};
+use crate::{checksum, AssetError, AssetResult};
 use serde::Deserialize;
 
 #[derive(Deserialize)]
@@ -33,11 +33,14 @@ struct AssetObject {
     hash: String,
 }
 
+/// Download `path` and check it against the asset index, reporting the
+/// download's `progress`. Returns the bytes and their SHA-1.
 pub async fn fetch_asset(
     client: &reqwest::Client,
     version: &str,
     path: &str,
-) -> AssetResult<Vec<u8>> {
+    progress: &dyn Fn(u64, Option<u64>),
+) -> AssetResult<(Vec<u8>, String)> {
     let manifest_url = "https://launchermeta.mojang.com/mc/game/version_manifest.json";
     let manifest: VersionManifest = client.get(manifest_url).send().await?.json().await?;
     
@@ -73,6 +76,8 @@ pub async fn fetch_asset(
         hash
     );
     
-    let data = client.get(&asset_url).send().await?.bytes().await?;
-    Ok(data.to_vec())
+    let response = client.get(&asset_url).send().await?;
+    let data = crate::download(response, progress).await?;
+    checksum::verify(path, &data, hash)?;
+    Ok((data, hash.clone()))
 }
@@ -4,6 +4,7 @@ pub async fn fetch_asset(
     client: &reqwest::Client,
     version: &str,
     path: &str,
+    progress: &dyn Fn(u64, Option<u64>),
 ) -> AssetResult<Vec<u8>> {
     let asset_path = path.strip_prefix("minecraft/").unwrap_or(path);
     
@@ -22,6 +23,5 @@ pub async fn fetch_asset(
         )));
     }
     
-    let data = response.bytes().await?;
-    Ok(data.to_vec())
+    crate::download(response, progress).await
 }
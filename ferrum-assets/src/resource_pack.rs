@@ -0,0 +1,52 @@
+use crate::AssetResult;
+use std::path::{Path, PathBuf};
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+/// A directory or zip whose files replace the assets at the same path
+/// relative to its root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourcePack {
+    Dir(PathBuf),
+    Zip(PathBuf),
+}
+
+impl ResourcePack {
+    /// A pack read from `path`, as a directory if it is one and as a zip
+    /// otherwise.
+    pub fn new(path: PathBuf) -> Self {
+        if path.is_dir() {
+            ResourcePack::Dir(path)
+        } else {
+            ResourcePack::Zip(path)
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        match self {
+            ResourcePack::Dir(path) | ResourcePack::Zip(path) => path,
+        }
+    }
+
+    /// The pack's copy of `path`, or `None` if it does not have one.
+    pub fn read(&self, path: &str) -> AssetResult<Option<Vec<u8>>> {
+        match self {
+            ResourcePack::Dir(root) => match std::fs::read(root.join(path)) {
+                Ok(data) => Ok(Some(data)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e.into()),
+            },
+            ResourcePack::Zip(zip_path) => {
+                let mut archive = ZipArchive::new(std::fs::File::open(zip_path)?)?;
+                let mut file = match archive.by_name(path) {
+                    Ok(file) => file,
+                    Err(ZipError::FileNotFound) => return Ok(None),
+                    Err(e) => return Err(e.into()),
+                };
+                let mut data = Vec::new();
+                std::io::Read::read_to_end(&mut file, &mut data)?;
+                Ok(Some(data))
+            }
+        }
+    }
+}
@@ -1,24 +1,43 @@
-use ferrum_assets::{AssetManager, AssetError};
+use ferrum_assets::{AssetManager, AssetError, ResourcePack};
+use ferrum_config::AssetsConfig;
 
 #[tokio::test]
 async fn test_asset_manager_creation() {
-    let result = AssetManager::new("1.20.1").await;
+    let cache = tempfile::TempDir::new().unwrap();
+    let result = AssetManager::with_cache_root("1.20.1", cache.path()).await;
     assert!(result.is_ok(), "AssetManager creation should succeed");
 }
 
 #[tokio::test]
 async fn test_cache_directory_creation() {
-    let manager = AssetManager::new("1.20.1").await.unwrap();
+    let cache = tempfile::TempDir::new().unwrap();
+    let manager = AssetManager::with_cache_root("1.20.1", cache.path()).await.unwrap();
     let cache_dir = manager.cache_dir();
     
     assert!(cache_dir.exists(), "Cache directory should be created");
     assert!(cache_dir.is_dir(), "Cache path should be a directory");
-    assert!(cache_dir.ends_with("1.20.1"), "Cache dir should include version");
+    assert_eq!(cache_dir, cache.path().join("1.20.1"), "Cache dir should include version");
+}
+
+#[tokio::test]
+async fn test_manager_from_assets_config() {
+    let cache = tempfile::TempDir::new().unwrap();
+    let jar_path = cache.path().join("client.jar");
+    let config = AssetsConfig {
+        cache_dir: cache.path().display().to_string(),
+        jar_path: Some(jar_path.display().to_string()),
+        ..AssetsConfig::default()
+    };
+
+    let manager = AssetManager::from_config("1.20.1", &config).await.unwrap();
+    assert_eq!(manager.cache_dir(), cache.path().join("assets").join("1.20.1"));
+    assert_eq!(manager.jar_locator().override_path(), Some(jar_path.as_path()));
 }
 
 #[tokio::test]
 async fn test_load_texture_all_sources_fail() {
-    let manager = AssetManager::new("1.20.1").await.unwrap();
+    let cache = tempfile::TempDir::new().unwrap();
+    let manager = AssetManager::with_cache_root("1.20.1", cache.path()).await.unwrap();
     
     let result = manager.load_texture("minecraft/textures/block/nonexistent_block_xyz_12345.png").await;
     assert!(result.is_err(), "Non-existent texture should fail");
@@ -34,7 +53,8 @@ async fn test_load_texture_all_sources_fail() {
 
 #[tokio::test]
 async fn test_cache_hit_after_manual_write() {
-    let manager = AssetManager::new("1.20.1").await.unwrap();
+    let cache = tempfile::TempDir::new().unwrap();
+    let manager = AssetManager::with_cache_root("1.20.1", cache.path()).await.unwrap();
     let test_path = "minecraft/textures/test_cache.png";
     let test_data = b"fake texture data";
     
@@ -49,8 +69,9 @@ async fn test_cache_hit_after_manual_write() {
 
 #[tokio::test]
 async fn test_multiple_versions_separate_caches() {
-    let manager1 = AssetManager::new("1.20.1").await.unwrap();
-    let manager2 = AssetManager::new("1.19.4").await.unwrap();
+    let cache = tempfile::TempDir::new().unwrap();
+    let manager1 = AssetManager::with_cache_root("1.20.1", cache.path()).await.unwrap();
+    let manager2 = AssetManager::with_cache_root("1.19.4", cache.path()).await.unwrap();
     
     assert_ne!(
         manager1.cache_dir(),
@@ -58,3 +79,181 @@ async fn test_multiple_versions_separate_caches() {
         "Different versions should have separate cache directories"
     );
 }
+
+#[tokio::test]
+async fn test_prefetch_reports_each_path() {
+    let cache = tempfile::TempDir::new().unwrap();
+    let manager = AssetManager::with_cache_root("1.20.1", cache.path()).await.unwrap();
+    let cached = [
+        "minecraft/textures/prefetch_cached_a.png",
+        "minecraft/textures/prefetch_cached_b.png",
+    ];
+    for path in cached {
+        let cache_file = manager.cache_dir().join(path);
+        tokio::fs::create_dir_all(cache_file.parent().unwrap()).await.unwrap();
+        tokio::fs::write(&cache_file, b"fake texture data").await.unwrap();
+    }
+    let missing = "minecraft/textures/block/nonexistent_block_xyz_12345.png";
+
+    let results = manager.prefetch(&[cached[0], missing, cached[1]], 2).await;
+    let paths: Vec<_> = results.iter().map(|(path, _)| path.as_str()).collect();
+    assert_eq!(paths, [cached[0], missing, cached[1]]);
+
+    // Cached files succeed without fetching, the missing one fails alone
+    assert!(results[0].1.is_ok());
+    assert!(matches!(results[1].1, Err(AssetError::AllSourcesFailed(_))));
+    assert!(results[2].1.is_ok());
+}
+
+#[tokio::test]
+async fn test_corrupt_cached_file_is_fetched_again() {
+    use sha1::{Digest, Sha1};
+    use std::io::Write;
+
+    let good = b"the real texture data";
+    let path = "minecraft/textures/checksum_test.png";
+
+    // A jar holding the real file, as a source to fetch it from again
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let jar_path = temp_dir.path().join("client.jar");
+    let mut jar = zip::ZipWriter::new(std::fs::File::create(&jar_path).unwrap());
+    jar.start_file("assets/textures/checksum_test.png", zip::write::SimpleFileOptions::default())
+        .unwrap();
+    jar.write_all(good).unwrap();
+    jar.finish().unwrap();
+    let cache = tempfile::TempDir::new().unwrap();
+    let manager = AssetManager::with_cache_root("1.20.1", cache.path())
+        .await
+        .unwrap()
+        .with_jar_path(&jar_path);
+
+    // A cached copy cut short, with the hash of the whole file beside it
+    let cache_file = manager.cache_dir().join(path);
+    let sidecar = cache_file.with_extension("png.sha1");
+    tokio::fs::create_dir_all(cache_file.parent().unwrap()).await.unwrap();
+    tokio::fs::write(&cache_file, &good[..8]).await.unwrap();
+    let hash: String = Sha1::digest(good).iter().map(|b| format!("{:02x}", b)).collect();
+    tokio::fs::write(&sidecar, &hash).await.unwrap();
+
+    let data = manager.load_texture(path).await.unwrap();
+    assert_eq!(data, good, "corrupt cache should be fetched again");
+    assert_eq!(tokio::fs::read(&cache_file).await.unwrap(), good);
+    // The jar has no hash, so the stale one is dropped
+    assert!(!sidecar.exists());
+
+    // A matching hash is served from the cache
+    tokio::fs::write(&sidecar, &hash).await.unwrap();
+    let manager = AssetManager::with_cache_root("1.20.1", cache.path()).await.unwrap();
+    assert_eq!(manager.load_texture(path).await.unwrap(), good);
+}
+
+#[tokio::test]
+async fn test_cache_limit_evicts_least_recently_used() {
+    use std::fs::{File, FileTimes};
+    use std::time::{Duration, SystemTime};
+
+    let cache = tempfile::TempDir::new().unwrap();
+    let manager = AssetManager::with_cache_root("1.20.1", cache.path()).await.unwrap();
+    let dir = manager.cache_dir().join("minecraft/textures");
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // Five 1000 byte files, each used an hour after the one before
+    let start = SystemTime::now() - Duration::from_secs(24 * 3600);
+    let files: Vec<_> = (0..5).map(|i| dir.join(format!("texture_{}.png", i))).collect();
+    for (i, file) in files.iter().enumerate() {
+        std::fs::write(file, vec![0u8; 1000]).unwrap();
+        let used = start + Duration::from_secs(i as u64 * 3600);
+        File::open(file)
+            .unwrap()
+            .set_times(FileTimes::new().set_accessed(used).set_modified(used))
+            .unwrap();
+    }
+    // The oldest file's checksum goes with it
+    let sidecar = dir.join("texture_0.png.sha1");
+    std::fs::write(&sidecar, "a".repeat(40)).unwrap();
+    assert_eq!(manager.cache_size().unwrap(), 5040);
+
+    let freed = manager.enforce_cache_limit(2500).unwrap();
+    assert_eq!(freed, 3040);
+    assert_eq!(manager.cache_size().unwrap(), 2000);
+    for (i, file) in files.iter().enumerate() {
+        assert_eq!(file.exists(), i >= 3, "{}", file.display());
+    }
+    assert!(!sidecar.exists());
+
+    // Already under the limit, nothing more goes
+    assert_eq!(manager.enforce_cache_limit(2500).unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_offline_mode_serves_only_the_cache() {
+    use std::io::Write;
+
+    // A jar that does hold the missing texture, which offline mode must not open
+    let missing = "minecraft/textures/offline_missing.png";
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let jar_path = temp_dir.path().join("client.jar");
+    let mut jar = zip::ZipWriter::new(std::fs::File::create(&jar_path).unwrap());
+    jar.start_file("assets/textures/offline_missing.png", zip::write::SimpleFileOptions::default())
+        .unwrap();
+    jar.write_all(b"from the jar").unwrap();
+    jar.finish().unwrap();
+
+    let cache = tempfile::TempDir::new().unwrap();
+    let mut manager = AssetManager::with_cache_root("1.20.1", cache.path())
+        .await
+        .unwrap()
+        .with_jar_path(&jar_path);
+    manager.set_offline(true);
+    assert!(manager.is_offline());
+
+    let cached = "minecraft/textures/offline_cached.png";
+    let cache_file = manager.cache_dir().join(cached);
+    tokio::fs::create_dir_all(cache_file.parent().unwrap()).await.unwrap();
+    tokio::fs::write(&cache_file, b"fake texture data").await.unwrap();
+    assert_eq!(manager.load_texture(cached).await.unwrap(), b"fake texture data");
+
+    let error = manager.load_texture(missing).await.unwrap_err();
+    assert!(matches!(error, AssetError::AllSourcesFailed(ref cause) if cause == "offline mode"));
+    assert!(error.to_string().contains("offline mode"));
+    assert!(!manager.cache_dir().join(missing).exists());
+}
+
+#[tokio::test]
+async fn test_resource_pack_overrides_cache_and_sources() {
+    use std::io::Write;
+
+    let path = "block/stone.png";
+    let cache = tempfile::TempDir::new().unwrap();
+    let mut manager = AssetManager::with_cache_root("1.20.1", cache.path()).await.unwrap();
+    // Stands in for the Mojang copy, which would otherwise be served from here
+    let cache_file = manager.cache_dir().join(path);
+    tokio::fs::create_dir_all(cache_file.parent().unwrap()).await.unwrap();
+    tokio::fs::write(&cache_file, b"mojang stone").await.unwrap();
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let pack_dir = temp_dir.path().join("pack");
+    std::fs::create_dir_all(pack_dir.join("block")).unwrap();
+    std::fs::write(pack_dir.join(path), b"pack stone").unwrap();
+    manager.add_resource_pack(pack_dir.clone());
+    assert_eq!(manager.load_texture(path).await.unwrap(), b"pack stone");
+
+    // A zip pack added later wins over the directory
+    let zip_path = temp_dir.path().join("pack.zip");
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+    zip.start_file(path, zip::write::SimpleFileOptions::default()).unwrap();
+    zip.write_all(b"zipped stone").unwrap();
+    zip.finish().unwrap();
+    manager.add_resource_pack(zip_path.clone());
+    assert_eq!(
+        manager.resource_packs(),
+        [ResourcePack::Dir(pack_dir), ResourcePack::Zip(zip_path)]
+    );
+    assert_eq!(manager.load_texture(path).await.unwrap(), b"zipped stone");
+
+    // Files no pack has fall through to the cache, which packs never touch
+    assert_eq!(tokio::fs::read(&cache_file).await.unwrap(), b"mojang stone");
+    let other = "block/dirt.png";
+    tokio::fs::write(manager.cache_dir().join(other), b"mojang dirt").await.unwrap();
+    assert_eq!(manager.load_texture(other).await.unwrap(), b"mojang dirt");
+}
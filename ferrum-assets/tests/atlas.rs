@@ -0,0 +1,130 @@
+use ferrum_assets::{AssetError, AssetManager, UvRect};
+use ferrum_core::BlockId;
+use image::{Rgba, RgbaImage};
+
+/// Write a solid `color` PNG of the given size into the manager's cache.
+fn cache_png(manager: &AssetManager, path: &str, width: u32, height: u32, color: [u8; 4]) {
+    let file = manager.cache_dir().join(path);
+    std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+    RgbaImage::from_pixel(width, height, Rgba(color))
+        .save(&file)
+        .unwrap();
+}
+
+fn overlaps(a: UvRect, b: UvRect) -> bool {
+    a.min[0] < b.max[0] && b.min[0] < a.max[0] && a.min[1] < b.max[1] && b.min[1] < a.max[1]
+}
+
+/// Every pixel of the atlas inside `rect`.
+fn pixels_in(atlas: &ferrum_assets::AtlasImage, rect: UvRect) -> Vec<Rgba<u8>> {
+    let size = atlas.image.width() as f32;
+    let (x0, y0) = ((rect.min[0] * size) as u32, (rect.min[1] * size) as u32);
+    let (x1, y1) = ((rect.max[0] * size) as u32, (rect.max[1] * size) as u32);
+    let mut pixels = Vec::new();
+    for y in y0..y1 {
+        for x in x0..x1 {
+            pixels.push(*atlas.image.get_pixel(x, y));
+        }
+    }
+    pixels
+}
+
+#[tokio::test]
+async fn test_build_atlas_packs_each_texture_once() {
+    let manager = AssetManager::new("1.20.1").await.unwrap();
+    let textures = [
+        ("stone", [128, 128, 128, 255]),
+        ("dirt", [134, 96, 67, 255]),
+        ("grass", [95, 159, 53, 255]),
+        ("sand", [219, 207, 163, 255]),
+        ("glass", [200, 230, 255, 64]),
+    ];
+    let mut entries = Vec::new();
+    for (id, (name, color)) in textures.iter().enumerate() {
+        let path = format!("minecraft/textures/atlas_test/{name}.png");
+        cache_png(&manager, &path, 16, 16, *color);
+        entries.push((BlockId::new(id as u16 + 1), path));
+    }
+    // A second block using the stone texture shares its tile
+    entries.push((BlockId::new(99), entries[0].1.clone()));
+
+    let atlas = manager.build_atlas(&entries).unwrap();
+    assert_eq!(atlas.tile_size, 16);
+    assert_eq!(atlas.image.width(), atlas.image.height());
+    assert!(atlas.image.width().is_power_of_two());
+    assert_eq!(
+        atlas.image.width(),
+        64,
+        "5 tiles fit in 3x3, rounded up to 4x4"
+    );
+    assert_eq!(atlas.uvs.len(), entries.len());
+
+    let rects: Vec<UvRect> = textures
+        .iter()
+        .enumerate()
+        .map(|(id, _)| atlas.uv(BlockId::new(id as u16 + 1)).unwrap())
+        .collect();
+    for (i, a) in rects.iter().enumerate() {
+        for b in &rects[i + 1..] {
+            assert!(!overlaps(*a, *b), "{a:?} overlaps {b:?}");
+        }
+    }
+    for (rect, (name, color)) in rects.iter().zip(&textures) {
+        assert!(rect.min[0] >= 0.0 && rect.min[1] >= 0.0);
+        assert!(rect.max[0] <= 1.0 && rect.max[1] <= 1.0);
+        assert_eq!((rect.max[0] - rect.min[0]) * 64.0, 16.0);
+        assert_eq!((rect.max[1] - rect.min[1]) * 64.0, 16.0);
+        let pixels = pixels_in(&atlas, *rect);
+        assert_eq!(pixels.len(), 16 * 16);
+        assert!(
+            pixels.iter().all(|pixel| pixel.0 == *color),
+            "{name} tile is not its texture"
+        );
+    }
+    assert_eq!(atlas.uv(BlockId::new(99)), Some(rects[0]));
+}
+
+#[tokio::test]
+async fn test_build_atlas_scales_to_a_common_tile_size() {
+    let manager = AssetManager::new("1.20.1").await.unwrap();
+    let small = "minecraft/textures/atlas_test/small.png".to_string();
+    let large = "minecraft/textures/atlas_test/large.png".to_string();
+    // An animation strip of four 16px frames
+    let animated = "minecraft/textures/atlas_test/animated.png".to_string();
+    cache_png(&manager, &small, 16, 16, [255, 0, 0, 255]);
+    cache_png(&manager, &large, 32, 32, [0, 255, 0, 255]);
+    cache_png(&manager, &animated, 16, 64, [0, 0, 255, 255]);
+
+    let entries = [
+        (BlockId::new(1), small),
+        (BlockId::new(2), large),
+        (BlockId::new(3), animated),
+    ];
+    let atlas = manager.build_atlas(&entries).unwrap();
+    assert_eq!(atlas.tile_size, 32);
+    assert_eq!(atlas.image.width(), 64);
+
+    for (block, color) in [
+        (1, [255, 0, 0, 255]),
+        (2, [0, 255, 0, 255]),
+        (3, [0, 0, 255, 255]),
+    ] {
+        let rect = atlas.uv(BlockId::new(block)).unwrap();
+        let pixels = pixels_in(&atlas, rect);
+        assert_eq!(pixels.len(), 32 * 32, "block {block} fills a whole tile");
+        assert!(pixels.iter().all(|pixel| pixel.0 == color));
+    }
+}
+
+#[tokio::test]
+async fn test_build_atlas_requires_cached_textures() {
+    let manager = AssetManager::new("1.20.1").await.unwrap();
+    let entries = [(
+        BlockId::new(1),
+        "minecraft/textures/atlas_test/never_loaded.png".to_string(),
+    )];
+    match manager.build_atlas(&entries) {
+        Err(AssetError::NotCached(path)) => assert!(path.ends_with("never_loaded.png")),
+        other => panic!("Expected NotCached, got {:?}", other.map(|_| ())),
+    }
+}
@@ -0,0 +1,145 @@
+use ferrum_assets::{AssetError, AssetManager, Face};
+use std::path::Path;
+
+const CUBE: &str = r##"{
+    "parent": "block/block",
+    "elements": [{
+        "from": [0, 0, 0],
+        "to": [16, 16, 16],
+        "faces": {
+            "down": { "texture": "#down", "cullface": "down" },
+            "up": { "texture": "#up", "cullface": "up" },
+            "north": { "texture": "#north", "cullface": "north" },
+            "south": { "texture": "#south", "cullface": "south" },
+            "west": { "texture": "#west", "cullface": "west" },
+            "east": { "texture": "#east", "cullface": "east" }
+        }
+    }]
+}"##;
+
+const CUBE_ALL: &str = r##"{
+    "parent": "block/cube",
+    "textures": {
+        "particle": "#all",
+        "down": "#all",
+        "up": "#all",
+        "north": "#all",
+        "east": "#all",
+        "south": "#all",
+        "west": "#all"
+    }
+}"##;
+
+/// A pack holding `models` as `minecraft/models/<name>.json`.
+fn model_pack(dir: &Path, models: &[(&str, &str)]) {
+    for (name, json) in models {
+        let path = dir.join(format!("minecraft/models/{}.json", name));
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, json).unwrap();
+    }
+}
+
+async fn manager_with(models: &[(&str, &str)]) -> (AssetManager, tempfile::TempDir) {
+    let pack = tempfile::TempDir::new().unwrap();
+    model_pack(pack.path(), models);
+    let mut manager = AssetManager::offline("model-test").await.unwrap();
+    manager.add_resource_pack(pack.path().to_path_buf());
+    (manager, pack)
+}
+
+#[tokio::test]
+async fn test_cube_all_resolves_to_a_full_textured_cube() {
+    let (manager, _pack) = manager_with(&[
+        ("block/block", r#"{ "gui_light": "side" }"#),
+        ("block/cube", CUBE),
+        ("block/cube_all", CUBE_ALL),
+        (
+            "block/stone",
+            r#"{ "parent": "minecraft:block/cube_all", "textures": { "all": "minecraft:block/stone" } }"#,
+        ),
+    ])
+    .await;
+
+    let cube_all = manager.load_block_model("block/cube_all").await.unwrap();
+    assert_eq!(cube_all.elements.len(), 1);
+    let element = &cube_all.elements[0];
+    assert_eq!(element.bounds(), [[0.0; 3], [16.0; 3]]);
+    assert_eq!(element.faces.len(), 6);
+    for face in Face::all() {
+        let model_face = &element.faces[&face];
+        // Every face points at the one variable cube_all leaves open
+        assert_eq!(model_face.texture, "#all");
+        assert_eq!(model_face.uv, [0.0, 0.0, 16.0, 16.0]);
+        assert_eq!(model_face.cullface, Some(face));
+    }
+    assert_eq!(cube_all.texture("down"), None);
+    assert!(cube_all.ambient_occlusion);
+
+    let stone = manager
+        .load_block_model("minecraft:block/stone")
+        .await
+        .unwrap();
+    for face in Face::all() {
+        assert_eq!(
+            stone.elements[0].faces[&face].texture,
+            "minecraft:block/stone"
+        );
+    }
+    assert_eq!(stone.texture("#particle"), Some("minecraft:block/stone"));
+}
+
+#[tokio::test]
+async fn test_partial_elements_get_vanilla_default_uvs() {
+    let (manager, _pack) = manager_with(&[(
+        "block/slab",
+        r##"{
+            "parent": "builtin/generated",
+            "ambientocclusion": false,
+            "textures": { "side": "minecraft:block/oak_planks" },
+            "elements": [{
+                "from": [0, 0, 0],
+                "to": [16, 8, 16],
+                "shade": false,
+                "faces": {
+                    "north": { "texture": "#side" },
+                    "up": { "texture": "#side", "uv": [0, 0, 8, 8], "rotation": 90, "tintindex": 0 }
+                }
+            }]
+        }"##,
+    )])
+    .await;
+
+    let slab = manager.load_block_model("block/slab").await.unwrap();
+    assert!(!slab.ambient_occlusion);
+    let element = &slab.elements[0];
+    assert_eq!(element.bounds(), [[0.0; 3], [16.0, 8.0, 16.0]]);
+    assert!(!element.shade);
+    assert_eq!(element.faces.len(), 2);
+
+    let north = &element.faces[&Face::North];
+    assert_eq!(north.uv, [0.0, 8.0, 16.0, 16.0]);
+    assert_eq!(north.cullface, None);
+    let up = &element.faces[&Face::Up];
+    assert_eq!(up.texture, "minecraft:block/oak_planks");
+    assert_eq!(up.uv, [0.0, 0.0, 8.0, 8.0]);
+    assert_eq!(up.rotation, 90);
+    assert_eq!(up.tint_index, Some(0));
+}
+
+#[tokio::test]
+async fn test_cyclic_parents_are_an_error() {
+    let (manager, _pack) = manager_with(&[
+        ("block/a", r#"{ "parent": "block/b" }"#),
+        ("block/b", r#"{ "parent": "minecraft:block/a" }"#),
+    ])
+    .await;
+
+    let error = manager.load_block_model("block/a").await.unwrap_err();
+    assert!(
+        matches!(error, AssetError::ModelCycle(ref chain) if chain == "block/a -> block/b -> block/a")
+    );
+
+    // A missing parent is reported rather than skipped
+    let (manager, _pack) = manager_with(&[("block/orphan", r#"{ "parent": "block/gone" }"#)]).await;
+    assert!(manager.load_block_model("block/orphan").await.is_err());
+}
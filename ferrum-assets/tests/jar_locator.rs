@@ -0,0 +1,153 @@
+use ferrum_assets::{AssetError, JarLocator, LauncherLayout};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn touch(path: &Path) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, b"jar").unwrap();
+}
+
+#[test]
+fn test_layout_jar_paths() {
+    let root = Path::new("/launcher");
+
+    assert_eq!(
+        LauncherLayout::Vanilla.jar_path(root, "1.20.1"),
+        root.join("versions/1.20.1/1.20.1.jar")
+    );
+    assert_eq!(
+        LauncherLayout::MultiMc.jar_path(root, "1.20.1"),
+        root.join("libraries/com/mojang/minecraft/1.20.1/minecraft-1.20.1-client.jar")
+    );
+    assert_eq!(
+        LauncherLayout::Modrinth.jar_path(root, "1.20.1"),
+        root.join("meta/versions/1.20.1/1.20.1.jar")
+    );
+}
+
+#[test]
+fn test_finds_jar_in_prism_layout() {
+    let temp_dir = TempDir::new().unwrap();
+    let vanilla = temp_dir.path().join(".minecraft");
+    let prism = temp_dir.path().join("PrismLauncher");
+    let jar = LauncherLayout::MultiMc.jar_path(&prism, "1.20.1");
+    touch(&jar);
+
+    let locator = JarLocator::with_roots(vec![
+        (LauncherLayout::Vanilla, vanilla),
+        (LauncherLayout::MultiMc, prism),
+    ]);
+
+    assert_eq!(locator.locate("1.20.1").unwrap(), jar);
+}
+
+#[test]
+fn test_finds_jar_in_modrinth_layout() {
+    let temp_dir = TempDir::new().unwrap();
+    let modrinth = temp_dir.path().join("ModrinthApp");
+    let jar = LauncherLayout::Modrinth.jar_path(&modrinth, "1.21.4");
+    touch(&jar);
+
+    let locator = JarLocator::with_roots(vec![(LauncherLayout::Modrinth, modrinth)]);
+
+    assert_eq!(locator.locate("1.21.4").unwrap(), jar);
+}
+
+#[test]
+fn test_earlier_roots_take_priority() {
+    let temp_dir = TempDir::new().unwrap();
+    let vanilla = temp_dir.path().join(".minecraft");
+    let prism = temp_dir.path().join("PrismLauncher");
+    let vanilla_jar = LauncherLayout::Vanilla.jar_path(&vanilla, "1.20.1");
+    touch(&vanilla_jar);
+    touch(&LauncherLayout::MultiMc.jar_path(&prism, "1.20.1"));
+
+    let locator = JarLocator::with_roots(vec![
+        (LauncherLayout::Vanilla, vanilla),
+        (LauncherLayout::MultiMc, prism),
+    ]);
+
+    assert_eq!(locator.locate("1.20.1").unwrap(), vanilla_jar);
+}
+
+#[test]
+fn test_override_file_wins() {
+    let temp_dir = TempDir::new().unwrap();
+    let vanilla = temp_dir.path().join(".minecraft");
+    touch(&LauncherLayout::Vanilla.jar_path(&vanilla, "1.20.1"));
+    let custom = temp_dir.path().join("custom").join("client.jar");
+    touch(&custom);
+
+    let locator =
+        JarLocator::with_roots(vec![(LauncherLayout::Vanilla, vanilla)]).with_override(&custom);
+
+    assert_eq!(locator.locate("1.20.1").unwrap(), custom);
+}
+
+#[test]
+fn test_override_directory_searches_all_layouts() {
+    let temp_dir = TempDir::new().unwrap();
+    let instance = temp_dir.path().join("instance");
+    let jar = LauncherLayout::MultiMc.jar_path(&instance, "1.20.1");
+    touch(&jar);
+
+    let locator = JarLocator::with_roots(Vec::new()).with_override(&instance);
+
+    assert_eq!(locator.locate("1.20.1").unwrap(), jar);
+}
+
+#[test]
+fn test_resolved_path_is_cached_and_revalidated() {
+    let temp_dir = TempDir::new().unwrap();
+    let vanilla = temp_dir.path().join(".minecraft");
+    let prism = temp_dir.path().join("PrismLauncher");
+    let vanilla_jar = LauncherLayout::Vanilla.jar_path(&vanilla, "1.20.1");
+    let prism_jar = LauncherLayout::MultiMc.jar_path(&prism, "1.20.1");
+    touch(&prism_jar);
+
+    let locator = JarLocator::with_roots(vec![
+        (LauncherLayout::Vanilla, vanilla),
+        (LauncherLayout::MultiMc, prism),
+    ]);
+    assert_eq!(locator.locate("1.20.1").unwrap(), prism_jar);
+
+    // A higher-priority jar appearing later does not displace the cached one
+    touch(&vanilla_jar);
+    assert_eq!(locator.locate("1.20.1").unwrap(), prism_jar);
+
+    // Once the cached jar disappears, the search runs again
+    fs::remove_file(&prism_jar).unwrap();
+    assert_eq!(locator.locate("1.20.1").unwrap(), vanilla_jar);
+}
+
+#[test]
+fn test_not_found_lists_searched_locations() {
+    let temp_dir = TempDir::new().unwrap();
+    let vanilla = temp_dir.path().join(".minecraft");
+    let prism = temp_dir.path().join("PrismLauncher");
+
+    let locator = JarLocator::with_roots(vec![
+        (LauncherLayout::Vanilla, vanilla.clone()),
+        (LauncherLayout::MultiMc, prism.clone()),
+    ]);
+
+    let err = locator.locate("1.20.1").unwrap_err();
+    match &err {
+        AssetError::JarNotFound { version, searched } => {
+            assert_eq!(version, "1.20.1");
+            assert_eq!(
+                searched,
+                &vec![
+                    LauncherLayout::Vanilla.jar_path(&vanilla, "1.20.1"),
+                    LauncherLayout::MultiMc.jar_path(&prism, "1.20.1"),
+                ]
+            );
+        }
+        _ => panic!("Expected JarNotFound error"),
+    }
+
+    let msg = err.to_string();
+    assert!(msg.contains("1.20.1"));
+    assert!(msg.contains("PrismLauncher"));
+}
@@ -2,9 +2,11 @@ use bevy::prelude::*;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -15,18 +17,45 @@ pub enum ConfigError {
     #[error("Failed to parse TOML: {0}")]
     TomlError(#[from] toml::de::Error),
 
+    #[error("Failed to serialize config: {0}")]
+    SerializeError(#[from] toml::ser::Error),
+
+    #[cfg(feature = "json")]
+    #[error("Failed to parse JSON: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[cfg(feature = "ron")]
+    #[error("Failed to parse RON: {0}")]
+    RonError(#[from] ron::error::SpannedError),
+
+    #[cfg(feature = "ron")]
+    #[error("Failed to serialize config as RON: {0}")]
+    RonSerializeError(#[from] ron::Error),
+
+    #[error("Config format {0:?} is not enabled in this build")]
+    UnsupportedFormat(ConfigFormat),
+
     #[error("Validation error: {0}")]
     ValidationError(String),
 
+    #[error("Config file is empty")]
+    EmptyFile,
+
     #[error("File watcher error: {0}")]
     WatcherError(#[from] notify::Error),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+/// Largest accepted `client.chunk_group_size`.
+pub const MAX_CHUNK_GROUP_SIZE: u32 = 8;
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, Resource)]
 pub struct Config {
     #[serde(default)]
     pub client: ClientConfig,
 
+    #[serde(default)]
+    pub render: RenderConfig,
+
     #[serde(default)]
     pub server: ServerConfig,
 
@@ -37,7 +66,7 @@ pub struct Config {
     pub keybindings: Keybindings,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ClientConfig {
     #[serde(default = "default_render_distance")]
     pub render_distance: u32,
@@ -50,27 +79,205 @@ pub struct ClientConfig {
 
     #[serde(default)]
     pub vsync: bool,
+
+    /// One of "off", "fxaa", "msaax2" or "msaax4".
+    #[serde(default = "default_anti_aliasing")]
+    pub anti_aliasing: String,
+
+    /// Brightness slider, 0.0 (moody) to 1.0 (bright). 0.5 leaves the image
+    /// unchanged.
+    #[serde(default = "default_brightness")]
+    pub brightness: f32,
+
+    /// Light everything fully, ignoring darkness.
+    #[serde(default)]
+    pub fullbright: bool,
+
+    /// Side length, in chunks, of the squares of chunk columns drawn as one
+    /// merged mesh. Larger groups mean fewer draw calls but slower rebuilds
+    /// when a chunk changes.
+    #[serde(default = "default_chunk_group_size")]
+    pub chunk_group_size: u32,
+
+    /// Seconds a newly loaded chunk takes to fade in. 0 makes chunks appear
+    /// at once.
+    #[serde(default = "default_chunk_fade_in")]
+    pub chunk_fade_in: f32,
+
+    /// Queued chunks loaded per frame, nearest first. Lower values spread
+    /// the loading done when crossing a chunk border over more frames.
+    #[serde(default = "default_chunks_per_frame")]
+    pub chunks_per_frame: u32,
+
+    /// One of "off", "fast" (flat) or "fancy" (with thickness).
+    #[serde(default = "default_clouds")]
+    pub clouds: String,
+
+    /// Height of the bottom of the cloud layer, in blocks.
+    #[serde(default = "default_cloud_height")]
+    pub cloud_height: f32,
+
+    /// Sway the camera and arm while walking. Turn off to reduce motion
+    /// sickness.
+    #[serde(default = "default_view_bobbing")]
+    pub view_bobbing: bool,
+
+    /// Widen the field of view while sprinting.
+    #[serde(default = "default_fov_effects")]
+    pub fov_effects: bool,
+
+    /// One of "off", "low", "medium" or "high".
+    #[serde(default = "default_shadows")]
+    pub shadows: String,
+
+    /// Size of each shadow map in texels, in place of the quality's.
+    /// Rounded up to a power of two between 256 and 8192.
+    #[serde(default)]
+    pub shadow_resolution: Option<u32>,
+
+    /// Number of shadow cascades, 1 to 4, in place of the quality's.
+    #[serde(default)]
+    pub shadow_cascades: Option<u32>,
+
+    /// Farthest distance shadows are drawn at, in blocks, in place of the
+    /// quality's. Never more than the render distance.
+    #[serde(default)]
+    pub shadow_distance: Option<f32>,
+}
+
+/// File format of a config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConfigFormat {
+    #[default]
+    Toml,
+    /// Needs the `json` feature.
+    Json,
+    /// Needs the `ron` feature.
+    Ron,
+}
+
+impl ConfigFormat {
+    /// The format for a file, by its extension. Anything other than `.json`
+    /// or `.ron` is read as TOML.
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        match extension.map(str::to_ascii_lowercase).as_deref() {
+            Some("json") => Self::Json,
+            Some("ron") => Self::Ron,
+            _ => Self::Toml,
+        }
+    }
+}
+
+/// Anti-aliasing applied to the game camera.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AntiAliasing {
+    Off,
+    Fxaa,
+    Msaa2,
+    #[default]
+    Msaa4,
+}
+
+impl AntiAliasing {
+    /// Parse a config value, case-insensitively. Returns `None` for unknown
+    /// modes.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "off" | "none" => Some(Self::Off),
+            "fxaa" => Some(Self::Fxaa),
+            "msaax2" | "msaa2" => Some(Self::Msaa2),
+            "msaax4" | "msaa4" => Some(Self::Msaa4),
+            _ => None,
+        }
+    }
+}
+
+/// Shadow quality preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShadowQuality {
+    #[default]
+    Off,
+    Low,
+    Medium,
+    High,
+}
+
+impl ShadowQuality {
+    /// Parse a config value, case-insensitively. Returns `None` for unknown
+    /// qualities.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "off" | "none" => Some(Self::Off),
+            "low" => Some(Self::Low),
+            "medium" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+}
+
+/// How the cloud layer is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CloudMode {
+    Off,
+    /// A flat sheet.
+    Fast,
+    /// Boxes with sides and shading.
+    #[default]
+    Fancy,
+}
+
+impl CloudMode {
+    /// Parse a config value, case-insensitively. Returns `None` for unknown
+    /// modes.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "off" | "none" => Some(Self::Off),
+            "fast" => Some(Self::Fast),
+            "fancy" => Some(Self::Fancy),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenderConfig {
+    /// Frame rate to hold. LOD distances shrink while frames run slower and
+    /// grow back once there is headroom.
+    #[serde(default = "default_target_fps")]
+    pub target_fps: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ServerConfig {
     #[serde(default = "default_server_address")]
     pub address: String,
 
     #[serde(default)]
     pub auto_start: bool,
+
+    /// Seconds to wait for a keepalive from the server before giving up on
+    /// the connection. Vanilla servers send one every 15. Only applies while
+    /// joining: the connection is closed 5 seconds into the game.
+    #[serde(default = "default_keepalive_timeout")]
+    pub keepalive_timeout: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AssetsConfig {
     #[serde(default = "default_asset_source")]
     pub source: String,
 
     #[serde(default = "default_cache_dir")]
     pub cache_dir: String,
+
+    /// Explicit client jar (or launcher directory) to extract assets from.
+    #[serde(default)]
+    pub jar_path: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Keybindings {
     #[serde(default = "default_forward")]
     pub forward: String,
@@ -109,9 +316,45 @@ fn default_render_distance() -> u32 {
 fn default_fov() -> f32 {
     70.0
 }
+fn default_anti_aliasing() -> String {
+    "msaax4".to_string()
+}
+fn default_brightness() -> f32 {
+    0.5
+}
+fn default_chunk_group_size() -> u32 {
+    1
+}
+fn default_chunk_fade_in() -> f32 {
+    0.25
+}
+fn default_chunks_per_frame() -> u32 {
+    8
+}
+fn default_clouds() -> String {
+    "fancy".to_string()
+}
+fn default_cloud_height() -> f32 {
+    192.0
+}
+fn default_view_bobbing() -> bool {
+    true
+}
+fn default_fov_effects() -> bool {
+    true
+}
+fn default_shadows() -> String {
+    "off".to_string()
+}
+fn default_target_fps() -> u32 {
+    60
+}
 fn default_server_address() -> String {
     "127.0.0.1:25565".to_string()
 }
+fn default_keepalive_timeout() -> u64 {
+    30
+}
 fn default_asset_source() -> String {
     "mojang".to_string()
 }
@@ -156,6 +399,48 @@ impl Default for ClientConfig {
             fov: default_fov(),
             fps_limit: None,
             vsync: false,
+            anti_aliasing: default_anti_aliasing(),
+            brightness: default_brightness(),
+            fullbright: false,
+            chunk_group_size: default_chunk_group_size(),
+            chunk_fade_in: default_chunk_fade_in(),
+            chunks_per_frame: default_chunks_per_frame(),
+            clouds: default_clouds(),
+            cloud_height: default_cloud_height(),
+            view_bobbing: default_view_bobbing(),
+            fov_effects: default_fov_effects(),
+            shadows: default_shadows(),
+            shadow_resolution: None,
+            shadow_cascades: None,
+            shadow_distance: None,
+        }
+    }
+}
+
+impl ClientConfig {
+    /// The configured anti-aliasing mode, or `Off` if the value is not a
+    /// supported mode.
+    pub fn anti_aliasing_mode(&self) -> AntiAliasing {
+        AntiAliasing::parse(&self.anti_aliasing).unwrap_or(AntiAliasing::Off)
+    }
+
+    /// The configured cloud mode, or `Off` if the value is not a supported
+    /// mode.
+    pub fn cloud_mode(&self) -> CloudMode {
+        CloudMode::parse(&self.clouds).unwrap_or(CloudMode::Off)
+    }
+
+    /// The configured shadow quality, or `Off` if the value is not a
+    /// supported quality.
+    pub fn shadow_quality(&self) -> ShadowQuality {
+        ShadowQuality::parse(&self.shadows).unwrap_or(ShadowQuality::Off)
+    }
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            target_fps: default_target_fps(),
         }
     }
 }
@@ -165,6 +450,7 @@ impl Default for ServerConfig {
         Self {
             address: default_server_address(),
             auto_start: false,
+            keepalive_timeout: default_keepalive_timeout(),
         }
     }
 }
@@ -174,6 +460,7 @@ impl Default for AssetsConfig {
         Self {
             source: default_asset_source(),
             cache_dir: default_cache_dir(),
+            jar_path: None,
         }
     }
 }
@@ -195,16 +482,168 @@ impl Default for Keybindings {
     }
 }
 
+impl Keybindings {
+    /// Every action with its bound key.
+    pub fn bindings(&self) -> [(&'static str, &str); 10] {
+        [
+            ("forward", &self.forward),
+            ("back", &self.back),
+            ("left", &self.left),
+            ("right", &self.right),
+            ("jump", &self.jump),
+            ("sneak", &self.sneak),
+            ("sprint", &self.sprint),
+            ("inventory", &self.inventory),
+            ("drop", &self.drop),
+            ("chat", &self.chat),
+        ]
+    }
+
+    /// Keys bound to more than one action, with those actions. Keys are
+    /// compared ignoring case and reported as written for the first action.
+    pub fn conflicts(&self) -> Vec<(String, Vec<String>)> {
+        let mut keys: Vec<(String, String, Vec<String>)> = Vec::new();
+        for (action, key) in self.bindings() {
+            let normalized = key.trim().to_lowercase();
+            match keys.iter_mut().find(|(other, _, _)| *other == normalized) {
+                Some((_, _, actions)) => actions.push(action.to_string()),
+                None => keys.push((normalized, key.to_string(), vec![action.to_string()])),
+            }
+        }
+        keys.into_iter()
+            .filter(|(_, _, actions)| actions.len() > 1)
+            .map(|(_, key, actions)| (key, actions))
+            .collect()
+    }
+}
+
 impl Config {
     pub fn from_str(content: &str) -> Result<Self, ConfigError> {
-        let config: Config = toml::from_str(content)?;
+        Self::from_str_with_format(content, ConfigFormat::Toml)
+    }
+
+    pub fn from_str_with_format(content: &str, format: ConfigFormat) -> Result<Self, ConfigError> {
+        let config: Config = match format {
+            ConfigFormat::Toml => toml::from_str(content)?,
+            #[cfg(feature = "json")]
+            ConfigFormat::Json => serde_json::from_str(content)?,
+            #[cfg(feature = "ron")]
+            ConfigFormat::Ron => ron::from_str(content)?,
+            #[allow(unreachable_patterns)]
+            format => return Err(ConfigError::UnsupportedFormat(format)),
+        };
         config.validate()?;
         Ok(config)
     }
 
+    /// Load a config in the format given by the file's extension, see
+    /// [`ConfigFormat::from_path`].
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
         let content = fs::read_to_string(path)?;
-        Self::from_str(&content)
+        Self::from_str_with_format(&content, ConfigFormat::from_path(path))
+    }
+
+    /// Load a config after its file changed, with the environment overrides
+    /// applied. An empty file fails with [`ConfigError::EmptyFile`], as an
+    /// editor may have truncated it and not written it yet.
+    pub fn reload<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        if content.trim().is_empty() {
+            return Err(ConfigError::EmptyFile);
+        }
+        let mut config = Self::from_str_with_format(&content, ConfigFormat::from_path(path))?;
+        config.apply_env_overrides()?;
+        Ok(config)
+    }
+
+    /// The config written out in `format`.
+    pub fn to_string_with_format(&self, format: ConfigFormat) -> Result<String, ConfigError> {
+        match format {
+            ConfigFormat::Toml => Ok(toml::to_string_pretty(self)?),
+            #[cfg(feature = "json")]
+            ConfigFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+            #[cfg(feature = "ron")]
+            ConfigFormat::Ron => Ok(ron::ser::to_string_pretty(
+                self,
+                ron::ser::PrettyConfig::default(),
+            )?),
+            #[allow(unreachable_patterns)]
+            format => Err(ConfigError::UnsupportedFormat(format)),
+        }
+    }
+
+    /// The default config as TOML, with every field written out.
+    pub fn default_toml_string() -> String {
+        toml::to_string_pretty(&Self::default()).expect("default config serializes to TOML")
+    }
+
+    /// Write the config to `path`, in the format given by its extension. The
+    /// file is written next to `path` first and then renamed over it, so a
+    /// crash mid-write leaves the old file intact. Invalid configs are not
+    /// written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ConfigError> {
+        self.validate()?;
+        let path = path.as_ref();
+        let content = self.to_string_with_format(ConfigFormat::from_path(path))?;
+
+        let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+        temp_name.push(".tmp");
+        let temp_path = path.with_file_name(temp_name);
+
+        let result = fs::File::create(&temp_path).and_then(|mut file| {
+            file.write_all(content.as_bytes())?;
+            file.sync_all()
+        });
+        if let Err(err) = result.and_then(|()| fs::rename(&temp_path, path)) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(err.into());
+        }
+        Ok(())
+    }
+
+    /// Override settings from environment variables, for CI and container
+    /// runs that shouldn't touch the config file:
+    ///
+    /// - `FERRUM_RENDER_DISTANCE`: `client.render_distance`
+    /// - `FERRUM_FOV`: `client.fov`
+    /// - `FERRUM_SERVER_ADDRESS`: `server.address`
+    /// - `FERRUM_VSYNC`: `client.vsync`, one of `true`, `false`, `1` or `0`
+    ///
+    /// Unset variables leave their field alone. If a variable doesn't parse
+    /// or the result fails [`validate`](Self::validate), nothing is changed.
+    pub fn apply_env_overrides(&mut self) -> Result<(), ConfigError> {
+        self.apply_overrides(|name| std::env::var(name).ok())
+    }
+
+    /// [`apply_env_overrides`](Self::apply_env_overrides) with the variables
+    /// looked up by `var` instead of read from the environment.
+    pub fn apply_overrides(
+        &mut self,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), ConfigError> {
+        let mut config = self.clone();
+        if let Some(value) = var("FERRUM_RENDER_DISTANCE") {
+            config.client.render_distance = parse_override("FERRUM_RENDER_DISTANCE", &value)?;
+        }
+        if let Some(value) = var("FERRUM_FOV") {
+            config.client.fov = parse_override("FERRUM_FOV", &value)?;
+        }
+        if let Some(value) = var("FERRUM_SERVER_ADDRESS") {
+            config.server.address = value;
+        }
+        if let Some(value) = var("FERRUM_VSYNC") {
+            config.client.vsync = match value.trim().to_ascii_lowercase().as_str() {
+                "true" | "1" => true,
+                "false" | "0" => false,
+                _ => return Err(invalid_override("FERRUM_VSYNC", &value)),
+            };
+        }
+
+        config.validate()?;
+        *self = config;
+        Ok(())
     }
 
     pub fn validate(&self) -> Result<(), ConfigError> {
@@ -220,6 +659,110 @@ impl Config {
             ));
         }
 
+        if AntiAliasing::parse(&self.client.anti_aliasing).is_none() {
+            return Err(ConfigError::ValidationError(format!(
+                "anti_aliasing must be one of off, fxaa, msaax2, msaax4 (got {:?})",
+                self.client.anti_aliasing
+            )));
+        }
+
+        if CloudMode::parse(&self.client.clouds).is_none() {
+            return Err(ConfigError::ValidationError(format!(
+                "clouds must be one of off, fast, fancy (got {:?})",
+                self.client.clouds
+            )));
+        }
+
+        if !self.client.cloud_height.is_finite() {
+            return Err(ConfigError::ValidationError(
+                "cloud_height must be a finite number".to_string(),
+            ));
+        }
+
+        if ShadowQuality::parse(&self.client.shadows).is_none() {
+            return Err(ConfigError::ValidationError(format!(
+                "shadows must be one of off, low, medium, high (got {:?})",
+                self.client.shadows
+            )));
+        }
+
+        if self.client.shadow_resolution == Some(0) || self.client.shadow_cascades == Some(0) {
+            return Err(ConfigError::ValidationError(
+                "shadow_resolution and shadow_cascades must be greater than 0".to_string(),
+            ));
+        }
+
+        if let Some(distance) = self.client.shadow_distance {
+            if !(distance.is_finite() && distance > 0.0) {
+                return Err(ConfigError::ValidationError(
+                    "shadow_distance must be a positive number of blocks".to_string(),
+                ));
+            }
+        }
+
+        if !(0.0..=1.0).contains(&self.client.brightness) {
+            return Err(ConfigError::ValidationError(
+                "brightness must be between 0 and 1".to_string(),
+            ));
+        }
+
+        if !(1..=MAX_CHUNK_GROUP_SIZE).contains(&self.client.chunk_group_size) {
+            return Err(ConfigError::ValidationError(format!(
+                "chunk_group_size must be between 1 and {}",
+                MAX_CHUNK_GROUP_SIZE
+            )));
+        }
+
+        if !(self.client.chunk_fade_in.is_finite() && self.client.chunk_fade_in >= 0.0) {
+            return Err(ConfigError::ValidationError(
+                "chunk_fade_in must be a non-negative number of seconds".to_string(),
+            ));
+        }
+
+        if self.client.chunks_per_frame == 0 {
+            return Err(ConfigError::ValidationError(
+                "chunks_per_frame must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.render.target_fps == 0 {
+            return Err(ConfigError::ValidationError(
+                "target_fps must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.server.keepalive_timeout == 0 {
+            return Err(ConfigError::ValidationError(
+                "keepalive_timeout must be greater than 0".to_string(),
+            ));
+        }
+
+        if let Some((action, _)) = self
+            .keybindings
+            .bindings()
+            .into_iter()
+            .find(|(_, key)| key.trim().is_empty())
+        {
+            return Err(ConfigError::ValidationError(format!(
+                "keybinding for {} must not be empty",
+                action
+            )));
+        }
+
+        let conflicts = self.keybindings.conflicts();
+        if !conflicts.is_empty() {
+            let messages: Vec<String> = conflicts
+                .iter()
+                .map(|(key, actions)| {
+                    let (last, rest) = actions.split_last().expect("conflicts have two actions");
+                    let both = if rest.len() == 1 { "both " } else { "" };
+                    let rest = rest.join(", ");
+                    format!("key '{key}' is bound to {both}{rest} and {last}")
+                })
+                .collect();
+            return Err(ConfigError::ValidationError(messages.join("; ")));
+        }
+
         if let Some(fps) = self.client.fps_limit {
             if fps == 0 {
                 return Err(ConfigError::ValidationError(
@@ -232,12 +775,22 @@ impl Config {
     }
 }
 
+/// How long the config file must go without changes before a change is
+/// reported, so an editor saving in several writes causes one reload.
+pub const DEFAULT_RELOAD_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Wait before reading a config file again when a reload finds it empty,
+/// in case it was caught between being truncated and written.
+const EMPTY_RELOAD_RETRY_DELAY: Duration = Duration::from_millis(100);
+
 #[derive(Resource, Clone)]
 pub struct ConfigWatcher {
     pub config_path: PathBuf,
     receiver: Arc<Mutex<mpsc::Receiver<notify::Result<notify::Event>>>>,
     _watcher: Arc<Mutex<RecommendedWatcher>>,
-    last_reload: Arc<Mutex<std::time::Instant>>,
+    debounce: Duration,
+    /// Latest event not yet reported, and when it arrived.
+    pending: Arc<Mutex<Option<(notify::Event, Instant)>>>,
 }
 
 impl ConfigWatcher {
@@ -252,49 +805,86 @@ impl ConfigWatcher {
             config_path: config_path.as_ref().to_path_buf(),
             receiver: Arc::new(Mutex::new(rx)),
             _watcher: Arc::new(Mutex::new(watcher)),
-            last_reload: Arc::new(Mutex::new(std::time::Instant::now())),
+            debounce: DEFAULT_RELOAD_DEBOUNCE,
+            pending: Arc::new(Mutex::new(None)),
         })
     }
 
+    /// Report changes only once the file has been quiet for `debounce`.
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// The latest change to the config file, once no further change has
+    /// arrived for the debounce window. Events within the window are
+    /// coalesced into one.
     pub fn check_for_changes(&self) -> Option<notify::Event> {
-        self.receiver
-            .lock()
-            .ok()?
-            .try_recv()
-            .ok()
-            .and_then(|r| r.ok())
+        let receiver = self.receiver.lock().ok()?;
+        let mut pending = self.pending.lock().ok()?;
+        while let Ok(result) = receiver.try_recv() {
+            if let Ok(event) = result {
+                *pending = Some((event, Instant::now()));
+            }
+        }
+
+        match &*pending {
+            Some((_, received)) if received.elapsed() >= self.debounce => {
+                pending.take().map(|(event, _)| event)
+            }
+            _ => None,
+        }
     }
 }
 
-pub fn hot_reload_system(mut config: ResMut<Config>, watcher: Res<ConfigWatcher>) {
-    // Check if there are any file change events
-    if watcher.check_for_changes().is_none() {
-        return;  // No changes detected, do nothing
+/// Reload the config when its file changes. A file found empty is read once
+/// more on a frame after a short delay, without holding up the frames in
+/// between.
+pub fn hot_reload_system(
+    mut config: ResMut<Config>,
+    watcher: Res<ConfigWatcher>,
+    mut empty_read: Local<Option<Instant>>,
+) {
+    // Check if there are any file change events (already debounced)
+    if watcher.check_for_changes().is_some() {
+        *empty_read = None;
+    } else if !empty_read.is_some_and(|read| read.elapsed() >= EMPTY_RELOAD_RETRY_DELAY) {
+        return;  // No changes detected and no retry due, do nothing
     }
+    let retried = empty_read.take().is_some();
 
-    // Debounce: only reload once per second even if file changed
-    if let Ok(mut last_reload) = watcher.last_reload.lock() {
-        if last_reload.elapsed().as_secs() < 1 {
-            // Changes detected but too soon since last reload, drain and skip
-            while watcher.check_for_changes().is_some() {}
-            return;
+    match Config::reload(&watcher.config_path) {
+        Ok(new_config) => {
+            *config = new_config;
+            info!("Config reloaded from {:?}", watcher.config_path);
+        }
+        Err(ConfigError::EmptyFile) if !retried => {
+            *empty_read = Some(Instant::now());
+        }
+        Err(e) => {
+            error!("Failed to reload config: {}", e);
         }
+    }
+}
 
-        // Drain any remaining events to prevent buildup
-        while watcher.check_for_changes().is_some() {}
+fn parse_override<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, ConfigError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| invalid_override(name, value))
+}
 
-        // File changed and enough time passed, reload config
-        match Config::load(&watcher.config_path) {
-            Ok(new_config) => {
-                *config = new_config;
-                *last_reload = std::time::Instant::now();
-                info!("Config reloaded from {:?}", watcher.config_path);
-            }
-            Err(e) => {
-                error!("Failed to reload config: {}", e);
-            }
-        }
+fn invalid_override(name: &str, value: &str) -> ConfigError {
+    ConfigError::ValidationError(format!("{name} has an invalid value '{value}'"))
+}
+
+/// Write the default config to `path` for users to edit, creating its
+/// directory if needed.
+fn write_default_config(path: &Path) -> Result<(), ConfigError> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
     }
+    Config::default().save(path)
 }
 
 pub struct ConfigPlugin {
@@ -303,18 +893,26 @@ pub struct ConfigPlugin {
 
 impl Plugin for ConfigPlugin {
     fn build(&self, app: &mut App) {
-        let config = Config::load(&self.config_path).unwrap_or_else(|e| {
+        if !self.config_path.exists() {
+            match write_default_config(&self.config_path) {
+                Ok(()) => info!("Wrote default config to {:?}", self.config_path),
+                Err(e) => warn!(
+                    "Failed to write default config to {:?}: {}",
+                    self.config_path, e
+                ),
+            }
+        }
+
+        let mut config = Config::load(&self.config_path).unwrap_or_else(|e| {
             warn!(
                 "Failed to load config from {:?}: {}. Using defaults.",
                 self.config_path, e
             );
-            Config {
-                client: ClientConfig::default(),
-                server: ServerConfig::default(),
-                assets: AssetsConfig::default(),
-                keybindings: Keybindings::default(),
-            }
+            Config::default()
         });
+        if let Err(e) = config.apply_env_overrides() {
+            panic!("Invalid config override from the environment: {}", e);
+        }
 
         app.insert_resource(config);
 
@@ -1,4 +1,5 @@
-use ferrum_config::{Config, ConfigError};
+use bevy::app::App;
+use ferrum_config::{Config, ConfigError, ConfigPlugin};
 use std::fs;
 use tempfile::TempDir;
 
@@ -128,6 +129,43 @@ fov = 85.0
     assert_eq!(config.client.fov, 85.0);
 }
 
+#[test]
+fn test_save_round_trips_through_load() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(&config_path, "[client]\nfov = 80.0\n").unwrap();
+
+    let mut config = Config::from_str("").unwrap();
+    config.client.render_distance = 12;
+    config.client.fov = 95.0;
+    config.client.fps_limit = Some(144);
+    config.client.clouds = "fast".to_string();
+    config.server.address = "play.example.com:25565".to_string();
+    config.keybindings.jump = "Enter".to_string();
+    config.save(&config_path).expect("Failed to save config");
+
+    let loaded = Config::load(&config_path).expect("Failed to load saved config");
+    assert_eq!(loaded, config);
+
+    // Only the config itself is left behind, no temp file
+    let files: Vec<_> = fs::read_dir(temp_dir.path()).unwrap().collect();
+    assert_eq!(files.len(), 1);
+}
+
+#[test]
+fn test_save_rejects_invalid_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+
+    let mut config = Config::from_str("").unwrap();
+    config.client.fov = 10.0;
+    match config.save(&config_path) {
+        Err(ConfigError::ValidationError(msg)) => assert!(msg.contains("fov")),
+        other => panic!("Expected ValidationError, got {:?}", other),
+    }
+    assert!(!config_path.exists());
+}
+
 #[test]
 fn test_hot_reload_detection() {
     let temp_dir = TempDir::new().unwrap();
@@ -155,3 +193,540 @@ render_distance = 16
     let reloaded_config = Config::load(&config_path).expect("Failed to reload config");
     assert_eq!(reloaded_config.client.render_distance, 16);
 }
+
+#[test]
+fn test_watcher_coalesces_bursts_of_writes() {
+    use ferrum_config::ConfigWatcher;
+    use std::time::{Duration, Instant};
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(&config_path, "[client]\nrender_distance = 8\n").unwrap();
+    let watcher = ConfigWatcher::new(&config_path)
+        .unwrap()
+        .with_debounce(Duration::from_millis(200));
+
+    // An editor truncating, then writing
+    fs::write(&config_path, "").unwrap();
+    std::thread::sleep(Duration::from_millis(20));
+    fs::write(&config_path, "[client]\nrender_distance = 16\n").unwrap();
+    let written = Instant::now();
+
+    let mut changes = 0;
+    while written.elapsed() < Duration::from_secs(2) {
+        if watcher.check_for_changes().is_some() {
+            assert!(written.elapsed() >= Duration::from_millis(200));
+            changes += 1;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(changes, 1);
+}
+
+#[test]
+fn test_reload_reports_an_empty_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(&config_path, " \n").unwrap();
+
+    assert!(matches!(
+        Config::reload(&config_path),
+        Err(ConfigError::EmptyFile)
+    ));
+}
+
+#[test]
+fn test_hot_reload_retries_an_empty_file_without_blocking() {
+    use bevy::app::Update;
+    use ferrum_config::{hot_reload_system, ConfigWatcher};
+    use std::time::{Duration, Instant};
+
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(&config_path, "[client]\nrender_distance = 8\n").unwrap();
+    let mut app = App::new();
+    app.insert_resource(Config::load(&config_path).unwrap())
+        .insert_resource(
+            ConfigWatcher::new(&config_path)
+                .unwrap()
+                .with_debounce(Duration::ZERO),
+        )
+        .add_systems(Update, hot_reload_system);
+
+    // Frames keep running while the empty read waits for its retry
+    fs::write(&config_path, "").unwrap();
+    let truncated = Instant::now();
+    while truncated.elapsed() < Duration::from_millis(50) {
+        let frame = Instant::now();
+        app.update();
+        assert!(frame.elapsed() < Duration::from_millis(50));
+    }
+    assert_eq!(app.world().resource::<Config>().client.render_distance, 8);
+
+    fs::write(&config_path, "[client]\nrender_distance = 12\n").unwrap();
+    let written = Instant::now();
+    while app.world().resource::<Config>().client.render_distance != 12 {
+        assert!(written.elapsed() < Duration::from_secs(2), "never reloaded");
+        app.update();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn test_parse_anti_aliasing_modes() {
+    use ferrum_config::AntiAliasing;
+
+    for (value, expected) in [
+        ("off", AntiAliasing::Off),
+        ("fxaa", AntiAliasing::Fxaa),
+        ("msaax2", AntiAliasing::Msaa2),
+        ("MSAAx4", AntiAliasing::Msaa4),
+    ] {
+        let toml_content = format!("[client]\nanti_aliasing = \"{}\"\n", value);
+        let config = Config::from_str(&toml_content).expect("Failed to parse anti_aliasing");
+        assert_eq!(config.client.anti_aliasing_mode(), expected);
+    }
+
+    let config = Config::from_str("").unwrap();
+    assert_eq!(config.client.anti_aliasing_mode(), AntiAliasing::Msaa4);
+}
+
+#[test]
+fn test_invalid_anti_aliasing() {
+    let toml_content = r#"
+[client]
+anti_aliasing = "ssaa16"
+"#;
+
+    match Config::from_str(toml_content) {
+        Err(ConfigError::ValidationError(msg)) => assert!(msg.contains("anti_aliasing")),
+        other => panic!("Expected ValidationError, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_unsupported_anti_aliasing_falls_back_to_off() {
+    let mut config = Config::from_str("").unwrap();
+    config.client.anti_aliasing = "msaax16".to_string();
+    assert_eq!(
+        config.client.anti_aliasing_mode(),
+        ferrum_config::AntiAliasing::Off
+    );
+}
+
+#[test]
+fn test_brightness_defaults_and_range() {
+    let config = Config::from_str("").unwrap();
+    assert_eq!(config.client.brightness, 0.5);
+    assert!(!config.client.fullbright);
+
+    let config = Config::from_str("[client]\nbrightness = 1.0\nfullbright = true\n").unwrap();
+    assert_eq!(config.client.brightness, 1.0);
+    assert!(config.client.fullbright);
+
+    match Config::from_str("[client]\nbrightness = 1.5\n") {
+        Err(ConfigError::ValidationError(msg)) => assert!(msg.contains("brightness")),
+        other => panic!("Expected ValidationError, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_chunk_group_size_defaults_and_range() {
+    let config = Config::from_str("").unwrap();
+    assert_eq!(config.client.chunk_group_size, 1);
+
+    let config = Config::from_str("[client]\nchunk_group_size = 4\n").unwrap();
+    assert_eq!(config.client.chunk_group_size, 4);
+
+    for size in [0, ferrum_config::MAX_CHUNK_GROUP_SIZE + 1] {
+        match Config::from_str(&format!("[client]\nchunk_group_size = {}\n", size)) {
+            Err(ConfigError::ValidationError(msg)) => assert!(msg.contains("chunk_group_size")),
+            other => panic!("Expected ValidationError, got {:?}", other.map(|_| ())),
+        }
+    }
+}
+
+#[test]
+fn test_chunk_fade_in_defaults_and_range() {
+    let config = Config::from_str("").unwrap();
+    assert_eq!(config.client.chunk_fade_in, 0.25);
+
+    let config = Config::from_str("[client]\nchunk_fade_in = 0.0\n").unwrap();
+    assert_eq!(config.client.chunk_fade_in, 0.0);
+
+    match Config::from_str("[client]\nchunk_fade_in = -1.0\n") {
+        Err(ConfigError::ValidationError(msg)) => assert!(msg.contains("chunk_fade_in")),
+        other => panic!("Expected ValidationError, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_chunks_per_frame_defaults_and_range() {
+    let config = Config::from_str("").unwrap();
+    assert_eq!(config.client.chunks_per_frame, 8);
+
+    let config = Config::from_str("[client]\nchunks_per_frame = 2\n").unwrap();
+    assert_eq!(config.client.chunks_per_frame, 2);
+
+    match Config::from_str("[client]\nchunks_per_frame = 0\n") {
+        Err(ConfigError::ValidationError(msg)) => assert!(msg.contains("chunks_per_frame")),
+        other => panic!("Expected ValidationError, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_target_fps_defaults_and_range() {
+    let config = Config::from_str("").unwrap();
+    assert_eq!(config.render.target_fps, 60);
+
+    let config = Config::from_str("[render]\ntarget_fps = 144\n").unwrap();
+    assert_eq!(config.render.target_fps, 144);
+
+    match Config::from_str("[render]\ntarget_fps = 0\n") {
+        Err(ConfigError::ValidationError(msg)) => assert!(msg.contains("target_fps")),
+        other => panic!("Expected ValidationError, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_keepalive_timeout_defaults_and_range() {
+    let config = Config::from_str("").unwrap();
+    assert_eq!(config.server.keepalive_timeout, 30);
+
+    let config = Config::from_str("[server]\nkeepalive_timeout = 60\n").unwrap();
+    assert_eq!(config.server.keepalive_timeout, 60);
+
+    match Config::from_str("[server]\nkeepalive_timeout = 0\n") {
+        Err(ConfigError::ValidationError(msg)) => assert!(msg.contains("keepalive_timeout")),
+        other => panic!("Expected ValidationError, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_clouds_modes_and_height() {
+    use ferrum_config::CloudMode;
+
+    let config = Config::from_str("").unwrap();
+    assert_eq!(config.client.cloud_mode(), CloudMode::Fancy);
+    assert_eq!(config.client.cloud_height, 192.0);
+
+    for (value, expected) in [
+        ("off", CloudMode::Off),
+        ("Fast", CloudMode::Fast),
+        ("fancy", CloudMode::Fancy),
+    ] {
+        let toml_content = format!("[client]\nclouds = \"{}\"\ncloud_height = 128.0\n", value);
+        let config = Config::from_str(&toml_content).expect("Failed to parse clouds");
+        assert_eq!(config.client.cloud_mode(), expected);
+        assert_eq!(config.client.cloud_height, 128.0);
+    }
+
+    match Config::from_str("[client]\nclouds = \"volumetric\"\n") {
+        Err(ConfigError::ValidationError(msg)) => assert!(msg.contains("clouds")),
+        other => panic!("Expected ValidationError, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_shadow_quality_and_overrides() {
+    use ferrum_config::ShadowQuality;
+
+    let config = Config::from_str("").unwrap();
+    assert_eq!(config.client.shadow_quality(), ShadowQuality::Off);
+    assert_eq!(config.client.shadow_resolution, None);
+
+    let toml_content = r#"
+[client]
+shadows = "High"
+shadow_resolution = 1024
+shadow_cascades = 2
+shadow_distance = 64.0
+"#;
+    let config = Config::from_str(toml_content).unwrap();
+    assert_eq!(config.client.shadow_quality(), ShadowQuality::High);
+    assert_eq!(config.client.shadow_resolution, Some(1024));
+    assert_eq!(config.client.shadow_cascades, Some(2));
+    assert_eq!(config.client.shadow_distance, Some(64.0));
+
+    for invalid in [
+        "shadows = \"ultra\"",
+        "shadow_resolution = 0",
+        "shadow_cascades = 0",
+        "shadow_distance = -8.0",
+    ] {
+        match Config::from_str(&format!("[client]\n{}\n", invalid)) {
+            Err(ConfigError::ValidationError(msg)) => assert!(msg.contains("shadow"), "{msg}"),
+            other => panic!("Expected ValidationError, got {:?}", other.map(|_| ())),
+        }
+    }
+}
+
+#[test]
+fn test_view_bobbing_and_fov_effects_toggles() {
+    let config = Config::from_str("").unwrap();
+    assert!(config.client.view_bobbing);
+    assert!(config.client.fov_effects);
+
+    let config = Config::from_str("[client]\nview_bobbing = false\nfov_effects = false\n").unwrap();
+    assert!(!config.client.view_bobbing);
+    assert!(!config.client.fov_effects);
+}
+
+#[test]
+fn test_default_keybindings_have_no_conflicts() {
+    let config = Config::from_str("").unwrap();
+    assert!(config.keybindings.conflicts().is_empty());
+}
+
+#[test]
+fn test_duplicate_keybindings_are_rejected() {
+    match Config::from_str("[keybindings]\nforward = \"W\"\njump = \"w\"\n") {
+        Err(ConfigError::ValidationError(msg)) => {
+            assert_eq!(msg, "key 'W' is bound to both forward and jump")
+        }
+        other => panic!("Expected ValidationError, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_keybinding_conflicts_list_every_action() {
+    let mut config = Config::from_str("").unwrap();
+    config.keybindings.jump = "w".to_string();
+    config.keybindings.sneak = "W".to_string();
+    config.keybindings.chat = "E".to_string();
+
+    assert_eq!(
+        config.keybindings.conflicts(),
+        vec![
+            (
+                "W".to_string(),
+                vec![
+                    "forward".to_string(),
+                    "jump".to_string(),
+                    "sneak".to_string()
+                ]
+            ),
+            (
+                "E".to_string(),
+                vec!["inventory".to_string(), "chat".to_string()]
+            ),
+        ]
+    );
+    match config.validate() {
+        Err(ConfigError::ValidationError(msg)) => assert_eq!(
+            msg,
+            "key 'W' is bound to forward, jump and sneak; \
+             key 'E' is bound to both inventory and chat"
+        ),
+        other => panic!("Expected ValidationError, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_empty_keybinding_is_rejected() {
+    match Config::from_str("[keybindings]\ndrop = \" \"\n") {
+        Err(ConfigError::ValidationError(msg)) => {
+            assert_eq!(msg, "keybinding for drop must not be empty")
+        }
+        other => panic!("Expected ValidationError, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[cfg(all(feature = "json", feature = "ron"))]
+#[test]
+fn test_same_config_loads_from_every_format() {
+    let temp_dir = TempDir::new().unwrap();
+    let files = [
+        (
+            "config.toml",
+            r#"
+[client]
+render_distance = 8
+fov = 85.0
+fps_limit = 144
+clouds = "fast"
+
+[server]
+address = "play.example.com:25565"
+
+[keybindings]
+jump = "Enter"
+"#,
+        ),
+        (
+            "config.json",
+            r#"{
+    "client": {"render_distance": 8, "fov": 85.0, "fps_limit": 144, "clouds": "fast"},
+    "server": {"address": "play.example.com:25565"},
+    "keybindings": {"jump": "Enter"}
+}"#,
+        ),
+        (
+            "config.ron",
+            r#"(
+    client: (render_distance: 8, fov: 85.0, fps_limit: Some(144), clouds: "fast"),
+    server: (address: "play.example.com:25565"),
+    keybindings: (jump: "Enter"),
+)"#,
+        ),
+    ];
+
+    let configs: Vec<Config> = files
+        .iter()
+        .map(|(name, content)| {
+            let path = temp_dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            Config::load(&path).unwrap_or_else(|e| panic!("Failed to load {}: {}", name, e))
+        })
+        .collect();
+
+    assert_eq!(configs[0].client.render_distance, 8);
+    assert_eq!(configs[0].keybindings.jump, "Enter");
+    assert_eq!(configs[0], configs[1]);
+    assert_eq!(configs[0], configs[2]);
+}
+
+#[cfg(all(feature = "json", feature = "ron"))]
+#[test]
+fn test_from_str_with_format_reports_format_errors() {
+    use ferrum_config::ConfigFormat;
+
+    let json = Config::from_str_with_format("{\"client\": ", ConfigFormat::Json);
+    assert!(matches!(json, Err(ConfigError::JsonError(_))));
+    let ron = Config::from_str_with_format("(client: ", ConfigFormat::Ron);
+    assert!(matches!(ron, Err(ConfigError::RonError(_))));
+}
+
+#[cfg(all(feature = "json", feature = "ron"))]
+#[test]
+fn test_save_uses_file_extension_format() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut config = Config::from_str("").unwrap();
+    config.client.fov = 100.0;
+
+    for name in ["saved.json", "saved.ron"] {
+        let path = temp_dir.path().join(name);
+        config.save(&path).unwrap();
+        assert_eq!(Config::load(&path).unwrap(), config);
+    }
+    let json = fs::read_to_string(temp_dir.path().join("saved.json")).unwrap();
+    assert!(json.trim_start().starts_with('{'));
+}
+
+fn overrides(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: Vec<(String, String)> = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    move |name| {
+        vars.iter()
+            .find(|(var, _)| var == name)
+            .map(|(_, value)| value.clone())
+    }
+}
+
+#[test]
+fn test_unset_overrides_leave_config_untouched() {
+    let mut config = Config::from_str("[client]\nfov = 85.0\n").unwrap();
+    let original = config.clone();
+    config.apply_overrides(overrides(&[])).unwrap();
+    assert_eq!(config, original);
+}
+
+#[test]
+fn test_overrides_replace_loaded_values() {
+    let mut config = Config::from_str("[client]\nrender_distance = 8\n").unwrap();
+    config
+        .apply_overrides(overrides(&[
+            ("FERRUM_RENDER_DISTANCE", "24"),
+            ("FERRUM_FOV", "100"),
+            ("FERRUM_SERVER_ADDRESS", "ci.example.com:25565"),
+            ("FERRUM_VSYNC", "true"),
+        ]))
+        .unwrap();
+
+    assert_eq!(config.client.render_distance, 24);
+    assert_eq!(config.client.fov, 100.0);
+    assert_eq!(config.server.address, "ci.example.com:25565");
+    assert!(config.client.vsync);
+}
+
+#[test]
+fn test_vsync_override_accepts_bools_and_digits() {
+    let mut config = Config::from_str("").unwrap();
+    for (value, expected) in [("true", true), ("0", false), ("1", true), ("false", false)] {
+        config
+            .apply_overrides(overrides(&[("FERRUM_VSYNC", value)]))
+            .unwrap();
+        assert_eq!(config.client.vsync, expected, "FERRUM_VSYNC={}", value);
+    }
+}
+
+#[test]
+fn test_bad_override_names_the_variable() {
+    let mut config = Config::from_str("").unwrap();
+    let original = config.clone();
+
+    let result = config.apply_overrides(overrides(&[
+        ("FERRUM_FOV", "100"),
+        ("FERRUM_RENDER_DISTANCE", "far"),
+    ]));
+    match result {
+        Err(ConfigError::ValidationError(msg)) => {
+            assert!(msg.contains("FERRUM_RENDER_DISTANCE"), "{}", msg)
+        }
+        other => panic!("Expected a validation error, got {:?}", other),
+    }
+    assert_eq!(config, original);
+
+    let result = config.apply_overrides(overrides(&[("FERRUM_VSYNC", "yes")]));
+    assert!(
+        matches!(result, Err(ConfigError::ValidationError(msg)) if msg.contains("FERRUM_VSYNC"))
+    );
+}
+
+#[test]
+fn test_override_failing_validation_is_rejected() {
+    let mut config = Config::from_str("").unwrap();
+    let original = config.clone();
+    let result = config.apply_overrides(overrides(&[("FERRUM_FOV", "200")]));
+    assert!(matches!(result, Err(ConfigError::ValidationError(_))));
+    assert_eq!(config, original);
+}
+
+#[test]
+fn test_default_toml_string_parses_to_defaults() {
+    let content = Config::default_toml_string();
+    assert!(content.contains("[client]"));
+    assert!(content.contains("render_distance"));
+    assert_eq!(Config::from_str(&content).unwrap(), Config::default());
+}
+
+#[test]
+fn test_plugin_writes_default_config_when_missing() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("nested").join("config.toml");
+
+    App::new().add_plugins(ConfigPlugin {
+        config_path: config_path.clone(),
+    });
+
+    let written = Config::load(&config_path).expect("default config should load");
+    assert_eq!(written, Config::default());
+}
+
+#[test]
+fn test_plugin_keeps_existing_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let content = "[client]\nrender_distance = 12\n";
+    fs::write(&config_path, content).unwrap();
+
+    let mut app = App::new();
+    app.add_plugins(ConfigPlugin {
+        config_path: config_path.clone(),
+    });
+
+    assert_eq!(fs::read_to_string(&config_path).unwrap(), content);
+    assert_eq!(app.world().resource::<Config>().client.render_distance, 12);
+}
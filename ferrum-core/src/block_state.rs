@@ -0,0 +1,117 @@
+use crate::BlockId;
+
+/// A block id together with its packed property bits.
+///
+/// Packed into a single `u32` (id in the low 16 bits, properties in the high
+/// 16 bits) so it is a cheap `Copy` key for palettes and hash maps. States are
+/// equal exactly when their bits are equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct BlockState(u32);
+
+impl BlockState {
+    /// Creates a BlockState from a block id and its packed properties.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ferrum_core::{BlockId, BlockState};
+    ///
+    /// let state = BlockState::new(BlockId::new(9), 3);
+    /// assert_eq!(state.id(), BlockId::new(9));
+    /// assert_eq!(state.properties(), 3);
+    /// ```
+    pub fn new(id: BlockId, properties: u16) -> Self {
+        BlockState(((properties as u32) << 16) | id.as_u16() as u32)
+    }
+
+    /// Reinterprets a raw packed value, as produced by [`BlockState::to_bits`].
+    pub fn from_bits(bits: u32) -> Self {
+        BlockState(bits)
+    }
+
+    /// Returns the raw packed value.
+    pub fn to_bits(self) -> u32 {
+        self.0
+    }
+
+    pub fn id(self) -> BlockId {
+        BlockId::new(self.0 as u16)
+    }
+
+    pub fn properties(self) -> u16 {
+        (self.0 >> 16) as u16
+    }
+
+    /// Returns this state with its properties replaced.
+    pub fn with_properties(self, properties: u16) -> Self {
+        BlockState::new(self.id(), properties)
+    }
+}
+
+impl From<BlockId> for BlockState {
+    /// The default state of a block: no property bits set.
+    fn from(id: BlockId) -> Self {
+        BlockState::new(id, 0)
+    }
+}
+
+impl From<BlockState> for BlockId {
+    fn from(state: BlockState) -> Self {
+        state.id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash_of(state: BlockState) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        state.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_block_state_is_four_bytes() {
+        assert_eq!(std::mem::size_of::<BlockState>(), 4);
+    }
+
+    #[test]
+    fn test_block_state_round_trips_id_and_properties() {
+        for (id, properties) in [(0, 0), (1, 0), (9, 7), (u16::MAX, u16::MAX), (42, 0x8001)] {
+            let state = BlockState::new(BlockId::new(id), properties);
+            assert_eq!(state.id(), BlockId::new(id));
+            assert_eq!(state.properties(), properties);
+            assert_eq!(BlockState::from_bits(state.to_bits()), state);
+        }
+    }
+
+    #[test]
+    fn test_block_state_packing_layout() {
+        let state = BlockState::new(BlockId::new(0x1234), 0xABCD);
+        assert_eq!(state.to_bits(), 0xABCD_1234);
+    }
+
+    #[test]
+    fn test_block_state_from_block_id() {
+        let state = BlockState::from(BlockId::new(5));
+        assert_eq!(state.id(), BlockId::new(5));
+        assert_eq!(state.properties(), 0);
+        assert_eq!(BlockId::from(state), BlockId::new(5));
+        assert_eq!(BlockState::default(), BlockState::from(BlockId::new(0)));
+    }
+
+    #[test]
+    fn test_block_state_equality_and_hash() {
+        let a = BlockState::new(BlockId::new(9), 3);
+        let b = BlockState::from_bits(a.to_bits());
+        let c = a.with_properties(4);
+
+        assert_eq!(a, b);
+        assert_eq!(hash_of(a), hash_of(b));
+        assert_ne!(a, c);
+        assert_eq!(c.id(), a.id());
+    }
+}
@@ -0,0 +1,376 @@
+//! Parsing of slash commands typed into chat or the server console, such as
+//! `/give @p stone 64`.
+//!
+//! Every command has a [`CommandSyntax`] listing the kinds of argument it
+//! takes, so `64` is read as a number where a count is expected and as a
+//! word elsewhere. Errors carry the byte offset of the token that failed.
+
+use crate::error::{CommandError, CommandErrorKind};
+use crate::Registries;
+use std::collections::HashMap;
+
+/// Longest player name a selector accepts, as in vanilla.
+const MAX_PLAYER_NAME_LEN: usize = 16;
+
+/// What a command argument is read as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgKind {
+    /// A target selector such as `@p` or `@e[type=zombie]`, or a player name.
+    Selector,
+    /// A 32-bit integer.
+    Int,
+    /// An item name from the item registry, with or without `minecraft:`.
+    Item,
+    /// One unquoted word.
+    Word,
+    /// A word or a double-quoted string, which may contain spaces.
+    String,
+    /// The rest of the input, as typed.
+    Message,
+}
+
+/// Who a `@` selector picks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectorKind {
+    /// `@p`
+    NearestPlayer,
+    /// `@a`
+    AllPlayers,
+    /// `@r`
+    RandomPlayer,
+    /// `@e`
+    AllEntities,
+    /// `@s`
+    Executor,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Selector {
+    /// A player by name.
+    Player(String),
+    /// A `@` selector and its `[key=value, ...]` filters, in order.
+    Entities {
+        kind: SelectorKind,
+        filters: Vec<(String, String)>,
+    },
+}
+
+/// A parsed argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Arg {
+    Selector(Selector),
+    Int(i32),
+    /// Id of the item in the item registry.
+    Item(u32),
+    Word(String),
+    String(String),
+    Message(String),
+}
+
+/// A parsed command: its name without the slash and its arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Command {
+    pub name: String,
+    pub args: Vec<Arg>,
+}
+
+/// The arguments a command takes: some required, then some optional ones,
+/// which can only be left out from the end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandSyntax {
+    name: String,
+    required: Vec<ArgKind>,
+    optional: Vec<ArgKind>,
+}
+
+impl CommandSyntax {
+    pub fn new(name: impl Into<String>, required: &[ArgKind]) -> Self {
+        Self {
+            name: name.into(),
+            required: required.to_vec(),
+            optional: Vec::new(),
+        }
+    }
+
+    /// Accept `optional` after the required arguments.
+    pub fn with_optional(mut self, optional: &[ArgKind]) -> Self {
+        self.optional = optional.to_vec();
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn params(&self) -> impl Iterator<Item = (ArgKind, bool)> + '_ {
+        let required = self.required.iter().map(|&kind| (kind, true));
+        let optional = self.optional.iter().map(|&kind| (kind, false));
+        required.chain(optional)
+    }
+}
+
+/// Parses commands against the syntaxes registered with it.
+#[derive(Debug, Clone, Default)]
+pub struct CommandParser {
+    syntaxes: HashMap<String, CommandSyntax>,
+}
+
+impl CommandParser {
+    /// A parser that knows no commands.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A parser for the common server commands: `give`, `clear`, `kill`,
+    /// `say`, `msg`, `tell`, `list`, `gamemode`, `kick`, `op`, `deop` and
+    /// `stop`.
+    pub fn standard() -> Self {
+        use ArgKind::*;
+        let mut parser = Self::new();
+        for syntax in [
+            CommandSyntax::new("give", &[Selector, Item]).with_optional(&[Int]),
+            CommandSyntax::new("clear", &[]).with_optional(&[Selector, Item, Int]),
+            CommandSyntax::new("kill", &[]).with_optional(&[Selector]),
+            CommandSyntax::new("say", &[Message]),
+            CommandSyntax::new("msg", &[Selector, Message]),
+            CommandSyntax::new("tell", &[Selector, Message]),
+            CommandSyntax::new("list", &[]),
+            CommandSyntax::new("gamemode", &[Word]).with_optional(&[Selector]),
+            CommandSyntax::new("kick", &[Selector]).with_optional(&[Message]),
+            CommandSyntax::new("op", &[Selector]),
+            CommandSyntax::new("deop", &[Selector]),
+            CommandSyntax::new("stop", &[]),
+        ] {
+            parser.register(syntax);
+        }
+        parser
+    }
+
+    /// Add a command, replacing any registered under the same name.
+    pub fn register(&mut self, syntax: CommandSyntax) {
+        self.syntaxes.insert(syntax.name.clone(), syntax);
+    }
+
+    pub fn syntax(&self, name: &str) -> Option<&CommandSyntax> {
+        self.syntaxes.get(name)
+    }
+
+    /// Parse `input`, with or without its leading slash. Item names are
+    /// looked up in `registries`.
+    pub fn parse(&self, input: &str, registries: &Registries) -> Result<Command, CommandError> {
+        let mut reader = Reader::new(input);
+        reader.skip_whitespace();
+        reader.eat('/');
+        let name = reader
+            .read_token()?
+            .ok_or(CommandError::new(reader.pos, CommandErrorKind::Empty))?;
+        let syntax = self.syntaxes.get(&name.text).ok_or_else(|| {
+            CommandError::new(
+                name.start,
+                CommandErrorKind::UnknownCommand(name.text.clone()),
+            )
+        })?;
+
+        let mut args = Vec::new();
+        for (kind, required) in syntax.params() {
+            reader.skip_whitespace();
+            if reader.at_end() {
+                if required {
+                    return Err(CommandError::new(
+                        reader.pos,
+                        CommandErrorKind::MissingArgument(kind),
+                    ));
+                }
+                break;
+            }
+            if kind == ArgKind::Message {
+                args.push(Arg::Message(reader.rest().to_string()));
+                continue;
+            }
+            let token = reader.read_token()?.expect("not at the end of the input");
+            args.push(parse_arg(kind, token, registries)?);
+        }
+
+        reader.skip_whitespace();
+        if !reader.at_end() {
+            return Err(CommandError::new(
+                reader.pos,
+                CommandErrorKind::TooManyArguments,
+            ));
+        }
+        Ok(Command {
+            name: name.text,
+            args,
+        })
+    }
+}
+
+fn parse_arg(kind: ArgKind, token: Token, registries: &Registries) -> Result<Arg, CommandError> {
+    let start = token.start;
+    let error = |kind| Err(CommandError::new(start, kind));
+    if token.quoted && kind != ArgKind::String {
+        return error(CommandErrorKind::UnexpectedQuote);
+    }
+    match kind {
+        ArgKind::Selector => match parse_selector(&token.text) {
+            Some(selector) => Ok(Arg::Selector(selector)),
+            None => error(CommandErrorKind::InvalidSelector(token.text)),
+        },
+        ArgKind::Int => match token.text.parse() {
+            Ok(value) => Ok(Arg::Int(value)),
+            Err(_) => error(CommandErrorKind::InvalidInt(token.text)),
+        },
+        ArgKind::Item => {
+            let name = token.text.strip_prefix("minecraft:").unwrap_or(&token.text);
+            match registries.items().id(name) {
+                Some(id) => Ok(Arg::Item(id)),
+                None => error(CommandErrorKind::UnknownItem(token.text)),
+            }
+        }
+        ArgKind::Word => Ok(Arg::Word(token.text)),
+        ArgKind::String => Ok(Arg::String(token.text)),
+        ArgKind::Message => unreachable!("messages are read from the raw input"),
+    }
+}
+
+fn parse_selector(text: &str) -> Option<Selector> {
+    let Some(selector) = text.strip_prefix('@') else {
+        let valid_name = (1..=MAX_PLAYER_NAME_LEN).contains(&text.len())
+            && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        return valid_name.then(|| Selector::Player(text.to_string()));
+    };
+
+    let (kind, filters) = match selector.split_once('[') {
+        Some((kind, filters)) => (kind, Some(filters.strip_suffix(']')?)),
+        None => (selector, None),
+    };
+    let kind = match kind {
+        "p" => SelectorKind::NearestPlayer,
+        "a" => SelectorKind::AllPlayers,
+        "r" => SelectorKind::RandomPlayer,
+        "e" => SelectorKind::AllEntities,
+        "s" => SelectorKind::Executor,
+        _ => return None,
+    };
+    let filters = match filters.map(str::trim) {
+        None | Some("") => Vec::new(),
+        Some(filters) => filters
+            .split(',')
+            .map(|filter| {
+                let (key, value) = filter.split_once('=')?;
+                let key = key.trim();
+                (!key.is_empty()).then(|| (key.to_string(), value.trim().to_string()))
+            })
+            .collect::<Option<_>>()?,
+    };
+    Some(Selector::Entities { kind, filters })
+}
+
+/// One whitespace-separated token, unescaped if it was quoted.
+struct Token {
+    text: String,
+    /// Byte offset of the token in the input.
+    start: usize,
+    quoted: bool,
+}
+
+struct Reader<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn rest(&mut self) -> &'a str {
+        let rest = &self.input[self.pos..];
+        self.pos = self.input.len();
+        rest
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        let matches = self.peek() == Some(expected);
+        if matches {
+            self.pos += expected.len_utf8();
+        }
+        matches
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += self.peek().map_or(0, char::len_utf8);
+        }
+    }
+
+    /// The next token, or `None` at the end of the input. Whitespace inside
+    /// a selector's brackets doesn't end the token.
+    fn read_token(&mut self) -> Result<Option<Token>, CommandError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        if self.at_end() {
+            return Ok(None);
+        }
+        if self.eat('"') {
+            return self.read_quoted(start).map(Some);
+        }
+
+        let mut depth = 0usize;
+        while let Some(c) = self.peek() {
+            match c {
+                '[' => depth += 1,
+                ']' => depth = depth.saturating_sub(1),
+                c if c.is_whitespace() && depth == 0 => break,
+                _ => {}
+            }
+            self.pos += c.len_utf8();
+        }
+        Ok(Some(Token {
+            text: self.input[start..self.pos].to_string(),
+            start,
+            quoted: false,
+        }))
+    }
+
+    /// The rest of a quoted string starting at `start`, with `\"` and `\\`
+    /// unescaped.
+    fn read_quoted(&mut self, start: usize) -> Result<Token, CommandError> {
+        let mut text = String::new();
+        let mut chars = self.input[self.pos..].char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += offset + 1;
+                    return Ok(Token {
+                        text,
+                        start,
+                        quoted: true,
+                    });
+                }
+                '\\' => match chars.next() {
+                    Some((_, escaped @ ('"' | '\\'))) => text.push(escaped),
+                    _ => {
+                        return Err(CommandError::new(
+                            self.pos + offset,
+                            CommandErrorKind::InvalidEscape,
+                        ))
+                    }
+                },
+                c => text.push(c),
+            }
+        }
+        Err(CommandError::new(
+            start,
+            CommandErrorKind::UnterminatedQuote,
+        ))
+    }
+}
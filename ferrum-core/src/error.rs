@@ -0,0 +1,140 @@
+use crate::{ArgKind, BlockId};
+use std::fmt;
+
+/// Why a block or block-state operation failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockError {
+    /// No block is registered under this name.
+    UnknownName(String),
+    /// A numeric id that does not fit the id space it was used in.
+    IdOutOfRange { id: u32, max: u32 },
+    /// The block has no property with this name.
+    PropertyNotFound { block: BlockId, property: String },
+    /// The property exists but does not accept this value.
+    InvalidPropertyValue { property: String, value: String },
+    /// A property was defined with no values to take.
+    EmptyProperty(String),
+    /// Property values packed past the `u16` a block state holds them in.
+    PropertiesOverflow { bits: u32 },
+}
+
+impl fmt::Display for BlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockError::UnknownName(name) => write!(f, "unknown block: {name}"),
+            BlockError::IdOutOfRange { id, max } => {
+                write!(f, "id {id} is out of range (max {max})")
+            }
+            BlockError::PropertyNotFound { block, property } => {
+                write!(f, "block {} has no property {property}", block.as_u16())
+            }
+            BlockError::InvalidPropertyValue { property, value } => {
+                write!(f, "invalid value {value:?} for property {property}")
+            }
+            BlockError::EmptyProperty(property) => {
+                write!(f, "property {property} has no values")
+            }
+            BlockError::PropertiesOverflow { bits } => {
+                write!(f, "property bits {bits} overflow a block state")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BlockError {}
+
+/// Why save data could not be written or read.
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    /// The file is not valid save data.
+    Malformed(serde_json::Error),
+    /// The save was written by a newer format version than this build reads.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(err) => write!(f, "save I/O failed: {err}"),
+            SaveError::Malformed(err) => write!(f, "malformed save: {err}"),
+            SaveError::UnsupportedVersion(version) => {
+                write!(f, "unsupported save version {version}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SaveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SaveError::Io(err) => Some(err),
+            SaveError::Malformed(err) => Some(err),
+            SaveError::UnsupportedVersion(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SaveError {
+    fn from(err: std::io::Error) -> Self {
+        SaveError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(err: serde_json::Error) -> Self {
+        SaveError::Malformed(err)
+    }
+}
+
+/// Why a command could not be parsed, and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandError {
+    /// Byte offset in the input of the token that failed, or the end of the
+    /// input if something is missing.
+    pub position: usize,
+    pub kind: CommandErrorKind,
+}
+
+impl CommandError {
+    pub(crate) fn new(position: usize, kind: CommandErrorKind) -> Self {
+        Self { position, kind }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandErrorKind {
+    /// There is no command name.
+    Empty,
+    UnknownCommand(String),
+    MissingArgument(ArgKind),
+    TooManyArguments,
+    InvalidInt(String),
+    InvalidSelector(String),
+    UnknownItem(String),
+    /// A quoted string where only a word is accepted.
+    UnexpectedQuote,
+    UnterminatedQuote,
+    /// A backslash in a quoted string not followed by `"` or `\`.
+    InvalidEscape,
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            CommandErrorKind::Empty => write!(f, "expected a command")?,
+            CommandErrorKind::UnknownCommand(name) => write!(f, "unknown command {name:?}")?,
+            CommandErrorKind::MissingArgument(kind) => write!(f, "missing {kind:?} argument")?,
+            CommandErrorKind::TooManyArguments => write!(f, "too many arguments")?,
+            CommandErrorKind::InvalidInt(text) => write!(f, "invalid integer {text:?}")?,
+            CommandErrorKind::InvalidSelector(text) => write!(f, "invalid selector {text:?}")?,
+            CommandErrorKind::UnknownItem(name) => write!(f, "unknown item {name:?}")?,
+            CommandErrorKind::UnexpectedQuote => write!(f, "unexpected quoted string")?,
+            CommandErrorKind::UnterminatedQuote => write!(f, "unterminated quoted string")?,
+            CommandErrorKind::InvalidEscape => write!(f, "invalid escape in quoted string")?,
+        }
+        write!(f, " at position {}", self.position)
+    }
+}
+
+impl std::error::Error for CommandError {}
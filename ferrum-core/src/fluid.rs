@@ -0,0 +1,86 @@
+use crate::{BlockId, BlockState};
+
+/// Water, as numbered in the client's block types.
+pub const WATER: BlockId = BlockId(5);
+/// Lava, as numbered in the client's block types.
+pub const LAVA: BlockId = BlockId(6);
+
+/// Lowest flowing level. Level 0 is a source block, and each step away from
+/// the source drops the surface by one eighth of a block.
+pub const MAX_FLUID_LEVEL: u8 = 7;
+
+/// Property bit set on fluid falling down from above, which fills its cell.
+const FALLING: u16 = 0x8;
+
+/// Whether the block is water or lava, at any level.
+pub fn is_fluid(id: BlockId) -> bool {
+    id == WATER || id == LAVA
+}
+
+/// The fluid level packed in the state's properties, or `None` if the block is
+/// not a fluid.
+///
+/// Falling fluid reports level 0, as its surface is at the top of the cell
+/// like a source block.
+///
+/// # Examples
+///
+/// ```
+/// use ferrum_core::{fluid_level, BlockId, BlockState, WATER};
+///
+/// assert_eq!(fluid_level(BlockState::new(WATER, 3)), Some(3));
+/// assert_eq!(fluid_level(BlockState::from(BlockId::new(1))), None);
+/// ```
+pub fn fluid_level(state: BlockState) -> Option<u8> {
+    if !is_fluid(state.id()) {
+        return None;
+    }
+    let properties = state.properties();
+    if properties & FALLING != 0 {
+        Some(0)
+    } else {
+        Some((properties as u8) & MAX_FLUID_LEVEL)
+    }
+}
+
+/// Whether both states are the same fluid, ignoring level. Faces between them
+/// are inside one body of fluid.
+pub fn is_same_fluid(a: BlockState, b: BlockState) -> bool {
+    is_fluid(a.id()) && a.id() == b.id()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_water_levels_are_same_fluid() {
+        let source = BlockState::from(WATER);
+        let flowing = BlockState::new(WATER, 5);
+
+        assert_ne!(source, flowing);
+        assert!(is_same_fluid(source, flowing));
+        assert!(is_same_fluid(flowing, source));
+        assert_eq!(fluid_level(source), Some(0));
+        assert_eq!(fluid_level(flowing), Some(5));
+    }
+
+    #[test]
+    fn test_different_fluids_and_solids_are_not_same_fluid() {
+        let water = BlockState::from(WATER);
+        let lava = BlockState::new(LAVA, 2);
+        let stone = BlockState::from(BlockId::new(1));
+
+        assert!(!is_same_fluid(water, lava));
+        assert!(!is_same_fluid(water, stone));
+        assert!(!is_same_fluid(stone, stone));
+        assert!(!is_same_fluid(water, BlockState::default()));
+        assert_eq!(fluid_level(stone), None);
+        assert_eq!(fluid_level(lava), Some(2));
+    }
+
+    #[test]
+    fn test_falling_fluid_fills_its_cell() {
+        assert_eq!(fluid_level(BlockState::new(WATER, 0x8 | 6)), Some(0));
+    }
+}
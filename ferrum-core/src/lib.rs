@@ -1,3 +1,26 @@
+mod block_state;
+mod command;
+mod error;
+mod fluid;
+mod player_save;
+mod properties;
+mod registry;
+mod state_definition;
+
+pub use block_state::BlockState;
+pub use command::{Arg, ArgKind, Command, CommandParser, CommandSyntax, Selector, SelectorKind};
+pub use error::{BlockError, CommandError, CommandErrorKind, SaveError};
+pub use fluid::{fluid_level, is_fluid, is_same_fluid, LAVA, MAX_FLUID_LEVEL, WATER};
+pub use player_save::{
+    GameMode, PlayerSave, SavedItem, ARMOR_SLOT_START, OFFHAND_SLOT, PLAYER_SAVE_FILE,
+    PLAYER_SAVE_VERSION,
+};
+pub use properties::{properties, BlockProperties, GLOWSTONE, LEAVES, MAX_LIGHT_EMISSION};
+pub use registry::{
+    init, registries, try_registries, Registries, RegistriesConfig, Registry, RegistryError,
+};
+pub use state_definition::{Property, StateDefinition};
+
 /// Unique identifier for a block type in the Minecraft world.
 ///
 /// BlockId represents a specific block type (e.g., stone, dirt, air).
@@ -27,6 +50,20 @@ impl BlockId {
     }
 }
 
+impl TryFrom<u32> for BlockId {
+    type Error = BlockError;
+
+    /// Fails with [`BlockError::IdOutOfRange`] for ids past `u16::MAX`.
+    fn try_from(id: u32) -> Result<Self, Self::Error> {
+        u16::try_from(id)
+            .map(BlockId)
+            .map_err(|_| BlockError::IdOutOfRange {
+                id,
+                max: u16::MAX as u32,
+            })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,152 @@
+//! The player's saved state: position, health, hunger, experience, game mode
+//! and inventory, stored as JSON in the world directory.
+//!
+//! Saves carry a format version. Older saves are upgraded step by step on
+//! load, so each format change only needs a migration from the one before.
+
+use crate::error::SaveError;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Current save format version.
+pub const PLAYER_SAVE_VERSION: u32 = 2;
+
+/// Name of the player save inside a world directory.
+pub const PLAYER_SAVE_FILE: &str = "player.json";
+
+/// Slot number of the first armor slot (boots), as in vanilla.
+pub const ARMOR_SLOT_START: i16 = 100;
+
+/// Slot number of the offhand, as in vanilla.
+pub const OFFHAND_SLOT: i16 = -106;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameMode {
+    #[default]
+    Survival,
+    Creative,
+    Adventure,
+    Spectator,
+}
+
+/// One occupied inventory slot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedItem {
+    /// 0-35 for the main inventory (0-8 being the hotbar), then
+    /// [`ARMOR_SLOT_START`].. for armor and [`OFFHAND_SLOT`].
+    pub slot: i16,
+    pub item_id: u16,
+    pub count: u8,
+    #[serde(default)]
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerSave {
+    pub version: u32,
+    pub position: [f64; 3],
+    pub yaw: f32,
+    pub pitch: f32,
+    pub health: f32,
+    pub hunger: f32,
+    pub xp_level: u32,
+    pub xp_progress: f32,
+    pub game_mode: GameMode,
+    pub selected_slot: u8,
+    pub inventory: Vec<SavedItem>,
+}
+
+impl Default for PlayerSave {
+    fn default() -> Self {
+        Self {
+            version: PLAYER_SAVE_VERSION,
+            position: [0.0, 80.0, 0.0],
+            yaw: 0.0,
+            pitch: 0.0,
+            health: 20.0,
+            hunger: 20.0,
+            xp_level: 0,
+            xp_progress: 0.0,
+            game_mode: GameMode::Survival,
+            selected_slot: 0,
+            inventory: Vec::new(),
+        }
+    }
+}
+
+impl PlayerSave {
+    /// Path of the player save in `world_dir`.
+    pub fn path(world_dir: &Path) -> PathBuf {
+        world_dir.join(PLAYER_SAVE_FILE)
+    }
+
+    pub fn to_json(&self) -> Result<String, SaveError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parse a save of any known version, upgrading it to the current one.
+    pub fn from_json(json: &str) -> Result<Self, SaveError> {
+        let value: Value = serde_json::from_str(json)?;
+        let value = migrate(value)?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Write to `world_dir`, creating it if needed. The old save is only
+    /// replaced once the new one is fully written.
+    pub fn save(&self, world_dir: &Path) -> Result<(), SaveError> {
+        fs::create_dir_all(world_dir)?;
+        let path = Self::path(world_dir);
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, self.to_json()?)?;
+        fs::rename(&temp, &path)?;
+        Ok(())
+    }
+
+    /// Read the save in `world_dir`, or `None` if the world has none yet.
+    pub fn load(world_dir: &Path) -> Result<Option<Self>, SaveError> {
+        match fs::read_to_string(Self::path(world_dir)) {
+            Ok(json) => Self::from_json(&json).map(Some),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Upgrade a parsed save to [`PLAYER_SAVE_VERSION`]. Saves without a version
+/// predate versioning and are version 1.
+fn migrate(mut value: Value) -> Result<Value, SaveError> {
+    let Some(fields) = value.as_object_mut() else {
+        // Let deserialization report the type error
+        return Ok(value);
+    };
+    let mut version = match fields.get("version").and_then(Value::as_u64) {
+        Some(version) => u32::try_from(version).unwrap_or(u32::MAX),
+        None => 1,
+    };
+    if version > PLAYER_SAVE_VERSION {
+        return Err(SaveError::UnsupportedVersion(version));
+    }
+
+    while version < PLAYER_SAVE_VERSION {
+        match version {
+            1 => migrate_v1(fields),
+            _ => return Err(SaveError::UnsupportedVersion(version)),
+        }
+        version += 1;
+    }
+    fields.insert("version".into(), version.into());
+    Ok(value)
+}
+
+/// Version 1 called hunger `food` and had no experience or game mode.
+fn migrate_v1(fields: &mut Map<String, Value>) {
+    if let Some(food) = fields.remove("food") {
+        fields.insert("hunger".into(), food);
+    }
+    fields.entry("xp_level").or_insert(0.into());
+    fields.entry("xp_progress").or_insert(0.0.into());
+    fields.entry("game_mode").or_insert("survival".into());
+}
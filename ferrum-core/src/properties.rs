@@ -0,0 +1,43 @@
+use crate::{is_fluid, BlockId, LAVA};
+
+/// Glowstone, as numbered in the client's block types.
+pub const GLOWSTONE: BlockId = BlockId(23);
+
+/// Leaves, as numbered in the client's block types.
+pub const LEAVES: BlockId = BlockId(13);
+
+/// Brightest block light a block can emit.
+pub const MAX_LIGHT_EMISSION: u8 = 15;
+
+/// Fixed per-block data shared by lighting and rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BlockProperties {
+    /// Block light the block gives off, from 0 (none) to
+    /// [`MAX_LIGHT_EMISSION`].
+    pub light_emission: u8,
+    /// Whether the block stops light. Air and fluids let it through.
+    pub opaque: bool,
+}
+
+/// Properties of a block type.
+///
+/// # Examples
+///
+/// ```
+/// use ferrum_core::{properties, BlockId, GLOWSTONE};
+///
+/// assert_eq!(properties(GLOWSTONE).light_emission, 15);
+/// assert_eq!(properties(BlockId::new(1)).light_emission, 0);
+/// assert!(properties(BlockId::new(1)).opaque);
+/// assert!(!properties(BlockId::new(0)).opaque);
+/// ```
+pub fn properties(id: BlockId) -> BlockProperties {
+    let light_emission = match id {
+        GLOWSTONE | LAVA => MAX_LIGHT_EMISSION,
+        _ => 0,
+    };
+    BlockProperties {
+        light_emission,
+        opaque: id != BlockId(0) && !is_fluid(id),
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{BlockError, BlockId, BlockState, Property, StateDefinition};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::OnceLock;
+
+static REGISTRIES: OnceLock<Registries> = OnceLock::new();
+
+/// Contents of the global registries, usually taken from the server's
+/// registry data or the bundled vanilla data.
+#[derive(Debug, Clone, Default)]
+pub struct RegistriesConfig {
+    /// Block names in id order.
+    pub blocks: Vec<String>,
+    /// Item names in id order.
+    pub items: Vec<String>,
+    /// Block tag name to the names of the blocks it contains.
+    pub block_tags: HashMap<String, Vec<String>>,
+    /// Block name to its state properties. Blocks left out have none.
+    pub block_states: HashMap<String, Vec<Property>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    /// [`init`] was called more than once.
+    AlreadyInitialized,
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::AlreadyInitialized => write!(f, "registries are already initialized"),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// Bidirectional mapping between names and numeric ids.
+#[derive(Debug, Clone, Default)]
+pub struct Registry {
+    names: Vec<String>,
+    ids: HashMap<String, u32>,
+}
+
+impl Registry {
+    /// Assign ids in order. A repeated name keeps its first id.
+    pub fn new(names: Vec<String>) -> Self {
+        let mut ids = HashMap::with_capacity(names.len());
+        for (id, name) in names.iter().enumerate() {
+            ids.entry(name.clone()).or_insert(id as u32);
+        }
+        Self { names, ids }
+    }
+
+    pub fn id(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: u32) -> Option<&str> {
+        self.names.get(id as usize).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// Block, item and tag registries shared by every thread. Immutable once
+/// built, so reads need no locking.
+#[derive(Debug, Clone, Default)]
+pub struct Registries {
+    blocks: Registry,
+    items: Registry,
+    block_tags: HashMap<String, HashSet<BlockId>>,
+    block_states: HashMap<BlockId, StateDefinition>,
+}
+
+impl Registries {
+    /// Build registries from `config`. Tag and state entries naming unknown
+    /// blocks, or blocks past the `u16` id space, are skipped, as are state
+    /// entries with a property that has no values.
+    pub fn from_config(config: RegistriesConfig) -> Self {
+        let blocks = Registry::new(config.blocks);
+        let items = Registry::new(config.items);
+        let block_id = |name: &str| blocks.id(name).and_then(|id| BlockId::try_from(id).ok());
+        let block_tags = config
+            .block_tags
+            .into_iter()
+            .map(|(tag, names)| {
+                let members = names.iter().filter_map(|name| block_id(name)).collect();
+                (tag, members)
+            })
+            .collect();
+        let block_states = config
+            .block_states
+            .into_iter()
+            .filter_map(|(name, properties)| {
+                Some((block_id(&name)?, StateDefinition::new(properties).ok()?))
+            })
+            .collect();
+        Self {
+            blocks,
+            items,
+            block_tags,
+            block_states,
+        }
+    }
+
+    pub fn blocks(&self) -> &Registry {
+        &self.blocks
+    }
+
+    pub fn items(&self) -> &Registry {
+        &self.items
+    }
+
+    pub fn block(&self, name: &str) -> Option<BlockId> {
+        self.blocks.id(name).map(|id| BlockId::new(id as u16))
+    }
+
+    pub fn block_name(&self, block: BlockId) -> Option<&str> {
+        self.blocks.name(block.as_u16() as u32)
+    }
+
+    /// Properties of `block`, or `None` if it has none.
+    pub fn state_definition(&self, block: BlockId) -> Option<&StateDefinition> {
+        self.block_states.get(&block)
+    }
+
+    /// The state of the block called `name` with `properties` set and every
+    /// other property at its first value.
+    pub fn block_state(
+        &self,
+        name: &str,
+        properties: &[(&str, &str)],
+    ) -> Result<BlockState, BlockError> {
+        let block = self
+            .block(name)
+            .ok_or_else(|| BlockError::UnknownName(name.to_string()))?;
+        properties
+            .iter()
+            .try_fold(BlockState::from(block), |state, (property, value)| {
+                self.set_property(state, property, value)
+            })
+    }
+
+    /// Current value of `property` in `state`.
+    pub fn property(&self, state: BlockState, property: &str) -> Result<&str, BlockError> {
+        self.definition_of(state, property)?.get(state, property)
+    }
+
+    /// `state` with `property` set to `value`.
+    pub fn set_property(
+        &self,
+        state: BlockState,
+        property: &str,
+        value: &str,
+    ) -> Result<BlockState, BlockError> {
+        self.definition_of(state, property)?
+            .set(state, property, value)
+    }
+
+    fn definition_of(
+        &self,
+        state: BlockState,
+        property: &str,
+    ) -> Result<&StateDefinition, BlockError> {
+        let id = state.id().as_u16() as u32;
+        if self.blocks.name(id).is_none() {
+            return Err(BlockError::IdOutOfRange {
+                id,
+                max: self.blocks.len().saturating_sub(1) as u32,
+            });
+        }
+        self.state_definition(state.id())
+            .ok_or_else(|| BlockError::PropertyNotFound {
+                block: state.id(),
+                property: property.to_string(),
+            })
+    }
+
+    /// Whether `block` is in the block tag `tag`. Unknown tags contain nothing.
+    pub fn block_has_tag(&self, block: BlockId, tag: &str) -> bool {
+        self.block_tags
+            .get(tag)
+            .is_some_and(|members| members.contains(&block))
+    }
+}
+
+/// Initialize the global registries. Only the first call takes effect; later
+/// calls return [`RegistryError::AlreadyInitialized`] and leave the existing
+/// registries untouched.
+pub fn init(config: RegistriesConfig) -> Result<&'static Registries, RegistryError> {
+    let mut initialized = false;
+    let registries = REGISTRIES.get_or_init(|| {
+        initialized = true;
+        Registries::from_config(config)
+    });
+    if initialized {
+        Ok(registries)
+    } else {
+        Err(RegistryError::AlreadyInitialized)
+    }
+}
+
+/// The global registries, or `None` before [`init`].
+pub fn try_registries() -> Option<&'static Registries> {
+    REGISTRIES.get()
+}
+
+/// The global registries.
+///
+/// # Panics
+///
+/// Panics if called before [`init`].
+pub fn registries() -> &'static Registries {
+    try_registries().expect("ferrum_core::registries() called before ferrum_core::init()")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RegistriesConfig {
+        RegistriesConfig {
+            blocks: vec!["air".into(), "stone".into(), "oak_log".into()],
+            items: vec!["stone".into(), "diamond_sword".into()],
+            block_tags: HashMap::from([(
+                "logs".to_string(),
+                vec!["oak_log".to_string(), "missing_log".to_string()],
+            )]),
+            block_states: HashMap::from([(
+                "oak_log".to_string(),
+                vec![Property::new("axis", ["x", "y", "z"])],
+            )]),
+        }
+    }
+
+    #[test]
+    fn test_lookup_by_name_and_id() {
+        let registries = Registries::from_config(config());
+
+        assert_eq!(registries.block("stone"), Some(BlockId::new(1)));
+        assert_eq!(registries.block_name(BlockId::new(2)), Some("oak_log"));
+        assert_eq!(registries.block("dirt"), None);
+        assert_eq!(registries.items().id("diamond_sword"), Some(1));
+        assert_eq!(registries.items().name(5), None);
+    }
+
+    #[test]
+    fn test_block_tags() {
+        let registries = Registries::from_config(config());
+
+        assert!(registries.block_has_tag(BlockId::new(2), "logs"));
+        assert!(!registries.block_has_tag(BlockId::new(1), "logs"));
+        assert!(!registries.block_has_tag(BlockId::new(2), "leaves"));
+    }
+}
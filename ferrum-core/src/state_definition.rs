@@ -0,0 +1,160 @@
+use crate::{BlockError, BlockState};
+
+/// A named block-state property and the values it can take, e.g. `axis`
+/// with `x`, `y` and `z`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Property {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+impl Property {
+    pub fn new(
+        name: impl Into<String>,
+        values: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            values: values.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// The properties of one block and how their values are packed into
+/// [`BlockState::properties`].
+///
+/// Values are packed in mixed radix, the first property varying fastest, so
+/// every combination has exactly one encoding and all zero bits is the state
+/// with each property at its first value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateDefinition {
+    properties: Vec<Property>,
+}
+
+impl StateDefinition {
+    /// Fails with [`BlockError::EmptyProperty`] if a property has no values.
+    pub fn new(properties: Vec<Property>) -> Result<Self, BlockError> {
+        if let Some(empty) = properties
+            .iter()
+            .find(|property| property.values.is_empty())
+        {
+            return Err(BlockError::EmptyProperty(empty.name.clone()));
+        }
+        Ok(Self { properties })
+    }
+
+    pub fn properties(&self) -> &[Property] {
+        &self.properties
+    }
+
+    /// Number of distinct states: the product of every property's value
+    /// count.
+    pub fn state_count(&self) -> u32 {
+        self.properties
+            .iter()
+            .map(|property| property.values.len() as u32)
+            .product()
+    }
+
+    /// Current value of `property` in `state`.
+    pub fn get<'a>(&'a self, state: BlockState, property: &str) -> Result<&'a str, BlockError> {
+        let (index, stride) = self.find(state, property)?;
+        let values = &self.properties[index].values;
+        let value = (state.properties() as u32 / stride) as usize % values.len();
+        Ok(&values[value])
+    }
+
+    /// `state` with `property` set to `value`.
+    pub fn set(
+        &self,
+        state: BlockState,
+        property: &str,
+        value: &str,
+    ) -> Result<BlockState, BlockError> {
+        let (index, stride) = self.find(state, property)?;
+        let values = &self.properties[index].values;
+        let Some(new) = values.iter().position(|v| v == value) else {
+            return Err(BlockError::InvalidPropertyValue {
+                property: property.to_string(),
+                value: value.to_string(),
+            });
+        };
+
+        let bits = state.properties() as u32;
+        let old = bits / stride % values.len() as u32;
+        let bits = bits - old * stride + new as u32 * stride;
+        let bits = u16::try_from(bits).map_err(|_| BlockError::PropertiesOverflow { bits })?;
+        Ok(state.with_properties(bits))
+    }
+
+    /// Index of `property` and the stride of its digit in the packed bits.
+    fn find(&self, state: BlockState, property: &str) -> Result<(usize, u32), BlockError> {
+        let mut stride = 1u32;
+        for (index, candidate) in self.properties.iter().enumerate() {
+            if candidate.name == property {
+                return Ok((index, stride));
+            }
+            stride = stride.saturating_mul(candidate.values.len() as u32);
+        }
+        Err(BlockError::PropertyNotFound {
+            block: state.id(),
+            property: property.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BlockId;
+
+    fn stairs() -> StateDefinition {
+        StateDefinition::new(vec![
+            Property::new("facing", ["north", "south", "west", "east"]),
+            Property::new("half", ["top", "bottom"]),
+            Property::new("waterlogged", ["true", "false"]),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_set_and_get_round_trip() {
+        let definition = stairs();
+        let state = BlockState::from(BlockId::new(40));
+        assert_eq!(definition.state_count(), 16);
+        assert_eq!(definition.get(state, "facing"), Ok("north"));
+
+        let state = definition.set(state, "half", "bottom").unwrap();
+        let state = definition.set(state, "facing", "east").unwrap();
+        assert_eq!(definition.get(state, "facing"), Ok("east"));
+        assert_eq!(definition.get(state, "half"), Ok("bottom"));
+        assert_eq!(definition.get(state, "waterlogged"), Ok("true"));
+        assert_eq!(state.properties(), 3 + 4);
+        assert_eq!(state.id(), BlockId::new(40));
+    }
+
+    #[test]
+    fn test_property_without_values_is_rejected() {
+        let definition = StateDefinition::new(vec![
+            Property::new("axis", ["x", "y", "z"]),
+            Property::new("level", Vec::<String>::new()),
+        ]);
+        assert_eq!(
+            definition,
+            Err(BlockError::EmptyProperty("level".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_overflowing_property_bits() {
+        let wide = |name| Property::new(name, (0..256).map(|value| value.to_string()));
+        let definition = StateDefinition::new(vec![wide("a"), wide("b"), wide("c")]).unwrap();
+        let state = BlockState::from(BlockId::new(1));
+        assert_eq!(
+            definition.set(state, "c", "1"),
+            Err(BlockError::PropertiesOverflow { bits: 65_536 })
+        );
+        let state = definition.set(state, "b", "255").unwrap();
+        assert_eq!(definition.get(state, "b"), Ok("255"));
+    }
+}
@@ -0,0 +1,114 @@
+use ferrum_core::{BlockError, BlockId, BlockState, Property, Registries, RegistriesConfig};
+use std::collections::HashMap;
+
+fn registries() -> Registries {
+    Registries::from_config(RegistriesConfig {
+        blocks: ["air", "stone", "oak_log", "oak_stairs"]
+            .map(String::from)
+            .to_vec(),
+        block_states: HashMap::from([
+            (
+                "oak_log".to_string(),
+                vec![Property::new("axis", ["x", "y", "z"])],
+            ),
+            (
+                "oak_stairs".to_string(),
+                vec![
+                    Property::new("facing", ["north", "south", "west", "east"]),
+                    Property::new("half", ["top", "bottom"]),
+                ],
+            ),
+        ]),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_block_state_from_name_and_properties() {
+    let registries = registries();
+    let state = registries
+        .block_state("oak_stairs", &[("half", "bottom"), ("facing", "west")])
+        .unwrap();
+
+    assert_eq!(state.id(), BlockId::new(3));
+    assert_eq!(registries.property(state, "facing"), Ok("west"));
+    assert_eq!(registries.property(state, "half"), Ok("bottom"));
+
+    let log = registries.block_state("oak_log", &[]).unwrap();
+    assert_eq!(log, BlockState::from(BlockId::new(2)));
+    assert_eq!(registries.property(log, "axis"), Ok("x"));
+}
+
+#[test]
+fn test_unknown_name() {
+    let registries = registries();
+    assert_eq!(
+        registries.block_state("dirt", &[]),
+        Err(BlockError::UnknownName("dirt".to_string()))
+    );
+    // Plain lookups stay optional
+    assert_eq!(registries.block("dirt"), None);
+}
+
+#[test]
+fn test_id_out_of_range() {
+    let registries = registries();
+    assert_eq!(registries.block_name(BlockId::new(4)), None);
+    assert_eq!(
+        registries.property(BlockState::from(BlockId::new(9)), "axis"),
+        Err(BlockError::IdOutOfRange { id: 9, max: 3 })
+    );
+    assert_eq!(
+        BlockId::try_from(70_000u32),
+        Err(BlockError::IdOutOfRange {
+            id: 70_000,
+            max: u16::MAX as u32
+        })
+    );
+    assert_eq!(BlockId::try_from(7u32), Ok(BlockId::new(7)));
+}
+
+#[test]
+fn test_property_not_found() {
+    let registries = registries();
+    let log = BlockState::from(BlockId::new(2));
+    let stone = BlockState::from(BlockId::new(1));
+
+    assert_eq!(
+        registries.set_property(log, "facing", "north"),
+        Err(BlockError::PropertyNotFound {
+            block: BlockId::new(2),
+            property: "facing".to_string(),
+        })
+    );
+    assert_eq!(
+        registries.property(stone, "axis"),
+        Err(BlockError::PropertyNotFound {
+            block: BlockId::new(1),
+            property: "axis".to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_invalid_property_value() {
+    let registries = registries();
+    let log = BlockState::from(BlockId::new(2));
+
+    let err = registries.set_property(log, "axis", "w").unwrap_err();
+    assert_eq!(
+        err,
+        BlockError::InvalidPropertyValue {
+            property: "axis".to_string(),
+            value: "w".to_string(),
+        }
+    );
+    assert_eq!(err.to_string(), "invalid value \"w\" for property axis");
+    assert_eq!(
+        registries.block_state("oak_stairs", &[("half", "middle")]),
+        Err(BlockError::InvalidPropertyValue {
+            property: "half".to_string(),
+            value: "middle".to_string(),
+        })
+    );
+}
@@ -0,0 +1,133 @@
+use ferrum_core::{
+    Arg, ArgKind, CommandErrorKind, CommandParser, CommandSyntax, Registries, RegistriesConfig,
+    Selector, SelectorKind,
+};
+
+fn registries() -> Registries {
+    Registries::from_config(RegistriesConfig {
+        items: ["air", "stone", "dirt", "diamond_sword"]
+            .map(String::from)
+            .to_vec(),
+        ..Default::default()
+    })
+}
+
+fn nearest_player() -> Arg {
+    Arg::Selector(Selector::Entities {
+        kind: SelectorKind::NearestPlayer,
+        filters: Vec::new(),
+    })
+}
+
+#[test]
+fn test_parse_typed_arguments() {
+    let parser = CommandParser::standard();
+    let registries = registries();
+
+    let give = parser.parse("/give @p stone 64", &registries).unwrap();
+    assert_eq!(give.name, "give");
+    assert_eq!(give.args, [nearest_player(), Arg::Item(1), Arg::Int(64)]);
+
+    // The count is optional and items may be namespaced
+    let give = parser
+        .parse("give Steve minecraft:diamond_sword", &registries)
+        .unwrap();
+    assert_eq!(
+        give.args,
+        [
+            Arg::Selector(Selector::Player("Steve".into())),
+            Arg::Item(3)
+        ]
+    );
+
+    let kill = parser
+        .parse("/kill @e[type=zombie, limit=2]", &registries)
+        .unwrap();
+    assert_eq!(
+        kill.args,
+        [Arg::Selector(Selector::Entities {
+            kind: SelectorKind::AllEntities,
+            filters: vec![
+                ("type".into(), "zombie".into()),
+                ("limit".into(), "2".into())
+            ],
+        })]
+    );
+
+    let list = parser.parse("/list", &registries).unwrap();
+    assert!(list.args.is_empty());
+
+    // Messages keep the rest of the line as typed
+    let say = parser
+        .parse("/say  hello   \"world\"", &registries)
+        .unwrap();
+    assert_eq!(say.args, [Arg::Message("hello   \"world\"".into())]);
+}
+
+#[test]
+fn test_parse_quoted_strings() {
+    let mut parser = CommandParser::new();
+    parser.register(CommandSyntax::new(
+        "nick",
+        &[ArgKind::Selector, ArgKind::String],
+    ));
+    let registries = registries();
+
+    let nick = parser
+        .parse(r#"/nick @p "The \"Great\" Steve""#, &registries)
+        .unwrap();
+    assert_eq!(
+        nick.args,
+        [nearest_player(), Arg::String(r#"The "Great" Steve"#.into())]
+    );
+
+    let nick = parser.parse("/nick @p Steve", &registries).unwrap();
+    assert_eq!(nick.args[1], Arg::String("Steve".into()));
+
+    let error = parser
+        .parse(r#"/nick @p "never closed"#, &registries)
+        .unwrap_err();
+    assert_eq!(error.kind, CommandErrorKind::UnterminatedQuote);
+    assert_eq!(error.position, 9);
+}
+
+#[test]
+fn test_errors_point_at_the_offending_token() {
+    let parser = CommandParser::standard();
+    let registries = registries();
+
+    let error = parser
+        .parse("/give @p stone lots", &registries)
+        .unwrap_err();
+    assert_eq!(error.kind, CommandErrorKind::InvalidInt("lots".into()));
+    assert_eq!(error.position, 15);
+    assert_eq!(error.to_string(), "invalid integer \"lots\" at position 15");
+
+    let error = parser
+        .parse("/give @p stone 99999999999", &registries)
+        .unwrap_err();
+    assert_eq!(error.position, 15);
+
+    let error = parser.parse("/give @p bedrock", &registries).unwrap_err();
+    assert_eq!(error.kind, CommandErrorKind::UnknownItem("bedrock".into()));
+    assert_eq!(error.position, 9);
+
+    let error = parser.parse("/give @x stone", &registries).unwrap_err();
+    assert_eq!(error.kind, CommandErrorKind::InvalidSelector("@x".into()));
+    assert_eq!(error.position, 6);
+
+    let error = parser.parse("/give @p", &registries).unwrap_err();
+    assert_eq!(error.kind, CommandErrorKind::MissingArgument(ArgKind::Item));
+    assert_eq!(error.position, 8);
+
+    let error = parser.parse("/list everyone", &registries).unwrap_err();
+    assert_eq!(error.kind, CommandErrorKind::TooManyArguments);
+    assert_eq!(error.position, 6);
+
+    let error = parser.parse("/fly", &registries).unwrap_err();
+    assert_eq!(error.kind, CommandErrorKind::UnknownCommand("fly".into()));
+    assert_eq!(error.position, 1);
+
+    let error = parser.parse("  ", &registries).unwrap_err();
+    assert_eq!(error.kind, CommandErrorKind::Empty);
+}
@@ -0,0 +1,112 @@
+use ferrum_core::{
+    GameMode, PlayerSave, SaveError, SavedItem, ARMOR_SLOT_START, OFFHAND_SLOT, PLAYER_SAVE_VERSION,
+};
+use tempfile::TempDir;
+
+fn full_save() -> PlayerSave {
+    PlayerSave {
+        version: PLAYER_SAVE_VERSION,
+        position: [120.5, 64.0, -33.25],
+        yaw: 1.25,
+        pitch: -0.5,
+        health: 13.5,
+        hunger: 7.0,
+        xp_level: 30,
+        xp_progress: 0.75,
+        game_mode: GameMode::Creative,
+        selected_slot: 4,
+        inventory: vec![
+            SavedItem {
+                slot: 0,
+                item_id: 1,
+                count: 64,
+                name: "Stone".into(),
+            },
+            SavedItem {
+                slot: 35,
+                item_id: 264,
+                count: 1,
+                name: "Diamond Sword".into(),
+            },
+            SavedItem {
+                slot: ARMOR_SLOT_START + 3,
+                item_id: 310,
+                count: 1,
+                name: "Diamond Helmet".into(),
+            },
+            SavedItem {
+                slot: OFFHAND_SLOT,
+                item_id: 50,
+                count: 16,
+                name: "Torch".into(),
+            },
+        ],
+    }
+}
+
+#[test]
+fn full_save_round_trips_through_world_dir() {
+    let dir = TempDir::new().unwrap();
+    let world = dir.path().join("world");
+
+    assert!(PlayerSave::load(&world).unwrap().is_none());
+
+    let save = full_save();
+    save.save(&world).unwrap();
+    assert_eq!(PlayerSave::load(&world).unwrap(), Some(save.clone()));
+
+    // Saving again replaces the old file
+    let moved = PlayerSave {
+        position: [0.0, 70.0, 0.0],
+        ..save
+    };
+    moved.save(&world).unwrap();
+    assert_eq!(PlayerSave::load(&world).unwrap(), Some(moved));
+    assert_eq!(std::fs::read_dir(&world).unwrap().count(), 1);
+}
+
+#[test]
+fn version_one_saves_are_migrated() {
+    let v1 = r#"{
+        "position": [10.0, 65.0, 10.0],
+        "yaw": 0.0,
+        "pitch": 0.0,
+        "health": 18.0,
+        "food": 12.0,
+        "selected_slot": 2,
+        "inventory": [{ "slot": 0, "item_id": 3, "count": 32 }]
+    }"#;
+
+    let save = PlayerSave::from_json(v1).unwrap();
+    assert_eq!(save.version, PLAYER_SAVE_VERSION);
+    assert_eq!(save.hunger, 12.0);
+    assert_eq!(save.health, 18.0);
+    assert_eq!(save.xp_level, 0);
+    assert_eq!(save.game_mode, GameMode::Survival);
+    assert_eq!(save.inventory[0].count, 32);
+    assert!(save.inventory[0].name.is_empty());
+
+    // A migrated save is written back in the current format
+    let reloaded = PlayerSave::from_json(&save.to_json().unwrap()).unwrap();
+    assert_eq!(reloaded, save);
+}
+
+#[test]
+fn newer_or_malformed_saves_are_rejected() {
+    let mut json: serde_json::Value =
+        serde_json::from_str(&full_save().to_json().unwrap()).unwrap();
+    json["version"] = (PLAYER_SAVE_VERSION + 1).into();
+    assert!(matches!(
+        PlayerSave::from_json(&json.to_string()),
+        Err(SaveError::UnsupportedVersion(v)) if v == PLAYER_SAVE_VERSION + 1
+    ));
+
+    assert!(matches!(
+        PlayerSave::from_json("{\"version\": 2}"),
+        Err(SaveError::Malformed(_))
+    ));
+    assert!(matches!(
+        PlayerSave::from_json("not json"),
+        Err(SaveError::Malformed(_))
+    ));
+}
@@ -0,0 +1,70 @@
+use ferrum_core::{BlockId, RegistriesConfig, RegistryError};
+use std::collections::HashMap;
+use std::thread;
+
+fn vanilla() -> RegistriesConfig {
+    RegistriesConfig {
+        blocks: ["air", "stone", "grass_block", "dirt", "oak_log"]
+            .map(String::from)
+            .to_vec(),
+        items: ["stone", "dirt", "diamond_sword"]
+            .map(String::from)
+            .to_vec(),
+        block_tags: HashMap::from([(
+            "dirt".to_string(),
+            vec!["grass_block".to_string(), "dirt".to_string()],
+        )]),
+        block_states: HashMap::new(),
+    }
+}
+
+#[test]
+fn second_init_is_rejected() {
+    // Another test may have initialized first, with the same contents.
+    let _ = ferrum_core::init(vanilla());
+
+    let mut other = vanilla();
+    other.blocks = vec!["air".to_string(), "netherrack".to_string()];
+    assert_eq!(
+        ferrum_core::init(other).unwrap_err(),
+        RegistryError::AlreadyInitialized
+    );
+
+    let registries = ferrum_core::registries();
+    assert_eq!(registries.block("stone"), Some(BlockId::new(1)));
+    assert_eq!(registries.block("netherrack"), None);
+}
+
+#[test]
+fn concurrent_reads_are_consistent() {
+    let _ = ferrum_core::init(vanilla());
+    let expected = ferrum_core::registries() as *const _;
+
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            thread::spawn(|| {
+                let mut results = Vec::new();
+                for _ in 0..1000 {
+                    let registries = ferrum_core::registries();
+                    let dirt = registries.block("dirt").unwrap();
+                    results.push((
+                        registries as *const _ as usize,
+                        dirt,
+                        registries.block_has_tag(dirt, "dirt"),
+                        registries.items().id("diamond_sword"),
+                    ));
+                }
+                results
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        for (address, dirt, tagged, sword) in handle.join().unwrap() {
+            assert_eq!(address, expected as usize);
+            assert_eq!(dirt, BlockId::new(3));
+            assert!(tagged);
+            assert_eq!(sword, Some(2));
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::items::{self, ToolKind};
+use crate::ItemStack;
+use ferrum_core::BlockState;
+
+/// How a block drops when broken.
+struct DropRule {
+    /// The block as an item, dropped instead when mined with silk touch.
+    block_item: u16,
+    /// Tool kind and mining level needed to drop anything.
+    requires: Option<(ToolKind, u8)>,
+    /// Item and count dropped normally, or `None` if only silk touch gets
+    /// anything.
+    drop: Option<(u16, u8)>,
+}
+
+/// Drop rule for a block type, numbered as in the client's block types.
+/// `None` for blocks that never drop, such as air, fluids and bedrock.
+fn drop_rule(block: u16) -> Option<DropRule> {
+    const PICKAXE: Option<(ToolKind, u8)> = Some((ToolKind::Pickaxe, 0));
+    let rule = |block_item, requires, drop| {
+        Some(DropRule {
+            block_item,
+            requires,
+            drop,
+        })
+    };
+    let itself = |item| rule(item, None, Some((item, 1)));
+
+    match block {
+        1 => rule(items::STONE, PICKAXE, Some((items::COBBLESTONE, 1))),
+        2 => itself(items::DIRT),
+        3 => rule(items::GRASS_BLOCK, None, Some((items::DIRT, 1))),
+        7 => itself(items::SAND),
+        8 => itself(items::GRAVEL),
+        9 => rule(
+            items::GOLD_ORE,
+            Some((ToolKind::Pickaxe, 2)),
+            Some((items::RAW_GOLD, 1)),
+        ),
+        10 => rule(
+            items::IRON_ORE,
+            Some((ToolKind::Pickaxe, 1)),
+            Some((items::RAW_IRON, 1)),
+        ),
+        11 => rule(items::COAL_ORE, PICKAXE, Some((items::COAL, 1))),
+        12 => itself(items::OAK_LOG),
+        13 => rule(items::OAK_LEAVES, None, None),
+        14 => itself(items::OAK_PLANKS),
+        15 => rule(items::COBBLESTONE, PICKAXE, Some((items::COBBLESTONE, 1))),
+        16 => rule(
+            items::DIAMOND_ORE,
+            Some((ToolKind::Pickaxe, 2)),
+            Some((items::DIAMOND, 1)),
+        ),
+        17 => rule(
+            items::DEEPSLATE,
+            PICKAXE,
+            Some((items::COBBLED_DEEPSLATE, 1)),
+        ),
+        18 => rule(
+            items::SNOW_BLOCK,
+            Some((ToolKind::Shovel, 0)),
+            Some((items::SNOWBALL, 4)),
+        ),
+        19 => rule(items::ICE, None, None),
+        20 => rule(items::CLAY, None, Some((items::CLAY_BALL, 4))),
+        21 => rule(
+            items::OBSIDIAN,
+            Some((ToolKind::Pickaxe, 3)),
+            Some((items::OBSIDIAN, 1)),
+        ),
+        22 => rule(items::NETHERRACK, PICKAXE, Some((items::NETHERRACK, 1))),
+        23 => rule(items::GLOWSTONE, None, Some((items::GLOWSTONE_DUST, 3))),
+        24 => itself(items::SOUL_SAND),
+        25 => rule(items::TERRACOTTA, PICKAXE, Some((items::TERRACOTTA, 1))),
+        _ => None,
+    }
+}
+
+/// Items dropped by breaking `state` with `tool` in hand, or bare-handed if
+/// `None`.
+///
+/// Blocks that need a tool, like stone and ores, drop nothing without one of
+/// the right kind and tier. A silk touch tool drops the block itself rather
+/// than what it normally drops, but still has to be the right tool.
+///
+/// # Examples
+///
+/// ```
+/// use ferrum_core::{BlockId, BlockState};
+/// use ferrum_inventory::{drops_of, items, ItemStack};
+///
+/// let stone = BlockState::from(BlockId::new(1));
+/// let pickaxe = ItemStack::new(items::WOODEN_PICKAXE, 1, 1);
+/// assert_eq!(drops_of(stone, Some(&pickaxe))[0].item_id, items::COBBLESTONE);
+/// assert!(drops_of(stone, None).is_empty());
+/// ```
+pub fn drops_of(state: BlockState, tool: Option<&ItemStack>) -> Vec<ItemStack> {
+    let Some(rule) = drop_rule(state.id().as_u16()) else {
+        return Vec::new();
+    };
+
+    if let Some((kind, level)) = rule.requires {
+        let harvestable = tool
+            .and_then(|stack| items::tool(stack.item_id))
+            .is_some_and(|(held, tier)| held == kind && tier.mining_level() >= level);
+        if !harvestable {
+            return Vec::new();
+        }
+    }
+
+    let silk_touch = tool.is_some_and(|stack| stack.silk_touch);
+    let (item, count) = match (silk_touch, rule.drop) {
+        (true, _) => (rule.block_item, 1),
+        (false, Some(drop)) => drop,
+        (false, None) => return Vec::new(),
+    };
+    vec![ItemStack::new(item, count, items::max_stack_size(item))]
+}
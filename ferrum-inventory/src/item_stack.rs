@@ -3,6 +3,8 @@ pub struct ItemStack {
     pub item_id: u16,
     pub count: u8,
     pub max_stack_size: u8,
+    /// Enchanted to drop broken blocks as themselves.
+    pub silk_touch: bool,
 }
 
 impl ItemStack {
@@ -11,11 +13,19 @@ impl ItemStack {
             item_id,
             count,
             max_stack_size,
+            silk_touch: false,
         }
     }
 
+    pub fn with_silk_touch(mut self) -> Self {
+        self.silk_touch = true;
+        self
+    }
+
     pub fn can_stack_with(&self, other: &ItemStack) -> bool {
-        self.item_id == other.item_id && self.max_stack_size == other.max_stack_size
+        self.item_id == other.item_id
+            && self.max_stack_size == other.max_stack_size
+            && self.silk_touch == other.silk_touch
     }
 
     pub fn remaining_space(&self) -> u8 {
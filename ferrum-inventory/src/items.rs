@@ -0,0 +1,165 @@
+//! Item ids in the numbering of pre-flattening Minecraft, which the client
+//! uses throughout. Items added after numeric ids were retired are numbered
+//! from [`DEEPSLATE`] up.
+
+pub const STONE: u16 = 1;
+pub const GRASS_BLOCK: u16 = 2;
+pub const DIRT: u16 = 3;
+pub const COBBLESTONE: u16 = 4;
+pub const OAK_PLANKS: u16 = 5;
+pub const SAND: u16 = 12;
+pub const GRAVEL: u16 = 13;
+pub const GOLD_ORE: u16 = 14;
+pub const IRON_ORE: u16 = 15;
+pub const COAL_ORE: u16 = 16;
+pub const OAK_LOG: u16 = 17;
+pub const OAK_LEAVES: u16 = 18;
+pub const OBSIDIAN: u16 = 49;
+pub const DIAMOND_ORE: u16 = 56;
+pub const ICE: u16 = 79;
+pub const SNOW_BLOCK: u16 = 80;
+pub const CLAY: u16 = 82;
+pub const NETHERRACK: u16 = 87;
+pub const SOUL_SAND: u16 = 88;
+pub const GLOWSTONE: u16 = 89;
+pub const TERRACOTTA: u16 = 172;
+
+pub const IRON_SHOVEL: u16 = 256;
+pub const IRON_PICKAXE: u16 = 257;
+pub const IRON_AXE: u16 = 258;
+pub const COAL: u16 = 263;
+pub const DIAMOND: u16 = 264;
+pub const WOODEN_SHOVEL: u16 = 269;
+pub const WOODEN_PICKAXE: u16 = 270;
+pub const WOODEN_AXE: u16 = 271;
+pub const STONE_SHOVEL: u16 = 273;
+pub const STONE_PICKAXE: u16 = 274;
+pub const STONE_AXE: u16 = 275;
+pub const DIAMOND_SHOVEL: u16 = 277;
+pub const DIAMOND_PICKAXE: u16 = 278;
+pub const DIAMOND_AXE: u16 = 279;
+pub const GOLDEN_SHOVEL: u16 = 284;
+pub const GOLDEN_PICKAXE: u16 = 285;
+pub const GOLDEN_AXE: u16 = 286;
+pub const SNOWBALL: u16 = 332;
+pub const CLAY_BALL: u16 = 337;
+pub const GLOWSTONE_DUST: u16 = 348;
+
+pub const DEEPSLATE: u16 = 2300;
+pub const COBBLED_DEEPSLATE: u16 = 2301;
+pub const RAW_IRON: u16 = 2302;
+pub const RAW_GOLD: u16 = 2303;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolKind {
+    Pickaxe,
+    Shovel,
+    Axe,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolTier {
+    Wood,
+    Gold,
+    Stone,
+    Iron,
+    Diamond,
+}
+
+impl ToolTier {
+    /// Hardest blocks the tier can harvest. Gold tools mine fast but only
+    /// harvest what wood does.
+    pub fn mining_level(self) -> u8 {
+        match self {
+            ToolTier::Wood | ToolTier::Gold => 0,
+            ToolTier::Stone => 1,
+            ToolTier::Iron => 2,
+            ToolTier::Diamond => 3,
+        }
+    }
+}
+
+/// Kind and tier of a tool item, or `None` for anything else.
+pub fn tool(item_id: u16) -> Option<(ToolKind, ToolTier)> {
+    let tool = match item_id {
+        WOODEN_PICKAXE => (ToolKind::Pickaxe, ToolTier::Wood),
+        STONE_PICKAXE => (ToolKind::Pickaxe, ToolTier::Stone),
+        IRON_PICKAXE => (ToolKind::Pickaxe, ToolTier::Iron),
+        GOLDEN_PICKAXE => (ToolKind::Pickaxe, ToolTier::Gold),
+        DIAMOND_PICKAXE => (ToolKind::Pickaxe, ToolTier::Diamond),
+        WOODEN_SHOVEL => (ToolKind::Shovel, ToolTier::Wood),
+        STONE_SHOVEL => (ToolKind::Shovel, ToolTier::Stone),
+        IRON_SHOVEL => (ToolKind::Shovel, ToolTier::Iron),
+        GOLDEN_SHOVEL => (ToolKind::Shovel, ToolTier::Gold),
+        DIAMOND_SHOVEL => (ToolKind::Shovel, ToolTier::Diamond),
+        WOODEN_AXE => (ToolKind::Axe, ToolTier::Wood),
+        STONE_AXE => (ToolKind::Axe, ToolTier::Stone),
+        IRON_AXE => (ToolKind::Axe, ToolTier::Iron),
+        GOLDEN_AXE => (ToolKind::Axe, ToolTier::Gold),
+        DIAMOND_AXE => (ToolKind::Axe, ToolTier::Diamond),
+        _ => return None,
+    };
+    Some(tool)
+}
+
+pub fn max_stack_size(item_id: u16) -> u8 {
+    if tool(item_id).is_some() {
+        1
+    } else if item_id == SNOWBALL {
+        16
+    } else {
+        64
+    }
+}
+
+/// Display name of an item.
+pub fn item_name(item_id: u16) -> &'static str {
+    match item_id {
+        STONE => "Stone",
+        GRASS_BLOCK => "Grass Block",
+        DIRT => "Dirt",
+        COBBLESTONE => "Cobblestone",
+        OAK_PLANKS => "Oak Planks",
+        SAND => "Sand",
+        GRAVEL => "Gravel",
+        GOLD_ORE => "Gold Ore",
+        IRON_ORE => "Iron Ore",
+        COAL_ORE => "Coal Ore",
+        OAK_LOG => "Oak Log",
+        OAK_LEAVES => "Oak Leaves",
+        OBSIDIAN => "Obsidian",
+        DIAMOND_ORE => "Diamond Ore",
+        ICE => "Ice",
+        SNOW_BLOCK => "Snow Block",
+        CLAY => "Clay",
+        NETHERRACK => "Netherrack",
+        SOUL_SAND => "Soul Sand",
+        GLOWSTONE => "Glowstone",
+        TERRACOTTA => "Terracotta",
+        COAL => "Coal",
+        DIAMOND => "Diamond",
+        SNOWBALL => "Snowball",
+        CLAY_BALL => "Clay Ball",
+        GLOWSTONE_DUST => "Glowstone Dust",
+        DEEPSLATE => "Deepslate",
+        COBBLED_DEEPSLATE => "Cobbled Deepslate",
+        RAW_IRON => "Raw Iron",
+        RAW_GOLD => "Raw Gold",
+        WOODEN_PICKAXE => "Wooden Pickaxe",
+        STONE_PICKAXE => "Stone Pickaxe",
+        IRON_PICKAXE => "Iron Pickaxe",
+        GOLDEN_PICKAXE => "Golden Pickaxe",
+        DIAMOND_PICKAXE => "Diamond Pickaxe",
+        WOODEN_SHOVEL => "Wooden Shovel",
+        STONE_SHOVEL => "Stone Shovel",
+        IRON_SHOVEL => "Iron Shovel",
+        GOLDEN_SHOVEL => "Golden Shovel",
+        DIAMOND_SHOVEL => "Diamond Shovel",
+        WOODEN_AXE => "Wooden Axe",
+        STONE_AXE => "Stone Axe",
+        IRON_AXE => "Iron Axe",
+        GOLDEN_AXE => "Golden Axe",
+        DIAMOND_AXE => "Diamond Axe",
+        _ => "Unknown Item",
+    }
+}
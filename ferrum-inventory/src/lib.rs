@@ -13,12 +13,16 @@
 
 mod combat;
 mod crafting;
+mod drops;
 mod inventory;
 mod item_stack;
+pub mod items;
 mod slot;
 
 pub use combat::{attack, Health, Weapon};
 pub use crafting::{CraftingTable, Recipe};
+pub use drops::drops_of;
 pub use inventory::Inventory;
 pub use item_stack::ItemStack;
+pub use items::{ToolKind, ToolTier};
 pub use slot::Slot;
@@ -0,0 +1,88 @@
+use ferrum_core::{BlockId, BlockState};
+use ferrum_inventory::{drops_of, items, ItemStack};
+
+const STONE: u16 = 1;
+const DIRT: u16 = 2;
+const GRASS: u16 = 3;
+const IRON_ORE: u16 = 10;
+const DIAMOND_ORE: u16 = 16;
+const LEAVES: u16 = 13;
+
+fn block(id: u16) -> BlockState {
+    BlockState::from(BlockId::new(id))
+}
+
+fn tool(item_id: u16) -> ItemStack {
+    ItemStack::new(item_id, 1, 1)
+}
+
+/// `(item_id, count)` of each drop.
+fn dropped(block_id: u16, held: Option<ItemStack>) -> Vec<(u16, u8)> {
+    drops_of(block(block_id), held.as_ref())
+        .iter()
+        .map(|stack| (stack.item_id, stack.count))
+        .collect()
+}
+
+#[test]
+fn test_stone_with_pickaxe_drops_cobblestone() {
+    assert_eq!(
+        dropped(STONE, Some(tool(items::WOODEN_PICKAXE))),
+        [(items::COBBLESTONE, 1)]
+    );
+    let drops = drops_of(block(STONE), Some(&tool(items::IRON_PICKAXE)));
+    assert_eq!(drops[0].max_stack_size, 64);
+}
+
+#[test]
+fn test_stone_without_pickaxe_drops_nothing() {
+    assert!(dropped(STONE, None).is_empty());
+    assert!(dropped(STONE, Some(tool(items::DIAMOND_SHOVEL))).is_empty());
+    assert!(dropped(STONE, Some(ItemStack::new(items::DIRT, 10, 64))).is_empty());
+}
+
+#[test]
+fn test_grass_drops_dirt() {
+    assert_eq!(dropped(GRASS, None), [(items::DIRT, 1)]);
+    assert_eq!(
+        dropped(GRASS, Some(tool(items::WOODEN_SHOVEL))),
+        [(items::DIRT, 1)]
+    );
+    assert_eq!(dropped(DIRT, None), [(items::DIRT, 1)]);
+}
+
+#[test]
+fn test_ores_need_the_right_tier() {
+    assert!(dropped(IRON_ORE, Some(tool(items::WOODEN_PICKAXE))).is_empty());
+    assert!(dropped(IRON_ORE, Some(tool(items::GOLDEN_PICKAXE))).is_empty());
+    assert_eq!(
+        dropped(IRON_ORE, Some(tool(items::STONE_PICKAXE))),
+        [(items::RAW_IRON, 1)]
+    );
+    assert!(dropped(DIAMOND_ORE, Some(tool(items::STONE_PICKAXE))).is_empty());
+    assert_eq!(
+        dropped(DIAMOND_ORE, Some(tool(items::IRON_PICKAXE))),
+        [(items::DIAMOND, 1)]
+    );
+}
+
+#[test]
+fn test_silk_touch_drops_the_block_itself() {
+    let silk = tool(items::IRON_PICKAXE).with_silk_touch();
+    assert_eq!(dropped(STONE, Some(silk)), [(items::STONE, 1)]);
+    assert_eq!(dropped(DIAMOND_ORE, Some(silk)), [(items::DIAMOND_ORE, 1)]);
+    assert_eq!(dropped(GRASS, Some(silk)), [(items::GRASS_BLOCK, 1)]);
+    assert_eq!(dropped(LEAVES, Some(silk)), [(items::OAK_LEAVES, 1)]);
+    assert!(dropped(LEAVES, Some(tool(items::IRON_PICKAXE))).is_empty());
+
+    // Still has to be the right tool
+    let silk_shovel = tool(items::IRON_SHOVEL).with_silk_touch();
+    assert!(dropped(STONE, Some(silk_shovel)).is_empty());
+}
+
+#[test]
+fn test_air_and_fluids_drop_nothing() {
+    for id in [0, 4, 5, 6] {
+        assert!(dropped(id, Some(tool(items::DIAMOND_PICKAXE))).is_empty());
+    }
+}
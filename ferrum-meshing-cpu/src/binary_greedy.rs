@@ -1,4 +1,7 @@
+use crate::neighbors::ChunkNeighbors;
+use crate::shape::{BlockShape, BlockShapes, FullCubes};
 use crate::{ChunkMesh, Face, MeshQuad, CHUNK_SIZE, CHUNK_SIZE_CB};
+use ferrum_core::{fluid_level, is_same_fluid, BlockState};
 
 const CS: usize = CHUNK_SIZE;
 const CS2: usize = CS * CS;
@@ -13,69 +16,334 @@ fn voxel_at(voxels: &[u32; CHUNK_SIZE_CB], x: usize, y: usize, z: usize) -> u32
 /// 1. Face culling: build 32-bit column masks per (row, layer) for each of 6 directions.
 ///    A face is exposed when the neighbor in that direction is air (0) or out of bounds.
 /// 2. Greedy merging: sweep 2D slices per face direction, use trailing_zeros to find
-///    exposed faces, extend right/forward while block type and merge count match.
+///    exposed faces, extend forward while the block type matches.
+///
+/// Every non-air block other than water and lava is treated as a full cube;
+/// see [`mesh_with_shapes`].
 pub fn mesh(voxels: &[u32; CHUNK_SIZE_CB]) -> ChunkMesh {
+    mesh_with_shapes(voxels, &FullCubes)
+}
+
+/// Like [`mesh`], but a face is only culled when the neighbor's shape covers
+/// the touching side, so e.g. a bottom slab does not hide the block above it.
+///
+/// Voxels are [`BlockState`] bits. Fluids never hide their neighbors, and
+/// faces between two cells of the same fluid are culled at any level, except
+/// where a taller cell's side rises above a lower neighbor.
+pub fn mesh_with_shapes<S: BlockShapes + ?Sized>(
+    voxels: &[u32; CHUNK_SIZE_CB],
+    shapes: &S,
+) -> ChunkMesh {
+    mesh_with_neighbors(voxels, shapes, &ChunkNeighbors::new())
+}
+
+/// Like [`mesh_with_shapes`], but faces on the chunk boundary are also culled
+/// against the adjacent chunks' blocks in `neighbors`, by the same rules as
+/// faces inside the chunk.
+pub fn mesh_with_neighbors<S: BlockShapes + ?Sized>(
+    voxels: &[u32; CHUNK_SIZE_CB],
+    shapes: &S,
+    neighbors: &ChunkNeighbors,
+) -> ChunkMesh {
     let mut result = ChunkMesh::new();
 
     // face_masks[face][layer * CS + row] = 32-bit mask of exposed faces along that column
     // face 0: +X, 1: -X, 2: +Y, 3: -Y, 4: +Z, 5: -Z
     let mut face_masks = [[0u32; CS2]; 6];
 
-    build_face_masks(voxels, &mut face_masks);
+    build_face_masks(voxels, shapes, neighbors, &mut face_masks);
+    cull_fluid_faces(voxels, neighbors, &mut face_masks);
     greedy_merge(voxels, &face_masks, &mut result);
 
     result
 }
 
-fn build_face_masks(voxels: &[u32; CHUNK_SIZE_CB], masks: &mut [[u32; CS2]; 6]) {
-    // Build opaque column masks along each axis, then derive face masks via bitwise ops.
-    // opaque_x[z * CS + y] = 32-bit mask where bit i is set if voxel(i, y, z) != 0
-    let mut opaque_x = [0u32; CS2];
-    // opaque_y[z * CS + x] = 32-bit mask where bit i is set if voxel(x, i, z) != 0
-    let mut opaque_y = [0u32; CS2];
-    // opaque_z[y * CS + x] = 32-bit mask where bit i is set if voxel(x, y, i) != 0
-    let mut opaque_z = [0u32; CS2];
+pub(crate) const FACES: [Face; 6] = [
+    Face::Right,
+    Face::Left,
+    Face::Up,
+    Face::Down,
+    Face::Front,
+    Face::Back,
+];
+
+fn build_face_masks<S: BlockShapes + ?Sized>(
+    voxels: &[u32; CHUNK_SIZE_CB],
+    shapes: &S,
+    neighbors: &ChunkNeighbors,
+    masks: &mut [[u32; CS2]; 6],
+) {
+    // Build column masks along each axis, then derive face masks via bitwise ops.
+    // Axis 0 (x): index z * CS + y, bit x
+    // Axis 1 (y): index z * CS + x, bit y
+    // Axis 2 (z): index y * CS + x, bit z
+    // solid[axis]: the voxel has a model
+    // occludes[face]: the voxel's model fully covers that side of its cell
+    // touches[face]: the voxel's face lies on the cell boundary (so it can be hidden)
+    let mut solid = [[0u32; CS2]; 3];
+    let mut occludes = [[0u32; CS2]; 6];
+    let mut touches = [[0u32; CS2]; 6];
 
     for z in 0..CS {
         for y in 0..CS {
             let row_base = z * CS2 + y * CS;
-            let mut col_x = 0u32;
             for x in 0..CS {
-                if voxels[row_base + x] != 0 {
-                    col_x |= 1 << x;
+                let block = voxels[row_base + x];
+                if block == 0 {
+                    continue;
                 }
+                let level = fluid_level(BlockState::from_bits(block));
+                let shape = shapes.shape(block);
+                if level.is_none() && shape == BlockShape::Empty {
+                    continue;
+                }
+
+                let columns = [(z * CS + y, x), (z * CS + x, y), (y * CS + x, z)];
+                for (axis, &(index, bit)) in columns.iter().enumerate() {
+                    solid[axis][index] |= 1 << bit;
+                    for face_idx in [axis * 2, axis * 2 + 1] {
+                        let (occludes_face, touches_face) =
+                            face_contact(level, shape, FACES[face_idx]);
+                        if occludes_face {
+                            occludes[face_idx][index] |= 1 << bit;
+                        }
+                        if touches_face {
+                            touches[face_idx][index] |= 1 << bit;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Face masks via bitwise column operations. Bit i + 1 of a column lines up
+    // with bit i after `>> 1`, and bit i - 1 after `<< 1`.
+    // +X: solid here AND NOT (face on boundary AND neighbor at i + 1 covers its -X side)
+    //     (bit 31 naturally has no right-shift neighbor)
+    // -X: solid here AND NOT (face on boundary AND neighbor at i - 1 covers its +X side)
+    //     (bit 0 naturally has no left-shift neighbor)
+    for (axis, solid) in solid.iter().enumerate() {
+        let pos = axis * 2;
+        let neg = pos + 1;
+        for (i, &col) in solid.iter().enumerate() {
+            masks[pos][i] = col & !(touches[pos][i] & (occludes[neg][i] >> 1));
+            masks[neg][i] = col & !(touches[neg][i] & (occludes[pos][i] << 1));
+        }
+    }
+
+    // Boundary faces: bit 31 of a column against the neighbor on the positive
+    // side, bit 0 against the one on the negative side.
+    for (face_idx, mask) in masks.iter_mut().enumerate() {
+        let face = FACES[face_idx];
+        let Some(slab) = neighbors.get(face) else {
+            continue;
+        };
+        let edge_bit = if face_idx % 2 == 0 { 1 << (CS - 1) } else { 1 };
+        for (i, &block) in slab.iter().enumerate() {
+            if covers_boundary(block, shapes, face) {
+                mask[i] &= !(touches[face_idx][i] & edge_bit);
             }
-            opaque_x[z * CS + y] = col_x;
+        }
+    }
+}
+
+/// How a block's model meets the `face` side of its cell: whether it fully
+/// covers that side, and whether its face there lies on the cell boundary
+/// (so a neighbor can hide it). Fluids are see-through, and below level 0
+/// the surface sits under the top of the cell.
+fn face_contact(level: Option<u8>, shape: BlockShape, face: Face) -> (bool, bool) {
+    match level {
+        Some(level) => (false, face != Face::Up || level == 0),
+        None => (shape.occludes_face(face), shape.touches_face(face)),
+    }
+}
+
+/// Whether a block in the adjacent chunk's slab hides the touching `face` of
+/// a block on this chunk's boundary.
+fn covers_boundary<S: BlockShapes + ?Sized>(block: u32, shapes: &S, face: Face) -> bool {
+    block != 0
+        && fluid_level(BlockState::from_bits(block)).is_none()
+        && shapes.shape(block).occludes_face(face.opposite())
+}
 
+/// Clear faces between cells of the same fluid. Vertical faces are always
+/// inside the fluid; a side face stays when the neighbor's surface is lower,
+/// so the step between levels still renders.
+fn cull_fluid_faces(
+    voxels: &[u32; CHUNK_SIZE_CB],
+    neighbors: &ChunkNeighbors,
+    masks: &mut [[u32; CS2]; 6],
+) {
+    for z in 0..CS {
+        for y in 0..CS {
             for x in 0..CS {
-                if voxels[row_base + x] != 0 {
-                    opaque_y[z * CS + x] |= 1 << y;
-                    opaque_z[y * CS + x] |= 1 << z;
+                let state = BlockState::from_bits(voxel_at(voxels, x, y, z));
+                let Some(level) = fluid_level(state) else {
+                    continue;
+                };
+
+                for (face_idx, mask) in masks.iter_mut().enumerate() {
+                    if fluid_face_culled(voxels, neighbors, state, level, (x, y, z), face_idx) {
+                        let (index, bit) = column_of(face_idx, x, y, z);
+                        mask[index] &= !(1 << bit);
+                    }
                 }
             }
         }
     }
+}
 
-    // Face masks via bitwise column operations:
-    // +X: solid here AND (neighbor to right is air or out of bounds)
-    //     = opaque & ~(opaque << 1)  (bit 31 naturally has no left-shift neighbor)
-    // -X: solid here AND (neighbor to left is air or out of bounds)
-    //     = opaque & ~(opaque >> 1)  (bit 0 naturally has no right-shift neighbor)
-    for i in 0..CS2 {
-        let col = opaque_x[i];
-        masks[0][i] = col & !(col << 1); // +X
-        masks[1][i] = col & !(col >> 1); // -X
-
-        let col = opaque_y[i];
-        masks[2][i] = col & !(col << 1); // +Y
-        masks[3][i] = col & !(col >> 1); // -Y
-
-        let col = opaque_z[i];
-        masks[4][i] = col & !(col << 1); // +Z
-        masks[5][i] = col & !(col >> 1); // -Z
+/// Whether the `face_idx` face of the fluid `state` at `pos` is inside one
+/// body of fluid, per [`cull_fluid_faces`].
+fn fluid_face_culled(
+    voxels: &[u32; CHUNK_SIZE_CB],
+    neighbors: &ChunkNeighbors,
+    state: BlockState,
+    level: u8,
+    (x, y, z): (usize, usize, usize),
+    face_idx: usize,
+) -> bool {
+    const OFFSETS: [(i32, i32, i32); 6] = [
+        (1, 0, 0),
+        (-1, 0, 0),
+        (0, 1, 0),
+        (0, -1, 0),
+        (0, 0, 1),
+        (0, 0, -1),
+    ];
+
+    let (dx, dy, dz) = OFFSETS[face_idx];
+    let (nx, ny, nz) = (x as i32 + dx, y as i32 + dy, z as i32 + dz);
+    let neighbor = if [nx, ny, nz].iter().any(|&c| c < 0 || c >= CS as i32) {
+        // Past the boundary the neighbor is in the adjacent chunk's slab
+        match neighbors.get(FACES[face_idx]) {
+            Some(slab) => slab[column_of(face_idx, x, y, z).0],
+            None => return false,
+        }
+    } else {
+        voxel_at(voxels, nx as usize, ny as usize, nz as usize)
+    };
+    let neighbor = BlockState::from_bits(neighbor);
+    if !is_same_fluid(state, neighbor) {
+        return false;
+    }
+    let vertical = dy != 0;
+    vertical || fluid_level(neighbor).is_none_or(|other| other <= level)
+}
+
+/// Mask index and bit of the voxel at (x, y, z) in the face masks of
+/// `face_idx`. The layer is `index / CS`.
+#[inline]
+fn column_of(face_idx: usize, x: usize, y: usize, z: usize) -> (usize, usize) {
+    match face_idx {
+        0 | 1 => (z * CS + y, x),
+        2 | 3 => (z * CS + x, y),
+        4 | 5 => (y * CS + x, z),
+        _ => unreachable!(),
     }
 }
 
+/// Exposed faces of one mask column, the same as row `index` of the full
+/// mesher's masks for `face_idx`. Everything that decides a face lies along
+/// its normal, which is the column itself.
+fn column_face_mask<S: BlockShapes + ?Sized>(
+    voxels: &[u32; CHUNK_SIZE_CB],
+    shapes: &S,
+    neighbors: &ChunkNeighbors,
+    face_idx: usize,
+    index: usize,
+) -> u32 {
+    let face = FACES[face_idx];
+    let (layer, row) = (index / CS, index % CS);
+    let mut solid = 0u32;
+    let mut touches = 0u32;
+    let mut occludes_opposite = 0u32;
+    let mut fluids = 0u32;
+
+    for bit in 0..CS {
+        let block = get_block(voxels, face_idx, layer, row, bit);
+        if block == 0 {
+            continue;
+        }
+        let state = BlockState::from_bits(block);
+        let level = fluid_level(state);
+        let shape = shapes.shape(block);
+        if level.is_none() && shape == BlockShape::Empty {
+            continue;
+        }
+
+        solid |= 1 << bit;
+        if face_contact(level, shape, face).1 {
+            touches |= 1 << bit;
+        }
+        if face_contact(level, shape, face.opposite()).0 {
+            occludes_opposite |= 1 << bit;
+        }
+        if level.is_some() {
+            fluids |= 1 << bit;
+        }
+    }
+
+    let (neighbor_covers, edge_bit) = if face_idx.is_multiple_of(2) {
+        (occludes_opposite >> 1, 1 << (CS - 1))
+    } else {
+        (occludes_opposite << 1, 1)
+    };
+    let mut mask = solid & !(touches & neighbor_covers);
+
+    if let Some(slab) = neighbors.get(face)
+        && covers_boundary(slab[index], shapes, face)
+    {
+        mask &= !(touches & edge_bit);
+    }
+
+    while fluids != 0 {
+        let bit = fluids.trailing_zeros() as usize;
+        fluids &= fluids - 1;
+        let state = BlockState::from_bits(get_block(voxels, face_idx, layer, row, bit));
+        let level = fluid_level(state).unwrap_or_default();
+        let pos = match face_idx {
+            0 | 1 => (bit, row, layer),
+            2 | 3 => (row, bit, layer),
+            _ => (row, layer, bit),
+        };
+        if fluid_face_culled(voxels, neighbors, state, level, pos, face_idx) {
+            mask &= !(1 << bit);
+        }
+    }
+    mask
+}
+
+/// Mesh a single layer of one face direction: the quads [`mesh_with_neighbors`]
+/// emits for that layer, in the same order. Quads never span layers, so a
+/// block change only affects the layer holding it in each direction.
+pub(crate) fn mesh_layer<S: BlockShapes + ?Sized>(
+    voxels: &[u32; CHUNK_SIZE_CB],
+    shapes: &S,
+    neighbors: &ChunkNeighbors,
+    face: Face,
+    layer: usize,
+) -> ChunkMesh {
+    let face_idx = face.index();
+    let mut masks = [0u32; CS];
+    for (row, mask) in masks.iter_mut().enumerate() {
+        *mask = column_face_mask(voxels, shapes, neighbors, face_idx, layer * CS + row);
+    }
+
+    let mut result = ChunkMesh::new();
+    let mut forward_merged = [0u8; CS];
+    merge_layer(
+        voxels,
+        &masks,
+        face,
+        face_idx,
+        layer,
+        &mut forward_merged,
+        &mut result,
+    );
+    result
+}
+
 /// Mask layout per face (all use [layer * CS + row] with bits along the third axis):
 ///   Face 0,1 (+X,-X): layer=z, row=y, bits=x
 ///   Face 2,3 (+Y,-Y): layer=z, row=x, bits=y
@@ -84,15 +352,7 @@ fn greedy_merge(voxels: &[u32; CHUNK_SIZE_CB], masks: &[[u32; CS2]; 6], result:
     let mut forward_merged = [0u8; CS];
 
     for face_idx in 0..6 {
-        let face = match face_idx {
-            0 => Face::Right,
-            1 => Face::Left,
-            2 => Face::Up,
-            3 => Face::Down,
-            4 => Face::Front,
-            5 => Face::Back,
-            _ => unreachable!(),
-        };
+        let face = FACES[face_idx];
 
         merge_face(
             voxels,
@@ -118,65 +378,73 @@ fn merge_face(
 ) {
     for layer in 0..CS {
         let base = layer * CS;
+        merge_layer(
+            voxels,
+            &masks[base..base + CS],
+            face,
+            face_idx,
+            layer,
+            forward_merged,
+            result,
+        );
+    }
+}
 
-        for row in 0..CS {
-            let mut bits = masks[base + row];
-            if bits == 0 {
-                continue;
-            }
+/// Greedy merge of one layer, given its `CS` row masks. Every forward merge
+/// ends within the layer, so `forward_merged` is all zero again afterwards.
+fn merge_layer(
+    voxels: &[u32; CHUNK_SIZE_CB],
+    masks: &[u32],
+    face: Face,
+    face_idx: usize,
+    layer: usize,
+    forward_merged: &mut [u8; CS],
+    result: &mut ChunkMesh,
+) {
+    for row in 0..CS {
+        let mut bits = masks[row];
+        if bits == 0 {
+            continue;
+        }
 
-            let next_bits = if row + 1 < CS {
-                masks[base + row + 1]
-            } else {
-                0
-            };
+        let next_bits = if row + 1 < CS { masks[row + 1] } else { 0 };
 
-            while bits != 0 {
-                let bit_pos = bits.trailing_zeros() as usize;
+        while bits != 0 {
+            let bit_pos = bits.trailing_zeros() as usize;
 
-                let block = get_block(voxels, face_idx, layer, row, bit_pos);
+            let block = get_block(voxels, face_idx, layer, row, bit_pos);
 
-                // Forward merge: extend one more row if same block type
-                if (next_bits >> bit_pos & 1) != 0
-                    && block == get_block(voxels, face_idx, layer, row + 1, bit_pos)
-                {
-                    forward_merged[bit_pos] += 1;
-                    bits &= !(1 << bit_pos);
-                    continue;
-                }
+            // Forward merge: extend one more row if same block type
+            if (next_bits >> bit_pos & 1) != 0
+                && block == get_block(voxels, face_idx, layer, row + 1, bit_pos)
+            {
+                forward_merged[bit_pos] += 1;
+                bits &= !(1 << bit_pos);
+                continue;
+            }
 
-                // Right merge: extend along the bit axis while same type and same forward count
-                let mut right_merged: u8 = 1;
-                for right in (bit_pos + 1)..CS {
-                    if (bits >> right & 1) == 0
-                        || forward_merged[bit_pos] != forward_merged[right]
-                        || block != get_block(voxels, face_idx, layer, row, right)
-                    {
-                        break;
-                    }
-                    forward_merged[right] = 0;
-                    right_merged += 1;
-                }
+            // No right merge: the bit axis is the face normal, so exposed faces at
+            // neighboring bits (e.g. a row of slabs) lie on different planes.
+            let right_merged: u8 = 1;
 
-                // Clear merged bits [bit_pos .. bit_pos + right_merged)
-                let end = bit_pos + right_merged as usize;
-                let clear_mask = if end >= 32 {
-                    !((1u32 << bit_pos) - 1)
-                } else {
-                    ((1u32 << end) - 1) & !((1u32 << bit_pos) - 1)
-                };
-                bits &= !clear_mask;
+            // Clear merged bits [bit_pos .. bit_pos + right_merged)
+            let end = bit_pos + right_merged as usize;
+            let clear_mask = if end >= 32 {
+                !((1u32 << bit_pos) - 1)
+            } else {
+                ((1u32 << end) - 1) & !((1u32 << bit_pos) - 1)
+            };
+            bits &= !clear_mask;
 
-                let row_start = row - forward_merged[bit_pos] as usize;
-                let length = forward_merged[bit_pos] as u8 + 1;
-                let width = right_merged;
+            let row_start = row - forward_merged[bit_pos] as usize;
+            let length = forward_merged[bit_pos] + 1;
+            let width = right_merged;
 
-                forward_merged[bit_pos] = 0;
+            forward_merged[bit_pos] = 0;
 
-                emit_quad(
-                    result, face, face_idx, layer, row_start, bit_pos, width, length, block,
-                );
-            }
+            emit_quad(
+                result, face, face_idx, layer, row_start, bit_pos, width, length, block,
+            );
         }
     }
 }
@@ -0,0 +1,90 @@
+use crate::binary_greedy::{FACES, mesh_layer};
+use crate::neighbors::ChunkNeighbors;
+use crate::shape::FullCubes;
+use crate::{CHUNK_SIZE, CHUNK_SIZE_CB, CHUNK_SIZE_SQ, ChunkMesh, Face};
+
+/// Keeps a chunk's voxels and mesh so single block changes can be remeshed
+/// without going over the whole chunk.
+///
+/// The mesh is kept per face direction and layer, the unit the greedy mesher
+/// merges in. A block only affects one layer in each direction (the one
+/// holding it, since quads never span layers and culling only looks along
+/// the face normal), so an update remeshes six layers instead of all 192.
+/// The result is always the same as [`binary_greedy::mesh`] of the current
+/// voxels, quad for quad.
+///
+/// [`binary_greedy::mesh`]: crate::binary_greedy::mesh
+pub struct IncrementalMesher {
+    voxels: Box<[u32; CHUNK_SIZE_CB]>,
+    /// Quads of each layer, indexed by `face * CHUNK_SIZE + layer`.
+    layers: Vec<ChunkMesh>,
+    mesh: ChunkMesh,
+}
+
+impl IncrementalMesher {
+    pub fn new(voxels: &[u32; CHUNK_SIZE_CB]) -> Self {
+        let voxels = Box::new(*voxels);
+        let mut layers = Vec::with_capacity(6 * CHUNK_SIZE);
+        for face in FACES {
+            for layer in 0..CHUNK_SIZE {
+                layers.push(Self::mesh_layer(&voxels, face, layer));
+            }
+        }
+
+        let mut mesher = Self {
+            voxels,
+            layers,
+            mesh: ChunkMesh::new(),
+        };
+        mesher.rebuild_mesh();
+        mesher
+    }
+
+    pub fn voxels(&self) -> &[u32; CHUNK_SIZE_CB] {
+        &self.voxels
+    }
+
+    pub fn mesh(&self) -> &ChunkMesh {
+        &self.mesh
+    }
+
+    /// Set the block at (x, y, z) and return the updated mesh.
+    ///
+    /// # Panics
+    ///
+    /// If a coordinate is outside the chunk.
+    pub fn update_block(&mut self, x: usize, y: usize, z: usize, block: u32) -> &ChunkMesh {
+        assert!(
+            x < CHUNK_SIZE && y < CHUNK_SIZE && z < CHUNK_SIZE,
+            "block ({x}, {y}, {z}) is outside the chunk"
+        );
+        let index = z * CHUNK_SIZE_SQ + y * CHUNK_SIZE + x;
+        if self.voxels[index] == block {
+            return &self.mesh;
+        }
+        self.voxels[index] = block;
+
+        for face in FACES {
+            // Layers run along z for the X and Y faces and along y for the Z faces
+            let layer = match face {
+                Face::Front | Face::Back => y,
+                _ => z,
+            };
+            self.layers[face.index() * CHUNK_SIZE + layer] =
+                Self::mesh_layer(&self.voxels, face, layer);
+        }
+        self.rebuild_mesh();
+        &self.mesh
+    }
+
+    fn mesh_layer(voxels: &[u32; CHUNK_SIZE_CB], face: Face, layer: usize) -> ChunkMesh {
+        mesh_layer(voxels, &FullCubes, &ChunkNeighbors::new(), face, layer)
+    }
+
+    fn rebuild_mesh(&mut self) {
+        self.mesh.quads.clear();
+        for layer in &self.layers {
+            self.mesh.quads.extend_from_slice(&layer.quads);
+        }
+    }
+}
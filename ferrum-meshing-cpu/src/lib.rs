@@ -1,7 +1,20 @@
 pub mod binary_greedy;
+mod incremental;
+mod neighbors;
+mod shape;
+
+pub use incremental::IncrementalMesher;
+pub use neighbors::{boundary_slab, BoundarySlab, ChunkNeighbors};
+pub use shape::{BlockShape, BlockShapes, FullCubes, ShapeTable};
 
 pub use ferrum_meshing_gpu::{CHUNK_SIZE, CHUNK_SIZE_CB, CHUNK_SIZE_SQ};
 
+use std::io::{self, Read, Write};
+
+/// Version of the meshing output. Bump it whenever the mesher changes so
+/// cached meshes from an older build are rebuilt instead of loaded.
+pub const MESHER_VERSION: u32 = 2;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Face {
     Right, // +X
@@ -16,6 +29,18 @@ impl Face {
     pub fn index(self) -> usize {
         self as usize
     }
+
+    /// The face pointing the other way along the same axis.
+    pub fn opposite(self) -> Self {
+        match self {
+            Face::Right => Face::Left,
+            Face::Left => Face::Right,
+            Face::Up => Face::Down,
+            Face::Down => Face::Up,
+            Face::Front => Face::Back,
+            Face::Back => Face::Front,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -46,10 +71,76 @@ impl ChunkMesh {
     pub fn is_empty(&self) -> bool {
         self.quads.is_empty()
     }
+
+    /// Serialize for a mesh cache, stamped with [`MESHER_VERSION`].
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&MESHER_VERSION.to_le_bytes())?;
+        writer.write_all(&(self.quads.len() as u32).to_le_bytes())?;
+        for q in &self.quads {
+            writer.write_all(&[q.x, q.y, q.z, q.width, q.height, q.face as u8])?;
+            writer.write_all(&q.block_type.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Read a mesh written by [`ChunkMesh::write_to`]. Returns `None` if it
+    /// was produced by another mesher version and must be rebuilt.
+    pub fn read_from<R: Read>(reader: &mut R) -> io::Result<Option<Self>> {
+        let mut word = [0u8; 4];
+        reader.read_exact(&mut word)?;
+        if u32::from_le_bytes(word) != MESHER_VERSION {
+            return Ok(None);
+        }
+
+        reader.read_exact(&mut word)?;
+        let count = u32::from_le_bytes(word);
+        let mut mesh = ChunkMesh::new();
+        for _ in 0..count {
+            let mut fields = [0u8; 6];
+            reader.read_exact(&mut fields)?;
+            reader.read_exact(&mut word)?;
+            let face = match fields[5] {
+                0 => Face::Right,
+                1 => Face::Left,
+                2 => Face::Up,
+                3 => Face::Down,
+                4 => Face::Front,
+                5 => Face::Back,
+                other => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid face {}", other),
+                    ))
+                }
+            };
+            mesh.quads.push(MeshQuad {
+                x: fields[0],
+                y: fields[1],
+                z: fields[2],
+                width: fields[3],
+                height: fields[4],
+                face,
+                block_type: u32::from_le_bytes(word),
+            });
+        }
+        Ok(Some(mesh))
+    }
 }
 
 pub trait ChunkMesher: Send + Sync {
     fn mesh_chunk(&self, voxels: &[u32; CHUNK_SIZE_CB]) -> ChunkMesh;
+
+    /// Mesh a chunk, also culling boundary faces hidden by solid blocks in
+    /// the adjacent chunks. Meshers that can't look across chunks mesh it on
+    /// its own.
+    fn mesh_chunk_with_neighbors(
+        &self,
+        voxels: &[u32; CHUNK_SIZE_CB],
+        neighbors: &ChunkNeighbors,
+    ) -> ChunkMesh {
+        let _ = neighbors;
+        self.mesh_chunk(voxels)
+    }
 }
 
 pub struct CpuMesher;
@@ -58,12 +149,48 @@ impl CpuMesher {
     pub fn new() -> Self {
         Self
     }
+
+    /// Mesh a chunk, culling faces according to each block's shape.
+    pub fn mesh_chunk_with_shapes(
+        &self,
+        voxels: &[u32; CHUNK_SIZE_CB],
+        shapes: &dyn BlockShapes,
+    ) -> ChunkMesh {
+        binary_greedy::mesh_with_shapes(voxels, shapes)
+    }
+
+    /// Mesh independent chunks on all cores, returning the meshes in the
+    /// order of `chunks`.
+    #[cfg(feature = "parallel")]
+    pub fn mesh_chunks_parallel(&self, chunks: &[&[u32; CHUNK_SIZE_CB]]) -> Vec<ChunkMesh> {
+        use rayon::prelude::*;
+
+        chunks
+            .par_iter()
+            .map(|voxels| self.mesh_chunk(voxels))
+            .collect()
+    }
+
+    /// Mesh several chunks, returning the meshes in the order of `chunks`.
+    /// Runs serially unless the `parallel` feature is enabled.
+    #[cfg(not(feature = "parallel"))]
+    pub fn mesh_chunks_parallel(&self, chunks: &[&[u32; CHUNK_SIZE_CB]]) -> Vec<ChunkMesh> {
+        chunks.iter().map(|voxels| self.mesh_chunk(voxels)).collect()
+    }
 }
 
 impl ChunkMesher for CpuMesher {
     fn mesh_chunk(&self, voxels: &[u32; CHUNK_SIZE_CB]) -> ChunkMesh {
         binary_greedy::mesh(voxels)
     }
+
+    fn mesh_chunk_with_neighbors(
+        &self,
+        voxels: &[u32; CHUNK_SIZE_CB],
+        neighbors: &ChunkNeighbors,
+    ) -> ChunkMesh {
+        binary_greedy::mesh_with_neighbors(voxels, &FullCubes, neighbors)
+    }
 }
 
 pub struct GpuMesher {
@@ -74,28 +201,24 @@ impl GpuMesher {
     pub fn new() -> Option<Self> {
         ferrum_meshing_gpu::GpuChunkMesher::new().map(|inner| Self { inner })
     }
-}
 
-impl ChunkMesher for GpuMesher {
-    fn mesh_chunk(&self, voxels: &[u32; CHUNK_SIZE_CB]) -> ChunkMesh {
-        let gpu_quads = self.inner.mesh_chunk(voxels);
+    fn to_chunk_mesh(gpu_quads: &[ferrum_meshing_gpu::PackedQuad]) -> ChunkMesh {
         let mut mesh = ChunkMesh::new();
-        for q in &gpu_quads {
-            let face = match q.face() {
+        for q in ferrum_meshing_gpu::decode_quads(gpu_quads) {
+            let face = match q.face {
                 0 => Face::Right,
                 1 => Face::Left,
                 2 => Face::Up,
                 3 => Face::Down,
                 4 => Face::Front,
-                5 => Face::Back,
-                _ => continue,
+                _ => Face::Back,
             };
             mesh.quads.push(MeshQuad {
-                x: q.x() as u8,
-                y: q.y() as u8,
-                z: q.z() as u8,
-                width: q.width() as u8,
-                height: q.height() as u8,
+                x: q.x,
+                y: q.y,
+                z: q.z,
+                width: q.width,
+                height: q.height,
                 face,
                 block_type: q.block_type,
             });
@@ -104,6 +227,24 @@ impl ChunkMesher for GpuMesher {
     }
 }
 
+impl ChunkMesher for GpuMesher {
+    fn mesh_chunk(&self, voxels: &[u32; CHUNK_SIZE_CB]) -> ChunkMesh {
+        Self::to_chunk_mesh(&self.inner.mesh_chunk(voxels))
+    }
+
+    fn mesh_chunk_with_neighbors(
+        &self,
+        voxels: &[u32; CHUNK_SIZE_CB],
+        neighbors: &ChunkNeighbors,
+    ) -> ChunkMesh {
+        if neighbors.is_empty() {
+            return self.mesh_chunk(voxels);
+        }
+        let masks = neighbors.solid_masks();
+        Self::to_chunk_mesh(&self.inner.mesh_chunk_with_neighbors(voxels, &masks))
+    }
+}
+
 pub fn create_mesher() -> Box<dyn ChunkMesher> {
     if let Some(gpu) = GpuMesher::new() {
         Box::new(gpu)
@@ -0,0 +1,86 @@
+use crate::{Face, CHUNK_SIZE, CHUNK_SIZE_CB, CHUNK_SIZE_SQ};
+use ferrum_core::{is_fluid, BlockState};
+use ferrum_meshing_gpu::NeighborMasks;
+
+const CS: usize = CHUNK_SIZE;
+
+/// One 32x32 layer of a chunk's blocks, as seen by the chunk next to it.
+///
+/// Indexed like the mesher's face masks: slabs on the ±X sides by
+/// `z * CHUNK_SIZE + y`, on the ±Y sides by `z * CHUNK_SIZE + x` and on the
+/// ±Z sides by `y * CHUNK_SIZE + x`.
+pub type BoundarySlab = [u32; CHUNK_SIZE_SQ];
+
+/// The layer of `voxels` on its `face` side, e.g. `x = 31` for
+/// [`Face::Right`]. The chunk to its right passes this as its
+/// [`Face::Left`] neighbor.
+pub fn boundary_slab(voxels: &[u32; CHUNK_SIZE_CB], face: Face) -> BoundarySlab {
+    let edge = match face {
+        Face::Right | Face::Up | Face::Front => CS - 1,
+        Face::Left | Face::Down | Face::Back => 0,
+    };
+    let mut slab = [0u32; CHUNK_SIZE_SQ];
+    for layer in 0..CS {
+        for row in 0..CS {
+            let (x, y, z) = match face {
+                Face::Right | Face::Left => (edge, row, layer),
+                Face::Up | Face::Down => (row, edge, layer),
+                Face::Front | Face::Back => (row, layer, edge),
+            };
+            slab[layer * CS + row] = voxels[z * CHUNK_SIZE_SQ + y * CS + x];
+        }
+    }
+    slab
+}
+
+/// Boundary slabs of the up to six chunks around the one being meshed, by
+/// the side they touch. Missing neighbors (unloaded, or past the world edge)
+/// hide nothing, as if the chunk were meshed on its own.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ChunkNeighbors<'a> {
+    slabs: [Option<&'a BoundarySlab>; 6],
+}
+
+impl<'a> ChunkNeighbors<'a> {
+    /// No neighbors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the neighbor on the `face` side: the slab of the adjacent chunk that
+    /// touches this one, i.e. its [`boundary_slab`] on `face.opposite()`.
+    pub fn with(mut self, face: Face, slab: &'a BoundarySlab) -> Self {
+        self.set(face, Some(slab));
+        self
+    }
+
+    pub fn set(&mut self, face: Face, slab: Option<&'a BoundarySlab>) {
+        self.slabs[face.index()] = slab;
+    }
+
+    pub fn get(&self, face: Face) -> Option<&'a BoundarySlab> {
+        self.slabs[face.index()]
+    }
+
+    /// Whether no neighbor is present.
+    pub fn is_empty(&self) -> bool {
+        self.slabs.iter().all(Option::is_none)
+    }
+
+    /// Masks of the boundary cells whose neighbor is any block other than air
+    /// or a fluid, in the layout the GPU mesher takes.
+    pub fn solid_masks(&self) -> NeighborMasks {
+        let mut masks = [[0u32; CS]; 6];
+        for (face, slab) in self.slabs.iter().enumerate() {
+            let Some(slab) = slab else {
+                continue;
+            };
+            for (index, &block) in slab.iter().enumerate() {
+                if block != 0 && !is_fluid(BlockState::from_bits(block).id()) {
+                    masks[face][index / CS] |= 1 << (index % CS);
+                }
+            }
+        }
+        masks
+    }
+}
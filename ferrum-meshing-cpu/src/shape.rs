@@ -0,0 +1,98 @@
+use crate::Face;
+use std::collections::HashMap;
+
+/// How much of its cell a block's model fills, as far as face culling cares.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum BlockShape {
+    /// Air and other blocks with no faces.
+    Empty,
+    /// A full cube.
+    #[default]
+    Full,
+    /// Lower half of the cell.
+    BottomSlab,
+    /// Upper half of the cell.
+    TopSlab,
+    /// Stairs, fences and other models that never fully cover a side.
+    Partial,
+}
+
+impl BlockShape {
+    /// Whether the model completely covers its `face` side of the cell, hiding
+    /// the touching face of the neighbor on that side.
+    pub fn occludes_face(self, face: Face) -> bool {
+        match self {
+            BlockShape::Full => true,
+            BlockShape::BottomSlab => face == Face::Down,
+            BlockShape::TopSlab => face == Face::Up,
+            BlockShape::Empty | BlockShape::Partial => false,
+        }
+    }
+
+    /// Whether the model's `face` lies on the cell boundary, so a neighbor can
+    /// hide it. A bottom slab's top face sits mid-cell and is never hidden.
+    pub fn touches_face(self, face: Face) -> bool {
+        match self {
+            BlockShape::Full => true,
+            BlockShape::BottomSlab => face != Face::Up,
+            BlockShape::TopSlab => face != Face::Down,
+            BlockShape::Empty | BlockShape::Partial => false,
+        }
+    }
+}
+
+/// Shape lookup for block types, consulted by the mesher when culling faces.
+pub trait BlockShapes: Sync {
+    fn shape(&self, block_type: u32) -> BlockShape;
+
+    fn occludes_face(&self, block_type: u32, face: Face) -> bool {
+        block_type != 0 && self.shape(block_type).occludes_face(face)
+    }
+}
+
+/// Every non-air block is a full cube.
+pub struct FullCubes;
+
+impl BlockShapes for FullCubes {
+    #[inline]
+    fn shape(&self, block_type: u32) -> BlockShape {
+        if block_type == 0 {
+            BlockShape::Empty
+        } else {
+            BlockShape::Full
+        }
+    }
+}
+
+/// Explicit shapes for some block types; everything else is a full cube.
+#[derive(Clone, Debug, Default)]
+pub struct ShapeTable {
+    shapes: HashMap<u32, BlockShape>,
+}
+
+impl ShapeTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, block_type: u32, shape: BlockShape) {
+        self.shapes.insert(block_type, shape);
+    }
+
+    pub fn with(mut self, block_type: u32, shape: BlockShape) -> Self {
+        self.set(block_type, shape);
+        self
+    }
+}
+
+impl BlockShapes for ShapeTable {
+    fn shape(&self, block_type: u32) -> BlockShape {
+        if block_type == 0 {
+            return BlockShape::Empty;
+        }
+        self.shapes
+            .get(&block_type)
+            .copied()
+            .unwrap_or(BlockShape::Full)
+    }
+}
@@ -0,0 +1,125 @@
+use ferrum_meshing_cpu::*;
+
+const STONE: u32 = 1;
+const SLAB: u32 = 2;
+
+fn idx(x: usize, y: usize, z: usize) -> usize {
+    z * CHUNK_SIZE_SQ + y * CHUNK_SIZE + x
+}
+
+fn has_face(mesh: &ChunkMesh, pos: (u8, u8, u8), face: Face, block_type: u32) -> bool {
+    mesh.quads
+        .iter()
+        .any(|q| (q.x, q.y, q.z) == pos && q.face == face && q.block_type == block_type)
+}
+
+fn slab_shapes() -> ShapeTable {
+    ShapeTable::new().with(SLAB, BlockShape::BottomSlab)
+}
+
+#[test]
+fn bottom_slab_occludes_only_its_bottom_face() {
+    let slab = BlockShape::BottomSlab;
+    assert!(slab.occludes_face(Face::Down));
+    for face in [Face::Up, Face::Right, Face::Left, Face::Front, Face::Back] {
+        assert!(!slab.occludes_face(face), "{face:?} should not be covered");
+    }
+    assert!(!slab.touches_face(Face::Up));
+
+    let shapes = slab_shapes();
+    assert!(shapes.occludes_face(STONE, Face::Up));
+    assert!(!shapes.occludes_face(SLAB, Face::Up));
+    assert!(!shapes.occludes_face(0, Face::Up));
+}
+
+#[test]
+fn cube_above_bottom_slab_keeps_its_bottom_face() {
+    let mut chunk = [0u32; CHUNK_SIZE_CB];
+    chunk[idx(4, 4, 4)] = SLAB;
+    chunk[idx(4, 5, 4)] = STONE;
+
+    let mesh = binary_greedy::mesh_with_shapes(&chunk, &slab_shapes());
+
+    assert!(
+        has_face(&mesh, (4, 5, 4), Face::Down, STONE),
+        "the slab does not reach the cube, so its bottom face must render"
+    );
+    assert!(
+        has_face(&mesh, (4, 4, 4), Face::Up, SLAB),
+        "the slab's top sits mid-cell and is never hidden"
+    );
+    assert_eq!(mesh.quad_count(), 12);
+}
+
+#[test]
+fn cube_below_bottom_slab_is_covered() {
+    let mut chunk = [0u32; CHUNK_SIZE_CB];
+    chunk[idx(4, 4, 4)] = STONE;
+    chunk[idx(4, 5, 4)] = SLAB;
+
+    let mesh = binary_greedy::mesh_with_shapes(&chunk, &slab_shapes());
+
+    assert!(!has_face(&mesh, (4, 4, 4), Face::Up, STONE));
+    assert!(!has_face(&mesh, (4, 5, 4), Face::Down, SLAB));
+    assert_eq!(mesh.quad_count(), 10);
+}
+
+#[test]
+fn cube_beside_slab_keeps_side_face_only_covered_by_cube() {
+    let mut chunk = [0u32; CHUNK_SIZE_CB];
+    chunk[idx(4, 4, 4)] = STONE;
+    chunk[idx(5, 4, 4)] = SLAB;
+
+    let mesh = binary_greedy::mesh_with_shapes(&chunk, &slab_shapes());
+
+    assert!(
+        has_face(&mesh, (4, 4, 4), Face::Right, STONE),
+        "half of the cube's side is still visible above the slab"
+    );
+    assert!(
+        !has_face(&mesh, (5, 4, 4), Face::Left, SLAB),
+        "the cube fully covers the slab's side"
+    );
+}
+
+#[test]
+fn full_cubes_match_default_mesher() {
+    let chunk = terrain_chunk();
+    let default_mesh = CpuMesher::new().mesh_chunk(&chunk);
+    let shaped_mesh = CpuMesher::new().mesh_chunk_with_shapes(&chunk, &FullCubes);
+    assert_eq!(default_mesh.quad_count(), shaped_mesh.quad_count());
+}
+
+#[test]
+fn exposed_faces_are_on_the_open_side() {
+    let mut chunk = [0u32; CHUNK_SIZE_CB];
+    chunk[idx(4, 4, 4)] = STONE;
+    chunk[idx(5, 4, 4)] = STONE;
+
+    let mesh = binary_greedy::mesh(&chunk);
+
+    assert!(has_face(&mesh, (5, 4, 4), Face::Right, STONE));
+    assert!(has_face(&mesh, (4, 4, 4), Face::Left, STONE));
+    assert!(!has_face(&mesh, (4, 4, 4), Face::Right, STONE));
+    assert!(!has_face(&mesh, (5, 4, 4), Face::Left, STONE));
+}
+
+#[test]
+fn row_of_slabs_emits_one_side_face_per_slab() {
+    let mut chunk = [0u32; CHUNK_SIZE_CB];
+    for x in 4..8 {
+        chunk[idx(x, 4, 4)] = SLAB;
+    }
+
+    let mesh = binary_greedy::mesh_with_shapes(&chunk, &slab_shapes());
+
+    for x in 4..8u8 {
+        assert!(has_face(&mesh, (x, 4, 4), Face::Right, SLAB));
+        assert!(has_face(&mesh, (x, 4, 4), Face::Left, SLAB));
+    }
+    assert!(mesh
+        .quads
+        .iter()
+        .filter(|q| matches!(q.face, Face::Right | Face::Left))
+        .all(|q| q.width == 1));
+}
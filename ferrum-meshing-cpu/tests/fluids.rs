@@ -0,0 +1,138 @@
+use ferrum_core::{BlockState, LAVA, WATER};
+use ferrum_meshing_cpu::*;
+use std::collections::BTreeSet;
+
+const STONE: u32 = 1;
+
+fn idx(x: usize, y: usize, z: usize) -> usize {
+    z * CHUNK_SIZE_SQ + y * CHUNK_SIZE + x
+}
+
+fn water(level: u16) -> u32 {
+    BlockState::new(WATER, level).to_bits()
+}
+
+fn has_face(mesh: &ChunkMesh, pos: (u8, u8, u8), face: Face) -> bool {
+    mesh.quads
+        .iter()
+        .any(|q| (q.x, q.y, q.z) == pos && q.face == face)
+}
+
+#[test]
+fn water_of_different_levels_hides_shared_faces() {
+    let mut chunk = [0u32; CHUNK_SIZE_CB];
+    chunk[idx(4, 4, 4)] = water(0);
+    chunk[idx(5, 4, 4)] = water(0);
+    chunk[idx(4, 5, 4)] = water(2);
+
+    let mesh = binary_greedy::mesh(&chunk);
+
+    assert!(!has_face(&mesh, (4, 4, 4), Face::Right));
+    assert!(!has_face(&mesh, (5, 4, 4), Face::Left));
+    assert!(
+        !has_face(&mesh, (4, 4, 4), Face::Up),
+        "water below other water has no surface"
+    );
+    assert!(!has_face(&mesh, (4, 5, 4), Face::Down));
+    assert!(has_face(&mesh, (4, 5, 4), Face::Up));
+}
+
+#[test]
+fn water_air_boundary_emits_face() {
+    let mut chunk = [0u32; CHUNK_SIZE_CB];
+    chunk[idx(4, 4, 4)] = water(3);
+
+    let mesh = binary_greedy::mesh(&chunk);
+
+    assert_eq!(mesh.quad_count(), 6);
+    assert!(has_face(&mesh, (4, 4, 4), Face::Up));
+}
+
+#[test]
+fn taller_water_keeps_side_above_lower_neighbor() {
+    let mut chunk = [0u32; CHUNK_SIZE_CB];
+    chunk[idx(4, 4, 4)] = water(1);
+    chunk[idx(5, 4, 4)] = water(4);
+
+    let mesh = binary_greedy::mesh(&chunk);
+
+    assert!(
+        has_face(&mesh, (4, 4, 4), Face::Right),
+        "the step down to the lower level must render"
+    );
+    assert!(!has_face(&mesh, (5, 4, 4), Face::Left));
+    assert!(has_face(&mesh, (4, 4, 4), Face::Up));
+    assert!(has_face(&mesh, (5, 4, 4), Face::Up));
+}
+
+#[test]
+fn water_does_not_hide_solid_neighbors() {
+    let mut chunk = [0u32; CHUNK_SIZE_CB];
+    chunk[idx(4, 4, 4)] = STONE;
+    chunk[idx(5, 4, 4)] = water(0);
+    chunk[idx(4, 5, 4)] = water(6);
+
+    let mesh = binary_greedy::mesh(&chunk);
+
+    assert!(has_face(&mesh, (4, 4, 4), Face::Right));
+    assert!(has_face(&mesh, (4, 4, 4), Face::Up));
+    assert!(!has_face(&mesh, (5, 4, 4), Face::Left));
+    assert!(!has_face(&mesh, (4, 5, 4), Face::Down));
+}
+
+/// Unit faces covered by the mesh's quads, as `(x, y, z, face, block)`.
+fn unit_faces(mesh: &ChunkMesh) -> BTreeSet<(u8, u8, u8, usize, u32)> {
+    let mut faces = BTreeSet::new();
+    for q in &mesh.quads {
+        let face = q.face.index();
+        for i in 0..q.width {
+            for j in 0..q.height {
+                let (x, y, z) = match face {
+                    0..=3 => (q.x + i, q.y + j, q.z),
+                    _ => (q.x + i, q.y, q.z + j),
+                };
+                faces.insert((x, y, z, face, q.block_type));
+            }
+        }
+    }
+    faces
+}
+
+#[test]
+fn gpu_mesher_culls_fluids_like_the_cpu_mesher() {
+    let Some(gpu) = GpuMesher::new() else {
+        eprintln!("No GPU available, skipping");
+        return;
+    };
+
+    let lava = |level: u16| BlockState::new(LAVA, level).to_bits();
+    let mut chunk = [0u32; CHUNK_SIZE_CB];
+    // A stone basin holding a pool that flows out over its rim
+    for x in 2..12 {
+        for z in 2..12 {
+            chunk[idx(x, 2, z)] = STONE;
+            for y in 3..6 {
+                let rim = x == 2 || x == 11 || z == 2 || z == 11;
+                chunk[idx(x, y, z)] = if rim { STONE } else { water(0) };
+            }
+        }
+    }
+    for (step, x) in (12..20).enumerate() {
+        chunk[idx(x, 5, 6)] = water(step as u16);
+    }
+    // Falling water, lava next to water, and a flowing cell under stone
+    chunk[idx(20, 5, 6)] = water(0x8 | 3);
+    chunk[idx(20, 4, 6)] = water(0x8 | 3);
+    chunk[idx(21, 4, 6)] = lava(0);
+    chunk[idx(22, 4, 6)] = lava(3);
+    chunk[idx(23, 4, 6)] = water(2);
+    chunk[idx(23, 5, 6)] = STONE;
+    // Fluids on the chunk boundary
+    chunk[idx(31, 4, 6)] = water(1);
+    chunk[idx(0, 4, 6)] = lava(5);
+
+    let cpu_mesh = CpuMesher::new().mesh_chunk(&chunk);
+    let gpu_mesh = gpu.mesh_chunk(&chunk);
+
+    assert_eq!(unit_faces(&gpu_mesh), unit_faces(&cpu_mesh));
+}
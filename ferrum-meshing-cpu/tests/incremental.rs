@@ -0,0 +1,80 @@
+use ferrum_core::{BlockState, LAVA, WATER};
+use ferrum_meshing_cpu::*;
+
+fn mesh_bytes(mesh: &ChunkMesh) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    mesh.write_to(&mut bytes).unwrap();
+    bytes
+}
+
+/// Small deterministic generator so failures reproduce.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) as u32
+    }
+
+    fn below(&mut self, n: u32) -> u32 {
+        self.next() % n
+    }
+}
+
+#[test]
+fn starts_with_the_full_mesh() {
+    let terrain = terrain_chunk();
+    let mesher = IncrementalMesher::new(&terrain);
+    assert_eq!(
+        mesh_bytes(mesher.mesh()),
+        mesh_bytes(&binary_greedy::mesh(&terrain))
+    );
+    assert!(IncrementalMesher::new(&uniform_chunk(0)).mesh().is_empty());
+}
+
+#[test]
+fn single_edits_match_a_full_remesh() {
+    let blocks = [
+        0,
+        0,
+        1,
+        2,
+        3,
+        BlockState::from(WATER).to_bits(),
+        BlockState::new(WATER, 3).to_bits(),
+        BlockState::new(LAVA, 6).to_bits(),
+    ];
+    let mut rng = Lcg(0x5eed);
+    let mut mesher = IncrementalMesher::new(&terrain_chunk());
+
+    for edit in 0..100 {
+        // Mostly around the terrain surface, where edits interact, with some
+        // anywhere in the chunk including its edges
+        let (x, y, z) = if edit % 4 == 0 {
+            (rng.below(32), rng.below(32), rng.below(32))
+        } else {
+            (rng.below(6), 12 + rng.below(8), rng.below(6))
+        };
+        let block = blocks[rng.below(blocks.len() as u32) as usize];
+
+        let incremental =
+            mesh_bytes(mesher.update_block(x as usize, y as usize, z as usize, block));
+        let full = binary_greedy::mesh(mesher.voxels());
+        assert_eq!(
+            incremental,
+            mesh_bytes(&full),
+            "edit {edit}: block {block} at ({x}, {y}, {z})"
+        );
+    }
+}
+
+#[test]
+fn unchanged_block_keeps_the_mesh() {
+    let mut mesher = IncrementalMesher::new(&terrain_chunk());
+    let before = mesh_bytes(mesher.mesh());
+    let block = mesher.voxels()[0];
+    assert_eq!(mesh_bytes(mesher.update_block(0, 0, 0, block)), before);
+}
@@ -0,0 +1,36 @@
+use ferrum_meshing_cpu::*;
+
+#[test]
+fn current_version_mesh_round_trips() {
+    let mesh = CpuMesher::new().mesh_chunk(&terrain_chunk());
+    let mut bytes = Vec::new();
+    mesh.write_to(&mut bytes).unwrap();
+
+    let loaded = ChunkMesh::read_from(&mut bytes.as_slice())
+        .unwrap()
+        .expect("current version should load");
+    assert_eq!(loaded.quad_count(), mesh.quad_count());
+    for (a, b) in loaded.quads.iter().zip(&mesh.quads) {
+        assert_eq!((a.x, a.y, a.z, a.face), (b.x, b.y, b.z, b.face));
+        assert_eq!(
+            (a.width, a.height, a.block_type),
+            (b.width, b.height, b.block_type)
+        );
+    }
+}
+
+#[test]
+fn old_version_mesh_is_rejected() {
+    let mut chunk = [0u32; CHUNK_SIZE_CB];
+    chunk[0] = 1;
+    let mut bytes = Vec::new();
+    CpuMesher::new()
+        .mesh_chunk(&chunk)
+        .write_to(&mut bytes)
+        .unwrap();
+    bytes[..4].copy_from_slice(&(MESHER_VERSION + 1).to_le_bytes());
+
+    assert!(ChunkMesh::read_from(&mut bytes.as_slice())
+        .unwrap()
+        .is_none());
+}
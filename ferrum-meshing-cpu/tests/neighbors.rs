@@ -0,0 +1,140 @@
+use ferrum_meshing_cpu::binary_greedy::mesh_with_neighbors;
+use ferrum_meshing_cpu::*;
+
+const STONE: u32 = 1;
+const SLAB: u32 = 2;
+
+const FACES: [Face; 6] = [
+    Face::Right,
+    Face::Left,
+    Face::Up,
+    Face::Down,
+    Face::Front,
+    Face::Back,
+];
+
+fn idx(x: usize, y: usize, z: usize) -> usize {
+    z * CHUNK_SIZE_SQ + y * CHUNK_SIZE + x
+}
+
+fn count_faces(mesh: &ChunkMesh, face: Face) -> usize {
+    mesh.quads.iter().filter(|q| q.face == face).count()
+}
+
+#[test]
+fn boundary_slab_takes_the_outer_layer() {
+    let mut chunk = [0u32; CHUNK_SIZE_CB];
+    chunk[idx(31, 3, 5)] = 7;
+    chunk[idx(0, 3, 5)] = 8;
+    chunk[idx(2, 0, 9)] = 9;
+
+    let right = boundary_slab(&chunk, Face::Right);
+    assert_eq!(right[5 * CHUNK_SIZE + 3], 7);
+    assert_eq!(right.iter().filter(|&&b| b != 0).count(), 1);
+    assert_eq!(boundary_slab(&chunk, Face::Left)[5 * CHUNK_SIZE + 3], 8);
+    assert_eq!(boundary_slab(&chunk, Face::Down)[9 * CHUNK_SIZE + 2], 9);
+    assert!(boundary_slab(&chunk, Face::Front).iter().all(|&b| b == 0));
+}
+
+#[test]
+fn solid_neighbors_cull_boundary_faces() {
+    let mesher = CpuMesher::new();
+    let stone = uniform_chunk(STONE);
+    let slab = boundary_slab(&stone, Face::Left);
+
+    let isolated = mesher.mesh_chunk(&stone);
+    assert!(count_faces(&isolated, Face::Right) > 0);
+
+    let neighbors = ChunkNeighbors::new().with(Face::Right, &slab);
+    let mesh = mesher.mesh_chunk_with_neighbors(&stone, &neighbors);
+    assert_eq!(count_faces(&mesh, Face::Right), 0);
+    for face in [Face::Left, Face::Up, Face::Down, Face::Front, Face::Back] {
+        assert_eq!(count_faces(&mesh, face), count_faces(&isolated, face));
+    }
+
+    // Surrounded on all sides, a full chunk has no visible faces
+    let slabs = FACES.map(|face| boundary_slab(&stone, face.opposite()));
+    let mut neighbors = ChunkNeighbors::new();
+    for (&face, slab) in FACES.iter().zip(&slabs) {
+        neighbors.set(face, Some(slab));
+    }
+    assert!(
+        mesher
+            .mesh_chunk_with_neighbors(&stone, &neighbors)
+            .is_empty()
+    );
+}
+
+#[test]
+fn missing_or_empty_neighbors_match_isolated_meshing() {
+    let mesher = CpuMesher::new();
+    let terrain = terrain_chunk();
+    let isolated = mesher.mesh_chunk(&terrain).quad_count();
+
+    let none = ChunkNeighbors::new();
+    assert!(none.is_empty());
+    assert_eq!(
+        mesher
+            .mesh_chunk_with_neighbors(&terrain, &none)
+            .quad_count(),
+        isolated
+    );
+
+    let air = [0u32; CHUNK_SIZE_SQ];
+    let neighbors = ChunkNeighbors::new()
+        .with(Face::Left, &air)
+        .with(Face::Down, &air);
+    assert_eq!(
+        mesher
+            .mesh_chunk_with_neighbors(&terrain, &neighbors)
+            .quad_count(),
+        isolated
+    );
+}
+
+#[test]
+fn neighbor_shapes_decide_culling() {
+    let mut chunk = [0u32; CHUNK_SIZE_CB];
+    chunk[idx(4, 31, 4)] = STONE;
+    let shapes = ShapeTable::new().with(SLAB, BlockShape::BottomSlab);
+
+    // A bottom slab above covers the stone's top face; one beside it does not
+    let mut above = [0u32; CHUNK_SIZE_SQ];
+    above[4 * CHUNK_SIZE + 4] = SLAB;
+    let neighbors = ChunkNeighbors::new().with(Face::Up, &above);
+    let mesh = mesh_with_neighbors(&chunk, &shapes, &neighbors);
+    assert_eq!(count_faces(&mesh, Face::Up), 0);
+
+    let mut edge = [0u32; CHUNK_SIZE_CB];
+    edge[idx(31, 4, 4)] = STONE;
+    let mut beside = [0u32; CHUNK_SIZE_SQ];
+    beside[4 * CHUNK_SIZE + 4] = SLAB;
+    let neighbors = ChunkNeighbors::new().with(Face::Right, &beside);
+    let mesh = mesh_with_neighbors(&edge, &shapes, &neighbors);
+    assert_eq!(count_faces(&mesh, Face::Right), 1);
+}
+
+#[test]
+fn gpu_mesher_culls_against_neighbors() {
+    let Some(mesher) = GpuMesher::new() else {
+        return;
+    };
+    let stone = uniform_chunk(STONE);
+    let slab = boundary_slab(&stone, Face::Back);
+
+    let isolated = mesher.mesh_chunk(&stone);
+    let neighbors = ChunkNeighbors::new().with(Face::Front, &slab);
+    let mesh = mesher.mesh_chunk_with_neighbors(&stone, &neighbors);
+    assert!(count_faces(&isolated, Face::Front) > 0);
+    assert_eq!(count_faces(&mesh, Face::Front), 0);
+    assert_eq!(
+        count_faces(&mesh, Face::Back),
+        count_faces(&isolated, Face::Back)
+    );
+
+    // Neighbors only apply to the call that passed them
+    assert_eq!(
+        mesher.mesh_chunk(&stone).quad_count(),
+        isolated.quad_count()
+    );
+}
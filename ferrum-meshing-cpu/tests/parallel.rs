@@ -0,0 +1,31 @@
+use ferrum_meshing_cpu::*;
+
+fn mesh_bytes(mesh: &ChunkMesh) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    mesh.write_to(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn parallel_meshing_matches_serial_meshing() {
+    let mut single = [0u32; CHUNK_SIZE_CB];
+    single[12345] = 7;
+    let chunks = [
+        terrain_chunk(),
+        uniform_chunk(0),
+        checkerboard_chunk(2),
+        uniform_chunk(1),
+        single,
+        terrain_chunk(),
+    ];
+    let refs: Vec<&[u32; CHUNK_SIZE_CB]> = chunks.iter().collect();
+
+    let mesher = CpuMesher::new();
+    let parallel = mesher.mesh_chunks_parallel(&refs);
+    assert_eq!(parallel.len(), chunks.len());
+    for (voxels, mesh) in chunks.iter().zip(&parallel) {
+        assert_eq!(mesh_bytes(mesh), mesh_bytes(&mesher.mesh_chunk(voxels)));
+    }
+
+    assert!(mesher.mesh_chunks_parallel(&[]).is_empty());
+}
@@ -3,24 +3,47 @@
 // Supports N chunks per dispatch via wgid.z as chunk index.
 // Buffers are laid out as arrays indexed by chunk_id:
 //   voxels:        [chunk_id * 32768 + voxel_index]
-//   face_mask_buf: [chunk_id * 6144 + face * 1024 + layer * 32 + row]
-//   quads:         [chunk_id * MAX_QUADS * 2 + quad_idx * 2]
+//   face_mask_buf: [chunk_id * 6336 + face * 1024 + layer * 32 + row]
+//                  neighbor masks at [chunk_id * 6336 + 6144 + face * 32 + layer], bit row
+//   quads:         [chunk_id * QUAD_CAPACITY * 2 + quad_idx * 2]
 //   quad_count:    [chunk_id]
 //
+// LOD meshing borrows the start of face_mask_buf as its reduced grid:
+//   [z * G * G + y * G + x] for grid size G = 32 / scale
+//
 // Pass 1 (face_culling): Dispatch (4, 6, N) @ workgroup_size(256)
 // Pass 2 (greedy_merge): Dispatch (32, 6, N) @ workgroup_size(32)
 //
+// LOD meshing runs one chunk through its own two passes:
+// Pass 1 (lod_downsample): Dispatch (ceil(G^3 / 64), 1, 1) @ workgroup_size(64)
+// Pass 2 (lod_greedy_merge): Dispatch (1, 6, 1) @ workgroup_size(16)
+// where G = 32 / scale is the reduced grid size.
+//
 // Quad packing (2x u32):
 //   word0: x(5) | y(5) | z(5) | w(5) | h(5) | face(3) | padding(4)
 //   word1: block_type
 //
 // Face directions: 0=+X, 1=-X, 2=+Y, 3=-Y, 4=+Z, 5=-Z
+//
+// Voxels are block state bits: block id in the low 16 bits, properties above.
+// Water and lava are culled like the CPU mesher: they never hide neighbors,
+// below level 0 their top face sits under the top of the cell, and faces
+// between two cells of the same fluid are hidden except where a taller cell's
+// side rises above a lower neighbor.
 
 const CHUNK_SIZE: u32 = 32u;
 const CHUNK_SIZE_SQ: u32 = 1024u;
 const CHUNK_SIZE_CB: u32 = 32768u;
-const MAX_QUADS: u32 = 65536u;
-const FACE_MASK_STRIDE: u32 = 6144u; // 6 * 1024
+// Quads per chunk in the output buffer. Set by the host when the buffer grows;
+// quads past it are counted but not written.
+override QUAD_CAPACITY: u32 = 65536u;
+const FACE_MASK_STRIDE: u32 = 6336u; // 6 * 1024 face masks + 6 * 32 neighbor masks
+const NEIGHBOR_MASK_OFFSET: u32 = 6144u;
+const WATER: u32 = 5u;
+const LAVA: u32 = 6u;
+const FLUID_FALLING: u32 = 0x8u;
+const MAX_FLUID_LEVEL: u32 = 7u;
+const NOT_FLUID: u32 = 0xFFFFFFFFu;
 
 @group(0) @binding(0)
 var<storage, read> voxels: array<u32>;
@@ -34,6 +57,16 @@ var<storage, read_write> quad_counts: array<atomic<u32>>;
 @group(0) @binding(3)
 var<storage, read_write> face_mask_buf: array<u32>;
 
+struct LodParams {
+    scale: u32,
+    grid_size: u32,
+    _pad0: u32,
+    _pad1: u32,
+}
+
+@group(0) @binding(4)
+var<uniform> lod_params: LodParams;
+
 fn voxel_index(chunk: u32, x: u32, y: u32, z: u32) -> u32 {
     return chunk * CHUNK_SIZE_CB + z * CHUNK_SIZE_SQ + y * CHUNK_SIZE + x;
 }
@@ -49,6 +82,28 @@ fn get_block_for_face(chunk: u32, face: u32, layer: u32, row: u32, bit: u32) ->
     return voxels[voxel_index(chunk, x, y, z)];
 }
 
+fn block_id(block: u32) -> u32 {
+    return block & 0xFFFFu;
+}
+
+fn is_fluid(block: u32) -> bool {
+    let id = block_id(block);
+    return id == WATER || id == LAVA;
+}
+
+// Fluid level of the block, NOT_FLUID for other blocks. Falling fluid fills
+// its cell like a source block.
+fn fluid_level(block: u32) -> u32 {
+    if !is_fluid(block) {
+        return NOT_FLUID;
+    }
+    let properties = block >> 16u;
+    if (properties & FLUID_FALLING) != 0u {
+        return 0u;
+    }
+    return properties & MAX_FLUID_LEVEL;
+}
+
 fn face_quad_xyz(face: u32, layer: u32, row_start: u32, bit: u32) -> vec3<u32> {
     switch face {
         case 0u, 1u: { return vec3<u32>(bit, row_start, layer); }
@@ -87,18 +142,77 @@ fn face_culling(
     let layer = col_idx / CHUNK_SIZE;
     let row = col_idx % CHUNK_SIZE;
 
+    // opaque: the voxel has a model
+    // occludes: it hides the touching face of its neighbor (fluids never do)
+    // touches: its face here lies on the cell boundary, so it can be hidden
     var opaque: u32 = 0u;
+    var occludes: u32 = 0u;
+    var touches: u32 = 0u;
     for (var depth: u32 = 0u; depth < CHUNK_SIZE; depth = depth + 1u) {
-        if get_block_for_face(chunk, face, layer, row, depth) != 0u {
-            opaque = opaque | (1u << depth);
+        let block = get_block_for_face(chunk, face, layer, row, depth);
+        if block == 0u {
+            continue;
+        }
+        opaque = opaque | (1u << depth);
+        let level = fluid_level(block);
+        if level == NOT_FLUID {
+            occludes = occludes | (1u << depth);
+        }
+        if face != 2u || level == NOT_FLUID || level == 0u {
+            touches = touches | (1u << depth);
         }
     }
 
     var mask: u32;
     if (face & 1u) == 0u {
-        mask = opaque & ~(opaque << 1u);
+        // Positive faces look at the neighbor at depth + 1.
+        mask = opaque & ~(touches & (occludes >> 1u));
     } else {
-        mask = opaque & ~(opaque >> 1u);
+        mask = opaque & ~(touches & (occludes << 1u));
+    }
+
+    // Boundary faces covered by a solid block in the adjacent chunk
+    let neighbor_mask = face_mask_buf[chunk * FACE_MASK_STRIDE + NEIGHBOR_MASK_OFFSET + face * CHUNK_SIZE + layer];
+    if ((neighbor_mask >> row) & 1u) != 0u {
+        if (face & 1u) == 0u {
+            mask = mask & ~(touches & (1u << 31u));
+        } else {
+            mask = mask & ~(touches & 1u);
+        }
+    }
+
+    // Faces inside one body of fluid. Vertical faces always are; a side face
+    // stays when the neighbor's surface is lower, so the step renders.
+    var fluid_faces = mask;
+    while fluid_faces != 0u {
+        let depth = countTrailingZeros(fluid_faces);
+        fluid_faces = fluid_faces & ~(1u << depth);
+
+        let block = get_block_for_face(chunk, face, layer, row, depth);
+        let level = fluid_level(block);
+        if level == NOT_FLUID {
+            continue;
+        }
+        var neighbor_depth: u32;
+        if (face & 1u) == 0u {
+            if depth == CHUNK_SIZE - 1u {
+                continue;
+            }
+            neighbor_depth = depth + 1u;
+        } else {
+            if depth == 0u {
+                continue;
+            }
+            neighbor_depth = depth - 1u;
+        }
+        let neighbor = get_block_for_face(chunk, face, layer, row, neighbor_depth);
+        if block_id(neighbor) != block_id(block) {
+            continue;
+        }
+        let vertical = face == 2u || face == 3u;
+        if vertical || fluid_level(neighbor) <= level {
+            mask = mask & ~(1u << depth);
+        }
     }
 
     face_mask_buf[chunk * FACE_MASK_STRIDE + face * CHUNK_SIZE_SQ + layer * CHUNK_SIZE + row] = mask;
@@ -194,14 +308,199 @@ fn greedy_merge(
 
     // All 32 threads flush quads to global buffer
     let total = min(atomicLoad(&wg_count), 512u);
-    let quad_base = chunk * MAX_QUADS * 2u;
+    let quad_base = chunk * QUAD_CAPACITY * 2u;
     var qi = lid.x;
     while qi < total {
         let gi = wg_base + qi;
-        if gi < MAX_QUADS {
+        if gi < QUAD_CAPACITY {
             quads[quad_base + gi * 2u] = wg_quads[qi * 2u];
             quads[quad_base + gi * 2u + 1u] = wg_quads[qi * 2u + 1u];
         }
         qi = qi + 32u;
     }
 }
+
+// ============================================================================
+// LOD Pass 1: Downsample — majority vote per scale^3 cell
+// ============================================================================
+// Dispatch: (ceil(G^3 / 64), 1, 1) with workgroup_size(64)
+// Matches the CPU LodMesher: a cell is air if more than half its voxels are,
+// otherwise the most common of the first four block types seen.
+
+@compute @workgroup_size(64, 1, 1)
+fn lod_downsample(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let scale = lod_params.scale;
+    let gs = lod_params.grid_size;
+    let cell = gid.x;
+    if cell >= gs * gs * gs {
+        return;
+    }
+
+    let base_x = (cell % gs) * scale;
+    let base_y = ((cell / gs) % gs) * scale;
+    let base_z = (cell / (gs * gs)) * scale;
+
+    var air_count: u32 = 0u;
+    var types: array<u32, 4>;
+    var counts: array<u32, 4>;
+    var num_types: u32 = 0u;
+
+    for (var dz: u32 = 0u; dz < scale; dz = dz + 1u) {
+        for (var dy: u32 = 0u; dy < scale; dy = dy + 1u) {
+            for (var dx: u32 = 0u; dx < scale; dx = dx + 1u) {
+                let block = voxels[voxel_index(0u, base_x + dx, base_y + dy, base_z + dz)];
+                if block == 0u {
+                    air_count = air_count + 1u;
+                    continue;
+                }
+
+                var found = false;
+                for (var i: u32 = 0u; i < num_types; i = i + 1u) {
+                    if types[i] == block {
+                        counts[i] = counts[i] + 1u;
+                        found = true;
+                        break;
+                    }
+                }
+                if !found && num_types < 4u {
+                    types[num_types] = block;
+                    counts[num_types] = 1u;
+                    num_types = num_types + 1u;
+                }
+            }
+        }
+    }
+
+    var best_type: u32 = 0u;
+    if air_count <= scale * scale * scale / 2u {
+        var best_count: u32 = 0u;
+        for (var i: u32 = 0u; i < num_types; i = i + 1u) {
+            if counts[i] > best_count {
+                best_count = counts[i];
+                best_type = types[i];
+            }
+        }
+    }
+    face_mask_buf[cell] = best_type;
+}
+
+// ============================================================================
+// LOD Pass 2: Greedy merge over the reduced grid
+// ============================================================================
+// Dispatch: (1, 6, 1) with workgroup_size(16)
+// Each thread meshes one layer of one face with a full 2D greedy merge, laid
+// out like the CPU LodMesher: ±X faces have layer z, row y, col x; ±Y faces
+// layer z, row x, col y; ±Z faces layer y, row x, col z. Quad positions are
+// scaled back to chunk coordinates; widths and heights stay in grid cells.
+
+fn lod_cell_xyz(face: u32, layer: u32, row: u32, col: u32) -> vec3<u32> {
+    switch face {
+        case 0u, 1u: { return vec3<u32>(col, row, layer); }
+        case 2u, 3u: { return vec3<u32>(row, col, layer); }
+        default:     { return vec3<u32>(row, layer, col); }
+    }
+}
+
+fn lod_block(p: vec3<i32>) -> u32 {
+    let gs = i32(lod_params.grid_size);
+    if any(p < vec3<i32>(0)) || any(p >= vec3<i32>(gs)) {
+        return 0u;
+    }
+    return face_mask_buf[u32(p.z * gs * gs + p.y * gs + p.x)];
+}
+
+// Block of a visible face at (layer, row, col), or 0 if there is none.
+fn lod_face_block(face: u32, layer: u32, row: u32, col: u32) -> u32 {
+    let p = vec3<i32>(lod_cell_xyz(face, layer, row, col));
+    let block = lod_block(p);
+    if block == 0u {
+        return 0u;
+    }
+
+    var step = vec3<i32>(0);
+    let sign = select(1, -1, (face & 1u) == 1u);
+    step[face / 2u] = sign;
+    if lod_block(p + step) != 0u {
+        return 0u;
+    }
+    return block;
+}
+
+@compute @workgroup_size(16, 1, 1)
+fn lod_greedy_merge(
+    @builtin(local_invocation_id) lid: vec3<u32>,
+    @builtin(workgroup_id) wgid: vec3<u32>,
+) {
+    let scale = lod_params.scale;
+    let gs = lod_params.grid_size;
+    let face = wgid.y;
+    let layer = lid.x;
+    if layer >= gs {
+        return;
+    }
+
+    // One bit per column of each row
+    var visited: array<u32, 16>;
+
+    for (var row: u32 = 0u; row < gs; row = row + 1u) {
+        for (var col: u32 = 0u; col < gs; col = col + 1u) {
+            if ((visited[row] >> col) & 1u) != 0u {
+                continue;
+            }
+            let block = lod_face_block(face, layer, row, col);
+            if block == 0u {
+                continue;
+            }
+
+            var width: u32 = 1u;
+            while col + width < gs {
+                if ((visited[row] >> (col + width)) & 1u) != 0u {
+                    break;
+                }
+                if lod_face_block(face, layer, row, col + width) != block {
+                    break;
+                }
+                width = width + 1u;
+            }
+
+            var height: u32 = 1u;
+            var extend = true;
+            while extend && row + height < gs {
+                for (var c: u32 = col; c < col + width; c = c + 1u) {
+                    if ((visited[row + height] >> c) & 1u) != 0u
+                        || lod_face_block(face, layer, row + height, c) != block {
+                        extend = false;
+                        break;
+                    }
+                }
+                if extend {
+                    height = height + 1u;
+                }
+            }
+
+            let span = ((1u << width) - 1u) << col;
+            for (var r: u32 = row; r < row + height; r = r + 1u) {
+                visited[r] = visited[r] | span;
+            }
+
+            // Widths run along x; ±X faces merge columns along it, the
+            // others rows
+            let coords = lod_cell_xyz(face, layer, row, col) * scale;
+            var wh = vec2<u32>(height, width);
+            if face < 2u {
+                wh = vec2<u32>(width, height);
+            }
+
+            let word0 = (coords.x & 0x1Fu) | ((coords.y & 0x1Fu) << 5u)
+                      | ((coords.z & 0x1Fu) << 10u)
+                      | ((wh.x & 0x1Fu) << 15u) | ((wh.y & 0x1Fu) << 20u)
+                      | ((face & 0x7u) << 25u);
+
+            let qi = atomicAdd(&quad_counts[0], 1u);
+            if qi < QUAD_CAPACITY {
+                quads[qi * 2u] = word0;
+                quads[qi * 2u + 1u] = block;
+            }
+        }
+    }
+}
@@ -0,0 +1,407 @@
+//! Frustum and distance culling of chunk meshes on the GPU.
+//!
+//! Each chunk's box is tested in a compute pass, and the visible ones are
+//! compacted into an index list and indexed indirect draw arguments, so the
+//! cost of culling stays on the GPU as the number of chunks grows.
+
+use std::borrow::Cow;
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::request_device;
+
+/// Chunks the culler holds buffers for before it has to grow them.
+const DEFAULT_CAPACITY: usize = 1024;
+
+/// Invocations per workgroup of the culling pass.
+const WORKGROUP_SIZE: u32 = 64;
+
+/// A chunk mesh to cull: its world space box and where its indices are in
+/// the shared index buffer. Laid out like `Chunk` in the shader.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct CullChunk {
+    pub min: [f32; 3],
+    pub index_count: u32,
+    pub max: [f32; 3],
+    pub first_index: u32,
+    pub base_vertex: i32,
+    pub _padding: [u32; 3],
+}
+
+impl CullChunk {
+    /// A chunk with box `min` to `max` and no mesh information, for culling
+    /// without drawing.
+    pub fn new(min: [f32; 3], max: [f32; 3]) -> Self {
+        Self {
+            min,
+            max,
+            ..Default::default()
+        }
+    }
+}
+
+/// Arguments of one indexed indirect draw, laid out as
+/// `draw_indexed_indirect` reads them. `first_instance` is the index of the
+/// chunk being drawn.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Pod, Zeroable)]
+pub struct DrawIndexedArgs {
+    pub index_count: u32,
+    pub instance_count: u32,
+    pub first_index: u32,
+    pub base_vertex: i32,
+    pub first_instance: u32,
+}
+
+/// Uniform parameters of the culling pass, laid out like `CullParams` in the
+/// shader.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+struct CullParams {
+    planes: [[f32; 4]; 6],
+    camera: [f32; 3],
+    max_distance: f32,
+    chunk_count: u32,
+    _padding: [u32; 3],
+}
+
+/// Chunks that passed culling, read back from the GPU.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CullResult {
+    /// Indices of the visible chunks, ascending.
+    pub visible: Vec<u32>,
+    /// One draw per visible chunk, in the same order as `visible`.
+    pub draw_args: Vec<DrawIndexedArgs>,
+}
+
+/// The six planes bounding the view volume of a column-major
+/// view-projection matrix (Gribb-Hartmann), with clip space depth in `0..=1`
+/// as wgpu uses. Each is `[nx, ny, nz, d]` with the normal pointing inwards.
+/// An infinite far plane comes out as all zeros, which rejects nothing.
+pub fn frustum_planes(view_projection: [[f32; 4]; 4]) -> [[f32; 4]; 6] {
+    let row = |i: usize| view_projection.map(|column| column[i]);
+    let combine = |a: [f32; 4], b: [f32; 4], sign: f32| [0, 1, 2, 3].map(|i| a[i] + sign * b[i]);
+    let [row0, row1, row2, row3] = [0, 1, 2, 3].map(row);
+    [
+        combine(row3, row0, 1.0),
+        combine(row3, row0, -1.0),
+        combine(row3, row1, 1.0),
+        combine(row3, row1, -1.0),
+        row2,
+        combine(row3, row2, -1.0),
+    ]
+}
+
+/// GPU buffers sized for `capacity` chunks.
+struct CullBuffers {
+    chunk_buffer: wgpu::Buffer,
+    params_buffer: wgpu::Buffer,
+    count_buffer: wgpu::Buffer,
+    visible_buffer: wgpu::Buffer,
+    draw_args_buffer: wgpu::Buffer,
+    count_staging: wgpu::Buffer,
+    visible_staging: wgpu::Buffer,
+    draw_args_staging: wgpu::Buffer,
+    capacity: usize,
+}
+
+impl CullBuffers {
+    fn new(device: &wgpu::Device, capacity: usize) -> Self {
+        let chunk_bytes = (capacity * size_of::<CullChunk>()) as u64;
+        let visible_bytes = (capacity * 4) as u64;
+        let draw_args_bytes = (capacity * size_of::<DrawIndexedArgs>()) as u64;
+        let buffer = |label, size, usage| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size,
+                usage,
+                mapped_at_creation: false,
+            })
+        };
+        let readable = wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ;
+        let output = wgpu::BufferUsages::STORAGE
+            | wgpu::BufferUsages::INDIRECT
+            | wgpu::BufferUsages::COPY_SRC;
+
+        Self {
+            chunk_buffer: buffer(
+                "Cull Chunk Buffer",
+                chunk_bytes,
+                wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            ),
+            params_buffer: buffer(
+                "Cull Params Buffer",
+                size_of::<CullParams>() as u64,
+                wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            ),
+            count_buffer: buffer(
+                "Visible Count Buffer",
+                4,
+                output | wgpu::BufferUsages::COPY_DST,
+            ),
+            visible_buffer: buffer("Visible Chunk Buffer", visible_bytes, output),
+            draw_args_buffer: buffer("Draw Args Buffer", draw_args_bytes, output),
+            count_staging: buffer("Visible Count Staging Buffer", 4, readable),
+            visible_staging: buffer("Visible Chunk Staging Buffer", visible_bytes, readable),
+            draw_args_staging: buffer("Draw Args Staging Buffer", draw_args_bytes, readable),
+            capacity,
+        }
+    }
+
+    fn bind_group(&self, device: &wgpu::Device, layout: &wgpu::BindGroupLayout) -> wgpu::BindGroup {
+        let buffers = [
+            &self.chunk_buffer,
+            &self.params_buffer,
+            &self.count_buffer,
+            &self.visible_buffer,
+            &self.draw_args_buffer,
+        ];
+        let entries: Vec<_> = buffers
+            .iter()
+            .enumerate()
+            .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                binding: binding as u32,
+                resource: buffer.as_entire_binding(),
+            })
+            .collect();
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Culling Bind Group"),
+            layout,
+            entries: &entries,
+        })
+    }
+}
+
+/// Culls chunk boxes against a view-projection and a draw distance in a
+/// compute pass.
+///
+/// [`Self::cull`] reads the visible chunks back. [`Self::dispatch`] leaves
+/// them on the GPU, where [`Self::draw_args_buffer`] and
+/// [`Self::visible_count_buffer`] can feed
+/// `multi_draw_indexed_indirect_count` on the same device.
+pub struct GpuChunkCuller {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    buffers: CullBuffers,
+    bind_group: wgpu::BindGroup,
+}
+
+impl GpuChunkCuller {
+    /// A culler on its own device, or `None` without a usable GPU.
+    pub fn new() -> Option<Self> {
+        let (device, queue) = request_device("Ferrum GPU Culler")?;
+        Some(Self::with_device(device, queue))
+    }
+
+    /// A culler sharing `device`, such as [`GpuChunkMesher::device`].
+    ///
+    /// [`GpuChunkMesher::device`]: crate::GpuChunkMesher::device
+    pub fn with_device(device: wgpu::Device, queue: wgpu::Queue) -> Self {
+        let storage = |binding, read_only| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Culling Bind Group Layout"),
+            entries: &[
+                storage(0, true),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage(2, false),
+                storage(3, false),
+                storage(4, false),
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Culling Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Chunk Culling Shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("culling.wgsl"))),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Chunk Culling Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some("cull_chunks"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let buffers = CullBuffers::new(&device, DEFAULT_CAPACITY);
+        let bind_group = buffers.bind_group(&device, &bind_group_layout);
+
+        Self {
+            device,
+            queue,
+            pipeline,
+            bind_group_layout,
+            buffers,
+            bind_group,
+        }
+    }
+
+    /// Chunks that can be culled in one dispatch before the buffers grow.
+    pub fn capacity(&self) -> usize {
+        self.buffers.capacity
+    }
+
+    /// Compacted draws of the last dispatch, one [`DrawIndexedArgs`] per
+    /// visible chunk.
+    pub fn draw_args_buffer(&self) -> &wgpu::Buffer {
+        &self.buffers.draw_args_buffer
+    }
+
+    /// A single `u32`: how many chunks the last dispatch kept.
+    pub fn visible_count_buffer(&self) -> &wgpu::Buffer {
+        &self.buffers.count_buffer
+    }
+
+    /// Cull `chunks` and read back the visible ones. Chunks are kept when
+    /// their box may be inside the frustum of `view_projection` and comes
+    /// within `max_distance` of `camera`; pass `f32::INFINITY` to cull by the
+    /// frustum alone.
+    pub fn cull(
+        &mut self,
+        chunks: &[CullChunk],
+        view_projection: [[f32; 4]; 4],
+        camera: [f32; 3],
+        max_distance: f32,
+    ) -> CullResult {
+        let mut encoder = self.encode(chunks, view_projection, camera, max_distance);
+        if chunks.is_empty() {
+            return CullResult::default();
+        }
+
+        let capacity = self.buffers.capacity as u64;
+        let buffers = &self.buffers;
+        encoder.copy_buffer_to_buffer(&buffers.count_buffer, 0, &buffers.count_staging, 0, 4);
+        encoder.copy_buffer_to_buffer(
+            &buffers.visible_buffer,
+            0,
+            &buffers.visible_staging,
+            0,
+            capacity * 4,
+        );
+        encoder.copy_buffer_to_buffer(
+            &buffers.draw_args_buffer,
+            0,
+            &buffers.draw_args_staging,
+            0,
+            capacity * size_of::<DrawIndexedArgs>() as u64,
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let count = self.read::<u32>(&self.buffers.count_staging, 1)[0] as usize;
+        let visible = self.read::<u32>(&self.buffers.visible_staging, count);
+        let draw_args = self.read::<DrawIndexedArgs>(&self.buffers.draw_args_staging, count);
+
+        // Invocations append in whatever order they finish
+        let mut kept: Vec<_> = visible.into_iter().zip(draw_args).collect();
+        kept.sort_unstable_by_key(|(index, _)| *index);
+        let (visible, draw_args) = kept.into_iter().unzip();
+        CullResult { visible, draw_args }
+    }
+
+    /// Cull `chunks` without reading anything back, leaving the results in
+    /// [`Self::draw_args_buffer`] and [`Self::visible_count_buffer`].
+    pub fn dispatch(
+        &mut self,
+        chunks: &[CullChunk],
+        view_projection: [[f32; 4]; 4],
+        camera: [f32; 3],
+        max_distance: f32,
+    ) {
+        let encoder = self.encode(chunks, view_projection, camera, max_distance);
+        self.queue.submit(Some(encoder.finish()));
+    }
+
+    /// Upload the inputs and record the culling pass, growing the buffers to
+    /// fit `chunks` first.
+    fn encode(
+        &mut self,
+        chunks: &[CullChunk],
+        view_projection: [[f32; 4]; 4],
+        camera: [f32; 3],
+        max_distance: f32,
+    ) -> wgpu::CommandEncoder {
+        if chunks.len() > self.buffers.capacity {
+            self.buffers = CullBuffers::new(&self.device, chunks.len().next_power_of_two());
+            self.bind_group = self
+                .buffers
+                .bind_group(&self.device, &self.bind_group_layout);
+        }
+
+        // Shaders may assume no infinities, so "unlimited" is the largest
+        // finite distance instead
+        let params = CullParams {
+            planes: frustum_planes(view_projection),
+            camera,
+            max_distance: max_distance.min(f32::MAX),
+            chunk_count: chunks.len() as u32,
+            _padding: [0; 3],
+        };
+        self.queue
+            .write_buffer(&self.buffers.params_buffer, 0, bytemuck::bytes_of(&params));
+        self.queue
+            .write_buffer(&self.buffers.count_buffer, 0, bytemuck::bytes_of(&0u32));
+        if !chunks.is_empty() {
+            self.queue
+                .write_buffer(&self.buffers.chunk_buffer, 0, bytemuck::cast_slice(chunks));
+        }
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Chunk Culling Encoder"),
+            });
+        if !chunks.is_empty() {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Chunk Culling Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            pass.dispatch_workgroups((chunks.len() as u32).div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+        encoder
+    }
+
+    /// Map the first `count` elements of a staging buffer and copy them out.
+    fn read<T: Pod>(&self, staging: &wgpu::Buffer, count: usize) -> Vec<T> {
+        if count == 0 {
+            return Vec::new();
+        }
+        let slice = staging.slice(..(count * size_of::<T>()) as u64);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        self.device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .unwrap();
+
+        let data = slice.get_mapped_range();
+        let values = bytemuck::cast_slice::<u8, T>(&data).to_vec();
+        drop(data);
+        staging.unmap();
+        values
+    }
+}
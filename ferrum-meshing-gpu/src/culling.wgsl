@@ -0,0 +1,82 @@
+// GPU Chunk Culling Compute Shader
+//
+// One invocation per chunk tests its box against the six frustum planes and
+// the maximum draw distance. Visible chunks are appended to `visible` and get
+// an indexed indirect draw in `draw_args`, both compacted and counted by
+// `visible_count`, so they can be drawn with multi_draw_indexed_indirect_count.
+//
+// Dispatch: (ceil(chunk_count / 64), 1, 1) @ workgroup_size(64)
+//
+// Planes are (normal, distance) with the normal pointing inwards: a point p
+// is inside when dot(normal, p) + distance >= 0.
+
+struct Chunk {
+    min: vec3<f32>,
+    index_count: u32,
+    max: vec3<f32>,
+    first_index: u32,
+    base_vertex: i32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+struct DrawIndexedArgs {
+    index_count: u32,
+    instance_count: u32,
+    first_index: u32,
+    base_vertex: i32,
+    first_instance: u32,
+}
+
+struct CullParams {
+    planes: array<vec4<f32>, 6>,
+    camera: vec3<f32>,
+    max_distance: f32,
+    chunk_count: u32,
+}
+
+@group(0) @binding(0) var<storage, read> chunks: array<Chunk>;
+@group(0) @binding(1) var<uniform> params: CullParams;
+@group(0) @binding(2) var<storage, read_write> visible_count: atomic<u32>;
+@group(0) @binding(3) var<storage, read_write> visible: array<u32>;
+@group(0) @binding(4) var<storage, read_write> draw_args: array<DrawIndexedArgs>;
+
+fn in_frustum(min: vec3<f32>, max: vec3<f32>) -> bool {
+    for (var i = 0u; i < 6u; i++) {
+        let plane = params.planes[i];
+        // The corner furthest along the plane's normal
+        let corner = select(min, max, plane.xyz >= vec3<f32>(0.0));
+        if dot(plane.xyz, corner) + plane.w < 0.0 {
+            return false;
+        }
+    }
+    return true;
+}
+
+fn in_range(min: vec3<f32>, max: vec3<f32>) -> bool {
+    let nearest = clamp(params.camera, min, max);
+    return distance(params.camera, nearest) <= params.max_distance;
+}
+
+@compute @workgroup_size(64)
+fn cull_chunks(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if index >= params.chunk_count {
+        return;
+    }
+    let chunk = chunks[index];
+    if !in_range(chunk.min, chunk.max) || !in_frustum(chunk.min, chunk.max) {
+        return;
+    }
+
+    let slot = atomicAdd(&visible_count, 1u);
+    visible[slot] = index;
+    draw_args[slot] = DrawIndexedArgs(
+        chunk.index_count,
+        1u,
+        chunk.first_index,
+        chunk.base_vertex,
+        index,
+    );
+}
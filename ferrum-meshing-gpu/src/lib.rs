@@ -4,27 +4,66 @@
 //! 1. **Face culling**: Binary face mask generation using bitwise ops.
 //! 2. **Greedy merge**: Full 2D greedy merge per depth slice.
 //!
+//! Distant chunks can instead be downsampled on the GPU and greedy merged at
+//! reduced resolution, see [`GpuChunkMesher::mesh_chunk_lod_gpu`].
+//!
 //! Batch processing amortizes GPU submission overhead across N chunks,
 //! achieving <0.2µs per chunk when processing 64+ chunks per batch.
+//!
+//! Chunk meshes can also be frustum and distance culled on the GPU into
+//! indirect draw arguments, see [`GpuChunkCuller`].
+
+mod culling;
 
 use std::borrow::Cow;
 
 use bytemuck::{Pod, Zeroable};
 use wgpu::util::DeviceExt;
 
+pub use culling::{CullChunk, CullResult, DrawIndexedArgs, GpuChunkCuller, frustum_planes};
+
 /// Chunk dimensions (32x32x32).
 pub const CHUNK_SIZE: usize = 32;
 pub const CHUNK_SIZE_SQ: usize = CHUNK_SIZE * CHUNK_SIZE;
 pub const CHUNK_SIZE_CB: usize = CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE;
 
-/// Maximum number of quads the shader can emit per chunk.
+/// Quads per chunk the output buffer holds by default. Dense chunks can need
+/// more; see [`GpuChunkMesher::mesh_chunk_full`].
 pub const MAX_QUADS: usize = 65536;
 
 /// Maximum batch size (chunks per dispatch).
 pub const MAX_BATCH_SIZE: usize = 256;
 
-/// Face mask stride per chunk: 6 faces * 1024 entries = 6144 u32s
-const FACE_MASK_STRIDE: usize = 6 * CHUNK_SIZE_SQ;
+/// Neighbor masks per chunk: one word per layer for each of the 6 faces.
+const NEIGHBOR_MASK_WORDS: usize = 6 * CHUNK_SIZE;
+
+/// Face mask stride per chunk: 6 faces * 1024 entries = 6144 u32s, followed
+/// by the chunk's neighbor masks.
+const FACE_MASK_STRIDE: usize = 6 * CHUNK_SIZE_SQ + NEIGHBOR_MASK_WORDS;
+
+/// Boundary cells covered by a solid block in the adjacent chunk. For each
+/// face (0=+X, 1=-X, 2=+Y, 3=-Y, 4=+Z, 5=-Z), one word per layer with a bit
+/// per row, laid out like the face masks: ±X faces have layer z and row y,
+/// ±Y faces layer z and row x, ±Z faces layer y and row x.
+pub type NeighborMasks = [[u32; CHUNK_SIZE]; 6];
+
+/// Timestamps per timed batch: start and end of each of the two passes.
+const TIMESTAMP_COUNT: u32 = 4;
+const TIMESTAMP_BYTES: u64 = TIMESTAMP_COUNT as u64 * 8;
+
+/// Downsampling factors [`GpuChunkMesher::mesh_chunk_lod_gpu`] supports, one
+/// for each reduced level of detail down to the 2x2x2 grid of far chunks.
+pub const LOD_SCALES: [u32; 4] = [2, 4, 8, 16];
+
+/// Uniform parameters of the LOD passes, laid out like `LodParams` in the
+/// shader.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+struct LodParams {
+    scale: u32,
+    grid_size: u32,
+    _padding: [u32; 2],
+}
 
 /// A packed quad as output by the compute shader.
 #[repr(C)]
@@ -55,29 +94,329 @@ impl PackedQuad {
     pub fn face(&self) -> u32 {
         (self.word0 >> 25) & 0x7
     }
+
+    /// Pack a quad the way the shader does. Each of `x`, `y`, `z`, `width`
+    /// and `height` keeps its low 5 bits and `face` its low 3.
+    pub fn pack(x: u8, y: u8, z: u8, width: u8, height: u8, face: u8, block_type: u32) -> Self {
+        let field = |value: u8, shift: u32| (value as u32 & 0x1F) << shift;
+        Self {
+            word0: field(x, 0)
+                | field(y, 5)
+                | field(z, 10)
+                | field(width, 15)
+                | field(height, 20)
+                | (face as u32 & 0x7) << 25,
+            block_type,
+        }
+    }
+
+    /// `(x, y, z, width, height, face)`, or `None` if the face index is not
+    /// a valid direction (6 or 7).
+    pub fn unpack(&self) -> Option<(u8, u8, u8, u8, u8, u8)> {
+        let face = self.face() as u8;
+        if face > 5 {
+            return None;
+        }
+        Some((
+            self.x() as u8,
+            self.y() as u8,
+            self.z() as u8,
+            self.width() as u8,
+            self.height() as u8,
+            face,
+        ))
+    }
+}
+
+/// A [`PackedQuad`] with its fields split out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnpackedQuad {
+    pub x: u8,
+    pub y: u8,
+    pub z: u8,
+    pub width: u8,
+    pub height: u8,
+    /// Face direction, as in [`PackedQuad::face`].
+    pub face: u8,
+    pub block_type: u32,
+}
+
+/// Unpack shader output, dropping quads with an invalid face index.
+pub fn decode_quads(quads: &[PackedQuad]) -> Vec<UnpackedQuad> {
+    quads
+        .iter()
+        .filter_map(|quad| {
+            let (x, y, z, width, height, face) = quad.unpack()?;
+            Some(UnpackedQuad {
+                x,
+                y,
+                z,
+                width,
+                height,
+                face,
+                block_type: quad.block_type,
+            })
+        })
+        .collect()
+}
+
+/// GPU time spent in each compute pass of one batch, in nanoseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PassTimings {
+    pub face_culling_ns: u64,
+    pub greedy_merge_ns: u64,
+}
+
+impl PassTimings {
+    /// Convert raw timestamps (face culling start and end, then greedy merge
+    /// start and end) using the queue's timestamp period in nanoseconds per
+    /// tick. A pass whose end is before its start reads as 0.
+    pub fn from_ticks(ticks: [u64; 4], period: f32) -> Self {
+        let ns = |start: u64, end: u64| (end.saturating_sub(start) as f64 * period as f64) as u64;
+        Self {
+            face_culling_ns: ns(ticks[0], ticks[1]),
+            greedy_merge_ns: ns(ticks[2], ticks[3]),
+        }
+    }
+}
+
+/// Query set and buffers for timing the compute passes. Only created when
+/// the device has `TIMESTAMP_QUERY`.
+struct TimestampQueries {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    staging_buffer: wgpu::Buffer,
+    /// Nanoseconds per timestamp tick.
+    period: f32,
+}
+
+impl TimestampQueries {
+    fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Meshing Timestamps"),
+            ty: wgpu::QueryType::Timestamp,
+            count: TIMESTAMP_COUNT,
+        });
+
+        // Queries resolve to a 256-byte aligned offset, so both buffers are
+        // padded to a whole alignment block and the results sit at offset 0.
+        let size = TIMESTAMP_BYTES.next_multiple_of(wgpu::QUERY_RESOLVE_BUFFER_ALIGNMENT);
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Timestamp Resolve Buffer"),
+            size,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Timestamp Staging Buffer"),
+            size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            query_set,
+            resolve_buffer,
+            staging_buffer,
+            period: queue.get_timestamp_period(),
+        }
+    }
+
+    /// Timestamp writes around the pass whose start is query `first`.
+    fn pass_writes(&self, first: u32) -> wgpu::ComputePassTimestampWrites<'_> {
+        wgpu::ComputePassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(first),
+            end_of_pass_write_index: Some(first + 1),
+        }
+    }
+
+    fn resolve(&self, encoder: &mut wgpu::CommandEncoder) {
+        encoder.resolve_query_set(&self.query_set, 0..TIMESTAMP_COUNT, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(
+            &self.resolve_buffer,
+            0,
+            &self.staging_buffer,
+            0,
+            TIMESTAMP_BYTES,
+        );
+    }
+
+    /// Read back the timestamps resolved by a submitted batch.
+    fn read(&self, device: &wgpu::Device) -> PassTimings {
+        let slice = self.staging_buffer.slice(..TIMESTAMP_BYTES);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+
+        let data = slice.get_mapped_range();
+        let mut ticks = [0u64; 4];
+        ticks.copy_from_slice(bytemuck::cast_slice::<u8, u64>(&data));
+        drop(data);
+        self.staging_buffer.unmap();
+
+        PassTimings::from_ticks(ticks, self.period)
+    }
+}
+
+/// Output of meshing one chunk, with whether the quad buffer was too small.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MeshResult {
+    /// The quads that fit in the buffer.
+    pub quads: Vec<PackedQuad>,
+    /// Whether quads were dropped because the buffer was full.
+    pub truncated: bool,
+    /// How many quads the chunk actually produced.
+    pub needed: u32,
 }
 
 struct GpuBuffers {
     voxel_buffer: wgpu::Buffer,
     quad_buffer: wgpu::Buffer,
     counter_buffer: wgpu::Buffer,
-    #[allow(dead_code)]
     face_mask_buffer: wgpu::Buffer,
+    /// LOD scale and reduced grid size, see [`LodParams`].
+    lod_params_buffer: wgpu::Buffer,
     counter_zero_buffer: wgpu::Buffer,
     quad_staging: wgpu::Buffer,
     counter_staging: wgpu::Buffer,
     batch_size: usize,
+    /// Quads each chunk's slot in `quad_buffer` holds.
+    quad_capacity: usize,
+}
+
+impl GpuBuffers {
+    /// Output and staging buffers for `quad_capacity` quads per chunk.
+    fn create_quad_buffers(
+        device: &wgpu::Device,
+        batch_size: usize,
+        quad_capacity: usize,
+    ) -> (wgpu::Buffer, wgpu::Buffer) {
+        let size = (batch_size * quad_capacity * 2 * 4) as u64;
+        let quad_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Quad Output Buffer"),
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let quad_staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Quad Staging Buffer"),
+            size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        (quad_buffer, quad_staging)
+    }
+
+    fn bind_group(&self, device: &wgpu::Device, layout: &wgpu::BindGroupLayout) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Meshing Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.voxel_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.quad_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.counter_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: self.face_mask_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: self.lod_params_buffer.as_entire_binding(),
+                },
+            ],
+        })
+    }
+}
+
+/// The compute pipelines of `compute.wgsl`, built for a quad capacity.
+struct Pipelines {
+    face_culling: wgpu::ComputePipeline,
+    greedy_merge: wgpu::ComputePipeline,
+    lod_downsample: wgpu::ComputePipeline,
+    lod_greedy_merge: wgpu::ComputePipeline,
+}
+
+/// Build every pipeline for a quad capacity.
+///
+/// Each call compiles its own shader module: pipelines sharing a module can
+/// end up with the override values of the first pipeline built from it.
+fn create_pipelines(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    quad_capacity: usize,
+) -> Pipelines {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Chunk Meshing Shader"),
+        source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("compute.wgsl"))),
+    });
+    let constants = [("QUAD_CAPACITY", quad_capacity as f64)];
+    let pipeline = |label, entry_point| {
+        device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some(label),
+            layout: Some(layout),
+            module: &shader,
+            entry_point: Some(entry_point),
+            compilation_options: wgpu::PipelineCompilationOptions {
+                constants: &constants,
+                ..Default::default()
+            },
+            cache: None,
+        })
+    };
+    Pipelines {
+        face_culling: pipeline("Face Culling Pipeline", "face_culling"),
+        greedy_merge: pipeline("Greedy Merge Pipeline", "greedy_merge"),
+        lod_downsample: pipeline("LOD Downsample Pipeline", "lod_downsample"),
+        lod_greedy_merge: pipeline("LOD Greedy Merge Pipeline", "lod_greedy_merge"),
+    }
+}
+
+/// Open the high performance adapter, enabling timestamp queries when it
+/// has them. `None` if there is no usable GPU.
+pub(crate) fn request_device(label: &str) -> Option<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::all(),
+        ..Default::default()
+    });
+
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::HighPerformance,
+        force_fallback_adapter: false,
+        compatible_surface: None,
+    }))
+    .ok()?;
+
+    // Pass timing is optional; adapters without it mesh untimed.
+    let required_features = adapter.features() & wgpu::Features::TIMESTAMP_QUERY;
+
+    pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        label: Some(label),
+        required_features,
+        required_limits: wgpu::Limits::downlevel_defaults(),
+        memory_hints: wgpu::MemoryHints::Performance,
+        ..Default::default()
+    }))
+    .ok()
 }
 
 pub struct GpuChunkMesher {
     device: wgpu::Device,
     queue: wgpu::Queue,
-    face_culling_pipeline: wgpu::ComputePipeline,
-    greedy_merge_pipeline: wgpu::ComputePipeline,
-    #[allow(dead_code)]
+    pipelines: Pipelines,
+    pipeline_layout: wgpu::PipelineLayout,
     bind_group_layout: wgpu::BindGroupLayout,
     buffers: GpuBuffers,
     bind_group: wgpu::BindGroup,
+    timestamps: Option<TimestampQueries>,
 }
 
 impl GpuChunkMesher {
@@ -89,32 +428,7 @@ impl GpuChunkMesher {
     pub fn with_batch_size(batch_size: usize) -> Option<Self> {
         let batch_size = batch_size.clamp(1, MAX_BATCH_SIZE);
 
-        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
-            ..Default::default()
-        });
-
-        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::HighPerformance,
-            force_fallback_adapter: false,
-            compatible_surface: None,
-        }))
-        .ok()?;
-
-        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
-            label: Some("Ferrum GPU Mesher"),
-            required_features: wgpu::Features::empty(),
-            required_limits: wgpu::Limits::downlevel_defaults(),
-            memory_hints: wgpu::MemoryHints::Performance,
-            ..Default::default()
-        }))
-        .ok()?;
-
-        let shader_source = include_str!("compute.wgsl");
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Chunk Meshing Shader"),
-            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(shader_source)),
-        });
+        let (device, queue) = request_device("Ferrum GPU Mesher")?;
 
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("Meshing Bind Group Layout"),
@@ -159,6 +473,16 @@ impl GpuChunkMesher {
                     },
                     count: None,
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         });
 
@@ -168,29 +492,10 @@ impl GpuChunkMesher {
             push_constant_ranges: &[],
         });
 
-        let face_culling_pipeline =
-            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
-                label: Some("Face Culling Pipeline"),
-                layout: Some(&pipeline_layout),
-                module: &shader,
-                entry_point: Some("face_culling"),
-                compilation_options: Default::default(),
-                cache: None,
-            });
-
-        let greedy_merge_pipeline =
-            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
-                label: Some("Greedy Merge Pipeline"),
-                layout: Some(&pipeline_layout),
-                module: &shader,
-                entry_point: Some("greedy_merge"),
-                compilation_options: Default::default(),
-                cache: None,
-            });
+        let pipelines = create_pipelines(&device, &pipeline_layout, MAX_QUADS);
 
         let n = batch_size;
         let voxel_buffer_size = (n * CHUNK_SIZE_CB * 4) as u64;
-        let quad_buffer_size = (n * MAX_QUADS * 2 * 4) as u64;
         let counter_buffer_size = (n * 4) as u64;
         let face_mask_buffer_size = (n * FACE_MASK_STRIDE * 4) as u64;
 
@@ -201,12 +506,7 @@ impl GpuChunkMesher {
             mapped_at_creation: false,
         });
 
-        let quad_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Quad Output Buffer"),
-            size: quad_buffer_size,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
-            mapped_at_creation: false,
-        });
+        let (quad_buffer, quad_staging) = GpuBuffers::create_quad_buffers(&device, n, MAX_QUADS);
 
         let counter_zeros = vec![0u32; n];
         let counter_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -220,7 +520,14 @@ impl GpuChunkMesher {
         let face_mask_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Face Mask Buffer"),
             size: face_mask_buffer_size,
-            usage: wgpu::BufferUsages::STORAGE,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let lod_params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("LOD Params Buffer"),
+            size: std::mem::size_of::<LodParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
 
@@ -230,13 +537,6 @@ impl GpuChunkMesher {
             usage: wgpu::BufferUsages::COPY_SRC,
         });
 
-        let quad_staging = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Quad Staging Buffer"),
-            size: quad_buffer_size,
-            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
-            mapped_at_creation: false,
-        });
-
         let counter_staging = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Counter Staging Buffer"),
             size: counter_buffer_size,
@@ -244,58 +544,128 @@ impl GpuChunkMesher {
             mapped_at_creation: false,
         });
 
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Meshing Bind Group"),
-            layout: &bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: voxel_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: quad_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: counter_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 3,
-                    resource: face_mask_buffer.as_entire_binding(),
-                },
-            ],
-        });
-
         let buffers = GpuBuffers {
             voxel_buffer,
             quad_buffer,
             counter_buffer,
             face_mask_buffer,
+            lod_params_buffer,
             counter_zero_buffer,
             quad_staging,
             counter_staging,
             batch_size,
+            quad_capacity: MAX_QUADS,
         };
+        let bind_group = buffers.bind_group(&device, &bind_group_layout);
+
+        let timestamps = device
+            .features()
+            .contains(wgpu::Features::TIMESTAMP_QUERY)
+            .then(|| TimestampQueries::new(&device, &queue));
 
         Some(Self {
             device,
             queue,
-            face_culling_pipeline,
-            greedy_merge_pipeline,
+            pipelines,
+            pipeline_layout,
             bind_group_layout,
             buffers,
             bind_group,
+            timestamps,
         })
     }
 
-    /// Mesh a single chunk (dispatch + readback).
+    /// The device meshing runs on, for sharing with other passes such as
+    /// [`GpuChunkCuller::with_device`].
+    pub fn device(&self) -> &wgpu::Device {
+        &self.device
+    }
+
+    pub fn queue(&self) -> &wgpu::Queue {
+        &self.queue
+    }
+
+    /// Quads each chunk can produce before output is truncated.
+    pub fn quad_capacity(&self) -> usize {
+        self.buffers.quad_capacity
+    }
+
+    /// Grow the quad buffers to hold at least `needed` quads per chunk,
+    /// rounded up to a power of two. Returns `false`, leaving the buffers as
+    /// they were, if that would exceed the device's buffer limits.
+    fn grow_quad_capacity(&mut self, needed: usize) -> bool {
+        let capacity = needed.next_power_of_two();
+        if capacity <= self.buffers.quad_capacity {
+            return true;
+        }
+        let bytes = (self.buffers.batch_size * capacity * 2 * 4) as u64;
+        let limits = self.device.limits();
+        if bytes > limits.max_storage_buffer_binding_size as u64 || bytes > limits.max_buffer_size {
+            return false;
+        }
+
+        let (quad_buffer, quad_staging) =
+            GpuBuffers::create_quad_buffers(&self.device, self.buffers.batch_size, capacity);
+        self.buffers.quad_buffer = quad_buffer;
+        self.buffers.quad_staging = quad_staging;
+        self.buffers.quad_capacity = capacity;
+        self.bind_group = self
+            .buffers
+            .bind_group(&self.device, &self.bind_group_layout);
+        self.pipelines = create_pipelines(&self.device, &self.pipeline_layout, capacity);
+        true
+    }
+
+    /// Whether [`Self::mesh_chunks_batch_timed`] can time passes on this GPU.
+    pub fn supports_pass_timings(&self) -> bool {
+        self.timestamps.is_some()
+    }
+
+    /// Mesh a single chunk (dispatch + readback). Quads past
+    /// [`Self::quad_capacity`] are dropped with a warning; use
+    /// [`Self::mesh_chunk_checked`] or [`Self::mesh_chunk_full`] to handle
+    /// dense chunks.
     pub fn mesh_chunk(&self, voxels: &[u32; CHUNK_SIZE_CB]) -> Vec<PackedQuad> {
+        let result = self.mesh_chunk_checked(voxels);
+        if result.truncated {
+            log::warn!(
+                "Chunk needs {} quads but the buffer holds {}; dropping the rest",
+                result.needed,
+                self.quad_capacity()
+            );
+        }
+        result.quads
+    }
+
+    /// Mesh a single chunk, hiding boundary faces that `neighbors` marks as
+    /// covered by the adjacent chunks.
+    pub fn mesh_chunk_with_neighbors(
+        &self,
+        voxels: &[u32; CHUNK_SIZE_CB],
+        neighbors: &NeighborMasks,
+    ) -> Vec<PackedQuad> {
+        let offset = (6 * CHUNK_SIZE_SQ * 4) as u64;
+        self.queue.write_buffer(
+            &self.buffers.face_mask_buffer,
+            offset,
+            bytemuck::cast_slice(neighbors.as_flattened()),
+        );
+        let quads = self.mesh_chunk(voxels);
+
+        // Every other entry point meshes chunks in isolation
+        self.queue.write_buffer(
+            &self.buffers.face_mask_buffer,
+            offset,
+            &[0; NEIGHBOR_MASK_WORDS * 4],
+        );
+        quads
+    }
+
+    /// Mesh a single chunk, reporting whether it overflowed the quad buffer.
+    pub fn mesh_chunk_checked(&self, voxels: &[u32; CHUNK_SIZE_CB]) -> MeshResult {
         self.queue
             .write_buffer(&self.buffers.voxel_buffer, 0, bytemuck::cast_slice(voxels));
 
-        let quad_buffer_size = (MAX_QUADS * 2 * 4) as u64;
-
         let mut encoder = self
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
@@ -315,7 +685,7 @@ impl GpuChunkMesher {
                 label: Some("Face Culling Pass"),
                 timestamp_writes: None,
             });
-            pass.set_pipeline(&self.face_culling_pipeline);
+            pass.set_pipeline(&self.pipelines.face_culling);
             pass.set_bind_group(0, &self.bind_group, &[]);
             pass.dispatch_workgroups(4, 6, 1);
         }
@@ -325,11 +695,126 @@ impl GpuChunkMesher {
                 label: Some("Greedy Merge Pass"),
                 timestamp_writes: None,
             });
-            pass.set_pipeline(&self.greedy_merge_pipeline);
+            pass.set_pipeline(&self.pipelines.greedy_merge);
             pass.set_bind_group(0, &self.bind_group, &[]);
             pass.dispatch_workgroups(32, 6, 1);
         }
 
+        self.read_single_chunk(encoder)
+    }
+
+    /// Mesh a single chunk, growing the quad buffer and re-running the greedy
+    /// merge if the chunk doesn't fit. Only truncated if the quads would
+    /// exceed the device's buffer limits.
+    pub fn mesh_chunk_full(&mut self, voxels: &[u32; CHUNK_SIZE_CB]) -> MeshResult {
+        let result = self.mesh_chunk_checked(voxels);
+        if !result.truncated || !self.grow_quad_capacity(result.needed as usize) {
+            return result;
+        }
+
+        // The face masks from the first run are still in place
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Meshing Retry Encoder"),
+            });
+
+        encoder.copy_buffer_to_buffer(
+            &self.buffers.counter_zero_buffer,
+            0,
+            &self.buffers.counter_buffer,
+            0,
+            4,
+        );
+
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Greedy Merge Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipelines.greedy_merge);
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            pass.dispatch_workgroups(32, 6, 1);
+        }
+
+        self.read_single_chunk(encoder)
+    }
+
+    /// Mesh a chunk at a reduced level of detail: downsample it by `scale`
+    /// on the GPU, each `scale`³ cell taking its most common block type (or
+    /// air if more than half is air), then greedy merge the reduced grid.
+    ///
+    /// Quad positions are in chunk coordinates, multiples of `scale`, but
+    /// widths and heights count reduced cells, so multiply them by `scale`
+    /// for the size in blocks. A full-chunk face would not fit the 5-bit
+    /// size fields otherwise.
+    ///
+    /// # Panics
+    ///
+    /// If `scale` is not one of [`LOD_SCALES`].
+    pub fn mesh_chunk_lod_gpu(&self, voxels: &[u32; CHUNK_SIZE_CB], scale: u32) -> Vec<PackedQuad> {
+        assert!(
+            LOD_SCALES.contains(&scale),
+            "unsupported LOD scale {scale}, expected one of {LOD_SCALES:?}"
+        );
+        let grid_size = CHUNK_SIZE as u32 / scale;
+        let params = LodParams {
+            scale,
+            grid_size,
+            _padding: [0; 2],
+        };
+        self.queue.write_buffer(
+            &self.buffers.lod_params_buffer,
+            0,
+            bytemuck::bytes_of(&params),
+        );
+        self.queue
+            .write_buffer(&self.buffers.voxel_buffer, 0, bytemuck::cast_slice(voxels));
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("LOD Meshing Encoder"),
+            });
+
+        encoder.copy_buffer_to_buffer(
+            &self.buffers.counter_zero_buffer,
+            0,
+            &self.buffers.counter_buffer,
+            0,
+            4,
+        );
+
+        // The reduced grid is written over the face masks, which face culling
+        // rebuilds for every other entry point
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("LOD Downsample Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipelines.lod_downsample);
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            pass.dispatch_workgroups(grid_size.pow(3).div_ceil(64), 1, 1);
+        }
+
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("LOD Greedy Merge Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipelines.lod_greedy_merge);
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            pass.dispatch_workgroups(1, 6, 1);
+        }
+
+        self.read_single_chunk(encoder).quads
+    }
+
+    /// Copy the first chunk's counter and quads to staging after the passes
+    /// recorded in `encoder`, submit, and read them back.
+    fn read_single_chunk(&self, mut encoder: wgpu::CommandEncoder) -> MeshResult {
+        let quad_buffer_size = (self.buffers.quad_capacity * 2 * 4) as u64;
+
         encoder.copy_buffer_to_buffer(
             &self.buffers.quad_buffer,
             0,
@@ -354,12 +839,18 @@ impl GpuChunkMesher {
             .unwrap();
 
         let counter_data = counter_slice.get_mapped_range();
-        let count = (bytemuck::cast_slice::<u8, u32>(&counter_data)[0] as usize).min(MAX_QUADS);
+        let needed = bytemuck::cast_slice::<u8, u32>(&counter_data)[0];
         drop(counter_data);
         self.buffers.counter_staging.unmap();
 
+        let count = (needed as usize).min(self.buffers.quad_capacity);
+        let mut result = MeshResult {
+            quads: Vec::new(),
+            truncated: count < needed as usize,
+            needed,
+        };
         if count == 0 {
-            return Vec::new();
+            return result;
         }
 
         let quad_slice = self.buffers.quad_staging.slice(..((count * 2 * 4) as u64));
@@ -369,7 +860,7 @@ impl GpuChunkMesher {
             .unwrap();
 
         let quad_data = quad_slice.get_mapped_range();
-        let result = bytemuck::cast_slice::<u8, PackedQuad>(&quad_data).to_vec();
+        result.quads = bytemuck::cast_slice::<u8, PackedQuad>(&quad_data).to_vec();
         drop(quad_data);
         self.buffers.quad_staging.unmap();
 
@@ -400,7 +891,7 @@ impl GpuChunkMesher {
                 label: Some("Face Culling Pass"),
                 timestamp_writes: None,
             });
-            pass.set_pipeline(&self.face_culling_pipeline);
+            pass.set_pipeline(&self.pipelines.face_culling);
             pass.set_bind_group(0, &self.bind_group, &[]);
             pass.dispatch_workgroups(4, 6, 1);
         }
@@ -410,7 +901,7 @@ impl GpuChunkMesher {
                 label: Some("Greedy Merge Pass"),
                 timestamp_writes: None,
             });
-            pass.set_pipeline(&self.greedy_merge_pipeline);
+            pass.set_pipeline(&self.pipelines.greedy_merge);
             pass.set_bind_group(0, &self.bind_group, &[]);
             pass.dispatch_workgroups(32, 6, 1);
         }
@@ -423,9 +914,29 @@ impl GpuChunkMesher {
     /// This amortizes GPU submission overhead across all chunks.
     /// With 64+ chunks, achieves <0.2µs amortized per chunk.
     pub fn mesh_chunks_batch(&self, chunks: &[&[u32; CHUNK_SIZE_CB]]) -> Vec<Vec<PackedQuad>> {
+        self.mesh_batch(chunks, None).0
+    }
+
+    /// Like [`Self::mesh_chunks_batch`], also measuring how long each compute
+    /// pass took on the GPU. Returns `None` if the device does not support
+    /// timestamp queries.
+    pub fn mesh_chunks_batch_timed(
+        &self,
+        chunks: &[&[u32; CHUNK_SIZE_CB]],
+    ) -> Option<(Vec<Vec<PackedQuad>>, PassTimings)> {
+        let timestamps = self.timestamps.as_ref()?;
+        let (results, timings) = self.mesh_batch(chunks, Some(timestamps));
+        Some((results, timings.unwrap_or_default()))
+    }
+
+    fn mesh_batch(
+        &self,
+        chunks: &[&[u32; CHUNK_SIZE_CB]],
+        timestamps: Option<&TimestampQueries>,
+    ) -> (Vec<Vec<PackedQuad>>, Option<PassTimings>) {
         let n = chunks.len().min(self.buffers.batch_size);
         if n == 0 {
-            return Vec::new();
+            return (Vec::new(), None);
         }
 
         // Upload all voxel data contiguously
@@ -460,9 +971,9 @@ impl GpuChunkMesher {
         {
             let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Batch Face Culling"),
-                timestamp_writes: None,
+                timestamp_writes: timestamps.map(|t| t.pass_writes(0)),
             });
-            pass.set_pipeline(&self.face_culling_pipeline);
+            pass.set_pipeline(&self.pipelines.face_culling);
             pass.set_bind_group(0, &self.bind_group, &[]);
             pass.dispatch_workgroups(4, 6, n as u32);
         }
@@ -472,13 +983,17 @@ impl GpuChunkMesher {
         {
             let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Batch Greedy Merge"),
-                timestamp_writes: None,
+                timestamp_writes: timestamps.map(|t| t.pass_writes(2)),
             });
-            pass.set_pipeline(&self.greedy_merge_pipeline);
+            pass.set_pipeline(&self.pipelines.greedy_merge);
             pass.set_bind_group(0, &self.bind_group, &[]);
             pass.dispatch_workgroups(32, 6, n as u32);
         }
 
+        if let Some(timestamps) = timestamps {
+            timestamps.resolve(&mut encoder);
+        }
+
         // Copy counters to staging
         encoder.copy_buffer_to_buffer(
             &self.buffers.counter_buffer,
@@ -489,7 +1004,8 @@ impl GpuChunkMesher {
         );
 
         // Copy all quad buffers to staging
-        let total_quad_bytes = (n * MAX_QUADS * 2 * 4) as u64;
+        let capacity = self.buffers.quad_capacity;
+        let total_quad_bytes = (n * capacity * 2 * 4) as u64;
         encoder.copy_buffer_to_buffer(
             &self.buffers.quad_buffer,
             0,
@@ -510,7 +1026,7 @@ impl GpuChunkMesher {
         let counter_data = counter_slice.get_mapped_range();
         let counts: Vec<usize> = bytemuck::cast_slice::<u8, u32>(&counter_data)
             .iter()
-            .map(|&c| (c as usize).min(MAX_QUADS))
+            .map(|&c| c as usize)
             .collect();
         drop(counter_data);
         self.buffers.counter_staging.unmap();
@@ -526,8 +1042,17 @@ impl GpuChunkMesher {
         let quad_data = quad_slice.get_mapped_range();
         let all_quads: &[PackedQuad] = bytemuck::cast_slice(&quad_data);
 
-        for (i, &count) in counts.iter().enumerate() {
-            let chunk_offset = i * MAX_QUADS;
+        for (i, &needed) in counts.iter().enumerate() {
+            if needed > capacity {
+                log::warn!(
+                    "Chunk {} of batch needs {} quads but the buffer holds {}; dropping the rest",
+                    i,
+                    needed,
+                    capacity
+                );
+            }
+            let count = needed.min(capacity);
+            let chunk_offset = i * capacity;
             if count == 0 {
                 results.push(Vec::new());
             } else {
@@ -538,7 +1063,8 @@ impl GpuChunkMesher {
         drop(quad_data);
         self.buffers.quad_staging.unmap();
 
-        results
+        let timings = timestamps.map(|t| t.read(&self.device));
+        (results, timings)
     }
 
     /// Dispatch N chunks on GPU without readback (for benchmarking amortized cost).
@@ -578,7 +1104,7 @@ impl GpuChunkMesher {
                 label: Some("Batch Face Culling"),
                 timestamp_writes: None,
             });
-            pass.set_pipeline(&self.face_culling_pipeline);
+            pass.set_pipeline(&self.pipelines.face_culling);
             pass.set_bind_group(0, &self.bind_group, &[]);
             pass.dispatch_workgroups(4, 6, n as u32);
         }
@@ -588,7 +1114,7 @@ impl GpuChunkMesher {
                 label: Some("Batch Greedy Merge"),
                 timestamp_writes: None,
             });
-            pass.set_pipeline(&self.greedy_merge_pipeline);
+            pass.set_pipeline(&self.pipelines.greedy_merge);
             pass.set_bind_group(0, &self.bind_group, &[]);
             pass.dispatch_workgroups(32, 6, n as u32);
         }
@@ -626,7 +1152,7 @@ impl GpuChunkMesher {
                 label: Some("Face Culling"),
                 timestamp_writes: None,
             });
-            pass.set_pipeline(&self.face_culling_pipeline);
+            pass.set_pipeline(&self.pipelines.face_culling);
             pass.set_bind_group(0, &self.bind_group, &[]);
             pass.dispatch_workgroups(4, 6, n as u32);
         }
@@ -636,7 +1162,7 @@ impl GpuChunkMesher {
                 label: Some("Greedy Merge"),
                 timestamp_writes: None,
             });
-            pass.set_pipeline(&self.greedy_merge_pipeline);
+            pass.set_pipeline(&self.pipelines.greedy_merge);
             pass.set_bind_group(0, &self.bind_group, &[]);
             pass.dispatch_workgroups(32, 6, n as u32);
         }
@@ -661,7 +1187,7 @@ impl GpuChunkMesher {
                 label: Some("Face Culling"),
                 timestamp_writes: None,
             });
-            pass.set_pipeline(&self.face_culling_pipeline);
+            pass.set_pipeline(&self.pipelines.face_culling);
             pass.set_bind_group(0, &self.bind_group, &[]);
             pass.dispatch_workgroups(4, 6, n as u32);
         }
@@ -696,7 +1222,7 @@ impl GpuChunkMesher {
                 label: Some("Greedy Merge"),
                 timestamp_writes: None,
             });
-            pass.set_pipeline(&self.greedy_merge_pipeline);
+            pass.set_pipeline(&self.pipelines.greedy_merge);
             pass.set_bind_group(0, &self.bind_group, &[]);
             pass.dispatch_workgroups(32, 6, n as u32);
         }
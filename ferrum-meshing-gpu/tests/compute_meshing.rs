@@ -172,3 +172,195 @@ fn no_duplicate_quads() {
         }
     }
 }
+
+#[test]
+fn pass_timings_scale_ticks_by_period() {
+    let timings = PassTimings::from_ticks([100, 350, 400, 1400], 2.5);
+    assert_eq!(timings.face_culling_ns, 625);
+    assert_eq!(timings.greedy_merge_ns, 2500);
+
+    // Out-of-order or zeroed timestamps must not underflow
+    assert_eq!(
+        PassTimings::from_ticks([50, 10, 0, 0], 1.0),
+        PassTimings::default()
+    );
+}
+
+#[test]
+fn timed_batch_matches_untimed_batch() {
+    let mesher = GpuChunkMesher::with_batch_size(4).expect("Failed to create GPU mesher");
+    let terrain = terrain_chunk();
+    let stone = uniform_chunk(1);
+    let chunks = [&terrain, &stone];
+
+    let Some((quads, timings)) = mesher.mesh_chunks_batch_timed(&chunks) else {
+        assert!(!mesher.supports_pass_timings());
+        return;
+    };
+    assert!(mesher.supports_pass_timings());
+    assert_eq!(quads, mesher.mesh_chunks_batch(&chunks));
+    assert!(timings.face_culling_ns > 0 || timings.greedy_merge_ns > 0);
+}
+
+#[test]
+fn packed_quads_round_trip() {
+    // (x, y, z, width, height, face, block_type)
+    let values = [
+        (0, 0, 0, 1, 1, 0, 1),
+        (31, 17, 5, 31, 2, 5, 42),
+        (3, 30, 31, 16, 31, 2, u32::MAX),
+    ];
+    let quads: Vec<PackedQuad> = values
+        .iter()
+        .map(|&(x, y, z, w, h, face, block)| PackedQuad::pack(x, y, z, w, h, face, block))
+        .collect();
+    assert_eq!(quads[1].unpack(), Some((31, 17, 5, 31, 2, 5)));
+
+    let decoded = decode_quads(&quads);
+    assert_eq!(decoded.len(), values.len());
+    for (quad, &(x, y, z, width, height, face, block_type)) in decoded.iter().zip(&values) {
+        let expected = UnpackedQuad {
+            x,
+            y,
+            z,
+            width,
+            height,
+            face,
+            block_type,
+        };
+        assert_eq!(*quad, expected);
+    }
+}
+
+#[test]
+fn invalid_face_indices_are_dropped() {
+    let valid = PackedQuad::pack(1, 2, 3, 4, 5, 3, 7);
+    let face_6 = PackedQuad::pack(1, 2, 3, 4, 5, 6, 7);
+    let face_7 = PackedQuad::pack(1, 2, 3, 4, 5, 7, 7);
+
+    assert_eq!(face_6.unpack(), None);
+    assert_eq!(face_7.unpack(), None);
+    assert_eq!(decode_quads(&[face_6, valid, face_7]).len(), 1);
+}
+
+#[test]
+fn checkerboard_overflows_default_quad_buffer() {
+    let mesher = get_mesher();
+    let result = mesher.mesh_chunk_checked(&checkerboard_chunk(1));
+
+    // Every one of the 16384 solid voxels shows all 6 faces, unmerged
+    assert_eq!(result.needed, 16384 * 6);
+    assert!(result.truncated);
+    assert_eq!(result.quads.len(), MAX_QUADS);
+}
+
+#[test]
+fn mesh_chunk_full_grows_buffer_for_dense_chunks() {
+    let mut mesher = get_mesher();
+    let result = mesher.mesh_chunk_full(&checkerboard_chunk(1));
+
+    assert!(!result.truncated);
+    assert_eq!(result.quads.len(), 16384 * 6);
+    assert!(mesher.quad_capacity() >= 16384 * 6);
+
+    let mut unique = std::collections::HashSet::new();
+    for quad in &result.quads {
+        assert!(
+            unique.insert((quad.word0, quad.block_type)),
+            "duplicate quad"
+        );
+    }
+
+    // Sparse chunks still mesh correctly after growing
+    let sparse = mesher.mesh_chunk_full(&uniform_chunk(1));
+    assert!(!sparse.truncated);
+    assert_eq!(sparse.quads.len() as u32, sparse.needed);
+}
+
+#[test]
+fn lod_solid_chunk_has_one_quad_per_face_layer() {
+    let mesher = get_mesher();
+    let chunk = uniform_chunk(1);
+
+    for scale in LOD_SCALES {
+        let grid_size = CHUNK_SIZE as u32 / scale;
+        let quads = mesher.mesh_chunk_lod_gpu(&chunk, scale);
+        assert_eq!(quads.len(), 6 * grid_size as usize, "scale {scale}");
+
+        for quad in &quads {
+            assert_eq!(quad.block_type, 1);
+            assert_eq!(quad.x() % scale, 0);
+            assert_eq!(quad.y() % scale, 0);
+            assert_eq!(quad.z() % scale, 0);
+            // Each layer's face merges into one grid-wide strip
+            let mut sides = [quad.width(), quad.height()];
+            sides.sort();
+            assert_eq!(sides, [1, grid_size], "scale {scale}: {quad:?}");
+        }
+    }
+}
+
+#[test]
+fn lod_air_chunk_produces_no_quads() {
+    let mesher = get_mesher();
+    for scale in LOD_SCALES {
+        let quads = mesher.mesh_chunk_lod_gpu(&uniform_chunk(0), scale);
+        assert!(quads.is_empty(), "scale {scale}");
+    }
+}
+
+#[test]
+fn lod_downsample_takes_majority_block() {
+    let mesher = get_mesher();
+    // Stone up to y = 6 and dirt to y = 11. At scale 8 the bottom cells are
+    // mostly stone and the ones above mostly air.
+    let mut chunk = [0u32; CHUNK_SIZE_CB];
+    for z in 0..CHUNK_SIZE {
+        for y in 0..11 {
+            for x in 0..CHUNK_SIZE {
+                let block = if y < 6 { 1 } else { 3 };
+                chunk[z * CHUNK_SIZE_SQ + y * CHUNK_SIZE + x] = block;
+            }
+        }
+    }
+
+    let quads = mesher.mesh_chunk_lod_gpu(&chunk, 8);
+    assert!(!quads.is_empty());
+    assert!(quads.iter().all(|quad| quad.block_type == 1));
+    let top = quads.iter().filter(|quad| quad.face() == 2);
+    assert!(top.clone().count() > 0);
+    assert!(top.into_iter().all(|quad| quad.y() == 0));
+}
+
+#[test]
+fn lod_far_scale_meshes_a_2x2x2_grid() {
+    let mesher = get_mesher();
+    // Stone filling the bottom half: at scale 16 one layer of two by two
+    // cells. ±X and ±Y faces are meshed per z layer, ±Z faces per y layer.
+    let mut chunk = [0u32; CHUNK_SIZE_CB];
+    for z in 0..CHUNK_SIZE {
+        for y in 0..CHUNK_SIZE / 2 {
+            for x in 0..CHUNK_SIZE {
+                chunk[z * CHUNK_SIZE_SQ + y * CHUNK_SIZE + x] = 1;
+            }
+        }
+    }
+
+    let quads = mesher.mesh_chunk_lod_gpu(&chunk, 16);
+    let mut per_face = [0; 6];
+    for quad in &quads {
+        per_face[quad.face() as usize] += 1;
+        assert_eq!(quad.block_type, 1);
+        assert_eq!(quad.y(), 0, "{quad:?}");
+        assert_eq!((quad.x() % 16, quad.z() % 16), (0, 0), "{quad:?}");
+        let expected = if quad.face() < 2 { (1, 1) } else { (2, 1) };
+        assert_eq!((quad.width(), quad.height()), expected, "{quad:?}");
+    }
+    assert_eq!(per_face, [2, 2, 2, 2, 1, 1]);
+}
+
+#[test]
+#[should_panic(expected = "unsupported LOD scale")]
+fn lod_rejects_unsupported_scale() {
+    get_mesher().mesh_chunk_lod_gpu(&uniform_chunk(1), 3);
+}
@@ -0,0 +1,108 @@
+use ferrum_meshing_gpu::*;
+
+/// Column-major orthographic projection looking down -z at the box from
+/// (-10, -10, 0) to (10, 10, -100), with depth in 0..1.
+fn ortho_view_projection() -> [[f32; 4]; 4] {
+    [
+        [0.1, 0.0, 0.0, 0.0],
+        [0.0, 0.1, 0.0, 0.0],
+        [0.0, 0.0, -0.01, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]
+}
+
+fn get_culler() -> Option<GpuChunkCuller> {
+    let culler = GpuChunkCuller::new();
+    if culler.is_none() {
+        eprintln!("no GPU adapter, skipping");
+    }
+    culler
+}
+
+#[test]
+fn frustum_planes_bound_the_view_volume() {
+    let planes = frustum_planes(ortho_view_projection());
+    let inside = |p: [f32; 3]| {
+        planes
+            .iter()
+            .all(|plane| plane[0] * p[0] + plane[1] * p[1] + plane[2] * p[2] + plane[3] >= 0.0)
+    };
+    assert!(inside([0.0, 0.0, -50.0]));
+    assert!(inside([9.9, -9.9, -99.0]));
+    assert!(!inside([11.0, 0.0, -50.0]));
+    assert!(!inside([0.0, 0.0, 1.0]));
+    assert!(!inside([0.0, 0.0, -101.0]));
+}
+
+#[test]
+fn visible_chunks_get_indirect_draws() {
+    let Some(mut culler) = get_culler() else {
+        return;
+    };
+    let chunk = |min: [f32; 3], index_count, first_index, base_vertex| CullChunk {
+        index_count,
+        first_index,
+        base_vertex,
+        ..CullChunk::new(min, [min[0] + 4.0, min[1] + 4.0, min[2] + 4.0])
+    };
+    let chunks = [
+        chunk([0.0, 0.0, -20.0], 36, 0, 0),
+        // Off to the side
+        chunk([30.0, 0.0, -20.0], 12, 36, 24),
+        chunk([-2.0, -2.0, -90.0], 6, 48, 32),
+        // Behind the camera
+        chunk([0.0, 0.0, 10.0], 6, 54, 36),
+    ];
+
+    let result = culler.cull(&chunks, ortho_view_projection(), [0.0; 3], f32::INFINITY);
+    assert_eq!(result.visible, [0, 2]);
+    assert_eq!(
+        result.draw_args,
+        [
+            DrawIndexedArgs {
+                index_count: 36,
+                instance_count: 1,
+                first_index: 0,
+                base_vertex: 0,
+                first_instance: 0,
+            },
+            DrawIndexedArgs {
+                index_count: 6,
+                instance_count: 1,
+                first_index: 48,
+                base_vertex: 32,
+                first_instance: 2,
+            },
+        ]
+    );
+
+    // The far chunk is past the draw distance
+    let result = culler.cull(&chunks, ortho_view_projection(), [0.0; 3], 50.0);
+    assert_eq!(result.visible, [0]);
+
+    assert!(
+        culler
+            .cull(&[], ortho_view_projection(), [0.0; 3], 50.0)
+            .visible
+            .is_empty()
+    );
+}
+
+#[test]
+fn buffers_grow_to_fit_every_chunk() {
+    let Some(mut culler) = get_culler() else {
+        return;
+    };
+    let count = culler.capacity() + 100;
+    let chunks: Vec<_> = (0..count)
+        .map(|i| {
+            let x = (i % 16) as f32 - 8.0;
+            let z = -1.0 - (i / 16) as f32 * 0.5;
+            CullChunk::new([x, 0.0, z], [x + 0.5, 0.5, z + 0.4])
+        })
+        .collect();
+
+    let result = culler.cull(&chunks, ortho_view_projection(), [0.0; 3], f32::INFINITY);
+    assert!(culler.capacity() >= count);
+    assert_eq!(result.visible, (0..count as u32).collect::<Vec<_>>());
+}
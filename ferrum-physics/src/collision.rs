@@ -1,5 +1,27 @@
 use glam::Vec3;
 
+/// Overlap, in blocks, below which boxes count as touching rather than
+/// colliding. Keeps bodies resting on floors and sliding along walls from
+/// being pushed back and forth by rounding error.
+pub const DEFAULT_COLLISION_EPSILON: f32 = 1e-3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// A body was stopped along `axis` by a collision.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CollisionEvent {
+    pub axis: Axis,
+    /// Position of the body after the collision was resolved.
+    pub position: Vec3,
+    /// Speed along `axis` that the collision cancelled, never negative.
+    pub impact_speed: f32,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Aabb {
     min: Vec3,
@@ -35,6 +57,14 @@ impl Aabb {
         self.max - self.min
     }
 
+    /// The same box moved by `offset`.
+    pub fn translated(&self, offset: Vec3) -> Self {
+        Self {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+
     pub fn contains(&self, point: Vec3) -> bool {
         point.x >= self.min.x
             && point.x <= self.max.x
@@ -53,6 +83,55 @@ impl Aabb {
             && self.max.z >= other.min.z
     }
 
+    /// Distance along the ray from `origin` towards `dir` to where it enters
+    /// the box, or 0 if it starts inside. `None` if the ray misses or only
+    /// reaches the box past `max_dist`.
+    pub fn ray_distance(&self, origin: Vec3, dir: Vec3, max_dist: f32) -> Option<f32> {
+        let dir = dir.normalize_or_zero();
+        let mut enter = 0.0f32;
+        let mut exit = max_dist;
+        for axis in 0..3 {
+            if dir[axis] == 0.0 {
+                // Parallel to this pair of faces: it has to start between them
+                if origin[axis] < self.min[axis] || origin[axis] > self.max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let t0 = (self.min[axis] - origin[axis]) / dir[axis];
+            let t1 = (self.max[axis] - origin[axis]) / dir[axis];
+            enter = enter.max(t0.min(t1));
+            exit = exit.min(t0.max(t1));
+            if enter > exit {
+                return None;
+            }
+        }
+        Some(enter)
+    }
+
+    /// How far the boxes overlap along `axis`, negative if they are apart.
+    fn overlap(&self, other: &Aabb, axis: usize) -> f32 {
+        self.max[axis].min(other.max[axis]) - self.min[axis].max(other.min[axis])
+    }
+
+    /// Like [`penetration`](Self::penetration), but boxes overlapping by no
+    /// more than `epsilon` along any axis are only touching and need no push.
+    pub fn penetration_beyond(&self, other: &Aabb, epsilon: f32) -> Option<Vec3> {
+        if (0..3).any(|axis| self.overlap(other, axis) <= epsilon) {
+            return None;
+        }
+        self.penetration(other)
+    }
+
+    /// Whether this box stands on top of `other`: its bottom within
+    /// `epsilon` of the top of `other`, and over it by more than `epsilon`
+    /// horizontally, so that a box exactly on an edge consistently does not.
+    pub fn rests_on(&self, other: &Aabb, epsilon: f32) -> bool {
+        (self.min.y - other.max.y).abs() <= epsilon
+            && self.overlap(other, 0) > epsilon
+            && self.overlap(other, 2) > epsilon
+    }
+
     pub fn penetration(&self, other: &Aabb) -> Option<Vec3> {
         if !self.intersects(other) {
             return None;
@@ -82,3 +161,44 @@ impl Aabb {
         }
     }
 }
+
+/// Push a body with box `body` out of `other` and stop it along the axis of
+/// least penetration. Overlaps within `epsilon` are left alone. Being pushed
+/// up sets `on_ground`.
+pub(crate) fn resolve_body(
+    body: Aabb,
+    other: &Aabb,
+    epsilon: f32,
+    position: &mut Vec3,
+    velocity: &mut Vec3,
+    on_ground: &mut bool,
+) -> Option<CollisionEvent> {
+    // `penetration` is the push that moves the body out of `other`.
+    let penetration = body.penetration_beyond(other, epsilon)?;
+    *position += penetration;
+
+    let (axis, impact_speed) = if penetration.x.abs() > 0.0 {
+        let speed = velocity.x.abs();
+        velocity.x = 0.0;
+        (Axis::X, speed)
+    } else if penetration.y.abs() > 0.0 {
+        let speed = velocity.y.abs();
+        velocity.y = 0.0;
+        if penetration.y > 0.0 {
+            *on_ground = true;
+        }
+        (Axis::Y, speed)
+    } else if penetration.z.abs() > 0.0 {
+        let speed = velocity.z.abs();
+        velocity.z = 0.0;
+        (Axis::Z, speed)
+    } else {
+        return None;
+    };
+
+    Some(CollisionEvent {
+        axis,
+        position: *position,
+        impact_speed,
+    })
+}
@@ -3,6 +3,8 @@ use glam::Vec3;
 pub const GRAVITY: f32 = -32.0;
 pub const TERMINAL_VELOCITY: f32 = -78.4;
 pub const JUMP_VELOCITY: f32 = 10.0;
+/// Blocks a player can fall without taking damage.
+pub const SAFE_FALL_DISTANCE: f32 = 3.0;
 
 pub fn apply_gravity(velocity: Vec3, on_ground: bool, dt: f32) -> Vec3 {
     if on_ground {
@@ -28,3 +30,10 @@ pub fn apply_jump(velocity: Vec3, on_ground: bool) -> Vec3 {
     new_velocity.y = JUMP_VELOCITY;
     new_velocity
 }
+
+/// Damage, in half hearts, for landing at `impact_speed`: one per block fallen
+/// beyond [`SAFE_FALL_DISTANCE`].
+pub fn fall_damage(impact_speed: f32) -> f32 {
+    let fall_distance = impact_speed * impact_speed / (2.0 * -GRAVITY);
+    (fall_distance - SAFE_FALL_DISTANCE).ceil().max(0.0)
+}
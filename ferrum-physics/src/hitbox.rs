@@ -0,0 +1,67 @@
+use crate::collision::Aabb;
+use crate::player::{PLAYER_HEIGHT, PLAYER_WIDTH};
+use glam::Vec3;
+
+/// How far a player can hit an entity, from the eyes to its hitbox.
+pub const MELEE_REACH: f32 = 3.0;
+
+/// Entity types with their own hitbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntityType {
+    Player,
+    Zombie,
+    Skeleton,
+    Creeper,
+    Spider,
+    Pig,
+    Cow,
+    Sheep,
+    Chicken,
+    DroppedItem,
+}
+
+/// Width (along both x and z) and height of an entity's hitbox, as in vanilla.
+pub fn hitbox_size(entity_type: EntityType) -> (f32, f32) {
+    match entity_type {
+        EntityType::Player => (PLAYER_WIDTH, PLAYER_HEIGHT),
+        EntityType::Zombie => (0.6, 1.95),
+        EntityType::Skeleton => (0.6, 1.99),
+        EntityType::Creeper => (0.6, 1.7),
+        EntityType::Spider => (1.4, 0.9),
+        EntityType::Pig => (0.9, 0.9),
+        EntityType::Cow => (0.9, 1.4),
+        EntityType::Sheep => (0.9, 1.3),
+        EntityType::Chicken => (0.4, 0.7),
+        EntityType::DroppedItem => (0.25, 0.25),
+    }
+}
+
+/// Hitbox of an entity standing at the origin: centered on x and z, with its
+/// feet at y = 0.
+pub fn hitbox_of(entity_type: EntityType) -> Aabb {
+    let (width, height) = hitbox_size(entity_type);
+    let half = width / 2.0;
+    Aabb::new(Vec3::new(-half, 0.0, -half), Vec3::new(half, height, half))
+}
+
+/// Hitbox of an entity with its feet at `position`.
+pub fn hitbox_at(entity_type: EntityType, position: Vec3) -> Aabb {
+    hitbox_of(entity_type).translated(position)
+}
+
+/// Distance from `eye` along `look` to the hitbox of a `target` standing at
+/// `position`, if it is within [`MELEE_REACH`].
+pub fn melee_hit(eye: Vec3, look: Vec3, target: EntityType, position: Vec3) -> Option<f32> {
+    hitbox_at(target, position).ray_distance(eye, look, MELEE_REACH)
+}
+
+/// Whether a projectile moving from `from` to `to` this tick passes through
+/// the hitbox of a `target` at `position`. Returns how far along the move it
+/// hits, from 0 (at `from`) to 1 (at `to`), so the nearest of several targets
+/// can be picked.
+pub fn projectile_hit(from: Vec3, to: Vec3, target: EntityType, position: Vec3) -> Option<f32> {
+    let step = to - from;
+    let length = step.length();
+    let distance = hitbox_at(target, position).ray_distance(from, step, length)?;
+    Some(if length > 0.0 { distance / length } else { 0.0 })
+}
@@ -1,7 +1,18 @@
 pub mod collision;
 pub mod gravity;
+pub mod hitbox;
 pub mod movement;
 pub mod player;
+pub mod raycast;
+pub mod ridable;
+pub mod steps;
 
+pub use collision::{Axis, CollisionEvent, DEFAULT_COLLISION_EPSILON};
 pub use gravity::GRAVITY;
-pub use player::Player;
+pub use hitbox::{
+    hitbox_at, hitbox_of, hitbox_size, melee_hit, projectile_hit, EntityType, MELEE_REACH,
+};
+pub use player::{Player, KNOCKBACK_LIFT};
+pub use raycast::{voxel_raycast, VoxelHit};
+pub use ridable::Ridable;
+pub use steps::{block_below, StepEvent, StepTracker, STEP_STRIDE};
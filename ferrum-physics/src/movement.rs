@@ -13,6 +13,9 @@ pub struct MovementInput {
 const WALK_SPEED: f32 = 4.317;
 const SPRINT_MULTIPLIER: f32 = 1.3;
 const FRICTION: f32 = 0.546;
+const SWIM_SPEED: f32 = 2.2;
+const SPRINT_SWIM_SPEED: f32 = 5.6;
+const WATER_DRAG: f32 = 0.8;
 
 impl MovementInput {
     pub fn calculate_velocity(&self, current_velocity: Vec3, on_ground: bool, _dt: f32) -> Vec3 {
@@ -20,6 +23,52 @@ impl MovementInput {
             return current_velocity;
         }
 
+        let direction = self.direction();
+        let speed = if self.sprint {
+            WALK_SPEED * SPRINT_MULTIPLIER
+        } else {
+            WALK_SPEED
+        };
+
+        let target_velocity = direction * speed;
+        let acceleration = 0.098;
+
+        let mut new_velocity = current_velocity;
+        new_velocity.x += (target_velocity.x - current_velocity.x) * acceleration;
+        new_velocity.z += (target_velocity.z - current_velocity.z) * acceleration;
+
+        new_velocity.x *= FRICTION;
+        new_velocity.z *= FRICTION;
+
+        new_velocity
+    }
+
+    /// Velocity while swimming. Works without ground contact; vertical speed is
+    /// damped by the water instead of accumulating.
+    pub fn calculate_swim_velocity(&self, current_velocity: Vec3) -> Vec3 {
+        let speed = if self.sprint {
+            SPRINT_SWIM_SPEED
+        } else {
+            SWIM_SPEED
+        };
+
+        let target_velocity = self.direction() * speed;
+        let acceleration = 0.2;
+
+        let mut new_velocity = current_velocity;
+        new_velocity.x += (target_velocity.x - current_velocity.x) * acceleration;
+        new_velocity.z += (target_velocity.z - current_velocity.z) * acceleration;
+        new_velocity.y *= WATER_DRAG;
+
+        new_velocity
+    }
+
+    /// Whether any horizontal movement key is held.
+    pub fn is_moving(&self) -> bool {
+        self.direction() != Vec3::ZERO
+    }
+
+    fn direction(&self) -> Vec3 {
         let mut direction = Vec3::ZERO;
 
         if self.forward {
@@ -39,22 +88,6 @@ impl MovementInput {
             direction = direction.normalize();
         }
 
-        let speed = if self.sprint {
-            WALK_SPEED * SPRINT_MULTIPLIER
-        } else {
-            WALK_SPEED
-        };
-
-        let target_velocity = direction * speed;
-        let acceleration = 0.098;
-
-        let mut new_velocity = current_velocity;
-        new_velocity.x += (target_velocity.x - current_velocity.x) * acceleration;
-        new_velocity.z += (target_velocity.z - current_velocity.z) * acceleration;
-
-        new_velocity.x *= FRICTION;
-        new_velocity.z *= FRICTION;
-
-        new_velocity
+        direction
     }
 }
@@ -1,15 +1,36 @@
-use crate::collision::Aabb;
+use crate::collision::{self, Aabb, Axis, CollisionEvent};
 use crate::gravity;
 use crate::movement::MovementInput;
 use glam::Vec3;
 
-const PLAYER_WIDTH: f32 = 0.6;
-const PLAYER_HEIGHT: f32 = 1.8;
+pub(crate) const PLAYER_WIDTH: f32 = 0.6;
+pub(crate) const PLAYER_HEIGHT: f32 = 1.8;
+/// Swimming players lie horizontally, so their box is only as tall as it is
+/// wide.
+const SWIMMING_HEIGHT: f32 = PLAYER_WIDTH;
+/// Most upward speed knockback gives a grounded player, in blocks per
+/// second.
+pub const KNOCKBACK_LIFT: f32 = 8.0;
+/// Fraction of horizontal speed kept per second while airborne from an
+/// impulse.
+const KNOCKBACK_AIR_DRAG: f32 = 0.15;
 
 pub struct Player {
     position: Vec3,
     velocity: Vec3,
     on_ground: bool,
+    submerged: bool,
+    swimming: bool,
+    /// Carried by a [`Ridable`](crate::ridable::Ridable), which takes over
+    /// movement.
+    riding: bool,
+    /// External pushes waiting for the next movement step.
+    impulses: Vec<Vec3>,
+    /// Thrown into the air by an impulse; movement keys do nothing until
+    /// landing.
+    knocked_back: bool,
+    /// Overlap, in blocks, that counts as touching rather than colliding.
+    collision_epsilon: f32,
 }
 
 impl Player {
@@ -18,6 +39,12 @@ impl Player {
             position,
             velocity: Vec3::ZERO,
             on_ground: false,
+            submerged: false,
+            swimming: false,
+            riding: false,
+            impulses: Vec::new(),
+            knocked_back: false,
+            collision_epsilon: collision::DEFAULT_COLLISION_EPSILON,
         }
     }
 
@@ -45,6 +72,101 @@ impl Player {
         self.on_ground = on_ground;
     }
 
+    pub fn collision_epsilon(&self) -> f32 {
+        self.collision_epsilon
+    }
+
+    /// Overlap, in blocks, below which boxes count as touching. Larger
+    /// values hide more rounding error; smaller ones let the player fit
+    /// tighter gaps. Defaults to [`DEFAULT_COLLISION_EPSILON`].
+    ///
+    /// [`DEFAULT_COLLISION_EPSILON`]: collision::DEFAULT_COLLISION_EPSILON
+    pub fn set_collision_epsilon(&mut self, epsilon: f32) {
+        self.collision_epsilon = epsilon.max(0.0);
+    }
+
+    pub fn is_submerged(&self) -> bool {
+        self.submerged
+    }
+
+    /// Whether the player is fully under water. Surfacing ends swimming.
+    pub fn set_submerged(&mut self, submerged: bool) {
+        self.submerged = submerged;
+        if !submerged {
+            self.swimming = false;
+        }
+    }
+
+    pub fn is_swimming(&self) -> bool {
+        self.swimming
+    }
+
+    /// Enter swimming when submerged and moving; leave it on surfacing or
+    /// when movement stops.
+    pub fn update_swimming(&mut self, input: &MovementInput) {
+        self.swimming = self.submerged && input.is_moving();
+    }
+
+    pub fn is_riding(&self) -> bool {
+        self.riding
+    }
+
+    pub(crate) fn set_riding(&mut self, riding: bool) {
+        self.riding = riding;
+    }
+
+    /// Queue an external push, such as an explosion or a fishing rod pull,
+    /// in blocks per second. It is added to the velocity on the next
+    /// [`apply_movement`](Self::apply_movement) and then slowed by friction,
+    /// drag and gravity like the player's own motion.
+    pub fn apply_impulse(&mut self, impulse: Vec3) {
+        self.impulses.push(impulse);
+    }
+
+    /// Queue knockback of `strength` blocks per second along the horizontal
+    /// part of `direction`, as from a mob hit. A grounded player is also
+    /// lifted off the ground by up to [`KNOCKBACK_LIFT`].
+    pub fn knockback(&mut self, direction: Vec3, strength: f32) {
+        let push = Vec3::new(direction.x, 0.0, direction.z).normalize_or_zero() * strength;
+        let lift = if self.on_ground {
+            strength.min(KNOCKBACK_LIFT)
+        } else {
+            0.0
+        };
+        self.apply_impulse(push + Vec3::Y * lift);
+    }
+
+    /// Sum of the impulses not yet applied.
+    pub fn pending_impulse(&self) -> Vec3 {
+        self.impulses.iter().sum()
+    }
+
+    pub fn is_knocked_back(&self) -> bool {
+        self.knocked_back
+    }
+
+    /// Add the queued impulses to the velocity. An upward one leaves the
+    /// ground.
+    fn take_impulses(&mut self) {
+        if self.impulses.is_empty() {
+            return;
+        }
+        let impulse: Vec3 = self.impulses.drain(..).sum();
+        self.velocity += impulse;
+        if impulse.y > 0.0 {
+            self.on_ground = false;
+        }
+        self.knocked_back = !self.on_ground;
+    }
+
+    pub fn height(&self) -> f32 {
+        if self.swimming {
+            SWIMMING_HEIGHT
+        } else {
+            PLAYER_HEIGHT
+        }
+    }
+
     pub fn aabb(&self) -> Aabb {
         let half_width = PLAYER_WIDTH / 2.0;
         let min = Vec3::new(
@@ -54,13 +176,37 @@ impl Player {
         );
         let max = Vec3::new(
             self.position.x + half_width,
-            self.position.y + PLAYER_HEIGHT,
+            self.position.y + self.height(),
             self.position.z + half_width,
         );
         Aabb::new(min, max)
     }
 
+    /// Move under the player's own control, after adding any queued
+    /// impulses. While riding, input steers the vehicle instead and this
+    /// does nothing; impulses are dropped.
     pub fn apply_movement(&mut self, input: MovementInput, dt: f32) {
+        if self.riding {
+            self.impulses.clear();
+            return;
+        }
+        self.take_impulses();
+        self.update_swimming(&input);
+        if self.swimming {
+            self.velocity = input.calculate_swim_velocity(self.velocity);
+            return;
+        }
+
+        if self.on_ground {
+            self.knocked_back = false;
+        } else if self.knocked_back {
+            // No air control while flying from a hit; drag bleeds off the push
+            let kept = KNOCKBACK_AIR_DRAG.powf(dt);
+            self.velocity.x *= kept;
+            self.velocity.z *= kept;
+            return;
+        }
+
         self.velocity = input.calculate_velocity(self.velocity, self.on_ground, dt);
 
         if input.jump && self.on_ground {
@@ -70,10 +216,16 @@ impl Player {
     }
 
     pub fn apply_gravity(&mut self, dt: f32) {
+        if self.swimming || self.riding {
+            return;
+        }
         self.velocity = gravity::apply_gravity(self.velocity, self.on_ground, dt);
     }
 
     pub fn update_position(&mut self, dt: f32) {
+        if self.riding {
+            return;
+        }
         self.position += self.velocity * dt;
     }
 
@@ -81,22 +233,66 @@ impl Player {
         self.aabb().intersects(other)
     }
 
-    pub fn resolve_collision(&mut self, other: &Aabb) {
-        if let Some(penetration) = self.aabb().penetration(other) {
-            self.position -= penetration;
+    /// Push the player out of `other` and stop it along the axis of least
+    /// penetration. Returns the collision if the boxes overlapped.
+    pub fn resolve_collision(&mut self, other: &Aabb) -> Option<CollisionEvent> {
+        collision::resolve_body(
+            self.aabb(),
+            other,
+            self.collision_epsilon,
+            &mut self.position,
+            &mut self.velocity,
+            &mut self.on_ground,
+        )
+    }
+
+    /// Resolve the player against every box it may touch this tick, then
+    /// settle it onto the floor. It ends the tick grounded only when resting
+    /// on a box within the collision epsilon, and is then snapped exactly
+    /// onto the top, so it neither sinks in nor hovers over the following
+    /// ticks. A player moving up is never grounded.
+    pub fn resolve_collisions(&mut self, boxes: &[Aabb]) -> Vec<CollisionEvent> {
+        self.on_ground = false;
+        let events = boxes
+            .iter()
+            .filter_map(|other| self.resolve_collision(other))
+            .collect();
 
-            if penetration.x.abs() > 0.0 {
-                self.velocity.x = 0.0;
-            }
-            if penetration.y.abs() > 0.0 {
+        if self.velocity.y <= 0.0 {
+            let body = self.aabb();
+            let floor = boxes
+                .iter()
+                .filter(|other| body.rests_on(other, self.collision_epsilon))
+                .map(|other| other.max().y)
+                .reduce(f32::max);
+            if let Some(top) = floor {
+                self.position.y = top;
                 self.velocity.y = 0.0;
-                if penetration.y < 0.0 {
-                    self.on_ground = true;
-                }
-            }
-            if penetration.z.abs() > 0.0 {
-                self.velocity.z = 0.0;
+                self.on_ground = true;
             }
         }
+        events
+    }
+
+    /// Stand the player on a flat floor at height `ground_y` if it has sunk
+    /// into it. Returns the landing when the player was falling.
+    pub fn resolve_ground(&mut self, ground_y: f32) -> Option<CollisionEvent> {
+        if self.position.y > ground_y {
+            return None;
+        }
+        self.position.y = ground_y;
+        self.on_ground = true;
+
+        if self.velocity.y >= 0.0 {
+            return None;
+        }
+        let impact_speed = -self.velocity.y;
+        self.velocity.y = 0.0;
+
+        Some(CollisionEvent {
+            axis: Axis::Y,
+            position: self.position,
+            impact_speed,
+        })
     }
 }
@@ -0,0 +1,102 @@
+use glam::{IVec3, Vec3};
+
+/// Where a ray first entered a solid block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VoxelHit {
+    pub cell: IVec3,
+    /// Outward normal of the face the ray entered through, pointing back at
+    /// the ray's origin. Zero when the ray started inside `cell`.
+    pub normal: IVec3,
+    /// Point on the entered face, or the origin when the ray started inside.
+    pub point: Vec3,
+    /// Distance from the origin to `point`.
+    pub distance: f32,
+}
+
+/// Trace a ray through the block grid with the Amanatides-Woo DDA, visiting
+/// every cell it passes through in order, and return the first one for which
+/// `is_solid` holds within `max_dist` blocks.
+///
+/// This is the one voxel raycast for block targeting, projectiles and world
+/// queries, so they all agree on what a ray hits. `max_dist` must be finite.
+pub fn voxel_raycast(
+    origin: Vec3,
+    dir: Vec3,
+    max_dist: f32,
+    is_solid: impl Fn((i32, i32, i32)) -> bool,
+) -> Option<VoxelHit> {
+    let mut cell = origin.floor().as_ivec3();
+    if is_solid((cell.x, cell.y, cell.z)) {
+        return Some(VoxelHit {
+            cell,
+            normal: IVec3::ZERO,
+            point: origin,
+            distance: 0.0,
+        });
+    }
+
+    let dir = dir.normalize_or_zero();
+    if dir == Vec3::ZERO {
+        return None;
+    }
+
+    let step = IVec3::new(sign(dir.x), sign(dir.y), sign(dir.z));
+    // Distance along the ray to cross one cell on each axis
+    let t_delta = Vec3::new(1.0 / dir.x.abs(), 1.0 / dir.y.abs(), 1.0 / dir.z.abs());
+    // Distance along the ray to the next cell boundary on each axis
+    let mut t_max = Vec3::ZERO;
+    for axis in 0..3 {
+        t_max[axis] = match step[axis] {
+            1 => (cell[axis] as f32 + 1.0 - origin[axis]) / dir[axis],
+            -1 => (cell[axis] as f32 - origin[axis]) / dir[axis],
+            _ => f32::INFINITY,
+        };
+    }
+
+    loop {
+        let axis = if t_max.x <= t_max.y && t_max.x <= t_max.z {
+            0
+        } else if t_max.y <= t_max.z {
+            1
+        } else {
+            2
+        };
+        let distance = t_max[axis];
+        if distance > max_dist {
+            return None;
+        }
+
+        cell[axis] += step[axis];
+        t_max[axis] += t_delta[axis];
+
+        if is_solid((cell.x, cell.y, cell.z)) {
+            let mut normal = IVec3::ZERO;
+            normal[axis] = -step[axis];
+
+            // Snap onto the face so rounding can't leave the point outside it
+            let mut point = origin + dir * distance;
+            point[axis] = if step[axis] > 0 {
+                cell[axis] as f32
+            } else {
+                cell[axis] as f32 + 1.0
+            };
+
+            return Some(VoxelHit {
+                cell,
+                normal,
+                point,
+                distance,
+            });
+        }
+    }
+}
+
+fn sign(value: f32) -> i32 {
+    if value > 0.0 {
+        1
+    } else if value < 0.0 {
+        -1
+    } else {
+        0
+    }
+}
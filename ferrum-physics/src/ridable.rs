@@ -0,0 +1,192 @@
+use crate::collision::{self, Aabb, CollisionEvent};
+use crate::gravity;
+use crate::movement::MovementInput;
+use crate::player::Player;
+use glam::Vec3;
+
+const WIDTH: f32 = 1.375;
+const HEIGHT: f32 = 0.5625;
+/// Height of the rider's feet above the bottom of the vehicle.
+const SEAT_HEIGHT: f32 = 0.25;
+
+/// Acceleration from holding forward, in blocks per second squared.
+const ACCELERATION: f32 = 6.0;
+/// Reversing is slower than going forward.
+const REVERSE_ACCELERATION: f32 = 2.0;
+const MAX_SPEED: f32 = 8.0;
+/// Turn rate while steering, in radians per second.
+const TURN_SPEED: f32 = 3.0;
+/// Share of horizontal velocity kept per game tick.
+const FRICTION: f32 = 0.9;
+const TICKS_PER_SECOND: f32 = 20.0;
+
+/// An entity a player can ride, such as a boat or minecart. While mounted,
+/// the rider's movement input steers the vehicle and the rider is carried in
+/// its seat.
+///
+/// Each tick: [`steer`](Self::steer), [`apply_gravity`](Self::apply_gravity),
+/// [`apply_friction`](Self::apply_friction),
+/// [`update_position`](Self::update_position), collisions, then
+/// [`carry`](Self::carry) the rider.
+pub struct Ridable {
+    position: Vec3,
+    velocity: Vec3,
+    /// Heading in radians. At 0 the vehicle faces -z, like forward movement.
+    yaw: f32,
+    on_ground: bool,
+    occupied: bool,
+}
+
+impl Ridable {
+    pub fn new(position: Vec3) -> Self {
+        Self {
+            position,
+            velocity: Vec3::ZERO,
+            yaw: 0.0,
+            on_ground: false,
+            occupied: false,
+        }
+    }
+
+    pub fn position(&self) -> Vec3 {
+        self.position
+    }
+
+    pub fn velocity(&self) -> Vec3 {
+        self.velocity
+    }
+
+    pub fn yaw(&self) -> f32 {
+        self.yaw
+    }
+
+    pub fn on_ground(&self) -> bool {
+        self.on_ground
+    }
+
+    pub fn is_occupied(&self) -> bool {
+        self.occupied
+    }
+
+    pub fn set_position(&mut self, position: Vec3) {
+        self.position = position;
+    }
+
+    pub fn set_velocity(&mut self, velocity: Vec3) {
+        self.velocity = velocity;
+    }
+
+    pub fn set_yaw(&mut self, yaw: f32) {
+        self.yaw = yaw;
+    }
+
+    pub fn set_on_ground(&mut self, on_ground: bool) {
+        self.on_ground = on_ground;
+    }
+
+    /// Unit vector the vehicle is facing.
+    pub fn heading(&self) -> Vec3 {
+        Vec3::new(-self.yaw.sin(), 0.0, -self.yaw.cos())
+    }
+
+    /// Where the rider's feet go.
+    pub fn seat_position(&self) -> Vec3 {
+        self.position + Vec3::new(0.0, SEAT_HEIGHT, 0.0)
+    }
+
+    pub fn aabb(&self) -> Aabb {
+        let half_width = WIDTH / 2.0;
+        Aabb::new(
+            self.position - Vec3::new(half_width, 0.0, half_width),
+            self.position + Vec3::new(half_width, HEIGHT, half_width),
+        )
+    }
+
+    /// Seat `rider` in the vehicle. Fails if the vehicle already has a rider
+    /// or the player is riding something else.
+    pub fn mount(&mut self, rider: &mut Player) -> bool {
+        if self.occupied || rider.is_riding() {
+            return false;
+        }
+        self.occupied = true;
+        rider.set_riding(true);
+        self.carry(rider);
+        true
+    }
+
+    /// Return control to `rider`, standing them on top of the vehicle with
+    /// its horizontal momentum.
+    pub fn dismount(&mut self, rider: &mut Player) {
+        if !self.occupied || !rider.is_riding() {
+            return;
+        }
+        self.occupied = false;
+        rider.set_riding(false);
+        rider.set_position(self.position + Vec3::new(0.0, HEIGHT, 0.0));
+        rider.set_velocity(Vec3::new(self.velocity.x, 0.0, self.velocity.z));
+        rider.set_on_ground(false);
+    }
+
+    /// Turn with left/right and accelerate along the heading with
+    /// forward/backward. Does nothing without a rider.
+    pub fn steer(&mut self, input: &MovementInput, dt: f32) {
+        if !self.occupied {
+            return;
+        }
+        if input.left {
+            self.yaw += TURN_SPEED * dt;
+        }
+        if input.right {
+            self.yaw -= TURN_SPEED * dt;
+        }
+
+        let thrust = match (input.forward, input.backward) {
+            (true, false) => ACCELERATION,
+            (false, true) => -REVERSE_ACCELERATION,
+            _ => 0.0,
+        };
+        let mut horizontal = Vec3::new(self.velocity.x, 0.0, self.velocity.z);
+        horizontal += self.heading() * thrust * dt;
+        horizontal = horizontal.clamp_length_max(MAX_SPEED);
+        self.velocity.x = horizontal.x;
+        self.velocity.z = horizontal.z;
+    }
+
+    pub fn apply_gravity(&mut self, dt: f32) {
+        self.velocity = gravity::apply_gravity(self.velocity, self.on_ground, dt);
+    }
+
+    /// Slow the vehicle down, with or without a rider.
+    pub fn apply_friction(&mut self, dt: f32) {
+        let keep = FRICTION.powf(dt * TICKS_PER_SECOND);
+        self.velocity.x *= keep;
+        self.velocity.z *= keep;
+    }
+
+    pub fn update_position(&mut self, dt: f32) {
+        self.position += self.velocity * dt;
+    }
+
+    /// Push the vehicle out of `other`. Returns the collision if the boxes
+    /// overlapped.
+    pub fn resolve_collision(&mut self, other: &Aabb) -> Option<CollisionEvent> {
+        collision::resolve_body(
+            self.aabb(),
+            other,
+            collision::DEFAULT_COLLISION_EPSILON,
+            &mut self.position,
+            &mut self.velocity,
+            &mut self.on_ground,
+        )
+    }
+
+    /// Move `rider` into the seat, travelling at the vehicle's velocity.
+    pub fn carry(&self, rider: &mut Player) {
+        if !rider.is_riding() {
+            return;
+        }
+        rider.set_position(self.seat_position());
+        rider.set_velocity(self.velocity);
+        rider.set_on_ground(self.on_ground);
+    }
+}
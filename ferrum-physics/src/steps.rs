@@ -0,0 +1,88 @@
+//! Footsteps from the distance a player actually walks.
+
+use crate::player::Player;
+use ferrum_core::BlockId;
+use glam::{IVec3, Vec3};
+
+/// Horizontal distance walked between footsteps, in blocks.
+pub const STEP_STRIDE: f32 = 1.6;
+/// Horizontal moves longer than this in one update are teleports, not
+/// walking, and make no footsteps.
+const MAX_WALK_PER_UPDATE: f32 = 4.0;
+
+/// A footstep: the player's feet at `position`, on `block_below`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepEvent {
+    pub position: Vec3,
+    pub block_below: BlockId,
+}
+
+/// Turns a player's movement into footsteps, one per [`STEP_STRIDE`] walked
+/// on the ground. Distance covered while airborne, swimming or riding does
+/// not count, but a jump doesn't lose the distance walked before it.
+#[derive(Debug, Clone)]
+pub struct StepTracker {
+    stride: f32,
+    last_position: Option<Vec3>,
+    travelled: f32,
+}
+
+impl StepTracker {
+    pub fn new(stride: f32) -> Self {
+        Self {
+            stride,
+            last_position: None,
+            travelled: 0.0,
+        }
+    }
+
+    /// Record where `player` is after a physics update, returning a footstep
+    /// if it has walked another stride. `block_at` gives the block at a
+    /// position, used to find the one under the player's feet.
+    pub fn update(
+        &mut self,
+        player: &Player,
+        block_at: impl FnOnce(IVec3) -> BlockId,
+    ) -> Option<StepEvent> {
+        let position = player.position();
+        let last = self.last_position.replace(position)?;
+        let walked = (position - last).with_y(0.0).length();
+        if !player.on_ground()
+            || player.is_swimming()
+            || player.is_riding()
+            || walked > MAX_WALK_PER_UPDATE
+        {
+            return None;
+        }
+
+        self.travelled += walked;
+        if self.travelled < self.stride {
+            return None;
+        }
+        self.travelled %= self.stride;
+
+        Some(StepEvent {
+            position,
+            block_below: block_at(block_below(position)),
+        })
+    }
+
+    /// Forget the last position, e.g. after a respawn, so the jump to the
+    /// new one isn't walked.
+    pub fn reset(&mut self) {
+        self.last_position = None;
+        self.travelled = 0.0;
+    }
+}
+
+/// The block under feet at `feet`. Feet resting on a block's top are just
+/// inside the block above it, so this looks a little lower.
+pub fn block_below(feet: Vec3) -> IVec3 {
+    (feet - Vec3::Y * 0.01).floor().as_ivec3()
+}
+
+impl Default for StepTracker {
+    fn default() -> Self {
+        Self::new(STEP_STRIDE)
+    }
+}
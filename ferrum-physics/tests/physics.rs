@@ -1,5 +1,11 @@
-use ferrum_physics::{collision::Aabb, movement::MovementInput, player::Player, GRAVITY};
-use glam::Vec3;
+use ferrum_core::BlockId;
+use ferrum_physics::{
+    collision::Aabb, gravity::fall_damage, hitbox_at, hitbox_of, melee_hit,
+    movement::MovementInput, player::Player, projectile_hit, voxel_raycast, Axis, EntityType,
+    Ridable, StepTracker, DEFAULT_COLLISION_EPSILON, GRAVITY, KNOCKBACK_LIFT, MELEE_REACH,
+    STEP_STRIDE,
+};
+use glam::{IVec3, Vec3};
 
 #[test]
 fn test_player_creation() {
@@ -243,3 +249,627 @@ fn test_sprint_speed_multiplier() {
 fn test_gravity_constant() {
     assert_eq!(GRAVITY, -32.0);
 }
+
+fn swim_input(sprint: bool) -> MovementInput {
+    MovementInput {
+        forward: true,
+        backward: false,
+        left: false,
+        right: false,
+        jump: false,
+        sprint,
+    }
+}
+
+#[test]
+fn test_submerged_and_moving_starts_swimming() {
+    let mut player = Player::new(Vec3::ZERO);
+    player.set_submerged(true);
+
+    player.apply_movement(MovementInput::default(), 0.05);
+    assert!(
+        !player.is_swimming(),
+        "standing still in water is not swimming"
+    );
+
+    player.apply_movement(swim_input(false), 0.05);
+    assert!(player.is_swimming());
+    assert!(player.velocity().z < 0.0);
+
+    let aabb = player.aabb();
+    assert!(aabb.size().y < 1.0, "swimming box should be horizontal");
+    assert_eq!(aabb.size().x, aabb.size().y);
+}
+
+#[test]
+fn test_surfacing_stops_swimming() {
+    let mut player = Player::new(Vec3::ZERO);
+    player.set_submerged(true);
+    player.apply_movement(swim_input(false), 0.05);
+    assert!(player.is_swimming());
+
+    player.set_submerged(false);
+    assert!(!player.is_swimming());
+    assert_eq!(player.aabb().size().y, 1.8);
+}
+
+#[test]
+fn test_stopping_in_water_stops_swimming() {
+    let mut player = Player::new(Vec3::ZERO);
+    player.set_submerged(true);
+    player.apply_movement(swim_input(false), 0.05);
+    assert!(player.is_swimming());
+
+    player.apply_movement(MovementInput::default(), 0.05);
+    assert!(!player.is_swimming());
+}
+
+#[test]
+fn test_not_swimming_out_of_water() {
+    let mut player = Player::new(Vec3::ZERO);
+    player.set_on_ground(true);
+    player.apply_movement(swim_input(true), 0.05);
+    assert!(!player.is_swimming());
+}
+
+#[test]
+fn test_sprint_swim_faster_than_swim() {
+    let mut swimmer = Player::new(Vec3::ZERO);
+    let mut sprinter = Player::new(Vec3::ZERO);
+    swimmer.set_submerged(true);
+    sprinter.set_submerged(true);
+
+    for _ in 0..40 {
+        swimmer.apply_movement(swim_input(false), 0.05);
+        sprinter.apply_movement(swim_input(true), 0.05);
+    }
+
+    let swim_speed = swimmer.velocity().length();
+    let sprint_swim_speed = sprinter.velocity().length();
+    assert!(swim_speed > 0.0);
+    assert!(sprint_swim_speed > swim_speed * 2.0);
+}
+
+#[test]
+fn test_swimming_ignores_gravity() {
+    let mut player = Player::new(Vec3::ZERO);
+    player.set_submerged(true);
+    player.apply_movement(swim_input(false), 0.05);
+
+    player.apply_gravity(0.05);
+
+    assert_eq!(player.velocity().y, 0.0);
+}
+
+#[test]
+fn test_landing_reports_y_collision() {
+    let mut player = Player::new(Vec3::new(0.0, -0.05, 0.0));
+    player.set_velocity(Vec3::new(0.0, -12.0, 0.0));
+    let ground = Aabb::new(Vec3::new(-5.0, -1.0, -5.0), Vec3::new(5.0, 0.0, 5.0));
+
+    let event = player
+        .resolve_collision(&ground)
+        .expect("landing collision");
+
+    assert_eq!(event.axis, Axis::Y);
+    assert_eq!(event.impact_speed, 12.0);
+    assert_eq!(event.position, player.position());
+    assert!(player.position().y >= 0.0, "pushed up out of the ground");
+    assert!(player.on_ground());
+}
+
+#[test]
+fn test_landing_on_ground_plane_reports_impact_speed() {
+    let mut player = Player::new(Vec3::new(0.0, 16.8, 0.0));
+    player.set_velocity(Vec3::new(1.0, -20.0, 0.0));
+
+    let event = player.resolve_ground(17.0).expect("landing collision");
+
+    assert_eq!(event.axis, Axis::Y);
+    assert_eq!(event.impact_speed, 20.0);
+    assert_eq!(event.position.y, 17.0);
+    assert_eq!(player.velocity(), Vec3::new(1.0, 0.0, 0.0));
+
+    // Standing still on the ground is not another landing.
+    assert!(player.resolve_ground(17.0).is_none());
+}
+
+#[test]
+fn test_walking_into_wall_reports_horizontal_collision() {
+    let mut player = Player::new(Vec3::ZERO);
+    player.set_velocity(Vec3::new(4.0, 0.0, 0.0));
+    let east_wall = Aabb::new(Vec3::new(0.2, 0.0, -0.5), Vec3::new(1.5, 1.0, 0.5));
+
+    let event = player
+        .resolve_collision(&east_wall)
+        .expect("wall collision");
+    assert_eq!(event.axis, Axis::X);
+    assert_eq!(event.impact_speed, 4.0);
+    assert!(player.aabb().max().x <= 0.2, "pushed back out of the wall");
+
+    let mut player = Player::new(Vec3::ZERO);
+    player.set_velocity(Vec3::new(0.0, 0.0, -3.0));
+    let north_wall = Aabb::new(Vec3::new(-0.5, 0.0, -1.5), Vec3::new(0.5, 1.0, -0.2));
+
+    let event = player
+        .resolve_collision(&north_wall)
+        .expect("wall collision");
+    assert_eq!(event.axis, Axis::Z);
+    assert_eq!(event.impact_speed, 3.0);
+}
+
+#[test]
+fn test_fall_damage_starts_after_three_blocks() {
+    let speed_after_fall = |blocks: f32| (2.0 * -GRAVITY * blocks).sqrt();
+
+    assert_eq!(fall_damage(speed_after_fall(2.5)), 0.0);
+    assert_eq!(fall_damage(speed_after_fall(3.0)), 0.0);
+    assert_eq!(fall_damage(speed_after_fall(10.0)), 7.0);
+}
+
+fn forward_input() -> MovementInput {
+    MovementInput {
+        forward: true,
+        ..MovementInput::default()
+    }
+}
+
+/// One tick of vehicle movement on flat ground, carrying the rider.
+fn ride_tick(vehicle: &mut Ridable, rider: &mut Player, input: MovementInput, dt: f32) {
+    rider.apply_movement(input, dt);
+    vehicle.steer(&input, dt);
+    vehicle.apply_friction(dt);
+    vehicle.update_position(dt);
+    rider.update_position(dt);
+    vehicle.carry(rider);
+}
+
+#[test]
+fn test_mounted_player_moves_with_vehicle() {
+    let mut vehicle = Ridable::new(Vec3::new(0.0, 64.0, 0.0));
+    vehicle.set_on_ground(true);
+    let mut rider = Player::new(Vec3::new(3.0, 64.0, 0.0));
+
+    assert!(vehicle.mount(&mut rider));
+    assert!(rider.is_riding());
+    assert_eq!(rider.position(), vehicle.seat_position());
+    assert!(!vehicle.mount(&mut Player::new(Vec3::ZERO)), "seat taken");
+
+    vehicle.set_velocity(Vec3::new(2.0, 0.0, 0.0));
+    for _ in 0..10 {
+        ride_tick(&mut vehicle, &mut rider, MovementInput::default(), 0.05);
+    }
+
+    assert!(vehicle.position().x > 0.5);
+    assert_eq!(rider.position(), vehicle.seat_position());
+    assert_eq!(rider.velocity(), vehicle.velocity());
+}
+
+#[test]
+fn test_steering_moves_vehicle_not_player() {
+    let mut vehicle = Ridable::new(Vec3::ZERO);
+    vehicle.set_on_ground(true);
+    let mut rider = Player::new(Vec3::ZERO);
+    rider.set_on_ground(true);
+    vehicle.mount(&mut rider);
+
+    // Forward input alone does not move a riding player
+    rider.apply_movement(forward_input(), 0.05);
+    rider.update_position(0.05);
+    assert_eq!(rider.position(), vehicle.seat_position());
+
+    for _ in 0..20 {
+        ride_tick(&mut vehicle, &mut rider, forward_input(), 0.05);
+    }
+    assert!(vehicle.position().z < -0.5, "vehicle drives forward");
+    assert_eq!(rider.position(), vehicle.seat_position());
+
+    let turning = MovementInput {
+        left: true,
+        ..MovementInput::default()
+    };
+    ride_tick(&mut vehicle, &mut rider, turning, 0.05);
+    assert!(vehicle.yaw() > 0.0);
+    assert!(vehicle.heading().x < 0.0, "turned towards the left");
+
+    // Without a rider, input does nothing
+    let mut empty = Ridable::new(Vec3::ZERO);
+    empty.steer(&forward_input(), 0.05);
+    assert_eq!(empty.velocity(), Vec3::ZERO);
+}
+
+#[test]
+fn test_vehicle_friction_and_collision() {
+    let mut vehicle = Ridable::new(Vec3::ZERO);
+    vehicle.set_velocity(Vec3::new(4.0, 0.0, 0.0));
+    vehicle.apply_friction(1.0);
+    assert!(vehicle.velocity().x < 0.5);
+
+    let mut vehicle = Ridable::new(Vec3::ZERO);
+    vehicle.set_velocity(Vec3::new(5.0, 0.0, 0.0));
+    let wall = Aabb::new(Vec3::new(0.5, 0.0, -1.0), Vec3::new(2.0, 1.0, 1.0));
+    let event = vehicle.resolve_collision(&wall).expect("wall collision");
+    assert_eq!(event.axis, Axis::X);
+    assert_eq!(vehicle.velocity().x, 0.0);
+    assert!(vehicle.aabb().max().x <= 0.5);
+}
+
+#[test]
+fn test_dismount_returns_control() {
+    let mut vehicle = Ridable::new(Vec3::ZERO);
+    vehicle.set_on_ground(true);
+    let mut rider = Player::new(Vec3::ZERO);
+    vehicle.mount(&mut rider);
+
+    vehicle.dismount(&mut rider);
+    assert!(!rider.is_riding());
+    assert!(!vehicle.is_occupied());
+    assert!(rider.position().y >= vehicle.aabb().max().y);
+
+    rider.set_on_ground(true);
+    rider.apply_movement(forward_input(), 0.05);
+    assert!(rider.velocity().z < 0.0, "player moves itself again");
+
+    vehicle.steer(&forward_input(), 0.05);
+    assert_eq!(vehicle.velocity(), Vec3::ZERO);
+}
+
+fn single_block(target: (i32, i32, i32)) -> impl Fn((i32, i32, i32)) -> bool {
+    move |cell| cell == target
+}
+
+#[test]
+fn test_raycast_axis_aligned() {
+    let origin = Vec3::new(0.5, 0.5, 0.5);
+    let hit = voxel_raycast(origin, Vec3::X, 10.0, single_block((4, 0, 0))).unwrap();
+    assert_eq!(hit.cell, IVec3::new(4, 0, 0));
+    assert_eq!(hit.normal, IVec3::NEG_X);
+    assert_eq!(hit.point, Vec3::new(4.0, 0.5, 0.5));
+    assert!((hit.distance - 3.5).abs() < 1e-5);
+
+    let hit = voxel_raycast(origin, Vec3::NEG_Y, 10.0, single_block((0, -3, 0))).unwrap();
+    assert_eq!(hit.cell, IVec3::new(0, -3, 0));
+    assert_eq!(hit.normal, IVec3::Y);
+    assert_eq!(hit.point.y, -2.0);
+
+    assert!(voxel_raycast(origin, Vec3::NEG_X, 10.0, single_block((4, 0, 0))).is_none());
+}
+
+#[test]
+fn test_raycast_diagonal() {
+    let origin = Vec3::new(0.5, 0.2, 0.5);
+    let dir = Vec3::new(1.0, 0.0, 1.0);
+    // Passes exactly through cell corners
+    let hit = voxel_raycast(origin, dir, 10.0, |cell| cell == (3, 0, 3)).unwrap();
+    assert_eq!(hit.cell, IVec3::new(3, 0, 3));
+    assert!(hit.normal == IVec3::NEG_X || hit.normal == IVec3::NEG_Z);
+    assert!((hit.point.x - 3.0).abs() < 1e-5 && (hit.point.z - 3.0).abs() < 1e-5);
+
+    // A shallow ray steps through cells on both axes, never skipping one
+    let dir = Vec3::new(3.0, 1.0, 0.0);
+    let hit = voxel_raycast(origin, dir, 20.0, |(x, y, _)| y == 1 && x >= 0).unwrap();
+    assert_eq!(hit.cell, IVec3::new(2, 1, 0));
+    assert_eq!(hit.normal, IVec3::NEG_Y);
+    assert_eq!(hit.point.y, 1.0);
+    assert!((hit.point.x - (0.5 + 3.0 * 0.8)).abs() < 1e-4);
+}
+
+#[test]
+fn test_raycast_starting_inside_block() {
+    let origin = Vec3::new(2.25, 3.5, -0.5);
+    let hit = voxel_raycast(origin, Vec3::Z, 10.0, single_block((2, 3, -1))).unwrap();
+    assert_eq!(hit.cell, IVec3::new(2, 3, -1));
+    assert_eq!(hit.normal, IVec3::ZERO);
+    assert_eq!(hit.point, origin);
+    assert_eq!(hit.distance, 0.0);
+}
+
+#[test]
+fn test_raycast_max_dist_truncates() {
+    let origin = Vec3::new(0.5, 0.5, 0.5);
+    let solid = single_block((0, 0, 6));
+    assert!(voxel_raycast(origin, Vec3::Z, 5.0, &solid).is_none());
+    assert!(voxel_raycast(origin, Vec3::Z, 5.5, &solid).is_some());
+    assert!(voxel_raycast(origin, Vec3::ZERO, 5.5, &solid).is_none());
+}
+
+#[test]
+fn test_hitbox_table() {
+    let player = hitbox_of(EntityType::Player);
+    assert_eq!(player.size(), Vec3::new(0.6, 1.8, 0.6));
+    assert_eq!(player.min().y, 0.0);
+    assert_eq!(player.center().x, 0.0);
+
+    let spider = hitbox_of(EntityType::Spider);
+    let chicken = hitbox_of(EntityType::Chicken);
+    assert!(spider.size().x > spider.size().y);
+    assert!(chicken.size().x < player.size().x);
+    assert!(chicken.size().y < player.size().y);
+
+    let moved = hitbox_at(EntityType::Spider, Vec3::new(4.0, 64.0, -2.0));
+    assert_eq!(moved.center(), spider.center() + Vec3::new(4.0, 64.0, -2.0));
+}
+
+#[test]
+fn test_projectile_grazes_wide_spider_but_misses_chicken() {
+    let target = Vec3::new(0.0, 64.0, 10.0);
+    // Flies level along +Z, 0.5 blocks to the side of the target's center
+    let from = Vec3::new(0.5, 64.3, 0.0);
+    let to = Vec3::new(0.5, 64.3, 20.0);
+
+    let t = projectile_hit(from, to, EntityType::Spider, target).unwrap();
+    assert!((t - (10.0 - 0.7) / 20.0).abs() < 1e-5);
+    assert_eq!(projectile_hit(from, to, EntityType::Chicken, target), None);
+
+    // Stopping short of the hitbox is not a hit yet
+    let short = Vec3::new(0.5, 64.3, 9.0);
+    assert_eq!(
+        projectile_hit(from, short, EntityType::Spider, target),
+        None
+    );
+
+    // Too high for the low spider, but not for a player
+    let high = Vec3::new(0.0, 65.5, 0.0);
+    let high_to = high + Vec3::new(0.0, 0.0, 20.0);
+    assert_eq!(
+        projectile_hit(high, high_to, EntityType::Spider, target),
+        None
+    );
+    assert!(projectile_hit(high, high_to, EntityType::Player, target).is_some());
+}
+
+#[test]
+fn test_melee_reach_uses_hitbox() {
+    let eye = Vec3::new(0.0, 65.62, 0.0);
+    let look = Vec3::new(0.0, -0.3, 1.0);
+
+    // The near side of a wide spider is within reach where a chicken's is not
+    let target = Vec3::new(0.0, 64.0, 3.5);
+    let distance = melee_hit(eye, look, EntityType::Spider, target).unwrap();
+    assert!(distance <= MELEE_REACH);
+    assert_eq!(melee_hit(eye, look, EntityType::Chicken, target), None);
+
+    // Looking away misses
+    assert_eq!(melee_hit(eye, -look, EntityType::Spider, target), None);
+}
+
+/// One physics tick on a floor at y = 64.
+fn step_on_floor(player: &mut Player, input: MovementInput, dt: f32) {
+    player.apply_movement(input, dt);
+    player.apply_gravity(dt);
+    player.update_position(dt);
+    player.resolve_ground(64.0);
+}
+
+#[test]
+fn test_horizontal_impulse_moves_and_decays() {
+    let mut player = Player::new(Vec3::new(0.0, 64.0, 0.0));
+    player.set_on_ground(true);
+    player.apply_impulse(Vec3::new(12.0, 0.0, 0.0));
+    assert_eq!(player.pending_impulse(), Vec3::new(12.0, 0.0, 0.0));
+    assert_eq!(player.velocity(), Vec3::ZERO, "queued until the next tick");
+
+    let dt = 0.05;
+    step_on_floor(&mut player, MovementInput::default(), dt);
+    assert_eq!(player.pending_impulse(), Vec3::ZERO);
+    let first_speed = player.velocity().x;
+    assert!(first_speed > 0.0);
+    assert!(player.on_ground());
+
+    for _ in 0..20 {
+        step_on_floor(&mut player, MovementInput::default(), dt);
+    }
+    assert!(player.position().x > 0.0);
+    assert!(player.velocity().x.abs() < 0.01 * first_speed);
+    assert_eq!(player.position().y, 64.0);
+}
+
+#[test]
+fn test_grounded_knockback_lifts_and_pushes() {
+    let mut player = Player::new(Vec3::new(0.0, 64.0, 0.0));
+    player.set_on_ground(true);
+    // Hit from the north, away from the held forward key
+    player.knockback(Vec3::new(0.0, 0.5, 2.0), 6.0);
+    assert_eq!(player.pending_impulse(), Vec3::new(0.0, 6.0, 6.0));
+
+    let forward = MovementInput {
+        forward: true,
+        ..Default::default()
+    };
+    let dt = 0.05;
+    step_on_floor(&mut player, forward, dt);
+    assert!(!player.on_ground());
+    assert!(player.is_knocked_back());
+    assert!(player.position().y > 64.0);
+    let launch_speed = player.velocity().z;
+    assert!(launch_speed > 0.0);
+
+    // Forward does nothing in the air; drag slows the push
+    step_on_floor(&mut player, forward, dt);
+    assert!(player.velocity().z > 0.0 && player.velocity().z < launch_speed);
+
+    let mut ticks = 0;
+    while !player.on_ground() {
+        step_on_floor(&mut player, forward, dt);
+        ticks += 1;
+        assert!(ticks < 40, "never landed");
+    }
+    assert!(player.position().z > 0.5);
+    step_on_floor(&mut player, forward, dt);
+    assert!(!player.is_knocked_back());
+
+    // The lift is capped, and an airborne player is not lifted again
+    let mut player = Player::new(Vec3::new(0.0, 64.0, 0.0));
+    player.set_on_ground(true);
+    player.knockback(Vec3::X, 40.0);
+    assert_eq!(player.pending_impulse().y, KNOCKBACK_LIFT);
+    let mut player = Player::new(Vec3::new(0.0, 70.0, 0.0));
+    player.knockback(Vec3::X, 6.0);
+    assert_eq!(player.pending_impulse(), Vec3::new(6.0, 0.0, 0.0));
+}
+
+/// Walk `player` along +X in `moves` moves of `distance`, counting footsteps
+/// and checking each is on grass at block height 63.
+fn count_steps(
+    tracker: &mut StepTracker,
+    player: &mut Player,
+    moves: usize,
+    distance: f32,
+) -> usize {
+    // Where the walk starts from
+    tracker.update(player, |_| BlockId::new(2));
+    let mut steps = 0;
+    for _ in 0..moves {
+        player.set_position(player.position() + Vec3::X * distance);
+        let step = tracker.update(player, |pos| {
+            assert_eq!(pos.y, 63);
+            BlockId::new(2)
+        });
+        if let Some(step) = step {
+            assert_eq!(step.block_below, BlockId::new(2));
+            assert_eq!(step.position, player.position());
+            steps += 1;
+        }
+    }
+    steps
+}
+
+#[test]
+fn test_steps_follow_distance_walked() {
+    let mut player = Player::new(Vec3::new(0.5, 64.0, 0.5));
+    player.set_on_ground(true);
+    let mut tracker = StepTracker::default();
+
+    // Ten and a half strides, in small moves
+    let moves = 210;
+    let distance = STEP_STRIDE * 10.5 / moves as f32;
+    assert_eq!(count_steps(&mut tracker, &mut player, moves, distance), 10);
+
+    // The same distance in bigger moves makes the same number of steps
+    let mut tracker = StepTracker::default();
+    assert_eq!(
+        count_steps(&mut tracker, &mut player, 21, distance * 10.0),
+        10
+    );
+}
+
+#[test]
+fn test_no_steps_standing_still_or_airborne() {
+    let mut player = Player::new(Vec3::new(0.5, 64.0, 0.5));
+    player.set_on_ground(true);
+    let mut tracker = StepTracker::default();
+    assert_eq!(count_steps(&mut tracker, &mut player, 100, 0.0), 0);
+
+    player.set_on_ground(false);
+    assert_eq!(count_steps(&mut tracker, &mut player, 100, 0.1), 0);
+
+    // A teleport is not a walk
+    player.set_on_ground(true);
+    assert_eq!(count_steps(&mut tracker, &mut player, 1, 50.0), 0);
+}
+
+/// A floor of `width` blocks along x, with its top at y = 64.
+fn floor_blocks(width: i32) -> Vec<Aabb> {
+    (0..width)
+        .map(|x| {
+            let min = Vec3::new(x as f32, 63.0, -2.0);
+            Aabb::new(min, min + Vec3::new(1.0, 1.0, 4.0))
+        })
+        .collect()
+}
+
+/// One physics tick against `blocks`.
+fn step_on_blocks(player: &mut Player, input: MovementInput, blocks: &[Aabb], dt: f32) {
+    player.apply_movement(input, dt);
+    player.apply_gravity(dt);
+    player.update_position(dt);
+    player.resolve_collisions(blocks);
+}
+
+#[test]
+fn test_dropped_player_settles_on_floor() {
+    let floor = floor_blocks(4);
+    let mut player = Player::new(Vec3::new(1.5, 66.3, 0.0));
+    let dt = 1.0 / 60.0;
+
+    let mut ticks = 0;
+    while !player.on_ground() {
+        step_on_blocks(&mut player, MovementInput::default(), &floor, dt);
+        ticks += 1;
+        assert!(ticks < 120, "player never landed");
+    }
+    assert_eq!(player.position().y, 64.0);
+
+    // Resting stays put and grounded every tick
+    for _ in 0..200 {
+        step_on_blocks(&mut player, MovementInput::default(), &floor, dt);
+        assert!(player.on_ground());
+        assert_eq!(player.position().y, 64.0);
+        assert_eq!(player.velocity().y, 0.0);
+    }
+}
+
+#[test]
+fn test_grounded_state_is_stable_on_block_edges() {
+    let floor = floor_blocks(1);
+    let dt = 1.0 / 60.0;
+
+    // Barely over the edge, and hovering within the epsilon, still stands
+    let mut player = Player::new(Vec3::new(1.25, 64.0005, 0.0));
+    for _ in 0..100 {
+        step_on_blocks(&mut player, MovementInput::default(), &floor, dt);
+        assert!(player.on_ground());
+        assert_eq!(player.position().y, 64.0);
+    }
+
+    // Exactly on the edge is never grounded, and falls
+    let mut player = Player::new(Vec3::new(1.3, 64.0, 0.0));
+    for _ in 0..20 {
+        step_on_blocks(&mut player, MovementInput::default(), &floor, dt);
+        assert!(!player.on_ground());
+    }
+    assert!(player.position().y < 63.0);
+
+    // A wider epsilon snaps a higher hover
+    let mut player = Player::new(Vec3::new(0.5, 64.005, 0.0));
+    player.set_collision_epsilon(0.01);
+    step_on_blocks(&mut player, MovementInput::default(), &floor, dt);
+    assert!(player.on_ground());
+    assert_eq!(player.position().y, 64.0);
+}
+
+#[test]
+fn test_player_slides_along_wall_without_vibrating() {
+    let mut blocks = floor_blocks(4);
+    // A wall on the west side, flush with the floor
+    blocks.push(Aabb::new(
+        Vec3::new(-1.0, 64.0, -2.0),
+        Vec3::new(0.0, 66.0, 2.0),
+    ));
+    let mut player = Player::new(Vec3::new(0.32, 64.0, 1.5));
+    player.set_on_ground(true);
+    let into_wall = MovementInput {
+        forward: true,
+        left: true,
+        ..MovementInput::default()
+    };
+    let dt = 1.0 / 60.0;
+
+    let mut last_z = player.position().z;
+    for _ in 0..60 {
+        step_on_blocks(&mut player, into_wall, &blocks, dt);
+        assert!(player.on_ground());
+        assert_eq!(player.position().y, 64.0);
+        assert!(
+            player.aabb().min().x > -DEFAULT_COLLISION_EPSILON,
+            "inside the wall"
+        );
+        assert!(player.position().z <= last_z, "slid backwards");
+        last_z = player.position().z;
+    }
+    assert!(
+        player.aabb().min().x.abs() <= DEFAULT_COLLISION_EPSILON,
+        "resting against the wall"
+    );
+    assert!(player.position().z < 1.4, "slid along the wall");
+}
@@ -0,0 +1,84 @@
+//! Custom payload channels: named side channels used by servers and mods,
+//! such as `minecraft:brand`.
+//!
+//! Each channel with a handler gets its payloads; anything on other channels
+//! is dropped, since servers routinely send channels a client doesn't know.
+
+use crate::status::{read_string, write_string};
+use std::collections::HashMap;
+
+/// Channel on which the server announces its brand and expects the client's.
+pub const BRAND_CHANNEL: &str = "minecraft:brand";
+
+/// Brand this client reports to servers.
+pub const CLIENT_BRAND: &str = "ferrum";
+
+/// Handles one channel's payloads. Returns a payload to send back on the same
+/// channel, if any.
+pub type ChannelHandler = Box<dyn FnMut(&[u8]) -> Option<Vec<u8>> + Send>;
+
+/// Handlers for custom payload channels, by channel name.
+#[derive(Default)]
+pub struct ChannelRegistry {
+    handlers: HashMap<String, ChannelHandler>,
+}
+
+impl ChannelRegistry {
+    /// No channels.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer brand requests with `brand`.
+    pub fn with_brand(brand: &str) -> Self {
+        let mut registry = Self::new();
+        let reply = encode_brand(brand);
+        registry.register(BRAND_CHANNEL, move |_| Some(reply.clone()));
+        registry
+    }
+
+    /// Route payloads on `channel` to `handler`, replacing any earlier one.
+    /// Returns whether a handler was replaced.
+    pub fn register(
+        &mut self,
+        channel: impl Into<String>,
+        handler: impl FnMut(&[u8]) -> Option<Vec<u8>> + Send + 'static,
+    ) -> bool {
+        self.handlers
+            .insert(channel.into(), Box::new(handler))
+            .is_some()
+    }
+
+    /// Stop handling `channel`. Returns whether it had a handler.
+    pub fn unregister(&mut self, channel: &str) -> bool {
+        self.handlers.remove(channel).is_some()
+    }
+
+    pub fn is_registered(&self, channel: &str) -> bool {
+        self.handlers.contains_key(channel)
+    }
+
+    /// Names of every handled channel, e.g. to announce them to the server.
+    pub fn channels(&self) -> impl Iterator<Item = &str> {
+        self.handlers.keys().map(String::as_str)
+    }
+
+    /// Pass an incoming payload to its channel's handler and return the
+    /// handler's reply. Payloads on unhandled channels are ignored.
+    pub fn dispatch(&mut self, channel: &str, payload: &[u8]) -> Option<Vec<u8>> {
+        let handler = self.handlers.get_mut(channel)?;
+        handler(payload)
+    }
+}
+
+/// Brand payload: the brand as a protocol string.
+pub fn encode_brand(brand: &str) -> Vec<u8> {
+    let mut data = Vec::new();
+    write_string(&mut data, brand);
+    data
+}
+
+/// Read a brand payload, or `None` if it is malformed.
+pub fn decode_brand(mut data: &[u8]) -> Option<String> {
+    read_string(&mut data).ok()
+}
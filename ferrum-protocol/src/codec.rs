@@ -0,0 +1,271 @@
+//! The layers wrapping packets on the wire, as independent codecs.
+//!
+//! An outgoing packet (id and data) is compressed once the server sets a
+//! threshold, then length-prefixed, and the resulting byte stream encrypted
+//! once encryption is enabled. Incoming bytes go through the same layers in
+//! reverse. [`CodecStack`] keeps them in that order so each can be switched
+//! on independently during login.
+//!
+//! Only connections framed by hand go through these codecs, which is the
+//! [status query](crate::status::query_status) for now. The client's login
+//! and play connection has not been moved onto them: it runs on azalea's
+//! `Connection`, which frames, compresses and encrypts packets itself.
+
+use cfb8::cipher::inout::InOutBuf;
+use cfb8::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use std::io::{Read, Write};
+
+/// Largest frame accepted, the largest length a 3-byte VarInt can hold.
+pub const MAX_PACKET_LENGTH: usize = 2_097_151;
+
+/// Largest size a compressed packet may claim once inflated.
+pub const MAX_DECOMPRESSED_LENGTH: usize = 8_388_608;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CodecError {
+    #[error("VarInt too long")]
+    VarIntTooLong,
+    #[error("Bad packet length {0}")]
+    BadLength(i32),
+    #[error("Invalid compressed packet: {0}")]
+    BadCompression(String),
+    #[error("Compression error: {0}")]
+    Zlib(#[from] std::io::Error),
+}
+
+pub fn write_varint(buf: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7F == 0 {
+            buf.push(value as u8);
+            return;
+        }
+        buf.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+}
+
+/// Read a VarInt from the front of `buf`, advancing it. `Ok(None)`, leaving
+/// `buf` as it was, if it ends before the VarInt does.
+pub fn read_varint(buf: &mut &[u8]) -> Result<Option<i32>, CodecError> {
+    let mut value = 0u32;
+    for (i, &byte) in buf.iter().take(5).enumerate() {
+        value |= ((byte & 0x7F) as u32) << (7 * i);
+        if byte & 0x80 == 0 {
+            *buf = &buf[i + 1..];
+            return Ok(Some(value as i32));
+        }
+    }
+    if buf.len() >= 5 {
+        return Err(CodecError::VarIntTooLong);
+    }
+    Ok(None)
+}
+
+/// VarInt length prefix on every packet.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Framing;
+
+impl Framing {
+    /// Append `body` to `out` with its length in front.
+    pub fn encode(&self, body: &[u8], out: &mut Vec<u8>) -> Result<(), CodecError> {
+        if body.len() > MAX_PACKET_LENGTH {
+            return Err(CodecError::BadLength(body.len() as i32));
+        }
+        write_varint(out, body.len() as i32);
+        out.extend_from_slice(body);
+        Ok(())
+    }
+
+    /// Take the first complete frame off the front of `buf` and return its
+    /// body, or `None` if `buf` doesn't hold a whole frame yet.
+    pub fn decode(&self, buf: &mut Vec<u8>) -> Result<Option<Vec<u8>>, CodecError> {
+        let mut cursor = buf.as_slice();
+        let Some(length) = read_varint(&mut cursor)? else {
+            return Ok(None);
+        };
+        let length = usize::try_from(length)
+            .ok()
+            .filter(|&length| length <= MAX_PACKET_LENGTH)
+            .ok_or(CodecError::BadLength(length))?;
+        if cursor.len() < length {
+            return Ok(None);
+        }
+
+        let start = buf.len() - cursor.len();
+        let body = buf[start..start + length].to_vec();
+        buf.drain(..start + length);
+        Ok(Some(body))
+    }
+}
+
+/// zlib compression of packets at or above a size threshold, with the
+/// uncompressed length in front (0 for packets sent as is).
+#[derive(Debug, Clone, Copy)]
+pub struct Compression {
+    threshold: usize,
+}
+
+impl Compression {
+    pub fn new(threshold: usize) -> Self {
+        Self { threshold }
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn compress(&self, packet: &[u8]) -> Result<Vec<u8>, CodecError> {
+        let mut out = Vec::new();
+        if packet.len() < self.threshold {
+            write_varint(&mut out, 0);
+            out.extend_from_slice(packet);
+            return Ok(out);
+        }
+
+        write_varint(&mut out, packet.len() as i32);
+        let mut encoder = ZlibEncoder::new(out, flate2::Compression::default());
+        encoder.write_all(packet)?;
+        Ok(encoder.finish()?)
+    }
+
+    pub fn decompress(&self, body: &[u8]) -> Result<Vec<u8>, CodecError> {
+        let mut cursor = body;
+        let length = read_varint(&mut cursor)?
+            .ok_or_else(|| CodecError::BadCompression("missing data length".into()))?;
+        if length == 0 {
+            return Ok(cursor.to_vec());
+        }
+
+        let length = usize::try_from(length)
+            .ok()
+            .filter(|&length| length <= MAX_DECOMPRESSED_LENGTH)
+            .ok_or_else(|| CodecError::BadCompression(format!("data length {length}")))?;
+        if length < self.threshold {
+            return Err(CodecError::BadCompression(format!(
+                "{length} bytes is below the threshold of {}",
+                self.threshold
+            )));
+        }
+
+        let mut packet = Vec::with_capacity(length);
+        ZlibDecoder::new(cursor)
+            .take(length as u64 + 1)
+            .read_to_end(&mut packet)?;
+        if packet.len() != length {
+            return Err(CodecError::BadCompression(format!(
+                "expected {length} bytes, inflated to {}",
+                packet.len()
+            )));
+        }
+        Ok(packet)
+    }
+}
+
+/// AES-128 in CFB8 mode over the whole byte stream, with the shared secret
+/// as both key and IV.
+pub struct Encryption {
+    encryptor: cfb8::Encryptor<aes::Aes128>,
+    decryptor: cfb8::Decryptor<aes::Aes128>,
+}
+
+impl Encryption {
+    pub fn new(shared_secret: &[u8; 16]) -> Self {
+        Self {
+            encryptor: cfb8::Encryptor::new(shared_secret.into(), shared_secret.into()),
+            decryptor: cfb8::Decryptor::new(shared_secret.into(), shared_secret.into()),
+        }
+    }
+
+    /// Encrypt outgoing bytes in place, continuing the stream.
+    pub fn encrypt(&mut self, data: &mut [u8]) {
+        let (blocks, _) = InOutBuf::from(data).into_chunks();
+        self.encryptor.encrypt_blocks_inout_mut(blocks);
+    }
+
+    /// Decrypt incoming bytes in place, continuing the stream.
+    pub fn decrypt(&mut self, data: &mut [u8]) {
+        let (blocks, _) = InOutBuf::from(data).into_chunks();
+        self.decryptor.decrypt_blocks_inout_mut(blocks);
+    }
+}
+
+/// Framing, then optional compression and encryption, in wire order, for
+/// connections driven byte by byte rather than through azalea.
+#[derive(Default)]
+pub struct CodecStack {
+    framing: Framing,
+    compression: Option<Compression>,
+    encryption: Option<Encryption>,
+    /// Decrypted bytes not yet making up a whole frame.
+    incoming: Vec<u8>,
+}
+
+impl CodecStack {
+    /// Plain framing, as at the start of every connection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compress packets of at least `threshold` bytes from now on. A
+    /// negative threshold, as sent by servers to turn it off, disables
+    /// compression.
+    pub fn set_compression_threshold(&mut self, threshold: i32) {
+        self.compression = usize::try_from(threshold).ok().map(Compression::new);
+    }
+
+    pub fn compression(&self) -> Option<&Compression> {
+        self.compression.as_ref()
+    }
+
+    /// Encrypt everything sent and received from now on.
+    pub fn enable_encryption(&mut self, shared_secret: &[u8; 16]) {
+        self.encryption = Some(Encryption::new(shared_secret));
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption.is_some()
+    }
+
+    /// Wire bytes for one packet (id followed by data).
+    pub fn encode(&mut self, packet: &[u8]) -> Result<Vec<u8>, CodecError> {
+        let compressed;
+        let body = match &self.compression {
+            Some(compression) => {
+                compressed = compression.compress(packet)?;
+                &compressed
+            }
+            None => packet,
+        };
+
+        let mut out = Vec::with_capacity(body.len() + 5);
+        self.framing.encode(body, &mut out)?;
+        if let Some(encryption) = &mut self.encryption {
+            encryption.encrypt(&mut out);
+        }
+        Ok(out)
+    }
+
+    /// Take in bytes read from the connection.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        let start = self.incoming.len();
+        self.incoming.extend_from_slice(bytes);
+        if let Some(encryption) = &mut self.encryption {
+            encryption.decrypt(&mut self.incoming[start..]);
+        }
+    }
+
+    /// The next complete packet received, or `None` until more bytes are
+    /// fed.
+    pub fn next_packet(&mut self) -> Result<Option<Vec<u8>>, CodecError> {
+        let Some(body) = self.framing.decode(&mut self.incoming)? else {
+            return Ok(None);
+        };
+        match &self.compression {
+            Some(compression) => compression.decompress(&body).map(Some),
+            None => Ok(Some(body)),
+        }
+    }
+}
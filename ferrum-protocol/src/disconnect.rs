@@ -0,0 +1,177 @@
+//! Disconnect (kick) packets, which a server can send in the login, config
+//! and play states before closing the connection.
+//!
+//! Only the packet body is parsed here; the caller has already matched the
+//! packet id for its protocol version. The reason is a chat component: JSON
+//! text during login, network NBT in the config and play states.
+
+use crate::status::{flatten_text, read_string};
+use crate::{ConnectionState, ProtocolState};
+use serde_json::{Map, Value};
+
+const TAG_END: u8 = 0;
+const TAG_BYTE: u8 = 1;
+const TAG_SHORT: u8 = 2;
+const TAG_INT: u8 = 3;
+const TAG_LONG: u8 = 4;
+const TAG_FLOAT: u8 = 5;
+const TAG_DOUBLE: u8 = 6;
+const TAG_BYTE_ARRAY: u8 = 7;
+const TAG_STRING: u8 = 8;
+const TAG_LIST: u8 = 9;
+const TAG_COMPOUND: u8 = 10;
+const TAG_INT_ARRAY: u8 = 11;
+const TAG_LONG_ARRAY: u8 = 12;
+
+/// Deepest nesting of NBT lists and compounds accepted.
+const MAX_NBT_DEPTH: usize = 512;
+
+/// The server closed the connection, with the reason it gave.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disconnected {
+    /// Reason as plain text, with formatting dropped.
+    pub reason: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DisconnectError {
+    #[error("No disconnect packet in the {0:?} state")]
+    UnexpectedState(ProtocolState),
+    #[error("Malformed disconnect packet: {0}")]
+    Malformed(String),
+    #[error("Invalid disconnect reason JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+impl ConnectionState {
+    /// Handle a disconnect packet with body `data` received in the current
+    /// state: parse its reason and end the connection in
+    /// [`ProtocolState::Disconnected`], as a clean teardown.
+    ///
+    /// The state only changes if the packet parses, so a malformed packet
+    /// can be reported without losing track of where the connection was.
+    pub fn handle_disconnect(&mut self, data: &[u8]) -> Result<Disconnected, DisconnectError> {
+        let component = match self.current() {
+            ProtocolState::Login => {
+                let json = read_string(&mut &data[..])
+                    .map_err(|e| DisconnectError::Malformed(e.to_string()))?;
+                serde_json::from_str(&json)?
+            }
+            ProtocolState::Config | ProtocolState::Play => read_nbt_component(data)?,
+            state => return Err(DisconnectError::UnexpectedState(state)),
+        };
+        let mut reason = String::new();
+        flatten_text(&component, &mut reason);
+        // Login, config and play, the only states reaching here, all allow it
+        self.current = ProtocolState::Disconnected;
+        Ok(Disconnected { reason })
+    }
+}
+
+/// Read an unnamed network NBT tag holding a chat component into the JSON
+/// form of the same component.
+fn read_nbt_component(mut data: &[u8]) -> Result<Value, DisconnectError> {
+    let tag = take_u8(&mut data)?;
+    read_nbt(tag, &mut data, 0)
+}
+
+fn read_nbt(tag: u8, data: &mut &[u8], depth: usize) -> Result<Value, DisconnectError> {
+    if depth > MAX_NBT_DEPTH {
+        return Err(DisconnectError::Malformed("NBT nested too deeply".into()));
+    }
+    Ok(match tag {
+        TAG_BYTE => Value::from(take::<1>(data)?[0] as i8),
+        TAG_SHORT => Value::from(i16::from_be_bytes(take(data)?)),
+        TAG_INT => Value::from(i32::from_be_bytes(take(data)?)),
+        TAG_LONG => Value::from(i64::from_be_bytes(take(data)?)),
+        TAG_FLOAT => Value::from(f32::from_be_bytes(take(data)?)),
+        TAG_DOUBLE => Value::from(f64::from_be_bytes(take(data)?)),
+        TAG_BYTE_ARRAY => {
+            let length = take_length(data, 1)?;
+            let bytes = take_slice(data, length)?;
+            Value::Array(bytes.iter().map(|&byte| Value::from(byte as i8)).collect())
+        }
+        TAG_STRING => Value::String(take_nbt_string(data)?),
+        TAG_LIST => {
+            let element = take_u8(data)?;
+            let length = take_length(data, 1)?;
+            let mut list = Vec::with_capacity(length);
+            for _ in 0..length {
+                list.push(read_nbt(element, data, depth + 1)?);
+            }
+            Value::Array(list)
+        }
+        TAG_COMPOUND => {
+            let mut fields = Map::new();
+            loop {
+                let tag = take_u8(data)?;
+                if tag == TAG_END {
+                    break;
+                }
+                let name = take_nbt_string(data)?;
+                fields.insert(name, read_nbt(tag, data, depth + 1)?);
+            }
+            // Mixed-type lists wrap each element in a compound with an empty
+            // name
+            match fields.remove("") {
+                Some(value) if fields.is_empty() => value,
+                Some(value) => {
+                    fields.insert(String::new(), value);
+                    Value::Object(fields)
+                }
+                None => Value::Object(fields),
+            }
+        }
+        TAG_INT_ARRAY => {
+            let length = take_length(data, 4)?;
+            let mut values = Vec::with_capacity(length);
+            for _ in 0..length {
+                values.push(Value::from(i32::from_be_bytes(take(data)?)));
+            }
+            Value::Array(values)
+        }
+        TAG_LONG_ARRAY => {
+            let length = take_length(data, 8)?;
+            let mut values = Vec::with_capacity(length);
+            for _ in 0..length {
+                values.push(Value::from(i64::from_be_bytes(take(data)?)));
+            }
+            Value::Array(values)
+        }
+        _ => return Err(DisconnectError::Malformed(format!("unknown NBT tag {tag}"))),
+    })
+}
+
+fn take_slice<'a>(data: &mut &'a [u8], length: usize) -> Result<&'a [u8], DisconnectError> {
+    if data.len() < length {
+        return Err(DisconnectError::Malformed("truncated NBT".into()));
+    }
+    let (taken, rest) = data.split_at(length);
+    *data = rest;
+    Ok(taken)
+}
+
+fn take<const N: usize>(data: &mut &[u8]) -> Result<[u8; N], DisconnectError> {
+    Ok(take_slice(data, N)?.try_into().unwrap())
+}
+
+fn take_u8(data: &mut &[u8]) -> Result<u8, DisconnectError> {
+    Ok(take::<1>(data)?[0])
+}
+
+/// Read an array or list length, checking there is room left for that many
+/// elements of at least `element_size` bytes.
+fn take_length(data: &mut &[u8], element_size: usize) -> Result<usize, DisconnectError> {
+    let length = i32::from_be_bytes(take(data)?).max(0) as usize;
+    if length.saturating_mul(element_size) > data.len() {
+        return Err(DisconnectError::Malformed("truncated NBT".into()));
+    }
+    Ok(length)
+}
+
+/// NBT strings are modified UTF-8, which only differs from UTF-8 for nul
+/// and characters outside the basic plane; those are replaced.
+fn take_nbt_string(data: &mut &[u8]) -> Result<String, DisconnectError> {
+    let length = u16::from_be_bytes(take(data)?) as usize;
+    Ok(String::from_utf8_lossy(take_slice(data, length)?).into_owned())
+}
@@ -3,6 +3,18 @@ pub use azalea_protocol::packets::game::c_level_chunk_with_light::ClientboundLev
 pub use azalea_protocol::packets::game::ClientboundGamePacket as GamePacket;
 pub use azalea_protocol::packets::login::ClientboundLoginPacket as LoginPacket;
 
+pub mod channels;
+pub mod codec;
+pub mod disconnect;
+pub mod status;
+
+pub use channels::{
+    decode_brand, encode_brand, ChannelHandler, ChannelRegistry, BRAND_CHANNEL, CLIENT_BRAND,
+};
+pub use codec::{CodecError, CodecStack, Compression, Encryption, Framing};
+pub use disconnect::{DisconnectError, Disconnected};
+pub use status::{parse_status_response, query_status, ServerStatus, StatusError};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProtocolState {
     Handshake,
@@ -10,6 +22,12 @@ pub enum ProtocolState {
     Login,
     Config,
     Play,
+    /// Ended without a clean teardown, e.g. the socket dropped or a kick
+    /// packet could not be read.
+    Closed,
+    /// Ended cleanly after getting past the handshake: the client left, or
+    /// the server kicked it with a reason.
+    Disconnected,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -19,16 +37,28 @@ pub enum ConnectionStateError {
         from: ProtocolState,
         to: ProtocolState,
     },
+    #[error("Cannot enable compression in the {0:?} state")]
+    CompressionNotAllowed(ProtocolState),
 }
 
+/// Protocol phase of a connection, plus the compression and encryption in
+/// effect on it. Only hand-framed connections apply these through a
+/// [`CodecStack`]; for azalea's login and play connection they are
+/// bookkeeping.
 pub struct ConnectionState {
     current: ProtocolState,
+    /// Size in bytes from which packets are compressed, once the server has
+    /// sent `SetCompression`.
+    compression_threshold: Option<i32>,
+    encrypted: bool,
 }
 
 impl ConnectionState {
     pub fn new() -> Self {
         Self {
             current: ProtocolState::Handshake,
+            compression_threshold: None,
+            encrypted: false,
         }
     }
 
@@ -36,6 +66,39 @@ impl ConnectionState {
         self.current
     }
 
+    pub fn compression_threshold(&self) -> Option<i32> {
+        self.compression_threshold
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.encrypted
+    }
+
+    /// Compress packets of `threshold` bytes or more from now on. A negative
+    /// threshold turns compression off, as it does in `SetCompression`.
+    /// Only servers past the handshake can enable it.
+    pub fn enable_compression(&mut self, threshold: i32) -> Result<(), ConnectionStateError> {
+        match self.current {
+            ProtocolState::Login | ProtocolState::Config | ProtocolState::Play => {
+                self.compression_threshold = (threshold >= 0).then_some(threshold);
+                Ok(())
+            }
+            _ => Err(ConnectionStateError::CompressionNotAllowed(self.current)),
+        }
+    }
+
+    /// Record that the login encryption handshake finished, so every byte
+    /// from here on is encrypted.
+    pub fn mark_encrypted(&mut self) {
+        self.encrypted = true;
+    }
+
+    /// Whether a packet of `packet_len` bytes goes out compressed.
+    pub fn should_compress(&self, packet_len: usize) -> bool {
+        self.compression_threshold
+            .is_some_and(|threshold| packet_len >= threshold as usize)
+    }
+
     pub fn transition_to_login(&mut self) -> Result<(), ConnectionStateError> {
         match self.current {
             ProtocolState::Handshake => {
@@ -87,6 +150,34 @@ impl ConnectionState {
             }),
         }
     }
+
+    /// Close the connection without a clean teardown. Allowed from any
+    /// state.
+    pub fn close(&mut self) {
+        self.current = ProtocolState::Closed;
+    }
+
+    /// Leave a connection that got past the handshake. This and a server's
+    /// kick both end in [`ProtocolState::Disconnected`], telling a clean
+    /// teardown apart from one [`close`](Self::close)d any other way.
+    pub fn disconnect(&mut self) -> Result<(), ConnectionStateError> {
+        match self.current {
+            ProtocolState::Login | ProtocolState::Config | ProtocolState::Play => {
+                self.current = ProtocolState::Disconnected;
+                Ok(())
+            }
+            _ => Err(ConnectionStateError::InvalidTransition {
+                from: self.current,
+                to: ProtocolState::Disconnected,
+            }),
+        }
+    }
+
+    /// Start over for a new connection: back to the handshake from any
+    /// state, without compression or encryption.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
 }
 
 impl Default for ConnectionState {
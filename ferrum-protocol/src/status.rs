@@ -0,0 +1,268 @@
+//! Server list ping: query a server's status and latency without logging in.
+//!
+//! The status exchange is small and stable across versions, so it is framed
+//! by hand instead of going through a full protocol connection.
+
+use crate::codec::{write_varint, CodecError, CodecStack};
+use crate::{ConnectionState, ConnectionStateError};
+use base64::Engine;
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Protocol version sent in the handshake. Servers answer status requests for
+/// any version, and -1 is the conventional "unknown".
+const STATUS_PROTOCOL_VERSION: i32 = -1;
+
+const HANDSHAKE_PACKET_ID: i32 = 0x00;
+const STATUS_REQUEST_PACKET_ID: i32 = 0x00;
+const STATUS_RESPONSE_PACKET_ID: i32 = 0x00;
+const PING_PACKET_ID: i32 = 0x01;
+const PONG_PACKET_ID: i32 = 0x01;
+
+/// Handshake intent asking for the status state.
+const NEXT_STATE_STATUS: i32 = 1;
+
+/// Prefix of the data URL servers send their icon as.
+const FAVICON_PREFIX: &str = "data:image/png;base64,";
+
+#[derive(Debug, thiserror::Error)]
+pub enum StatusError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Status query timed out")]
+    Timeout,
+    #[error("Invalid status response: {0}")]
+    InvalidResponse(String),
+    #[error("Invalid status JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    State(#[from] ConnectionStateError),
+    #[error(transparent)]
+    Codec(#[from] CodecError),
+}
+
+/// What a server reports in its status response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerStatus {
+    pub version_name: String,
+    pub protocol_version: i32,
+    pub online_players: u32,
+    pub max_players: u32,
+    /// Message of the day as plain text, with formatting dropped.
+    pub description: String,
+    /// PNG bytes of the server icon, if the server has one.
+    pub favicon: Option<Vec<u8>>,
+}
+
+impl ServerStatus {
+    /// Parse the JSON body of a status response.
+    pub fn from_json(json: &str) -> Result<Self, StatusError> {
+        parse_status_response(json)
+    }
+}
+
+/// Parse the JSON body of a status response. `players` and `favicon` may be
+/// left out, and `description` may be a string or a chat component. An icon
+/// that is not a base64 PNG data URL is dropped, as vanilla does, rather
+/// than failing the whole response.
+pub fn parse_status_response(json: &str) -> Result<ServerStatus, StatusError> {
+    let raw: RawStatus = serde_json::from_str(json)?;
+    let mut description = String::new();
+    flatten_text(&raw.description, &mut description);
+    Ok(ServerStatus {
+        version_name: raw.version.name,
+        protocol_version: raw.version.protocol,
+        online_players: raw.players.online,
+        max_players: raw.players.max,
+        description,
+        favicon: raw.favicon.as_deref().and_then(decode_favicon),
+    })
+}
+
+/// PNG bytes of a `data:image/png;base64,...` icon. Some servers wrap the
+/// base64 in lines, so whitespace is skipped.
+fn decode_favicon(data_url: &str) -> Option<Vec<u8>> {
+    let encoded: String = data_url
+        .strip_prefix(FAVICON_PREFIX)?
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .ok()
+}
+
+#[derive(Deserialize)]
+struct RawStatus {
+    version: RawVersion,
+    #[serde(default)]
+    players: RawPlayers,
+    #[serde(default)]
+    description: serde_json::Value,
+    favicon: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawVersion {
+    name: String,
+    protocol: i32,
+}
+
+#[derive(Deserialize, Default)]
+struct RawPlayers {
+    online: u32,
+    max: u32,
+}
+
+/// Append the text of a chat component: a plain string, an object with
+/// `text` (or a `translate` key) and `extra`, or an array of components.
+pub(crate) fn flatten_text(component: &serde_json::Value, out: &mut String) {
+    match component {
+        serde_json::Value::String(text) => out.push_str(text),
+        serde_json::Value::Array(parts) => {
+            for part in parts {
+                flatten_text(part, out);
+            }
+        }
+        serde_json::Value::Object(fields) => {
+            if let Some(text) = fields.get("text") {
+                flatten_text(text, out);
+            } else if let Some(serde_json::Value::String(key)) = fields.get("translate") {
+                // No translations here; the key at least says what happened
+                out.push_str(key);
+            }
+            if let Some(extra) = fields.get("extra") {
+                flatten_text(extra, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Query `addr` for its status and measure the round trip of a ping.
+///
+/// Performs the handshake, status request and ping/pong, then closes the
+/// connection. The whole exchange must finish within `timeout`. The returned
+/// latency is the time from sending the ping to receiving the pong.
+pub async fn query_status(
+    addr: SocketAddr,
+    timeout: Duration,
+) -> Result<(ServerStatus, Duration), StatusError> {
+    tokio::time::timeout(timeout, exchange(addr))
+        .await
+        .map_err(|_| StatusError::Timeout)?
+}
+
+async fn exchange(addr: SocketAddr) -> Result<(ServerStatus, Duration), StatusError> {
+    let mut stream = TcpStream::connect(addr).await?;
+    stream.set_nodelay(true)?;
+    let mut state = ConnectionState::new();
+    let mut codec = CodecStack::new();
+
+    let mut handshake = Vec::new();
+    write_varint(&mut handshake, STATUS_PROTOCOL_VERSION);
+    write_string(&mut handshake, &addr.ip().to_string());
+    handshake.extend_from_slice(&addr.port().to_be_bytes());
+    write_varint(&mut handshake, NEXT_STATE_STATUS);
+    write_packet(&mut stream, &mut codec, HANDSHAKE_PACKET_ID, &handshake).await?;
+    state.transition_to_status()?;
+
+    write_packet(&mut stream, &mut codec, STATUS_REQUEST_PACKET_ID, &[]).await?;
+    let response = read_packet(&mut stream, &mut codec, STATUS_RESPONSE_PACKET_ID).await?;
+    let json = read_string(&mut response.as_slice())?;
+    let status = parse_status_response(&json)?;
+
+    let payload = ping_payload();
+    let sent = Instant::now();
+    write_packet(
+        &mut stream,
+        &mut codec,
+        PING_PACKET_ID,
+        &payload.to_be_bytes(),
+    )
+    .await?;
+    let pong = read_packet(&mut stream, &mut codec, PONG_PACKET_ID).await?;
+    let latency = sent.elapsed();
+    if pong.as_slice() != payload.to_be_bytes() {
+        return Err(StatusError::InvalidResponse(
+            "pong payload does not match ping".into(),
+        ));
+    }
+
+    stream.shutdown().await?;
+    Ok((status, latency))
+}
+
+/// Ping payload. Vanilla sends the current time in milliseconds; the server
+/// only echoes it back.
+fn ping_payload() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as i64)
+}
+
+async fn write_packet(
+    stream: &mut TcpStream,
+    codec: &mut CodecStack,
+    id: i32,
+    data: &[u8],
+) -> Result<(), StatusError> {
+    let mut packet = Vec::with_capacity(data.len() + 5);
+    write_varint(&mut packet, id);
+    packet.extend_from_slice(data);
+    stream.write_all(&codec.encode(&packet)?).await?;
+    Ok(())
+}
+
+/// Read one packet and return its data, failing if its id is not `expected_id`.
+async fn read_packet(
+    stream: &mut TcpStream,
+    codec: &mut CodecStack,
+    expected_id: i32,
+) -> Result<Vec<u8>, StatusError> {
+    let packet = loop {
+        if let Some(packet) = codec.next_packet()? {
+            break packet;
+        }
+        let mut buf = [0; 4096];
+        let read = stream.read(&mut buf).await?;
+        if read == 0 {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
+        codec.feed(&buf[..read]);
+    };
+
+    let mut cursor = packet.as_slice();
+    let id = read_varint(&mut cursor)?;
+    if id != expected_id {
+        return Err(StatusError::InvalidResponse(format!(
+            "expected packet {expected_id:#04x}, got {id:#04x}"
+        )));
+    }
+    Ok(cursor.to_vec())
+}
+
+pub(crate) fn write_string(buf: &mut Vec<u8>, value: &str) {
+    write_varint(buf, value.len() as i32);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+fn read_varint(buf: &mut &[u8]) -> Result<i32, StatusError> {
+    crate::codec::read_varint(buf)?
+        .ok_or_else(|| StatusError::InvalidResponse("truncated VarInt".into()))
+}
+
+pub(crate) fn read_string(buf: &mut &[u8]) -> Result<String, StatusError> {
+    let length = read_varint(buf)?;
+    let length = usize::try_from(length)
+        .ok()
+        .filter(|&length| length <= buf.len())
+        .ok_or_else(|| StatusError::InvalidResponse(format!("bad string length {length}")))?;
+    let (text, rest) = buf.split_at(length);
+    *buf = rest;
+    String::from_utf8(text.to_vec())
+        .map_err(|_| StatusError::InvalidResponse("string is not UTF-8".into()))
+}
@@ -0,0 +1,61 @@
+use ferrum_protocol::{decode_brand, encode_brand, ChannelRegistry, BRAND_CHANNEL, CLIENT_BRAND};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_registered_channel_receives_payload() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let mut channels = ChannelRegistry::new();
+    let sink = received.clone();
+    channels.register("example:sync", move |payload| {
+        sink.lock().unwrap().push(payload.to_vec());
+        None
+    });
+
+    assert!(channels.is_registered("example:sync"));
+    assert_eq!(channels.dispatch("example:sync", &[1, 2, 3]), None);
+    assert_eq!(*received.lock().unwrap(), vec![vec![1, 2, 3]]);
+}
+
+#[test]
+fn test_unregistered_channel_is_ignored() {
+    let received = Arc::new(Mutex::new(0));
+    let mut channels = ChannelRegistry::new();
+    let count = received.clone();
+    channels.register("example:sync", move |_| {
+        *count.lock().unwrap() += 1;
+        Some(vec![0])
+    });
+
+    assert_eq!(channels.dispatch("example:other", &[9]), None);
+    assert_eq!(*received.lock().unwrap(), 0);
+
+    assert!(channels.unregister("example:sync"));
+    assert_eq!(channels.dispatch("example:sync", &[9]), None);
+    assert_eq!(*received.lock().unwrap(), 0);
+}
+
+#[test]
+fn test_brand_request_is_answered() {
+    let mut channels = ChannelRegistry::with_brand(CLIENT_BRAND);
+    let server_brand = encode_brand("vanilla");
+
+    let reply = channels.dispatch(BRAND_CHANNEL, &server_brand).unwrap();
+    assert_eq!(decode_brand(&reply).as_deref(), Some(CLIENT_BRAND));
+    assert_eq!(channels.channels().collect::<Vec<_>>(), [BRAND_CHANNEL]);
+}
+
+#[test]
+fn test_brand_round_trip() {
+    let data = encode_brand("ferrum");
+    assert_eq!(data, b"\x06ferrum");
+    assert_eq!(decode_brand(&data).as_deref(), Some("ferrum"));
+    assert_eq!(decode_brand(b"\x09short"), None);
+}
+
+#[test]
+fn test_register_replaces_handler() {
+    let mut channels = ChannelRegistry::new();
+    assert!(!channels.register("example:echo", |_| Some(vec![1])));
+    assert!(channels.register("example:echo", |payload| Some(payload.to_vec())));
+    assert_eq!(channels.dispatch("example:echo", &[7, 8]), Some(vec![7, 8]));
+}
@@ -0,0 +1,113 @@
+use ferrum_protocol::{CodecError, CodecStack, Compression, Framing};
+
+const SECRET: [u8; 16] = *b"0123456789abcdef";
+
+/// A small packet and one large enough to compress.
+fn packets() -> Vec<Vec<u8>> {
+    let small = vec![0x01, 0x02, 0x03];
+    let large: Vec<u8> = (0..4096u32).map(|i| (i % 7) as u8).collect();
+    vec![small, large, Vec::new()]
+}
+
+/// Encode every packet with `sender`, deliver the bytes to `receiver` in
+/// uneven pieces, and check the packets come out unchanged.
+fn round_trip(sender: &mut CodecStack, receiver: &mut CodecStack) -> Vec<u8> {
+    let packets = packets();
+    let mut wire = Vec::new();
+    for packet in &packets {
+        wire.extend(sender.encode(packet).unwrap());
+    }
+
+    let mut received = Vec::new();
+    for piece in wire.chunks(37) {
+        receiver.feed(piece);
+        while let Some(packet) = receiver.next_packet().unwrap() {
+            received.push(packet);
+        }
+    }
+    assert_eq!(received, packets);
+    wire
+}
+
+fn stack(threshold: Option<i32>, encrypted: bool) -> CodecStack {
+    let mut stack = CodecStack::new();
+    if let Some(threshold) = threshold {
+        stack.set_compression_threshold(threshold);
+    }
+    if encrypted {
+        stack.enable_encryption(&SECRET);
+    }
+    stack
+}
+
+#[test]
+fn test_framing_round_trip() {
+    let wire = round_trip(&mut stack(None, false), &mut stack(None, false));
+    assert_eq!(&wire[..4], &[3, 1, 2, 3]);
+}
+
+#[test]
+fn test_framing_with_compression_round_trip() {
+    let wire = round_trip(&mut stack(Some(256), false), &mut stack(Some(256), false));
+
+    // Below the threshold: data length 0, then the packet as is
+    assert_eq!(&wire[..5], &[4, 0, 1, 2, 3]);
+    // The large packet shrinks
+    assert!(wire.len() < 1024);
+}
+
+#[test]
+fn test_framing_with_encryption_round_trip() {
+    let wire = round_trip(&mut stack(None, true), &mut stack(None, true));
+    assert_ne!(&wire[..4], &[3, 1, 2, 3]);
+}
+
+#[test]
+fn test_full_stack_round_trip() {
+    let mut client = stack(Some(64), true);
+    let mut server = stack(Some(64), true);
+    round_trip(&mut client, &mut server);
+    // The cipher streams stay in step across batches
+    round_trip(&mut client, &mut server);
+}
+
+#[test]
+fn test_negative_threshold_disables_compression() {
+    let mut stack = stack(Some(64), false);
+    assert_eq!(stack.compression().map(Compression::threshold), Some(64));
+    stack.set_compression_threshold(-1);
+    assert!(stack.compression().is_none());
+}
+
+#[test]
+fn test_partial_frame_waits_for_more_bytes() {
+    let mut buf = Vec::new();
+    Framing.encode(&[7; 300], &mut buf).unwrap();
+    let mut partial = buf[..100].to_vec();
+    assert!(Framing.decode(&mut partial).unwrap().is_none());
+    assert_eq!(partial.len(), 100);
+
+    let body = Framing.decode(&mut buf).unwrap().unwrap();
+    assert_eq!(body, vec![7; 300]);
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn test_compressed_packet_below_threshold_is_rejected() {
+    let lenient = Compression::new(0);
+    let strict = Compression::new(256);
+    let body = lenient.compress(&[1; 16]).unwrap();
+    assert!(matches!(
+        strict.decompress(&body),
+        Err(CodecError::BadCompression(_))
+    ));
+}
+
+#[test]
+fn test_oversized_frame_length_is_rejected() {
+    let mut buf = vec![0xFF, 0xFF, 0xFF, 0x7F];
+    assert!(matches!(
+        Framing.decode(&mut buf),
+        Err(CodecError::BadLength(_))
+    ));
+}
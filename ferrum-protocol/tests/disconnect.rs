@@ -0,0 +1,170 @@
+use ferrum_protocol::{ConnectionState, DisconnectError, ProtocolState};
+
+/// Minimal network NBT writer, enough for chat components.
+enum Tag {
+    Byte(i8),
+    String(&'static str),
+    List(Vec<Tag>),
+    Compound(Vec<(&'static str, Tag)>),
+}
+
+impl Tag {
+    fn id(&self) -> u8 {
+        match self {
+            Tag::Byte(_) => 1,
+            Tag::String(_) => 8,
+            Tag::List(_) => 9,
+            Tag::Compound(_) => 10,
+        }
+    }
+
+    fn write_payload(&self, buf: &mut Vec<u8>) {
+        match self {
+            Tag::Byte(value) => buf.push(*value as u8),
+            Tag::String(text) => {
+                buf.extend_from_slice(&(text.len() as u16).to_be_bytes());
+                buf.extend_from_slice(text.as_bytes());
+            }
+            Tag::List(elements) => {
+                buf.push(elements.first().map_or(0, Tag::id));
+                buf.extend_from_slice(&(elements.len() as i32).to_be_bytes());
+                for element in elements {
+                    element.write_payload(buf);
+                }
+            }
+            Tag::Compound(fields) => {
+                for (name, tag) in fields {
+                    buf.push(tag.id());
+                    Tag::String(name).write_payload(buf);
+                    tag.write_payload(buf);
+                }
+                buf.push(0);
+            }
+        }
+    }
+
+    /// Unnamed root tag, as sent since 1.20.2.
+    fn to_network(&self) -> Vec<u8> {
+        let mut buf = vec![self.id()];
+        self.write_payload(&mut buf);
+        buf
+    }
+}
+
+fn play_state() -> ConnectionState {
+    let mut state = ConnectionState::new();
+    state.transition_to_login().unwrap();
+    state.transition_to_config().unwrap();
+    state.transition_to_play().unwrap();
+    state
+}
+
+#[test]
+fn test_play_disconnect_reason_and_close() {
+    let reason = Tag::Compound(vec![
+        ("text", Tag::String("Kicked: ")),
+        ("bold", Tag::Byte(1)),
+        (
+            "extra",
+            Tag::List(vec![
+                Tag::Compound(vec![("text", Tag::String("flying is not enabled"))]),
+                Tag::Compound(vec![("text", Tag::String(" on this server"))]),
+            ]),
+        ),
+    ]);
+    let mut state = play_state();
+    let disconnected = state.handle_disconnect(&reason.to_network()).unwrap();
+    assert_eq!(
+        disconnected.reason,
+        "Kicked: flying is not enabled on this server"
+    );
+    assert_eq!(state.current(), ProtocolState::Disconnected);
+}
+
+#[test]
+fn test_play_disconnect_plain_string_and_translate() {
+    let mut state = play_state();
+    let disconnected = state
+        .handle_disconnect(&Tag::String("Server closed").to_network())
+        .unwrap();
+    assert_eq!(disconnected.reason, "Server closed");
+
+    let mut state = play_state();
+    let reason = Tag::Compound(vec![(
+        "translate",
+        Tag::String("multiplayer.disconnect.kicked"),
+    )]);
+    let disconnected = state.handle_disconnect(&reason.to_network()).unwrap();
+    assert_eq!(disconnected.reason, "multiplayer.disconnect.kicked");
+}
+
+#[test]
+fn test_login_disconnect_reads_json() {
+    let json = r#"{"text":"You are not whitelisted ","extra":["on this server"]}"#;
+    let mut data = vec![json.len() as u8];
+    data.extend_from_slice(json.as_bytes());
+
+    let mut state = ConnectionState::new();
+    state.transition_to_login().unwrap();
+    let disconnected = state.handle_disconnect(&data).unwrap();
+    assert_eq!(
+        disconnected.reason,
+        "You are not whitelisted on this server"
+    );
+    assert_eq!(state.current(), ProtocolState::Disconnected);
+}
+
+#[test]
+fn test_kick_ends_like_leaving_not_like_closing() {
+    let mut kicked = play_state();
+    kicked
+        .handle_disconnect(&Tag::String("Server closed").to_network())
+        .unwrap();
+    let mut left = play_state();
+    left.disconnect().unwrap();
+    let mut dropped = play_state();
+    dropped.close();
+
+    assert_eq!(kicked.current(), left.current());
+    assert_eq!(kicked.current(), ProtocolState::Disconnected);
+    assert_eq!(dropped.current(), ProtocolState::Closed);
+
+    // Both are final until reset
+    assert!(kicked.disconnect().is_err());
+    assert!(matches!(
+        kicked.handle_disconnect(&Tag::String("Again").to_network()),
+        Err(DisconnectError::UnexpectedState(
+            ProtocolState::Disconnected
+        ))
+    ));
+    assert!(dropped.transition_to_login().is_err());
+}
+
+#[test]
+fn test_malformed_disconnect_keeps_state() {
+    let mut data = Tag::Compound(vec![("text", Tag::String("Bye"))]).to_network();
+    data.truncate(data.len() - 3);
+
+    let mut state = play_state();
+    assert!(matches!(
+        state.handle_disconnect(&data),
+        Err(DisconnectError::Malformed(_))
+    ));
+    assert_eq!(state.current(), ProtocolState::Play);
+}
+
+#[test]
+fn test_disconnect_outside_connected_states() {
+    let mut state = ConnectionState::new();
+    assert!(matches!(
+        state.handle_disconnect(&[]),
+        Err(DisconnectError::UnexpectedState(ProtocolState::Handshake))
+    ));
+
+    state.close();
+    assert_eq!(state.current(), ProtocolState::Closed);
+    assert!(matches!(
+        state.handle_disconnect(&[]),
+        Err(DisconnectError::UnexpectedState(ProtocolState::Closed))
+    ));
+}
@@ -74,6 +74,121 @@ fn test_connection_state_invalid_transition_status_to_login() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_connection_state_compression_threshold_boundary() {
+    let mut state = ConnectionState::new();
+    assert_eq!(state.compression_threshold(), None);
+    assert!(!state.should_compress(1_000_000));
+
+    state.transition_to_login().unwrap();
+    state.enable_compression(256).unwrap();
+    assert_eq!(state.compression_threshold(), Some(256));
+    assert!(!state.should_compress(255));
+    assert!(state.should_compress(256));
+    assert!(state.should_compress(257));
+
+    // A negative threshold turns compression back off
+    state.transition_to_config().unwrap();
+    state.enable_compression(-1).unwrap();
+    assert_eq!(state.compression_threshold(), None);
+    assert!(!state.should_compress(256));
+}
+
+#[test]
+fn test_connection_state_rejects_compression_before_login() {
+    let mut state = ConnectionState::new();
+    let result = state.enable_compression(256);
+    assert!(matches!(
+        result,
+        Err(ConnectionStateError::CompressionNotAllowed(
+            ferrum_protocol::ProtocolState::Handshake
+        ))
+    ));
+    assert_eq!(state.compression_threshold(), None);
+
+    state.transition_to_status().unwrap();
+    assert!(state.enable_compression(256).is_err());
+}
+
+#[test]
+fn test_connection_state_mark_encrypted() {
+    let mut state = ConnectionState::new();
+    assert!(!state.is_encrypted());
+    state.transition_to_login().unwrap();
+    state.mark_encrypted();
+    assert!(state.is_encrypted());
+    state.transition_to_config().unwrap();
+    assert!(state.is_encrypted());
+}
+
+#[test]
+fn test_connection_state_reset_from_play() {
+    let mut state = ConnectionState::new();
+    state.transition_to_login().unwrap();
+    state.mark_encrypted();
+    state.enable_compression(256).unwrap();
+    state.transition_to_config().unwrap();
+    state.transition_to_play().unwrap();
+
+    state.reset();
+    assert_eq!(state.current(), ferrum_protocol::ProtocolState::Handshake);
+    assert_eq!(state.compression_threshold(), None);
+    assert!(!state.is_encrypted());
+
+    // The same state machine carries the reconnect
+    state.transition_to_login().unwrap();
+    assert_eq!(state.current(), ferrum_protocol::ProtocolState::Login);
+}
+
+#[test]
+fn test_connection_state_abandon_and_restart_handshake() {
+    let mut state = ConnectionState::new();
+    state.transition_to_status().unwrap();
+    state.reset();
+    state.transition_to_login().unwrap();
+
+    // Dropped partway through login, then tried again
+    state.enable_compression(64).unwrap();
+    state.reset();
+    assert_eq!(state.current(), ferrum_protocol::ProtocolState::Handshake);
+    state.transition_to_login().unwrap();
+    state.transition_to_config().unwrap();
+    assert_eq!(state.current(), ferrum_protocol::ProtocolState::Config);
+}
+
+#[test]
+fn test_connection_state_disconnect() {
+    let mut state = ConnectionState::new();
+    match state.disconnect() {
+        Err(ConnectionStateError::InvalidTransition { from, to }) => {
+            assert_eq!(from, ferrum_protocol::ProtocolState::Handshake);
+            assert_eq!(to, ferrum_protocol::ProtocolState::Disconnected);
+        }
+        _ => panic!("Expected InvalidTransition error"),
+    }
+
+    state.transition_to_login().unwrap();
+    state.transition_to_config().unwrap();
+    state.transition_to_play().unwrap();
+    state.disconnect().unwrap();
+    assert_eq!(
+        state.current(),
+        ferrum_protocol::ProtocolState::Disconnected
+    );
+
+    // Nothing moves on from a teardown except a reset
+    assert!(state.disconnect().is_err());
+    assert!(state.transition_to_login().is_err());
+    state.reset();
+    state.transition_to_login().unwrap();
+
+    let mut state = ConnectionState::new();
+    state.transition_to_status().unwrap();
+    assert!(state.disconnect().is_err());
+    state.close();
+    assert!(state.disconnect().is_err());
+}
+
 #[tokio::test]
 async fn test_packet_type_aliases_exist() {
     // This test verifies that type aliases compile and are accessible
@@ -0,0 +1,178 @@
+use ferrum_protocol::{parse_status_response, query_status, ServerStatus, StatusError};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const STATUS_JSON: &str = r#"{
+    "version": {"name": "1.21.4", "protocol": 769},
+    "players": {"max": 20, "online": 3},
+    "description": {"text": "A ", "extra": [{"text": "Ferrum"}, " server"]}
+}"#;
+
+fn write_varint(buf: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7F == 0 {
+            buf.push(value as u8);
+            return;
+        }
+        buf.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+}
+
+async fn read_varint(stream: &mut TcpStream) -> i32 {
+    let mut value = 0u32;
+    for shift in (0..35).step_by(7) {
+        let byte = stream.read_u8().await.unwrap();
+        value |= ((byte & 0x7F) as u32) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+    value as i32
+}
+
+/// Read a packet, returning its id byte and data.
+async fn read_packet(stream: &mut TcpStream) -> (u8, Vec<u8>) {
+    let length = read_varint(stream).await as usize;
+    let mut body = vec![0; length];
+    stream.read_exact(&mut body).await.unwrap();
+    (body[0], body[1..].to_vec())
+}
+
+async fn write_packet(stream: &mut TcpStream, id: u8, data: &[u8]) {
+    let mut frame = Vec::new();
+    write_varint(&mut frame, data.len() as i32 + 1);
+    frame.push(id);
+    frame.extend_from_slice(data);
+    stream.write_all(&frame).await.unwrap();
+}
+
+/// Serve one status query, waiting `pong_delay` before answering the ping.
+async fn mock_server(pong_delay: Duration) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        let (id, handshake) = read_packet(&mut stream).await;
+        assert_eq!(id, 0x00);
+        assert_eq!(*handshake.last().unwrap(), 1, "handshake asks for status");
+
+        let (id, _) = read_packet(&mut stream).await;
+        assert_eq!(id, 0x00);
+        let mut response = Vec::new();
+        write_varint(&mut response, STATUS_JSON.len() as i32);
+        response.extend_from_slice(STATUS_JSON.as_bytes());
+        write_packet(&mut stream, 0x00, &response).await;
+
+        let (id, payload) = read_packet(&mut stream).await;
+        assert_eq!(id, 0x01);
+        tokio::time::sleep(pong_delay).await;
+        write_packet(&mut stream, 0x01, &payload).await;
+
+        let mut rest = Vec::new();
+        stream.read_to_end(&mut rest).await.unwrap();
+        assert!(rest.is_empty(), "client closes after the pong");
+    });
+    addr
+}
+
+#[tokio::test]
+async fn test_query_status_returns_status_and_latency() {
+    let addr = mock_server(Duration::from_millis(50)).await;
+
+    let (status, latency) = query_status(addr, Duration::from_secs(5)).await.unwrap();
+
+    assert_eq!(status.version_name, "1.21.4");
+    assert_eq!(status.protocol_version, 769);
+    assert_eq!(status.online_players, 3);
+    assert_eq!(status.max_players, 20);
+    assert_eq!(status.description, "A Ferrum server");
+    assert_eq!(status.favicon, None);
+    assert!(latency >= Duration::from_millis(50), "latency {latency:?}");
+    assert!(latency < Duration::from_secs(5), "latency {latency:?}");
+}
+
+#[tokio::test]
+async fn test_query_status_times_out() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (_stream, _) = listener.accept().await.unwrap();
+        tokio::time::sleep(Duration::from_secs(10)).await;
+    });
+
+    let result = query_status(addr, Duration::from_millis(100)).await;
+    assert!(matches!(result, Err(StatusError::Timeout)));
+}
+
+#[test]
+fn test_status_plain_description() {
+    let status = ServerStatus::from_json(
+        r#"{"version":{"name":"Paper 1.21","protocol":767},"description":"Hello","favicon":"data:image/png;base64,AAAA"}"#,
+    )
+    .unwrap();
+
+    assert_eq!(status.description, "Hello");
+    assert_eq!(status.online_players, 0);
+    assert_eq!(status.favicon, Some(vec![0, 0, 0]));
+}
+
+#[test]
+fn test_parse_minimal_status_response() {
+    // No players, sample or favicon at all
+    let status =
+        parse_status_response(r#"{"version":{"name":"1.21.4","protocol":769},"description":""}"#)
+            .unwrap();
+
+    assert_eq!(status.version_name, "1.21.4");
+    assert_eq!(status.protocol_version, 769);
+    assert_eq!((status.online_players, status.max_players), (0, 0));
+    assert_eq!(status.description, "");
+    assert_eq!(status.favicon, None);
+
+    // Players without a sample
+    let status = parse_status_response(
+        r#"{"version":{"name":"1.21.4","protocol":769},"players":{"max":100,"online":7}}"#,
+    )
+    .unwrap();
+    assert_eq!((status.online_players, status.max_players), (7, 100));
+
+    assert!(matches!(
+        parse_status_response(r#"{"description":"no version"}"#),
+        Err(StatusError::Json(_))
+    ));
+}
+
+#[test]
+fn test_parse_status_description_component_and_favicon() {
+    let status = parse_status_response(
+        r#"{
+            "version": {"name": "Paper 1.21.4", "protocol": 769},
+            "players": {
+                "max": 50,
+                "online": 2,
+                "sample": [{"name": "Steve", "id": "069a79f4-44e9-4726-a5be-fca90e38aaf5"}]
+            },
+            "description": {
+                "text": "",
+                "extra": [{"text": "Ferrum", "bold": true}, {"text": " test", "color": "gray"}]
+            },
+            "favicon": "data:image/png;base64,iVBO\nRw0KGgo="
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(status.description, "Ferrum test");
+    assert_eq!(status.favicon.as_deref(), Some(&b"\x89PNG\r\n\x1a\n"[..]));
+
+    // An icon that is not a PNG data URL is dropped, not an error
+    let status = parse_status_response(
+        r#"{"version":{"name":"1.21.4","protocol":769},"favicon":"data:image/gif;base64,AAAA"}"#,
+    )
+    .unwrap();
+    assert_eq!(status.favicon, None);
+}
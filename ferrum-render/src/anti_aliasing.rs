@@ -0,0 +1,47 @@
+//! Camera anti-aliasing driven by `client.anti_aliasing` in the config.
+
+use bevy::anti_alias::fxaa::Fxaa;
+use bevy::prelude::*;
+use ferrum_config::{AntiAliasing, Config};
+
+/// MSAA sample count and optional FXAA pass for a mode. FXAA runs as a
+/// post-process on the resolved image, so MSAA is turned off with it.
+pub fn camera_anti_aliasing(mode: AntiAliasing) -> (Msaa, Option<Fxaa>) {
+    match mode {
+        AntiAliasing::Off => (Msaa::Off, None),
+        AntiAliasing::Fxaa => (Msaa::Off, Some(Fxaa::default())),
+        AntiAliasing::Msaa2 => (Msaa::Sample2, None),
+        AntiAliasing::Msaa4 => (Msaa::Sample4, None),
+    }
+}
+
+/// Apply the configured mode to new 3D cameras, and to every 3D camera when
+/// the config is reloaded.
+pub fn apply_anti_aliasing(
+    mut commands: Commands,
+    config: Res<Config>,
+    cameras: Query<(Entity, Ref<Camera3d>)>,
+) {
+    let reapply = config.is_changed();
+    let (msaa, fxaa) = camera_anti_aliasing(config.client.anti_aliasing_mode());
+
+    for (entity, camera) in &cameras {
+        if !reapply && !camera.is_added() {
+            continue;
+        }
+        let mut camera = commands.entity(entity);
+        camera.insert(msaa);
+        match fxaa.clone() {
+            Some(fxaa) => camera.insert(fxaa),
+            None => camera.remove::<Fxaa>(),
+        };
+    }
+}
+
+pub struct AntiAliasingPlugin;
+
+impl Plugin for AntiAliasingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, apply_anti_aliasing);
+    }
+}
@@ -0,0 +1,67 @@
+//! Swapping the block atlas image at runtime, e.g. after a resource pack
+//! reload.
+//!
+//! Chunk meshes only store UVs, and [`TextureAtlas`](crate::TextureAtlas)
+//! places every block's tiles at fixed positions. As long as a rebuilt atlas
+//! keeps that layout, pointing the materials at the new image is enough for
+//! every loaded chunk to pick up the new textures without being remeshed.
+
+use crate::item_icons::ItemIcons;
+use bevy::prelude::*;
+
+/// Sent when the block atlas image is replaced by `new`. Every material
+/// drawing with `old` is switched over to `new`.
+#[derive(Message, Debug, Clone)]
+pub struct AtlasImageSwapped {
+    pub old: Handle<Image>,
+    pub new: Handle<Image>,
+}
+
+/// Point every material textured with `old` at `new` instead, returning how
+/// many were changed. Other materials are left untouched, so the renderer
+/// doesn't re-upload them.
+pub fn swap_atlas_image(
+    materials: &mut Assets<StandardMaterial>,
+    old: &Handle<Image>,
+    new: &Handle<Image>,
+) -> usize {
+    let old = Some(old.id());
+    let textured: Vec<_> = materials
+        .iter()
+        .filter(|(_, material)| material.base_color_texture.as_ref().map(Handle::id) == old)
+        .map(|(id, _)| id)
+        .collect();
+    for &id in &textured {
+        if let Some(material) = materials.get_mut(id) {
+            material.base_color_texture = Some(new.clone());
+        }
+    }
+    textured.len()
+}
+
+/// Apply atlas swaps to the materials. Block item icons were painted from
+/// the old atlas, so they are dropped to be painted again.
+pub fn apply_atlas_swaps(
+    mut swaps: MessageReader<AtlasImageSwapped>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    icons: Option<ResMut<ItemIcons>>,
+) {
+    let mut swapped = false;
+    for swap in swaps.read() {
+        let count = swap_atlas_image(&mut materials, &swap.old, &swap.new);
+        info!("Swapped the block atlas in {} materials", count);
+        swapped = true;
+    }
+    if let (true, Some(mut icons)) = (swapped, icons) {
+        icons.clear();
+    }
+}
+
+pub struct AtlasSwapPlugin;
+
+impl Plugin for AtlasSwapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<AtlasImageSwapped>()
+            .add_systems(Update, apply_atlas_swaps);
+    }
+}
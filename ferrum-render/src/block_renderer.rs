@@ -2,109 +2,438 @@ use crate::texture_atlas::TextureAtlas;
 use bevy::asset::RenderAssetUsages;
 use bevy::mesh::{Indices, PrimitiveTopology};
 use bevy::prelude::*;
-use ferrum_meshing_cpu::{ChunkMesh, Face};
+use ferrum_core::{is_fluid, properties, BlockState, MAX_LIGHT_EMISSION};
+use ferrum_meshing_cpu::{ChunkMesh, Face, MeshQuad, CHUNK_SIZE, CHUNK_SIZE_CB, CHUNK_SIZE_SQ};
+use std::collections::BTreeMap;
+
+/// Vertex brightness by how many of a corner's three neighbors (two sides
+/// and the diagonal) are solid.
+pub const AO_BRIGHTNESS: [f32; 4] = [1.0, 0.8, 0.65, 0.5];
 
 pub struct BlockRenderer;
 
 impl BlockRenderer {
     pub fn create_mesh(chunk_mesh: &ChunkMesh, atlas: &TextureAtlas) -> Mesh {
-        let mut positions = Vec::new();
-        let mut normals = Vec::new();
-        let mut uvs = Vec::new();
-        let mut indices = Vec::new();
-
-        let mut vertex_count = 0u32;
+        Self::create_mesh_at(chunk_mesh, atlas, IVec3::ZERO)
+    }
 
+    /// Like [`create_mesh`](Self::create_mesh), but every quad is wound
+    /// counter-clockwise when seen from the side its normal points to, so
+    /// back-face culling keeps exactly the visible side. Each quad has four
+    /// vertices, shared by its two triangles through the index buffer.
+    pub fn create_indexed_mesh(chunk_mesh: &ChunkMesh, atlas: &TextureAtlas) -> Mesh {
+        let mut buffers = MeshBuffers::default();
         for quad in &chunk_mesh.quads {
-            let base_pos = [quad.x as f32, quad.y as f32, quad.z as f32];
-            let width = quad.width as f32;
-            let height = quad.height as f32;
-
-            let (quad_positions, normal) = match quad.face {
-                Face::Up => (
-                    [
-                        [base_pos[0], base_pos[1] + 1.0, base_pos[2]],
-                        [base_pos[0] + width, base_pos[1] + 1.0, base_pos[2]],
-                        [base_pos[0] + width, base_pos[1] + 1.0, base_pos[2] + height],
-                        [base_pos[0], base_pos[1] + 1.0, base_pos[2] + height],
-                    ],
-                    [0.0, 1.0, 0.0],
-                ),
-                Face::Down => (
-                    [
-                        [base_pos[0], base_pos[1], base_pos[2] + height],
-                        [base_pos[0] + width, base_pos[1], base_pos[2] + height],
-                        [base_pos[0] + width, base_pos[1], base_pos[2]],
-                        [base_pos[0], base_pos[1], base_pos[2]],
-                    ],
-                    [0.0, -1.0, 0.0],
-                ),
-                Face::Right => (
-                    [
-                        [base_pos[0] + 1.0, base_pos[1], base_pos[2]],
-                        [base_pos[0] + 1.0, base_pos[1], base_pos[2] + width],
-                        [base_pos[0] + 1.0, base_pos[1] + height, base_pos[2] + width],
-                        [base_pos[0] + 1.0, base_pos[1] + height, base_pos[2]],
-                    ],
-                    [1.0, 0.0, 0.0],
-                ),
-                Face::Left => (
-                    [
-                        [base_pos[0], base_pos[1], base_pos[2] + width],
-                        [base_pos[0], base_pos[1], base_pos[2]],
-                        [base_pos[0], base_pos[1] + height, base_pos[2]],
-                        [base_pos[0], base_pos[1] + height, base_pos[2] + width],
-                    ],
-                    [-1.0, 0.0, 0.0],
-                ),
-                Face::Front => (
-                    [
-                        [base_pos[0], base_pos[1], base_pos[2] + 1.0],
-                        [base_pos[0] + width, base_pos[1], base_pos[2] + 1.0],
-                        [base_pos[0] + width, base_pos[1] + height, base_pos[2] + 1.0],
-                        [base_pos[0], base_pos[1] + height, base_pos[2] + 1.0],
-                    ],
-                    [0.0, 0.0, 1.0],
-                ),
-                Face::Back => (
-                    [
-                        [base_pos[0] + width, base_pos[1], base_pos[2]],
-                        [base_pos[0], base_pos[1], base_pos[2]],
-                        [base_pos[0], base_pos[1] + height, base_pos[2]],
-                        [base_pos[0] + width, base_pos[1] + height, base_pos[2]],
-                    ],
-                    [0.0, 0.0, -1.0],
-                ),
+            if atlas.has_variation(quad.block_type, quad.face) {
+                for cell in unit_quads(quad) {
+                    let pos = IVec3::new(cell.x as i32, cell.y as i32, cell.z as i32);
+                    let uvs = atlas.get_uvs_at(cell.block_type, cell.face, pos);
+                    buffers.push_front_facing_quad(&cell, uvs);
+                }
+            } else {
+                buffers.push_front_facing_quad(quad, atlas.get_uvs(quad.block_type, quad.face));
+            }
+        }
+        buffers.into_mesh()
+    }
+
+    /// Like [`create_indexed_mesh`](Self::create_indexed_mesh), with
+    /// Minecraft-style ambient occlusion from `voxels`, the blocks the mesh was
+    /// built from. Each vertex is darkened by the solid blocks around its
+    /// corner, stored as the vertex color in [`Mesh::ATTRIBUTE_COLOR`].
+    ///
+    /// Merged quads are split into one quad per block wherever any corner is
+    /// occluded, so the shading follows each block. Each quad is split into
+    /// triangles along the diagonal with the brighter ends, so a single dark
+    /// corner doesn't smear along the diagonal.
+    pub fn create_mesh_with_ao(
+        chunk_mesh: &ChunkMesh,
+        atlas: &TextureAtlas,
+        voxels: &[u32; CHUNK_SIZE_CB],
+    ) -> Mesh {
+        let mut buffers = MeshBuffers::default();
+        let mut colors = Vec::new();
+        let mut push = |quad: &MeshQuad, uvs: [[f32; 2]; 4], occluded: bool| {
+            let base = buffers.positions.len() as u32;
+            let (positions, normal, uvs) = front_facing_vertices(quad, uvs);
+            let occlusion = if occluded {
+                positions.map(|corner| corner_occlusion(voxels, quad, normal, corner))
+            } else {
+                [0; 4]
             };
+            buffers.push_vertices(positions, normal, uvs, quad.block_type);
+            if occlusion[0] + occlusion[2] > occlusion[1] + occlusion[3] {
+                let start = buffers.indices.len() - 6;
+                buffers.indices[start..].copy_from_slice(&[
+                    base + 1,
+                    base + 2,
+                    base + 3,
+                    base + 1,
+                    base + 3,
+                    base,
+                ]);
+            }
+            colors.extend(occlusion.map(|level| {
+                let brightness = AO_BRIGHTNESS[level];
+                [brightness, brightness, brightness, 1.0]
+            }));
+        };
 
-            let quad_uvs = atlas.get_uvs(quad.block_type, quad.face);
+        for quad in &chunk_mesh.quads {
+            let occluded = unit_quads(quad).any(|cell| {
+                let (positions, normal) = quad_vertices(&cell);
+                positions
+                    .iter()
+                    .any(|&corner| corner_occlusion(voxels, &cell, normal, corner) > 0)
+            });
+            let variation = atlas.has_variation(quad.block_type, quad.face);
+            if !occluded && !variation {
+                push(quad, atlas.get_uvs(quad.block_type, quad.face), false);
+                continue;
+            }
+            for cell in unit_quads(quad) {
+                let uvs = if variation {
+                    let pos = IVec3::new(cell.x as i32, cell.y as i32, cell.z as i32);
+                    atlas.get_uvs_at(cell.block_type, cell.face, pos)
+                } else {
+                    atlas.get_uvs(cell.block_type, cell.face)
+                };
+                push(&cell, uvs, occluded);
+            }
+        }
 
-            positions.extend_from_slice(&quad_positions);
-            normals.extend_from_slice(&[normal; 4]);
-            uvs.extend_from_slice(&quad_uvs);
+        let mut mesh = buffers.into_mesh();
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        mesh
+    }
+
+    /// Separate the quads of animated blocks from the rest. Returns the static
+    /// quads and, per animated block id, that block's quads in every state,
+    /// so each animated block can be drawn with its own material.
+    pub fn split_animated(
+        chunk_mesh: &ChunkMesh,
+        atlas: &TextureAtlas,
+    ) -> (ChunkMesh, Vec<(u32, ChunkMesh)>) {
+        let mut static_mesh = ChunkMesh::new();
+        let mut animated: BTreeMap<u32, ChunkMesh> = BTreeMap::new();
+        for quad in &chunk_mesh.quads {
+            if atlas.is_animated(quad.block_type) {
+                let block_id = BlockState::from_bits(quad.block_type).id().as_u16() as u32;
+                animated
+                    .entry(block_id)
+                    .or_default()
+                    .quads
+                    .push(quad.clone());
+            } else {
+                static_mesh.quads.push(quad.clone());
+            }
+        }
+        (static_mesh, animated.into_iter().collect())
+    }
+
+    /// Block light given off by a voxel's block, 0 for ordinary blocks.
+    pub fn light_emission(block_type: u32) -> u8 {
+        properties(BlockState::from_bits(block_type).id()).light_emission
+    }
 
-            indices.extend_from_slice(&[
-                vertex_count,
-                vertex_count + 1,
-                vertex_count + 2,
-                vertex_count,
-                vertex_count + 2,
-                vertex_count + 3,
-            ]);
+    /// Emissive color for a material drawing blocks of the given emission,
+    /// scaled so the brightest blocks glow at their full texture color.
+    pub fn emissive_color(light_emission: u8) -> LinearRgba {
+        let strength = light_emission.min(MAX_LIGHT_EMISSION) as f32 / MAX_LIGHT_EMISSION as f32;
+        LinearRgba::rgb(strength, strength, strength)
+    }
 
-            vertex_count += 4;
+    /// Separate the quads of light-emitting blocks from the rest. Returns the
+    /// unlit quads and, per emission level, the quads of blocks with that
+    /// level, so each level can be drawn with a glowing material.
+    pub fn split_emissive(chunk_mesh: &ChunkMesh) -> (ChunkMesh, Vec<(u8, ChunkMesh)>) {
+        let mut unlit = ChunkMesh::new();
+        let mut emissive: BTreeMap<u8, ChunkMesh> = BTreeMap::new();
+        for quad in &chunk_mesh.quads {
+            match Self::light_emission(quad.block_type) {
+                0 => unlit.quads.push(quad.clone()),
+                level => emissive.entry(level).or_default().quads.push(quad.clone()),
+            }
+        }
+        (unlit, emissive.into_iter().collect())
+    }
+
+    /// A material drawing the atlas at full brightness scaled by
+    /// `light_emission`, whatever the light around it.
+    pub fn emissive_material(atlas_texture: Handle<Image>, light_emission: u8) -> StandardMaterial {
+        StandardMaterial {
+            base_color_texture: Some(atlas_texture.clone()),
+            emissive: Self::emissive_color(light_emission),
+            emissive_texture: Some(atlas_texture),
+            ..default()
+        }
+    }
+
+    /// Build a mesh for a chunk whose minimum corner is at `chunk_origin` in
+    /// world block coordinates. Faces with texture variants or random rotation
+    /// are split into one quad per block so each block can pick its own.
+    pub fn create_mesh_at(
+        chunk_mesh: &ChunkMesh,
+        atlas: &TextureAtlas,
+        chunk_origin: IVec3,
+    ) -> Mesh {
+        Self::build_buffers(chunk_mesh, atlas, chunk_origin).into_mesh()
+    }
+
+    /// Vertex and index buffers for [`create_mesh_at`](Self::create_mesh_at).
+    /// Needs no access to the ECS, so it can run off the main thread.
+    pub fn build_buffers(
+        chunk_mesh: &ChunkMesh,
+        atlas: &TextureAtlas,
+        chunk_origin: IVec3,
+    ) -> MeshBuffers {
+        let mut buffers = MeshBuffers::default();
+
+        for quad in &chunk_mesh.quads {
+            if atlas.has_variation(quad.block_type, quad.face) {
+                for cell in unit_quads(quad) {
+                    let pos =
+                        chunk_origin + IVec3::new(cell.x as i32, cell.y as i32, cell.z as i32);
+                    buffers.push_quad(&cell, atlas.get_uvs_at(cell.block_type, cell.face, pos));
+                }
+            } else {
+                buffers.push_quad(quad, atlas.get_uvs(quad.block_type, quad.face));
+            }
         }
 
+        buffers
+    }
+}
+
+/// Vertex and index data of a chunk mesh, ready to become a [`Mesh`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeshBuffers {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    /// Block type of each vertex. Quads split per block give more vertices
+    /// than the chunk mesh has quads, so this is the way back to the block.
+    pub block_types: Vec<u32>,
+    pub indices: Vec<u32>,
+}
+
+impl MeshBuffers {
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Add another mesh's vertices moved by `offset`, e.g. to place a chunk
+    /// at its world position inside a merged mesh.
+    pub fn append(&mut self, other: MeshBuffers, offset: Vec3) {
+        let base = self.positions.len() as u32;
+        self.positions.extend(
+            other
+                .positions
+                .iter()
+                .map(|&p| (Vec3::from(p) + offset).to_array()),
+        );
+        self.normals.extend(other.normals);
+        self.uvs.extend(other.uvs);
+        self.block_types.extend(other.block_types);
+        self.indices.extend(other.indices.iter().map(|i| i + base));
+    }
+
+    fn push_quad(&mut self, quad: &MeshQuad, quad_uvs: [[f32; 2]; 4]) {
+        let (quad_positions, normal) = quad_vertices(quad);
+        self.push_vertices(quad_positions, normal, quad_uvs, quad.block_type);
+    }
+
+    /// Push a quad with its corners reordered, if needed, so both triangles
+    /// wind counter-clockwise around its normal. UVs move with their corners.
+    fn push_front_facing_quad(&mut self, quad: &MeshQuad, quad_uvs: [[f32; 2]; 4]) {
+        let (quad_positions, normal, quad_uvs) = front_facing_vertices(quad, quad_uvs);
+        self.push_vertices(quad_positions, normal, quad_uvs, quad.block_type);
+    }
+
+    fn push_vertices(
+        &mut self,
+        quad_positions: [[f32; 3]; 4],
+        normal: [f32; 3],
+        quad_uvs: [[f32; 2]; 4],
+        block_type: u32,
+    ) {
+        let vertex_count = self.positions.len() as u32;
+
+        self.positions.extend_from_slice(&quad_positions);
+        self.normals.extend_from_slice(&[normal; 4]);
+        self.uvs.extend_from_slice(&quad_uvs);
+        self.block_types.extend_from_slice(&[block_type; 4]);
+
+        self.indices.extend_from_slice(&[
+            vertex_count,
+            vertex_count + 1,
+            vertex_count + 2,
+            vertex_count,
+            vertex_count + 2,
+            vertex_count + 3,
+        ]);
+    }
+
+    pub fn into_mesh(self) -> Mesh {
         let mut mesh = Mesh::new(
             PrimitiveTopology::TriangleList,
             RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
         );
 
-        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
-        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
-        mesh.insert_indices(Indices::U32(indices));
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs);
+        mesh.insert_indices(Indices::U32(self.indices));
 
         mesh
     }
 }
+
+/// Corner positions and normal of a quad. `width` and `height` run along x/z
+/// for Up/Down, z/y for Right/Left and x/y for Front/Back.
+fn quad_vertices(quad: &MeshQuad) -> ([[f32; 3]; 4], [f32; 3]) {
+    let base_pos = [quad.x as f32, quad.y as f32, quad.z as f32];
+    let width = quad.width as f32;
+    let height = quad.height as f32;
+
+    match quad.face {
+        Face::Up => (
+            [
+                [base_pos[0], base_pos[1] + 1.0, base_pos[2]],
+                [base_pos[0] + width, base_pos[1] + 1.0, base_pos[2]],
+                [base_pos[0] + width, base_pos[1] + 1.0, base_pos[2] + height],
+                [base_pos[0], base_pos[1] + 1.0, base_pos[2] + height],
+            ],
+            [0.0, 1.0, 0.0],
+        ),
+        Face::Down => (
+            [
+                [base_pos[0], base_pos[1], base_pos[2] + height],
+                [base_pos[0] + width, base_pos[1], base_pos[2] + height],
+                [base_pos[0] + width, base_pos[1], base_pos[2]],
+                [base_pos[0], base_pos[1], base_pos[2]],
+            ],
+            [0.0, -1.0, 0.0],
+        ),
+        Face::Right => (
+            [
+                [base_pos[0] + 1.0, base_pos[1], base_pos[2]],
+                [base_pos[0] + 1.0, base_pos[1], base_pos[2] + width],
+                [base_pos[0] + 1.0, base_pos[1] + height, base_pos[2] + width],
+                [base_pos[0] + 1.0, base_pos[1] + height, base_pos[2]],
+            ],
+            [1.0, 0.0, 0.0],
+        ),
+        Face::Left => (
+            [
+                [base_pos[0], base_pos[1], base_pos[2] + width],
+                [base_pos[0], base_pos[1], base_pos[2]],
+                [base_pos[0], base_pos[1] + height, base_pos[2]],
+                [base_pos[0], base_pos[1] + height, base_pos[2] + width],
+            ],
+            [-1.0, 0.0, 0.0],
+        ),
+        Face::Front => (
+            [
+                [base_pos[0], base_pos[1], base_pos[2] + 1.0],
+                [base_pos[0] + width, base_pos[1], base_pos[2] + 1.0],
+                [base_pos[0] + width, base_pos[1] + height, base_pos[2] + 1.0],
+                [base_pos[0], base_pos[1] + height, base_pos[2] + 1.0],
+            ],
+            [0.0, 0.0, 1.0],
+        ),
+        Face::Back => (
+            [
+                [base_pos[0] + width, base_pos[1], base_pos[2]],
+                [base_pos[0], base_pos[1], base_pos[2]],
+                [base_pos[0], base_pos[1] + height, base_pos[2]],
+                [base_pos[0] + width, base_pos[1] + height, base_pos[2]],
+            ],
+            [0.0, 0.0, -1.0],
+        ),
+    }
+}
+
+/// Corners, normal and UVs of a quad, with the corners reordered if needed so
+/// both triangles wind counter-clockwise around the normal. UVs move with
+/// their corners.
+fn front_facing_vertices(
+    quad: &MeshQuad,
+    mut quad_uvs: [[f32; 2]; 4],
+) -> ([[f32; 3]; 4], [f32; 3], [[f32; 2]; 4]) {
+    let (mut quad_positions, normal) = quad_vertices(quad);
+    let [p0, p1, p2, _] = quad_positions.map(Vec3::from);
+    if (p1 - p0).cross(p2 - p0).dot(Vec3::from(normal)) < 0.0 {
+        quad_positions.swap(1, 3);
+        quad_uvs.swap(1, 3);
+    }
+    (quad_positions, normal, quad_uvs)
+}
+
+/// Whether the block at `pos` casts ambient occlusion. Fluids and blocks
+/// outside the chunk don't.
+fn occludes(voxels: &[u32; CHUNK_SIZE_CB], pos: IVec3) -> bool {
+    if pos.cmplt(IVec3::ZERO).any() || pos.cmpge(IVec3::splat(CHUNK_SIZE as i32)).any() {
+        return false;
+    }
+    let block =
+        voxels[pos.z as usize * CHUNK_SIZE_SQ + pos.y as usize * CHUNK_SIZE + pos.x as usize];
+    block != 0 && !is_fluid(BlockState::from_bits(block).id())
+}
+
+/// Occlusion level (0-3) of the `corner` vertex of a one-block quad: how many
+/// of the two side blocks and the diagonal block in front of the face around
+/// that corner are solid. Two solid sides block the corner whatever the
+/// diagonal.
+fn corner_occlusion(
+    voxels: &[u32; CHUNK_SIZE_CB],
+    quad: &MeshQuad,
+    normal: [f32; 3],
+    corner: [f32; 3],
+) -> usize {
+    let cell = IVec3::new(quad.x as i32, quad.y as i32, quad.z as i32);
+    let front = cell + Vec3::from(normal).as_ivec3();
+    let mut sides = [IVec3::ZERO; 2];
+    let tangents = (0..3).filter(|&axis| normal[axis] == 0.0);
+    for (side, axis) in sides.iter_mut().zip(tangents) {
+        side[axis] = if corner[axis] > cell[axis] as f32 + 0.5 {
+            1
+        } else {
+            -1
+        };
+    }
+
+    let side1 = occludes(voxels, front + sides[0]);
+    let side2 = occludes(voxels, front + sides[1]);
+    let diagonal = occludes(voxels, front + sides[0] + sides[1]);
+    if side1 && side2 {
+        3
+    } else {
+        side1 as usize + side2 as usize + diagonal as usize
+    }
+}
+
+/// Split a merged quad into 1x1 quads, one per block.
+fn unit_quads(quad: &MeshQuad) -> impl Iterator<Item = MeshQuad> + '_ {
+    (0..quad.width).flat_map(move |i| {
+        (0..quad.height).map(move |j| {
+            let (x, y, z) = match quad.face {
+                Face::Up | Face::Down => (quad.x + i, quad.y, quad.z + j),
+                Face::Right | Face::Left => (quad.x, quad.y + j, quad.z + i),
+                Face::Front | Face::Back => (quad.x + i, quad.y + j, quad.z),
+            };
+            MeshQuad {
+                x,
+                y,
+                z,
+                width: 1,
+                height: 1,
+                ..quad.clone()
+            }
+        })
+    })
+}
@@ -0,0 +1,118 @@
+//! Brightness and fullbright, driven by `client.brightness` and
+//! `client.fullbright` in the config.
+//!
+//! Brightness is a gamma curve applied by the camera's color grading in the
+//! tonemapping pass. Fullbright adds a strong ambient light to the camera so
+//! unlit areas render as if fully lit. Materials lit by a block light level,
+//! such as those of glowing blocks, glow by [`light_factor`] of their level.
+
+use bevy::prelude::*;
+use bevy::render::view::ColorGrading;
+use ferrum_config::Config;
+
+/// Gamma at the top of the brightness slider. The bottom is its inverse and
+/// the middle is neutral.
+pub const MAX_GAMMA: f32 = 1.6;
+
+/// Ambient brightness used for fullbright, well above daylight ambient.
+pub const FULLBRIGHT_AMBIENT: f32 = 2000.0;
+
+/// Color grading gamma for a brightness slider value in `0.0..=1.0`. Values
+/// above 1 brighten shadows and midtones, below 1 darken them.
+pub fn brightness_to_gamma(brightness: f32) -> f32 {
+    MAX_GAMMA.powf(brightness.clamp(0.0, 1.0) * 2.0 - 1.0)
+}
+
+/// Share of full light reaching a block at `light_level` (0..=15), on the
+/// vanilla light curve. Brightness lifts dark levels towards full; fullbright
+/// makes every level full.
+pub fn light_factor(light_level: u8, brightness: f32, fullbright: bool) -> f32 {
+    if fullbright {
+        return 1.0;
+    }
+    let level = light_level.min(15) as f32 / 15.0;
+    let curved = level / (4.0 - 3.0 * level);
+    let lifted = 1.0 - (1.0 - curved).powi(4);
+    curved + (lifted - curved) * brightness.clamp(0.0, 1.0)
+}
+
+/// Apply the configured brightness and fullbright to new 3D cameras, and to
+/// every 3D camera when the config is reloaded.
+pub fn apply_brightness(
+    mut commands: Commands,
+    config: Res<Config>,
+    cameras: Query<(Entity, Ref<Camera3d>)>,
+) {
+    let reapply = config.is_changed();
+    let gamma = brightness_to_gamma(config.client.brightness);
+
+    for (entity, camera) in &cameras {
+        if !reapply && !camera.is_added() {
+            continue;
+        }
+        let mut grading = ColorGrading::default();
+        grading.shadows.gamma = gamma;
+        grading.midtones.gamma = gamma;
+
+        let mut camera = commands.entity(entity);
+        camera.insert(grading);
+        if config.client.fullbright {
+            camera.insert(AmbientLight {
+                color: Color::WHITE,
+                brightness: FULLBRIGHT_AMBIENT,
+                affects_lightmapped_meshes: true,
+            });
+        } else {
+            camera.remove::<AmbientLight>();
+        }
+    }
+}
+
+/// Materials lit by a block light level rather than by the scene's lights,
+/// with their level.
+#[derive(Resource, Default)]
+pub struct LightLevelMaterials {
+    materials: Vec<(Handle<StandardMaterial>, u8)>,
+}
+
+impl LightLevelMaterials {
+    pub fn add(&mut self, material: Handle<StandardMaterial>, light_level: u8) {
+        self.materials.push((material, light_level));
+    }
+
+    pub fn clear(&mut self) {
+        self.materials.clear();
+    }
+}
+
+/// Set the glow of every [`LightLevelMaterials`] material from its light
+/// level and the configured brightness, when either changes.
+pub fn apply_light_levels(
+    config: Res<Config>,
+    lit: Res<LightLevelMaterials>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !config.is_changed() && !lit.is_changed() {
+        return;
+    }
+    for (handle, light_level) in &lit.materials {
+        let Some(material) = materials.get_mut(handle) else {
+            continue;
+        };
+        let factor = light_factor(
+            *light_level,
+            config.client.brightness,
+            config.client.fullbright,
+        );
+        material.emissive = LinearRgba::rgb(factor, factor, factor);
+    }
+}
+
+pub struct BrightnessPlugin;
+
+impl Plugin for BrightnessPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LightLevelMaterials>()
+            .add_systems(Update, (apply_brightness, apply_light_levels));
+    }
+}
@@ -0,0 +1,152 @@
+//! View bobbing and sprint FOV on the player camera, driven by
+//! `client.view_bobbing`, `client.fov` and `client.fov_effects` in the
+//! config.
+//!
+//! Both apply in `PostUpdate`, on top of the pose and field of view the game
+//! gave the [`FirstPersonView`] camera during `Update`, so the player
+//! controller never sees the bob.
+
+use crate::view_model::{FirstPersonView, ViewBob};
+use bevy::prelude::*;
+use bevy::transform::TransformSystems;
+use ferrum_config::Config;
+
+/// Field of view while sprinting relative to the configured one, as vanilla
+/// derives from the 30% sprint speed boost.
+pub const SPRINT_FOV_SCALE: f32 = 1.15;
+
+/// How fast the field of view eases towards its target, per second. Vanilla
+/// halves the distance every tick.
+const FOV_EASE_RATE: f32 = 20.0 * std::f32::consts::LN_2;
+
+/// Whether the player is sprinting, set by the client's movement code.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sprinting(pub bool);
+
+/// Vertical field of view in degrees the camera should have: the configured
+/// one, widened while sprinting if FOV effects are on.
+pub fn target_fov(base_fov: f32, sprinting: bool, fov_effects: bool) -> f32 {
+    if sprinting && fov_effects {
+        base_fov * SPRINT_FOV_SCALE
+    } else {
+        base_fov
+    }
+}
+
+/// Move `current` towards `target` over `dt` seconds. Settles exactly on the
+/// target once within a hundredth of a degree.
+pub fn ease_fov(current: f32, target: f32, dt: f32) -> f32 {
+    let eased = target + (current - target) * (-FOV_EASE_RATE * dt).exp();
+    if (eased - target).abs() < 0.01 {
+        target
+    } else {
+        eased
+    }
+}
+
+/// Bobbing state of a [`FirstPersonView`] camera.
+#[derive(Component, Debug, Default)]
+pub struct CameraBob {
+    pub bob: ViewBob,
+    /// Pose the game gave the camera, before bobbing.
+    base: Option<(Vec3, Quat)>,
+    /// Pose written over it last frame.
+    applied: Option<(Vec3, Quat)>,
+}
+
+impl CameraBob {
+    /// Position of the camera without bobbing.
+    pub fn base_translation(&self) -> Option<Vec3> {
+        self.base.map(|(translation, _)| translation)
+    }
+}
+
+/// Give each new [`FirstPersonView`] camera bobbing state and the configured
+/// field of view.
+fn attach_camera_effects(
+    mut commands: Commands,
+    config: Res<Config>,
+    mut cameras: Query<(Entity, &mut Projection), Added<FirstPersonView>>,
+) {
+    for (entity, mut projection) in &mut cameras {
+        commands.entity(entity).insert(CameraBob::default());
+        if let Projection::Perspective(perspective) = projection.as_mut() {
+            perspective.fov = config.client.fov.to_radians();
+        }
+    }
+}
+
+/// Bob the camera with how fast it moved since the last frame.
+pub fn apply_view_bobbing(
+    time: Res<Time>,
+    config: Res<Config>,
+    mut cameras: Query<(&mut CameraBob, &mut Transform), With<FirstPersonView>>,
+) {
+    let dt = time.delta_secs();
+
+    for (mut state, mut transform) in &mut cameras {
+        // Whatever the game wrote since the last frame is the new base; what
+        // is left over from the last bob is not
+        let (mut translation, mut rotation) = (transform.translation, transform.rotation);
+        if let (Some(base), Some(applied)) = (state.base, state.applied) {
+            if translation == applied.0 {
+                translation = base.0;
+            }
+            if rotation == applied.1 {
+                rotation = base.1;
+            }
+        }
+
+        if !config.client.view_bobbing {
+            state.bob = ViewBob::default();
+        } else if dt > 0.0 {
+            let moved = state
+                .base
+                .map_or(Vec3::ZERO, |(last, _)| translation - last);
+            state.bob.update(moved.xz().length() / dt, dt);
+        }
+        state.base = Some((translation, rotation));
+
+        let bobbed_translation = translation + rotation * state.bob.camera_offset();
+        let bobbed_rotation = rotation * state.bob.camera_tilt();
+        transform.translation = bobbed_translation;
+        transform.rotation = bobbed_rotation;
+        state.applied = Some((bobbed_translation, bobbed_rotation));
+    }
+}
+
+/// Ease the camera's field of view towards the configured one, widened while
+/// sprinting.
+pub fn apply_dynamic_fov(
+    time: Res<Time>,
+    config: Res<Config>,
+    sprinting: Res<Sprinting>,
+    mut cameras: Query<&mut Projection, With<FirstPersonView>>,
+) {
+    let target = target_fov(config.client.fov, sprinting.0, config.client.fov_effects);
+    for mut projection in &mut cameras {
+        let Projection::Perspective(perspective) = projection.as_ref() else {
+            continue;
+        };
+        let fov = ease_fov(perspective.fov.to_degrees(), target, time.delta_secs()).to_radians();
+        if fov == perspective.fov {
+            continue;
+        }
+        if let Projection::Perspective(perspective) = projection.as_mut() {
+            perspective.fov = fov;
+        }
+    }
+}
+
+pub struct CameraEffectsPlugin;
+
+impl Plugin for CameraEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Sprinting>().add_systems(
+            PostUpdate,
+            (attach_camera_effects, apply_view_bobbing, apply_dynamic_fov)
+                .chain()
+                .before(TransformSystems::Propagate),
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! Chunks fading in when they first appear.
+//!
+//! When a chunk group gets its first mesh it is drawn with a blended copy of
+//! the chunk material whose alpha ramps from 0 to 1 over
+//! `client.chunk_fade_in` seconds, then switches back to the shared opaque
+//! material. Later re-meshes of the group replace its mesh in place and do
+//! not fade again.
+
+use crate::chunk_groups::ChunkGroupMesh;
+use crate::mesh_upload::upload_chunk_meshes;
+use bevy::prelude::*;
+use ferrum_config::Config;
+
+/// Progress of a chunk fading in.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct ChunkFade {
+    elapsed: f32,
+    duration: f32,
+}
+
+impl ChunkFade {
+    /// A fade lasting `duration` seconds. A duration of 0 or less is already
+    /// complete.
+    pub fn new(duration: f32) -> Self {
+        Self {
+            elapsed: 0.0,
+            duration: duration.max(0.0),
+        }
+    }
+
+    /// Advance the fade by `delta` seconds.
+    pub fn tick(&mut self, delta: f32) {
+        self.elapsed = (self.elapsed + delta).min(self.duration);
+    }
+
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Opacity from 0 when the fade starts to 1 when it completes.
+    pub fn alpha(&self) -> f32 {
+        if self.duration <= 0.0 {
+            1.0
+        } else {
+            self.elapsed / self.duration
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+/// The shared material a fading chunk goes back to once it is opaque.
+#[derive(Component)]
+pub struct FadeOpaqueMaterial(Handle<StandardMaterial>);
+
+/// Chunk groups that just got their first mesh.
+type NewChunkGroups<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static MeshMaterial3d<StandardMaterial>),
+    (With<ChunkGroupMesh>, Added<Mesh3d>),
+>;
+
+/// Start fading in chunk groups that just got their first mesh.
+pub fn start_chunk_fades(
+    mut commands: Commands,
+    config: Res<Config>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    added: NewChunkGroups,
+) {
+    let duration = config.client.chunk_fade_in;
+    if duration <= 0.0 {
+        return;
+    }
+    for (entity, material) in &added {
+        let Some(opaque) = materials.get(&material.0) else {
+            continue;
+        };
+        let mut fading = opaque.clone();
+        fading.alpha_mode = AlphaMode::Blend;
+        fading.base_color.set_alpha(0.0);
+
+        commands.entity(entity).insert((
+            ChunkFade::new(duration),
+            FadeOpaqueMaterial(material.0.clone()),
+            MeshMaterial3d(materials.add(fading)),
+        ));
+    }
+}
+
+/// Advance chunk fades, restoring the opaque material when they complete.
+pub fn update_chunk_fades(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut fading: Query<(
+        Entity,
+        &mut ChunkFade,
+        &MeshMaterial3d<StandardMaterial>,
+        &FadeOpaqueMaterial,
+    )>,
+) {
+    for (entity, mut fade, material, opaque) in &mut fading {
+        fade.tick(time.delta_secs());
+        if fade.is_complete() {
+            materials.remove(&material.0);
+            commands
+                .entity(entity)
+                .remove::<(ChunkFade, FadeOpaqueMaterial)>()
+                .insert(MeshMaterial3d(opaque.0.clone()));
+            continue;
+        }
+
+        let Some(alpha) = materials
+            .get(&opaque.0)
+            .map(|opaque| opaque.base_color.alpha() * fade.alpha())
+        else {
+            continue;
+        };
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.base_color.set_alpha(alpha);
+        }
+    }
+}
+
+pub struct ChunkFadePlugin;
+
+impl Plugin for ChunkFadePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (start_chunk_fades, update_chunk_fades)
+                .chain()
+                .after(upload_chunk_meshes),
+        );
+    }
+}
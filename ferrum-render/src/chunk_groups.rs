@@ -0,0 +1,230 @@
+//! Chunk meshes merged into groups to cut draw calls.
+//!
+//! Every chunk in a square of `group_size` × `group_size` chunk columns,
+//! including all of their vertical sections, is drawn as one mesh. Changing
+//! any member rebuilds the whole group, so larger groups mean fewer draw
+//! calls but more work per chunk update. The size comes from
+//! `client.chunk_group_size` in the config.
+
+use crate::block_renderer::{BlockRenderer, MeshBuffers};
+use crate::frustum::ChunkBounds;
+use crate::mesh_upload::{upload_chunk_meshes, PendingChunkMesh};
+use crate::texture_atlas::TextureAtlas;
+use bevy::prelude::*;
+use ferrum_config::Config;
+use ferrum_meshing_cpu::ChunkMesh;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Merge chunk meshes into one, each moved to its chunk's world origin.
+pub fn merge_chunk_meshes<'a>(
+    chunks: impl IntoIterator<Item = (&'a ChunkMesh, IVec3)>,
+    atlas: &TextureAtlas,
+) -> MeshBuffers {
+    let mut merged = MeshBuffers::default();
+    for (chunk_mesh, origin) in chunks {
+        let buffers = BlockRenderer::build_buffers(chunk_mesh, atlas, origin);
+        merged.append(buffers, origin.as_vec3());
+    }
+    merged
+}
+
+struct GroupMember {
+    origin: IVec3,
+    mesh: ChunkMesh,
+}
+
+/// Chunk meshes sorted into groups, with the groups that need rebuilding.
+#[derive(Resource)]
+pub struct ChunkGroups {
+    group_size: i32,
+    groups: HashMap<IVec2, HashMap<IVec3, GroupMember>>,
+    dirty: HashSet<IVec2>,
+}
+
+impl Default for ChunkGroups {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl ChunkGroups {
+    /// Groups of `group_size` × `group_size` chunk columns. A size of 0 is
+    /// treated as 1.
+    pub fn new(group_size: u32) -> Self {
+        Self {
+            group_size: group_size.max(1) as i32,
+            groups: HashMap::new(),
+            dirty: HashSet::new(),
+        }
+    }
+
+    pub fn group_size(&self) -> u32 {
+        self.group_size as u32
+    }
+
+    /// Change the group size, regrouping every chunk.
+    pub fn set_group_size(&mut self, group_size: u32) {
+        let group_size = group_size.max(1) as i32;
+        if group_size == self.group_size {
+            return;
+        }
+        self.dirty.extend(self.groups.keys().copied());
+        self.group_size = group_size;
+        for (chunk, member) in std::mem::take(&mut self.groups).into_values().flatten() {
+            self.insert(chunk, member.origin, member.mesh);
+        }
+    }
+
+    /// Group containing the chunk at `chunk` (chunk coordinates, y being the
+    /// vertical section).
+    pub fn group_of(&self, chunk: IVec3) -> IVec2 {
+        IVec2::new(
+            chunk.x.div_euclid(self.group_size),
+            chunk.z.div_euclid(self.group_size),
+        )
+    }
+
+    /// Add or replace a chunk's mesh, with `origin` its position in the
+    /// world.
+    pub fn insert(&mut self, chunk: IVec3, origin: IVec3, mesh: ChunkMesh) {
+        let group = self.group_of(chunk);
+        self.groups
+            .entry(group)
+            .or_default()
+            .insert(chunk, GroupMember { origin, mesh });
+        self.dirty.insert(group);
+    }
+
+    pub fn remove(&mut self, chunk: IVec3) -> Option<ChunkMesh> {
+        let group = self.group_of(chunk);
+        let members = self.groups.get_mut(&group)?;
+        let member = members.remove(&chunk)?;
+        if members.is_empty() {
+            self.groups.remove(&group);
+        }
+        self.dirty.insert(group);
+        Some(member.mesh)
+    }
+
+    /// Remove every chunk. The group meshes already drawn are left for the
+    /// caller to despawn.
+    pub fn clear(&mut self) {
+        self.groups.clear();
+        self.dirty.clear();
+    }
+
+    /// Chunks in `group` as (mesh, origin) pairs.
+    pub fn members(&self, group: IVec2) -> impl Iterator<Item = (&ChunkMesh, IVec3)> {
+        self.groups
+            .get(&group)
+            .into_iter()
+            .flat_map(|members| members.values())
+            .map(|member| (&member.mesh, member.origin))
+    }
+
+    /// Every chunk as (mesh, origin) pairs.
+    pub fn chunks(&self) -> impl Iterator<Item = (&ChunkMesh, IVec3)> {
+        self.groups
+            .values()
+            .flat_map(|members| members.values())
+            .map(|member| (&member.mesh, member.origin))
+    }
+
+    /// Number of groups with at least one chunk, i.e. draw calls.
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Groups changed since the last call.
+    pub fn take_dirty(&mut self) -> Vec<IVec2> {
+        self.dirty.drain().collect()
+    }
+}
+
+/// Atlas and material used to build and draw group meshes.
+#[derive(Resource, Clone)]
+pub struct ChunkGroupRendering {
+    pub atlas: Arc<TextureAtlas>,
+    pub material: Handle<StandardMaterial>,
+}
+
+/// The merged mesh of one chunk group.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkGroupMesh(pub IVec2);
+
+/// Follow `client.chunk_group_size` in the config.
+pub fn apply_chunk_group_size(config: Res<Config>, mut groups: ResMut<ChunkGroups>) {
+    if config.is_changed() && groups.group_size() != config.client.chunk_group_size {
+        groups.set_group_size(config.client.chunk_group_size);
+    }
+}
+
+/// Start rebuilding the mesh of every changed group in the background. A
+/// group keeps its old mesh until the new one is uploaded.
+pub fn rebuild_chunk_groups(
+    mut commands: Commands,
+    mut groups: ResMut<ChunkGroups>,
+    rendering: Option<Res<ChunkGroupRendering>>,
+    existing: Query<(Entity, &ChunkGroupMesh)>,
+) {
+    let Some(rendering) = rendering else {
+        return;
+    };
+    let dirty = groups.take_dirty();
+    if dirty.is_empty() {
+        return;
+    }
+    let entities: HashMap<IVec2, Entity> = existing
+        .iter()
+        .map(|(entity, group)| (group.0, entity))
+        .collect();
+
+    for group in dirty {
+        let parts: Vec<(ChunkMesh, IVec3)> = groups
+            .members(group)
+            .filter(|(mesh, _)| !mesh.is_empty())
+            .map(|(mesh, origin)| (mesh.clone(), origin))
+            .collect();
+
+        let Some(bounds) = ChunkBounds::chunks(parts.iter().map(|&(_, origin)| origin)) else {
+            if let Some(&entity) = entities.get(&group) {
+                commands.entity(entity).despawn();
+            }
+            continue;
+        };
+
+        let task = PendingChunkMesh::spawn_merged(parts, rendering.atlas.clone());
+        match entities.get(&group) {
+            Some(&entity) => {
+                commands.entity(entity).insert((task, bounds));
+            }
+            None => {
+                commands.spawn((
+                    ChunkGroupMesh(group),
+                    task,
+                    bounds,
+                    MeshMaterial3d(rendering.material.clone()),
+                    Transform::default(),
+                ));
+            }
+        }
+    }
+}
+
+pub struct ChunkGroupPlugin;
+
+impl Plugin for ChunkGroupPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChunkGroups>().add_systems(
+            Update,
+            (apply_chunk_group_size, rebuild_chunk_groups)
+                .chain()
+                .before(upload_chunk_meshes),
+        );
+    }
+}
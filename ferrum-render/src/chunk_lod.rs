@@ -0,0 +1,305 @@
+//! LOD selection for the chunks of a loaded [`World`], or for the chunk
+//! columns received from a server.
+//!
+//! [`ChunkLods`] picks a [`LodLevel`] for each chunk from its distance to the
+//! camera and meshes it at that level, then only meshes it again once it
+//! crosses a LOD boundary by more than the hysteresis. With
+//! [`AdaptiveLodPlugin`], its thresholds follow the frame rate.
+
+use crate::lod::{AdaptiveLod, LodConfig, LodLevel, LodMesher, LodStats};
+use crate::lod_jobs::LodMeshJobs;
+use bevy::prelude::*;
+use ferrum_config::Config;
+use ferrum_meshing_cpu::{
+    ChunkMesh, ChunkMesher, ChunkNeighbors, Face, CHUNK_SIZE, CHUNK_SIZE_CB, CHUNK_SIZE_SQ,
+};
+use ferrum_world::{Chunk, ChunkColumn, ChunkPos, ChunkSide, NeighborEdges, World};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Chunks past a LOD boundary by less than this keep their LOD.
+pub const DEFAULT_LOD_HYSTERESIS: f32 = 1.0;
+
+/// Horizontal distance in chunks from `camera` to the centre of the chunk
+/// column at `pos`.
+pub fn chunk_distance(pos: ChunkPos, camera: Vec3) -> f32 {
+    column_distance(pos, CHUNK_SIZE as f32, camera)
+}
+
+/// Horizontal distance in columns from `camera` to the centre of the chunk
+/// column at `pos`, for columns `width` blocks wide.
+pub fn column_distance(pos: ChunkPos, width: f32, camera: Vec3) -> f32 {
+    let centre = (Vec2::new(pos.x as f32, pos.z as f32) + 0.5) * width;
+    centre.distance(camera.xz()) / width
+}
+
+/// Block ids of `chunk` in the mesher's `z, y, x` voxel order.
+pub fn chunk_voxels(chunk: &Chunk) -> Box<[u32; CHUNK_SIZE_CB]> {
+    let mut voxels = Box::new([0u32; CHUNK_SIZE_CB]);
+    for z in 0..CHUNK_SIZE {
+        for y in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                voxels[z * CHUNK_SIZE_SQ + y * CHUNK_SIZE + x] =
+                    chunk.get_block(x, y, z).as_u16() as u32;
+            }
+        }
+    }
+    voxels
+}
+
+/// The loaded sides of `edges`, as the mesher takes them.
+pub fn chunk_neighbors(edges: &NeighborEdges) -> ChunkNeighbors<'_> {
+    let mut neighbors = ChunkNeighbors::new();
+    for side in ChunkSide::ALL {
+        if !edges.has(side) {
+            continue;
+        }
+        let face = match side {
+            ChunkSide::PosX => Face::Right,
+            ChunkSide::NegX => Face::Left,
+            ChunkSide::PosY => Face::Up,
+            ChunkSide::NegY => Face::Down,
+            ChunkSide::PosZ => Face::Front,
+            ChunkSide::NegZ => Face::Back,
+        };
+        neighbors.set(face, Some(edges.get(side)));
+    }
+    neighbors
+}
+
+/// Voxels of each allocated section of `column`, bottom up, with the world y
+/// of the section's bottom. All-air sections are skipped.
+pub fn column_section_voxels(
+    column: &ChunkColumn,
+) -> impl Iterator<Item = (i32, Box<[u32; CHUNK_SIZE_CB]>)> + '_ {
+    column
+        .sections()
+        .map(|(min_y, section)| (min_y, chunk_voxels(section)))
+}
+
+/// Meshes to swap in after [`ChunkLods::update`].
+#[derive(Default)]
+pub struct ChunkLodUpdate {
+    /// Chunks meshed for the first time or at a new LOD.
+    pub meshed: Vec<(ChunkPos, LodLevel, ChunkMesh)>,
+    /// Chunks unloaded or beyond the render distance, whose meshes should
+    /// go.
+    pub removed: Vec<ChunkPos>,
+}
+
+/// The LOD each chunk is meshed at.
+#[derive(Resource, Debug, Clone)]
+pub struct ChunkLods {
+    pub config: LodConfig,
+    /// How far in chunks a chunk must pass a boundary before changing LOD.
+    pub hysteresis: f32,
+    /// LOD and quad count of every meshed chunk.
+    meshed: HashMap<ChunkPos, (LodLevel, u32)>,
+    stats: LodStats,
+}
+
+impl Default for ChunkLods {
+    fn default() -> Self {
+        Self::new(LodConfig::default(), DEFAULT_LOD_HYSTERESIS)
+    }
+}
+
+impl ChunkLods {
+    pub fn new(config: LodConfig, hysteresis: f32) -> Self {
+        Self {
+            config,
+            hysteresis,
+            meshed: HashMap::new(),
+            stats: LodStats::new(),
+        }
+    }
+
+    /// LOD `pos` is currently meshed at.
+    pub fn lod(&self, pos: ChunkPos) -> Option<LodLevel> {
+        self.meshed.get(&pos).map(|&(lod, _)| lod)
+    }
+
+    /// Chunks and quads at each LOD as of the last update.
+    pub fn stats(&self) -> &LodStats {
+        &self.stats
+    }
+
+    /// Select a LOD for every chunk of `world` seen from `camera` and mesh
+    /// the chunks that are new or changed LOD. `Full` chunks use `mesher`,
+    /// culling faces against the neighbouring chunks, the others
+    /// [`LodMesher`].
+    pub fn update(
+        &mut self,
+        world: &World,
+        camera: Vec3,
+        mesher: &impl ChunkMesher,
+    ) -> ChunkLodUpdate {
+        let mut meshed = Vec::new();
+        let removed = self.select(world_chunks(world, camera), |pos, lod, chunk| {
+            let voxels = chunk_voxels(chunk);
+            let mesh = LodMesher::mesh_chunk_lod(&voxels, lod).unwrap_or_else(|| {
+                let edges = world.neighbor_edges(pos);
+                mesher.mesh_chunk_with_neighbors(&voxels, &chunk_neighbors(&edges))
+            });
+            let quads = mesh.quads.len() as u32;
+            meshed.push((pos, lod, mesh));
+            quads
+        });
+        ChunkLodUpdate { meshed, removed }
+    }
+
+    /// Like [`Self::update`], but mesh on `jobs` instead of right away.
+    /// Returns the chunks whose meshes should go; report each finished mesh
+    /// back with [`Self::record_quads`] so the stats count it.
+    pub fn update_async(
+        &mut self,
+        world: &World,
+        camera: Vec3,
+        jobs: &mut LodMeshJobs,
+    ) -> Vec<ChunkPos> {
+        let removed = self.select(world_chunks(world, camera), |pos, lod, chunk| {
+            jobs.submit(pos, Arc::from(chunk_voxels(chunk)), lod);
+            0
+        });
+        for &pos in &removed {
+            jobs.cancel(pos);
+        }
+        removed
+    }
+
+    /// Like [`Self::update_async`], for the chunk columns at `columns`, each
+    /// `width` blocks wide, such as the 16 wide columns a server sends. Each
+    /// section of a column is meshed as its own job, keyed
+    /// `IVec3::new(pos.x, index, pos.z)` by its index up the column.
+    /// `sections` gives the index and voxels of each section of a column
+    /// worth meshing, and is only called for columns that need a new mesh.
+    pub fn update_columns_async<S>(
+        &mut self,
+        columns: impl IntoIterator<Item = ChunkPos>,
+        width: f32,
+        camera: Vec3,
+        mut sections: impl FnMut(ChunkPos) -> S,
+        jobs: &mut LodMeshJobs<IVec3>,
+    ) -> Vec<ChunkPos>
+    where
+        S: IntoIterator<Item = (i32, Box<[u32; CHUNK_SIZE_CB]>)>,
+    {
+        let columns = columns
+            .into_iter()
+            .map(|pos| (pos, column_distance(pos, width, camera), ()));
+        let removed = self.select(columns, |pos, lod, ()| {
+            for (index, voxels) in sections(pos) {
+                jobs.submit(IVec3::new(pos.x, index, pos.z), Arc::from(voxels), lod);
+            }
+            0
+        });
+        jobs.cancel_matching(|section| {
+            removed.contains(&ChunkPos {
+                x: section.x,
+                z: section.z,
+            })
+        });
+        removed
+    }
+
+    /// Count the quads of a mesh finished in the background, unless the
+    /// chunk has moved on to another LOD since. The meshes of a column's
+    /// sections add up.
+    pub fn record_quads(&mut self, pos: ChunkPos, lod: LodLevel, quads: u32) {
+        match self.meshed.get_mut(&pos) {
+            Some(entry) if entry.0 == lod => entry.1 += quads,
+            _ => return,
+        }
+        self.recount();
+    }
+
+    /// Forget every meshed chunk, e.g. when the world is replaced.
+    pub fn clear(&mut self) {
+        self.meshed.clear();
+        self.recount();
+    }
+
+    /// Pick the LOD of each of `chunks`, given with its distance from the
+    /// camera, calling `mesh` for the chunks that need a new mesh and keeping
+    /// the quad count it returns. Returns the chunks that no longer have a
+    /// LOD, including those missing from `chunks`.
+    fn select<T>(
+        &mut self,
+        chunks: impl IntoIterator<Item = (ChunkPos, f32, T)>,
+        mut mesh: impl FnMut(ChunkPos, LodLevel, T) -> u32,
+    ) -> Vec<ChunkPos> {
+        let mut meshed = HashMap::with_capacity(self.meshed.len());
+
+        for (pos, distance, chunk) in chunks {
+            let current = self.meshed.get(&pos).copied();
+            let selected = self.config.select_lod_with_hysteresis(
+                distance,
+                current.map(|(lod, _)| lod),
+                self.hysteresis,
+            );
+            match (selected, current) {
+                (Some(lod), Some((current_lod, quads))) if lod == current_lod => {
+                    meshed.insert(pos, (lod, quads));
+                }
+                (Some(lod), _) => {
+                    meshed.insert(pos, (lod, mesh(pos, lod, chunk)));
+                }
+                (None, _) => {}
+            }
+        }
+        // Chunks out of range or no longer loaded
+        let removed = self
+            .meshed
+            .keys()
+            .filter(|pos| !meshed.contains_key(*pos))
+            .copied()
+            .collect();
+
+        self.meshed = meshed;
+        self.recount();
+        removed
+    }
+
+    fn recount(&mut self) {
+        self.stats = LodStats::new();
+        for &(lod, quads) in self.meshed.values() {
+            self.stats.record(lod, quads);
+        }
+    }
+}
+
+/// Follow `render.target_fps`: feed every frame time into [`AdaptiveLod`]
+/// and hand the thresholds it settles on to [`ChunkLods`].
+pub fn adapt_chunk_lods(
+    time: Res<Time>,
+    config: Res<Config>,
+    mut adaptive: ResMut<AdaptiveLod>,
+    mut lods: ResMut<ChunkLods>,
+) {
+    if config.is_changed() {
+        adaptive.set_target_fps(config.render.target_fps as f32);
+    }
+    let frame_time = time.delta_secs();
+    if frame_time > 0.0 && adaptive.record_frame(frame_time) {
+        lods.config = adaptive.config().clone();
+    }
+}
+
+/// Pulls the LOD thresholds of [`ChunkLods`] closer while frames run over
+/// `render.target_fps`, and lets them back out once there is headroom.
+pub struct AdaptiveLodPlugin;
+
+impl Plugin for AdaptiveLodPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChunkLods>()
+            .init_resource::<AdaptiveLod>()
+            .add_systems(Update, adapt_chunk_lods);
+    }
+}
+
+/// The chunks of `world` with their distance from `camera`.
+fn world_chunks(world: &World, camera: Vec3) -> impl Iterator<Item = (ChunkPos, f32, &Chunk)> {
+    world
+        .iter_chunks()
+        .map(move |(pos, chunk)| (pos, chunk_distance(pos, camera), chunk))
+}
@@ -0,0 +1,279 @@
+//! The cloud layer, driven by `client.clouds` and `client.cloud_height` in the
+//! config.
+//!
+//! Clouds are a repeating pattern of cells drawn as one translucent mesh that
+//! follows the camera and drifts along +X. They use the camera's distance fog
+//! like any other material, so they fade into the sky at the edge of the view.
+
+use bevy::asset::RenderAssetUsages;
+use bevy::mesh::{Indices, PrimitiveTopology};
+use bevy::prelude::*;
+use bevy::render::render_resource::Face;
+use ferrum_config::{CloudMode, Config};
+
+use crate::ViewModelCamera;
+
+/// Side length of one cloud cell, in blocks.
+pub const CLOUD_CELL_SIZE: f32 = 12.0;
+
+/// Height of fancy clouds, in blocks.
+pub const CLOUD_THICKNESS: f32 = 4.0;
+
+/// Cells along each side of the repeating cloud pattern.
+pub const CLOUD_GRID: u32 = 32;
+
+/// Distance after which the cloud pattern repeats, in blocks.
+pub const CLOUD_PERIOD: f32 = CLOUD_CELL_SIZE * CLOUD_GRID as f32;
+
+/// Drift speed in blocks per second, as in vanilla.
+pub const CLOUD_SPEED: f32 = 0.6;
+
+/// How far the pattern has drifted after `elapsed_secs`, wrapped to
+/// `[0, CLOUD_PERIOD)`. Computed in `f64` so it stays smooth in long sessions.
+pub fn cloud_offset(elapsed_secs: f64, speed: f32) -> f32 {
+    let offset = (elapsed_secs * speed as f64).rem_euclid(CLOUD_PERIOD as f64) as f32;
+    // Rounding can land exactly on the period
+    if offset >= CLOUD_PERIOD {
+        0.0
+    } else {
+        offset
+    }
+}
+
+/// Where to place the cloud mesh for a camera at `camera`. The origin is
+/// snapped to whole pattern periods (shifted by the drift `offset`) so the
+/// pattern stays put in the world while the mesh follows the camera.
+pub fn cloud_origin(camera: Vec3, offset: f32, height: f32) -> Vec3 {
+    let x = offset + ((camera.x - offset) / CLOUD_PERIOD).floor() * CLOUD_PERIOD;
+    let z = (camera.z / CLOUD_PERIOD).floor() * CLOUD_PERIOD;
+    Vec3::new(x, height, z)
+}
+
+/// The cloud layer entity, drawn in `mode`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CloudLayer {
+    pub mode: CloudMode,
+}
+
+/// Pseudo-random value in `[0, 1)` for a cell.
+fn cell_noise(x: u32, z: u32, salt: u32) -> f32 {
+    let mut h = x.wrapping_mul(0x27d4_eb2d) ^ z.wrapping_mul(0x1656_67b1) ^ salt;
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2c1b_3c6d);
+    h ^= h >> 12;
+    h = h.wrapping_mul(0x297a_2d39);
+    h ^= h >> 15;
+    (h >> 8) as f32 / (1u32 << 24) as f32
+}
+
+/// Whether the cell at `(x, z)` holds cloud. Repeats every `CLOUD_GRID` cells.
+fn cloud_cell(x: i32, z: i32) -> bool {
+    let x = x.rem_euclid(CLOUD_GRID as i32) as u32;
+    let z = z.rem_euclid(CLOUD_GRID as i32) as u32;
+    // Clumps from a coarse grid, broken up by the fine one
+    (cell_noise(x / 4, z / 4, 1) < 0.45 && cell_noise(x, z, 2) < 0.8) || cell_noise(x, z, 3) < 0.08
+}
+
+#[derive(Default)]
+struct CloudMeshBuilder {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    colors: Vec<[f32; 4]>,
+    indices: Vec<u32>,
+}
+
+impl CloudMeshBuilder {
+    /// Add a quad with corners in counter-clockwise order seen from `normal`.
+    fn quad(&mut self, corners: [Vec3; 4], normal: Vec3, shade: f32) {
+        let base = self.positions.len() as u32;
+        self.positions
+            .extend(corners.iter().map(|corner| corner.to_array()));
+        self.normals.extend_from_slice(&[normal.to_array(); 4]);
+        self.colors
+            .extend_from_slice(&[[shade, shade, shade, 1.0]; 4]);
+        self.indices
+            .extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+
+    fn build(self) -> Mesh {
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, self.colors);
+        mesh.insert_indices(Indices::U32(self.indices));
+        mesh
+    }
+}
+
+/// Mesh of three pattern periods square, spanning `[-CLOUD_PERIOD,
+/// 2 * CLOUD_PERIOD)` on X and Z around [`cloud_origin`]. Fast clouds are a
+/// flat sheet at the layer height; fancy ones are shaded boxes with the faces
+/// between neighbouring cells left out. `None` when clouds are off.
+pub fn cloud_mesh(mode: CloudMode) -> Option<Mesh> {
+    if mode == CloudMode::Off {
+        return None;
+    }
+
+    let grid = CLOUD_GRID as i32;
+    let s = CLOUD_CELL_SIZE;
+    let t = CLOUD_THICKNESS;
+    let mut builder = CloudMeshBuilder::default();
+
+    for cx in -grid..2 * grid {
+        for cz in -grid..2 * grid {
+            if !cloud_cell(cx, cz) {
+                continue;
+            }
+            let (x0, z0) = (cx as f32 * s, cz as f32 * s);
+            let (x1, z1) = (x0 + s, z0 + s);
+
+            if mode == CloudMode::Fast {
+                builder.quad(
+                    [
+                        Vec3::new(x0, 0.0, z0),
+                        Vec3::new(x0, 0.0, z1),
+                        Vec3::new(x1, 0.0, z1),
+                        Vec3::new(x1, 0.0, z0),
+                    ],
+                    Vec3::Y,
+                    1.0,
+                );
+                continue;
+            }
+
+            builder.quad(
+                [
+                    Vec3::new(x0, t, z0),
+                    Vec3::new(x0, t, z1),
+                    Vec3::new(x1, t, z1),
+                    Vec3::new(x1, t, z0),
+                ],
+                Vec3::Y,
+                1.0,
+            );
+            builder.quad(
+                [
+                    Vec3::new(x0, 0.0, z0),
+                    Vec3::new(x1, 0.0, z0),
+                    Vec3::new(x1, 0.0, z1),
+                    Vec3::new(x0, 0.0, z1),
+                ],
+                Vec3::NEG_Y,
+                0.7,
+            );
+            if !cloud_cell(cx + 1, cz) {
+                builder.quad(
+                    [
+                        Vec3::new(x1, 0.0, z0),
+                        Vec3::new(x1, t, z0),
+                        Vec3::new(x1, t, z1),
+                        Vec3::new(x1, 0.0, z1),
+                    ],
+                    Vec3::X,
+                    0.9,
+                );
+            }
+            if !cloud_cell(cx - 1, cz) {
+                builder.quad(
+                    [
+                        Vec3::new(x0, 0.0, z1),
+                        Vec3::new(x0, t, z1),
+                        Vec3::new(x0, t, z0),
+                        Vec3::new(x0, 0.0, z0),
+                    ],
+                    Vec3::NEG_X,
+                    0.9,
+                );
+            }
+            if !cloud_cell(cx, cz + 1) {
+                builder.quad(
+                    [
+                        Vec3::new(x1, 0.0, z1),
+                        Vec3::new(x1, t, z1),
+                        Vec3::new(x0, t, z1),
+                        Vec3::new(x0, 0.0, z1),
+                    ],
+                    Vec3::Z,
+                    0.8,
+                );
+            }
+            if !cloud_cell(cx, cz - 1) {
+                builder.quad(
+                    [
+                        Vec3::new(x0, 0.0, z0),
+                        Vec3::new(x0, t, z0),
+                        Vec3::new(x1, t, z0),
+                        Vec3::new(x1, 0.0, z0),
+                    ],
+                    Vec3::NEG_Z,
+                    0.8,
+                );
+            }
+        }
+    }
+
+    Some(builder.build())
+}
+
+/// Spawn, rebuild or remove the cloud layer to match the config, and keep it
+/// drifting above the camera.
+pub fn update_clouds(
+    mut commands: Commands,
+    config: Res<Config>,
+    time: Res<Time>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    cameras: Query<&GlobalTransform, (With<Camera3d>, Without<ViewModelCamera>)>,
+    mut layers: Query<(Entity, &CloudLayer, &mut Transform)>,
+) {
+    let mode = config.client.cloud_mode();
+    let camera = cameras
+        .iter()
+        .next()
+        .map(GlobalTransform::translation)
+        .unwrap_or(Vec3::ZERO);
+    let offset = cloud_offset(time.elapsed_secs_f64(), CLOUD_SPEED);
+    let origin = cloud_origin(camera, offset, config.client.cloud_height);
+
+    let mut current = false;
+    for (entity, layer, mut transform) in &mut layers {
+        if layer.mode == mode && !current {
+            transform.translation = origin;
+            current = true;
+        } else {
+            commands.entity(entity).despawn();
+        }
+    }
+    if current {
+        return;
+    }
+
+    let Some(mesh) = cloud_mesh(mode) else {
+        return;
+    };
+    let material = StandardMaterial {
+        base_color: Color::srgba(1.0, 1.0, 1.0, 0.8),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        // A flat sheet is seen from below and above
+        cull_mode: (mode == CloudMode::Fancy).then_some(Face::Back),
+        ..default()
+    };
+    commands.spawn((
+        CloudLayer { mode },
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(materials.add(material)),
+        Transform::from_translation(origin),
+    ));
+}
+
+pub struct CloudsPlugin;
+
+impl Plugin for CloudsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_clouds);
+    }
+}
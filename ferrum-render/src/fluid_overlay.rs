@@ -0,0 +1,159 @@
+//! Screen tint and fog while the camera is inside water or lava.
+//!
+//! The app reports the fluid around the camera through [`CameraFluid`],
+//! usually by checking the world with [`fluid_at`]. While it is set, a
+//! full-screen tint is drawn under the HUD and the camera's distance fog is
+//! pulled in to the fluid's color. The fog from before is restored on leaving.
+
+use bevy::pbr::{DistanceFog, FogFalloff};
+use bevy::prelude::*;
+use ferrum_core::{fluid_level, BlockState, LAVA, MAX_FLUID_LEVEL, WATER};
+
+/// A fluid the camera can be inside.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmergedFluid {
+    Water,
+    Lava,
+}
+
+impl SubmergedFluid {
+    /// The fluid a block is, at any level.
+    pub fn from_state(state: BlockState) -> Option<Self> {
+        match state.id() {
+            WATER => Some(Self::Water),
+            LAVA => Some(Self::Lava),
+            _ => None,
+        }
+    }
+
+    /// Translucent tint drawn over the whole screen.
+    pub fn overlay_color(self) -> Color {
+        match self {
+            Self::Water => Color::srgba(0.1, 0.25, 0.7, 0.3),
+            Self::Lava => Color::srgba(0.9, 0.3, 0.05, 0.6),
+        }
+    }
+
+    pub fn fog_color(self) -> Color {
+        match self {
+            Self::Water => Color::srgb(0.05, 0.15, 0.45),
+            Self::Lava => Color::srgb(0.6, 0.1, 0.0),
+        }
+    }
+
+    /// Fog distances, in blocks. Lava is nearly opaque.
+    pub fn fog_falloff(self) -> FogFalloff {
+        match self {
+            Self::Water => FogFalloff::Linear {
+                start: 0.0,
+                end: 48.0,
+            },
+            Self::Lava => FogFalloff::Linear {
+                start: 0.0,
+                end: 2.0,
+            },
+        }
+    }
+}
+
+/// Height of a fluid's surface within its cell, from just above 0 to 1, or
+/// `None` if the block is not a fluid.
+pub fn fluid_surface_height(state: BlockState) -> Option<f32> {
+    let steps = (MAX_FLUID_LEVEL + 1) as f32;
+    fluid_level(state).map(|level| (steps - level as f32) / steps)
+}
+
+/// The fluid containing `position`, looking blocks up with `block_at`. A
+/// position above the surface of a partly filled cell is not inside it.
+pub fn fluid_at(position: Vec3, block_at: impl Fn(IVec3) -> BlockState) -> Option<SubmergedFluid> {
+    let cell = position.floor().as_ivec3();
+    let state = block_at(cell);
+    let surface = fluid_surface_height(state)?;
+    if position.y - cell.y as f32 >= surface {
+        return None;
+    }
+    SubmergedFluid::from_state(state)
+}
+
+/// The fluid the camera is inside, if any.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CameraFluid(pub Option<SubmergedFluid>);
+
+/// The full-screen tint node.
+#[derive(Component)]
+pub struct FluidOverlay;
+
+/// A camera's fog from before it entered a fluid.
+#[derive(Component, Clone)]
+pub struct SurfaceFog(pub DistanceFog);
+
+fn spawn_fluid_overlay(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        BackgroundColor(Color::NONE),
+        // Below the HUD and menus
+        GlobalZIndex(-1),
+        Visibility::Hidden,
+        FluidOverlay,
+    ));
+}
+
+/// Show the tint for the current [`CameraFluid`].
+pub fn update_fluid_overlay(
+    camera_fluid: Res<CameraFluid>,
+    mut overlays: Query<(&mut BackgroundColor, &mut Visibility), With<FluidOverlay>>,
+) {
+    if !camera_fluid.is_changed() {
+        return;
+    }
+    for (mut background, mut visibility) in &mut overlays {
+        match camera_fluid.0 {
+            Some(fluid) => {
+                background.0 = fluid.overlay_color();
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
+
+/// Replace the fog of 3D cameras while in a fluid, and put it back after.
+/// Runs every frame so it wins over systems that set the sky fog in `Update`.
+pub fn apply_fluid_fog(
+    mut commands: Commands,
+    camera_fluid: Res<CameraFluid>,
+    mut cameras: Query<(Entity, &mut DistanceFog, Option<&SurfaceFog>), With<Camera3d>>,
+) {
+    for (entity, mut fog, surface) in &mut cameras {
+        match (camera_fluid.0, surface) {
+            (Some(fluid), surface) => {
+                if surface.is_none() {
+                    commands.entity(entity).insert(SurfaceFog(fog.clone()));
+                }
+                fog.color = fluid.fog_color();
+                fog.directional_light_color = Color::NONE;
+                fog.falloff = fluid.fog_falloff();
+            }
+            (None, Some(surface)) => {
+                *fog = surface.0.clone();
+                commands.entity(entity).remove::<SurfaceFog>();
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+pub struct FluidOverlayPlugin;
+
+impl Plugin for FluidOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraFluid>()
+            .add_systems(Startup, spawn_fluid_overlay)
+            .add_systems(PostUpdate, (update_fluid_overlay, apply_fluid_fog));
+    }
+}
@@ -0,0 +1,112 @@
+//! Frustum culling of chunk meshes on the CPU.
+//!
+//! Chunks tagged with [`ChunkBounds`] are hidden while their box is outside
+//! the player camera's view, so they are neither extracted nor drawn.
+
+use crate::view_model::FirstPersonView;
+use bevy::camera::visibility::VisibilitySystems;
+use bevy::camera::CameraUpdateSystems;
+use bevy::prelude::*;
+use bevy::transform::TransformSystems;
+use ferrum_meshing_cpu::CHUNK_SIZE;
+
+/// The six planes bounding a view volume, as `(normal, distance)` with the
+/// normal pointing inwards: a point `p` is inside a plane when
+/// `normal.dot(p) + distance >= 0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    planes: [Vec4; 6],
+}
+
+impl Frustum {
+    /// Extract the planes of a view-projection matrix (Gribb-Hartmann), with
+    /// clip space depth in `0..=1` as Bevy's projections use. Works for
+    /// reversed and infinite depth too: an infinite far plane never rejects
+    /// anything.
+    pub fn from_view_projection(clip_from_world: Mat4) -> Self {
+        let [row0, row1, row2, row3] = [0, 1, 2, 3].map(|i| clip_from_world.row(i));
+        Self {
+            planes: [
+                row3 + row0,
+                row3 - row0,
+                row3 + row1,
+                row3 - row1,
+                row2,
+                row3 - row2,
+            ],
+        }
+    }
+
+    /// Whether any part of the box from `min` to `max` may be inside the
+    /// frustum. Conservative: a box near a corner of the frustum can pass
+    /// without being visible.
+    pub fn intersects_aabb(&self, min: Vec3, max: Vec3) -> bool {
+        self.planes.iter().all(|plane| {
+            let normal = plane.truncate();
+            // The corner furthest along the plane's normal
+            let corner = Vec3::select(normal.cmpge(Vec3::ZERO), max, min);
+            normal.dot(corner) + plane.w >= 0.0
+        })
+    }
+}
+
+/// World space box of a chunk mesh, for frustum culling.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct ChunkBounds {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl ChunkBounds {
+    /// Bounds of the chunk with its minimum corner at `origin`.
+    pub fn chunk(origin: IVec3) -> Self {
+        let min = origin.as_vec3();
+        Self {
+            min,
+            max: min + CHUNK_SIZE as f32,
+        }
+    }
+
+    /// Bounds of several chunks together, or `None` if there are none.
+    pub fn chunks(origins: impl IntoIterator<Item = IVec3>) -> Option<Self> {
+        origins.into_iter().map(Self::chunk).reduce(|a, b| Self {
+            min: a.min.min(b.min),
+            max: a.max.max(b.max),
+        })
+    }
+}
+
+/// Hide chunks outside the player camera's frustum and show them again once
+/// they come into view.
+pub fn cull_chunks(
+    cameras: Query<(&Camera, &GlobalTransform), With<FirstPersonView>>,
+    mut chunks: Query<(&ChunkBounds, &mut Visibility)>,
+) {
+    let Ok((camera, transform)) = cameras.single() else {
+        return;
+    };
+    let frustum =
+        Frustum::from_view_projection(camera.clip_from_view() * transform.to_matrix().inverse());
+
+    for (bounds, mut visibility) in &mut chunks {
+        visibility.set_if_neq(if frustum.intersects_aabb(bounds.min, bounds.max) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+pub struct FrustumCullingPlugin;
+
+impl Plugin for FrustumCullingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            cull_chunks
+                .after(TransformSystems::Propagate)
+                .after(CameraUpdateSystems)
+                .before(VisibilitySystems::VisibilityPropagate),
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Binary glTF (`.glb`) export of chunk meshes, or of every loaded chunk in
+//! [`ChunkGroups`], for inspection in external tools.
+//!
+//! Geometry comes from [`BlockRenderer::build_buffers`], so the exported file
+//! matches what the client uploads to the GPU. Vertex colors encode the block
+//! type so merged quads are easy to tell apart in Blender or an online viewer.
+
+use crate::{merge_chunk_meshes, BlockRenderer, ChunkGroups, MeshBuffers, TextureAtlas};
+use bevy::math::IVec3;
+use ferrum_meshing_cpu::ChunkMesh;
+use std::io;
+use std::path::Path;
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_VERSION: u32 = 2;
+const CHUNK_JSON: u32 = 0x4E4F_534A;
+const CHUNK_BIN: u32 = 0x004E_4942;
+
+const COMPONENT_FLOAT: u32 = 5126;
+const COMPONENT_UNSIGNED_INT: u32 = 5125;
+const TARGET_ARRAY_BUFFER: u32 = 34962;
+const TARGET_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// Export a mesh to a binary glTF file.
+pub trait GltfExport {
+    fn export_gltf(&self, atlas: &TextureAtlas, path: &Path) -> io::Result<()>;
+}
+
+impl GltfExport for ChunkMesh {
+    fn export_gltf(&self, atlas: &TextureAtlas, path: &Path) -> io::Result<()> {
+        let buffers = BlockRenderer::build_buffers(self, atlas, IVec3::ZERO);
+        write_glb(&buffers, path)
+    }
+}
+
+/// Every loaded chunk, each at its world position.
+impl GltfExport for ChunkGroups {
+    fn export_gltf(&self, atlas: &TextureAtlas, path: &Path) -> io::Result<()> {
+        let buffers = merge_chunk_meshes(self.chunks(), atlas);
+        write_glb(&buffers, path)
+    }
+}
+
+fn write_glb(buffers: &MeshBuffers, path: &Path) -> io::Result<()> {
+    let colors: Vec<[f32; 4]> = buffers
+        .block_types
+        .iter()
+        .map(|&block_type| block_type_color(block_type))
+        .collect();
+
+    let glb = encode_glb(
+        &buffers.positions,
+        &buffers.normals,
+        &buffers.uvs,
+        &colors,
+        &buffers.indices,
+    );
+    std::fs::write(path, glb)
+}
+
+/// Stable, well-separated debug color per block type.
+fn block_type_color(block_type: u32) -> [f32; 4] {
+    let hue = (block_type as f32 * 0.618_034).fract();
+    let h = hue * 6.0;
+    let x = 1.0 - (h % 2.0 - 1.0).abs();
+    let (r, g, b) = match h as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    [r, g, b, 1.0]
+}
+
+fn encode_glb(
+    positions: &[[f32; 3]],
+    normals: &[[f32; 3]],
+    uvs: &[[f32; 2]],
+    colors: &[[f32; 4]],
+    indices: &[u32],
+) -> Vec<u8> {
+    let mut bin = Vec::new();
+    let mut views = Vec::new();
+    let mut accessors = Vec::new();
+
+    if !indices.is_empty() {
+        let (min, max) = bounds(positions);
+        let vertex_count = positions.len();
+
+        let mut push_view = |bytes: &[u8], target: u32| {
+            views.push(format!(
+                r#"{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{}}}"#,
+                bin.len(),
+                bytes.len(),
+                target
+            ));
+            bin.extend_from_slice(bytes);
+            views.len() - 1
+        };
+
+        let position_view = push_view(&flatten(positions), TARGET_ARRAY_BUFFER);
+        let normal_view = push_view(&flatten(normals), TARGET_ARRAY_BUFFER);
+        let uv_view = push_view(&flatten(uvs), TARGET_ARRAY_BUFFER);
+        let color_view = push_view(&flatten(colors), TARGET_ARRAY_BUFFER);
+        let index_bytes: Vec<u8> = indices.iter().flat_map(|i| i.to_le_bytes()).collect();
+        let index_view = push_view(&index_bytes, TARGET_ELEMENT_ARRAY_BUFFER);
+
+        accessors.push(format!(
+            r#"{{"bufferView":{},"componentType":{},"count":{},"type":"VEC3","min":[{},{},{}],"max":[{},{},{}]}}"#,
+            position_view,
+            COMPONENT_FLOAT,
+            vertex_count,
+            min[0],
+            min[1],
+            min[2],
+            max[0],
+            max[1],
+            max[2]
+        ));
+        for (view, ty) in [
+            (normal_view, "VEC3"),
+            (uv_view, "VEC2"),
+            (color_view, "VEC4"),
+        ] {
+            accessors.push(format!(
+                r#"{{"bufferView":{},"componentType":{},"count":{},"type":"{}"}}"#,
+                view, COMPONENT_FLOAT, vertex_count, ty
+            ));
+        }
+        accessors.push(format!(
+            r#"{{"bufferView":{},"componentType":{},"count":{},"type":"SCALAR"}}"#,
+            index_view,
+            COMPONENT_UNSIGNED_INT,
+            indices.len()
+        ));
+    }
+
+    let json = if accessors.is_empty() {
+        // glTF forbids zero-length accessors, so an empty mesh is an empty scene.
+        r#"{"asset":{"version":"2.0","generator":"ferrum-render"},"scene":0,"scenes":[{"nodes":[]}]}"#
+            .to_string()
+    } else {
+        format!(
+            concat!(
+                r#"{{"asset":{{"version":"2.0","generator":"ferrum-render"}},"#,
+                r#""scene":0,"scenes":[{{"nodes":[0]}}],"nodes":[{{"mesh":0}}],"#,
+                r#""meshes":[{{"primitives":[{{"attributes":{{"POSITION":0,"NORMAL":1,"TEXCOORD_0":2,"COLOR_0":3}},"indices":4,"mode":4}}]}}],"#,
+                r#""buffers":[{{"byteLength":{}}}],"bufferViews":[{}],"accessors":[{}]}}"#
+            ),
+            bin.len(),
+            views.join(","),
+            accessors.join(",")
+        )
+    };
+
+    let mut json_bytes = json.into_bytes();
+    pad_to_four(&mut json_bytes, b' ');
+    pad_to_four(&mut bin, 0);
+
+    let mut total_len = 12 + 8 + json_bytes.len();
+    if !bin.is_empty() {
+        total_len += 8 + bin.len();
+    }
+
+    let mut out = Vec::with_capacity(total_len);
+    out.extend_from_slice(&GLB_MAGIC.to_le_bytes());
+    out.extend_from_slice(&GLB_VERSION.to_le_bytes());
+    out.extend_from_slice(&(total_len as u32).to_le_bytes());
+    out.extend_from_slice(&(json_bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(&CHUNK_JSON.to_le_bytes());
+    out.extend_from_slice(&json_bytes);
+    if !bin.is_empty() {
+        out.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+        out.extend_from_slice(&CHUNK_BIN.to_le_bytes());
+        out.extend_from_slice(&bin);
+    }
+    out
+}
+
+fn flatten<const N: usize>(values: &[[f32; N]]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|v| v.iter().flat_map(|c| c.to_le_bytes()))
+        .collect()
+}
+
+fn bounds(positions: &[[f32; 3]]) -> ([f32; 3], [f32; 3]) {
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+    for p in positions {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(p[axis]);
+            max[axis] = max[axis].max(p[axis]);
+        }
+    }
+    (min, max)
+}
+
+fn pad_to_four(bytes: &mut Vec<u8>, fill: u8) {
+    let padding = (4 - bytes.len() % 4) % 4;
+    bytes.resize(bytes.len() + padding, fill);
+}
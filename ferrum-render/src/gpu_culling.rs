@@ -0,0 +1,57 @@
+//! Frustum and distance culling of chunk meshes on the GPU.
+//!
+//! The same test as [`Frustum::intersects_aabb`](crate::Frustum), run as a
+//! compute pass from `ferrum-meshing-gpu`, for when there are too many
+//! chunks to test on the CPU every frame.
+
+use crate::frustum::ChunkBounds;
+use bevy::prelude::*;
+use ferrum_meshing_gpu::{CullChunk, GpuChunkCuller};
+
+pub struct GpuFrustumCuller {
+    culler: GpuChunkCuller,
+    chunks: Vec<CullChunk>,
+}
+
+impl GpuFrustumCuller {
+    /// A culler on its own device, or `None` without a usable GPU.
+    pub fn new() -> Option<Self> {
+        Some(Self::from_culler(GpuChunkCuller::new()?))
+    }
+
+    pub fn from_culler(culler: GpuChunkCuller) -> Self {
+        Self {
+            culler,
+            chunks: Vec::new(),
+        }
+    }
+
+    /// Indices into `bounds` of the chunks that may be inside the frustum of
+    /// `clip_from_world` and come within `max_distance` of `camera`, in
+    /// ascending order. Pass `f32::INFINITY` to cull by the frustum alone.
+    pub fn visible_chunks(
+        &mut self,
+        bounds: &[ChunkBounds],
+        clip_from_world: Mat4,
+        camera: Vec3,
+        max_distance: f32,
+    ) -> Vec<usize> {
+        self.chunks.clear();
+        self.chunks.extend(
+            bounds
+                .iter()
+                .map(|bounds| CullChunk::new(bounds.min.to_array(), bounds.max.to_array())),
+        );
+        self.culler
+            .cull(
+                &self.chunks,
+                clip_from_world.to_cols_array_2d(),
+                camera.to_array(),
+                max_distance,
+            )
+            .visible
+            .into_iter()
+            .map(|index| index as usize)
+            .collect()
+    }
+}
@@ -0,0 +1,300 @@
+//! Item icons for inventory and hotbar slots.
+//!
+//! Block items are drawn as a small isometric cube textured with the
+//! block's atlas tiles, other items as a flat sprite. Each icon is painted
+//! into its own texture the first time an item is shown and kept in
+//! [`ItemIcons`] for every later slot showing the same item.
+
+use crate::chunk_groups::ChunkGroupRendering;
+use crate::texture_atlas::TextureAtlas;
+use crate::view_model::item_color;
+use bevy::asset::RenderAssetUsages;
+use bevy::ecs::system::SystemParam;
+use bevy::image::ImageSampler;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use ferrum_inventory::items;
+use ferrum_meshing_cpu::Face;
+use std::collections::HashMap;
+
+/// Width and height of an icon in pixels.
+pub const ITEM_ICON_SIZE: u32 = 32;
+
+/// How an item's icon is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemIconKind {
+    /// An isometric cube with the textures of this block type.
+    Block(u32),
+    /// A flat sprite.
+    Sprite,
+}
+
+impl ItemIconKind {
+    pub fn of(item_id: u32) -> Self {
+        match item_block_type(item_id) {
+            Some(block_type) => ItemIconKind::Block(block_type),
+            None => ItemIconKind::Sprite,
+        }
+    }
+}
+
+/// The block type placed by a block item, or `None` for other items.
+pub fn item_block_type(item_id: u32) -> Option<u32> {
+    let item_id = u16::try_from(item_id).ok()?;
+    let block_type = match item_id {
+        items::STONE => 1,
+        items::DIRT => 2,
+        items::GRASS_BLOCK => 3,
+        items::SAND => 7,
+        items::GRAVEL => 8,
+        items::GOLD_ORE => 9,
+        items::IRON_ORE => 10,
+        items::COAL_ORE => 11,
+        items::OAK_LOG => 12,
+        items::OAK_LEAVES => 13,
+        items::OAK_PLANKS => 14,
+        items::COBBLESTONE => 15,
+        items::DIAMOND_ORE => 16,
+        items::DEEPSLATE | items::COBBLED_DEEPSLATE => 17,
+        items::SNOW_BLOCK => 18,
+        items::ICE => 19,
+        items::CLAY => 20,
+        items::OBSIDIAN => 21,
+        items::NETHERRACK => 22,
+        items::GLOWSTONE => 23,
+        items::SOUL_SAND => 24,
+        items::TERRACOTTA => 25,
+        _ => return None,
+    };
+    Some(block_type)
+}
+
+/// Icon textures by item id, each painted once.
+#[derive(Resource, Default)]
+pub struct ItemIcons {
+    icons: HashMap<u32, Handle<Image>>,
+}
+
+impl ItemIcons {
+    pub fn get(&self, item_id: u32) -> Option<&Handle<Image>> {
+        self.icons.get(&item_id)
+    }
+
+    /// The icon of `item_id`, painted by `paint` and added to `images` if
+    /// the item has none yet.
+    pub fn get_or_insert_with(
+        &mut self,
+        item_id: u32,
+        images: &mut Assets<Image>,
+        paint: impl FnOnce(&Assets<Image>) -> Image,
+    ) -> Handle<Image> {
+        if let Some(icon) = self.icons.get(&item_id) {
+            return icon.clone();
+        }
+        let icon = images.add(paint(images));
+        self.icons.insert(item_id, icon.clone());
+        icon
+    }
+
+    /// Forget every icon, so each is painted again when next asked for.
+    pub fn clear(&mut self) {
+        self.icons.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.icons.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.icons.is_empty()
+    }
+}
+
+/// Half the width of the cube's top face, in pixels.
+const CUBE_HALF_WIDTH: f32 = 15.0;
+/// Half the height of the cube's top face, in pixels.
+const CUBE_HALF_DEPTH: f32 = 7.5;
+/// Height of the cube's sides, in pixels.
+const CUBE_SIDE_HEIGHT: f32 = 16.0;
+
+/// An isometric cube of `block_type`, showing its top, front (+Z) and right
+/// (+X) faces, the sides shaded darker than the top.
+pub fn paint_block_icon(block_type: u32, atlas: &TextureAtlas, atlas_image: &Image) -> Image {
+    let size = ITEM_ICON_SIZE as usize;
+    let centre = ITEM_ICON_SIZE as f32 / 2.0;
+    let top = (ITEM_ICON_SIZE as f32 - 2.0 * CUBE_HALF_DEPTH - CUBE_SIDE_HEIGHT) / 2.0;
+    let (a, b, h) = (CUBE_HALF_WIDTH, CUBE_HALF_DEPTH, CUBE_SIDE_HEIGHT);
+    let unit = 0.0..=1.0;
+
+    let mut pixels = vec![0u8; size * size * 4];
+    for py in 0..size {
+        for px in 0..size {
+            let dx = px as f32 + 0.5 - centre;
+            let dy = py as f32 + 0.5 - top;
+
+            // Screen position of cube point (x, y, z) is
+            // (centre + (x - z) * a, top + (x + z) * b + (1 - y) * h)
+            let (x, z) = ((dx / a + dy / b) / 2.0, (dy / b - dx / a) / 2.0);
+            let hit = if unit.contains(&x) && unit.contains(&z) {
+                Some((Face::Up, x, z, 1.0))
+            } else if dx >= 0.0 {
+                let z = 1.0 - dx / a;
+                let y = 1.0 - (dy - (1.0 + z) * b) / h;
+                (unit.contains(&z) && unit.contains(&y)).then_some((
+                    Face::Right,
+                    1.0 - z,
+                    1.0 - y,
+                    0.6,
+                ))
+            } else {
+                let x = 1.0 + dx / a;
+                let y = 1.0 - (dy - (x + 1.0) * b) / h;
+                (unit.contains(&x) && unit.contains(&y)).then_some((Face::Front, x, 1.0 - y, 0.8))
+            };
+
+            let Some((face, u, v, shade)) = hit else {
+                continue;
+            };
+            let Some(texel) = atlas_texel(atlas, atlas_image, atlas.tile(block_type, face), u, v)
+            else {
+                continue;
+            };
+            let index = (py * size + px) * 4;
+            for channel in 0..3 {
+                pixels[index + channel] = (texel[channel] as f32 * shade) as u8;
+            }
+            pixels[index + 3] = texel[3];
+        }
+    }
+    icon_image(pixels)
+}
+
+/// The texel at (`u`, `v`), each 0 to 1, of an atlas tile.
+fn atlas_texel(
+    atlas: &TextureAtlas,
+    atlas_image: &Image,
+    (tile_x, tile_y): (u32, u32),
+    u: f32,
+    v: f32,
+) -> Option<[u8; 4]> {
+    let tile_size = atlas.tile_size();
+    let texel = |t: f32| ((t * tile_size as f32) as u32).min(tile_size - 1);
+    let x = tile_x * tile_size + texel(u);
+    let y = tile_y * tile_size + texel(v);
+    let width = atlas_image.width();
+    if x >= width || y >= atlas_image.height() {
+        return None;
+    }
+    // Mip level 0 comes first
+    let index = ((y * width + x) * 4) as usize;
+    atlas_image
+        .data
+        .as_ref()?
+        .get(index..index + 4)
+        .map(|texel| [texel[0], texel[1], texel[2], texel[3]])
+}
+
+/// A flat sprite in the item's color: a tool head on a handle for tools,
+/// a gem shape for anything else.
+pub fn paint_sprite_icon(item_id: u32) -> Image {
+    let size = ITEM_ICON_SIZE as usize;
+    let color = item_color(item_id).to_srgba().to_u8_array();
+    let outline = [color[0] / 2, color[1] / 2, color[2] / 2, 255];
+    let handle = [110, 80, 45, 255];
+    let is_tool = u16::try_from(item_id).ok().and_then(items::tool).is_some();
+
+    let mut pixels = vec![0u8; size * size * 4];
+    let centre = ITEM_ICON_SIZE as f32 / 2.0;
+    for py in 0..size {
+        for px in 0..size {
+            let (x, y) = (px as f32 + 0.5 - centre, py as f32 + 0.5 - centre);
+            let texel = if is_tool {
+                // Handle along the diagonal from the bottom left, head
+                // across it at the top right
+                let along = (x - y) / 2.0;
+                let across = (x + y).abs() / 2.0;
+                if (along - 9.0).abs() < 2.0 && across < 7.0 {
+                    Some(color)
+                } else if along > -12.0 && along < 9.0 && across < 1.5 {
+                    Some(handle)
+                } else {
+                    None
+                }
+            } else {
+                let distance = x.abs() + y.abs();
+                if distance < 9.0 {
+                    Some(color)
+                } else if distance < 11.0 {
+                    Some(outline)
+                } else {
+                    None
+                }
+            };
+            if let Some(texel) = texel {
+                let index = (py * size + px) * 4;
+                pixels[index..index + 4].copy_from_slice(&texel);
+            }
+        }
+    }
+    icon_image(pixels)
+}
+
+fn icon_image(pixels: Vec<u8>) -> Image {
+    let mut image = Image::new(
+        Extent3d {
+            width: ITEM_ICON_SIZE,
+            height: ITEM_ICON_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        pixels,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+/// Looks up and paints item icons, using the chunk atlas for block items.
+#[derive(SystemParam)]
+pub struct ItemIconPainter<'w> {
+    icons: ResMut<'w, ItemIcons>,
+    images: ResMut<'w, Assets<Image>>,
+    materials: Res<'w, Assets<StandardMaterial>>,
+    rendering: Option<Res<'w, ChunkGroupRendering>>,
+}
+
+impl ItemIconPainter<'_> {
+    /// The icon of `item_id`, or `None` for a block item until the chunk
+    /// atlas is loaded.
+    pub fn icon(&mut self, item_id: u32) -> Option<Handle<Image>> {
+        if let Some(icon) = self.icons.get(item_id) {
+            return Some(icon.clone());
+        }
+        match ItemIconKind::of(item_id) {
+            ItemIconKind::Sprite => Some(self.icons.get_or_insert_with(
+                item_id,
+                &mut self.images,
+                |_| paint_sprite_icon(item_id),
+            )),
+            ItemIconKind::Block(block_type) => {
+                let rendering = self.rendering.as_ref()?;
+                let material = self.materials.get(&rendering.material)?;
+                let atlas_image = self.images.get(material.base_color_texture.as_ref()?)?;
+                let icon = paint_block_icon(block_type, &rendering.atlas, atlas_image);
+                Some(
+                    self.icons
+                        .get_or_insert_with(item_id, &mut self.images, |_| icon),
+                )
+            }
+        }
+    }
+}
+
+pub struct ItemIconsPlugin;
+
+impl Plugin for ItemIconsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ItemIcons>();
+    }
+}
@@ -1,12 +1,94 @@
+mod anti_aliasing;
+mod atlas_swap;
 mod block_renderer;
+mod brightness;
+mod camera_effects;
+mod chunk_fade;
+mod chunk_groups;
+mod chunk_lod;
+mod clouds;
+mod fluid_overlay;
+mod frustum;
+mod gltf_export;
+mod gpu_culling;
+mod item_icons;
 pub mod lighting;
 pub mod lod;
+mod lod_fade;
+mod lod_jobs;
+mod mesh_upload;
+mod particles;
+mod shadows;
+mod texture_animation;
 mod texture_atlas;
+mod update_throttle;
+mod view_model;
 
-pub use block_renderer::BlockRenderer;
+pub use anti_aliasing::{apply_anti_aliasing, camera_anti_aliasing, AntiAliasingPlugin};
+pub use atlas_swap::{apply_atlas_swaps, swap_atlas_image, AtlasImageSwapped, AtlasSwapPlugin};
+pub use block_renderer::{BlockRenderer, MeshBuffers, AO_BRIGHTNESS};
+pub use brightness::{
+    apply_brightness, apply_light_levels, brightness_to_gamma, light_factor, BrightnessPlugin,
+    LightLevelMaterials, FULLBRIGHT_AMBIENT, MAX_GAMMA,
+};
+pub use camera_effects::{
+    apply_dynamic_fov, apply_view_bobbing, ease_fov, target_fov, CameraBob, CameraEffectsPlugin,
+    Sprinting, SPRINT_FOV_SCALE,
+};
+pub use chunk_fade::{
+    start_chunk_fades, update_chunk_fades, ChunkFade, ChunkFadePlugin, FadeOpaqueMaterial,
+};
+pub use chunk_groups::{
+    apply_chunk_group_size, merge_chunk_meshes, rebuild_chunk_groups, ChunkGroupMesh,
+    ChunkGroupPlugin, ChunkGroupRendering, ChunkGroups,
+};
+pub use chunk_lod::{
+    adapt_chunk_lods, chunk_distance, chunk_neighbors, chunk_voxels, column_distance,
+    column_section_voxels, AdaptiveLodPlugin, ChunkLodUpdate, ChunkLods, DEFAULT_LOD_HYSTERESIS,
+};
+pub use clouds::{
+    cloud_mesh, cloud_offset, cloud_origin, update_clouds, CloudLayer, CloudsPlugin,
+    CLOUD_CELL_SIZE, CLOUD_GRID, CLOUD_PERIOD, CLOUD_SPEED, CLOUD_THICKNESS,
+};
+pub use fluid_overlay::{
+    apply_fluid_fog, fluid_at, fluid_surface_height, update_fluid_overlay, CameraFluid,
+    FluidOverlay, FluidOverlayPlugin, SubmergedFluid, SurfaceFog,
+};
+pub use frustum::{cull_chunks, ChunkBounds, Frustum, FrustumCullingPlugin};
+pub use gltf_export::GltfExport;
+pub use gpu_culling::GpuFrustumCuller;
+pub use item_icons::{
+    item_block_type, paint_block_icon, paint_sprite_icon, ItemIconKind, ItemIconPainter, ItemIcons,
+    ItemIconsPlugin, ITEM_ICON_SIZE,
+};
 pub use lighting::LightingEngine;
-pub use lod::{LodConfig, LodLevel, LodMesher, LodStats, LodTransition};
-pub use texture_atlas::TextureAtlas;
+pub use lod::{
+    AdaptiveLod, AdaptiveLodSettings, LodConfig, LodLevel, LodMesher, LodStats, LodTransition,
+};
+pub use lod_fade::{
+    apply_lod_fade, dither_threshold, LodCrossFadePlugin, LodDither, LodDitherMaterial, LodFade,
+    LodFadeLayer,
+};
+pub use lod_jobs::LodMeshJobs;
+pub use mesh_upload::{
+    upload_chunk_meshes, MeshUploadBudget, MeshUploadPlugin, PendingChunkMesh,
+    DEFAULT_UPLOADS_PER_FRAME,
+};
+pub use particles::{AmbientParticle, Particle, ParticleSystem, DEFAULT_MAX_PARTICLES};
+pub use shadows::{
+    apply_shadows, ShadowSettings, ShadowsPlugin, MAX_SHADOW_CASCADES, MAX_SHADOW_MAP_SIZE,
+    MIN_SHADOW_DISTANCE, MIN_SHADOW_MAP_SIZE,
+};
+pub use texture_animation::{
+    animate_textures, AnimatedMaterial, TextureAnimation, TextureAnimationPlugin, TextureAnimations,
+};
+pub use texture_atlas::{TextureAtlas, TextureVariant, DEFAULT_UV_INSET};
+pub use update_throttle::{ThrottledUpdate, UpdateThrottle};
+pub use view_model::{
+    animate_view_model, update_held_item, FirstPersonView, HeldItem, HotbarSelection,
+    SwingAnimation, SwingHand, ViewBob, ViewModel, ViewModelCamera, ViewModelPlugin, HOTBAR_SLOTS,
+    VIEW_MODEL_CAMERA_ORDER, VIEW_MODEL_LAYER,
+};
 
 use thiserror::Error;
 
@@ -17,6 +99,9 @@ pub enum RenderError {
 
     #[error("Invalid mesh data: {0}")]
     InvalidMeshData(String),
+
+    #[error("Invalid texture animation: {0}")]
+    InvalidAnimation(String),
 }
 
 pub type RenderResult<T> = Result<T, RenderError>;
@@ -1,11 +1,19 @@
+use ferrum_core::{properties, BlockId, BlockState, LEAVES, WATER};
+use ferrum_meshing_cpu::CHUNK_SIZE_CB;
+use ferrum_world::Chunk;
 use std::collections::VecDeque;
 
 pub const CHUNK_SIZE: usize = 32;
 
+/// Block types that dim skylight rather than block it: water and leaves.
+pub const SKYLIGHT_FILTERS: [BlockId; 2] = [WATER, LEAVES];
+
 pub struct LightingEngine {
     block_light: [[[u8; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
     sky_light: [[[u8; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
     opaque: [[[bool; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
+    skylight_attenuation: u8,
+    skylight_smoothing: bool,
 }
 
 impl LightingEngine {
@@ -14,9 +22,24 @@ impl LightingEngine {
             block_light: [[[0; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
             sky_light: [[[0; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
             opaque: [[[false; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
+            skylight_attenuation: 1,
+            skylight_smoothing: true,
         }
     }
 
+    /// How much skylight each [`SKYLIGHT_FILTERS`] block takes away. 1 by
+    /// default.
+    pub fn set_skylight_attenuation(&mut self, amount: u8) {
+        self.skylight_attenuation = amount;
+    }
+
+    /// Whether [`compute_skylight`](Self::compute_skylight) spreads light one
+    /// block sideways after lighting columns, so cliff faces and overhangs
+    /// are not pitch black. On by default.
+    pub fn set_skylight_smoothing(&mut self, enabled: bool) {
+        self.skylight_smoothing = enabled;
+    }
+
     pub fn set_opaque(&mut self, x: usize, y: usize, z: usize, is_opaque: bool) {
         if x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE {
             return;
@@ -82,41 +105,49 @@ impl LightingEngine {
             }
         }
 
-        while let Some((x, y, z)) = queue.pop_front() {
-            let current_light = result[x][y][z];
-            if current_light <= 1 {
-                continue;
-            }
-
-            let new_light = current_light - 1;
-            let neighbors = [
-                (x + 1, y, z),
-                (x.wrapping_sub(1), y, z),
-                (x, y + 1, z),
-                (x, y.wrapping_sub(1), z),
-                (x, y, z + 1),
-                (x, y, z.wrapping_sub(1)),
-            ];
-
-            for (nx, ny, nz) in neighbors {
-                if nx >= CHUNK_SIZE || ny >= CHUNK_SIZE || nz >= CHUNK_SIZE {
-                    continue;
-                }
+        spread_block_light(&mut result, opaque, queue);
+        self.block_light = result;
+    }
 
-                if opaque[nx][ny][nz] {
-                    continue;
+    /// Replace the block light with light flood-filled through `chunk` from
+    /// `sources`, each `(x, y, z, level)` with a level up to 15.
+    ///
+    /// Light drops by one per block and passes through air and fluids but not
+    /// opaque blocks. A source still lights its neighbours when its own block
+    /// is opaque, like glowstone. Where sources overlap the brightest wins.
+    /// Sources outside the chunk are ignored, and light never leaves it.
+    pub fn propagate_block_light_from(&mut self, chunk: &Chunk, sources: &[(u8, u8, u8, u8)]) {
+        for x in 0..CHUNK_SIZE {
+            for y in 0..CHUNK_SIZE {
+                for z in 0..CHUNK_SIZE {
+                    self.opaque[x][y][z] = properties(chunk.get_block(x, y, z)).opaque;
                 }
+            }
+        }
 
-                if result[nx][ny][nz] < new_light {
-                    result[nx][ny][nz] = new_light;
-                    queue.push_back((nx, ny, nz));
-                }
+        let mut result = [[[0u8; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE];
+        let mut queue = VecDeque::new();
+        for &(x, y, z, level) in sources {
+            let (x, y, z) = (x as usize, y as usize, z as usize);
+            if x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE {
+                continue;
+            }
+            let level = level.min(15);
+            if result[x][y][z] < level {
+                result[x][y][z] = level;
+                queue.push_back((x, y, z));
             }
         }
 
+        spread_block_light(&mut result, &self.opaque, queue);
         self.block_light = result;
     }
 
+    /// Block light at a position, 0 outside the chunk.
+    pub fn light_at(&self, x: usize, y: usize, z: usize) -> u8 {
+        self.get_block_light(x, y, z)
+    }
+
     pub fn propagate_sky_light(&mut self, opaque: &[[[bool; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE]) {
         let mut result = [[[0u8; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE];
         let mut queue = VecDeque::new();
@@ -167,6 +198,76 @@ impl LightingEngine {
         self.sky_light = result;
     }
 
+    /// Light every column of `voxels` from the sky down, and store and
+    /// return the result in the same `z, y, x` order as the voxels.
+    ///
+    /// Each column is 15 from the top down to its first opaque block and 0
+    /// below. Air (block 0) lets light through and [`SKYLIGHT_FILTERS`] dim
+    /// it; every other block is opaque. With smoothing on, one sideways pass
+    /// then gives each non-opaque block at least its brightest horizontal
+    /// neighbour minus one.
+    pub fn compute_skylight(&mut self, voxels: &[u32; CHUNK_SIZE_CB]) -> [u8; CHUNK_SIZE_CB] {
+        let index = |x: usize, y: usize, z: usize| (z * CHUNK_SIZE + y) * CHUNK_SIZE + x;
+        let filters = |block: u32| SKYLIGHT_FILTERS.contains(&BlockState::from_bits(block).id());
+
+        let mut light = [0u8; CHUNK_SIZE_CB];
+        for z in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let mut level = 15u8;
+                for y in (0..CHUNK_SIZE).rev() {
+                    let block = voxels[index(x, y, z)];
+                    if filters(block) {
+                        level = level.saturating_sub(self.skylight_attenuation);
+                    } else if block != 0 {
+                        break;
+                    }
+                    light[index(x, y, z)] = level;
+                }
+            }
+        }
+
+        if self.skylight_smoothing {
+            let columns = light;
+            for z in 0..CHUNK_SIZE {
+                for y in 0..CHUNK_SIZE {
+                    for x in 0..CHUNK_SIZE {
+                        let block = voxels[index(x, y, z)];
+                        if block != 0 && !filters(block) {
+                            continue;
+                        }
+                        let neighbors = [
+                            (x + 1, z),
+                            (x.wrapping_sub(1), z),
+                            (x, z + 1),
+                            (x, z.wrapping_sub(1)),
+                        ];
+                        let brightest = neighbors
+                            .into_iter()
+                            .filter(|&(nx, nz)| nx < CHUNK_SIZE && nz < CHUNK_SIZE)
+                            .map(|(nx, nz)| columns[index(nx, y, nz)])
+                            .max()
+                            .unwrap_or(0);
+                        let mut spread = brightest.saturating_sub(1);
+                        if filters(block) {
+                            spread = spread.saturating_sub(self.skylight_attenuation);
+                        }
+                        let cell = &mut light[index(x, y, z)];
+                        *cell = (*cell).max(spread);
+                    }
+                }
+            }
+        }
+
+        for z in 0..CHUNK_SIZE {
+            for y in 0..CHUNK_SIZE {
+                for x in 0..CHUNK_SIZE {
+                    self.sky_light[x][y][z] = light[index(x, y, z)];
+                }
+            }
+        }
+        light
+    }
+
     pub fn get_smooth_light(&self, x: usize, y: usize, z: usize, _face: usize) -> u8 {
         let x0 = x.saturating_sub(1);
         let y0 = y.saturating_sub(1);
@@ -255,6 +356,46 @@ impl LightingEngine {
     }
 }
 
+/// Spread block light from the queued blocks, one level lower per step,
+/// raising each non-opaque block to the brightest light that reaches it.
+fn spread_block_light(
+    result: &mut [[[u8; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
+    opaque: &[[[bool; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
+    mut queue: VecDeque<(usize, usize, usize)>,
+) {
+    while let Some((x, y, z)) = queue.pop_front() {
+        let current_light = result[x][y][z];
+        if current_light <= 1 {
+            continue;
+        }
+
+        let new_light = current_light - 1;
+        let neighbors = [
+            (x + 1, y, z),
+            (x.wrapping_sub(1), y, z),
+            (x, y + 1, z),
+            (x, y.wrapping_sub(1), z),
+            (x, y, z + 1),
+            (x, y, z.wrapping_sub(1)),
+        ];
+
+        for (nx, ny, nz) in neighbors {
+            if nx >= CHUNK_SIZE || ny >= CHUNK_SIZE || nz >= CHUNK_SIZE {
+                continue;
+            }
+
+            if opaque[nx][ny][nz] {
+                continue;
+            }
+
+            if result[nx][ny][nz] < new_light {
+                result[nx][ny][nz] = new_light;
+                queue.push_back((nx, ny, nz));
+            }
+        }
+    }
+}
+
 impl Default for LightingEngine {
     fn default() -> Self {
         Self::new()
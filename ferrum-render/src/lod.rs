@@ -1,16 +1,18 @@
 //! Level of Detail (LOD) system for chunk rendering.
 //!
-//! Supports 4 detail levels to enable 48-64 chunk render distances:
+//! Supports 5 detail levels to enable 48-64 chunk render distances and beyond:
 //!
 //! - **LOD 0** (0-16 chunks): Full detail — uses existing binary greedy meshing.
 //! - **LOD 1** (17-32 chunks): Reduced detail — 2x2x2 downsampling, simplified meshing.
 //! - **LOD 2** (33-48 chunks): Low detail — 4x4x4 downsampling, large quads only.
 //! - **LOD 3** (49-64 chunks): Minimal detail — 8x8x8 downsampling, silhouette only.
+//! - **LOD 4** (65+ chunks, opt-in): Far detail — 16x16x16 downsampling, a few quads per face.
 //!
 //! Each LOD level downsamples the voxel data by its scale factor, then runs a
 //! simplified greedy mesh on the reduced grid. The resulting quads are scaled back
 //! to chunk coordinates so they can be rendered with the same pipeline.
 
+use bevy::prelude::Resource;
 use ferrum_meshing_cpu::{ChunkMesh, Face, MeshQuad, CHUNK_SIZE, CHUNK_SIZE_CB, CHUNK_SIZE_SQ};
 
 /// LOD level identifier.
@@ -25,6 +27,9 @@ pub enum LodLevel {
     Low = 2,
     /// Minimal detail (49-64 chunks). 8x8x8 downsampling, silhouette.
     Minimal = 3,
+    /// Far detail (beyond 64 chunks). 16x16x16 downsampling, a 2x2x2 grid
+    /// of flat colored blocks per chunk.
+    Far = 4,
 }
 
 impl LodLevel {
@@ -35,6 +40,7 @@ impl LodLevel {
             LodLevel::Reduced => 2,
             LodLevel::Low => 4,
             LodLevel::Minimal => 8,
+            LodLevel::Far => 16,
         }
     }
 
@@ -44,12 +50,13 @@ impl LodLevel {
     }
 
     /// All LOD levels in order.
-    pub fn all() -> [LodLevel; 4] {
+    pub fn all() -> [LodLevel; 5] {
         [
             LodLevel::Full,
             LodLevel::Reduced,
             LodLevel::Low,
             LodLevel::Minimal,
+            LodLevel::Far,
         ]
     }
 }
@@ -66,6 +73,9 @@ pub struct LodConfig {
     pub reduced_max: f32,
     /// Maximum distance (inclusive) for low detail. Default: 48.
     pub low_max: f32,
+    /// Maximum distance (inclusive) for minimal detail. Chunks between this
+    /// and `max_render_distance` use [`LodLevel::Far`]. Default: 64.
+    pub minimal_max: f32,
     /// Maximum render distance. Chunks beyond this are not rendered. Default: 64.
     pub max_render_distance: f32,
     /// Width of the transition zone between LOD levels (in chunks). Default: 2.0.
@@ -78,6 +88,7 @@ impl Default for LodConfig {
             full_max: 16.0,
             reduced_max: 32.0,
             low_max: 48.0,
+            minimal_max: 64.0,
             max_render_distance: 64.0,
             transition_width: 2.0,
         }
@@ -85,17 +96,27 @@ impl Default for LodConfig {
 }
 
 impl LodConfig {
-    /// Create a config with custom distance thresholds.
+    /// Create a config with custom distance thresholds. Minimal detail
+    /// extends to the render distance, leaving no far band.
     pub fn new(full_max: f32, reduced_max: f32, low_max: f32, max_render_distance: f32) -> Self {
         Self {
             full_max,
             reduced_max,
             low_max,
+            minimal_max: max_render_distance,
             max_render_distance,
             transition_width: 2.0,
         }
     }
 
+    /// Extend the render distance to `max_render_distance` chunks, drawing
+    /// everything past the current minimal detail band at
+    /// [`LodLevel::Far`].
+    pub fn with_far_band(mut self, max_render_distance: f32) -> Self {
+        self.max_render_distance = max_render_distance.max(self.minimal_max);
+        self
+    }
+
     /// Determine the LOD level for a chunk at the given distance (in chunk units).
     pub fn select_lod(&self, distance: f32) -> Option<LodLevel> {
         if distance > self.max_render_distance {
@@ -106,8 +127,45 @@ impl LodConfig {
             Some(LodLevel::Reduced)
         } else if distance <= self.low_max {
             Some(LodLevel::Low)
-        } else {
+        } else if distance <= self.minimal_max {
             Some(LodLevel::Minimal)
+        } else {
+            Some(LodLevel::Far)
+        }
+    }
+
+    /// Like [`select_lod`](Self::select_lod), but a chunk already at
+    /// `current` keeps it until its distance leaves that level's range by
+    /// more than `hysteresis` chunks. A camera moving back and forth across a
+    /// boundary then doesn't make chunks near it switch LOD every frame.
+    pub fn select_lod_with_hysteresis(
+        &self,
+        distance: f32,
+        current: Option<LodLevel>,
+        hysteresis: f32,
+    ) -> Option<LodLevel> {
+        let selected = self.select_lod(distance);
+        let Some(current) = current else {
+            return selected;
+        };
+        let (min, max) = self.range(current);
+        if selected != Some(current) && distance >= min - hysteresis && distance <= max + hysteresis
+        {
+            Some(current)
+        } else {
+            selected
+        }
+    }
+
+    /// Distances, in chunks, selecting `lod`: above the first, up to and
+    /// including the second.
+    fn range(&self, lod: LodLevel) -> (f32, f32) {
+        match lod {
+            LodLevel::Full => (f32::NEG_INFINITY, self.full_max),
+            LodLevel::Reduced => (self.full_max, self.reduced_max),
+            LodLevel::Low => (self.reduced_max, self.low_max),
+            LodLevel::Minimal => (self.low_max, self.minimal_max),
+            LodLevel::Far => (self.minimal_max, self.max_render_distance),
         }
     }
 
@@ -120,18 +178,21 @@ impl LodConfig {
     pub fn select_lod_with_blend(&self, distance: f32) -> Option<LodTransition> {
         let lod = self.select_lod(distance)?;
 
+        let has_far_band = self.max_render_distance > self.minimal_max;
         let boundary = match lod {
             LodLevel::Full => self.full_max,
             LodLevel::Reduced => self.reduced_max,
             LodLevel::Low => self.low_max,
-            LodLevel::Minimal => self.max_render_distance,
+            LodLevel::Minimal if has_far_band => self.minimal_max,
+            LodLevel::Minimal | LodLevel::Far => self.max_render_distance,
         };
 
         let next_lod = match lod {
             LodLevel::Full => Some(LodLevel::Reduced),
             LodLevel::Reduced => Some(LodLevel::Low),
             LodLevel::Low => Some(LodLevel::Minimal),
-            LodLevel::Minimal => None,
+            LodLevel::Minimal if has_far_band => Some(LodLevel::Far),
+            LodLevel::Minimal | LodLevel::Far => None,
         };
 
         let half_width = self.transition_width / 2.0;
@@ -186,6 +247,7 @@ impl LodMesher {
             LodLevel::Reduced => Some(Self::mesh_downsampled(voxels, 2)),
             LodLevel::Low => Some(Self::mesh_downsampled(voxels, 4)),
             LodLevel::Minimal => Some(Self::mesh_downsampled(voxels, 8)),
+            LodLevel::Far => Some(Self::mesh_downsampled(voxels, 16)),
         }
     }
 
@@ -491,9 +553,9 @@ impl LodMesher {
 #[derive(Clone, Debug, Default)]
 pub struct LodStats {
     /// Number of chunks at each LOD level.
-    pub chunks_per_level: [u32; 4],
+    pub chunks_per_level: [u32; 5],
     /// Total quads generated at each LOD level.
-    pub quads_per_level: [u32; 4],
+    pub quads_per_level: [u32; 5],
 }
 
 impl LodStats {
@@ -529,6 +591,136 @@ impl LodStats {
     }
 }
 
+/// Bounds and pacing for [`AdaptiveLod`].
+#[derive(Clone, Debug)]
+pub struct AdaptiveLodSettings {
+    /// Frame time to aim for, in seconds.
+    pub target_frame_time: f32,
+    /// Smallest allowed threshold scale relative to the base config.
+    pub min_scale: f32,
+    /// Largest allowed threshold scale relative to the base config.
+    pub max_scale: f32,
+    /// Scale change applied per adjustment.
+    pub step: f32,
+    /// Frames averaged before each adjustment. Limits how often the
+    /// thresholds can change.
+    pub window_frames: u32,
+    /// Fraction of the target below which there is enough headroom to relax
+    /// the thresholds. Frame times between this and the target leave them
+    /// unchanged, which keeps the controller from oscillating.
+    pub headroom: f32,
+}
+
+impl Default for AdaptiveLodSettings {
+    fn default() -> Self {
+        Self::for_fps(60.0)
+    }
+}
+
+impl AdaptiveLodSettings {
+    /// Settings targeting the given frame rate.
+    pub fn for_fps(fps: f32) -> Self {
+        Self {
+            target_frame_time: 1.0 / fps,
+            min_scale: 0.25,
+            max_scale: 1.0,
+            step: 0.05,
+            window_frames: 30,
+            headroom: 0.8,
+        }
+    }
+}
+
+/// Adjusts LOD distance thresholds to keep frame time near a target.
+///
+/// Sustained over-budget frames pull the detail thresholds closer to the
+/// camera; sustained headroom pushes them back out towards the base config.
+/// The render distance itself is never changed.
+#[derive(Resource, Clone, Debug)]
+pub struct AdaptiveLod {
+    base: LodConfig,
+    current: LodConfig,
+    settings: AdaptiveLodSettings,
+    scale: f32,
+    window_total: f32,
+    window_len: u32,
+}
+
+impl Default for AdaptiveLod {
+    fn default() -> Self {
+        Self::new(LodConfig::default(), AdaptiveLodSettings::default())
+    }
+}
+
+impl AdaptiveLod {
+    pub fn new(base: LodConfig, settings: AdaptiveLodSettings) -> Self {
+        let mut adaptive = Self {
+            current: base.clone(),
+            base,
+            scale: settings.max_scale,
+            settings,
+            window_total: 0.0,
+            window_len: 0,
+        };
+        adaptive.apply_scale();
+        adaptive
+    }
+
+    /// Aim for `fps` from now on, keeping the current thresholds.
+    pub fn set_target_fps(&mut self, fps: f32) {
+        self.settings.target_frame_time = 1.0 / fps;
+    }
+
+    /// Thresholds to use for LOD selection this frame.
+    pub fn config(&self) -> &LodConfig {
+        &self.current
+    }
+
+    /// Current threshold scale relative to the base config.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Record a frame time in seconds. Returns `true` if the thresholds
+    /// changed.
+    pub fn record_frame(&mut self, frame_time: f32) -> bool {
+        self.window_total += frame_time;
+        self.window_len += 1;
+        if self.window_len < self.settings.window_frames.max(1) {
+            return false;
+        }
+
+        let average = self.window_total / self.window_len as f32;
+        self.window_total = 0.0;
+        self.window_len = 0;
+
+        let target = self.settings.target_frame_time;
+        let new_scale = if average > target {
+            self.scale - self.settings.step
+        } else if average < target * self.settings.headroom {
+            self.scale + self.settings.step
+        } else {
+            self.scale
+        }
+        .clamp(self.settings.min_scale, self.settings.max_scale);
+
+        if new_scale == self.scale {
+            return false;
+        }
+        self.scale = new_scale;
+        self.apply_scale();
+        true
+    }
+
+    fn apply_scale(&mut self) {
+        let limit = self.base.max_render_distance;
+        self.current.full_max = (self.base.full_max * self.scale).min(limit);
+        self.current.reduced_max = (self.base.reduced_max * self.scale).min(limit);
+        self.current.low_max = (self.base.low_max * self.scale).min(limit);
+        self.current.minimal_max = (self.base.minimal_max * self.scale).min(limit);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -540,6 +732,7 @@ mod tests {
         assert_eq!(LodLevel::Reduced.scale(), 2);
         assert_eq!(LodLevel::Low.scale(), 4);
         assert_eq!(LodLevel::Minimal.scale(), 8);
+        assert_eq!(LodLevel::Far.scale(), 16);
     }
 
     #[test]
@@ -548,6 +741,7 @@ mod tests {
         assert_eq!(LodLevel::Reduced.grid_size(), 16);
         assert_eq!(LodLevel::Low.grid_size(), 8);
         assert_eq!(LodLevel::Minimal.grid_size(), 4);
+        assert_eq!(LodLevel::Far.grid_size(), 2);
     }
 
     #[test]
@@ -555,6 +749,27 @@ mod tests {
         assert!(LodLevel::Full < LodLevel::Reduced);
         assert!(LodLevel::Reduced < LodLevel::Low);
         assert!(LodLevel::Low < LodLevel::Minimal);
+        assert!(LodLevel::Minimal < LodLevel::Far);
+    }
+
+    #[test]
+    fn hysteresis_keeps_the_current_level_near_a_boundary() {
+        let config = LodConfig::default();
+        let full = Some(LodLevel::Full);
+        let reduced = Some(LodLevel::Reduced);
+        assert_eq!(config.select_lod_with_hysteresis(16.5, full, 1.0), full);
+        assert_eq!(config.select_lod_with_hysteresis(17.5, full, 1.0), reduced);
+        assert_eq!(
+            config.select_lod_with_hysteresis(15.5, reduced, 1.0),
+            reduced
+        );
+        assert_eq!(config.select_lod_with_hysteresis(14.5, reduced, 1.0), full);
+        assert_eq!(config.select_lod_with_hysteresis(16.5, None, 1.0), reduced);
+        assert_eq!(
+            config.select_lod_with_hysteresis(64.5, Some(LodLevel::Minimal), 1.0),
+            Some(LodLevel::Minimal)
+        );
+        assert_eq!(config.select_lod_with_hysteresis(16.5, full, 0.0), reduced);
     }
 
     #[test]
@@ -581,6 +796,33 @@ mod tests {
         assert_eq!(config.select_lod(33.0), None);
     }
 
+    #[test]
+    fn far_band_beyond_minimal_detail() {
+        let config = LodConfig::default().with_far_band(128.0);
+        assert_eq!(config.select_lod(64.0), Some(LodLevel::Minimal));
+        assert_eq!(config.select_lod(65.0), Some(LodLevel::Far));
+        assert_eq!(config.select_lod(128.0), Some(LodLevel::Far));
+        assert_eq!(config.select_lod(129.0), None);
+
+        let transition = config.select_lod_with_blend(64.0).unwrap();
+        assert_eq!(transition.primary, LodLevel::Minimal);
+        assert_eq!(transition.next, Some(LodLevel::Far));
+        assert!(transition.is_blending());
+        assert_eq!(config.select_lod_with_blend(100.0).unwrap().next, None);
+        assert_eq!(
+            config.select_lod_with_hysteresis(64.5, Some(LodLevel::Minimal), 1.0),
+            Some(LodLevel::Minimal)
+        );
+    }
+
+    #[test]
+    fn far_band_never_shrinks_below_minimal_detail() {
+        let config = LodConfig::default().with_far_band(32.0);
+        assert_eq!(config.max_render_distance, 64.0);
+        assert_eq!(config.select_lod(64.0), Some(LodLevel::Minimal));
+        assert_eq!(config.select_lod(65.0), None);
+    }
+
     #[test]
     fn blend_factor_zero_in_center() {
         let config = LodConfig::default();
@@ -624,7 +866,12 @@ mod tests {
     #[test]
     fn lod_air_chunk_produces_no_quads() {
         let chunk = uniform_chunk(0);
-        for lod in [LodLevel::Reduced, LodLevel::Low, LodLevel::Minimal] {
+        for lod in [
+            LodLevel::Reduced,
+            LodLevel::Low,
+            LodLevel::Minimal,
+            LodLevel::Far,
+        ] {
             let mesh = LodMesher::mesh_chunk_lod(&chunk, lod).unwrap();
             assert!(
                 mesh.is_empty(),
@@ -638,7 +885,12 @@ mod tests {
     #[test]
     fn lod_solid_chunk_produces_surface_quads() {
         let chunk = uniform_chunk(1);
-        for lod in [LodLevel::Reduced, LodLevel::Low, LodLevel::Minimal] {
+        for lod in [
+            LodLevel::Reduced,
+            LodLevel::Low,
+            LodLevel::Minimal,
+            LodLevel::Far,
+        ] {
             let mesh = LodMesher::mesh_chunk_lod(&chunk, lod).unwrap();
             assert!(
                 !mesh.is_empty(),
@@ -688,12 +940,24 @@ mod tests {
             minimal.quad_count(),
             low.quad_count()
         );
+        let far = LodMesher::mesh_chunk_lod(&chunk, LodLevel::Far).unwrap();
+        assert!(
+            far.quad_count() < minimal.quad_count(),
+            "Far ({}) should have fewer quads than Minimal ({})",
+            far.quad_count(),
+            minimal.quad_count()
+        );
     }
 
     #[test]
     fn lod_quads_within_chunk_bounds() {
         let chunk = terrain_chunk();
-        for lod in [LodLevel::Reduced, LodLevel::Low, LodLevel::Minimal] {
+        for lod in [
+            LodLevel::Reduced,
+            LodLevel::Low,
+            LodLevel::Minimal,
+            LodLevel::Far,
+        ] {
             let mesh = LodMesher::mesh_chunk_lod(&chunk, lod).unwrap();
             for q in &mesh.quads {
                 assert!(q.x < CHUNK_SIZE as u8, "{:?}: x={} out of bounds", lod, q.x);
@@ -709,7 +973,12 @@ mod tests {
     #[test]
     fn lod_quad_dimensions_are_multiples_of_scale() {
         let chunk = uniform_chunk(1);
-        for lod in [LodLevel::Reduced, LodLevel::Low, LodLevel::Minimal] {
+        for lod in [
+            LodLevel::Reduced,
+            LodLevel::Low,
+            LodLevel::Minimal,
+            LodLevel::Far,
+        ] {
             let scale = lod.scale() as u8;
             let mesh = LodMesher::mesh_chunk_lod(&chunk, lod).unwrap();
             for q in &mesh.quads {
@@ -761,7 +1030,12 @@ mod tests {
     fn lod_solid_chunk_quad_count_per_level() {
         let chunk = uniform_chunk(1);
 
-        for lod in [LodLevel::Reduced, LodLevel::Low, LodLevel::Minimal] {
+        for lod in [
+            LodLevel::Reduced,
+            LodLevel::Low,
+            LodLevel::Minimal,
+            LodLevel::Far,
+        ] {
             let mesh = LodMesher::mesh_chunk_lod(&chunk, lod).unwrap();
             let expected = 6 * lod.grid_size();
             assert_eq!(
@@ -778,7 +1052,12 @@ mod tests {
     #[test]
     fn lod_preserves_block_types_in_terrain() {
         let chunk = terrain_chunk();
-        for lod in [LodLevel::Reduced, LodLevel::Low, LodLevel::Minimal] {
+        for lod in [
+            LodLevel::Reduced,
+            LodLevel::Low,
+            LodLevel::Minimal,
+            LodLevel::Far,
+        ] {
             let mesh = LodMesher::mesh_chunk_lod(&chunk, lod).unwrap();
             let block_types: std::collections::HashSet<_> =
                 mesh.quads.iter().map(|q| q.block_type).collect();
@@ -806,11 +1085,13 @@ mod tests {
         stats.record(LodLevel::Reduced, 200);
         stats.record(LodLevel::Low, 50);
         stats.record(LodLevel::Minimal, 10);
+        stats.record(LodLevel::Far, 2);
 
-        assert_eq!(stats.total_chunks(), 5);
-        assert_eq!(stats.total_quads(), 2060);
+        assert_eq!(stats.total_chunks(), 6);
+        assert_eq!(stats.total_quads(), 2062);
         assert_eq!(stats.chunks_per_level[0], 2);
         assert_eq!(stats.chunks_per_level[1], 1);
+        assert_eq!(stats.chunks_per_level[4], 1);
     }
 
     #[test]
@@ -825,4 +1106,82 @@ mod tests {
         let ratio = stats.reduction_ratio(100);
         assert!((ratio - 0.625).abs() < 0.01);
     }
+
+    fn adaptive() -> AdaptiveLod {
+        AdaptiveLod::new(
+            LodConfig::default(),
+            AdaptiveLodSettings {
+                window_frames: 10,
+                ..AdaptiveLodSettings::for_fps(60.0)
+            },
+        )
+    }
+
+    #[test]
+    fn adaptive_lod_tightens_when_over_budget() {
+        let mut adaptive = adaptive();
+        let mut changes = 0;
+        for _ in 0..100 {
+            if adaptive.record_frame(1.0 / 30.0) {
+                changes += 1;
+            }
+        }
+
+        assert_eq!(changes, 10, "one adjustment per window");
+        assert!((adaptive.scale() - 0.5).abs() < 1e-4);
+        assert!((adaptive.config().full_max - 8.0).abs() < 1e-3);
+        assert!((adaptive.config().low_max - 24.0).abs() < 1e-3);
+        assert_eq!(adaptive.config().max_render_distance, 64.0);
+    }
+
+    #[test]
+    fn adaptive_lod_clamps_to_min_scale() {
+        let mut adaptive = adaptive();
+        for _ in 0..10_000 {
+            adaptive.record_frame(0.1);
+        }
+        assert_eq!(adaptive.scale(), 0.25);
+        assert!((adaptive.config().full_max - 4.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn adaptive_lod_relaxes_with_headroom() {
+        let mut adaptive = adaptive();
+        for _ in 0..200 {
+            adaptive.record_frame(0.1);
+        }
+        let tightened = adaptive.config().full_max;
+
+        for _ in 0..10_000 {
+            adaptive.record_frame(1.0 / 240.0);
+        }
+        assert!(adaptive.config().full_max > tightened);
+        assert_eq!(adaptive.scale(), 1.0);
+        assert!((adaptive.config().full_max - 16.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn adaptive_lod_holds_steady_near_target() {
+        let mut adaptive = adaptive();
+        for _ in 0..100 {
+            adaptive.record_frame(0.1);
+        }
+        let scale = adaptive.scale();
+
+        // Between the headroom band and the target: no change either way.
+        for _ in 0..1000 {
+            assert!(!adaptive.record_frame(0.9 / 60.0));
+        }
+        assert_eq!(adaptive.scale(), scale);
+    }
+
+    #[test]
+    fn adaptive_lod_ignores_single_spikes() {
+        let mut adaptive = adaptive();
+        for i in 0..100 {
+            let frame_time = if i % 10 == 0 { 0.05 } else { 0.012 };
+            adaptive.record_frame(frame_time);
+        }
+        assert_eq!(adaptive.scale(), 1.0);
+    }
 }
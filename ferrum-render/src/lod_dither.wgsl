@@ -0,0 +1,48 @@
+// Screen-door cross-fade between two LOD meshes of the same chunk.
+//
+// Both meshes are drawn opaque. Each pixel is kept by exactly one of them,
+// chosen by comparing `blend` against a 4x4 Bayer threshold, so there is no
+// double blending and no sorting.
+
+#import bevy_pbr::{
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::{alpha_discard, apply_pbr_lighting, main_pass_post_lighting_processing},
+    forward_io::{VertexOutput, FragmentOutput},
+}
+
+struct LodDither {
+    blend: f32,
+    // 0 = primary (fades out), 1 = next (fades in)
+    layer: u32,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(100)
+var<uniform> lod_dither: LodDither;
+
+var<private> BAYER_4X4: array<u32, 16> = array<u32, 16>(
+    0u, 8u, 2u, 10u,
+    12u, 4u, 14u, 6u,
+    3u, 11u, 1u, 9u,
+    15u, 7u, 13u, 5u,
+);
+
+@fragment
+fn fragment(
+    in: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    let pixel = vec2<u32>(in.position.xy) % 4u;
+    let threshold = (f32(BAYER_4X4[pixel.y * 4u + pixel.x]) + 0.5) / 16.0;
+    let visible = select(threshold >= lod_dither.blend, threshold < lod_dither.blend, lod_dither.layer == 1u);
+    if !visible {
+        discard;
+    }
+
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+
+    var out: FragmentOutput;
+    out.color = apply_pbr_lighting(pbr_input);
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+    return out;
+}
@@ -0,0 +1,147 @@
+//! Dithered cross-fade between LOD meshes.
+//!
+//! During a [`LodTransition`] both the primary and the next LOD mesh are
+//! rendered. Each gets a [`LodDitherMaterial`] that discards pixels against a
+//! 4x4 Bayer pattern; the two layers use complementary thresholds, so every
+//! pixel comes from exactly one mesh and nothing is blended twice.
+
+use crate::lod::LodTransition;
+use bevy::pbr::{ExtendedMaterial, MaterialExtension};
+use bevy::prelude::*;
+use bevy::render::render_resource::AsBindGroup;
+use bevy::shader::ShaderRef;
+
+const LOD_DITHER_SHADER_PATH: &str = "embedded://ferrum_render/lod_dither.wgsl";
+
+/// Must match `BAYER_4X4` in `lod_dither.wgsl`.
+const BAYER_4X4: [u8; 16] = [0, 8, 2, 10, 12, 4, 14, 6, 3, 11, 1, 9, 15, 7, 13, 5];
+
+/// Dither threshold in `(0, 1)` for a screen pixel.
+pub fn dither_threshold(x: u32, y: u32) -> f32 {
+    let index = (y % 4) * 4 + x % 4;
+    (BAYER_4X4[index as usize] as f32 + 0.5) / 16.0
+}
+
+/// Which side of a transition a mesh renders.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub enum LodFadeLayer {
+    /// The higher-detail mesh, fading out as `blend` rises.
+    Primary,
+    /// The lower-detail mesh, fading in as `blend` rises.
+    Next,
+}
+
+impl LodFadeLayer {
+    /// Whether this layer draws the pixel at `(x, y)` for the given blend.
+    pub fn is_pixel_visible(self, blend: f32, x: u32, y: u32) -> bool {
+        let threshold = dither_threshold(x, y);
+        match self {
+            LodFadeLayer::Primary => threshold >= blend,
+            LodFadeLayer::Next => threshold < blend,
+        }
+    }
+
+    /// Fraction of screen pixels this layer draws for the given blend.
+    pub fn coverage(self, blend: f32) -> f32 {
+        let visible = (0..4)
+            .flat_map(|y| (0..4).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.is_pixel_visible(blend, x, y))
+            .count();
+        visible as f32 / 16.0
+    }
+}
+
+/// Material extension that dithers a mesh in or out.
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone, Default)]
+pub struct LodDither {
+    #[uniform(100)]
+    pub blend: f32,
+    /// 0 for [`LodFadeLayer::Primary`], 1 for [`LodFadeLayer::Next`].
+    #[uniform(100)]
+    pub layer: u32,
+}
+
+impl LodDither {
+    pub fn new(layer: LodFadeLayer, blend: f32) -> Self {
+        Self {
+            blend,
+            layer: match layer {
+                LodFadeLayer::Primary => 0,
+                LodFadeLayer::Next => 1,
+            },
+        }
+    }
+}
+
+impl MaterialExtension for LodDither {
+    fn fragment_shader() -> ShaderRef {
+        LOD_DITHER_SHADER_PATH.into()
+    }
+}
+
+pub type LodDitherMaterial = ExtendedMaterial<StandardMaterial, LodDither>;
+
+/// Fade state of one LOD mesh entity. The entity also needs a
+/// `MeshMaterial3d<LodDitherMaterial>` of its own.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct LodFade {
+    pub layer: LodFadeLayer,
+    pub blend: f32,
+}
+
+impl LodFade {
+    /// Fade states for the primary mesh and, if the transition has one, the
+    /// next mesh.
+    pub fn for_transition(transition: &LodTransition) -> (LodFade, Option<LodFade>) {
+        let primary = LodFade {
+            layer: LodFadeLayer::Primary,
+            blend: transition.blend,
+        };
+        let next = transition.next.map(|_| LodFade {
+            layer: LodFadeLayer::Next,
+            blend: transition.blend,
+        });
+        (primary, next)
+    }
+
+    /// Whether the mesh draws any pixels at all.
+    pub fn is_visible(&self) -> bool {
+        self.layer.coverage(self.blend) > 0.0
+    }
+}
+
+/// Push changed fade states into the materials and hide meshes that are
+/// fully dithered away, so they cost no draw call.
+pub fn apply_lod_fade(
+    mut query: Query<
+        (
+            &LodFade,
+            &MeshMaterial3d<LodDitherMaterial>,
+            &mut Visibility,
+        ),
+        Changed<LodFade>,
+    >,
+    mut materials: ResMut<Assets<LodDitherMaterial>>,
+) {
+    for (fade, material, mut visibility) in &mut query {
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.extension = LodDither::new(fade.layer, fade.blend);
+        }
+        *visibility = if fade.is_visible() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+/// Registers the dither shader, material and [`apply_lod_fade`].
+pub struct LodCrossFadePlugin;
+
+impl Plugin for LodCrossFadePlugin {
+    fn build(&self, app: &mut App) {
+        bevy::asset::embedded_asset!(app, "lod_dither.wgsl");
+        app.add_plugins(MaterialPlugin::<LodDitherMaterial>::default())
+            .add_systems(PostUpdate, apply_lod_fade);
+    }
+}
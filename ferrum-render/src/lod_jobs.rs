@@ -0,0 +1,89 @@
+//! LOD meshes built off the main thread.
+//!
+//! [`LodMeshJobs`] runs [`LodMesher`] on the async compute pool, so meshing
+//! dozens of distant chunks after the camera moves no longer stalls a frame.
+//! A Bevy system submits chunks and collects finished meshes each frame.
+
+use crate::lod::{LodLevel, LodMesher};
+use bevy::prelude::*;
+use bevy::tasks::futures::check_ready;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use ferrum_meshing_cpu::{ChunkMesh, ChunkMesher, CpuMesher, CHUNK_SIZE_CB};
+use ferrum_world::ChunkPos;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+
+/// Chunk LOD meshes being built in the background, at most one per chunk.
+/// Chunks are keyed by `K`: a [`ChunkPos`] for the chunks of a [`World`],
+/// or the chunk group of each section of a server's chunk columns.
+///
+/// [`World`]: ferrum_world::World
+#[derive(Resource)]
+pub struct LodMeshJobs<K = ChunkPos> {
+    running: HashMap<K, (LodLevel, Task<ChunkMesh>)>,
+}
+
+impl<K> Default for LodMeshJobs<K> {
+    fn default() -> Self {
+        Self {
+            running: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash> LodMeshJobs<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start meshing `voxels` at `lod` on the async compute pool. `Full` uses
+    /// the standard mesher. Replaces and cancels any job still running for
+    /// `pos`, so only the latest LOD of a chunk is delivered.
+    pub fn submit(&mut self, pos: K, voxels: Arc<[u32; CHUNK_SIZE_CB]>, lod: LodLevel) {
+        let pool = AsyncComputeTaskPool::get_or_init(Default::default);
+        let task = pool.spawn(async move {
+            LodMesher::mesh_chunk_lod(&voxels, lod)
+                .unwrap_or_else(|| CpuMesher::new().mesh_chunk(&voxels))
+        });
+        self.running.insert(pos, (lod, task));
+    }
+
+    /// Drop the job for `pos`, e.g. when the chunk unloads.
+    pub fn cancel(&mut self, pos: K) {
+        self.running.remove(&pos);
+    }
+
+    /// Drop the jobs of every chunk `cancel` picks.
+    pub fn cancel_matching(&mut self, mut cancel: impl FnMut(&K) -> bool) {
+        self.running.retain(|pos, _| !cancel(pos));
+    }
+
+    /// LOD being meshed for `pos`, if any.
+    pub fn pending(&self, pos: K) -> Option<LodLevel> {
+        self.running.get(&pos).map(|&(lod, _)| lod)
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.running.len()
+    }
+
+    /// Drop every running job.
+    pub fn clear(&mut self) {
+        self.running.clear();
+    }
+
+    /// Take every finished mesh. Unfinished jobs keep running.
+    pub fn drain_completed(&mut self) -> Vec<(K, LodLevel, ChunkMesh)> {
+        let mut completed = Vec::new();
+        self.running
+            .retain(|&pos, (lod, task)| match check_ready(task) {
+                Some(mesh) => {
+                    completed.push((pos, *lod, mesh));
+                    false
+                }
+                None => true,
+            });
+        completed
+    }
+}
@@ -0,0 +1,94 @@
+//! Chunk meshes built off the main thread.
+//!
+//! Converting a [`ChunkMesh`] into vertex and index buffers runs on the async
+//! compute pool. The main thread only hands finished buffers to
+//! `Assets<Mesh>`, and at most [`MeshUploadBudget`] of them per frame, so a
+//! burst of streamed chunks is spread over several frames instead of causing
+//! a hitch.
+
+use crate::block_renderer::{BlockRenderer, MeshBuffers};
+use crate::chunk_groups::merge_chunk_meshes;
+use crate::texture_atlas::TextureAtlas;
+use bevy::prelude::*;
+use bevy::tasks::futures::check_ready;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use ferrum_meshing_cpu::ChunkMesh;
+use std::sync::Arc;
+
+/// Chunk meshes added to `Assets<Mesh>` per frame by default.
+pub const DEFAULT_UPLOADS_PER_FRAME: usize = 8;
+
+/// Most chunk meshes handed to `Assets<Mesh>` in one frame.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeshUploadBudget(pub usize);
+
+impl Default for MeshUploadBudget {
+    fn default() -> Self {
+        Self(DEFAULT_UPLOADS_PER_FRAME)
+    }
+}
+
+/// A chunk whose mesh is being built in the background. Replaced by a
+/// [`Mesh3d`] once the mesh is uploaded.
+#[derive(Component)]
+pub struct PendingChunkMesh(Task<MeshBuffers>);
+
+impl PendingChunkMesh {
+    /// Start building the buffers for `chunk_mesh` on the async compute pool.
+    pub fn spawn(chunk_mesh: ChunkMesh, atlas: Arc<TextureAtlas>, chunk_origin: IVec3) -> Self {
+        let pool = AsyncComputeTaskPool::get_or_init(Default::default);
+        let task = pool
+            .spawn(async move { BlockRenderer::build_buffers(&chunk_mesh, &atlas, chunk_origin) });
+        Self(task)
+    }
+
+    /// Start building one mesh from several chunks, each placed at its world
+    /// origin. See [`ChunkGroups`](crate::ChunkGroups).
+    pub fn spawn_merged(chunks: Vec<(ChunkMesh, IVec3)>, atlas: Arc<TextureAtlas>) -> Self {
+        let pool = AsyncComputeTaskPool::get_or_init(Default::default);
+        let task = pool.spawn(async move {
+            merge_chunk_meshes(chunks.iter().map(|(mesh, origin)| (mesh, *origin)), &atlas)
+        });
+        Self(task)
+    }
+
+    /// Whether the buffers are ready to upload.
+    pub fn is_finished(&self) -> bool {
+        self.0.is_finished()
+    }
+}
+
+/// Upload finished chunk meshes, up to the frame's budget. Chunks over budget
+/// keep their finished task and are uploaded in a later frame.
+pub fn upload_chunk_meshes(
+    mut commands: Commands,
+    budget: Res<MeshUploadBudget>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut pending: Query<(Entity, &mut PendingChunkMesh)>,
+) {
+    let mut uploaded = 0;
+    for (entity, mut task) in &mut pending {
+        if uploaded >= budget.0 {
+            break;
+        }
+        let Some(buffers) = check_ready(&mut task.0) else {
+            continue;
+        };
+
+        let mut chunk = commands.entity(entity);
+        chunk.remove::<PendingChunkMesh>();
+        if !buffers.is_empty() {
+            chunk.insert(Mesh3d(meshes.add(buffers.into_mesh())));
+            uploaded += 1;
+        }
+    }
+}
+
+pub struct MeshUploadPlugin;
+
+impl Plugin for MeshUploadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MeshUploadBudget>()
+            .add_systems(Update, upload_chunk_meshes);
+    }
+}
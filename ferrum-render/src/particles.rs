@@ -0,0 +1,313 @@
+//! CPU-simulated particles for block breaking and ambient effects.
+//!
+//! Particles are short-lived quads that face the camera. The whole system is
+//! written into a single mesh whose vertex buffers are reused between frames,
+//! so steady-state updates do not allocate.
+
+use crate::TextureAtlas;
+use bevy::asset::RenderAssetUsages;
+use bevy::math::Vec3;
+use bevy::mesh::{Indices, MeshVertexAttribute, PrimitiveTopology, VertexAttributeValues};
+use bevy::prelude::Mesh;
+use ferrum_meshing_cpu::Face;
+use std::collections::VecDeque;
+
+/// Default cap on live particles.
+pub const DEFAULT_MAX_PARTICLES: usize = 2048;
+
+/// Particles spawned per axis when a block breaks (4x4x4 = 64 total).
+const BREAK_PARTICLES_PER_AXIS: usize = 4;
+/// Fragments kicked up by a footstep.
+const STEP_PARTICLES: usize = 4;
+
+/// Ambient effects spawned by the world rather than by interaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmbientParticle {
+    /// Bright spark thrown upwards from lava surfaces.
+    LavaSpark,
+    /// Slowly rising, fading smoke.
+    Smoke,
+}
+
+#[derive(Clone, Debug)]
+pub struct Particle {
+    pub position: Vec3,
+    pub velocity: Vec3,
+    /// Seconds since spawn.
+    pub age: f32,
+    /// Seconds the particle lives for.
+    pub lifetime: f32,
+    /// Edge length of the quad in blocks.
+    pub size: f32,
+    /// Multiplier on the system gravity. Negative values make particles rise.
+    pub gravity_scale: f32,
+    pub uvs: [[f32; 2]; 4],
+    pub color: [f32; 4],
+}
+
+impl Particle {
+    pub fn is_alive(&self) -> bool {
+        self.age < self.lifetime
+    }
+}
+
+pub struct ParticleSystem {
+    particles: VecDeque<Particle>,
+    max_particles: usize,
+    /// Downward acceleration in blocks per second squared.
+    gravity: f32,
+    rng: u32,
+}
+
+impl ParticleSystem {
+    pub fn new(max_particles: usize) -> Self {
+        Self {
+            particles: VecDeque::with_capacity(max_particles),
+            max_particles,
+            gravity: 20.0,
+            rng: 0x9E37_79B9,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    pub fn max_particles(&self) -> usize {
+        self.max_particles
+    }
+
+    /// Live particles, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Particle> {
+        self.particles.iter()
+    }
+
+    /// Add a particle, dropping the oldest one if the cap is reached.
+    pub fn spawn(&mut self, particle: Particle) {
+        if self.max_particles == 0 {
+            return;
+        }
+        while self.particles.len() >= self.max_particles {
+            self.particles.pop_front();
+        }
+        self.particles.push_back(particle);
+    }
+
+    /// Scatter fragments of a broken block. `block_min` is the block's
+    /// minimum corner in world space.
+    pub fn spawn_block_break(&mut self, block_min: Vec3, block_type: u32, atlas: &TextureAtlas) {
+        let tile = atlas.get_uvs(block_type, Face::Front);
+        let center = block_min + Vec3::splat(0.5);
+        let n = BREAK_PARTICLES_PER_AXIS;
+
+        for x in 0..n {
+            for y in 0..n {
+                for z in 0..n {
+                    let offset = Vec3::new(
+                        (x as f32 + 0.5) / n as f32,
+                        (y as f32 + 0.5) / n as f32,
+                        (z as f32 + 0.5) / n as f32,
+                    );
+                    let position = block_min + offset;
+                    let outward = (position - center) * 4.0;
+                    let jitter = Vec3::new(
+                        self.next_signed(),
+                        self.next_f32() * 0.5,
+                        self.next_signed(),
+                    );
+                    let particle = Particle {
+                        position,
+                        velocity: outward + jitter + Vec3::Y * 2.0,
+                        age: 0.0,
+                        lifetime: 0.5 + self.next_f32() * 0.5,
+                        size: 0.1 + self.next_f32() * 0.05,
+                        gravity_scale: 1.0,
+                        uvs: self.fragment_uvs(tile),
+                        color: [1.0, 1.0, 1.0, 1.0],
+                    };
+                    self.spawn(particle);
+                }
+            }
+        }
+    }
+
+    /// Kick up a few small fragments of the block walked on around `feet`.
+    pub fn spawn_step(&mut self, feet: Vec3, block_type: u32, atlas: &TextureAtlas) {
+        let tile = atlas.get_uvs(block_type, Face::Up);
+        for _ in 0..STEP_PARTICLES {
+            let offset = Vec3::new(self.next_signed() * 0.3, 0.05, self.next_signed() * 0.3);
+            let particle = Particle {
+                position: feet + offset,
+                velocity: Vec3::new(offset.x, 1.0 + self.next_f32(), offset.z),
+                age: 0.0,
+                lifetime: 0.3 + self.next_f32() * 0.2,
+                size: 0.06,
+                gravity_scale: 1.0,
+                uvs: self.fragment_uvs(tile),
+                color: [1.0, 1.0, 1.0, 1.0],
+            };
+            self.spawn(particle);
+        }
+    }
+
+    /// Spawn one ambient particle at `position`.
+    pub fn spawn_ambient(&mut self, kind: AmbientParticle, position: Vec3) {
+        let particle = match kind {
+            AmbientParticle::LavaSpark => Particle {
+                position,
+                velocity: Vec3::new(
+                    self.next_signed() * 0.5,
+                    3.0 + self.next_f32() * 2.0,
+                    self.next_signed() * 0.5,
+                ),
+                age: 0.0,
+                lifetime: 1.0 + self.next_f32(),
+                size: 0.08,
+                gravity_scale: 0.5,
+                uvs: [[0.0; 2]; 4],
+                color: [1.0, 0.55, 0.1, 1.0],
+            },
+            AmbientParticle::Smoke => Particle {
+                position,
+                velocity: Vec3::new(self.next_signed() * 0.1, 0.0, self.next_signed() * 0.1),
+                age: 0.0,
+                lifetime: 2.0 + self.next_f32() * 2.0,
+                size: 0.25,
+                gravity_scale: -0.05,
+                uvs: [[0.0; 2]; 4],
+                color: [0.3, 0.3, 0.3, 0.6],
+            },
+        };
+        self.spawn(particle);
+    }
+
+    /// Advance the simulation by `dt` seconds and remove expired particles.
+    pub fn update(&mut self, dt: f32) {
+        let gravity = self.gravity;
+        for particle in self.particles.iter_mut() {
+            particle.velocity.y -= gravity * particle.gravity_scale * dt;
+            particle.position += particle.velocity * dt;
+            particle.age += dt;
+        }
+        self.particles.retain(Particle::is_alive);
+    }
+
+    /// An empty mesh suitable for [`ParticleSystem::write_mesh`].
+    pub fn create_mesh() -> Mesh {
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+        );
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, Vec::<[f32; 3]>::new());
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, Vec::<[f32; 3]>::new());
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, Vec::<[f32; 2]>::new());
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, Vec::<[f32; 4]>::new());
+        mesh.insert_indices(Indices::U32(Vec::new()));
+        mesh
+    }
+
+    /// Rebuild `mesh` with one camera-facing quad per live particle, reusing
+    /// its existing vertex buffers. Particles fade out over their last
+    /// quarter of life.
+    pub fn write_mesh(&self, mesh: &mut Mesh, camera_right: Vec3, camera_up: Vec3) {
+        let mut positions = take_float32x3(mesh, Mesh::ATTRIBUTE_POSITION);
+        let mut normals = take_float32x3(mesh, Mesh::ATTRIBUTE_NORMAL);
+        let mut uvs = match mesh.remove_attribute(Mesh::ATTRIBUTE_UV_0) {
+            Some(VertexAttributeValues::Float32x2(values)) => values,
+            _ => Vec::new(),
+        };
+        let mut colors = match mesh.remove_attribute(Mesh::ATTRIBUTE_COLOR) {
+            Some(VertexAttributeValues::Float32x4(values)) => values,
+            _ => Vec::new(),
+        };
+        let mut indices = match mesh.remove_indices() {
+            Some(Indices::U32(values)) => values,
+            _ => Vec::new(),
+        };
+        positions.clear();
+        normals.clear();
+        uvs.clear();
+        colors.clear();
+        indices.clear();
+
+        let normal = camera_right.cross(camera_up).normalize_or_zero().to_array();
+        for particle in &self.particles {
+            let half = particle.size * 0.5;
+            let right = camera_right * half;
+            let up = camera_up * half;
+            let p = particle.position;
+
+            let base = positions.len() as u32;
+            positions.extend_from_slice(&[
+                (p - right - up).to_array(),
+                (p + right - up).to_array(),
+                (p + right + up).to_array(),
+                (p - right + up).to_array(),
+            ]);
+            normals.extend_from_slice(&[normal; 4]);
+            uvs.extend_from_slice(&particle.uvs);
+
+            let remaining = 1.0 - particle.age / particle.lifetime;
+            let fade = (remaining * 4.0).clamp(0.0, 1.0);
+            let mut color = particle.color;
+            color[3] *= fade;
+            colors.extend_from_slice(&[color; 4]);
+
+            indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        mesh.insert_indices(Indices::U32(indices));
+    }
+
+    /// A random quarter-size patch of a block's tile, so fragments of the same
+    /// block show different parts of its texture.
+    fn fragment_uvs(&mut self, tile: [[f32; 2]; 4]) -> [[f32; 2]; 4] {
+        let [u_min, v_max] = tile[0];
+        let [u_max, v_min] = tile[2];
+        let width = (u_max - u_min) * 0.25;
+        let height = (v_max - v_min) * 0.25;
+        let u = u_min + self.next_f32() * (u_max - u_min - width);
+        let v = v_min + self.next_f32() * (v_max - v_min - height);
+        [
+            [u, v + height],
+            [u + width, v + height],
+            [u + width, v],
+            [u, v],
+        ]
+    }
+
+    /// Uniform in `[0, 1)`, from a xorshift generator. Particles only need
+    /// visual variety, not statistical quality.
+    fn next_f32(&mut self) -> f32 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        (self.rng >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Uniform in `[-1, 1)`.
+    fn next_signed(&mut self) -> f32 {
+        self.next_f32() * 2.0 - 1.0
+    }
+}
+
+impl Default for ParticleSystem {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_PARTICLES)
+    }
+}
+
+fn take_float32x3(mesh: &mut Mesh, attribute: MeshVertexAttribute) -> Vec<[f32; 3]> {
+    match mesh.remove_attribute(attribute) {
+        Some(VertexAttributeValues::Float32x3(values)) => values,
+        _ => Vec::new(),
+    }
+}
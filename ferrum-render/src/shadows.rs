@@ -0,0 +1,114 @@
+//! Directional light shadows driven by `client.shadows` and the shadow
+//! overrides in the config.
+
+use bevy::light::{CascadeShadowConfig, CascadeShadowConfigBuilder, DirectionalLightShadowMap};
+use bevy::prelude::*;
+use ferrum_config::{ClientConfig, Config, ShadowQuality};
+
+/// Smallest shadow map size accepted from the config, in texels.
+pub const MIN_SHADOW_MAP_SIZE: usize = 256;
+/// Largest shadow map size accepted from the config, in texels.
+pub const MAX_SHADOW_MAP_SIZE: usize = 8192;
+/// Most shadow cascades Bevy supports per light.
+pub const MAX_SHADOW_CASCADES: usize = 4;
+/// Shortest shadow distance, in blocks.
+pub const MIN_SHADOW_DISTANCE: f32 = 16.0;
+
+/// Shadow map size, cascades and reach for the sun.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadowSettings {
+    pub enabled: bool,
+    /// Width and height of each cascade's shadow map, in texels.
+    pub map_size: usize,
+    pub cascades: usize,
+    /// Farthest distance from the camera that gets shadows, in blocks.
+    pub max_distance: f32,
+}
+
+impl ShadowSettings {
+    pub fn preset(quality: ShadowQuality) -> Self {
+        let (map_size, cascades, max_distance) = match quality {
+            ShadowQuality::Low => (1024, 1, 48.0),
+            ShadowQuality::Off | ShadowQuality::Medium => (2048, 2, 96.0),
+            ShadowQuality::High => (4096, 4, 192.0),
+        };
+        Self {
+            enabled: quality != ShadowQuality::Off,
+            map_size,
+            cascades,
+            max_distance,
+        }
+    }
+
+    /// The configured quality's preset with the config's overrides applied,
+    /// clamped to what Bevy supports. Shadows never reach past the render
+    /// distance, where there are no chunks to cast them.
+    pub fn from_config(client: &ClientConfig) -> Self {
+        let mut settings = Self::preset(client.shadow_quality());
+        if let Some(resolution) = client.shadow_resolution {
+            settings.map_size = resolution
+                .checked_next_power_of_two()
+                .map_or(MAX_SHADOW_MAP_SIZE, |size| size as usize);
+        }
+        if let Some(cascades) = client.shadow_cascades {
+            settings.cascades = cascades as usize;
+        }
+        if let Some(distance) = client.shadow_distance {
+            settings.max_distance = distance;
+        }
+
+        let render_distance = (client.render_distance as f32 * 16.0).max(MIN_SHADOW_DISTANCE);
+        settings.map_size = settings
+            .map_size
+            .clamp(MIN_SHADOW_MAP_SIZE, MAX_SHADOW_MAP_SIZE);
+        settings.cascades = settings.cascades.clamp(1, MAX_SHADOW_CASCADES);
+        settings.max_distance = settings
+            .max_distance
+            .clamp(MIN_SHADOW_DISTANCE, render_distance);
+        settings
+    }
+
+    /// Cascade split for the light, with the first cascade covering the
+    /// nearest eighth of the shadow distance.
+    pub fn cascade_config(&self) -> CascadeShadowConfig {
+        CascadeShadowConfigBuilder {
+            num_cascades: self.cascades,
+            maximum_distance: self.max_distance,
+            first_cascade_far_bound: self.max_distance / 8.0,
+            ..default()
+        }
+        .build()
+    }
+}
+
+/// Apply the configured shadows to new directional lights, and to every
+/// directional light when the config is reloaded.
+pub fn apply_shadows(
+    mut commands: Commands,
+    config: Res<Config>,
+    mut lights: Query<(&mut DirectionalLight, &mut CascadeShadowConfig)>,
+) {
+    let reapply = config.is_changed();
+    let settings = ShadowSettings::from_config(&config.client);
+    if reapply {
+        commands.insert_resource(DirectionalLightShadowMap {
+            size: settings.map_size,
+        });
+    }
+
+    for (mut light, mut cascades) in &mut lights {
+        if !reapply && !light.is_added() {
+            continue;
+        }
+        light.shadows_enabled = settings.enabled;
+        *cascades = settings.cascade_config();
+    }
+}
+
+pub struct ShadowsPlugin;
+
+impl Plugin for ShadowsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, apply_shadows);
+    }
+}
@@ -0,0 +1,153 @@
+//! Animated block textures such as water, lava and fire.
+//!
+//! Every frame of an animation is its own tile in the atlas. Animated blocks
+//! are meshed with the UVs of the first frame and drawn with a material of
+//! their own; a system moves that material's UV transform onto the current
+//! frame's tile, so chunk meshes never have to be rebuilt.
+
+use bevy::math::Affine2;
+use bevy::prelude::*;
+
+/// A texture cycling through atlas tiles at a fixed rate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureAnimation {
+    /// Atlas tiles in playback order. A tile may appear more than once to
+    /// hold or repeat frames.
+    frames: Vec<(u32, u32)>,
+    /// Seconds each frame is shown.
+    frame_time: f32,
+}
+
+impl TextureAnimation {
+    /// # Panics
+    ///
+    /// Panics if `frames` is empty or `frame_time` is not positive.
+    pub fn new(frames: Vec<(u32, u32)>, frame_time: f32) -> Self {
+        assert!(!frames.is_empty(), "texture animation needs a frame");
+        assert!(frame_time > 0.0, "frame time must be positive");
+        Self { frames, frame_time }
+    }
+
+    /// Frames laid out left to right in one atlas row, starting at `first`.
+    pub fn strip(first: (u32, u32), frame_count: u32, frame_time: f32) -> Self {
+        let frames = (0..frame_count).map(|i| (first.0 + i, first.1)).collect();
+        Self::new(frames, frame_time)
+    }
+
+    pub fn frames(&self) -> &[(u32, u32)] {
+        &self.frames
+    }
+
+    pub fn frame_time(&self) -> f32 {
+        self.frame_time
+    }
+
+    /// Length of one pass through all frames, in seconds.
+    pub fn duration(&self) -> f32 {
+        self.frames.len() as f32 * self.frame_time
+    }
+
+    /// Index of the frame shown `elapsed` seconds after the animation
+    /// started, wrapping back to the first frame after the last.
+    pub fn frame_at(&self, elapsed: f32) -> usize {
+        let frame = (elapsed.max(0.0) / self.frame_time) as usize;
+        frame % self.frames.len()
+    }
+
+    /// Atlas tile shown `elapsed` seconds after the animation started.
+    pub fn tile_at(&self, elapsed: f32) -> (u32, u32) {
+        self.frames[self.frame_at(elapsed)]
+    }
+
+    /// UV transform moving the first frame's tile onto frame `frame` in an
+    /// atlas `tiles_per_row` tiles wide and high.
+    pub fn uv_transform(&self, frame: usize, tiles_per_row: u32) -> Affine2 {
+        let (first_x, first_y) = self.frames[0];
+        let (x, y) = self.frames[frame % self.frames.len()];
+        let tile = 1.0 / tiles_per_row as f32;
+        Affine2::from_translation(Vec2::new(
+            (x as f32 - first_x as f32) * tile,
+            (y as f32 - first_y as f32) * tile,
+        ))
+    }
+}
+
+/// A material whose texture is animated.
+#[derive(Debug, Clone)]
+pub struct AnimatedMaterial {
+    pub material: Handle<StandardMaterial>,
+    pub animation: TextureAnimation,
+    /// Frame currently applied to the material.
+    frame: usize,
+}
+
+/// Materials driven by [`animate_textures`].
+#[derive(Resource, Debug, Clone)]
+pub struct TextureAnimations {
+    /// Tiles per row and column of the atlas the animations index into.
+    pub tiles_per_row: u32,
+    entries: Vec<AnimatedMaterial>,
+}
+
+impl TextureAnimations {
+    pub fn new(tiles_per_row: u32) -> Self {
+        Self {
+            tiles_per_row,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Animate `material`. Its texture must be laid out for the first frame.
+    pub fn add(&mut self, material: Handle<StandardMaterial>, animation: TextureAnimation) {
+        self.entries.push(AnimatedMaterial {
+            material,
+            animation,
+            frame: 0,
+        });
+    }
+
+    pub fn entries(&self) -> &[AnimatedMaterial] {
+        &self.entries
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl Default for TextureAnimations {
+    fn default() -> Self {
+        Self::new(16)
+    }
+}
+
+/// Show the current frame of every animated material. Materials are only
+/// touched when their frame changes.
+pub fn animate_textures(
+    time: Res<Time>,
+    mut animations: ResMut<TextureAnimations>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let elapsed = time.elapsed_secs();
+    let tiles_per_row = animations.tiles_per_row;
+
+    for entry in &mut animations.entries {
+        let frame = entry.animation.frame_at(elapsed);
+        if frame == entry.frame {
+            continue;
+        }
+        if let Some(material) = materials.get_mut(&entry.material) {
+            material.uv_transform = entry.animation.uv_transform(frame, tiles_per_row);
+            entry.frame = frame;
+        }
+    }
+}
+
+pub struct TextureAnimationPlugin;
+
+impl Plugin for TextureAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TextureAnimations>()
+            .add_systems(Update, animate_textures);
+    }
+}
@@ -1,9 +1,37 @@
+use crate::texture_animation::TextureAnimation;
+use crate::{RenderError, RenderResult};
+use bevy::image::Image;
+use bevy::math::IVec3;
+use ferrum_core::BlockState;
 use ferrum_meshing_cpu::Face;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+/// Texels the UVs of each tile are pulled in by, so bilinear filtering never
+/// samples the neighbouring tile.
+pub const DEFAULT_UV_INSET: f32 = 0.5;
 
 pub struct TextureAtlas {
     tile_size: u32,
+    /// Inset of tile UVs from the tile edges, in texels.
+    uv_inset: f32,
     block_textures: HashMap<(u32, Face), (u32, u32)>,
+    /// Alternative tiles picked per block position, in addition to the base
+    /// tile.
+    variants: HashMap<(u32, Face), Vec<(u32, u32)>>,
+    /// Faces whose texture is rotated by a random multiple of 90° per block.
+    rotated: HashSet<(u32, Face)>,
+    /// Block types whose texture cycles through frames. Their faces use the
+    /// first frame's tile.
+    animations: HashMap<u32, TextureAnimation>,
+}
+
+/// Texture choice for one block face at a particular world position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextureVariant {
+    /// 0 is the base tile, higher values index the registered variants.
+    pub variant: usize,
+    /// Quarter turns, 0..4.
+    pub rotation: u8,
 }
 
 impl TextureAtlas {
@@ -216,29 +244,209 @@ impl TextureAtlas {
 
         Self {
             tile_size,
+            uv_inset: DEFAULT_UV_INSET,
             block_textures,
+            variants: HashMap::new(),
+            rotated: HashSet::new(),
+            animations: HashMap::new(),
         }
     }
 
+    /// Register alternative tiles for a block face. Each block position picks
+    /// the base tile or one of these deterministically.
+    pub fn add_variants(&mut self, block_type: u32, face: Face, tiles: &[(u32, u32)]) {
+        self.variants
+            .entry((block_type, face))
+            .or_default()
+            .extend_from_slice(tiles);
+    }
+
+    /// Rotate a block face's texture by a per-position multiple of 90°.
+    pub fn set_random_rotation(&mut self, block_type: u32, face: Face, enabled: bool) {
+        if enabled {
+            self.rotated.insert((block_type, face));
+        } else {
+            self.rotated.remove(&(block_type, face));
+        }
+    }
+
+    /// Animate every face of a block type. Animated blocks are meshed with the
+    /// first frame and drawn with a material of their own.
+    pub fn set_animation(&mut self, block_type: u32, animation: TextureAnimation) {
+        self.animations.insert(block_type, animation);
+    }
+
+    /// Animate a block type through atlas tiles given by index, row by row
+    /// across the 16 tile wide atlas, at `fps` frames per second.
+    ///
+    /// Fails without changing anything if `frames` is empty or `fps` is not a
+    /// positive number.
+    pub fn register_animated(
+        &mut self,
+        block_type: u32,
+        frames: Vec<u32>,
+        fps: f32,
+    ) -> RenderResult<()> {
+        if frames.is_empty() {
+            return Err(RenderError::InvalidAnimation(format!(
+                "block type {block_type} has no frames"
+            )));
+        }
+        if !(fps > 0.0 && fps.is_finite()) {
+            return Err(RenderError::InvalidAnimation(format!(
+                "block type {block_type} has frame rate {fps}"
+            )));
+        }
+        let tiles = frames
+            .into_iter()
+            .map(|index| (index % 16, index / 16))
+            .collect();
+        self.set_animation(block_type, TextureAnimation::new(tiles, 1.0 / fps));
+        Ok(())
+    }
+
+    /// UVs of the tile a block type shows `elapsed_secs` after its animation
+    /// started. Blocks that aren't animated get their top face's tile.
+    pub fn uvs_at_time(&self, block_type: u32, elapsed_secs: f32) -> [[f32; 2]; 4] {
+        match self.animations.get(&block_id(block_type)) {
+            Some(animation) => self.tile_uvs(animation.tile_at(elapsed_secs)),
+            None => self.get_uvs(block_type, Face::Up),
+        }
+    }
+
+    pub fn animation(&self, block_type: u32) -> Option<&TextureAnimation> {
+        self.animations.get(&block_id(block_type))
+    }
+
+    pub fn is_animated(&self, block_type: u32) -> bool {
+        self.animations.contains_key(&block_id(block_type))
+    }
+
+    /// Whether the face looks different from block to block, so merged quads
+    /// must be split per block.
+    pub fn has_variation(&self, block_type: u32, face: Face) -> bool {
+        let key = (block_id(block_type), face);
+        self.variants.contains_key(&key) || self.rotated.contains(&key)
+    }
+
+    /// Variant and rotation for a block face at a world position. Stable for a
+    /// given position.
+    pub fn texture_variant(&self, block_type: u32, face: Face, pos: IVec3) -> TextureVariant {
+        let key = (block_id(block_type), face);
+        let hash = position_hash(pos);
+        let variant = match self.variants.get(&key) {
+            Some(tiles) => (hash % (tiles.len() as u32 + 1)) as usize,
+            None => 0,
+        };
+        let rotation = if self.rotated.contains(&key) {
+            (hash >> 16) as u8 & 3
+        } else {
+            0
+        };
+        TextureVariant { variant, rotation }
+    }
+
+    /// UVs for a block face at a world position, with its variant tile and
+    /// rotation applied.
+    pub fn get_uvs_at(&self, block_type: u32, face: Face, pos: IVec3) -> [[f32; 2]; 4] {
+        let TextureVariant { variant, rotation } = self.texture_variant(block_type, face, pos);
+        let mut uvs = match variant {
+            0 => self.get_uvs(block_type, face),
+            n => self.tile_uvs(self.variants[&(block_id(block_type), face)][n - 1]),
+        };
+        uvs.rotate_left(rotation as usize);
+        uvs
+    }
+
     pub fn tile_size(&self) -> u32 {
         self.tile_size
     }
 
+    pub fn uv_inset(&self) -> f32 {
+        self.uv_inset
+    }
+
+    /// Pull tile UVs in from the tile edges by `texels`.
+    pub fn set_uv_inset(&mut self, texels: f32) {
+        self.uv_inset = texels;
+    }
+
+    /// Mip chain of an RGBA8 atlas laid out in tiles of this atlas's tile
+    /// size, starting with the image itself. Each level halves the previous
+    /// one with a 2×2 box filter applied within each tile, so colours never
+    /// bleed across tile borders.
+    ///
+    /// Always holds the image itself and stops early once tiles are a single
+    /// texel, so its length is the `mip_level_count` to upload with rather
+    /// than `levels`. Empty if the image has no data.
+    pub fn generate_mipmaps(&self, image: &Image, levels: u32) -> Vec<Vec<u8>> {
+        let Some(data) = &image.data else {
+            return Vec::new();
+        };
+        let max_levels = self.tile_size.max(1).ilog2() + 1;
+        let (columns, rows) = (
+            image.width() / self.tile_size.max(1),
+            image.height() / self.tile_size.max(1),
+        );
+
+        let mut mips = vec![data.clone()];
+        let mut tile = self.tile_size;
+        for _ in 1..levels.min(max_levels) {
+            let previous = mips.last().unwrap();
+            let (width, half) = (columns * tile, tile / 2);
+            let half_width = columns * half;
+            let mut mip = vec![0u8; (half_width * rows * half * 4) as usize];
+            for tile_y in 0..rows {
+                for tile_x in 0..columns {
+                    for y in 0..half {
+                        for x in 0..half {
+                            let src_x = tile_x * tile + x * 2;
+                            let src_y = tile_y * tile + y * 2;
+                            let dst = ((tile_y * half + y) * half_width + tile_x * half + x) * 4;
+                            for channel in 0..4 {
+                                let texel = |dx: u32, dy: u32| {
+                                    let i = ((src_y + dy) * width + src_x + dx) * 4 + channel;
+                                    previous[i as usize] as u32
+                                };
+                                let sum = texel(0, 0) + texel(1, 0) + texel(0, 1) + texel(1, 1);
+                                mip[(dst + channel) as usize] = ((sum + 2) / 4) as u8;
+                            }
+                        }
+                    }
+                }
+            }
+            mips.push(mip);
+            tile = half;
+        }
+        mips
+    }
+
     pub fn get_uvs(&self, block_type: u32, face: Face) -> [[f32; 2]; 4] {
-        let (tile_x, tile_y) = self
-            .block_textures
+        self.tile_uvs(self.tile(block_type, face))
+    }
+
+    /// Atlas tile, in tiles from the top left, drawn on a face of a block.
+    /// Animated blocks use their first frame.
+    pub fn tile(&self, block_type: u32, face: Face) -> (u32, u32) {
+        let block_type = block_id(block_type);
+        if let Some(animation) = self.animations.get(&block_type) {
+            return animation.frames()[0];
+        }
+        self.block_textures
             .get(&(block_type, face))
             .copied()
-            .unwrap_or((0, 0));
+            .unwrap_or((0, 0))
+    }
 
+    fn tile_uvs(&self, (tile_x, tile_y): (u32, u32)) -> [[f32; 2]; 4] {
         let atlas_width = 16.0_f32;
         let atlas_height = 16.0_f32;
-        let half_texel = 0.5 / (atlas_width * self.tile_size as f32);
+        let inset = self.uv_inset / (atlas_width * self.tile_size as f32);
 
-        let u_min = tile_x as f32 / atlas_width + half_texel;
-        let v_min = tile_y as f32 / atlas_height + half_texel;
-        let u_max = (tile_x + 1) as f32 / atlas_width - half_texel;
-        let v_max = (tile_y + 1) as f32 / atlas_height - half_texel;
+        let u_min = tile_x as f32 / atlas_width + inset;
+        let v_min = tile_y as f32 / atlas_height + inset;
+        let u_max = (tile_x + 1) as f32 / atlas_width - inset;
+        let v_max = (tile_y + 1) as f32 / atlas_height - inset;
 
         [
             [u_min, v_max],
@@ -248,3 +456,23 @@ impl TextureAtlas {
         ]
     }
 }
+
+/// The block id of a voxel, without the property bits that vary between
+/// states of the same block, such as a fluid's level. Textures are looked up
+/// by block id alone.
+fn block_id(block_type: u32) -> u32 {
+    BlockState::from_bits(block_type).id().as_u16() as u32
+}
+
+/// Scrambled hash of a block position, so neighbouring blocks get unrelated
+/// variants.
+fn position_hash(pos: IVec3) -> u32 {
+    let mut h = (pos.x as u32).wrapping_mul(3_129_871)
+        ^ (pos.z as u32).wrapping_mul(116_129_781)
+        ^ (pos.y as u32).wrapping_mul(0x9E37_79B9);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7FEB_352D);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846C_A68B);
+    h ^ (h >> 16)
+}
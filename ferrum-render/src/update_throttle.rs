@@ -0,0 +1,69 @@
+//! Distance-based throttling for per-entity updates.
+//!
+//! Entities near the camera update every frame, mid-range ones every few
+//! frames and far ones rarely. Each entity is given a fixed slot within its
+//! interval, so a crowd of far entities is spread over the interval rather
+//! than all updating on the same frame.
+
+use bevy::prelude::*;
+
+/// How often entities update, by distance from the camera.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct UpdateThrottle {
+    /// Entities closer than this update every frame.
+    pub near_distance: f32,
+    /// Entities closer than this, but not near, update every `mid_interval`
+    /// frames.
+    pub mid_distance: f32,
+    pub mid_interval: u32,
+    /// Everything further away updates every `far_interval` frames.
+    pub far_interval: u32,
+}
+
+impl Default for UpdateThrottle {
+    fn default() -> Self {
+        Self {
+            near_distance: 24.0,
+            mid_distance: 64.0,
+            mid_interval: 4,
+            far_interval: 16,
+        }
+    }
+}
+
+impl UpdateThrottle {
+    /// Frames between updates for an entity `distance` blocks away. Never 0.
+    pub fn interval(&self, distance: f32) -> u32 {
+        let interval = if distance < self.near_distance {
+            1
+        } else if distance < self.mid_distance {
+            self.mid_interval
+        } else {
+            self.far_interval
+        };
+        interval.max(1)
+    }
+
+    /// Whether the entity with `id`, `distance` blocks away, updates on
+    /// `frame`. Consecutive ids fall on consecutive frames of the interval.
+    pub fn should_update(&self, id: u64, distance: f32, frame: u32) -> bool {
+        let interval = self.interval(distance) as u64;
+        (frame as u64 + id) % interval == 0
+    }
+}
+
+/// Time an entity has gone without an update, so the one it does get can
+/// catch up by the whole gap.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub struct ThrottledUpdate {
+    pub pending_secs: f32,
+}
+
+impl ThrottledUpdate {
+    /// Add this frame's `delta` and, if the entity updates this frame, return
+    /// the time since its last update.
+    pub fn tick(&mut self, delta: f32, update: bool) -> Option<f32> {
+        self.pending_secs += delta;
+        update.then(|| std::mem::take(&mut self.pending_secs))
+    }
+}
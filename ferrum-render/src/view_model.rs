@@ -0,0 +1,357 @@
+//! First-person view model: the player's arm and held item.
+//!
+//! The view model is drawn by a second camera that follows the player camera
+//! and only sees [`VIEW_MODEL_LAYER`]. It has its own depth buffer, so the
+//! arm never clips into walls the player stands against.
+
+use bevy::camera::visibility::RenderLayers;
+use bevy::camera::ClearColorConfig;
+use bevy::prelude::*;
+use ferrum_config::Config;
+use std::f32::consts::{PI, TAU};
+
+/// Render layer holding the arm and held item.
+pub const VIEW_MODEL_LAYER: usize = 1;
+
+/// Render order of the view-model camera: after the world, before the HUD.
+pub const VIEW_MODEL_CAMERA_ORDER: isize = 1;
+
+/// Slots in the hotbar.
+pub const HOTBAR_SLOTS: usize = 9;
+
+/// Length of an attack/use swing in seconds (6 game ticks).
+const SWING_DURATION: f32 = 0.3;
+
+/// Horizontal speed, in blocks per second, at which bobbing is at full
+/// strength.
+const BOB_FULL_SPEED: f32 = 4.317;
+
+/// Bob cycles per block walked.
+const BOB_FREQUENCY: f32 = 0.6;
+
+/// Where the arm rests relative to the view-model camera.
+const REST_POSITION: Vec3 = Vec3::new(0.45, -0.4, -0.7);
+
+/// Marks the player camera that the view model follows.
+#[derive(Component, Default)]
+pub struct FirstPersonView;
+
+/// The camera rendering [`VIEW_MODEL_LAYER`].
+#[derive(Component)]
+pub struct ViewModelCamera;
+
+/// Start a swing of the arm, e.g. on attack or use.
+#[derive(Message, Debug, Clone, Copy, Default)]
+pub struct SwingHand;
+
+/// Item ids in the hotbar and the selected slot, kept up to date by the
+/// client from its inventory.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct HotbarSelection {
+    pub items: [Option<u32>; HOTBAR_SLOTS],
+    pub selected: usize,
+}
+
+impl HotbarSelection {
+    pub fn held_item(&self) -> Option<u32> {
+        self.items.get(self.selected).copied().flatten()
+    }
+}
+
+/// Progress of an arm swing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwingAnimation {
+    elapsed: Option<f32>,
+    duration: f32,
+}
+
+impl SwingAnimation {
+    pub fn new(duration: f32) -> Self {
+        Self {
+            elapsed: None,
+            duration,
+        }
+    }
+
+    /// Start a swing. A swing already in its first half keeps going, so
+    /// holding attack swings at a steady rate.
+    pub fn start(&mut self) {
+        if !self.is_swinging() || self.progress() >= 0.5 {
+            self.elapsed = Some(0.0);
+        }
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        if let Some(elapsed) = self.elapsed.as_mut() {
+            *elapsed += dt;
+            if *elapsed >= self.duration {
+                self.elapsed = None;
+            }
+        }
+    }
+
+    pub fn is_swinging(&self) -> bool {
+        self.elapsed.is_some()
+    }
+
+    /// Fraction of the swing completed, 0 when idle.
+    pub fn progress(&self) -> f32 {
+        self.elapsed
+            .map_or(0.0, |elapsed| (elapsed / self.duration).min(1.0))
+    }
+
+    /// Offset from the rest position and rotation of the arm at the current
+    /// progress. Both return to identity at the end of the swing.
+    pub fn pose(&self) -> (Vec3, Quat) {
+        let p = self.progress();
+        let sqrt_p = p.sqrt();
+        let offset = Vec3::new(
+            -0.4 * (sqrt_p * PI).sin(),
+            0.2 * (sqrt_p * TAU).sin(),
+            -0.2 * (p * PI).sin(),
+        );
+        let rotation = Quat::from_rotation_y((sqrt_p * PI).sin() * 0.7)
+            * Quat::from_rotation_x(-(p * p * PI).sin() * 1.2);
+        (offset, rotation)
+    }
+}
+
+impl Default for SwingAnimation {
+    fn default() -> Self {
+        Self::new(SWING_DURATION)
+    }
+}
+
+/// Sway of the arm while walking.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ViewBob {
+    phase: f32,
+    amount: f32,
+}
+
+impl ViewBob {
+    /// Advance by `dt` seconds of moving at `speed` blocks per second. The
+    /// sway eases in and out rather than snapping when walking starts or
+    /// stops.
+    pub fn update(&mut self, speed: f32, dt: f32) {
+        let target = (speed / BOB_FULL_SPEED).min(1.0);
+        self.amount += (target - self.amount) * (dt * 10.0).min(1.0);
+        self.phase = (self.phase + speed * dt * BOB_FREQUENCY * TAU) % TAU;
+    }
+
+    pub fn offset(&self) -> Vec3 {
+        Vec3::new(self.phase.sin() * 0.03, -self.phase.cos().abs() * 0.04, 0.0) * self.amount
+    }
+
+    /// Offset of the player camera in its own space: a sideways sway and a
+    /// dip with each step.
+    pub fn camera_offset(&self) -> Vec3 {
+        Vec3::new(self.phase.sin() * 0.05, -self.phase.cos().abs() * 0.1, 0.0) * self.amount
+    }
+
+    /// Tilt of the player camera: a roll with the sway and a nod with each
+    /// step, both under a degree.
+    pub fn camera_tilt(&self) -> Quat {
+        let roll = self.phase.sin() * 0.5_f32.to_radians();
+        let nod = -(self.phase - 0.2).cos().abs() * 0.8_f32.to_radians();
+        Quat::from_rotation_z(roll * self.amount) * Quat::from_rotation_x(nod * self.amount)
+    }
+}
+
+/// Animation state of the arm, on the entity positioned in front of the
+/// view-model camera.
+#[derive(Component, Debug, Default)]
+pub struct ViewModel {
+    pub swing: SwingAnimation,
+    pub bob: ViewBob,
+    last_position: Option<Vec3>,
+}
+
+/// Item shown in the hand.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeldItem(pub Option<u32>);
+
+#[derive(Component)]
+struct HeldItemModel;
+
+/// Give each new [`FirstPersonView`] camera a view-model camera with an arm and
+/// held item in front of it.
+fn attach_view_model(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    hosts: Query<Entity, Added<FirstPersonView>>,
+) {
+    for host in &hosts {
+        let layer = RenderLayers::layer(VIEW_MODEL_LAYER);
+        let arm_mesh = meshes.add(Cuboid::new(0.2, 0.2, 0.7));
+        let arm_material = materials.add(StandardMaterial {
+            base_color: Color::srgb(0.85, 0.65, 0.5),
+            unlit: true,
+            ..default()
+        });
+        let item_mesh = meshes.add(Cuboid::new(0.3, 0.3, 0.3));
+        let item_material = materials.add(StandardMaterial {
+            unlit: true,
+            ..default()
+        });
+
+        commands.entity(host).with_children(|parent| {
+            parent
+                .spawn((
+                    ViewModelCamera,
+                    Camera3d::default(),
+                    Camera {
+                        order: VIEW_MODEL_CAMERA_ORDER,
+                        clear_color: ClearColorConfig::None,
+                        ..default()
+                    },
+                    Projection::from(PerspectiveProjection {
+                        fov: 70f32.to_radians(),
+                        near: 0.01,
+                        ..default()
+                    }),
+                    layer.clone(),
+                ))
+                .with_children(|camera| {
+                    camera
+                        .spawn((
+                            ViewModel::default(),
+                            HeldItem::default(),
+                            Transform::from_translation(REST_POSITION),
+                            Visibility::default(),
+                            layer.clone(),
+                        ))
+                        .with_children(|model| {
+                            model.spawn((
+                                Mesh3d(arm_mesh),
+                                MeshMaterial3d(arm_material),
+                                Transform::from_xyz(0.0, -0.1, 0.25)
+                                    .with_rotation(Quat::from_rotation_x(0.3)),
+                                layer.clone(),
+                            ));
+                            model.spawn((
+                                HeldItemModel,
+                                Mesh3d(item_mesh),
+                                MeshMaterial3d(item_material),
+                                Transform::from_xyz(-0.05, 0.1, -0.15),
+                                Visibility::Hidden,
+                                layer.clone(),
+                            ));
+                        });
+                });
+        });
+    }
+}
+
+/// Put the selected hotbar item in the view model's hand.
+pub fn update_held_item(
+    selection: Res<HotbarSelection>,
+    mut models: Query<&mut HeldItem, With<ViewModel>>,
+) {
+    let held = HeldItem(selection.held_item());
+    for mut item in &mut models {
+        item.set_if_neq(held);
+    }
+}
+
+/// Show the held item's model, tinted by item id. Blocks are drawn as a cube,
+/// other items as a flat plate.
+fn refresh_held_item_model(
+    models: Query<(&HeldItem, &Children), Changed<HeldItem>>,
+    mut items: Query<
+        (
+            &mut Visibility,
+            &mut Transform,
+            &MeshMaterial3d<StandardMaterial>,
+        ),
+        With<HeldItemModel>,
+    >,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (held, children) in &models {
+        for child in children.iter() {
+            let Ok((mut visibility, mut transform, material)) = items.get_mut(child) else {
+                continue;
+            };
+            let Some(item_id) = held.0 else {
+                *visibility = Visibility::Hidden;
+                continue;
+            };
+            *visibility = Visibility::Inherited;
+            transform.scale = if item_id < 256 {
+                Vec3::ONE
+            } else {
+                Vec3::new(1.0, 1.6, 0.15)
+            };
+            if let Some(material) = materials.get_mut(&material.0) {
+                material.base_color = item_color(item_id);
+            }
+        }
+    }
+}
+
+/// Stable, distinct color for an item id until held items are textured.
+pub(crate) fn item_color(item_id: u32) -> Color {
+    let hue = (item_id.wrapping_mul(2_654_435_761) >> 16) as f32 % 360.0;
+    Color::hsl(hue, 0.5, 0.55)
+}
+
+/// Advance swings and bobbing and pose the arm. The arm only bobs when
+/// `client.view_bobbing` is on.
+pub fn animate_view_model(
+    time: Res<Time>,
+    config: Option<Res<Config>>,
+    mut swings: MessageReader<SwingHand>,
+    cameras: Query<&GlobalTransform, With<ViewModelCamera>>,
+    mut models: Query<(&mut ViewModel, &mut Transform, &ChildOf)>,
+) {
+    let swing = swings.read().count() > 0;
+    let dt = time.delta_secs();
+    let bobbing = config.is_none_or(|config| config.client.view_bobbing);
+
+    for (mut model, mut transform, child_of) in &mut models {
+        if swing {
+            model.swing.start();
+        }
+        model.swing.tick(dt);
+
+        if let Ok(camera) = cameras.get(child_of.parent()) {
+            let position = camera.translation();
+            if dt > 0.0 {
+                let moved = model
+                    .last_position
+                    .map_or(Vec3::ZERO, |last| position - last);
+                model.bob.update(moved.xz().length() / dt, dt);
+            }
+            model.last_position = Some(position);
+        }
+
+        if !bobbing {
+            model.bob = ViewBob::default();
+        }
+
+        let (swing_offset, swing_rotation) = model.swing.pose();
+        transform.translation = REST_POSITION + model.bob.offset() + swing_offset;
+        transform.rotation = swing_rotation;
+    }
+}
+
+pub struct ViewModelPlugin;
+
+impl Plugin for ViewModelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HotbarSelection>()
+            .add_message::<SwingHand>()
+            .add_systems(
+                Update,
+                (
+                    attach_view_model,
+                    update_held_item,
+                    refresh_held_item_model,
+                    animate_view_model,
+                )
+                    .chain(),
+            );
+    }
+}
@@ -0,0 +1,146 @@
+use bevy::math::Vec3;
+use bevy::mesh::{Indices, Mesh, VertexAttributeValues};
+use ferrum_meshing_cpu::{
+    binary_greedy, ChunkMesh, Face, MeshQuad, CHUNK_SIZE, CHUNK_SIZE_CB, CHUNK_SIZE_SQ,
+};
+use ferrum_render::{BlockRenderer, TextureAtlas, AO_BRIGHTNESS};
+
+const STONE: u32 = 1;
+
+fn set(voxels: &mut [u32; CHUNK_SIZE_CB], x: usize, y: usize, z: usize) {
+    voxels[z * CHUNK_SIZE_SQ + y * CHUNK_SIZE + x] = STONE;
+}
+
+/// Position, normal and brightness of every vertex.
+fn vertices(mesh: &Mesh) -> Vec<(Vec3, Vec3, f32)> {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("Expected Float32x3 positions");
+    };
+    let Some(VertexAttributeValues::Float32x3(normals)) = mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+    else {
+        panic!("Expected Float32x3 normals");
+    };
+    let Some(VertexAttributeValues::Float32x4(colors)) = mesh.attribute(Mesh::ATTRIBUTE_COLOR)
+    else {
+        panic!("Expected Float32x4 colors");
+    };
+    positions
+        .iter()
+        .zip(normals)
+        .zip(colors)
+        .map(|((&p, &n), c)| (Vec3::from(p), Vec3::from(n), c[0]))
+        .collect()
+}
+
+fn indices(mesh: &Mesh) -> Vec<u32> {
+    match mesh.indices() {
+        Some(Indices::U32(indices)) => indices.clone(),
+        _ => panic!("Expected U32 indices"),
+    }
+}
+
+fn up_quad(x: u8, y: u8, z: u8) -> ChunkMesh {
+    let mut chunk_mesh = ChunkMesh::new();
+    chunk_mesh.quads.push(MeshQuad {
+        x,
+        y,
+        z,
+        width: 1,
+        height: 1,
+        face: Face::Up,
+        block_type: STONE,
+    });
+    chunk_mesh
+}
+
+#[test]
+fn open_surfaces_stay_bright_and_merged() {
+    let mut voxels = [0u32; CHUNK_SIZE_CB];
+    for x in 0..4 {
+        for z in 0..4 {
+            set(&mut voxels, x, 0, z);
+        }
+    }
+    let chunk_mesh = binary_greedy::mesh(&voxels);
+    let atlas = TextureAtlas::new(16);
+    let mesh = BlockRenderer::create_mesh_with_ao(&chunk_mesh, &atlas, &voxels);
+
+    let vertices = vertices(&mesh);
+    assert_eq!(vertices.len(), chunk_mesh.quad_count() * 4);
+    assert!(vertices.iter().all(|&(_, _, brightness)| brightness == 1.0));
+}
+
+#[test]
+fn corners_next_to_a_block_are_darkened() {
+    let mut voxels = [0u32; CHUNK_SIZE_CB];
+    for x in 0..4 {
+        for z in 0..4 {
+            set(&mut voxels, x, 0, z);
+        }
+    }
+    set(&mut voxels, 0, 1, 0);
+    let chunk_mesh = binary_greedy::mesh(&voxels);
+    let atlas = TextureAtlas::new(16);
+    let mesh = BlockRenderer::create_mesh_with_ao(&chunk_mesh, &atlas, &voxels);
+
+    let floor_top = vertices(&mesh)
+        .into_iter()
+        .filter(|&(position, normal, _)| normal == Vec3::Y && position.y == 1.0);
+    let mut darkened = 0;
+    for (position, _, brightness) in floor_top {
+        let touches_block = position.x <= 1.0 && position.z <= 1.0;
+        assert_eq!(brightness < 1.0, touches_block, "vertex at {position}");
+        darkened += touches_block as usize;
+    }
+    assert!(darkened > 0);
+}
+
+#[test]
+fn two_solid_sides_fully_occlude_a_corner() {
+    let mut voxels = [0u32; CHUNK_SIZE_CB];
+    set(&mut voxels, 5, 5, 5);
+    set(&mut voxels, 4, 6, 5);
+    set(&mut voxels, 5, 6, 4);
+    let atlas = TextureAtlas::new(16);
+    let mesh = BlockRenderer::create_mesh_with_ao(&up_quad(5, 5, 5), &atlas, &voxels);
+
+    for (position, _, brightness) in vertices(&mesh) {
+        let expected = match (position.x, position.z) {
+            (5.0, 5.0) => AO_BRIGHTNESS[3],
+            (5.0, 6.0) | (6.0, 5.0) => AO_BRIGHTNESS[1],
+            _ => AO_BRIGHTNESS[0],
+        };
+        assert_eq!(brightness, expected, "vertex at {position}");
+    }
+}
+
+#[test]
+fn quads_split_along_the_brighter_diagonal() {
+    let atlas = TextureAtlas::new(16);
+    // One occluder over each corner in turn, so both triangulations are needed
+    for (dx, dz) in [(0, 0), (2, 0), (2, 2), (0, 2)] {
+        let mut voxels = [0u32; CHUNK_SIZE_CB];
+        set(&mut voxels, 5, 5, 5);
+        set(&mut voxels, 4 + dx, 6, 4 + dz);
+        let mesh = BlockRenderer::create_mesh_with_ao(&up_quad(5, 5, 5), &atlas, &voxels);
+
+        let vertices = vertices(&mesh);
+        let indices = indices(&mesh);
+        let dark = vertices
+            .iter()
+            .position(|&(_, _, brightness)| brightness < 1.0)
+            .unwrap() as u32;
+        let triangles_with_dark = indices
+            .chunks(3)
+            .filter(|triangle| triangle.contains(&dark))
+            .count();
+        assert_eq!(triangles_with_dark, 1, "occluder at offset ({dx}, {dz})");
+
+        for triangle in indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| vertices[triangle[i] as usize].0);
+            assert!((b - a).cross(c - a).dot(Vec3::Y) > 0.0);
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use bevy::anti_alias::fxaa::Fxaa;
+use bevy::prelude::*;
+use ferrum_config::{AntiAliasing, Config};
+use ferrum_render::{camera_anti_aliasing, AntiAliasingPlugin};
+
+fn config_with(mode: &str) -> Config {
+    Config::from_str(&format!("[client]\nanti_aliasing = \"{}\"\n", mode)).unwrap()
+}
+
+#[test]
+fn config_value_maps_to_camera_settings() {
+    let cases = [
+        ("off", Msaa::Off, false),
+        ("fxaa", Msaa::Off, true),
+        ("msaax2", Msaa::Sample2, false),
+        ("msaax4", Msaa::Sample4, false),
+    ];
+    for (value, msaa, fxaa) in cases {
+        let config = config_with(value);
+        let (got_msaa, got_fxaa) = camera_anti_aliasing(config.client.anti_aliasing_mode());
+        assert_eq!(got_msaa, msaa, "{value}");
+        assert_eq!(got_fxaa.is_some(), fxaa, "{value}");
+    }
+}
+
+#[test]
+fn unsupported_mode_renders_without_anti_aliasing() {
+    let mut config = Config::from_str("").unwrap();
+    config.client.anti_aliasing = "taa".to_string();
+    let (msaa, fxaa) = camera_anti_aliasing(config.client.anti_aliasing_mode());
+    assert_eq!(msaa, Msaa::Off);
+    assert!(fxaa.is_none());
+    assert_eq!(camera_anti_aliasing(AntiAliasing::Off).0, Msaa::Off);
+}
+
+#[test]
+fn camera_is_updated_on_spawn_and_reload() {
+    let mut app = App::new();
+    app.add_plugins(AntiAliasingPlugin)
+        .insert_resource(config_with("msaax2"));
+    let camera = app.world_mut().spawn(Camera3d::default()).id();
+
+    app.update();
+    assert_eq!(app.world().get::<Msaa>(camera), Some(&Msaa::Sample2));
+    assert!(app.world().get::<Fxaa>(camera).is_none());
+
+    app.world_mut()
+        .resource_mut::<Config>()
+        .client
+        .anti_aliasing = "fxaa".to_string();
+    app.update();
+    assert_eq!(app.world().get::<Msaa>(camera), Some(&Msaa::Off));
+    assert!(app.world().get::<Fxaa>(camera).is_some());
+}
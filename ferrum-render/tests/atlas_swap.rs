@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+use ferrum_meshing_cpu::Face;
+use ferrum_render::{
+    paint_sprite_icon, swap_atlas_image, AtlasImageSwapped, AtlasSwapPlugin, ChunkGroupRendering,
+    ItemIcons, TextureAtlas,
+};
+use std::sync::Arc;
+
+fn textured(image: &Handle<Image>) -> StandardMaterial {
+    StandardMaterial {
+        base_color_texture: Some(image.clone()),
+        ..default()
+    }
+}
+
+fn texture_of(
+    materials: &Assets<StandardMaterial>,
+    material: &Handle<StandardMaterial>,
+) -> AssetId<Image> {
+    materials
+        .get(material)
+        .and_then(|material| material.base_color_texture.as_ref())
+        .map(Handle::id)
+        .unwrap()
+}
+
+#[test]
+fn test_swap_retargets_materials_using_the_old_atlas() {
+    let mut images = Assets::<Image>::default();
+    let mut materials = Assets::<StandardMaterial>::default();
+    let old = images.add(Image::default());
+    let new = images.add(Image::default());
+    let other = images.add(Image::default());
+
+    let chunks = materials.add(textured(&old));
+    let particles = materials.add(textured(&old));
+    let sky = materials.add(textured(&other));
+    let plain = materials.add(StandardMaterial::default());
+
+    assert_eq!(swap_atlas_image(&mut materials, &old, &new), 2);
+    assert_eq!(texture_of(&materials, &chunks), new.id());
+    assert_eq!(texture_of(&materials, &particles), new.id());
+    assert_eq!(texture_of(&materials, &sky), other.id());
+    assert!(materials.get(&plain).unwrap().base_color_texture.is_none());
+
+    // Swapping from an image nothing uses any more changes nothing
+    assert_eq!(swap_atlas_image(&mut materials, &old, &new), 0);
+}
+
+#[test]
+fn test_swap_keeps_chunk_material_and_uvs() {
+    let mut app = App::new();
+    app.init_resource::<Assets<Image>>()
+        .init_resource::<Assets<StandardMaterial>>()
+        .init_resource::<ItemIcons>()
+        .add_plugins(AtlasSwapPlugin);
+
+    let (old, new) = {
+        let mut images = app.world_mut().resource_mut::<Assets<Image>>();
+        (images.add(Image::default()), images.add(Image::default()))
+    };
+    let material = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(textured(&old));
+    app.insert_resource(ChunkGroupRendering {
+        atlas: Arc::new(TextureAtlas::new(16)),
+        material: material.clone(),
+    });
+    let world = app.world_mut();
+    world.resource_scope(|world, mut icons: Mut<ItemIcons>| {
+        let mut images = world.resource_mut::<Assets<Image>>();
+        icons.get_or_insert_with(264, &mut images, |_| paint_sprite_icon(264));
+    });
+    let block_types = 0..26;
+    let uvs_before: Vec<_> = {
+        let rendering = app.world().resource::<ChunkGroupRendering>();
+        block_types
+            .clone()
+            .map(|block| rendering.atlas.get_uvs(block, Face::Front))
+            .collect()
+    };
+
+    app.world_mut().write_message(AtlasImageSwapped {
+        old: old.clone(),
+        new: new.clone(),
+    });
+    app.update();
+
+    // Chunks keep their material and UVs; only the image behind them changes
+    let rendering = app.world().resource::<ChunkGroupRendering>();
+    assert_eq!(rendering.material, material);
+    let uvs_after: Vec<_> = block_types
+        .map(|block| rendering.atlas.get_uvs(block, Face::Front))
+        .collect();
+    assert_eq!(uvs_before, uvs_after);
+    let materials = app.world().resource::<Assets<StandardMaterial>>();
+    assert_eq!(texture_of(materials, &material), new.id());
+
+    // Icons painted from the old atlas are dropped
+    assert!(app.world().resource::<ItemIcons>().is_empty());
+}
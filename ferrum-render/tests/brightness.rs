@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+use bevy::render::view::ColorGrading;
+use ferrum_config::Config;
+use ferrum_render::{
+    brightness_to_gamma, light_factor, BrightnessPlugin, LightLevelMaterials, MAX_GAMMA,
+};
+
+#[test]
+fn brightness_maps_to_gamma() {
+    assert_eq!(brightness_to_gamma(0.5), 1.0);
+    assert!((brightness_to_gamma(1.0) - MAX_GAMMA).abs() < 1e-6);
+    assert!((brightness_to_gamma(0.0) - 1.0 / MAX_GAMMA).abs() < 1e-6);
+
+    let mut previous = 0.0;
+    for step in 0..=10 {
+        let gamma = brightness_to_gamma(step as f32 / 10.0);
+        assert!(gamma > previous, "gamma rises with brightness");
+        previous = gamma;
+    }
+
+    assert_eq!(brightness_to_gamma(-1.0), brightness_to_gamma(0.0));
+    assert_eq!(brightness_to_gamma(3.0), brightness_to_gamma(1.0));
+}
+
+#[test]
+fn fullbright_forces_full_light() {
+    for level in 0..=15 {
+        assert_eq!(light_factor(level, 0.0, true), 1.0);
+    }
+
+    assert_eq!(light_factor(0, 0.0, false), 0.0);
+    assert_eq!(light_factor(15, 0.0, false), 1.0);
+    assert!(light_factor(7, 1.0, false) > light_factor(7, 0.0, false));
+}
+
+#[test]
+fn camera_follows_brightness_config() {
+    let mut app = App::new();
+    app.add_plugins(BrightnessPlugin)
+        .init_resource::<Assets<StandardMaterial>>()
+        .insert_resource(Config::from_str("[client]\nbrightness = 1.0\n").unwrap());
+    let camera = app.world_mut().spawn(Camera3d::default()).id();
+
+    app.update();
+    let grading = app.world().get::<ColorGrading>(camera).unwrap();
+    assert_eq!(grading.midtones.gamma, brightness_to_gamma(1.0));
+    assert!(app.world().get::<AmbientLight>(camera).is_none());
+
+    app.world_mut().resource_mut::<Config>().client.fullbright = true;
+    app.update();
+    let ambient = app.world().get::<AmbientLight>(camera).unwrap();
+    assert!(ambient.brightness > 1000.0);
+
+    app.world_mut().resource_mut::<Config>().client.fullbright = false;
+    app.update();
+    assert!(app.world().get::<AmbientLight>(camera).is_none());
+}
+
+#[test]
+fn light_level_materials_glow_by_light_factor() {
+    let mut app = App::new();
+    app.add_plugins(BrightnessPlugin)
+        .init_resource::<Assets<StandardMaterial>>()
+        .insert_resource(Config::from_str("[client]\nbrightness = 0.0\n").unwrap());
+    let dim = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial::default());
+    app.world_mut()
+        .resource_mut::<LightLevelMaterials>()
+        .add(dim.clone(), 7);
+
+    let glow = |app: &App| {
+        let materials = app.world().resource::<Assets<StandardMaterial>>();
+        materials.get(&dim).unwrap().emissive.red
+    };
+    app.update();
+    assert_eq!(glow(&app), light_factor(7, 0.0, false));
+
+    app.world_mut().resource_mut::<Config>().client.brightness = 1.0;
+    app.update();
+    assert_eq!(glow(&app), light_factor(7, 1.0, false));
+
+    app.world_mut().resource_mut::<Config>().client.fullbright = true;
+    app.update();
+    assert_eq!(glow(&app), 1.0);
+}
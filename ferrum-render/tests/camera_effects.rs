@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+use ferrum_config::Config;
+use ferrum_render::{
+    ease_fov, target_fov, CameraBob, CameraEffectsPlugin, FirstPersonView, Sprinting, ViewBob,
+    SPRINT_FOV_SCALE,
+};
+use std::time::Duration;
+
+const FRAME: Duration = Duration::from_millis(50);
+
+fn app(config: &str) -> App {
+    let mut app = App::new();
+    app.insert_resource(Config::from_str(config).unwrap())
+        .init_resource::<Time>()
+        .add_plugins(CameraEffectsPlugin);
+    app
+}
+
+fn spawn_camera(app: &mut App) -> Entity {
+    app.world_mut()
+        .spawn((
+            FirstPersonView,
+            Transform::from_xyz(0.0, 65.62, 0.0),
+            Projection::from(PerspectiveProjection::default()),
+        ))
+        .id()
+}
+
+/// Advance one frame, moving the camera's un-bobbed position by `step` first
+/// as the player controller would.
+fn step(app: &mut App, camera: Entity, step: Vec3) {
+    let base = app
+        .world()
+        .get::<CameraBob>(camera)
+        .and_then(CameraBob::base_translation);
+    let mut transform = app.world_mut().get_mut::<Transform>(camera).unwrap();
+    transform.translation = base.unwrap_or(transform.translation) + step;
+    app.world_mut().resource_mut::<Time>().advance_by(FRAME);
+    app.update();
+}
+
+fn bob_offset(app: &App, camera: Entity) -> Vec3 {
+    let bob = app.world().get::<CameraBob>(camera).unwrap();
+    app.world().get::<Transform>(camera).unwrap().translation - bob.base_translation().unwrap()
+}
+
+fn fov_degrees(app: &App, camera: Entity) -> f32 {
+    match app.world().get::<Projection>(camera).unwrap() {
+        Projection::Perspective(perspective) => perspective.fov.to_degrees(),
+        _ => panic!("Expected a perspective projection"),
+    }
+}
+
+#[test]
+fn camera_bob_is_zero_when_stationary() {
+    let mut bob = ViewBob::default();
+    for _ in 0..20 {
+        bob.update(0.0, 0.05);
+    }
+    assert_eq!(bob.camera_offset(), Vec3::ZERO);
+    assert_eq!(bob.camera_tilt(), Quat::IDENTITY);
+
+    let mut app = app("");
+    let camera = spawn_camera(&mut app);
+    for _ in 0..20 {
+        step(&mut app, camera, Vec3::ZERO);
+    }
+    let transform = app.world().get::<Transform>(camera).unwrap();
+    assert_eq!(transform.translation, Vec3::new(0.0, 65.62, 0.0));
+    assert_eq!(transform.rotation, Quat::IDENTITY);
+}
+
+#[test]
+fn camera_bobs_while_walking() {
+    let mut app = app("");
+    let camera = spawn_camera(&mut app);
+    // Walking speed, 4.3 blocks per second
+    let walk = Vec3::new(0.0, 0.0, -0.215);
+    let mut largest = 0.0_f32;
+    for _ in 0..20 {
+        step(&mut app, camera, walk);
+        largest = largest.max(bob_offset(&app, camera).length());
+    }
+    assert!(largest > 0.01, "camera bobbed by {largest}");
+    assert!(largest < 0.2, "bob stays subtle");
+
+    // The bob is applied on top of the movement, never fed back into it
+    let base = app
+        .world()
+        .get::<CameraBob>(camera)
+        .unwrap()
+        .base_translation()
+        .unwrap();
+    assert!(base.abs_diff_eq(Vec3::new(0.0, 65.62, -4.3), 1e-3));
+}
+
+#[test]
+fn view_bobbing_can_be_turned_off() {
+    let mut app = app("[client]\nview_bobbing = false\n");
+    let camera = spawn_camera(&mut app);
+    for _ in 0..20 {
+        step(&mut app, camera, Vec3::new(0.0, 0.0, -0.215));
+        assert_eq!(bob_offset(&app, camera), Vec3::ZERO);
+        let transform = app.world().get::<Transform>(camera).unwrap();
+        assert_eq!(transform.rotation, Quat::IDENTITY);
+    }
+}
+
+#[test]
+fn sprint_fov_eases_to_its_target() {
+    assert_eq!(target_fov(70.0, false, true), 70.0);
+    assert_eq!(target_fov(70.0, true, true), 70.0 * SPRINT_FOV_SCALE);
+    assert_eq!(target_fov(70.0, true, false), 70.0);
+
+    // Eases in without overshooting and settles on the target
+    let mut fov = 70.0;
+    let target = 70.0 * SPRINT_FOV_SCALE;
+    for _ in 0..5 {
+        let next = ease_fov(fov, target, 0.05);
+        assert!(next > fov && next <= target);
+        fov = next;
+    }
+    assert!(fov < target);
+    for _ in 0..20 {
+        fov = ease_fov(fov, target, 0.05);
+    }
+    assert_eq!(fov, target);
+
+    let mut app = app("[client]\nfov = 80.0\n");
+    let camera = spawn_camera(&mut app);
+    app.update();
+    assert!((fov_degrees(&app, camera) - 80.0).abs() < 1e-3);
+
+    app.insert_resource(Sprinting(true));
+    for _ in 0..20 {
+        step(&mut app, camera, Vec3::ZERO);
+    }
+    assert!((fov_degrees(&app, camera) - 80.0 * SPRINT_FOV_SCALE).abs() < 1e-3);
+
+    app.insert_resource(Sprinting(false));
+    for _ in 0..20 {
+        step(&mut app, camera, Vec3::ZERO);
+    }
+    assert!((fov_degrees(&app, camera) - 80.0).abs() < 1e-3);
+}
+
+#[test]
+fn fov_effects_can_be_turned_off() {
+    let mut app = app("[client]\nfov_effects = false\n");
+    let camera = spawn_camera(&mut app);
+    app.insert_resource(Sprinting(true));
+    for _ in 0..20 {
+        step(&mut app, camera, Vec3::ZERO);
+        assert!((fov_degrees(&app, camera) - 70.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+use ferrum_config::Config;
+use ferrum_render::{ChunkFade, ChunkFadePlugin, ChunkGroupMesh};
+use std::time::Duration;
+
+#[test]
+fn test_fade_progresses_and_completes() {
+    let mut fade = ChunkFade::new(0.5);
+    assert_eq!(fade.alpha(), 0.0);
+    assert!(!fade.is_complete());
+
+    fade.tick(0.25);
+    assert!((fade.alpha() - 0.5).abs() < 1e-6);
+    assert!(!fade.is_complete());
+
+    fade.tick(1.0);
+    assert_eq!(fade.alpha(), 1.0);
+    assert_eq!(fade.elapsed(), 0.5);
+    assert!(fade.is_complete());
+}
+
+#[test]
+fn test_zero_duration_fade_is_complete() {
+    let fade = ChunkFade::new(0.0);
+    assert!(fade.is_complete());
+    assert_eq!(fade.alpha(), 1.0);
+}
+
+fn fade_app() -> (App, Handle<StandardMaterial>) {
+    let mut app = App::new();
+    app.init_resource::<Time>()
+        .init_resource::<Assets<StandardMaterial>>()
+        .init_resource::<Assets<Mesh>>()
+        .insert_resource(Config::from_str("[client]\nchunk_fade_in = 1.0\n").unwrap())
+        .add_plugins(ChunkFadePlugin);
+    let material = app
+        .world_mut()
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial::default());
+    (app, material)
+}
+
+fn advance(app: &mut App, millis: u64) {
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_millis(millis));
+    app.update();
+}
+
+fn alpha(app: &App, entity: Entity) -> f32 {
+    let material = app
+        .world()
+        .get::<MeshMaterial3d<StandardMaterial>>(entity)
+        .unwrap();
+    app.world()
+        .resource::<Assets<StandardMaterial>>()
+        .get(&material.0)
+        .unwrap()
+        .base_color
+        .alpha()
+}
+
+fn spawn_group(app: &mut App, material: &Handle<StandardMaterial>) -> Entity {
+    let mesh = app
+        .world_mut()
+        .resource_mut::<Assets<Mesh>>()
+        .add(Cuboid::default());
+    app.world_mut()
+        .spawn((
+            ChunkGroupMesh(IVec2::ZERO),
+            MeshMaterial3d(material.clone()),
+            Mesh3d(mesh),
+        ))
+        .id()
+}
+
+#[test]
+fn test_new_chunk_fades_in_then_turns_opaque() {
+    let (mut app, material) = fade_app();
+    let chunk = spawn_group(&mut app, &material);
+
+    advance(&mut app, 0);
+    assert!(app.world().get::<ChunkFade>(chunk).is_some());
+    assert_eq!(alpha(&app, chunk), 0.0);
+
+    advance(&mut app, 500);
+    assert!((alpha(&app, chunk) - 0.5).abs() < 1e-3);
+
+    advance(&mut app, 600);
+    assert!(app.world().get::<ChunkFade>(chunk).is_none());
+    let restored = app.world().get::<MeshMaterial3d<StandardMaterial>>(chunk);
+    assert_eq!(restored.unwrap().0, material);
+    assert_eq!(alpha(&app, chunk), 1.0);
+}
+
+#[test]
+fn test_remesh_does_not_restart_fade() {
+    let (mut app, material) = fade_app();
+    let chunk = spawn_group(&mut app, &material);
+    advance(&mut app, 0);
+    advance(&mut app, 500);
+
+    // A re-mesh replaces the mesh of the existing group entity
+    let mesh = app
+        .world_mut()
+        .resource_mut::<Assets<Mesh>>()
+        .add(Cuboid::default());
+    app.world_mut().entity_mut(chunk).insert(Mesh3d(mesh));
+    advance(&mut app, 100);
+    let fade = app.world().get::<ChunkFade>(chunk).unwrap();
+    assert!((fade.elapsed() - 0.6).abs() < 1e-3);
+
+    // Nor once the fade has finished
+    advance(&mut app, 1000);
+    let mesh = app
+        .world_mut()
+        .resource_mut::<Assets<Mesh>>()
+        .add(Cuboid::default());
+    app.world_mut().entity_mut(chunk).insert(Mesh3d(mesh));
+    advance(&mut app, 100);
+    assert!(app.world().get::<ChunkFade>(chunk).is_none());
+    assert_eq!(alpha(&app, chunk), 1.0);
+}
+
+#[test]
+fn test_zero_fade_in_disables_fading() {
+    let (mut app, material) = fade_app();
+    app.world_mut()
+        .resource_mut::<Config>()
+        .client
+        .chunk_fade_in = 0.0;
+    let chunk = spawn_group(&mut app, &material);
+
+    advance(&mut app, 0);
+    assert!(app.world().get::<ChunkFade>(chunk).is_none());
+    assert_eq!(alpha(&app, chunk), 1.0);
+}
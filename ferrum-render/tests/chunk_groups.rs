@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+use ferrum_meshing_cpu::{ChunkMesh, Face, MeshQuad};
+use ferrum_render::{
+    merge_chunk_meshes, rebuild_chunk_groups, BlockRenderer, ChunkGroupMesh, ChunkGroupRendering,
+    ChunkGroups, PendingChunkMesh, TextureAtlas,
+};
+use std::sync::Arc;
+
+fn quad(x: u8, face: Face) -> MeshQuad {
+    MeshQuad {
+        x,
+        y: 0,
+        z: 0,
+        width: 1,
+        height: 1,
+        face,
+        block_type: 1,
+    }
+}
+
+fn chunk_mesh(quads: usize) -> ChunkMesh {
+    ChunkMesh {
+        quads: (0..quads).map(|i| quad(i as u8, Face::Up)).collect(),
+    }
+}
+
+#[test]
+fn test_merged_counts_sum() {
+    let atlas = TextureAtlas::new(16);
+    let chunks = [chunk_mesh(1), chunk_mesh(2), chunk_mesh(3)];
+    let origins = [IVec3::ZERO, IVec3::new(32, 0, 0), IVec3::new(0, 32, 32)];
+
+    let merged = merge_chunk_meshes(chunks.iter().zip(origins), &atlas);
+
+    assert_eq!(merged.vertex_count(), 6 * 4);
+    assert_eq!(merged.normals.len(), 6 * 4);
+    assert_eq!(merged.uvs.len(), 6 * 4);
+    assert_eq!(merged.indices.len(), 6 * 6);
+    assert!(merged
+        .indices
+        .iter()
+        .all(|&i| (i as usize) < merged.vertex_count()));
+    // The second chunk's indices point at its own vertices
+    assert_eq!(&merged.indices[6..12], &[4, 5, 6, 4, 6, 7]);
+}
+
+#[test]
+fn test_merged_positions_offset_to_world() {
+    let atlas = TextureAtlas::new(16);
+    let mesh = chunk_mesh(1);
+    let local = BlockRenderer::build_buffers(&mesh, &atlas, IVec3::ZERO);
+    let origin = IVec3::new(-32, 64, 96);
+
+    let merged = merge_chunk_meshes([(&mesh, IVec3::ZERO), (&mesh, origin)], &atlas);
+
+    assert_eq!(&merged.positions[..4], &local.positions[..]);
+    for (world, local) in merged.positions[4..].iter().zip(&local.positions) {
+        assert_eq!(Vec3::from(*world), Vec3::from(*local) + origin.as_vec3());
+    }
+}
+
+#[test]
+fn test_chunks_sorted_into_groups() {
+    let mut groups = ChunkGroups::new(2);
+    groups.insert(IVec3::new(0, 0, 0), IVec3::ZERO, chunk_mesh(1));
+    groups.insert(IVec3::new(1, 3, 1), IVec3::new(32, 96, 32), chunk_mesh(1));
+    groups.insert(IVec3::new(-1, 0, 0), IVec3::new(-32, 0, 0), chunk_mesh(1));
+
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups.group_of(IVec3::new(-1, 0, 0)), IVec2::new(-1, 0));
+    assert_eq!(groups.members(IVec2::ZERO).count(), 2);
+
+    let mut dirty = groups.take_dirty();
+    dirty.sort_by_key(|g| (g.x, g.y));
+    assert_eq!(dirty, vec![IVec2::new(-1, 0), IVec2::ZERO]);
+    assert!(groups.take_dirty().is_empty());
+
+    // Changing one member marks only its group for a rebuild
+    groups.insert(IVec3::new(0, 1, 0), IVec3::new(0, 32, 0), chunk_mesh(2));
+    assert_eq!(groups.take_dirty(), vec![IVec2::ZERO]);
+
+    groups.set_group_size(1);
+    assert_eq!(groups.len(), 3);
+    assert_eq!(groups.members(IVec2::ZERO).count(), 2);
+    assert_eq!(groups.take_dirty().len(), 3, "old and new groups rebuild");
+}
+
+#[test]
+fn test_rebuild_spawns_one_entity_per_group() {
+    let mut app = App::new();
+    let mut groups = ChunkGroups::new(4);
+    for x in 0..4 {
+        for z in 0..4 {
+            let origin = IVec3::new(x * 32, 0, z * 32);
+            groups.insert(IVec3::new(x, 0, z), origin, chunk_mesh(1));
+        }
+    }
+    app.insert_resource(groups)
+        .insert_resource(ChunkGroupRendering {
+            atlas: Arc::new(TextureAtlas::new(16)),
+            material: Handle::default(),
+        })
+        .add_systems(Update, rebuild_chunk_groups);
+
+    app.update();
+    let mut spawned = app
+        .world_mut()
+        .query::<(Entity, &ChunkGroupMesh, &PendingChunkMesh)>();
+    let entities: Vec<Entity> = spawned.iter(app.world()).map(|(e, ..)| e).collect();
+    assert_eq!(entities.len(), 1, "16 chunks share one draw call");
+
+    // Emptying the group removes its entity
+    let mut groups = app.world_mut().resource_mut::<ChunkGroups>();
+    for x in 0..4 {
+        for z in 0..4 {
+            groups.remove(IVec3::new(x, 0, z));
+        }
+    }
+    app.update();
+    assert!(app.world().get_entity(entities[0]).is_err());
+}
@@ -0,0 +1,208 @@
+use bevy::prelude::*;
+use ferrum_config::Config;
+use ferrum_core::BlockId;
+use ferrum_meshing_cpu::{CpuMesher, Face};
+use ferrum_render::{
+    chunk_distance, column_section_voxels, AdaptiveLodPlugin, ChunkLods, LodConfig, LodLevel,
+};
+use ferrum_world::{Chunk, ChunkColumn, ChunkPos, World};
+use std::time::Duration;
+
+/// Solid ground thick enough to survive the coarsest LOD.
+fn ground_chunk() -> Chunk {
+    let mut chunk = Chunk::new();
+    for x in 0..32 {
+        for y in 0..8 {
+            for z in 0..32 {
+                chunk.set_block(x, y, z, BlockId::new(1));
+            }
+        }
+    }
+    chunk
+}
+
+/// A row of chunks along +x at the given chunk x coordinates.
+fn row_world(xs: impl IntoIterator<Item = i32>) -> World {
+    let mut world = World::new();
+    for x in xs {
+        world.set_chunk(ChunkPos { x, z: 0 }, ground_chunk());
+    }
+    world
+}
+
+/// Camera above the centre of chunk column `x`, 0.
+fn camera_over(x: f32) -> Vec3 {
+    Vec3::new(x * 32.0 + 16.0, 70.0, 16.0)
+}
+
+fn pos(x: i32) -> ChunkPos {
+    ChunkPos { x, z: 0 }
+}
+
+#[test]
+fn chunks_get_the_lod_for_their_distance() {
+    let world = row_world([0, 10, 20, 40, 60, 70]);
+    assert_eq!(chunk_distance(pos(20), camera_over(0.0)), 20.0);
+
+    let mut lods = ChunkLods::default();
+    let update = lods.update(&world, camera_over(0.0), &CpuMesher::new());
+
+    assert_eq!(lods.lod(pos(0)), Some(LodLevel::Full));
+    assert_eq!(lods.lod(pos(10)), Some(LodLevel::Full));
+    assert_eq!(lods.lod(pos(20)), Some(LodLevel::Reduced));
+    assert_eq!(lods.lod(pos(40)), Some(LodLevel::Low));
+    assert_eq!(lods.lod(pos(60)), Some(LodLevel::Minimal));
+    assert_eq!(lods.lod(pos(70)), None, "beyond the render distance");
+
+    assert_eq!(update.meshed.len(), 5);
+    assert!(update.removed.is_empty());
+    for (pos, lod, mesh) in &update.meshed {
+        assert_eq!(Some(*lod), lods.lod(*pos));
+        assert!(!mesh.quads.is_empty());
+    }
+
+    let stats = lods.stats();
+    assert_eq!(stats.chunks_per_level, [2, 1, 1, 1, 0]);
+    let quads: u32 = update
+        .meshed
+        .iter()
+        .map(|(_, _, mesh)| mesh.quads.len() as u32)
+        .sum();
+    assert_eq!(stats.total_quads(), quads);
+}
+
+#[test]
+fn moving_the_camera_remeshes_only_on_boundary_crossings() {
+    let world = row_world(0..=40);
+    let mesher = CpuMesher::new();
+    let mut lods = ChunkLods::default();
+    assert_eq!(
+        lods.update(&world, camera_over(0.0), &mesher).meshed.len(),
+        41
+    );
+
+    // Nothing moves: nothing to do
+    let update = lods.update(&world, camera_over(0.0), &mesher);
+    assert!(update.meshed.is_empty() && update.removed.is_empty());
+
+    // Half a chunk takes chunk 17 to 16.5, past the Full boundary but
+    // within the hysteresis
+    assert!(lods
+        .update(&world, camera_over(0.5), &mesher)
+        .meshed
+        .is_empty());
+    assert_eq!(lods.lod(pos(17)), Some(LodLevel::Reduced));
+
+    // Three chunks: only chunks more than a chunk past a boundary switch
+    let update = lods.update(&world, camera_over(3.0), &mesher);
+    let mut switched: Vec<_> = update
+        .meshed
+        .iter()
+        .map(|(pos, lod, _)| (pos.x, *lod))
+        .collect();
+    switched.sort_by_key(|&(x, _)| x);
+    assert_eq!(switched, [(17, LodLevel::Full), (33, LodLevel::Reduced)]);
+    assert_eq!(lods.lod(pos(18)), Some(LodLevel::Reduced));
+
+    // Going back is within the hysteresis of the new levels
+    assert!(lods
+        .update(&world, camera_over(0.0), &mesher)
+        .meshed
+        .is_empty());
+}
+
+#[test]
+fn unloaded_and_distant_chunks_are_removed() {
+    let mut world = row_world([0, 5, 60]);
+    let mesher = CpuMesher::new();
+    let mut lods = ChunkLods::new(LodConfig::default(), 0.0);
+    lods.update(&world, camera_over(0.0), &mesher);
+
+    world.remove_chunk(pos(5));
+    let update = lods.update(&world, camera_over(-10.0), &mesher);
+    let mut removed: Vec<_> = update.removed.iter().map(|pos| pos.x).collect();
+    removed.sort();
+    assert_eq!(removed, [5, 60]);
+    assert_eq!(lods.lod(pos(5)), None);
+    assert_eq!(lods.lod(pos(60)), None);
+    assert_eq!(lods.lod(pos(0)), Some(LodLevel::Full));
+    assert_eq!(lods.stats().total_chunks(), 1);
+}
+
+#[test]
+fn full_detail_chunks_cull_faces_against_their_neighbours() {
+    let world = row_world([0, 1]);
+    let mut lods = ChunkLods::new(LodConfig::default(), 0.0);
+    let update = lods.update(&world, camera_over(0.5), &CpuMesher::new());
+
+    let faces = |x: i32, face: Face| {
+        let (_, lod, mesh) = update.meshed.iter().find(|(p, _, _)| *p == pos(x)).unwrap();
+        assert_eq!(*lod, LodLevel::Full);
+        mesh.quads.iter().filter(|quad| quad.face == face).count()
+    };
+    // The shared side is hidden, the outer sides are not
+    assert_eq!(faces(0, Face::Right), 0);
+    assert_eq!(faces(1, Face::Left), 0);
+    assert!(faces(0, Face::Left) > 0);
+    assert!(faces(1, Face::Right) > 0);
+}
+
+#[test]
+fn column_sections_are_meshed_bottom_up_skipping_air() {
+    let mut column = ChunkColumn::new();
+    column.set_block(1, -64, 2, BlockId::new(1));
+    column.set_block(1, 100, 2, BlockId::new(3));
+
+    let sections: Vec<_> = column_section_voxels(&column).collect();
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[0].0, -64);
+    assert_eq!(sections[0].1[2 * 32 * 32 + 1], 1);
+    // y = 100 is 4 blocks into the section starting at 96
+    assert_eq!(sections[1].0, 96);
+    assert_eq!(sections[1].1[2 * 32 * 32 + 4 * 32 + 1], 3);
+}
+
+fn adaptive_app(target_fps: u32) -> App {
+    let mut app = App::new();
+    app.init_resource::<Time>()
+        .insert_resource(
+            Config::from_str(&format!("[render]\ntarget_fps = {}\n", target_fps)).unwrap(),
+        )
+        .add_plugins(AdaptiveLodPlugin);
+    app
+}
+
+fn run_frames(app: &mut App, frames: u32, millis: u64) {
+    for _ in 0..frames {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(millis));
+        app.update();
+    }
+}
+
+fn full_max(app: &App) -> f32 {
+    app.world().resource::<ChunkLods>().config.full_max
+}
+
+#[test]
+fn lod_distances_follow_the_target_fps() {
+    let mut app = adaptive_app(60);
+    let base = full_max(&app);
+
+    // 10 fps against a 60 fps target pulls the distances in
+    run_frames(&mut app, 90, 100);
+    let tightened = full_max(&app);
+    assert!(tightened < base);
+
+    // Plenty of headroom lets them back out, but never past the base
+    run_frames(&mut app, 30, 1);
+    assert!(full_max(&app) > tightened);
+    run_frames(&mut app, 300, 1);
+    assert_eq!(full_max(&app), base);
+
+    // The same 10 fps meets a 5 fps target
+    let mut app = adaptive_app(5);
+    run_frames(&mut app, 90, 100);
+    assert_eq!(full_max(&app), base);
+}
@@ -0,0 +1,108 @@
+use bevy::mesh::VertexAttributeValues;
+use bevy::prelude::*;
+use ferrum_config::{CloudMode, Config};
+use ferrum_render::{
+    cloud_mesh, cloud_offset, cloud_origin, CloudLayer, CloudsPlugin, CLOUD_PERIOD, CLOUD_SPEED,
+    CLOUD_THICKNESS,
+};
+
+#[test]
+fn offset_drifts_with_time_and_wraps() {
+    assert_eq!(cloud_offset(0.0, CLOUD_SPEED), 0.0);
+    assert!((cloud_offset(10.0, CLOUD_SPEED) - 10.0 * CLOUD_SPEED).abs() < 1e-4);
+    assert!(cloud_offset(20.0, CLOUD_SPEED) > cloud_offset(10.0, CLOUD_SPEED));
+
+    let period_secs = (CLOUD_PERIOD / CLOUD_SPEED) as f64;
+    assert!(cloud_offset(period_secs, CLOUD_SPEED) < 1e-3);
+    assert!((cloud_offset(period_secs + 10.0, CLOUD_SPEED) - 10.0 * CLOUD_SPEED).abs() < 1e-3);
+
+    // Still in range and smooth after days of play
+    let day = 60.0 * 60.0 * 24.0 * 3.0;
+    let a = cloud_offset(day, CLOUD_SPEED);
+    let b = cloud_offset(day + 1.0, CLOUD_SPEED);
+    assert!((0.0..CLOUD_PERIOD).contains(&a));
+    assert!(((b - a).rem_euclid(CLOUD_PERIOD) - CLOUD_SPEED).abs() < 1e-3);
+}
+
+#[test]
+fn origin_follows_camera_in_whole_periods() {
+    let offset = 5.0;
+    let origin = cloud_origin(Vec3::new(10.0, 70.0, -10.0), offset, 192.0);
+    assert_eq!(origin, Vec3::new(offset, 192.0, -CLOUD_PERIOD));
+
+    for x in [-1000.0, -1.0, 0.0, 383.0, 5000.0] {
+        let origin = cloud_origin(Vec3::new(x, 0.0, x), offset, 192.0);
+        assert!(origin.x <= x && x < origin.x + CLOUD_PERIOD, "{x}");
+        assert!(origin.z <= x && x < origin.z + CLOUD_PERIOD, "{x}");
+        assert_eq!(((origin.x - offset) / CLOUD_PERIOD).fract(), 0.0);
+    }
+}
+
+fn heights(mesh: &Mesh) -> Vec<f32> {
+    match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(positions)) => {
+            positions.iter().map(|position| position[1]).collect()
+        }
+        _ => panic!("cloud mesh has no positions"),
+    }
+}
+
+#[test]
+fn fast_clouds_are_flat_and_fancy_clouds_have_thickness() {
+    assert!(cloud_mesh(CloudMode::Off).is_none());
+
+    let fast = heights(&cloud_mesh(CloudMode::Fast).unwrap());
+    assert!(!fast.is_empty());
+    assert!(fast.iter().all(|&y| y == 0.0));
+
+    let fancy = heights(&cloud_mesh(CloudMode::Fancy).unwrap());
+    assert!(fancy.contains(&0.0));
+    assert!(fancy.contains(&CLOUD_THICKNESS));
+    assert!(fancy.len() > fast.len());
+}
+
+fn cloud_layers(app: &mut App) -> Vec<CloudLayer> {
+    let world = app.world_mut();
+    world.query::<&CloudLayer>().iter(world).copied().collect()
+}
+
+#[test]
+fn config_toggles_cloud_layer() {
+    let mut app = App::new();
+    app.init_resource::<Time>()
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<StandardMaterial>>()
+        .insert_resource(Config::from_str("[client]\ncloud_height = 150.0\n").unwrap())
+        .add_plugins(CloudsPlugin);
+
+    app.update();
+    assert_eq!(
+        cloud_layers(&mut app),
+        vec![CloudLayer {
+            mode: CloudMode::Fancy
+        }]
+    );
+
+    app.update();
+    let world = app.world_mut();
+    let height = world
+        .query_filtered::<&Transform, With<CloudLayer>>()
+        .single(world)
+        .unwrap()
+        .translation
+        .y;
+    assert_eq!(height, 150.0);
+
+    app.world_mut().resource_mut::<Config>().client.clouds = "off".to_string();
+    app.update();
+    assert!(cloud_layers(&mut app).is_empty());
+
+    app.world_mut().resource_mut::<Config>().client.clouds = "fast".to_string();
+    app.update();
+    assert_eq!(
+        cloud_layers(&mut app),
+        vec![CloudLayer {
+            mode: CloudMode::Fast
+        }]
+    );
+}
@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+use ferrum_core::{BlockState, GLOWSTONE, LAVA};
+use ferrum_meshing_cpu::{ChunkMesh, Face, MeshQuad};
+use ferrum_render::BlockRenderer;
+
+const STONE: u32 = 1;
+
+fn quad(x: u8, block_type: u32) -> MeshQuad {
+    MeshQuad {
+        x,
+        y: 0,
+        z: 0,
+        width: 1,
+        height: 1,
+        face: Face::Up,
+        block_type,
+    }
+}
+
+fn glowstone() -> u32 {
+    BlockState::from(GLOWSTONE).to_bits()
+}
+
+#[test]
+fn test_glowstone_quad_is_emissive_and_stone_is_not() {
+    let glow = BlockRenderer::light_emission(quad(0, glowstone()).block_type);
+    let stone = BlockRenderer::light_emission(quad(1, STONE).block_type);
+
+    assert_eq!(glow, 15);
+    assert_eq!(stone, 0);
+    assert_ne!(BlockRenderer::emissive_color(glow), LinearRgba::BLACK);
+    assert_eq!(BlockRenderer::emissive_color(stone), LinearRgba::BLACK);
+    assert_eq!(BlockRenderer::emissive_color(15), LinearRgba::WHITE);
+}
+
+#[test]
+fn test_emissive_color_scales_with_level() {
+    let dim = BlockRenderer::emissive_color(5);
+    let bright = BlockRenderer::emissive_color(10);
+    assert!(dim.red > 0.0 && dim.red < bright.red);
+    assert_eq!(
+        BlockRenderer::emissive_color(200),
+        BlockRenderer::emissive_color(15)
+    );
+}
+
+#[test]
+fn test_split_emissive_groups_by_level() {
+    let lava = BlockState::new(LAVA, 3).to_bits();
+    let mesh = ChunkMesh {
+        quads: vec![
+            quad(0, STONE),
+            quad(1, glowstone()),
+            quad(2, lava),
+            quad(3, STONE),
+        ],
+    };
+
+    let (unlit, emissive) = BlockRenderer::split_emissive(&mesh);
+
+    assert_eq!(unlit.quad_count(), 2);
+    assert!(unlit.quads.iter().all(|q| q.block_type == STONE));
+    assert_eq!(emissive.len(), 1, "glowstone and lava share a level");
+    assert_eq!(emissive[0].0, 15);
+    assert_eq!(emissive[0].1.quad_count(), 2);
+}
+
+#[test]
+fn test_emissive_material_glows_with_atlas() {
+    let texture = Handle::<Image>::default();
+    let material = BlockRenderer::emissive_material(texture.clone(), 15);
+    assert_eq!(material.emissive, LinearRgba::WHITE);
+    assert_eq!(material.emissive_texture, Some(texture.clone()));
+    assert_eq!(material.base_color_texture, Some(texture));
+}
@@ -0,0 +1,121 @@
+use bevy::pbr::{DistanceFog, FogFalloff};
+use bevy::prelude::*;
+use ferrum_core::{BlockId, BlockState, LAVA, WATER};
+use ferrum_render::{
+    fluid_at, fluid_surface_height, CameraFluid, FluidOverlay, FluidOverlayPlugin, SubmergedFluid,
+    SurfaceFog,
+};
+
+/// A world with water at y 0-1, lava at y 5 and stone at y 10, all above the
+/// cell at x = z = 0.
+fn block_at(cell: IVec3) -> BlockState {
+    if cell.x != 0 || cell.z != 0 {
+        return BlockState::default();
+    }
+    match cell.y {
+        0 => BlockState::from(WATER),
+        1 => BlockState::new(WATER, 4),
+        5 => BlockState::from(LAVA),
+        10 => BlockState::from(BlockId::new(1)),
+        _ => BlockState::default(),
+    }
+}
+
+#[test]
+fn detects_fluid_around_the_camera() {
+    assert_eq!(
+        fluid_at(Vec3::new(0.5, 0.5, 0.5), block_at),
+        Some(SubmergedFluid::Water)
+    );
+    assert_eq!(
+        fluid_at(Vec3::new(0.5, 5.9, 0.5), block_at),
+        Some(SubmergedFluid::Lava)
+    );
+    assert_eq!(fluid_at(Vec3::new(0.5, 10.5, 0.5), block_at), None);
+    assert_eq!(fluid_at(Vec3::new(0.5, 3.0, 0.5), block_at), None);
+    // Cells are found by flooring, also on the negative side
+    assert_eq!(fluid_at(Vec3::new(-0.5, 0.5, 0.5), block_at), None);
+}
+
+#[test]
+fn partial_fluid_only_counts_below_its_surface() {
+    let flowing = BlockState::new(WATER, 4);
+    assert_eq!(fluid_surface_height(BlockState::from(WATER)), Some(1.0));
+    assert_eq!(fluid_surface_height(flowing), Some(0.5));
+    assert_eq!(
+        fluid_surface_height(BlockState::from(BlockId::new(1))),
+        None
+    );
+
+    assert_eq!(
+        fluid_at(Vec3::new(0.5, 1.4, 0.5), block_at),
+        Some(SubmergedFluid::Water)
+    );
+    assert_eq!(fluid_at(Vec3::new(0.5, 1.6, 0.5), block_at), None);
+}
+
+#[test]
+fn water_and_lava_have_their_own_colors() {
+    let water = SubmergedFluid::Water.overlay_color().to_srgba();
+    let lava = SubmergedFluid::Lava.overlay_color().to_srgba();
+    assert!(water.blue > water.red);
+    assert!(lava.red > lava.blue);
+    assert!(water.alpha > 0.0 && water.alpha < 1.0);
+    assert!(lava.alpha > water.alpha);
+
+    assert!(SubmergedFluid::Water.fog_color().to_srgba().blue > 0.3);
+    assert!(SubmergedFluid::Lava.fog_color().to_srgba().red > 0.3);
+    let (FogFalloff::Linear { end: water_end, .. }, FogFalloff::Linear { end: lava_end, .. }) = (
+        SubmergedFluid::Water.fog_falloff(),
+        SubmergedFluid::Lava.fog_falloff(),
+    ) else {
+        panic!("fluid fog should be linear");
+    };
+    assert!(lava_end < water_end);
+}
+
+#[test]
+fn fluid_tints_the_screen_and_restores_fog() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(FluidOverlayPlugin);
+    let surface_fog = DistanceFog {
+        color: Color::srgb(0.7, 0.8, 1.0),
+        falloff: FogFalloff::Linear {
+            start: 200.0,
+            end: 256.0,
+        },
+        ..default()
+    };
+    let camera = app
+        .world_mut()
+        .spawn((Camera3d::default(), surface_fog.clone()))
+        .id();
+    app.update();
+
+    let overlay = |app: &mut App| {
+        let mut query = app
+            .world_mut()
+            .query_filtered::<(&BackgroundColor, &Visibility), With<FluidOverlay>>();
+        let (background, visibility) = query.single(app.world()).unwrap();
+        (background.0, *visibility)
+    };
+    assert_eq!(overlay(&mut app).1, Visibility::Hidden);
+
+    app.insert_resource(CameraFluid(Some(SubmergedFluid::Lava)));
+    app.update();
+    assert_eq!(
+        overlay(&mut app),
+        (SubmergedFluid::Lava.overlay_color(), Visibility::Inherited)
+    );
+    let fog = app.world().get::<DistanceFog>(camera).unwrap();
+    assert_eq!(fog.color, SubmergedFluid::Lava.fog_color());
+    assert!(app.world().get::<SurfaceFog>(camera).is_some());
+
+    app.insert_resource(CameraFluid(None));
+    app.update();
+    assert_eq!(overlay(&mut app).1, Visibility::Hidden);
+    let fog = app.world().get::<DistanceFog>(camera).unwrap();
+    assert_eq!(fog.color, surface_fog.color);
+    assert!(app.world().get::<SurfaceFog>(camera).is_none());
+}
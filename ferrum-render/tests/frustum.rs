@@ -0,0 +1,75 @@
+use bevy::math::{IVec3, Mat4, Vec3};
+use ferrum_render::{ChunkBounds, Frustum};
+
+/// Camera at `eye` looking along -z, with Bevy's default projection.
+fn camera_frustum(eye: Vec3) -> Frustum {
+    let clip_from_view = Mat4::perspective_infinite_reverse_rh(70f32.to_radians(), 16.0 / 9.0, 0.1);
+    let view_from_world = Mat4::look_to_rh(eye, Vec3::NEG_Z, Vec3::Y);
+    Frustum::from_view_projection(clip_from_view * view_from_world)
+}
+
+#[test]
+fn box_fully_inside_is_kept() {
+    let frustum = camera_frustum(Vec3::ZERO);
+    assert!(frustum.intersects_aabb(Vec3::new(-1.0, -1.0, -11.0), Vec3::new(1.0, 1.0, -9.0)));
+
+    // Infinite far plane: distance alone never culls
+    let far = Vec3::new(0.0, 0.0, -10_000.0);
+    assert!(frustum.intersects_aabb(far - 1.0, far + 1.0));
+
+    // The chunk the camera is in is always drawn
+    let frustum = camera_frustum(Vec3::new(40.0, 70.0, 40.0));
+    let bounds = ChunkBounds::chunk(IVec3::new(32, 64, 32));
+    assert!(frustum.intersects_aabb(bounds.min, bounds.max));
+}
+
+#[test]
+fn box_behind_the_near_plane_is_culled() {
+    let frustum = camera_frustum(Vec3::ZERO);
+    assert!(!frustum.intersects_aabb(Vec3::new(-1.0, -1.0, 9.0), Vec3::new(1.0, 1.0, 11.0)));
+    // Right in front of the camera but closer than the near plane
+    assert!(!frustum.intersects_aabb(Vec3::new(-1.0, -1.0, -0.05), Vec3::new(1.0, 1.0, 1.0)));
+
+    let frustum = camera_frustum(Vec3::new(16.0, 80.0, 100.0));
+    let behind = ChunkBounds::chunk(IVec3::new(0, 64, 128));
+    assert!(!frustum.intersects_aabb(behind.min, behind.max));
+}
+
+#[test]
+fn box_outside_a_side_plane_is_culled() {
+    let frustum = camera_frustum(Vec3::ZERO);
+    // Far off to the left, beyond the 70° vertical, ~124° horizontal view
+    assert!(!frustum.intersects_aabb(Vec3::new(-100.0, -1.0, -11.0), Vec3::new(-60.0, 1.0, -9.0)));
+    assert!(!frustum.intersects_aabb(Vec3::new(-1.0, 20.0, -11.0), Vec3::new(1.0, 30.0, -9.0)));
+}
+
+#[test]
+fn box_straddling_a_plane_is_kept() {
+    let frustum = camera_frustum(Vec3::ZERO);
+    // Across the near plane, reaching from behind the camera to in front
+    assert!(frustum.intersects_aabb(Vec3::new(-1.0, -1.0, -5.0), Vec3::new(1.0, 1.0, 5.0)));
+    // Across the left plane, mostly outside
+    assert!(frustum.intersects_aabb(Vec3::new(-100.0, -1.0, -11.0), Vec3::new(-5.0, 1.0, -9.0)));
+}
+
+#[test]
+fn finite_far_plane_culls_distant_boxes() {
+    let clip_from_view = Mat4::perspective_rh(70f32.to_radians(), 1.0, 0.1, 100.0);
+    let frustum = Frustum::from_view_projection(clip_from_view);
+    assert!(frustum.intersects_aabb(Vec3::new(-1.0, -1.0, -99.0), Vec3::new(1.0, 1.0, -90.0)));
+    assert!(frustum.intersects_aabb(Vec3::new(-1.0, -1.0, -110.0), Vec3::new(1.0, 1.0, -95.0)));
+    assert!(!frustum.intersects_aabb(Vec3::new(-1.0, -1.0, -120.0), Vec3::new(1.0, 1.0, -101.0)));
+}
+
+#[test]
+fn group_bounds_cover_every_chunk() {
+    assert_eq!(ChunkBounds::chunks([]), None);
+    let bounds = ChunkBounds::chunks([
+        IVec3::new(0, 0, 32),
+        IVec3::new(-32, 64, 0),
+        IVec3::new(32, -32, 0),
+    ])
+    .unwrap();
+    assert_eq!(bounds.min, Vec3::new(-32.0, -32.0, 0.0));
+    assert_eq!(bounds.max, Vec3::new(64.0, 96.0, 64.0));
+}
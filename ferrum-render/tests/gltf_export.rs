@@ -0,0 +1,203 @@
+use bevy::math::IVec3;
+use ferrum_meshing_cpu::{uniform_chunk, ChunkMesher, CpuMesher, Face};
+use ferrum_render::{ChunkGroups, GltfExport, TextureAtlas};
+use std::path::PathBuf;
+
+fn temp_glb(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("ferrum_{}_{}.glb", name, std::process::id()))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+/// Split a GLB file into its JSON document and binary chunk.
+fn parse_glb(bytes: &[u8]) -> (serde_json::Value, Vec<u8>) {
+    assert_eq!(&bytes[0..4], b"glTF", "GLB magic mismatch");
+    assert_eq!(read_u32(bytes, 4), 2, "GLB version should be 2");
+    assert_eq!(
+        read_u32(bytes, 8) as usize,
+        bytes.len(),
+        "GLB length mismatch"
+    );
+
+    let json_len = read_u32(bytes, 12) as usize;
+    assert_eq!(&bytes[16..20], b"JSON");
+    let json = serde_json::from_slice(&bytes[20..20 + json_len]).expect("JSON chunk should parse");
+
+    let bin_start = 20 + json_len;
+    let bin = if bin_start < bytes.len() {
+        let bin_len = read_u32(bytes, bin_start) as usize;
+        assert_eq!(&bytes[bin_start + 4..bin_start + 8], b"BIN\0");
+        bytes[bin_start + 8..bin_start + 8 + bin_len].to_vec()
+    } else {
+        Vec::new()
+    };
+
+    (json, bin)
+}
+
+#[test]
+fn test_export_solid_chunk_counts_match() {
+    let chunk_mesh = CpuMesher::new().mesh_chunk(&uniform_chunk(1));
+    let atlas = TextureAtlas::new(16);
+    let path = temp_glb("solid_chunk");
+
+    chunk_mesh.export_gltf(&atlas, &path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let (json, bin) = parse_glb(&bytes);
+    let accessors = json["accessors"].as_array().unwrap();
+    let primitive = &json["meshes"][0]["primitives"][0];
+    let attributes = &primitive["attributes"];
+
+    let expected_vertices = chunk_mesh.quad_count() * 4;
+    let expected_indices = chunk_mesh.quad_count() * 6;
+
+    for name in ["POSITION", "NORMAL", "TEXCOORD_0", "COLOR_0"] {
+        let accessor = &accessors[attributes[name].as_u64().unwrap() as usize];
+        assert_eq!(
+            accessor["count"].as_u64().unwrap() as usize,
+            expected_vertices,
+            "{} count mismatch",
+            name
+        );
+    }
+
+    let index_accessor = &accessors[primitive["indices"].as_u64().unwrap() as usize];
+    assert_eq!(
+        index_accessor["count"].as_u64().unwrap() as usize,
+        expected_indices
+    );
+
+    // 3+3+2+4 floats per vertex plus one u32 per index
+    let expected_bytes = expected_vertices * 12 * 4 + expected_indices * 4;
+    assert_eq!(
+        json["buffers"][0]["byteLength"].as_u64().unwrap() as usize,
+        expected_bytes
+    );
+    assert_eq!(bin.len(), expected_bytes);
+
+    let max = accessors[attributes["POSITION"].as_u64().unwrap() as usize]["max"]
+        .as_array()
+        .unwrap();
+    assert!(max.iter().all(|v| v.as_f64().unwrap() == 32.0));
+}
+
+#[test]
+fn test_export_with_texture_variation_counts_match() {
+    let chunk_mesh = CpuMesher::new().mesh_chunk(&uniform_chunk(1));
+    let mut atlas = TextureAtlas::new(16);
+    atlas.add_variants(1, Face::Up, &[(1, 0)]);
+    let path = temp_glb("varied_chunk");
+
+    chunk_mesh.export_gltf(&atlas, &path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let (json, _) = parse_glb(&bytes);
+    let accessors = json["accessors"].as_array().unwrap();
+    let views = json["bufferViews"].as_array().unwrap();
+    let attributes = &json["meshes"][0]["primitives"][0]["attributes"];
+
+    // The top is drawn block by block, so it has more vertices than quads
+    let position_count = accessors[attributes["POSITION"].as_u64().unwrap() as usize]["count"]
+        .as_u64()
+        .unwrap();
+    assert!(position_count as usize > chunk_mesh.quad_count() * 4);
+
+    for (name, floats) in [
+        ("POSITION", 3),
+        ("NORMAL", 3),
+        ("TEXCOORD_0", 2),
+        ("COLOR_0", 4),
+    ] {
+        let accessor = &accessors[attributes[name].as_u64().unwrap() as usize];
+        let count = accessor["count"].as_u64().unwrap();
+        assert_eq!(count, position_count, "{} count mismatch", name);
+        let view = &views[accessor["bufferView"].as_u64().unwrap() as usize];
+        assert_eq!(
+            view["byteLength"].as_u64().unwrap(),
+            count * floats * 4,
+            "{} buffer view length mismatch",
+            name
+        );
+    }
+}
+
+#[test]
+fn test_export_chunks_fill_the_file() {
+    let chunk_mesh = CpuMesher::new().mesh_chunk(&uniform_chunk(1));
+    let atlas = TextureAtlas::new(16);
+    let path = temp_glb("chunk_lengths");
+
+    chunk_mesh.export_gltf(&atlas, &path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    // 12 byte header, then a JSON and a BIN chunk with 8 byte headers, each
+    // padded to 4 bytes and together making up the rest of the file
+    assert_eq!(read_u32(&bytes, 0), 0x4654_6C67);
+    assert_eq!(read_u32(&bytes, 8) as usize, bytes.len());
+    let json_len = read_u32(&bytes, 12) as usize;
+    assert_eq!(read_u32(&bytes, 16), 0x4E4F_534A);
+    assert_eq!(json_len % 4, 0);
+
+    let bin_start = 20 + json_len;
+    let bin_len = read_u32(&bytes, bin_start) as usize;
+    assert_eq!(read_u32(&bytes, bin_start + 4), 0x004E_4942);
+    assert_eq!(bin_len % 4, 0);
+    assert_eq!(bin_start + 8 + bin_len, bytes.len());
+
+    let (json, bin) = parse_glb(&bytes);
+    assert_eq!(bin.len(), bin_len);
+    let buffer_len = json["buffers"][0]["byteLength"].as_u64().unwrap() as usize;
+    assert!(buffer_len <= bin_len && bin_len - buffer_len < 4);
+}
+
+#[test]
+fn test_export_chunk_groups_places_chunks_in_the_world() {
+    let chunk_mesh = CpuMesher::new().mesh_chunk(&uniform_chunk(1));
+    let atlas = TextureAtlas::new(16);
+    let mut groups = ChunkGroups::new(2);
+    groups.insert(IVec3::ZERO, IVec3::ZERO, chunk_mesh.clone());
+    groups.insert(
+        IVec3::new(3, 0, 0),
+        IVec3::new(96, 0, 0),
+        chunk_mesh.clone(),
+    );
+    let path = temp_glb("chunk_groups");
+
+    groups.export_gltf(&atlas, &path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let (json, _) = parse_glb(&bytes);
+    let accessors = json["accessors"].as_array().unwrap();
+    let position = &accessors[json["meshes"][0]["primitives"][0]["attributes"]["POSITION"]
+        .as_u64()
+        .unwrap() as usize];
+    assert_eq!(
+        position["count"].as_u64().unwrap() as usize,
+        chunk_mesh.quad_count() * 8
+    );
+    assert_eq!(position["min"][0].as_f64().unwrap(), 0.0);
+    assert_eq!(position["max"][0].as_f64().unwrap(), 128.0);
+}
+
+#[test]
+fn test_export_empty_mesh_is_valid() {
+    let chunk_mesh = CpuMesher::new().mesh_chunk(&uniform_chunk(0));
+    let atlas = TextureAtlas::new(16);
+    let path = temp_glb("empty_chunk");
+
+    chunk_mesh.export_gltf(&atlas, &path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let (json, bin) = parse_glb(&bytes);
+    assert_eq!(json["asset"]["version"], "2.0");
+    assert!(json.get("meshes").is_none());
+    assert!(bin.is_empty());
+}
@@ -0,0 +1,60 @@
+use bevy::math::{IVec3, Mat4, Vec3};
+use ferrum_render::{ChunkBounds, Frustum, GpuFrustumCuller};
+
+/// Chunks in a 16 x 16 grid, two sections tall, around the origin.
+fn chunk_grid() -> Vec<ChunkBounds> {
+    let mut bounds = Vec::new();
+    for x in -8..8 {
+        for z in -8..8 {
+            for y in 0..2 {
+                bounds.push(ChunkBounds::chunk(IVec3::new(x, y, z) * 32));
+            }
+        }
+    }
+    bounds
+}
+
+/// The chunks the CPU frustum keeps, within `max_distance` of `eye`.
+fn cpu_visible(
+    bounds: &[ChunkBounds],
+    clip_from_world: Mat4,
+    eye: Vec3,
+    max_distance: f32,
+) -> Vec<usize> {
+    let frustum = Frustum::from_view_projection(clip_from_world);
+    bounds
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| frustum.intersects_aabb(b.min, b.max))
+        .filter(|(_, b)| eye.distance(eye.clamp(b.min, b.max)) <= max_distance)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+#[test]
+fn gpu_culling_matches_the_cpu_frustum() {
+    let Some(mut culler) = GpuFrustumCuller::new() else {
+        eprintln!("no GPU adapter, skipping");
+        return;
+    };
+    let bounds = chunk_grid();
+
+    let views = [
+        (Vec3::new(5.0, 40.0, 7.0), Vec3::new(1.0, -0.2, -0.5)),
+        (Vec3::new(-100.0, 70.0, 30.0), Vec3::new(0.3, -0.8, 0.1)),
+        (Vec3::new(0.0, 20.0, 0.0), Vec3::NEG_Z),
+    ];
+    for (eye, direction) in views {
+        let clip_from_view =
+            Mat4::perspective_infinite_reverse_rh(70f32.to_radians(), 16.0 / 9.0, 0.1);
+        let view_from_world = Mat4::look_to_rh(eye, direction.normalize(), Vec3::Y);
+        let clip_from_world = clip_from_view * view_from_world;
+
+        for max_distance in [f32::INFINITY, 96.0] {
+            let expected = cpu_visible(&bounds, clip_from_world, eye, max_distance);
+            let visible = culler.visible_chunks(&bounds, clip_from_world, eye, max_distance);
+            assert_eq!(visible, expected, "{eye} looking {direction}");
+            assert!(!visible.is_empty() && visible.len() < bounds.len());
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use ferrum_inventory::items;
+use ferrum_render::{
+    item_block_type, paint_block_icon, paint_sprite_icon, ItemIconKind, ItemIcons, TextureAtlas,
+    ITEM_ICON_SIZE,
+};
+
+/// A 16x16-tile atlas of 16px tiles, each a flat color made from its
+/// position.
+fn test_atlas() -> Image {
+    let size = 256;
+    let mut data = vec![0u8; size * size * 4];
+    for y in 0..size {
+        for x in 0..size {
+            let index = (y * size + x) * 4;
+            data[index..index + 4].copy_from_slice(&[
+                (x / 16 * 16) as u8,
+                (y / 16 * 16) as u8,
+                200,
+                255,
+            ]);
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: size as u32,
+            height: size as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::MAIN_WORLD,
+    )
+}
+
+fn pixel(image: &Image, x: u32, y: u32) -> [u8; 4] {
+    let index = ((y * image.width() + x) * 4) as usize;
+    let data = image.data.as_ref().unwrap();
+    [
+        data[index],
+        data[index + 1],
+        data[index + 2],
+        data[index + 3],
+    ]
+}
+
+#[test]
+fn test_blocks_get_cubes_and_items_get_sprites() {
+    let stone = items::STONE as u32;
+    assert_eq!(ItemIconKind::of(stone), ItemIconKind::Block(1));
+    assert_eq!(
+        ItemIconKind::of(items::GRASS_BLOCK as u32),
+        ItemIconKind::Block(3)
+    );
+    assert_eq!(
+        ItemIconKind::of(items::DEEPSLATE as u32),
+        ItemIconKind::Block(17)
+    );
+    assert_eq!(
+        ItemIconKind::of(items::DIAMOND as u32),
+        ItemIconKind::Sprite
+    );
+    assert_eq!(
+        ItemIconKind::of(items::IRON_PICKAXE as u32),
+        ItemIconKind::Sprite
+    );
+    assert_eq!(item_block_type(u32::MAX), None);
+}
+
+#[test]
+fn test_icons_are_painted_once_per_item() {
+    let mut images = Assets::<Image>::default();
+    let mut icons = ItemIcons::default();
+    let mut painted = 0;
+
+    let pickaxe = icons.get_or_insert_with(257, &mut images, |_| {
+        painted += 1;
+        paint_sprite_icon(257)
+    });
+    let again = icons.get_or_insert_with(257, &mut images, |_| {
+        painted += 1;
+        paint_sprite_icon(257)
+    });
+    assert_eq!(pickaxe, again);
+    assert_eq!(painted, 1);
+
+    let diamond = icons.get_or_insert_with(264, &mut images, |_| {
+        painted += 1;
+        paint_sprite_icon(264)
+    });
+    assert_ne!(pickaxe, diamond);
+    assert_eq!(painted, 2);
+    assert_eq!(icons.len(), 2);
+    assert_eq!(images.len(), 2);
+    assert_eq!(icons.get(264), Some(&diamond));
+}
+
+#[test]
+fn test_block_icon_uses_the_block_tiles() {
+    let atlas = TextureAtlas::new(16);
+    let atlas_image = test_atlas();
+    let icon = paint_block_icon(1, &atlas, &atlas_image);
+    assert_eq!(icon.width(), ITEM_ICON_SIZE);
+    assert_eq!(icon.height(), ITEM_ICON_SIZE);
+
+    // Stone is tile (1, 0) on every face: the top is drawn as is, the
+    // sides darker
+    let (tile_x, tile_y) = atlas.tile(1, ferrum_meshing_cpu::Face::Up);
+    let expected = [(tile_x * 16) as u8, (tile_y * 16) as u8, 200, 255];
+    assert_eq!(pixel(&icon, 16, 6), expected);
+    let side = pixel(&icon, 8, 24);
+    assert_eq!(side[3], 255);
+    assert!(side[2] < 200);
+
+    // Corners stay transparent
+    assert_eq!(pixel(&icon, 0, 0)[3], 0);
+    assert_eq!(pixel(&icon, 31, 31)[3], 0);
+}
+
+#[test]
+fn test_sprite_icon_is_drawn_in_the_middle() {
+    let icon = paint_sprite_icon(items::DIAMOND as u32);
+    assert_eq!(icon.width(), ITEM_ICON_SIZE);
+    assert_eq!(pixel(&icon, 16, 16)[3], 255);
+    assert_eq!(pixel(&icon, 0, 0)[3], 0);
+
+    let tool = paint_sprite_icon(items::IRON_PICKAXE as u32);
+    let data = tool.data.as_ref().unwrap();
+    assert!(data.chunks(4).any(|texel| texel[3] == 255));
+}
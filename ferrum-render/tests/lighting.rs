@@ -1,4 +1,7 @@
+use ferrum_core::{properties, BlockId, BlockState, GLOWSTONE, LEAVES, WATER};
+use ferrum_meshing_cpu::CHUNK_SIZE_CB;
 use ferrum_render::lighting::{LightingEngine, CHUNK_SIZE};
+use ferrum_world::Chunk;
 
 #[test]
 fn test_lighting_engine_creation() {
@@ -426,3 +429,214 @@ fn test_ao_integration_with_lighting() {
     // AO should darken the corner even if lit
     assert!(ao < 1.0, "Corner should be darkened by occlusion");
 }
+
+fn manhattan(a: (usize, usize, usize), b: (usize, usize, usize)) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1) + a.2.abs_diff(b.2)
+}
+
+#[test]
+fn test_torch_in_open_air_lights_a_diamond() {
+    let chunk = Chunk::new();
+    let mut lighting = LightingEngine::new();
+    lighting.propagate_block_light_from(&chunk, &[(16, 16, 16, 14)]);
+
+    for x in 0..CHUNK_SIZE {
+        for y in 0..CHUNK_SIZE {
+            for z in 0..CHUNK_SIZE {
+                let distance = manhattan((x, y, z), (16, 16, 16));
+                let expected = 14usize.saturating_sub(distance) as u8;
+                assert_eq!(lighting.light_at(x, y, z), expected, "at ({x}, {y}, {z})");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_overlapping_sources_keep_the_brightest() {
+    let chunk = Chunk::new();
+    let mut lighting = LightingEngine::new();
+    let sources = [(10, 16, 16, 14), (15, 16, 16, 10), (10, 16, 16, 7)];
+    lighting.propagate_block_light_from(&chunk, &sources);
+
+    for x in 0..CHUNK_SIZE {
+        for y in 0..CHUNK_SIZE {
+            for z in 0..CHUNK_SIZE {
+                let expected = sources
+                    .iter()
+                    .map(|&(sx, sy, sz, level)| {
+                        let source = (sx as usize, sy as usize, sz as usize);
+                        let distance = manhattan((x, y, z), source);
+                        (level as usize).saturating_sub(distance) as u8
+                    })
+                    .max()
+                    .unwrap();
+                assert_eq!(lighting.light_at(x, y, z), expected, "at ({x}, {y}, {z})");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_solid_blocks_stop_block_light() {
+    let mut chunk = Chunk::new();
+    // A solid wall across the whole chunk at x = 18
+    for y in 0..CHUNK_SIZE {
+        for z in 0..CHUNK_SIZE {
+            chunk.set_block(18, y, z, BlockId::new(1));
+        }
+    }
+    let mut lighting = LightingEngine::new();
+    lighting.propagate_block_light_from(&chunk, &[(16, 16, 16, 14)]);
+
+    assert_eq!(lighting.light_at(17, 16, 16), 13);
+    assert_eq!(lighting.light_at(18, 16, 16), 0);
+    for x in 19..CHUNK_SIZE {
+        for y in 0..CHUNK_SIZE {
+            for z in 0..CHUNK_SIZE {
+                assert_eq!(lighting.light_at(x, y, z), 0, "at ({x}, {y}, {z})");
+            }
+        }
+    }
+    // Water lets light through
+    chunk.set_block(18, 16, 16, WATER);
+    lighting.propagate_block_light_from(&chunk, &[(16, 16, 16, 14)]);
+    assert_eq!(lighting.light_at(19, 16, 16), 11);
+}
+
+#[test]
+fn test_glowstone_lights_its_neighbours() {
+    let mut chunk = Chunk::new();
+    chunk.set_block(4, 4, 4, GLOWSTONE);
+    let sources: Vec<(u8, u8, u8, u8)> = chunk
+        .light_sources()
+        .iter()
+        .map(|&(x, y, z)| (x, y, z, properties(GLOWSTONE).light_emission))
+        .collect();
+    let mut lighting = LightingEngine::new();
+    lighting.propagate_block_light_from(&chunk, &sources);
+
+    assert_eq!(lighting.light_at(4, 4, 4), 15);
+    assert_eq!(lighting.light_at(5, 4, 4), 14);
+    assert_eq!(lighting.light_at(4, 4, 1), 12);
+}
+
+#[test]
+fn test_block_light_stays_inside_the_chunk() {
+    let chunk = Chunk::new();
+    let mut lighting = LightingEngine::new();
+    // Corner sources, one too bright, and two outside the chunk
+    let sources = [
+        (0, 0, 0, 20),
+        (31, 31, 31, 15),
+        (32, 0, 0, 15),
+        (0, 255, 0, 15),
+    ];
+    lighting.propagate_block_light_from(&chunk, &sources);
+
+    assert_eq!(lighting.light_at(0, 0, 0), 15);
+    assert_eq!(lighting.light_at(1, 0, 0), 14);
+    assert_eq!(lighting.light_at(31, 31, 31), 15);
+    assert_eq!(lighting.light_at(31, 31, 30), 14);
+    assert_eq!(lighting.light_at(31, 0, 0), 0);
+    assert_eq!(lighting.light_at(32, 0, 0), 0);
+
+    // Propagating again replaces the previous light
+    lighting.propagate_block_light_from(&chunk, &[]);
+    assert_eq!(lighting.light_at(0, 0, 0), 0);
+}
+
+fn voxel_index(x: usize, y: usize, z: usize) -> usize {
+    (z * CHUNK_SIZE + y) * CHUNK_SIZE + x
+}
+
+/// Stone from y = 0 up to and including `surface`, air above.
+fn flat_terrain(surface: usize) -> Box<[u32; CHUNK_SIZE_CB]> {
+    let mut voxels = Box::new([0u32; CHUNK_SIZE_CB]);
+    for z in 0..CHUNK_SIZE {
+        for y in 0..=surface {
+            for x in 0..CHUNK_SIZE {
+                voxels[voxel_index(x, y, z)] = 1;
+            }
+        }
+    }
+    voxels
+}
+
+#[test]
+fn test_skylight_lights_flat_terrain_down_to_the_surface() {
+    let voxels = flat_terrain(10);
+    let mut engine = LightingEngine::new();
+    let light = engine.compute_skylight(&voxels);
+
+    for y in 0..CHUNK_SIZE {
+        let expected = if y > 10 { 15 } else { 0 };
+        assert_eq!(light[voxel_index(7, y, 20)], expected, "y = {y}");
+        assert_eq!(engine.get_sky_light(7, y, 20), expected, "y = {y}");
+    }
+}
+
+#[test]
+fn test_skylight_enclosed_cells_are_dark() {
+    let mut voxels = flat_terrain(20);
+    // A sealed cave under the surface
+    for x in 10..15 {
+        for y in 5..8 {
+            voxels[voxel_index(x, y, 10)] = 0;
+        }
+    }
+    let mut engine = LightingEngine::new();
+    let light = engine.compute_skylight(&voxels);
+    for x in 10..15 {
+        for y in 5..8 {
+            assert_eq!(light[voxel_index(x, y, 10)], 0);
+        }
+    }
+}
+
+#[test]
+fn test_skylight_dimmed_by_water_and_leaves() {
+    let mut voxels = flat_terrain(10);
+    // Three blocks of water, flowing at the top, on top of the ground in one
+    // column, and one leaf block floating over another
+    for y in 11..14 {
+        voxels[voxel_index(3, y, 3)] = BlockState::new(WATER, y as u16 - 11).to_bits();
+    }
+    voxels[voxel_index(20, 25, 20)] = BlockState::from(LEAVES).to_bits();
+
+    let mut engine = LightingEngine::new();
+    engine.set_skylight_smoothing(false);
+    let light = engine.compute_skylight(&voxels);
+    assert_eq!(light[voxel_index(3, 13, 3)], 14);
+    assert_eq!(light[voxel_index(3, 11, 3)], 12);
+    assert_eq!(light[voxel_index(3, 10, 3)], 0);
+    assert_eq!(light[voxel_index(20, 25, 20)], 14);
+    assert_eq!(light[voxel_index(20, 11, 20)], 14);
+
+    engine.set_skylight_attenuation(4);
+    let light = engine.compute_skylight(&voxels);
+    assert_eq!(light[voxel_index(3, 11, 3)], 3);
+    assert_eq!(light[voxel_index(20, 24, 20)], 11);
+}
+
+#[test]
+fn test_skylight_smoothing_lights_under_an_overhang() {
+    let mut voxels = flat_terrain(10);
+    // A roof over x = 0..8 at y = 15, open to the sky beyond
+    for z in 0..CHUNK_SIZE {
+        for x in 0..8 {
+            voxels[voxel_index(x, 15, z)] = 1;
+        }
+    }
+
+    let mut engine = LightingEngine::new();
+    engine.set_skylight_smoothing(false);
+    let light = engine.compute_skylight(&voxels);
+    assert_eq!(light[voxel_index(7, 12, 5)], 0);
+
+    engine.set_skylight_smoothing(true);
+    let light = engine.compute_skylight(&voxels);
+    assert_eq!(light[voxel_index(8, 12, 5)], 15);
+    assert_eq!(light[voxel_index(7, 12, 5)], 14, "one block in from the edge");
+    assert_eq!(light[voxel_index(6, 12, 5)], 0, "a single pass only");
+    assert_eq!(light[voxel_index(7, 10, 5)], 0, "solid blocks stay dark");
+}
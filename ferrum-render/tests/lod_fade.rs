@@ -0,0 +1,85 @@
+use ferrum_render::{LodConfig, LodFade, LodFadeLayer, LodLevel, LodTransition};
+
+fn transition(blend: f32) -> LodTransition {
+    LodTransition {
+        primary: LodLevel::Full,
+        next: Some(LodLevel::Reduced),
+        blend,
+    }
+}
+
+#[test]
+fn blend_zero_shows_only_primary() {
+    let (primary, next) = LodFade::for_transition(&transition(0.0));
+    let next = next.unwrap();
+
+    assert_eq!(primary.layer.coverage(primary.blend), 1.0);
+    assert_eq!(next.layer.coverage(next.blend), 0.0);
+    assert!(primary.is_visible());
+    assert!(!next.is_visible());
+}
+
+#[test]
+fn blend_one_shows_only_next() {
+    let (primary, next) = LodFade::for_transition(&transition(1.0));
+    let next = next.unwrap();
+
+    assert_eq!(primary.layer.coverage(primary.blend), 0.0);
+    assert_eq!(next.layer.coverage(next.blend), 1.0);
+    assert!(!primary.is_visible());
+    assert!(next.is_visible());
+}
+
+#[test]
+fn partial_blend_shows_both_without_overlap() {
+    for blend in [0.1, 0.25, 0.5, 0.75, 0.9] {
+        let primary = LodFadeLayer::Primary.coverage(blend);
+        let next = LodFadeLayer::Next.coverage(blend);
+        assert!(primary > 0.0 && next > 0.0, "both contribute at {blend}");
+        assert_eq!(primary + next, 1.0);
+
+        for y in 0..8 {
+            for x in 0..8 {
+                assert_ne!(
+                    LodFadeLayer::Primary.is_pixel_visible(blend, x, y),
+                    LodFadeLayer::Next.is_pixel_visible(blend, x, y),
+                    "pixel ({x}, {y}) must come from exactly one mesh"
+                );
+            }
+        }
+    }
+
+    assert!((LodFadeLayer::Next.coverage(0.5) - 0.5).abs() < f32::EPSILON);
+}
+
+#[test]
+fn next_coverage_grows_with_blend() {
+    let mut previous = 0.0;
+    for step in 0..=16 {
+        let coverage = LodFadeLayer::Next.coverage(step as f32 / 16.0);
+        assert!(coverage >= previous);
+        previous = coverage;
+    }
+}
+
+#[test]
+fn transition_from_config_feeds_fade() {
+    let config = LodConfig::default();
+    let far = config.select_lod_with_blend(config.full_max - 0.4).unwrap();
+    assert!(far.is_blending());
+
+    let (primary, next) = LodFade::for_transition(&far);
+    assert!(primary.is_visible());
+    assert!(next.unwrap().is_visible());
+}
+
+#[test]
+fn last_level_has_no_next_mesh() {
+    let transition = LodTransition {
+        primary: LodLevel::Minimal,
+        next: None,
+        blend: 0.5,
+    };
+    let (_, next) = LodFade::for_transition(&transition);
+    assert!(next.is_none());
+}
@@ -0,0 +1,174 @@
+use bevy::math::{IVec3, Vec3};
+use ferrum_core::BlockId;
+use ferrum_meshing_cpu::{
+    ChunkMesh, ChunkMesher, CpuMesher, CHUNK_SIZE, CHUNK_SIZE_CB, CHUNK_SIZE_SQ,
+};
+use ferrum_render::lod::LodMesher;
+use ferrum_render::{ChunkLods, LodLevel, LodMeshJobs};
+use ferrum_world::{Chunk, ChunkPos, World};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Solid ground `depth` blocks deep.
+fn ground(depth: usize) -> Arc<[u32; CHUNK_SIZE_CB]> {
+    let mut voxels = [0u32; CHUNK_SIZE_CB];
+    for z in 0..CHUNK_SIZE {
+        for y in 0..depth {
+            for x in 0..CHUNK_SIZE {
+                voxels[z * CHUNK_SIZE_SQ + y * CHUNK_SIZE + x] = 1;
+            }
+        }
+    }
+    Arc::new(voxels)
+}
+
+fn wait_for_all<K: Copy + Eq + Hash>(jobs: &mut LodMeshJobs<K>) -> Vec<(K, LodLevel, ChunkMesh)> {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let mut completed = Vec::new();
+    while jobs.pending_count() > 0 {
+        assert!(Instant::now() < deadline, "LOD jobs did not finish");
+        completed.extend(jobs.drain_completed());
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    completed
+}
+
+#[test]
+fn test_every_submitted_job_comes_back() {
+    const LEVELS: [LodLevel; 5] = [
+        LodLevel::Full,
+        LodLevel::Reduced,
+        LodLevel::Low,
+        LodLevel::Minimal,
+        LodLevel::Far,
+    ];
+    let voxels = ground(8);
+    let mut jobs = LodMeshJobs::new();
+    for x in 0..24 {
+        jobs.submit(
+            ChunkPos { x, z: -x },
+            voxels.clone(),
+            LEVELS[x as usize % LEVELS.len()],
+        );
+    }
+    assert_eq!(jobs.pending_count(), 24);
+
+    let completed = wait_for_all(&mut jobs);
+    assert_eq!(completed.len(), 24);
+    let by_pos: HashMap<_, _> = completed
+        .into_iter()
+        .map(|(pos, lod, mesh)| (pos, (lod, mesh)))
+        .collect();
+    for x in 0..24 {
+        let (lod, mesh) = &by_pos[&ChunkPos { x, z: -x }];
+        assert_eq!(*lod, LEVELS[x as usize % LEVELS.len()]);
+        let expected = LodMesher::mesh_chunk_lod(&voxels, *lod)
+            .unwrap_or_else(|| CpuMesher::new().mesh_chunk(&voxels));
+        assert_eq!(mesh.quads.len(), expected.quads.len());
+    }
+    assert!(jobs.drain_completed().is_empty());
+}
+
+#[test]
+fn test_resubmitting_keeps_only_the_latest_lod() {
+    let pos = ChunkPos { x: 3, z: 4 };
+    let mut jobs = LodMeshJobs::new();
+    jobs.submit(pos, ground(8), LodLevel::Reduced);
+    jobs.submit(pos, ground(8), LodLevel::Minimal);
+    assert_eq!(jobs.pending_count(), 1);
+    assert_eq!(jobs.pending(pos), Some(LodLevel::Minimal));
+
+    let completed = wait_for_all(&mut jobs);
+    assert_eq!(completed.len(), 1);
+    assert_eq!((completed[0].0, completed[0].1), (pos, LodLevel::Minimal));
+
+    jobs.submit(pos, ground(8), LodLevel::Low);
+    jobs.cancel(pos);
+    assert_eq!(jobs.pending(pos), None);
+    assert!(wait_for_all(&mut jobs).is_empty());
+}
+
+#[test]
+fn test_chunk_lods_mesh_in_the_background() {
+    let mut chunk = Chunk::new();
+    for x in 0..32 {
+        for z in 0..32 {
+            for y in 0..8 {
+                chunk.set_block(x, y, z, BlockId::new(1));
+            }
+        }
+    }
+    let mut world = World::new();
+    world.set_chunk(ChunkPos { x: 0, z: 0 }, chunk);
+    let camera = Vec3::new(16.0, 70.0, 16.0);
+
+    let mut lods = ChunkLods::default();
+    let mut jobs = LodMeshJobs::new();
+    assert!(lods.update_async(&world, camera, &mut jobs).is_empty());
+    assert_eq!(lods.lod(ChunkPos { x: 0, z: 0 }), Some(LodLevel::Full));
+    assert_eq!(lods.stats().total_quads(), 0, "not meshed yet");
+
+    for (pos, lod, mesh) in wait_for_all(&mut jobs) {
+        lods.record_quads(pos, lod, mesh.quads.len() as u32);
+    }
+    assert!(lods.stats().total_quads() > 0);
+
+    // Unloading cancels and removes
+    world.remove_chunk(ChunkPos { x: 0, z: 0 });
+    let removed = lods.update_async(&world, camera, &mut jobs);
+    assert_eq!(removed, [ChunkPos { x: 0, z: 0 }]);
+    assert_eq!(jobs.pending_count(), 0);
+}
+
+#[test]
+fn test_server_columns_mesh_each_section_in_the_background() {
+    let near = ChunkPos { x: 0, z: 0 };
+    // 20 sixteen wide columns away: past the Full boundary
+    let far = ChunkPos { x: 20, z: 0 };
+    let camera = Vec3::new(8.0, 70.0, 8.0);
+    let sections = |_| vec![(0, Box::new(*ground(8))), (2, Box::new(*ground(8)))];
+
+    let mut lods = ChunkLods::default();
+    let mut jobs = LodMeshJobs::new();
+    let removed = lods.update_columns_async([near, far], 16.0, camera, sections, &mut jobs);
+    assert!(removed.is_empty());
+    assert_eq!(lods.lod(near), Some(LodLevel::Full));
+    assert_eq!(lods.lod(far), Some(LodLevel::Reduced));
+
+    let mut completed = wait_for_all(&mut jobs);
+    completed.sort_by_key(|(section, _, _)| (section.x, section.y));
+    let keys: Vec<_> = completed
+        .iter()
+        .map(|&(section, lod, _)| (section, lod))
+        .collect();
+    assert_eq!(
+        keys,
+        [
+            (IVec3::new(0, 0, 0), LodLevel::Full),
+            (IVec3::new(0, 2, 0), LodLevel::Full),
+            (IVec3::new(20, 0, 0), LodLevel::Reduced),
+            (IVec3::new(20, 2, 0), LodLevel::Reduced),
+        ]
+    );
+    let mut quads = 0;
+    for (section, lod, mesh) in completed {
+        quads += mesh.quads.len() as u32;
+        lods.record_quads(
+            ChunkPos {
+                x: section.x,
+                z: section.z,
+            },
+            lod,
+            mesh.quads.len() as u32,
+        );
+    }
+    assert_eq!(lods.stats().total_quads(), quads, "sections add up");
+
+    // A column no longer received cancels its sections and is removed
+    jobs.submit(IVec3::new(20, 1, 0), ground(8), LodLevel::Reduced);
+    let removed = lods.update_columns_async([near], 16.0, camera, sections, &mut jobs);
+    assert_eq!(removed, [far]);
+    assert_eq!(jobs.pending_count(), 0);
+}
@@ -0,0 +1,120 @@
+use bevy::mesh::VertexAttributeValues;
+use bevy::prelude::*;
+use ferrum_meshing_cpu::{ChunkMesh, Face, MeshQuad};
+use ferrum_render::{
+    upload_chunk_meshes, BlockRenderer, MeshUploadBudget, PendingChunkMesh, TextureAtlas,
+};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn quad(x: u8, face: Face) -> MeshQuad {
+    MeshQuad {
+        x,
+        y: 0,
+        z: 0,
+        width: 2,
+        height: 1,
+        face,
+        block_type: 1,
+    }
+}
+
+fn chunk_mesh() -> ChunkMesh {
+    ChunkMesh {
+        quads: vec![quad(0, Face::Up), quad(4, Face::Front)],
+    }
+}
+
+#[test]
+fn test_buffers_match_mesh() {
+    let atlas = TextureAtlas::new(16);
+    let buffers = BlockRenderer::build_buffers(&chunk_mesh(), &atlas, IVec3::ZERO);
+
+    assert_eq!(buffers.vertex_count(), 8);
+    assert_eq!(buffers.normals.len(), 8);
+    assert_eq!(buffers.uvs.len(), 8);
+    assert_eq!(buffers.indices, vec![0, 1, 2, 0, 2, 3, 4, 5, 6, 4, 6, 7]);
+    assert_eq!(&buffers.normals[..4], &[[0.0, 1.0, 0.0]; 4]);
+    assert_eq!(&buffers.normals[4..], &[[0.0, 0.0, 1.0]; 4]);
+    assert_eq!(
+        buffers.positions[1],
+        [2.0, 1.0, 0.0],
+        "quad spans its width"
+    );
+    assert_eq!(&buffers.uvs[..4], &atlas.get_uvs(1, Face::Up));
+
+    let mesh = BlockRenderer::create_mesh(&chunk_mesh(), &atlas);
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("mesh has positions");
+    };
+    assert_eq!(positions, &buffers.positions);
+    assert_eq!(buffers.clone().into_mesh().count_vertices(), 8);
+}
+
+fn wait_until_built(app: &mut App) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let mut pending = app.world_mut().query::<&PendingChunkMesh>();
+    while !pending.iter(app.world()).all(PendingChunkMesh::is_finished) {
+        assert!(Instant::now() < deadline, "mesh tasks did not finish");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn uploaded(app: &mut App) -> usize {
+    app.world_mut().query::<&Mesh3d>().iter(app.world()).count()
+}
+
+#[test]
+fn test_uploads_limited_per_frame() {
+    let mut app = App::new();
+    app.init_resource::<Assets<Mesh>>()
+        .insert_resource(MeshUploadBudget(2))
+        .add_systems(Update, upload_chunk_meshes);
+
+    let atlas = Arc::new(TextureAtlas::new(16));
+    for i in 0..5 {
+        let task = PendingChunkMesh::spawn(chunk_mesh(), atlas.clone(), IVec3::new(i * 32, 0, 0));
+        app.world_mut().spawn(task);
+    }
+    wait_until_built(&mut app);
+
+    app.update();
+    assert_eq!(uploaded(&mut app), 2);
+    assert_eq!(app.world().resource::<Assets<Mesh>>().len(), 2);
+
+    app.update();
+    assert_eq!(uploaded(&mut app), 4);
+
+    app.update();
+    assert_eq!(uploaded(&mut app), 5);
+    let mut pending = app.world_mut().query::<&PendingChunkMesh>();
+    assert_eq!(pending.iter(app.world()).count(), 0);
+}
+
+#[test]
+fn test_empty_meshes_do_not_use_budget() {
+    let mut app = App::new();
+    app.init_resource::<Assets<Mesh>>()
+        .insert_resource(MeshUploadBudget(1))
+        .add_systems(Update, upload_chunk_meshes);
+
+    let atlas = Arc::new(TextureAtlas::new(16));
+    let empty = app
+        .world_mut()
+        .spawn(PendingChunkMesh::spawn(
+            ChunkMesh::new(),
+            atlas.clone(),
+            IVec3::ZERO,
+        ))
+        .id();
+    app.world_mut()
+        .spawn(PendingChunkMesh::spawn(chunk_mesh(), atlas, IVec3::ZERO));
+    wait_until_built(&mut app);
+
+    app.update();
+    assert_eq!(uploaded(&mut app), 1);
+    assert!(app.world().get::<PendingChunkMesh>(empty).is_none());
+    assert!(app.world().get::<Mesh3d>(empty).is_none());
+}
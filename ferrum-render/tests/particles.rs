@@ -0,0 +1,127 @@
+use bevy::math::Vec3;
+use bevy::mesh::{Indices, VertexAttributeValues};
+use bevy::prelude::Mesh;
+use ferrum_render::{AmbientParticle, Particle, ParticleSystem, TextureAtlas};
+
+fn particle_at(x: f32) -> Particle {
+    Particle {
+        position: Vec3::new(x, 0.0, 0.0),
+        velocity: Vec3::ZERO,
+        age: 0.0,
+        lifetime: 1.0,
+        size: 0.1,
+        gravity_scale: 0.0,
+        uvs: [[0.0; 2]; 4],
+        color: [1.0; 4],
+    }
+}
+
+#[test]
+fn test_block_break_spawns_moving_particles() {
+    let atlas = TextureAtlas::new(16);
+    let mut system = ParticleSystem::default();
+
+    system.spawn_block_break(Vec3::new(10.0, 64.0, -3.0), 1, &atlas);
+
+    assert_eq!(system.len(), 64);
+    for particle in system.iter() {
+        assert!(particle.velocity.length() > 0.0);
+        assert!(particle.lifetime > 0.0);
+        assert!(particle.position.x >= 10.0 && particle.position.x <= 11.0);
+        assert!(particle.position.y >= 64.0 && particle.position.y <= 65.0);
+    }
+}
+
+#[test]
+fn test_step_kicks_up_a_few_particles_at_the_feet() {
+    let atlas = TextureAtlas::new(16);
+    let mut system = ParticleSystem::default();
+
+    let feet = Vec3::new(3.5, 65.0, 7.5);
+    system.spawn_step(feet, 2, &atlas);
+
+    assert!(!system.is_empty() && system.len() < 8);
+    for particle in system.iter() {
+        assert!(particle.velocity.y > 0.0);
+        assert!(particle.position.distance(feet) < 0.5);
+    }
+}
+
+#[test]
+fn test_particles_fall_under_gravity() {
+    let atlas = TextureAtlas::new(16);
+    let mut system = ParticleSystem::default();
+    system.spawn_block_break(Vec3::ZERO, 1, &atlas);
+
+    let before: Vec<f32> = system.iter().map(|p| p.velocity.y).collect();
+    system.update(0.1);
+    for (particle, vy) in system.iter().zip(before) {
+        assert!(particle.velocity.y < vy);
+    }
+}
+
+#[test]
+fn test_particles_removed_after_lifetime() {
+    let atlas = TextureAtlas::new(16);
+    let mut system = ParticleSystem::default();
+    system.spawn_block_break(Vec3::ZERO, 1, &atlas);
+    system.spawn_ambient(AmbientParticle::LavaSpark, Vec3::ZERO);
+
+    system.update(0.25);
+    assert!(
+        !system.is_empty(),
+        "particles should outlive a short update"
+    );
+
+    for _ in 0..40 {
+        system.update(0.1);
+    }
+    assert!(system.is_empty());
+}
+
+#[test]
+fn test_cap_drops_oldest_particles() {
+    let mut system = ParticleSystem::new(4);
+    for i in 0..6 {
+        system.spawn(particle_at(i as f32));
+    }
+
+    assert_eq!(system.len(), 4);
+    let xs: Vec<f32> = system.iter().map(|p| p.position.x).collect();
+    assert_eq!(xs, vec![2.0, 3.0, 4.0, 5.0]);
+}
+
+#[test]
+fn test_write_mesh_reuses_buffers() {
+    let mut system = ParticleSystem::new(16);
+    for i in 0..3 {
+        system.spawn(particle_at(i as f32));
+    }
+
+    let mut mesh = ParticleSystem::create_mesh();
+    system.write_mesh(&mut mesh, Vec3::X, Vec3::Y);
+
+    let vertex_count = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(values)) => values.len(),
+        _ => panic!("missing positions"),
+    };
+    assert_eq!(vertex_count, 12);
+    match mesh.indices() {
+        Some(Indices::U32(indices)) => assert_eq!(indices.len(), 18),
+        _ => panic!("missing indices"),
+    }
+
+    let capacity = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(values)) => values.capacity(),
+        _ => unreachable!(),
+    };
+    system.update(2.0);
+    system.write_mesh(&mut mesh, Vec3::X, Vec3::Y);
+    match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(values)) => {
+            assert!(values.is_empty());
+            assert_eq!(values.capacity(), capacity);
+        }
+        _ => panic!("missing positions"),
+    }
+}
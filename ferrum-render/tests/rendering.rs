@@ -1,3 +1,4 @@
+use bevy::math::Vec3;
 use bevy::mesh::{Indices, VertexAttributeValues};
 use ferrum_meshing_cpu::{ChunkMesh, Face, MeshQuad};
 use ferrum_render::{BlockRenderer, TextureAtlas};
@@ -166,3 +167,125 @@ fn test_all_block_types_mapped() {
         "Too many block types map to same tile as air: only {different_count} are different"
     );
 }
+
+fn positions(mesh: &bevy::prelude::Mesh) -> Vec<[f32; 3]> {
+    match mesh.attribute(bevy::prelude::Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(positions)) => positions.clone(),
+        _ => panic!("Expected Float32x3 positions"),
+    }
+}
+
+fn normals(mesh: &bevy::prelude::Mesh) -> Vec<[f32; 3]> {
+    match mesh.attribute(bevy::prelude::Mesh::ATTRIBUTE_NORMAL) {
+        Some(VertexAttributeValues::Float32x3(normals)) => normals.clone(),
+        _ => panic!("Expected Float32x3 normals"),
+    }
+}
+
+fn uvs(mesh: &bevy::prelude::Mesh) -> Vec<[f32; 2]> {
+    match mesh.attribute(bevy::prelude::Mesh::ATTRIBUTE_UV_0) {
+        Some(VertexAttributeValues::Float32x2(uvs)) => uvs.clone(),
+        _ => panic!("Expected Float32x2 UVs"),
+    }
+}
+
+fn one_quad_per_face() -> ChunkMesh {
+    let mut chunk_mesh = ChunkMesh::new();
+    for face in [
+        Face::Right,
+        Face::Left,
+        Face::Up,
+        Face::Down,
+        Face::Front,
+        Face::Back,
+    ] {
+        chunk_mesh.quads.push(MeshQuad {
+            x: 1,
+            y: 2,
+            z: 3,
+            width: 3,
+            height: 2,
+            face,
+            block_type: 2,
+        });
+    }
+    chunk_mesh
+}
+
+#[test]
+fn test_indexed_mesh_shares_vertices_per_quad() {
+    let atlas = TextureAtlas::new(16);
+    let mesh = BlockRenderer::create_indexed_mesh(&one_quad_per_face(), &atlas);
+
+    assert_eq!(positions(&mesh).len(), 6 * 4);
+    let Some(Indices::U32(indices)) = mesh.indices() else {
+        panic!("Expected U32 indices");
+    };
+    assert_eq!(indices.len(), 6 * 6);
+    for (quad, triangles) in indices.chunks(6).enumerate() {
+        let base = quad as u32 * 4;
+        assert_eq!(
+            triangles,
+            [base, base + 1, base + 2, base, base + 2, base + 3]
+        );
+    }
+}
+
+#[test]
+fn test_indexed_mesh_winds_counter_clockwise_around_normals() {
+    let atlas = TextureAtlas::new(16);
+    let mesh = BlockRenderer::create_indexed_mesh(&one_quad_per_face(), &atlas);
+    let positions = positions(&mesh);
+    let normals = normals(&mesh);
+    let Some(Indices::U32(indices)) = mesh.indices() else {
+        panic!("Expected U32 indices");
+    };
+
+    let expected = [
+        [1.0, 0.0, 0.0],
+        [-1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, -1.0, 0.0],
+        [0.0, 0.0, 1.0],
+        [0.0, 0.0, -1.0],
+    ];
+    for (quad, normal) in expected.iter().enumerate() {
+        assert!(normals[quad * 4..quad * 4 + 4].iter().all(|n| n == normal));
+    }
+
+    for triangle in indices.chunks(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(positions[triangle[i] as usize]));
+        let winding = (b - a).cross(c - a).normalize();
+        let normal = Vec3::from(normals[triangle[0] as usize]);
+        assert!(
+            winding.dot(normal) > 0.99,
+            "triangle {triangle:?} faces {winding}, normal is {normal}"
+        );
+    }
+}
+
+#[test]
+fn test_indexed_mesh_keeps_uvs_on_their_corners() {
+    let atlas = TextureAtlas::new(16);
+    let chunk_mesh = one_quad_per_face();
+    let indexed = BlockRenderer::create_indexed_mesh(&chunk_mesh, &atlas);
+    let plain = BlockRenderer::create_mesh(&chunk_mesh, &atlas);
+
+    // Corners may be reordered, but each keeps the UV it had
+    let corners = |mesh: &bevy::prelude::Mesh| {
+        let mut corners: Vec<_> = positions(mesh)
+            .into_iter()
+            .zip(uvs(mesh))
+            .map(|(p, uv)| format!("{p:?} {uv:?}"))
+            .collect();
+        corners.sort();
+        corners
+    };
+    assert_eq!(corners(&indexed), corners(&plain));
+    for (quad, face) in chunk_mesh.quads.iter().enumerate() {
+        let face_uvs = atlas.get_uvs(face.block_type, face.face);
+        for uv in &uvs(&indexed)[quad * 4..quad * 4 + 4] {
+            assert!(face_uvs.contains(uv));
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use bevy::light::{CascadeShadowConfig, DirectionalLightShadowMap};
+use bevy::prelude::*;
+use ferrum_config::{Config, ShadowQuality};
+use ferrum_render::{
+    ShadowSettings, ShadowsPlugin, MAX_SHADOW_CASCADES, MAX_SHADOW_MAP_SIZE, MIN_SHADOW_DISTANCE,
+};
+
+fn config_with(client: &str) -> Config {
+    Config::from_str(&format!("[client]\n{}\n", client)).unwrap()
+}
+
+#[test]
+fn quality_presets_map_to_shadow_settings() {
+    let cases = [
+        ("off", false, 2048, 2, 96.0),
+        ("low", true, 1024, 1, 48.0),
+        ("medium", true, 2048, 2, 96.0),
+        ("high", true, 4096, 4, 192.0),
+    ];
+    for (value, enabled, map_size, cascades, max_distance) in cases {
+        let config = config_with(&format!("shadows = \"{}\"", value));
+        let settings = ShadowSettings::from_config(&config.client);
+        assert_eq!(settings.enabled, enabled, "{value}");
+        assert_eq!(settings.map_size, map_size, "{value}");
+        assert_eq!(settings.cascades, cascades, "{value}");
+        assert_eq!(settings.max_distance, max_distance, "{value}");
+
+        let cascade_config = settings.cascade_config();
+        assert_eq!(cascade_config.bounds.len(), cascades, "{value}");
+        assert_eq!(*cascade_config.bounds.last().unwrap(), max_distance);
+    }
+    assert!(!ShadowSettings::preset(ShadowQuality::Off).enabled);
+}
+
+#[test]
+fn overrides_are_clamped() {
+    let config = config_with(
+        r#"
+shadows = "low"
+shadow_resolution = 3000
+shadow_cascades = 3
+shadow_distance = 80.0
+"#,
+    );
+    let settings = ShadowSettings::from_config(&config.client);
+    assert_eq!(settings.map_size, 4096);
+    assert_eq!(settings.cascades, 3);
+    assert_eq!(settings.max_distance, 80.0);
+
+    let config = config_with(
+        r#"
+shadows = "high"
+shadow_resolution = 100000
+shadow_cascades = 9
+shadow_distance = 2.0
+"#,
+    );
+    let settings = ShadowSettings::from_config(&config.client);
+    assert_eq!(settings.map_size, MAX_SHADOW_MAP_SIZE);
+    assert_eq!(settings.cascades, MAX_SHADOW_CASCADES);
+    assert_eq!(settings.max_distance, MIN_SHADOW_DISTANCE);
+
+    // Shadows stop at the render distance
+    let config = config_with("render_distance = 4\nshadows = \"high\"");
+    assert_eq!(
+        ShadowSettings::from_config(&config.client).max_distance,
+        64.0
+    );
+}
+
+#[test]
+fn light_is_updated_on_spawn_and_reload() {
+    let mut app = App::new();
+    app.add_plugins(ShadowsPlugin)
+        .insert_resource(config_with("shadows = \"high\""));
+    let light = app.world_mut().spawn(DirectionalLight::default()).id();
+
+    app.update();
+    assert!(
+        app.world()
+            .get::<DirectionalLight>(light)
+            .unwrap()
+            .shadows_enabled
+    );
+    let cascades = app.world().get::<CascadeShadowConfig>(light).unwrap();
+    assert_eq!(cascades.bounds.len(), 4);
+    assert_eq!(
+        app.world().resource::<DirectionalLightShadowMap>().size,
+        4096
+    );
+
+    // Turning shadows off disables them on the light
+    app.world_mut().resource_mut::<Config>().client.shadows = "off".to_string();
+    app.update();
+    assert!(
+        !app.world()
+            .get::<DirectionalLight>(light)
+            .unwrap()
+            .shadows_enabled
+    );
+}
@@ -0,0 +1,204 @@
+use bevy::math::Affine2;
+use bevy::prelude::*;
+use ferrum_core::{BlockId, BlockState};
+use ferrum_meshing_cpu::{ChunkMesh, Face, MeshQuad};
+use ferrum_render::{
+    animate_textures, BlockRenderer, TextureAnimation, TextureAnimations, TextureAtlas,
+};
+use std::time::Duration;
+
+const STONE: u32 = 0;
+const WATER: u32 = 5;
+
+fn water() -> TextureAnimation {
+    TextureAnimation::new(vec![(13, 0), (0, 2), (1, 2), (2, 2)], 0.25)
+}
+
+fn quad(x: u8, block_type: u32) -> MeshQuad {
+    MeshQuad {
+        x,
+        y: 0,
+        z: 0,
+        width: 1,
+        height: 1,
+        face: Face::Up,
+        block_type,
+    }
+}
+
+#[test]
+fn test_frame_advances_with_elapsed_time() {
+    let animation = water();
+
+    assert_eq!(animation.frame_at(0.0), 0);
+    assert_eq!(animation.frame_at(0.2), 0);
+    assert_eq!(animation.frame_at(0.25), 1);
+    assert_eq!(animation.frame_at(0.6), 2);
+    assert_eq!(animation.frame_at(0.9), 3);
+    assert_eq!(animation.tile_at(0.9), (2, 2));
+}
+
+#[test]
+fn test_frame_wraps_at_sequence_end() {
+    let animation = water();
+
+    assert_eq!(animation.duration(), 1.0);
+    assert_eq!(animation.frame_at(1.0), 0);
+    assert_eq!(animation.frame_at(1.3), 1);
+    assert_eq!(animation.frame_at(10.75), 3);
+
+    let strip = TextureAnimation::strip((4, 3), 3, 0.5);
+    assert_eq!(strip.frames(), &[(4, 3), (5, 3), (6, 3)]);
+    assert_eq!(strip.tile_at(1.6), (4, 3));
+}
+
+#[test]
+fn test_system_moves_uvs_of_animated_material_only() {
+    let mut app = App::new();
+    app.init_resource::<Time>()
+        .init_resource::<Assets<StandardMaterial>>()
+        .init_resource::<TextureAnimations>()
+        .add_systems(Update, animate_textures);
+
+    let (animated, still) = {
+        let mut materials = app.world_mut().resource_mut::<Assets<StandardMaterial>>();
+        (
+            materials.add(StandardMaterial::default()),
+            materials.add(StandardMaterial::default()),
+        )
+    };
+    app.world_mut()
+        .resource_mut::<TextureAnimations>()
+        .add(animated.clone(), water());
+
+    let uv_transform = |app: &App, handle: &Handle<StandardMaterial>| {
+        app.world()
+            .resource::<Assets<StandardMaterial>>()
+            .get(handle)
+            .unwrap()
+            .uv_transform
+    };
+
+    app.update();
+    assert_eq!(uv_transform(&app, &animated), Affine2::IDENTITY);
+
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_millis(300));
+    app.update();
+    let frame_one = Affine2::from_translation(Vec2::new(-13.0 / 16.0, 2.0 / 16.0));
+    assert!(uv_transform(&app, &animated).abs_diff_eq(frame_one, 1e-6));
+    assert_eq!(uv_transform(&app, &still), Affine2::IDENTITY);
+
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_millis(700));
+    app.update();
+    assert_eq!(uv_transform(&app, &animated), Affine2::IDENTITY);
+}
+
+#[test]
+fn test_static_blocks_unaffected_by_animations() {
+    let plain = TextureAtlas::new(16);
+    let mut atlas = TextureAtlas::new(16);
+    atlas.set_animation(WATER, water());
+
+    assert!(atlas.is_animated(WATER));
+    assert!(!atlas.is_animated(STONE));
+    assert_eq!(
+        atlas.get_uvs(STONE, Face::Up),
+        plain.get_uvs(STONE, Face::Up)
+    );
+    assert_eq!(
+        atlas.get_uvs(WATER, Face::Up),
+        plain.get_uvs(WATER, Face::Up),
+        "animated blocks mesh with their first frame"
+    );
+
+    let chunk = ChunkMesh {
+        quads: vec![quad(0, STONE), quad(1, WATER), quad(2, STONE)],
+    };
+    let (still, animated) = BlockRenderer::split_animated(&chunk, &atlas);
+    assert_eq!(still.quads.len(), 2);
+    assert!(still.quads.iter().all(|quad| quad.block_type == STONE));
+    assert_eq!(animated.len(), 1);
+    assert_eq!(animated[0].0, WATER);
+    assert_eq!(animated[0].1.quads.len(), 1);
+
+    let (still, animated) = BlockRenderer::split_animated(&chunk, &plain);
+    assert_eq!(still.quads.len(), 3);
+    assert!(animated.is_empty());
+}
+
+#[test]
+fn test_leveled_water_is_animated_like_a_source() {
+    let mut atlas = TextureAtlas::new(16);
+    atlas.set_animation(WATER, water());
+    let level_3 = BlockState::new(BlockId::new(WATER as u16), 3).to_bits();
+
+    assert!(atlas.is_animated(level_3));
+    assert_eq!(atlas.tile(level_3, Face::Up), (13, 0));
+    assert_eq!(
+        atlas.get_uvs(level_3, Face::Up),
+        atlas.get_uvs(WATER, Face::Up)
+    );
+    assert_eq!(
+        atlas.uvs_at_time(level_3, 0.3),
+        atlas.uvs_at_time(WATER, 0.3)
+    );
+
+    let chunk = ChunkMesh {
+        quads: vec![quad(0, STONE), quad(1, level_3), quad(2, WATER)],
+    };
+    let (still, animated) = BlockRenderer::split_animated(&chunk, &atlas);
+    assert_eq!(still.quads.len(), 1);
+    assert_eq!(animated.len(), 1);
+    assert_eq!(animated[0].0, WATER);
+    assert_eq!(animated[0].1.quads.len(), 2);
+}
+
+/// UVs of the atlas tile at `index`, via a one frame animation.
+fn tile_uvs(index: u32) -> [[f32; 2]; 4] {
+    let mut atlas = TextureAtlas::new(16);
+    atlas.register_animated(STONE, vec![index], 1.0).unwrap();
+    atlas.uvs_at_time(STONE, 0.0)
+}
+
+#[test]
+fn test_registered_animation_wraps_with_elapsed_time() {
+    let mut atlas = TextureAtlas::new(16);
+    // Water's own tile, then three tiles from the third row
+    atlas
+        .register_animated(WATER, vec![13, 32, 33, 34], 4.0)
+        .unwrap();
+    assert!(atlas.is_animated(WATER));
+    assert_eq!(atlas.animation(WATER).unwrap().tile_at(0.3), (0, 2));
+
+    assert_eq!(atlas.uvs_at_time(WATER, 0.0), tile_uvs(13));
+    assert_eq!(atlas.uvs_at_time(WATER, 0.3), tile_uvs(32));
+    assert_eq!(atlas.uvs_at_time(WATER, 0.9), tile_uvs(34));
+    assert_eq!(atlas.uvs_at_time(WATER, 1.0), tile_uvs(13));
+    assert_eq!(atlas.uvs_at_time(WATER, 10.6), tile_uvs(33));
+    assert_eq!(atlas.get_uvs(WATER, Face::Front), tile_uvs(13));
+}
+
+#[test]
+fn test_static_blocks_ignore_elapsed_time() {
+    let atlas = TextureAtlas::new(16);
+    let stone = atlas.get_uvs(STONE, Face::Up);
+    assert_eq!(atlas.uvs_at_time(STONE, 0.0), stone);
+    assert_eq!(atlas.uvs_at_time(STONE, 7.5), stone);
+}
+
+#[test]
+fn test_register_animated_rejects_bad_input() {
+    let mut atlas = TextureAtlas::new(16);
+    for fps in [0.0, -2.0, f32::NAN, f32::INFINITY] {
+        assert!(
+            atlas.register_animated(WATER, vec![13, 32], fps).is_err(),
+            "fps {fps}"
+        );
+    }
+    assert!(atlas.register_animated(WATER, Vec::new(), 8.0).is_err());
+    assert!(!atlas.is_animated(WATER));
+}
@@ -0,0 +1,103 @@
+use bevy::asset::RenderAssetUsages;
+use bevy::image::Image;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use ferrum_meshing_cpu::Face;
+use ferrum_render::{TextureAtlas, DEFAULT_UV_INSET};
+
+const TILE: u32 = 4;
+const RED: [u8; 4] = [200, 30, 30, 255];
+const BLUE: [u8; 4] = [20, 40, 220, 255];
+const GREEN: [u8; 4] = [40, 180, 60, 128];
+
+/// A 16×16 tile atlas: tile 0 red, tile 1 blue and the rest green.
+fn atlas_image() -> Image {
+    let size = 16 * TILE;
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let color = match (x / TILE, y / TILE) {
+                (0, 0) => RED,
+                (1, 0) => BLUE,
+                _ => GREEN,
+            };
+            data.extend_from_slice(&color);
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+/// Every texel of a tile in a mip level `width` texels wide with tiles
+/// `tile` texels across.
+fn tile_texels(mip: &[u8], width: u32, tile: u32, (tile_x, tile_y): (u32, u32)) -> Vec<[u8; 4]> {
+    let mut texels = Vec::new();
+    for y in tile_y * tile..(tile_y + 1) * tile {
+        for x in tile_x * tile..(tile_x + 1) * tile {
+            let i = ((y * width + x) * 4) as usize;
+            texels.push(mip[i..i + 4].try_into().unwrap());
+        }
+    }
+    texels
+}
+
+#[test]
+fn test_solid_tiles_keep_their_colour_at_every_mip() {
+    let atlas = TextureAtlas::new(TILE);
+    let mips = atlas.generate_mipmaps(&atlas_image(), 8);
+
+    // 4, 2 and 1 texel tiles
+    assert_eq!(mips.len(), 3);
+    for (level, mip) in mips.iter().enumerate() {
+        let tile = TILE >> level;
+        let width = 16 * tile;
+        assert_eq!(mip.len(), (width * width * 4) as usize);
+        for (coords, color) in [
+            ((0, 0), RED),
+            ((1, 0), BLUE),
+            ((2, 0), GREEN),
+            ((0, 1), GREEN),
+        ] {
+            assert!(
+                tile_texels(mip, width, tile, coords)
+                    .iter()
+                    .all(|&texel| texel == color),
+                "tile {coords:?} at level {level}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_mip_levels_are_limited_by_request() {
+    let atlas = TextureAtlas::new(TILE);
+    let mips = atlas.generate_mipmaps(&atlas_image(), 2);
+    assert_eq!(mips.len(), 2);
+    assert_eq!(mips[0], atlas_image().data.unwrap());
+    assert_eq!(atlas.generate_mipmaps(&atlas_image(), 0).len(), 1);
+}
+
+#[test]
+fn test_uv_inset_is_half_a_texel_by_default() {
+    let mut atlas = TextureAtlas::new(16);
+    assert_eq!(atlas.uv_inset(), DEFAULT_UV_INSET);
+
+    // Stone is tile (1, 0) of a 256 texel atlas
+    let texel = 1.0 / 256.0;
+    let uvs = atlas.get_uvs(1, Face::Up);
+    assert!((uvs[3][0] - (16.0 + 0.5) * texel).abs() < 1e-6);
+    assert!((uvs[1][0] - (32.0 - 0.5) * texel).abs() < 1e-6);
+
+    atlas.set_uv_inset(0.0);
+    let uvs = atlas.get_uvs(1, Face::Up);
+    assert_eq!(uvs[3], [16.0 * texel, 0.0]);
+    assert_eq!(uvs[1], [32.0 * texel, 16.0 * texel]);
+}
@@ -0,0 +1,94 @@
+use bevy::math::IVec3;
+use ferrum_meshing_cpu::{ChunkMesh, Face, MeshQuad};
+use ferrum_render::{BlockRenderer, TextureAtlas};
+use std::collections::HashSet;
+
+const GRASS: u32 = 2;
+
+fn varied_atlas() -> TextureAtlas {
+    let mut atlas = TextureAtlas::new(16);
+    atlas.add_variants(GRASS, Face::Up, &[(5, 0), (6, 0), (7, 0)]);
+    atlas.set_random_rotation(GRASS, Face::Up, true);
+    atlas
+}
+
+fn strip(face: Face, length: u8) -> ChunkMesh {
+    ChunkMesh {
+        quads: vec![MeshQuad {
+            x: 0,
+            y: 0,
+            z: 0,
+            width: length,
+            height: 1,
+            face,
+            block_type: GRASS,
+        }],
+    }
+}
+
+fn vertex_count(mesh: &bevy::prelude::Mesh) -> usize {
+    mesh.attribute(bevy::prelude::Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .len()
+}
+
+#[test]
+fn variant_is_stable_for_a_position() {
+    let atlas = varied_atlas();
+    let pos = IVec3::new(12, 64, -7);
+
+    let first = atlas.texture_variant(GRASS, Face::Up, pos);
+    for _ in 0..10 {
+        assert_eq!(atlas.texture_variant(GRASS, Face::Up, pos), first);
+    }
+    assert_eq!(
+        atlas.get_uvs_at(GRASS, Face::Up, pos),
+        atlas.get_uvs_at(GRASS, Face::Up, pos)
+    );
+}
+
+#[test]
+fn variants_and_rotations_differ_across_positions() {
+    let atlas = varied_atlas();
+
+    let mut variants = HashSet::new();
+    let mut rotations = HashSet::new();
+    for x in 0..16 {
+        for z in 0..16 {
+            let v = atlas.texture_variant(GRASS, Face::Up, IVec3::new(x, 64, z));
+            assert!(v.variant <= 3);
+            assert!(v.rotation < 4);
+            variants.insert(v.variant);
+            rotations.insert(v.rotation);
+        }
+    }
+    assert_eq!(
+        variants.len(),
+        4,
+        "base tile and all three variants are used"
+    );
+    assert_eq!(rotations.len(), 4, "all four rotations are used");
+}
+
+#[test]
+fn faces_without_variation_use_base_tile() {
+    let atlas = varied_atlas();
+
+    assert!(!atlas.has_variation(GRASS, Face::Right));
+    let pos = IVec3::new(3, 70, 9);
+    assert_eq!(
+        atlas.get_uvs_at(GRASS, Face::Right, pos),
+        atlas.get_uvs(GRASS, Face::Right)
+    );
+}
+
+#[test]
+fn varied_quads_are_split_per_block() {
+    let atlas = varied_atlas();
+
+    let varied = BlockRenderer::create_mesh(&strip(Face::Up, 4), &atlas);
+    assert_eq!(vertex_count(&varied), 16);
+
+    let plain = BlockRenderer::create_mesh(&strip(Face::Right, 4), &atlas);
+    assert_eq!(vertex_count(&plain), 4);
+}
@@ -0,0 +1,70 @@
+use ferrum_render::{ThrottledUpdate, UpdateThrottle};
+
+fn throttle() -> UpdateThrottle {
+    UpdateThrottle {
+        near_distance: 16.0,
+        mid_distance: 48.0,
+        mid_interval: 4,
+        far_interval: 16,
+    }
+}
+
+#[test]
+fn test_interval_by_distance() {
+    let throttle = throttle();
+    assert_eq!(throttle.interval(0.0), 1);
+    assert_eq!(throttle.interval(15.9), 1);
+    assert_eq!(throttle.interval(16.0), 4);
+    assert_eq!(throttle.interval(47.9), 4);
+    assert_eq!(throttle.interval(48.0), 16);
+    assert_eq!(throttle.interval(500.0), 16);
+
+    let never_zero = UpdateThrottle {
+        mid_interval: 0,
+        ..throttle
+    };
+    assert_eq!(never_zero.interval(20.0), 1);
+}
+
+#[test]
+fn test_near_entities_update_every_frame() {
+    let throttle = throttle();
+    assert!((0..64).all(|frame| throttle.should_update(7, 5.0, frame)));
+}
+
+#[test]
+fn test_each_entity_updates_once_per_interval() {
+    let throttle = throttle();
+    for id in 0..40 {
+        let updates = (0..64)
+            .filter(|&frame| throttle.should_update(id, 100.0, frame))
+            .count();
+        assert_eq!(updates, 4, "entity {id}");
+
+        let updates = (0..64)
+            .filter(|&frame| throttle.should_update(id, 30.0, frame))
+            .count();
+        assert_eq!(updates, 16, "entity {id}");
+    }
+}
+
+#[test]
+fn test_stagger_spreads_entities_across_frames() {
+    let throttle = throttle();
+    // 64 far entities, 16-frame interval: 4 per frame, never all at once
+    for frame in 0..32 {
+        let updating = (0..64)
+            .filter(|&id| throttle.should_update(id, 100.0, frame))
+            .count();
+        assert_eq!(updating, 4, "frame {frame}");
+    }
+}
+
+#[test]
+fn test_throttled_update_catches_up() {
+    let mut throttled = ThrottledUpdate::default();
+    assert_eq!(throttled.tick(0.25, false), None);
+    assert_eq!(throttled.tick(0.25, false), None);
+    assert_eq!(throttled.tick(0.5, true), Some(1.0));
+    assert_eq!(throttled.tick(0.5, true), Some(0.5));
+}
@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+use ferrum_render::{
+    animate_view_model, update_held_item, HeldItem, HotbarSelection, SwingAnimation, SwingHand,
+    ViewBob, ViewModel, ViewModelCamera,
+};
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-4,
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn swing_progress_advances_over_time() {
+    let mut swing = SwingAnimation::new(0.3);
+    assert!(!swing.is_swinging());
+    assert_eq!(swing.progress(), 0.0);
+
+    swing.start();
+    assert!(swing.is_swinging());
+    assert_eq!(swing.progress(), 0.0);
+
+    swing.tick(0.1);
+    assert_close(swing.progress(), 1.0 / 3.0);
+    swing.tick(0.1);
+    assert_close(swing.progress(), 2.0 / 3.0);
+    let (offset, _) = swing.pose();
+    assert!(offset.length() > 0.0, "arm is away from rest mid-swing");
+
+    swing.tick(0.15);
+    assert!(!swing.is_swinging());
+    assert_eq!(swing.progress(), 0.0);
+    assert_eq!(swing.pose(), (Vec3::ZERO, Quat::IDENTITY));
+}
+
+#[test]
+fn swing_restarts_only_past_halfway() {
+    let mut swing = SwingAnimation::new(0.3);
+    swing.start();
+    swing.tick(0.1);
+
+    swing.start();
+    assert_close(swing.progress(), 1.0 / 3.0);
+
+    swing.tick(0.08);
+    swing.start();
+    assert_eq!(swing.progress(), 0.0);
+    assert!(swing.is_swinging());
+}
+
+#[test]
+fn bob_follows_movement() {
+    let mut bob = ViewBob::default();
+    for _ in 0..10 {
+        bob.update(0.0, 0.05);
+    }
+    assert_eq!(bob.offset(), Vec3::ZERO);
+
+    for _ in 0..7 {
+        bob.update(4.3, 0.05);
+    }
+    assert!(bob.offset().length() > 0.0);
+
+    for _ in 0..60 {
+        bob.update(0.0, 0.05);
+    }
+    assert!(bob.offset().length() < 1e-3, "bob settles when standing");
+}
+
+#[test]
+fn held_item_follows_selected_slot() {
+    let mut selection = HotbarSelection::default();
+    selection.items[0] = Some(1);
+    selection.items[4] = Some(264);
+
+    let mut app = App::new();
+    app.insert_resource(selection)
+        .add_systems(Update, update_held_item);
+    let model = app
+        .world_mut()
+        .spawn((ViewModel::default(), HeldItem::default()))
+        .id();
+
+    app.update();
+    assert_eq!(app.world().get::<HeldItem>(model), Some(&HeldItem(Some(1))));
+
+    app.world_mut().resource_mut::<HotbarSelection>().selected = 4;
+    app.update();
+    assert_eq!(
+        app.world().get::<HeldItem>(model),
+        Some(&HeldItem(Some(264)))
+    );
+
+    app.world_mut().resource_mut::<HotbarSelection>().selected = 2;
+    app.update();
+    assert_eq!(app.world().get::<HeldItem>(model), Some(&HeldItem(None)));
+}
+
+#[test]
+fn swing_message_starts_swing() {
+    let mut app = App::new();
+    app.init_resource::<Time>()
+        .add_message::<SwingHand>()
+        .add_systems(Update, animate_view_model);
+    let camera = app
+        .world_mut()
+        .spawn((ViewModelCamera, GlobalTransform::default()))
+        .id();
+    let model = app
+        .world_mut()
+        .spawn((ViewModel::default(), Transform::default(), ChildOf(camera)))
+        .id();
+
+    app.update();
+    assert!(!app
+        .world()
+        .get::<ViewModel>(model)
+        .unwrap()
+        .swing
+        .is_swinging());
+
+    app.world_mut().write_message(SwingHand);
+    app.update();
+    assert!(app
+        .world()
+        .get::<ViewModel>(model)
+        .unwrap()
+        .swing
+        .is_swinging());
+}
@@ -1,10 +1,23 @@
-use std::path::PathBuf;
-use std::process::Stdio;
-use std::time::Duration;
+mod log_stream;
+mod properties;
+mod readiness;
+mod supervisor;
+
+pub use log_stream::{LogLine, Stream, LOG_CHANNEL_CAPACITY};
+pub use properties::ServerProperties;
+pub use readiness::{LogPattern, ReadinessMatcher};
+pub use supervisor::{ServerSupervisor, SupervisorConfig, SupervisorEvent, SupervisorHandle};
+
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+use properties::EULA_ACCEPTED;
+use readiness::{ReadinessTracker, PORT_PROBE_INTERVAL};
 use thiserror::Error;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::process::{Child, Command};
-use tokio::time::timeout;
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::mpsc;
+use tokio::time::{interval, timeout};
 
 #[derive(Debug, Error)]
 pub enum SubprocessError {
@@ -14,19 +27,59 @@ pub enum SubprocessError {
     #[error("Process crashed during startup with exit code: {0:?}")]
     ProcessCrashed(Option<i32>),
 
-    #[error("Startup timeout: Done message not received within {0:?}")]
+    #[error("Startup timeout: server not ready within {0:?}")]
     StartupTimeout(Duration),
 
+    #[error("Invalid ready pattern: {0}")]
+    InvalidReadyPattern(#[from] regex::Error),
+
     #[error("Failed to send stop command: {0}")]
     StopCommandFailed(std::io::Error),
 
+    #[error("Failed to send command: {0}")]
+    CommandFailed(std::io::Error),
+
     #[error("Process is not running")]
     NotRunning,
+
+    #[error("A process is already running")]
+    AlreadyRunning,
+}
+
+/// How long [`PumpkinServer::start`] waits for the server to become ready
+/// unless changed with [`PumpkinServer::with_startup_timeout`].
+pub const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What [`PumpkinServer::poll_status`] found the server doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerStatus {
+    Running,
+    /// The server shut down cleanly, with this exit code.
+    Exited(Option<i32>),
+    /// The server failed with this exit code, or `None` if it was killed by
+    /// a signal.
+    Crashed(Option<i32>),
+    /// No server was started, or its exit has already been reported.
+    NotRunning,
 }
 
+/// A local Pumpkin server, either spawned by [`start`](Self::start) or adopted
+/// with [`attach`](Self::attach).
 pub struct PumpkinServer {
     binary_path: PathBuf,
     child: Option<Child>,
+    /// The spawned server's console, kept open between commands.
+    stdin: Option<ChildStdin>,
+    /// PID of an adopted process that is not our child.
+    attached: Option<u32>,
+    readiness: ReadinessMatcher,
+    startup_timeout: Duration,
+    /// Directory the server runs in, or the current one if `None`.
+    working_dir: Option<PathBuf>,
+    /// Output of the running server, until taken.
+    log_stream: Option<mpsc::Receiver<LogLine>>,
+    /// When the spawned server last became ready, while it is running.
+    started_at: Option<Instant>,
 }
 
 impl PumpkinServer {
@@ -34,14 +87,90 @@ impl PumpkinServer {
         Self {
             binary_path,
             child: None,
+            stdin: None,
+            attached: None,
+            readiness: ReadinessMatcher::default(),
+            startup_timeout: DEFAULT_STARTUP_TIMEOUT,
+            working_dir: None,
+            log_stream: None,
+            started_at: None,
         }
     }
 
+    /// Run the server in `dir` instead of the current directory.
+    pub fn with_working_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.working_dir = Some(dir.into());
+        self
+    }
+
+    /// Directory the server runs in and keeps its files in.
+    pub fn working_dir(&self) -> &Path {
+        self.working_dir.as_deref().unwrap_or(Path::new("."))
+    }
+
+    /// Prepare the working directory for a first start: write an `eula.txt`
+    /// accepting the EULA and a `server.properties` with `props`. Files that
+    /// already exist are kept unless `props.overwrite` is set.
+    pub fn ensure_config(&self, props: &ServerProperties) -> std::io::Result<()> {
+        let dir = self.working_dir();
+        std::fs::create_dir_all(dir)?;
+        properties::write_file(&dir.join("eula.txt"), EULA_ACCEPTED, props.overwrite)?;
+        properties::write_file(
+            &dir.join("server.properties"),
+            &props.to_properties(),
+            props.overwrite,
+        )
+    }
+
+    /// Replace the condition `start` waits for. Defaults to
+    /// [`ReadinessMatcher::DoneMessage`].
+    pub fn with_readiness(mut self, readiness: ReadinessMatcher) -> Self {
+        self.readiness = readiness;
+        self
+    }
+
+    /// Wait for a log line matching the regular expression `pattern`
+    /// instead, for servers that announce startup differently. Fails with
+    /// [`SubprocessError::InvalidReadyPattern`] if `pattern` doesn't compile.
+    pub fn with_ready_pattern(self, pattern: String) -> Result<Self, SubprocessError> {
+        let pattern = LogPattern::new(&pattern)?;
+        Ok(self.with_readiness(ReadinessMatcher::LogMatches(pattern)))
+    }
+
+    /// How long `start` waits for the server to become ready. Defaults to
+    /// [`DEFAULT_STARTUP_TIMEOUT`].
+    pub fn with_startup_timeout(mut self, startup_timeout: Duration) -> Self {
+        self.startup_timeout = startup_timeout;
+        self
+    }
+
+    /// Hand the server to a [`ServerSupervisor`] that restarts it whenever it
+    /// crashes, up to `max_restarts` times in a row. The first restart waits
+    /// `backoff`, and each further one twice as long as the one before.
+    /// Restarts are counted afresh once the server stays up for
+    /// [`SupervisorConfig::stable_uptime`]. Nothing happens until the
+    /// supervisor is [run](ServerSupervisor::run).
+    pub fn enable_auto_restart(
+        self,
+        max_restarts: u32,
+        backoff: Duration,
+    ) -> (ServerSupervisor, mpsc::UnboundedReceiver<SupervisorEvent>) {
+        let config = SupervisorConfig {
+            max_restarts,
+            initial_backoff: backoff,
+            ..SupervisorConfig::default()
+        };
+        ServerSupervisor::new(self, config)
+    }
+
     pub async fn start(&mut self) -> Result<(), SubprocessError> {
         let mut cmd = Command::new(&self.binary_path);
         cmd.stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::inherit());
+            .stderr(Stdio::piped());
+        if let Some(dir) = &self.working_dir {
+            cmd.current_dir(dir);
+        }
 
         #[cfg(unix)]
         {
@@ -55,30 +184,42 @@ impl PumpkinServer {
 
         let mut child = cmd.spawn()?;
 
+        let (log_sender, log_receiver) = mpsc::channel(LOG_CHANNEL_CAPACITY);
+        let stderr = child.stderr.take().expect("stderr should be piped");
+        log_stream::forward(stderr, Stream::Stderr, log_sender.clone());
+
         let stdout = child.stdout.take().expect("stdout should be piped");
-        let mut reader = BufReader::new(stdout);
+        let mut lines = BufReader::new(stdout).lines();
+
+        let mut tracker = ReadinessTracker::new(&self.readiness);
+        let watches_port = self.readiness.watches_port();
+        let mut probe = interval(PORT_PROBE_INTERVAL);
 
-        let startup_timeout = Duration::from_secs(30);
+        let startup_timeout = self.startup_timeout;
         let result = timeout(startup_timeout, async {
-            let mut line = String::new();
-            loop {
-                line.clear();
-                match reader.read_line(&mut line).await {
-                    Ok(0) => {
-                        let exit_status = child.wait().await.ok();
-                        return Err(SubprocessError::ProcessCrashed(
-                            exit_status.and_then(|s| s.code()),
-                        ));
-                    }
-                    Ok(_) => {
-                        // Support both vanilla ("Done (X.XXXs)!") and Pumpkin ("Started server; took Xms")
-                        if (line.contains("Done") && line.contains("s)!"))
-                            || line.contains("Server is now running")
-                        {
-                            break;
+            let mut stdout_open = true;
+            while !tracker.is_ready() {
+                tokio::select! {
+                    line = lines.next_line(), if stdout_open => match line {
+                        Ok(Some(line)) => {
+                            tracker.observe_line(&line);
+                            let _ = log_sender.try_send(LogLine::new(Stream::Stdout, line));
                         }
+                        Ok(None) if watches_port => stdout_open = false,
+                        Ok(None) => {
+                            let exit_status = child.wait().await.ok();
+                            return Err(SubprocessError::ProcessCrashed(
+                                exit_status.and_then(|s| s.code()),
+                            ));
+                        }
+                        Err(e) => return Err(SubprocessError::SpawnFailed(e)),
+                    },
+                    _ = probe.tick(), if watches_port => {
+                        if let Some(status) = child.try_wait()? {
+                            return Err(SubprocessError::ProcessCrashed(status.code()));
+                        }
+                        tracker.probe_ports().await;
                     }
-                    Err(e) => return Err(SubprocessError::SpawnFailed(e)),
                 }
             }
             Ok(())
@@ -87,8 +228,11 @@ impl PumpkinServer {
 
         match result {
             Ok(Ok(())) => {
-                child.stdout = Some(reader.into_inner());
+                log_stream::forward_lines(lines.into_inner(), Stream::Stdout, log_sender);
+                self.stdin = child.stdin.take();
                 self.child = Some(child);
+                self.started_at = Some(Instant::now());
+                self.log_stream = Some(log_receiver);
                 Ok(())
             }
             Ok(Err(e)) => {
@@ -102,16 +246,125 @@ impl PumpkinServer {
         }
     }
 
+    /// Send a console command such as `list` or `say hello` to the started
+    /// server, as if typed at its console. The command is flushed right
+    /// away, and the console stays open for further commands.
+    ///
+    /// Fails with [`SubprocessError::NotRunning`] if the server has exited,
+    /// or was [attached](Self::attach) and so has no console to write to.
+    pub async fn send_command(&mut self, cmd: &str) -> Result<(), SubprocessError> {
+        if self.try_status()?.is_some() {
+            return Err(SubprocessError::NotRunning);
+        }
+        let stdin = self.stdin.as_mut().ok_or(SubprocessError::NotRunning)?;
+        write_command(stdin, cmd)
+            .await
+            .map_err(SubprocessError::CommandFailed)
+    }
+
+    /// Take the output of the started server: every line it has written to
+    /// stdout and stderr since starting, then each new one as it is written.
+    /// The stream ends once the server exits. Returns `None` if no server
+    /// was started, the stream was already taken, or the server was
+    /// [attached](Self::attach), as its output can't be read.
+    pub fn take_log_stream(&mut self) -> Option<mpsc::Receiver<LogLine>> {
+        self.log_stream.take()
+    }
+
+    /// Adopt a server that is already running with the given PID instead of
+    /// spawning one.
+    ///
+    /// An attached process is not our child, so it is managed with reduced
+    /// capabilities:
+    /// - there is no stdin, so [`stop`](Self::stop) asks it to shut down with
+    ///   `SIGTERM` instead of the `stop` command, then kills it after the grace
+    ///   period;
+    /// - liveness is checked through the PID, and the exit code cannot be
+    ///   observed, so [`try_status`](Self::try_status) reports any exit as a
+    ///   success;
+    /// - it is left running when the `PumpkinServer` is dropped.
+    ///
+    /// Fails with [`SubprocessError::NotRunning`] if no process has that PID.
+    #[cfg(unix)]
+    pub fn attach(&mut self, pid: u32) -> Result<(), SubprocessError> {
+        if self.is_running() {
+            return Err(SubprocessError::AlreadyRunning);
+        }
+        if !process_exists(pid) {
+            return Err(SubprocessError::NotRunning);
+        }
+        self.attached = Some(pid);
+        self.log_stream = None;
+        Ok(())
+    }
+
+    /// PID of the managed process, spawned or attached.
+    pub fn pid(&self) -> Option<u32> {
+        match &self.child {
+            Some(child) => child.id(),
+            None => self.attached,
+        }
+    }
+
+    /// Whether the server was adopted with [`attach`](Self::attach).
+    pub fn is_attached(&self) -> bool {
+        self.attached.is_some()
+    }
+
     pub fn is_running(&self) -> bool {
-        self.child.is_some()
+        self.child.is_some() || self.attached.is_some()
+    }
+
+    /// How long the spawned server has been up since it became ready, or
+    /// `None` once it has stopped, or if it was [attached](Self::attach).
+    pub fn uptime(&self) -> Option<Duration> {
+        self.started_at.map(|started| started.elapsed())
+    }
+
+    /// Check whether the process has exited without blocking. Once an exit
+    /// status is returned the server is no longer considered running.
+    pub fn try_status(&mut self) -> Result<Option<ExitStatus>, SubprocessError> {
+        if let Some(pid) = self.attached {
+            if process_exists(pid) {
+                return Ok(None);
+            }
+            self.attached = None;
+            return Ok(Some(unobserved_exit()));
+        }
+        let child = self.child.as_mut().ok_or(SubprocessError::NotRunning)?;
+        match child.try_wait()? {
+            Some(status) => {
+                self.child = None;
+                self.stdin = None;
+                self.started_at = None;
+                Ok(Some(status))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Check on the server without blocking, forgetting it once it has
+    /// exited. Only reports a crash: restarting is left to the
+    /// [supervisor](Self::enable_auto_restart).
+    pub fn poll_status(&mut self) -> ServerStatus {
+        match self.try_status() {
+            Ok(Some(status)) if status.success() => ServerStatus::Exited(status.code()),
+            Ok(Some(status)) => ServerStatus::Crashed(status.code()),
+            Err(SubprocessError::NotRunning) => ServerStatus::NotRunning,
+            // A process that can't be checked is assumed to be alive
+            Ok(None) | Err(_) => ServerStatus::Running,
+        }
     }
 
     pub async fn stop(&mut self) -> Result<(), SubprocessError> {
+        if let Some(pid) = self.attached {
+            return self.stop_attached(pid).await;
+        }
         let child = self.child.as_mut().ok_or(SubprocessError::NotRunning)?;
+        self.started_at = None;
 
-        if let Some(mut stdin) = child.stdin.take() {
-            stdin
-                .write_all(b"stop\n")
+        if let Some(mut stdin) = self.stdin.take() {
+            write_command(&mut stdin, "stop")
                 .await
                 .map_err(SubprocessError::StopCommandFailed)?;
             drop(stdin);
@@ -135,20 +388,132 @@ impl PumpkinServer {
     }
 
     pub async fn kill(&mut self) -> Result<(), SubprocessError> {
+        if let Some(pid) = self.attached.take() {
+            send_signal(pid, Signal::Kill)?;
+            return Ok(());
+        }
         let child = self.child.as_mut().ok_or(SubprocessError::NotRunning)?;
         child.kill().await?;
         self.child = None;
+        self.stdin = None;
+        self.started_at = None;
+        Ok(())
+    }
+
+    async fn stop_attached(&mut self, pid: u32) -> Result<(), SubprocessError> {
+        send_signal(pid, Signal::Terminate)?;
+
+        let graceful_timeout = Duration::from_secs(30);
+        let exited = timeout(graceful_timeout, async {
+            let mut poll = interval(ATTACHED_POLL_INTERVAL);
+            while process_exists(pid) {
+                poll.tick().await;
+            }
+        })
+        .await;
+
+        if exited.is_err() {
+            send_signal(pid, Signal::Kill)?;
+        }
+        self.attached = None;
         Ok(())
     }
 }
 
+/// Write `cmd` as one line of console input and flush it.
+async fn write_command(stdin: &mut ChildStdin, cmd: &str) -> std::io::Result<()> {
+    stdin.write_all(format!("{}\n", cmd).as_bytes()).await?;
+    stdin.flush().await
+}
+
+/// How often [`PumpkinServer::stop`] checks whether an attached process exited.
+const ATTACHED_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+enum Signal {
+    Terminate,
+    Kill,
+}
+
+#[cfg(unix)]
+fn process_exists(pid: u32) -> bool {
+    // Signal 0 only checks that the process exists. EPERM means it does but
+    // belongs to another user.
+    let result = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn process_exists(_pid: u32) -> bool {
+    false
+}
+
+/// Signal a process, treating one that already exited as success.
+#[cfg(unix)]
+fn send_signal(pid: u32, signal: Signal) -> std::io::Result<()> {
+    let signal = match signal {
+        Signal::Terminate => libc::SIGTERM,
+        Signal::Kill => libc::SIGKILL,
+    };
+    if unsafe { libc::kill(pid as libc::pid_t, signal) } == 0 {
+        return Ok(());
+    }
+    let error = std::io::Error::last_os_error();
+    match error.raw_os_error() {
+        Some(libc::ESRCH) => Ok(()),
+        _ => Err(error),
+    }
+}
+
+#[cfg(not(unix))]
+fn send_signal(_pid: u32, _signal: Signal) -> std::io::Result<()> {
+    Err(std::io::ErrorKind::Unsupported.into())
+}
+
+/// Exit status reported for an attached process, whose real status belongs to
+/// its parent.
+#[cfg(unix)]
+fn unobserved_exit() -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    ExitStatus::from_raw(0)
+}
+
+#[cfg(windows)]
+fn unobserved_exit() -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(0)
+}
+
+/// Forcibly end a spawned server and any processes it started, without
+/// waiting for them to exit.
+#[cfg(unix)]
+fn kill_process_tree(pid: u32) {
+    // `start` makes the server lead its own process group, so signalling the
+    // group reaches its children too
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(windows)]
+fn kill_process_tree(pid: u32) {
+    let _ = std::process::Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+#[cfg(not(any(unix, windows)))]
+fn kill_process_tree(_pid: u32) {}
+
 impl Drop for PumpkinServer {
     fn drop(&mut self) {
-        if let Some(child) = self.child.take() {
-            let _ = std::process::Command::new("kill")
-                .arg("-9")
-                .arg(child.id().unwrap().to_string())
-                .spawn();
+        if let Some(mut child) = self.child.take() {
+            // A child without a PID has already been reaped
+            if let Some(pid) = child.id() {
+                kill_process_tree(pid);
+            }
+            let _ = child.start_kill();
         }
     }
 }
@@ -0,0 +1,53 @@
+//! Forwarding of a running server's output.
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::mpsc;
+
+/// Lines a log stream holds for its receiver. Lines past this are dropped
+/// rather than stalling the server while the receiver catches up.
+pub const LOG_CHANNEL_CAPACITY: usize = 1024;
+
+/// Which of the server's outputs a [`LogLine`] was written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// One line of server output, without its line ending.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    pub stream: Stream,
+    pub text: String,
+}
+
+impl LogLine {
+    pub(crate) fn new(stream: Stream, text: String) -> Self {
+        Self { stream, text }
+    }
+}
+
+/// Read `output` line by line on a background task, forwarding each line to
+/// `sender`. The task keeps draining the pipe even once nobody is listening,
+/// so the server never blocks on a full pipe, and ends when the pipe closes
+/// as the server exits.
+pub(crate) fn forward<R>(output: R, stream: Stream, sender: mpsc::Sender<LogLine>)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    forward_lines(BufReader::new(output), stream, sender);
+}
+
+/// [`forward`] for output already partly read through a [`BufReader`],
+/// which may hold lines that are not read yet.
+pub(crate) fn forward_lines<R>(output: BufReader<R>, stream: Stream, sender: mpsc::Sender<LogLine>)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = output.lines();
+        while let Ok(Some(text)) = lines.next_line().await {
+            let _ = sender.try_send(LogLine::new(stream, text));
+        }
+    });
+}
@@ -0,0 +1,68 @@
+//! Files a fresh server needs before its first start.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Settings written to `server.properties` by
+/// [`PumpkinServer::ensure_config`](crate::PumpkinServer::ensure_config).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerProperties {
+    pub server_port: u16,
+    /// Authenticate players with Mojang. Off by default so the client can
+    /// join a local server without an account.
+    pub online_mode: bool,
+    pub motd: String,
+    pub max_players: u32,
+    /// Further `key=value` pairs, written after the ones above.
+    pub extra: Vec<(String, String)>,
+    /// Replace `eula.txt` and `server.properties` if they already exist
+    /// instead of leaving them alone.
+    pub overwrite: bool,
+}
+
+impl Default for ServerProperties {
+    fn default() -> Self {
+        Self {
+            server_port: 25565,
+            online_mode: false,
+            motd: "A Ferrum server".to_string(),
+            max_players: 20,
+            extra: Vec::new(),
+            overwrite: false,
+        }
+    }
+}
+
+impl ServerProperties {
+    /// The contents of `server.properties`.
+    pub fn to_properties(&self) -> String {
+        let mut content = String::from("# Generated by Ferrum\n");
+        let mut line = |key: &str, value: &str| {
+            content.push_str(key);
+            content.push('=');
+            content.push_str(value);
+            content.push('\n');
+        };
+        line("server-port", &self.server_port.to_string());
+        line("online-mode", &self.online_mode.to_string());
+        line("motd", &self.motd);
+        line("max-players", &self.max_players.to_string());
+        for (key, value) in &self.extra {
+            line(key, value);
+        }
+        content
+    }
+}
+
+/// Contents of an `eula.txt` accepting the Minecraft EULA.
+pub(crate) const EULA_ACCEPTED: &str =
+    "# Accepted by Ferrum, see https://aka.ms/MinecraftEULA\neula=true\n";
+
+/// Write `content` to `path` unless it exists and `overwrite` is false.
+pub(crate) fn write_file(path: &Path, content: &str, overwrite: bool) -> io::Result<()> {
+    if !overwrite && path.exists() {
+        return Ok(());
+    }
+    fs::write(path, content)
+}
@@ -0,0 +1,164 @@
+//! Conditions that mark a starting server as ready.
+
+use regex::Regex;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+/// Interval between connection attempts for [`ReadinessMatcher::Port`].
+pub(crate) const PORT_PROBE_INTERVAL: Duration = Duration::from_millis(100);
+const PORT_CONNECT_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// When [`PumpkinServer::start`](crate::PumpkinServer::start) considers the
+/// server ready.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ReadinessMatcher {
+    /// The vanilla "Done (X.XXXs)!" or Pumpkin "Server is now running" line.
+    #[default]
+    DoneMessage,
+    /// A log line containing this text.
+    LogContains(String),
+    /// A log line matching this regular expression.
+    LogMatches(LogPattern),
+    /// The address accepts TCP connections.
+    Port(SocketAddr),
+    /// Ready as soon as any of the matchers is. An empty list never is.
+    Any(Vec<ReadinessMatcher>),
+    /// Ready once every matcher has been satisfied, in any order.
+    All(Vec<ReadinessMatcher>),
+}
+
+/// A compiled regular expression for [`ReadinessMatcher::LogMatches`].
+/// Patterns compare equal when their source text is the same.
+#[derive(Debug, Clone)]
+pub struct LogPattern(Regex);
+
+impl LogPattern {
+    /// Compile `pattern`, failing if it is not a valid regular expression.
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern).map(Self)
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    pub fn is_match(&self, line: &str) -> bool {
+        self.0.is_match(line)
+    }
+}
+
+impl PartialEq for LogPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for LogPattern {}
+
+impl ReadinessMatcher {
+    /// Whether any condition needs the port to be probed.
+    pub(crate) fn watches_port(&self) -> bool {
+        match self {
+            ReadinessMatcher::Port(_) => true,
+            ReadinessMatcher::Any(matchers) | ReadinessMatcher::All(matchers) => {
+                matchers.iter().any(Self::watches_port)
+            }
+            _ => false,
+        }
+    }
+
+    /// Visit leaf conditions in a fixed order, numbering them from 0.
+    fn for_each_leaf<'a>(&'a self, next: &mut usize, f: &mut impl FnMut(usize, &'a Self)) {
+        match self {
+            ReadinessMatcher::Any(matchers) | ReadinessMatcher::All(matchers) => {
+                for matcher in matchers {
+                    matcher.for_each_leaf(next, f);
+                }
+            }
+            leaf => {
+                f(*next, leaf);
+                *next += 1;
+            }
+        }
+    }
+
+    fn is_satisfied(&self, next: &mut usize, met: &[bool]) -> bool {
+        match self {
+            ReadinessMatcher::Any(matchers) => {
+                let results: Vec<bool> =
+                    matchers.iter().map(|m| m.is_satisfied(next, met)).collect();
+                results.contains(&true)
+            }
+            ReadinessMatcher::All(matchers) => {
+                let results: Vec<bool> =
+                    matchers.iter().map(|m| m.is_satisfied(next, met)).collect();
+                !results.contains(&false)
+            }
+            _ => {
+                let satisfied = met[*next];
+                *next += 1;
+                satisfied
+            }
+        }
+    }
+}
+
+/// Which leaf conditions of a matcher have been seen so far. Conditions stay
+/// met once seen, so `All` does not require them at the same time.
+pub(crate) struct ReadinessTracker<'a> {
+    matcher: &'a ReadinessMatcher,
+    met: Vec<bool>,
+}
+
+impl<'a> ReadinessTracker<'a> {
+    pub(crate) fn new(matcher: &'a ReadinessMatcher) -> Self {
+        let mut leaves = 0;
+        matcher.for_each_leaf(&mut 0, &mut |_, _| leaves += 1);
+        Self {
+            matcher,
+            met: vec![false; leaves],
+        }
+    }
+
+    pub(crate) fn is_ready(&self) -> bool {
+        self.matcher.is_satisfied(&mut 0, &self.met)
+    }
+
+    pub(crate) fn observe_line(&mut self, line: &str) {
+        let met = &mut self.met;
+        self.matcher.for_each_leaf(&mut 0, &mut |i, leaf| {
+            let matched = match leaf {
+                ReadinessMatcher::DoneMessage => {
+                    // Support both vanilla ("Done (X.XXXs)!") and Pumpkin ("Started server; took
+                    // Xms")
+                    (line.contains("Done") && line.contains("s)!"))
+                        || line.contains("Server is now running")
+                }
+                ReadinessMatcher::LogContains(text) => line.contains(text.as_str()),
+                ReadinessMatcher::LogMatches(pattern) => pattern.is_match(line),
+                _ => false,
+            };
+            met[i] |= matched;
+        });
+    }
+
+    pub(crate) async fn probe_ports(&mut self) {
+        let mut pending = Vec::new();
+        self.matcher.for_each_leaf(&mut 0, &mut |i, leaf| {
+            if let ReadinessMatcher::Port(addr) = leaf {
+                pending.push((i, *addr));
+            }
+        });
+
+        for (i, addr) in pending {
+            if self.met[i] {
+                continue;
+            }
+            if let Ok(Ok(_)) = timeout(PORT_CONNECT_TIMEOUT, TcpStream::connect(addr)).await {
+                self.met[i] = true;
+            }
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Restart-on-crash supervision for a local [`PumpkinServer`].
+//!
+//! The supervisor polls the child with [`PumpkinServer::try_status`]. A
+//! requested stop or a zero exit code is treated as a clean shutdown; any other
+//! exit is a crash and triggers a restart with exponential backoff until
+//! [`SupervisorConfig::max_restarts`] is reached.
+
+use crate::{PumpkinServer, SubprocessError};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::time::sleep;
+
+#[derive(Debug, Clone)]
+pub struct SupervisorConfig {
+    /// Consecutive restarts attempted before giving up.
+    pub max_restarts: u32,
+    /// Delay before the first restart. Doubles on every further attempt.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// How often the process is checked for an exit.
+    pub poll_interval: Duration,
+    /// Uptime after which a crash no longer counts towards `max_restarts`.
+    pub stable_uptime: Duration,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            poll_interval: Duration::from_millis(500),
+            stable_uptime: Duration::from_secs(60),
+        }
+    }
+}
+
+impl SupervisorConfig {
+    /// Delay before restart `attempt`, counting from 1.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Lifecycle notifications, e.g. for showing "server restarting" in the UI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SupervisorEvent {
+    Started,
+    Crashed { exit_code: Option<i32> },
+    Restarting { attempt: u32, delay: Duration },
+    Restarted { attempt: u32 },
+    RestartFailed { attempt: u32, reason: String },
+    GaveUp { attempts: u32 },
+    Stopped,
+}
+
+/// Requests a clean shutdown of a running supervisor.
+#[derive(Clone)]
+pub struct SupervisorHandle {
+    stop_tx: Arc<watch::Sender<bool>>,
+}
+
+impl SupervisorHandle {
+    pub fn stop(&self) {
+        let _ = self.stop_tx.send(true);
+    }
+}
+
+pub struct ServerSupervisor {
+    server: PumpkinServer,
+    config: SupervisorConfig,
+    events: mpsc::UnboundedSender<SupervisorEvent>,
+    stop_tx: Arc<watch::Sender<bool>>,
+    stop_rx: watch::Receiver<bool>,
+}
+
+impl ServerSupervisor {
+    pub fn new(
+        server: PumpkinServer,
+        config: SupervisorConfig,
+    ) -> (Self, mpsc::UnboundedReceiver<SupervisorEvent>) {
+        let (events, events_rx) = mpsc::unbounded_channel();
+        let (stop_tx, stop_rx) = watch::channel(false);
+        let supervisor = Self {
+            server,
+            config,
+            events,
+            stop_tx: Arc::new(stop_tx),
+            stop_rx,
+        };
+        (supervisor, events_rx)
+    }
+
+    pub fn handle(&self) -> SupervisorHandle {
+        SupervisorHandle {
+            stop_tx: self.stop_tx.clone(),
+        }
+    }
+
+    /// Start the server if needed and keep it alive until a clean stop or
+    /// until restarts are exhausted.
+    pub async fn run(mut self) -> Result<(), SubprocessError> {
+        if !self.server.is_running() {
+            self.server.start().await?;
+        }
+        self.emit(SupervisorEvent::Started);
+
+        let mut attempts = 0;
+
+        loop {
+            // Uptime is taken just before each check, as an exited server
+            // has none
+            let (status, uptime) = loop {
+                if *self.stop_rx.borrow() {
+                    match self.server.stop().await {
+                        Ok(()) | Err(SubprocessError::NotRunning) => {}
+                        Err(e) => return Err(e),
+                    }
+                    self.emit(SupervisorEvent::Stopped);
+                    return Ok(());
+                }
+                let uptime = self.server.uptime();
+                if let Some(status) = self.server.try_status()? {
+                    break (status, uptime);
+                }
+                tokio::select! {
+                    _ = self.stop_rx.changed() => {}
+                    _ = sleep(self.config.poll_interval) => {}
+                }
+            };
+
+            if status.success() {
+                self.emit(SupervisorEvent::Stopped);
+                return Ok(());
+            }
+            self.emit(SupervisorEvent::Crashed {
+                exit_code: status.code(),
+            });
+
+            if uptime.is_some_and(|uptime| uptime >= self.config.stable_uptime) {
+                attempts = 0;
+            }
+
+            loop {
+                if attempts >= self.config.max_restarts {
+                    self.emit(SupervisorEvent::GaveUp { attempts });
+                    return Ok(());
+                }
+                attempts += 1;
+
+                let delay = self.config.backoff(attempts);
+                self.emit(SupervisorEvent::Restarting {
+                    attempt: attempts,
+                    delay,
+                });
+                tokio::select! {
+                    _ = self.stop_rx.changed() => {}
+                    _ = sleep(delay) => {}
+                }
+                if *self.stop_rx.borrow() {
+                    self.emit(SupervisorEvent::Stopped);
+                    return Ok(());
+                }
+
+                match self.server.start().await {
+                    Ok(()) => {
+                        self.emit(SupervisorEvent::Restarted { attempt: attempts });
+                        break;
+                    }
+                    Err(e) => self.emit(SupervisorEvent::RestartFailed {
+                        attempt: attempts,
+                        reason: e.to_string(),
+                    }),
+                }
+            }
+        }
+    }
+
+    fn emit(&self, event: SupervisorEvent) {
+        let _ = self.events.send(event);
+    }
+}
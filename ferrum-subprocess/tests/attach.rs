@@ -0,0 +1,102 @@
+#![cfg(unix)]
+
+use ferrum_subprocess::{PumpkinServer, SubprocessError};
+use std::path::PathBuf;
+use std::process::{Child, Command};
+use std::time::Duration;
+
+fn spawn_helper() -> Child {
+    Command::new("sleep").arg("30").spawn().unwrap()
+}
+
+fn unmanaged() -> PumpkinServer {
+    PumpkinServer::new(PathBuf::from("pumpkin"))
+}
+
+#[tokio::test]
+async fn test_attach_reports_liveness() {
+    let mut helper = spawn_helper();
+    let mut server = unmanaged();
+    assert_eq!(server.pid(), None);
+
+    server.attach(helper.id()).unwrap();
+    assert!(server.is_running());
+    assert!(server.is_attached());
+    assert_eq!(server.pid(), Some(helper.id()));
+    assert!(
+        server.try_status().unwrap().is_none(),
+        "helper is still alive"
+    );
+
+    assert!(matches!(
+        server.attach(helper.id()),
+        Err(SubprocessError::AlreadyRunning)
+    ));
+
+    helper.kill().unwrap();
+    helper.wait().unwrap();
+
+    let status = server.try_status().unwrap();
+    assert!(status.is_some(), "exit of the attached process is detected");
+    assert!(!server.is_running());
+    assert_eq!(server.pid(), None);
+}
+
+#[tokio::test]
+async fn test_attach_to_missing_process_fails() {
+    let mut helper = spawn_helper();
+    let pid = helper.id();
+    helper.kill().unwrap();
+    helper.wait().unwrap();
+
+    let mut server = unmanaged();
+    assert!(matches!(
+        server.attach(pid),
+        Err(SubprocessError::NotRunning)
+    ));
+    assert!(!server.is_running());
+}
+
+#[tokio::test]
+async fn test_stop_attached_terminates_process() {
+    let mut helper = spawn_helper();
+    let mut server = unmanaged();
+    server.attach(helper.id()).unwrap();
+
+    // The test process is the helper's parent and has to reap it
+    let reaper = tokio::task::spawn_blocking(move || helper.wait().unwrap());
+
+    tokio::time::timeout(Duration::from_secs(10), server.stop())
+        .await
+        .expect("SIGTERM stops the helper well before the grace period")
+        .unwrap();
+    assert!(!server.is_running());
+
+    let status = reaper.await.unwrap();
+    assert!(!status.success(), "helper was terminated by a signal");
+}
+
+#[tokio::test]
+async fn test_spawned_server_exposes_pid() {
+    use std::io::Write;
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = std::env::temp_dir().join(format!("mock_pumpkin_pid_{}", std::process::id()));
+    let mut file = std::fs::File::create(&path).unwrap();
+    file.write_all(b"#!/bin/bash\necho \"Done (0.1s)!\"\nread -r line\n")
+        .unwrap();
+    drop(file);
+    let mut perms = std::fs::metadata(&path).unwrap().permissions();
+    perms.set_mode(0o755);
+    std::fs::set_permissions(&path, perms).unwrap();
+
+    let mut server = PumpkinServer::new(path.clone());
+    server.start().await.unwrap();
+    let pid = server.pid().expect("spawned server has a pid");
+    assert!(pid > 0);
+    assert!(!server.is_attached());
+
+    server.stop().await.unwrap();
+    assert_eq!(server.pid(), None);
+    let _ = std::fs::remove_file(path);
+}
@@ -0,0 +1,180 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::write_mock_binary;
+use ferrum_subprocess::{
+    PumpkinServer, ServerStatus, ServerSupervisor, SupervisorConfig, SupervisorEvent,
+};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// A server that records each start in `runs`, then exits with `code`
+/// after `uptime` seconds.
+fn crashing_server(name: &str, runs: &Path, uptime: &str, code: i32) -> PathBuf {
+    let _ = std::fs::remove_file(runs);
+    write_mock_binary(
+        name,
+        &format!(
+            "#!/bin/bash\necho run >> {}\necho \"Done (0.010s)!\"\nsleep {}\nexit {}\n",
+            runs.display(),
+            uptime,
+            code
+        ),
+    )
+}
+
+fn run_count(runs: &Path) -> usize {
+    std::fs::read_to_string(runs).map_or(0, |runs| runs.lines().count())
+}
+
+fn drain(events: &mut UnboundedReceiver<SupervisorEvent>) -> Vec<SupervisorEvent> {
+    let mut out = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        out.push(event);
+    }
+    out
+}
+
+/// Poll until the server stops reporting itself as running.
+async fn next_exit(server: &mut PumpkinServer) -> ServerStatus {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            match server.poll_status() {
+                ServerStatus::Running => tokio::time::sleep(Duration::from_millis(20)).await,
+                status => return status,
+            }
+        }
+    })
+    .await
+    .expect("server should exit")
+}
+
+#[tokio::test]
+async fn test_poll_status_tells_exits_from_crashes() {
+    let runs = std::env::temp_dir().join(format!("clean_runs_{}", std::process::id()));
+    let clean = crashing_server("mock_pumpkin_clean_exit", &runs, "0.1", 0);
+    let mut server = PumpkinServer::new(clean.clone());
+    assert_eq!(server.poll_status(), ServerStatus::NotRunning);
+
+    server.start().await.unwrap();
+    assert_eq!(server.poll_status(), ServerStatus::Running);
+    assert!(server.uptime().is_some());
+    assert_eq!(next_exit(&mut server).await, ServerStatus::Exited(Some(0)));
+    assert!(!server.is_running());
+    assert_eq!(server.uptime(), None);
+    assert_eq!(server.poll_status(), ServerStatus::NotRunning);
+
+    let runs = std::env::temp_dir().join(format!("crash_runs_{}", std::process::id()));
+    let crashing = crashing_server("mock_pumpkin_crash", &runs, "0.1", 3);
+    let mut server = PumpkinServer::new(crashing.clone());
+    server.start().await.unwrap();
+    assert_eq!(next_exit(&mut server).await, ServerStatus::Crashed(Some(3)));
+    assert!(!server.is_running());
+
+    // Polling only reports the crash, nothing brings the server back
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(server.poll_status(), ServerStatus::NotRunning);
+    assert_eq!(run_count(&runs), 1);
+
+    for path in [clean, crashing, runs] {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[tokio::test]
+async fn test_stop_clears_uptime() {
+    let mock = write_mock_binary(
+        "mock_pumpkin_stoppable",
+        r#"#!/bin/bash
+echo "Done (0.010s)!"
+while read -r line; do
+    if [ "$line" = "stop" ]; then
+        exit 0
+    fi
+done
+"#,
+    );
+    let mut server = PumpkinServer::new(mock.clone());
+    server.start().await.unwrap();
+    assert!(server.uptime().is_some());
+
+    server.stop().await.unwrap();
+    assert_eq!(server.uptime(), None);
+
+    let _ = std::fs::remove_file(mock);
+}
+
+#[tokio::test]
+async fn test_auto_restart_gives_up_after_max_restarts() {
+    let runs = std::env::temp_dir().join(format!("restart_runs_{}", std::process::id()));
+    let mock = crashing_server("mock_pumpkin_crash_loop", &runs, "0", 1);
+    let (supervisor, mut events) =
+        PumpkinServer::new(mock.clone()).enable_auto_restart(2, Duration::from_millis(10));
+
+    tokio::time::timeout(Duration::from_secs(10), supervisor.run())
+        .await
+        .expect("supervisor should give up before the timeout")
+        .unwrap();
+
+    // Each restart waits twice as long as the one before
+    let delays: Vec<_> = drain(&mut events)
+        .into_iter()
+        .filter_map(|event| match event {
+            SupervisorEvent::Restarting { delay, .. } => Some(delay),
+            SupervisorEvent::GaveUp { attempts } => {
+                assert_eq!(attempts, 2);
+                None
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        delays,
+        [Duration::from_millis(10), Duration::from_millis(20)]
+    );
+    assert_eq!(run_count(&runs), 3);
+
+    let _ = std::fs::remove_file(mock);
+    let _ = std::fs::remove_file(runs);
+}
+
+#[tokio::test]
+async fn test_restarts_reset_after_stable_uptime() {
+    let runs = std::env::temp_dir().join(format!("stable_runs_{}", std::process::id()));
+    let mock = crashing_server("mock_pumpkin_rare_crash", &runs, "0.4", 1);
+    let config = SupervisorConfig {
+        max_restarts: 1,
+        initial_backoff: Duration::from_millis(10),
+        poll_interval: Duration::from_millis(10),
+        stable_uptime: Duration::from_millis(200),
+        ..SupervisorConfig::default()
+    };
+    let (supervisor, mut events) = ServerSupervisor::new(PumpkinServer::new(mock.clone()), config);
+    let handle = supervisor.handle();
+    let task = tokio::spawn(supervisor.run());
+
+    // Every run stays up past the stable uptime, so a single allowed restart
+    // keeps covering each crash
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while run_count(&runs) < 4 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("server should be restarted three times");
+    handle.stop();
+    task.await.unwrap().unwrap();
+
+    let events = drain(&mut events);
+    assert!(!events
+        .iter()
+        .any(|event| matches!(event, SupervisorEvent::GaveUp { .. })));
+    assert!(events
+        .iter()
+        .all(|event| !matches!(event, SupervisorEvent::Restarted { attempt } if *attempt != 1)));
+
+    let _ = std::fs::remove_file(mock);
+    let _ = std::fs::remove_file(runs);
+}
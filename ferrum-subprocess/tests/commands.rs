@@ -0,0 +1,86 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::write_mock_binary;
+use ferrum_subprocess::{PumpkinServer, SubprocessError};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_commands_reach_the_server_console() {
+    let mock = write_mock_binary(
+        "mock_pumpkin_console",
+        r#"#!/bin/bash
+echo "Done (0.010s)!"
+while read -r line; do
+    if [ "$line" = "stop" ]; then
+        exit 0
+    fi
+    echo "> $line"
+done
+"#,
+    );
+
+    let mut server = PumpkinServer::new(mock.clone());
+    server.start().await.unwrap();
+    let mut stream = server.take_log_stream().unwrap();
+
+    // Each command is flushed on its own, so the server answers before the
+    // next one is sent
+    for cmd in ["list", "say hello", "tp Steve 0 64 0"] {
+        server.send_command(cmd).await.unwrap();
+        let reply = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let line = stream.recv().await.unwrap();
+                if line.text.starts_with('>') {
+                    return line.text;
+                }
+            }
+        })
+        .await
+        .expect("server should echo the command");
+        assert_eq!(reply, format!("> {}", cmd));
+    }
+
+    // The console is still open for stop
+    server.stop().await.unwrap();
+    assert!(!server.is_running());
+    assert!(matches!(
+        server.send_command("list").await,
+        Err(SubprocessError::NotRunning)
+    ));
+
+    let _ = std::fs::remove_file(mock);
+}
+
+#[tokio::test]
+async fn test_send_command_fails_once_the_server_exits() {
+    let mock = write_mock_binary(
+        "mock_pumpkin_short_lived",
+        r#"#!/bin/bash
+echo "Done (0.010s)!"
+exit 0
+"#,
+    );
+
+    let mut server = PumpkinServer::new(mock.clone());
+    assert!(matches!(
+        server.send_command("list").await,
+        Err(SubprocessError::NotRunning)
+    ));
+
+    server.start().await.unwrap();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while server.try_status().unwrap().is_none() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("server should exit");
+    assert!(matches!(
+        server.send_command("list").await,
+        Err(SubprocessError::NotRunning)
+    ));
+
+    let _ = std::fs::remove_file(mock);
+}
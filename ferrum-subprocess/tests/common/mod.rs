@@ -0,0 +1,18 @@
+use std::path::PathBuf;
+
+/// Write `script` to an executable file in the temp directory.
+pub fn write_mock_binary(name: &str, script: &str) -> PathBuf {
+    use std::fs;
+    use std::io::Write;
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = std::env::temp_dir().join(format!("{}_{}", name, std::process::id()));
+    let mut file = fs::File::create(&path).unwrap();
+    file.write_all(script.as_bytes()).unwrap();
+    drop(file);
+
+    let mut perms = fs::metadata(&path).unwrap().permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&path, perms).unwrap();
+    path
+}
@@ -151,3 +151,65 @@ sleep 100
     let _ = server.kill().await;
     let _ = fs::remove_file(mock_path);
 }
+
+/// Whether a process is still running. Zombies waiting to be reaped count
+/// as exited.
+fn process_alive(pid: &str) -> bool {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "stat=", "-p", pid])
+        .output()
+        .unwrap();
+    let state = String::from_utf8_lossy(&output.stdout);
+    !state.trim().is_empty() && !state.trim().starts_with('Z')
+}
+
+#[tokio::test]
+async fn test_drop_kills_server_and_its_children() {
+    let mock_script = r#"#!/bin/bash
+sleep 100 &
+echo "Worker $!"
+echo "Done (0.123s)!"
+wait
+"#;
+
+    let temp_dir = std::env::temp_dir();
+    let mock_path = temp_dir.join(format!("mock_pumpkin_forking_{}", std::process::id()));
+
+    use std::fs;
+    use std::io::Write;
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut file = fs::File::create(&mock_path).unwrap();
+    file.write_all(mock_script.as_bytes()).unwrap();
+    drop(file);
+
+    let mut perms = fs::metadata(&mock_path).unwrap().permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&mock_path, perms).unwrap();
+
+    let mut server = ferrum_subprocess::PumpkinServer::new(mock_path.clone());
+    server.start().await.unwrap();
+    let server_pid = server.pid().unwrap().to_string();
+    let mut logs = server.take_log_stream().unwrap();
+    let worker_pid = loop {
+        let line = logs.recv().await.unwrap();
+        if let Some(pid) = line.text.strip_prefix("Worker ") {
+            break pid.to_string();
+        }
+    };
+    assert!(process_alive(&server_pid));
+    assert!(process_alive(&worker_pid));
+
+    drop(server);
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while (process_alive(&server_pid) || process_alive(&worker_pid))
+        && std::time::Instant::now() < deadline
+    {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert!(!process_alive(&server_pid), "server should be killed on drop");
+    assert!(!process_alive(&worker_pid), "server's children should be killed on drop");
+
+    let _ = fs::remove_file(mock_path);
+}
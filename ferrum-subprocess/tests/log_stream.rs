@@ -0,0 +1,70 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::write_mock_binary;
+use ferrum_subprocess::{LogLine, PumpkinServer, Stream};
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn texts(lines: &[LogLine], stream: Stream) -> Vec<&str> {
+    lines
+        .iter()
+        .filter(|line| line.stream == stream)
+        .map(|line| line.text.as_str())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_log_stream_forwards_output_until_exit() {
+    let mock = write_mock_binary(
+        "mock_pumpkin_chatty",
+        r#"#!/bin/bash
+echo "Loading world" >&2
+echo "Done (0.010s)!"
+sleep 0.2
+echo "Steve joined the game"
+echo "Can't keep up!" >&2
+echo "Steve left the game"
+exit 0
+"#,
+    );
+
+    let mut server = PumpkinServer::new(mock.clone());
+    server.start().await.unwrap();
+    let mut stream = server
+        .take_log_stream()
+        .expect("started server has a log stream");
+    assert!(server.take_log_stream().is_none());
+
+    // The stream ends once the server has exited and its readers are done
+    let mut lines = Vec::new();
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while let Some(line) = stream.recv().await {
+            lines.push(line);
+        }
+    })
+    .await
+    .expect("log stream should end when the server exits");
+
+    assert_eq!(
+        texts(&lines, Stream::Stdout),
+        [
+            "Done (0.010s)!",
+            "Steve joined the game",
+            "Steve left the game"
+        ]
+    );
+    assert_eq!(
+        texts(&lines, Stream::Stderr),
+        ["Loading world", "Can't keep up!"]
+    );
+
+    let _ = std::fs::remove_file(mock);
+}
+
+#[test]
+fn test_no_log_stream_before_start() {
+    let mut server = PumpkinServer::new(PathBuf::from("pumpkin"));
+    assert!(server.take_log_stream().is_none());
+}
@@ -0,0 +1,59 @@
+use ferrum_subprocess::{PumpkinServer, ServerProperties};
+use std::fs;
+use std::path::PathBuf;
+
+fn temp_server_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_ensure_config_writes_eula_and_properties() {
+    let dir = temp_server_dir("ferrum_server_config");
+    let server = PumpkinServer::new(PathBuf::from("pumpkin")).with_working_dir(&dir);
+    let props = ServerProperties {
+        server_port: 25570,
+        online_mode: false,
+        extra: vec![("view-distance".to_string(), "12".to_string())],
+        ..Default::default()
+    };
+
+    server.ensure_config(&props).unwrap();
+
+    let eula = fs::read_to_string(dir.join("eula.txt")).unwrap();
+    assert!(eula.lines().any(|line| line == "eula=true"));
+
+    let properties = fs::read_to_string(dir.join("server.properties")).unwrap();
+    let lines: Vec<&str> = properties.lines().collect();
+    assert!(lines.contains(&"server-port=25570"));
+    assert!(lines.contains(&"online-mode=false"));
+    assert!(lines.contains(&"max-players=20"));
+    assert!(lines.contains(&"view-distance=12"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_ensure_config_keeps_existing_files_unless_overwriting() {
+    let dir = temp_server_dir("ferrum_server_config_existing");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("server.properties"), "server-port=30000\n").unwrap();
+    let server = PumpkinServer::new(PathBuf::from("pumpkin")).with_working_dir(&dir);
+
+    server.ensure_config(&ServerProperties::default()).unwrap();
+    let properties = fs::read_to_string(dir.join("server.properties")).unwrap();
+    assert_eq!(properties, "server-port=30000\n");
+    assert!(dir.join("eula.txt").exists());
+
+    let props = ServerProperties {
+        overwrite: true,
+        ..Default::default()
+    };
+    server.ensure_config(&props).unwrap();
+    let properties = fs::read_to_string(dir.join("server.properties")).unwrap();
+    assert_eq!(properties, props.to_properties());
+    assert!(properties.contains("server-port=25565\n"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,250 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::write_mock_binary;
+use ferrum_subprocess::{PumpkinServer, ReadinessMatcher, SubprocessError};
+use std::net::{SocketAddr, TcpListener};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// A port nothing is listening on.
+fn closed_port() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap()
+}
+
+#[tokio::test]
+async fn test_any_succeeds_when_only_port_opens() {
+    let mock = write_mock_binary(
+        "mock_pumpkin_quiet",
+        r#"#!/bin/bash
+echo "Starting Pumpkin server..."
+while read -r line; do
+    if [ "$line" = "stop" ]; then
+        exit 0
+    fi
+done
+"#,
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let mut server = PumpkinServer::new(mock.clone()).with_readiness(ReadinessMatcher::Any(vec![
+        ReadinessMatcher::DoneMessage,
+        ReadinessMatcher::Port(addr),
+    ]));
+    tokio::time::timeout(Duration::from_secs(10), server.start())
+        .await
+        .expect("port should make the server ready without the done message")
+        .unwrap();
+    assert!(server.is_running());
+
+    server.stop().await.unwrap();
+    let _ = std::fs::remove_file(mock);
+}
+
+#[tokio::test]
+async fn test_any_succeeds_on_log_with_closed_port() {
+    let mock = write_mock_binary(
+        "mock_pumpkin_logs_only",
+        r#"#!/bin/bash
+echo "Done (0.010s)!"
+while read -r line; do
+    if [ "$line" = "stop" ]; then
+        exit 0
+    fi
+done
+"#,
+    );
+
+    let mut server = PumpkinServer::new(mock.clone()).with_readiness(ReadinessMatcher::Any(vec![
+        ReadinessMatcher::Port(closed_port()),
+        ReadinessMatcher::DoneMessage,
+    ]));
+    tokio::time::timeout(Duration::from_secs(10), server.start())
+        .await
+        .unwrap()
+        .unwrap();
+
+    server.stop().await.unwrap();
+    let _ = std::fs::remove_file(mock);
+}
+
+#[tokio::test]
+async fn test_all_waits_for_both_conditions() {
+    let mock = write_mock_binary(
+        "mock_pumpkin_slow_done",
+        r#"#!/bin/bash
+sleep 0.5
+echo "Done (0.500s)!"
+while read -r line; do
+    if [ "$line" = "stop" ]; then
+        exit 0
+    fi
+done
+"#,
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let mut server = PumpkinServer::new(mock.clone()).with_readiness(ReadinessMatcher::All(vec![
+        ReadinessMatcher::Port(addr),
+        ReadinessMatcher::DoneMessage,
+    ]));
+    let started = Instant::now();
+    tokio::time::timeout(Duration::from_secs(10), server.start())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(
+        started.elapsed() >= Duration::from_millis(500),
+        "open port alone must not be enough"
+    );
+
+    server.stop().await.unwrap();
+    let _ = std::fs::remove_file(mock);
+}
+
+#[tokio::test]
+async fn test_all_waits_for_port_after_log() {
+    let mock = write_mock_binary(
+        "mock_pumpkin_port_later",
+        r#"#!/bin/bash
+echo "Done (0.010s)!"
+while read -r line; do
+    if [ "$line" = "stop" ]; then
+        exit 0
+    fi
+done
+"#,
+    );
+    let addr = closed_port();
+
+    let mut server = PumpkinServer::new(mock.clone()).with_readiness(ReadinessMatcher::All(vec![
+        ReadinessMatcher::DoneMessage,
+        ReadinessMatcher::Port(addr),
+    ]));
+    let opener = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        TcpListener::bind(addr).unwrap()
+    });
+
+    let started = Instant::now();
+    tokio::time::timeout(Duration::from_secs(10), server.start())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(400));
+
+    drop(opener.await.unwrap());
+    server.stop().await.unwrap();
+    let _ = std::fs::remove_file(mock);
+}
+
+#[tokio::test]
+async fn test_crash_is_detected_while_waiting_for_port() {
+    let mock = write_mock_binary(
+        "mock_pumpkin_quiet_crash",
+        r#"#!/bin/bash
+exec 1>&-
+sleep 0.1
+exit 4
+"#,
+    );
+
+    let mut server =
+        PumpkinServer::new(mock.clone()).with_readiness(ReadinessMatcher::Port(closed_port()));
+    let result = tokio::time::timeout(Duration::from_secs(10), server.start())
+        .await
+        .expect("crash should be reported before the startup timeout");
+    assert!(matches!(
+        result,
+        Err(SubprocessError::ProcessCrashed(Some(4)))
+    ));
+
+    let _ = std::fs::remove_file(mock);
+}
+
+#[tokio::test]
+async fn test_ready_pattern_matches_custom_banner() {
+    let mock = write_mock_binary(
+        "mock_pumpkin_custom_banner",
+        r#"#!/bin/bash
+echo "Serveur démarré en 42 ms"
+while read -r line; do
+    if [ "$line" = "stop" ]; then
+        exit 0
+    fi
+done
+"#,
+    );
+
+    let mut server = PumpkinServer::new(mock.clone())
+        .with_ready_pattern(r"démarré en \d+ ms".to_string())
+        .unwrap();
+    tokio::time::timeout(Duration::from_secs(10), server.start())
+        .await
+        .expect("the pattern should match the banner")
+        .unwrap();
+    assert!(server.is_running());
+
+    server.stop().await.unwrap();
+    let _ = std::fs::remove_file(mock);
+}
+
+#[test]
+fn test_invalid_ready_pattern_is_rejected() {
+    let result = PumpkinServer::new(PathBuf::from("pumpkin")).with_ready_pattern("Done (".into());
+    assert!(matches!(
+        result,
+        Err(SubprocessError::InvalidReadyPattern(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_exit_before_ready_pattern_is_a_crash() {
+    let mock = write_mock_binary(
+        "mock_pumpkin_exits_early",
+        r#"#!/bin/bash
+echo "Done (0.010s)!"
+exit 3
+"#,
+    );
+
+    let mut server = PumpkinServer::new(mock.clone())
+        .with_ready_pattern("^Listening on".to_string())
+        .unwrap();
+    let result = tokio::time::timeout(Duration::from_secs(10), server.start())
+        .await
+        .expect("exit should be reported before the startup timeout");
+    assert!(matches!(
+        result,
+        Err(SubprocessError::ProcessCrashed(Some(3)))
+    ));
+
+    let _ = std::fs::remove_file(mock);
+}
+
+#[tokio::test]
+async fn test_startup_timeout_is_configurable() {
+    let mock = write_mock_binary(
+        "mock_pumpkin_never_ready",
+        r#"#!/bin/bash
+echo "Starting Pumpkin server..."
+sleep 100
+"#,
+    );
+
+    let mut server =
+        PumpkinServer::new(mock.clone()).with_startup_timeout(Duration::from_millis(300));
+    let started = Instant::now();
+    let result = server.start().await;
+    assert!(matches!(
+        result,
+        Err(SubprocessError::StartupTimeout(timeout)) if timeout == Duration::from_millis(300)
+    ));
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    let _ = std::fs::remove_file(mock);
+}
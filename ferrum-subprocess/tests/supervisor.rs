@@ -0,0 +1,147 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::write_mock_binary;
+use ferrum_subprocess::{PumpkinServer, ServerSupervisor, SupervisorConfig, SupervisorEvent};
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+fn fast_config(max_restarts: u32) -> SupervisorConfig {
+    SupervisorConfig {
+        max_restarts,
+        initial_backoff: Duration::from_millis(10),
+        max_backoff: Duration::from_secs(1),
+        poll_interval: Duration::from_millis(10),
+        stable_uptime: Duration::from_secs(60),
+    }
+}
+
+fn drain(events: &mut UnboundedReceiver<SupervisorEvent>) -> Vec<SupervisorEvent> {
+    let mut out = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        out.push(event);
+    }
+    out
+}
+
+#[test]
+fn test_backoff_doubles_and_caps() {
+    let config = SupervisorConfig {
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(500),
+        ..SupervisorConfig::default()
+    };
+
+    assert_eq!(config.backoff(1), Duration::from_millis(100));
+    assert_eq!(config.backoff(2), Duration::from_millis(200));
+    assert_eq!(config.backoff(3), Duration::from_millis(400));
+    assert_eq!(config.backoff(4), Duration::from_millis(500));
+    assert_eq!(config.backoff(40), Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn test_crashing_server_restarts_with_growing_backoff_then_gives_up() {
+    let mock = write_mock_binary(
+        "mock_pumpkin_crash_loop",
+        r#"#!/bin/bash
+echo "Done (0.010s)!"
+sleep 0.05
+exit 3
+"#,
+    );
+
+    let (supervisor, mut events) =
+        ServerSupervisor::new(PumpkinServer::new(mock.clone()), fast_config(3));
+    tokio::time::timeout(Duration::from_secs(10), supervisor.run())
+        .await
+        .expect("supervisor should give up before the timeout")
+        .unwrap();
+
+    let events = drain(&mut events);
+    let delays: Vec<Duration> = events
+        .iter()
+        .filter_map(|e| match e {
+            SupervisorEvent::Restarting { delay, .. } => Some(*delay),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        delays,
+        vec![
+            Duration::from_millis(10),
+            Duration::from_millis(20),
+            Duration::from_millis(40)
+        ]
+    );
+
+    let crashes = events
+        .iter()
+        .filter(|e| **e == SupervisorEvent::Crashed { exit_code: Some(3) })
+        .count();
+    assert_eq!(crashes, 4, "initial run plus three restarts should crash");
+    assert_eq!(
+        events.last(),
+        Some(&SupervisorEvent::GaveUp { attempts: 3 })
+    );
+
+    let _ = std::fs::remove_file(mock);
+}
+
+#[tokio::test]
+async fn test_clean_stop_does_not_restart() {
+    let mock = write_mock_binary(
+        "mock_pumpkin_supervised",
+        r#"#!/bin/bash
+echo "Done (0.010s)!"
+while read -r line; do
+    if [ "$line" = "stop" ]; then
+        exit 0
+    fi
+done
+"#,
+    );
+
+    let (supervisor, mut events) =
+        ServerSupervisor::new(PumpkinServer::new(mock.clone()), fast_config(3));
+    let handle = supervisor.handle();
+    let task = tokio::spawn(supervisor.run());
+
+    assert_eq!(events.recv().await, Some(SupervisorEvent::Started));
+    handle.stop();
+    tokio::time::timeout(Duration::from_secs(10), task)
+        .await
+        .expect("supervisor should stop")
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(drain(&mut events), vec![SupervisorEvent::Stopped]);
+
+    let _ = std::fs::remove_file(mock);
+}
+
+#[tokio::test]
+async fn test_zero_exit_is_not_a_crash() {
+    let mock = write_mock_binary(
+        "mock_pumpkin_clean_exit",
+        r#"#!/bin/bash
+echo "Done (0.010s)!"
+sleep 0.05
+exit 0
+"#,
+    );
+
+    let (supervisor, mut events) =
+        ServerSupervisor::new(PumpkinServer::new(mock.clone()), fast_config(3));
+    tokio::time::timeout(Duration::from_secs(10), supervisor.run())
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        drain(&mut events),
+        vec![SupervisorEvent::Started, SupervisorEvent::Stopped]
+    );
+
+    let _ = std::fs::remove_file(mock);
+}
@@ -0,0 +1,135 @@
+use std::io::{self, Read, Write};
+
+/// Extra per-block state that does not fit in a block id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockEntityData {
+    /// Sign text, one string per line.
+    Sign { lines: [String; 4] },
+    /// Chests, barrels, hoppers and other slot-based storage.
+    Container { items: Vec<ContainerItem> },
+    /// Furnace progress, in ticks.
+    Furnace {
+        burn_time: u16,
+        cook_time: u16,
+        cook_time_total: u16,
+    },
+}
+
+/// A non-empty slot inside a container block entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContainerItem {
+    pub slot: u8,
+    pub item_id: u16,
+    pub count: u8,
+}
+
+const TAG_SIGN: u8 = 0;
+const TAG_CONTAINER: u8 = 1;
+const TAG_FURNACE: u8 = 2;
+
+impl BlockEntityData {
+    pub fn sign(lines: [&str; 4]) -> Self {
+        BlockEntityData::Sign {
+            lines: lines.map(str::to_string),
+        }
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            BlockEntityData::Sign { lines } => {
+                writer.write_all(&[TAG_SIGN])?;
+                for line in lines {
+                    write_string(writer, line)?;
+                }
+            }
+            BlockEntityData::Container { items } => {
+                writer.write_all(&[TAG_CONTAINER])?;
+                writer.write_all(&(items.len() as u16).to_le_bytes())?;
+                for item in items {
+                    writer.write_all(&[item.slot])?;
+                    writer.write_all(&item.item_id.to_le_bytes())?;
+                    writer.write_all(&[item.count])?;
+                }
+            }
+            BlockEntityData::Furnace {
+                burn_time,
+                cook_time,
+                cook_time_total,
+            } => {
+                writer.write_all(&[TAG_FURNACE])?;
+                writer.write_all(&burn_time.to_le_bytes())?;
+                writer.write_all(&cook_time.to_le_bytes())?;
+                writer.write_all(&cook_time_total.to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+        match read_u8(reader)? {
+            TAG_SIGN => {
+                let lines = [
+                    read_string(reader)?,
+                    read_string(reader)?,
+                    read_string(reader)?,
+                    read_string(reader)?,
+                ];
+                Ok(BlockEntityData::Sign { lines })
+            }
+            TAG_CONTAINER => {
+                let len = read_u16(reader)? as usize;
+                let mut items = Vec::with_capacity(len);
+                for _ in 0..len {
+                    items.push(ContainerItem {
+                        slot: read_u8(reader)?,
+                        item_id: read_u16(reader)?,
+                        count: read_u8(reader)?,
+                    });
+                }
+                Ok(BlockEntityData::Container { items })
+            }
+            TAG_FURNACE => Ok(BlockEntityData::Furnace {
+                burn_time: read_u16(reader)?,
+                cook_time: read_u16(reader)?,
+                cook_time_total: read_u16(reader)?,
+            }),
+            tag => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown block entity tag {}", tag),
+            )),
+        }
+    }
+}
+
+pub(crate) fn read_u8<R: Read>(reader: &mut R) -> io::Result<u8> {
+    let mut buf = [0u8; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+pub(crate) fn read_u16<R: Read>(reader: &mut R) -> io::Result<u16> {
+    let mut buf = [0u8; 2];
+    reader.read_exact(&mut buf)?;
+    Ok(u16::from_le_bytes(buf))
+}
+
+pub(crate) fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn write_string<W: Write>(writer: &mut W, value: &str) -> io::Result<()> {
+    let bytes = value.as_bytes();
+    let len = u16::try_from(bytes.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "string too long"))?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(bytes)
+}
+
+fn read_string<R: Read>(reader: &mut R) -> io::Result<String> {
+    let len = read_u16(reader)? as usize;
+    let mut buf = vec![0u8; len];
+    reader.read_exact(&mut buf)?;
+    String::from_utf8(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -1,15 +1,28 @@
-use ferrum_core::BlockId;
+use crate::block_entity::{read_u16, read_u32, read_u8, BlockEntityData};
+use ferrum_core::{properties, BlockId};
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
 
-const CHUNK_SIZE: usize = 32;
+pub(crate) const CHUNK_SIZE: usize = 32;
+
+/// Version of terrain generation and of the chunk layout. Bump it whenever
+/// either changes so chunks cached by an older build are regenerated instead
+/// of loaded.
+pub const CHUNK_GENERATION_VERSION: u32 = 1;
 
 pub struct Chunk {
     blocks: [[[BlockId; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
+    block_entities: HashMap<(u8, u8, u8), BlockEntityData>,
+    /// Positions of light-emitting blocks, kept up to date by `set_block`.
+    light_sources: Vec<(u8, u8, u8)>,
 }
 
 impl Chunk {
     pub fn new() -> Self {
         Self {
             blocks: [[[BlockId::new(0); CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
+            block_entities: HashMap::new(),
+            light_sources: Vec::new(),
         }
     }
 
@@ -20,11 +33,176 @@ impl Chunk {
         self.blocks[x][y][z]
     }
 
+    /// Set a block. Changing the block id drops any block entity at that
+    /// position.
     pub fn set_block(&mut self, x: usize, y: usize, z: usize, block_id: BlockId) {
         if x < CHUNK_SIZE && y < CHUNK_SIZE && z < CHUNK_SIZE {
+            let pos = (x as u8, y as u8, z as u8);
+            let old = self.blocks[x][y][z];
+            if old != block_id {
+                self.block_entities.remove(&pos);
+            }
             self.blocks[x][y][z] = block_id;
+
+            match (emits_light(old), emits_light(block_id)) {
+                (false, true) => self.light_sources.push(pos),
+                (true, false) => self.light_sources.retain(|&source| source != pos),
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether every block is air.
+    pub fn is_empty(&self) -> bool {
+        self.blocks
+            .iter()
+            .flatten()
+            .flatten()
+            .all(|block| block.as_u16() == 0)
+    }
+
+    /// Positions of every light-emitting block, in no particular order, so
+    /// relighting can visit the sources without scanning the whole chunk.
+    pub fn light_sources(&self) -> &[(u8, u8, u8)] {
+        &self.light_sources
+    }
+
+    pub fn get_block_entity(&self, x: usize, y: usize, z: usize) -> Option<&BlockEntityData> {
+        if x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE {
+            return None;
+        }
+        self.block_entities.get(&(x as u8, y as u8, z as u8))
+    }
+
+    pub fn get_block_entity_mut(
+        &mut self,
+        x: usize,
+        y: usize,
+        z: usize,
+    ) -> Option<&mut BlockEntityData> {
+        if x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE {
+            return None;
+        }
+        self.block_entities.get_mut(&(x as u8, y as u8, z as u8))
+    }
+
+    /// Attach data to the block at a position. Ignored for air and
+    /// out-of-bounds positions.
+    pub fn set_block_entity(&mut self, x: usize, y: usize, z: usize, data: BlockEntityData) {
+        if self.get_block(x, y, z).as_u16() == 0 {
+            return;
+        }
+        self.block_entities
+            .insert((x as u8, y as u8, z as u8), data);
+    }
+
+    pub fn remove_block_entity(&mut self, x: usize, y: usize, z: usize) -> Option<BlockEntityData> {
+        if x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE {
+            return None;
+        }
+        self.block_entities.remove(&(x as u8, y as u8, z as u8))
+    }
+
+    pub fn block_entity_count(&self) -> usize {
+        self.block_entities.len()
+    }
+
+    pub fn iter_block_entities(
+        &self,
+    ) -> impl Iterator<Item = ((u8, u8, u8), &BlockEntityData)> + '_ {
+        self.block_entities.iter().map(|(pos, data)| (*pos, data))
+    }
+
+    /// Serialize block ids followed by block entities.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for plane in &self.blocks {
+            for column in plane {
+                for block in column {
+                    writer.write_all(&block.as_u16().to_le_bytes())?;
+                }
+            }
+        }
+
+        writer.write_all(&(self.block_entities.len() as u32).to_le_bytes())?;
+        for (&(x, y, z), data) in &self.block_entities {
+            writer.write_all(&[x, y, z])?;
+            data.write_to(writer)?;
+        }
+        Ok(())
+    }
+
+    pub fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut chunk = Self::new();
+        for plane in chunk.blocks.iter_mut() {
+            for column in plane.iter_mut() {
+                for block in column.iter_mut() {
+                    *block = BlockId::new(read_u16(reader)?);
+                }
+            }
+        }
+
+        chunk.light_sources = chunk.scan_light_sources();
+
+        let count = read_u32(reader)?;
+        for _ in 0..count {
+            let pos = (read_u8(reader)?, read_u8(reader)?, read_u8(reader)?);
+            if pos.0 as usize >= CHUNK_SIZE
+                || pos.1 as usize >= CHUNK_SIZE
+                || pos.2 as usize >= CHUNK_SIZE
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("block entity position {:?} outside chunk", pos),
+                ));
+            }
+            let data = BlockEntityData::read_from(reader)?;
+            chunk.block_entities.insert(pos, data);
         }
+        Ok(chunk)
     }
+
+    fn scan_light_sources(&self) -> Vec<(u8, u8, u8)> {
+        let mut sources = Vec::new();
+        for (x, plane) in self.blocks.iter().enumerate() {
+            for (y, column) in plane.iter().enumerate() {
+                for (z, &block) in column.iter().enumerate() {
+                    if emits_light(block) {
+                        sources.push((x as u8, y as u8, z as u8));
+                    }
+                }
+            }
+        }
+        sources
+    }
+
+    /// Serialize for a chunk cache, stamped with [`CHUNK_GENERATION_VERSION`].
+    pub fn write_versioned<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&CHUNK_GENERATION_VERSION.to_le_bytes())?;
+        self.write_to(writer)
+    }
+
+    /// Read a chunk written by [`Chunk::write_versioned`]. Returns `None`
+    /// without reading the body if it was stamped with another generation
+    /// version.
+    pub fn read_versioned<R: Read>(reader: &mut R) -> io::Result<Option<Self>> {
+        if read_u32(reader)? != CHUNK_GENERATION_VERSION {
+            return Ok(None);
+        }
+        Self::read_from(reader).map(Some)
+    }
+
+    /// Load a cached chunk, discarding it in favour of `generate` when it is
+    /// from an older generation version.
+    pub fn load_or_generate<R: Read>(
+        reader: &mut R,
+        generate: impl FnOnce() -> Chunk,
+    ) -> io::Result<Self> {
+        Ok(Self::read_versioned(reader)?.unwrap_or_else(generate))
+    }
+}
+
+fn emits_light(block: BlockId) -> bool {
+    properties(block).light_emission > 0
 }
 
 impl Default for Chunk {
@@ -0,0 +1,140 @@
+use crate::chunk::CHUNK_SIZE;
+use crate::Chunk;
+use ferrum_core::BlockId;
+
+/// Lowest block y of an overworld column.
+pub const WORLD_MIN_Y: i32 = -64;
+/// Blocks from the bottom to the top of an overworld column.
+pub const WORLD_HEIGHT: u32 = 384;
+
+/// A full-height column of [`Chunk`] sections stacked from `min_y` up.
+///
+/// Sections that are all air are not allocated, so a mostly empty column
+/// costs a pointer per section and iterating it skips straight to the
+/// blocks.
+pub struct ChunkColumn {
+    min_y: i32,
+    sections: Vec<Option<Box<Chunk>>>,
+}
+
+impl ChunkColumn {
+    /// An empty overworld column, from [`WORLD_MIN_Y`] up [`WORLD_HEIGHT`]
+    /// blocks.
+    pub fn new() -> Self {
+        Self::with_height(WORLD_MIN_Y, WORLD_HEIGHT)
+    }
+
+    /// An empty column of at least `height` blocks from `min_y`, rounded up
+    /// to whole sections.
+    pub fn with_height(min_y: i32, height: u32) -> Self {
+        let count = (height as usize).div_ceil(CHUNK_SIZE);
+        Self {
+            min_y,
+            sections: (0..count).map(|_| None).collect(),
+        }
+    }
+
+    pub fn min_y(&self) -> i32 {
+        self.min_y
+    }
+
+    /// One past the highest block y.
+    pub fn max_y(&self) -> i32 {
+        self.min_y + (self.sections.len() * CHUNK_SIZE) as i32
+    }
+
+    pub fn section_count(&self) -> usize {
+        self.sections.len()
+    }
+
+    /// Sections holding any blocks.
+    pub fn allocated_sections(&self) -> usize {
+        self.sections
+            .iter()
+            .filter(|section| section.is_some())
+            .count()
+    }
+
+    /// Index of the section containing world `y`, if it is in the column.
+    pub fn section_index(&self, y: i32) -> Option<usize> {
+        if y < self.min_y {
+            return None;
+        }
+        let index = (y - self.min_y) as usize / CHUNK_SIZE;
+        (index < self.sections.len()).then_some(index)
+    }
+
+    /// World y of the bottom of section `index`.
+    pub fn section_min_y(&self, index: usize) -> i32 {
+        self.min_y + (index * CHUNK_SIZE) as i32
+    }
+
+    /// Section `index`, or `None` if it is all air or outside the column.
+    pub fn section(&self, index: usize) -> Option<&Chunk> {
+        self.sections.get(index)?.as_deref()
+    }
+
+    /// Replace section `index`. An all-air chunk frees the section.
+    /// Ignored outside the column.
+    pub fn set_section(&mut self, index: usize, chunk: Chunk) {
+        if let Some(section) = self.sections.get_mut(index) {
+            *section = (!chunk.is_empty()).then(|| Box::new(chunk));
+        }
+    }
+
+    /// Block at local `x`, `z` and world `y`. Air outside the column.
+    pub fn get_block(&self, x: usize, y: i32, z: usize) -> BlockId {
+        match self.section_index(y).and_then(|index| self.section(index)) {
+            Some(section) => section.get_block(x, self.local_y(y), z),
+            None => BlockId::new(0),
+        }
+    }
+
+    /// Set the block at local `x`, `z` and world `y`, allocating its section
+    /// for the first non-air block. Ignored outside the column.
+    pub fn set_block(&mut self, x: usize, y: i32, z: usize, block_id: BlockId) {
+        if x >= CHUNK_SIZE || z >= CHUNK_SIZE {
+            return;
+        }
+        let Some(index) = self.section_index(y) else {
+            return;
+        };
+        let local_y = self.local_y(y);
+        let section = &mut self.sections[index];
+        match section {
+            Some(chunk) => chunk.set_block(x, local_y, z, block_id),
+            None if block_id.as_u16() == 0 => {}
+            None => section
+                .insert(Box::new(Chunk::new()))
+                .set_block(x, local_y, z, block_id),
+        }
+    }
+
+    /// Free sections that have become all air.
+    pub fn release_empty_sections(&mut self) {
+        for section in &mut self.sections {
+            if section.as_ref().is_some_and(|chunk| chunk.is_empty()) {
+                *section = None;
+            }
+        }
+    }
+
+    /// Allocated sections from the bottom up, with the world y of their
+    /// bottom. All-air sections are skipped.
+    pub fn sections(&self) -> impl Iterator<Item = (i32, &Chunk)> + '_ {
+        self.sections
+            .iter()
+            .enumerate()
+            .filter_map(|(index, section)| Some((self.section_min_y(index), section.as_deref()?)))
+    }
+
+    fn local_y(&self, y: i32) -> usize {
+        (y - self.min_y) as usize % CHUNK_SIZE
+    }
+}
+
+impl Default for ChunkColumn {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,12 +1,14 @@
-use ferrum_core::BlockId;
+use ferrum_core::{BlockId, BlockState};
+use std::collections::HashMap;
 
 const CHUNK_SIZE: usize = 32;
 const TOTAL_BLOCKS: usize = CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE;
 
 /// Palette-compressed chunk storage.
 ///
-/// Maps unique block IDs to small palette indices, then packs indices using
-/// variable-width bit encoding (0/1/2/4/8/16 bits per block based on palette size).
+/// Maps unique block states to small palette indices, then packs indices using
+/// variable-width bit encoding (0/1/2/4/8/16 bits per block based on palette
+/// size).
 ///
 /// Memory per chunk (32³ blocks):
 /// - 1 unique block: ~26 bytes (single-value optimization, 0 bpb)
@@ -16,7 +18,7 @@ const TOTAL_BLOCKS: usize = CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE;
 /// - 17-256 blocks: ~33.3 KB (8 bpb)
 /// - 257+ blocks: ~65.5 KB (16 bpb, uncompressed fallback)
 pub struct CompressedChunk {
-    palette: Vec<BlockId>,
+    palette: Vec<BlockState>,
     data: Vec<u64>,
     bits_per_block: u8,
 }
@@ -24,15 +26,24 @@ pub struct CompressedChunk {
 impl CompressedChunk {
     pub fn new() -> Self {
         Self {
-            palette: vec![BlockId::new(0)],
+            palette: vec![BlockState::default()],
             data: Vec::new(),
             bits_per_block: 0,
         }
     }
 
     pub fn get_block(&self, x: usize, y: usize, z: usize) -> BlockId {
+        self.get_state(x, y, z).id()
+    }
+
+    /// Set a block to its default state, dropping any properties.
+    pub fn set_block(&mut self, x: usize, y: usize, z: usize, block_id: BlockId) {
+        self.set_state(x, y, z, BlockState::from(block_id));
+    }
+
+    pub fn get_state(&self, x: usize, y: usize, z: usize) -> BlockState {
         if x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE {
-            return BlockId::new(0);
+            return BlockState::default();
         }
 
         let index = block_index(x, y, z);
@@ -40,15 +51,15 @@ impl CompressedChunk {
         self.palette[palette_idx]
     }
 
-    pub fn set_block(&mut self, x: usize, y: usize, z: usize, block_id: BlockId) {
+    pub fn set_state(&mut self, x: usize, y: usize, z: usize, state: BlockState) {
         if x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE {
             return;
         }
 
-        let palette_idx = match self.palette.iter().position(|&b| b == block_id) {
+        let palette_idx = match self.palette.iter().position(|&s| s == state) {
             Some(idx) => idx,
             None => {
-                self.palette.push(block_id);
+                self.palette.push(state);
                 let new_idx = self.palette.len() - 1;
 
                 let required_bpb = bits_needed(self.palette.len());
@@ -66,7 +77,7 @@ impl CompressedChunk {
 
     pub fn memory_usage(&self) -> usize {
         let struct_size = std::mem::size_of::<Self>();
-        let palette_heap = self.palette.capacity() * std::mem::size_of::<BlockId>();
+        let palette_heap = self.palette.capacity() * std::mem::size_of::<BlockState>();
         let data_heap = self.data.capacity() * std::mem::size_of::<u64>();
         struct_size + palette_heap + data_heap
     }
@@ -79,11 +90,90 @@ impl CompressedChunk {
         self.bits_per_block
     }
 
+    /// Number of blocks of each id, with all states of an id counted together.
+    /// Ids that do not occur are omitted.
+    pub fn block_counts(&self) -> HashMap<BlockId, u32> {
+        let mut counts = HashMap::new();
+        for (state, count) in self.palette.iter().zip(self.palette_counts()) {
+            if count > 0 {
+                *counts.entry(state.id()).or_insert(0) += count;
+            }
+        }
+        counts
+    }
+
+    /// Number of blocks with the given id, in any state.
+    pub fn count_of(&self, id: BlockId) -> u32 {
+        let matches: Vec<bool> = self.palette.iter().map(|s| s.id() == id).collect();
+        if !matches.contains(&true) {
+            return 0;
+        }
+
+        match self.bits_per_block {
+            0 => TOTAL_BLOCKS as u32,
+            1 => {
+                let ones: u32 = self.data.iter().map(|word| word.count_ones()).sum();
+                match (matches[0], matches.get(1).copied().unwrap_or(false)) {
+                    (true, true) => TOTAL_BLOCKS as u32,
+                    (true, false) => TOTAL_BLOCKS as u32 - ones,
+                    (false, _) => ones,
+                }
+            }
+            _ => {
+                let mut count = 0;
+                self.for_each_palette_index(|idx| {
+                    if matches.get(idx).copied().unwrap_or(false) {
+                        count += 1;
+                    }
+                });
+                count
+            }
+        }
+    }
+
+    /// Occurrences of each palette entry, read straight from the packed words.
+    fn palette_counts(&self) -> Vec<u32> {
+        let mut counts = vec![0u32; self.palette.len()];
+        match self.bits_per_block {
+            0 => counts[0] = TOTAL_BLOCKS as u32,
+            1 => {
+                let ones: u32 = self.data.iter().map(|word| word.count_ones()).sum();
+                counts[0] = TOTAL_BLOCKS as u32 - ones;
+                counts[1] = ones;
+            }
+            _ => self.for_each_palette_index(|idx| {
+                if let Some(count) = counts.get_mut(idx) {
+                    *count += 1;
+                }
+            }),
+        }
+        counts
+    }
+
+    /// Visit every block's palette index in storage order. Every supported
+    /// width divides 64, so each word holds a whole number of indices.
+    fn for_each_palette_index(&self, mut f: impl FnMut(usize)) {
+        let bpb = self.bits_per_block as usize;
+        if bpb == 0 {
+            return;
+        }
+        let indices_per_u64 = 64 / bpb;
+        let mask = (1u64 << bpb) - 1;
+        for &word in &self.data {
+            let mut word = word;
+            for _ in 0..indices_per_u64 {
+                f((word & mask) as usize);
+                word >>= bpb;
+            }
+        }
+    }
+
     pub fn from_blocks(blocks: &[BlockId; TOTAL_BLOCKS]) -> Self {
-        let mut palette: Vec<BlockId> = Vec::new();
+        let mut palette: Vec<BlockState> = Vec::new();
         let mut indices = [0u16; TOTAL_BLOCKS];
 
         for (i, &block) in blocks.iter().enumerate() {
+            let block = BlockState::from(block);
             let idx = match palette.iter().position(|&b| b == block) {
                 Some(idx) => idx,
                 None => {
@@ -239,6 +329,25 @@ mod tests {
         assert_eq!(chunk.get_block(31, 31, 31), BlockId::new(0));
     }
 
+    #[test]
+    fn test_states_with_different_properties_are_distinct() {
+        let mut chunk = CompressedChunk::new();
+        let water = BlockId::new(9);
+        let still = BlockState::from(water);
+        let flowing = BlockState::new(water, 3);
+
+        chunk.set_state(0, 0, 0, still);
+        chunk.set_state(1, 0, 0, flowing);
+
+        assert_eq!(chunk.palette_size(), 3);
+        assert_eq!(chunk.get_state(0, 0, 0), still);
+        assert_eq!(chunk.get_state(1, 0, 0), flowing);
+        assert_eq!(chunk.get_block(1, 0, 0), water);
+
+        chunk.set_block(1, 0, 0, water);
+        assert_eq!(chunk.get_state(1, 0, 0), still);
+    }
+
     #[test]
     fn test_out_of_bounds_returns_air() {
         let chunk = CompressedChunk::new();
@@ -420,7 +529,8 @@ mod tests {
 
     #[test]
     fn test_memory_target_262k_chunks() {
-        // Realistic world: ~70% air-only, ~20% two-type (air+stone), ~10% terrain (5-8 types)
+        // Realistic world: ~70% air-only, ~20% two-type (air+stone), ~10% terrain (5-8
+        // types)
         let air_chunk = CompressedChunk::new();
 
         let mut two_type_chunk = CompressedChunk::new();
@@ -533,4 +643,78 @@ mod tests {
             }
         }
     }
+
+    fn brute_force_counts(chunk: &CompressedChunk) -> HashMap<BlockId, u32> {
+        let mut counts = HashMap::new();
+        for x in 0..32 {
+            for y in 0..32 {
+                for z in 0..32 {
+                    *counts.entry(chunk.get_block(x, y, z)).or_insert(0) += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn test_block_counts_match_brute_force_on_terrain() {
+        let mut chunk = CompressedChunk::new();
+        for x in 0..32 {
+            for z in 0..32 {
+                let height = 12 + (x * 3 + z * 7) % 9;
+                for y in 0..height {
+                    let block = if y < height - 4 {
+                        1
+                    } else if y < height - 1 {
+                        2
+                    } else {
+                        3
+                    };
+                    chunk.set_block(x, y, z, BlockId::new(block));
+                }
+            }
+        }
+        chunk.set_state(0, 0, 0, BlockState::new(BlockId::new(1), 5));
+        assert_eq!(chunk.bits_per_block(), 4);
+
+        let expected = brute_force_counts(&chunk);
+        assert_eq!(chunk.block_counts(), expected);
+        for id in 0..6 {
+            let id = BlockId::new(id);
+            assert_eq!(chunk.count_of(id), expected.get(&id).copied().unwrap_or(0));
+        }
+    }
+
+    #[test]
+    fn test_block_counts_on_uniform_chunk() {
+        let chunk = CompressedChunk::from_blocks(&[BlockId::new(1); TOTAL_BLOCKS]);
+        assert_eq!(chunk.bits_per_block(), 0);
+
+        let counts = chunk.block_counts();
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts[&BlockId::new(1)], TOTAL_BLOCKS as u32);
+        assert_eq!(counts, brute_force_counts(&chunk));
+        assert_eq!(chunk.count_of(BlockId::new(1)), TOTAL_BLOCKS as u32);
+        assert_eq!(chunk.count_of(BlockId::new(0)), 0);
+    }
+
+    #[test]
+    fn test_block_counts_two_types_and_overwritten() {
+        let mut chunk = CompressedChunk::new();
+        for x in 0..32 {
+            for z in 0..32 {
+                chunk.set_block(x, 0, z, BlockId::new(1));
+            }
+        }
+        assert_eq!(chunk.bits_per_block(), 1);
+        assert_eq!(chunk.count_of(BlockId::new(1)), 1024);
+        assert_eq!(chunk.count_of(BlockId::new(0)), TOTAL_BLOCKS as u32 - 1024);
+        assert_eq!(chunk.block_counts(), brute_force_counts(&chunk));
+
+        // A palette entry that is no longer used is not reported.
+        chunk.set_block(0, 5, 0, BlockId::new(2));
+        chunk.set_block(0, 5, 0, BlockId::new(0));
+        assert_eq!(chunk.count_of(BlockId::new(2)), 0);
+        assert!(!chunk.block_counts().contains_key(&BlockId::new(2)));
+    }
 }
@@ -0,0 +1,79 @@
+use crate::chunk::CHUNK_SIZE;
+use crate::{Chunk, ChunkPos};
+use ferrum_core::BlockId;
+
+/// A block placed by a feature in a chunk other than the one being generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpilledBlock {
+    pub chunk: ChunkPos,
+    /// Position within `chunk`.
+    pub local: (u8, u8, u8),
+    pub block: BlockId,
+}
+
+/// A chunk under generation. Features such as trees may write past its x/z
+/// edges; those blocks are collected as spillover for the neighbouring chunks
+/// instead of being lost.
+pub struct ProtoChunk {
+    pos: ChunkPos,
+    chunk: Chunk,
+    spillover: Vec<SpilledBlock>,
+}
+
+impl ProtoChunk {
+    pub fn new(pos: ChunkPos) -> Self {
+        Self {
+            pos,
+            chunk: Chunk::new(),
+            spillover: Vec::new(),
+        }
+    }
+
+    pub fn pos(&self) -> ChunkPos {
+        self.pos
+    }
+
+    pub fn chunk(&self) -> &Chunk {
+        &self.chunk
+    }
+
+    pub fn chunk_mut(&mut self) -> &mut Chunk {
+        &mut self.chunk
+    }
+
+    /// Set a block relative to this chunk's origin. x and z may fall outside
+    /// the chunk, in which case the block is recorded for the neighbour that
+    /// owns it. Blocks outside the chunk's height are dropped.
+    pub fn set_block(&mut self, x: i32, y: i32, z: i32, block: BlockId) {
+        let size = CHUNK_SIZE as i32;
+        if !(0..size).contains(&y) {
+            return;
+        }
+
+        let chunk = ChunkPos {
+            x: self.pos.x + x.div_euclid(size),
+            z: self.pos.z + z.div_euclid(size),
+        };
+        let (lx, lz) = (x.rem_euclid(size), z.rem_euclid(size));
+
+        if chunk == self.pos {
+            self.chunk
+                .set_block(lx as usize, y as usize, lz as usize, block);
+        } else {
+            self.spillover.push(SpilledBlock {
+                chunk,
+                local: (lx as u8, y as u8, lz as u8),
+                block,
+            });
+        }
+    }
+
+    /// Blocks written outside this chunk so far.
+    pub fn spillover(&self) -> &[SpilledBlock] {
+        &self.spillover
+    }
+
+    pub fn into_parts(self) -> (Chunk, Vec<SpilledBlock>) {
+        (self.chunk, self.spillover)
+    }
+}
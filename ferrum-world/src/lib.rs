@@ -1,9 +1,27 @@
+mod block_entity;
 mod block_interaction;
 mod chunk;
+mod column;
 mod compressed;
+mod explosion;
+mod generation;
+mod light_overlay;
+mod mesh_cache;
+mod neighbor_edges;
+mod streaming;
 mod world;
 
+pub use block_entity::{BlockEntityData, ContainerItem};
 pub use block_interaction::BlockInteraction;
-pub use chunk::Chunk;
+pub use chunk::{Chunk, CHUNK_GENERATION_VERSION};
+pub use column::{ChunkColumn, WORLD_HEIGHT, WORLD_MIN_Y};
 pub use compressed::CompressedChunk;
+pub use explosion::{blast_resistance, Explosion};
+pub use generation::{ProtoChunk, SpilledBlock};
+pub use light_overlay::{
+    is_spawnable, light_overlay, overlay_cells, BlockGrid, LightGrid, OverlayCell,
+};
+pub use mesh_cache::{section_hash, SectionMeshCache, DEFAULT_SECTION_CACHE_SIZE};
+pub use neighbor_edges::{ChunkSide, EdgeLayer, NeighborEdges};
+pub use streaming::{spiral, spiral_key, ChunkStreamer, DEFAULT_CHUNKS_PER_TICK};
 pub use world::{ChunkPos, World};
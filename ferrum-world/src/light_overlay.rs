@@ -0,0 +1,114 @@
+use crate::chunk::CHUNK_SIZE;
+use crate::{ChunkPos, World};
+use ferrum_core::BlockId;
+use glam::IVec3;
+
+/// Source of block light levels for the overlay, e.g. the lighting engine's
+/// per-chunk grids.
+pub trait LightGrid {
+    /// Block light, 0..=15, at a world position.
+    fn block_light(&self, x: i32, y: i32, z: i32) -> u8;
+}
+
+impl<F: Fn(i32, i32, i32) -> u8> LightGrid for F {
+    fn block_light(&self, x: i32, y: i32, z: i32) -> u8 {
+        self(x, y, z)
+    }
+}
+
+/// Source of blocks for the overlay, e.g. a [`World`] or the chunk columns
+/// received from a server.
+pub trait BlockGrid {
+    /// Block at a world position, air where nothing is loaded.
+    fn block(&self, x: i32, y: i32, z: i32) -> BlockId;
+}
+
+impl BlockGrid for World {
+    fn block(&self, x: i32, y: i32, z: i32) -> BlockId {
+        self.get_block(x, y, z)
+    }
+}
+
+impl<F: Fn(i32, i32, i32) -> BlockId> BlockGrid for F {
+    fn block(&self, x: i32, y: i32, z: i32) -> BlockId {
+        self(x, y, z)
+    }
+}
+
+/// One marker of the light-level overlay: an empty block standing on a solid
+/// one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverlayCell {
+    pub pos: IVec3,
+    pub block_light: u8,
+    pub spawnable: bool,
+}
+
+fn is_air(block: BlockId) -> bool {
+    block.as_u16() == 0
+}
+
+/// Whether a hostile mob could spawn with its feet at `(x, y, z)`: the block
+/// below is solid, the two blocks of the mob's height are empty and block
+/// light there is 0.
+pub fn is_spawnable(blocks: &impl BlockGrid, x: i32, y: i32, z: i32, block_light: u8) -> bool {
+    block_light == 0
+        && !is_air(blocks.block(x, y - 1, z))
+        && is_air(blocks.block(x, y, z))
+        && is_air(blocks.block(x, y + 1, z))
+}
+
+/// Overlay cells for every standable block in loaded chunks within
+/// `radius_chunks` chunks of `center`, in no particular order.
+pub fn light_overlay(
+    world: &World,
+    light: &impl LightGrid,
+    center: ChunkPos,
+    radius_chunks: i32,
+) -> Vec<OverlayCell> {
+    let size = CHUNK_SIZE as i32;
+    let mut cells = Vec::new();
+    for cz in center.z - radius_chunks..=center.z + radius_chunks {
+        for cx in center.x - radius_chunks..=center.x + radius_chunks {
+            if !world.has_chunk(ChunkPos { x: cx, z: cz }) {
+                continue;
+            }
+            let min = IVec3::new(cx * size, 1, cz * size);
+            cells.extend(overlay_cells(
+                world,
+                light,
+                min,
+                min + IVec3::new(size, size - 1, size),
+            ));
+        }
+    }
+    cells
+}
+
+/// Overlay cells for every standable block from `min` up to but not
+/// including `max`, in no particular order. A cell's position is where a
+/// mob's feet would go, so the block below `min.y` is looked at too.
+pub fn overlay_cells(
+    blocks: &impl BlockGrid,
+    light: &impl LightGrid,
+    min: IVec3,
+    max: IVec3,
+) -> Vec<OverlayCell> {
+    let mut cells = Vec::new();
+    for z in min.z..max.z {
+        for x in min.x..max.x {
+            for y in min.y..max.y {
+                if !is_air(blocks.block(x, y, z)) || is_air(blocks.block(x, y - 1, z)) {
+                    continue;
+                }
+                let block_light = light.block_light(x, y, z);
+                cells.push(OverlayCell {
+                    pos: IVec3::new(x, y, z),
+                    block_light,
+                    spawnable: is_spawnable(blocks, x, y, z, block_light),
+                });
+            }
+        }
+    }
+    cells
+}
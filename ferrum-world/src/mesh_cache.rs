@@ -0,0 +1,113 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Sections cached by default.
+pub const DEFAULT_SECTION_CACHE_SIZE: usize = 256;
+
+/// Hash of a section's packed block data, the key of [`SectionMeshCache`].
+/// Equal data always hashes equally within a build.
+pub fn section_hash(voxels: &[u32]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    voxels.hash(&mut hasher);
+    hasher.finish()
+}
+
+struct CachedMesh<M> {
+    mesh: M,
+    last_used: u64,
+}
+
+/// Meshes of recently meshed sections, keyed by [`section_hash`], so that
+/// identical sections (all air, all stone, repeated terrain) are meshed once.
+///
+/// Meshes are section-local, so a cached mesh is reused as-is and placed at
+/// each section's own origin. Holds at most `capacity` meshes, dropping the
+/// least recently used.
+pub struct SectionMeshCache<M> {
+    capacity: usize,
+    entries: HashMap<u64, CachedMesh<M>>,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl<M> SectionMeshCache<M> {
+    /// Cache at most `capacity` meshes. A capacity of 0 is treated as 1.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+            clock: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Lookups answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Lookups that had to mesh.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    pub fn contains(&self, hash: u64) -> bool {
+        self.entries.contains_key(&hash)
+    }
+
+    /// The mesh for the section with `hash`, calling `mesh` to build it only
+    /// if it isn't cached.
+    pub fn get_or_insert_with(&mut self, hash: u64, mesh: impl FnOnce() -> M) -> &M {
+        self.clock += 1;
+        if self.entries.contains_key(&hash) {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+            if self.entries.len() >= self.capacity {
+                self.evict_oldest();
+            }
+            let mesh = mesh();
+            self.entries.insert(hash, CachedMesh { mesh, last_used: 0 });
+        }
+
+        let entry = self.entries.get_mut(&hash).expect("entry was just ensured");
+        entry.last_used = self.clock;
+        &entry.mesh
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn evict_oldest(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(&hash, _)| hash);
+        if let Some(hash) = oldest {
+            self.entries.remove(&hash);
+        }
+    }
+}
+
+impl<M> Default for SectionMeshCache<M> {
+    fn default() -> Self {
+        Self::new(DEFAULT_SECTION_CACHE_SIZE)
+    }
+}
@@ -0,0 +1,115 @@
+use crate::chunk::CHUNK_SIZE;
+use crate::Chunk;
+
+const CS: usize = CHUNK_SIZE;
+
+/// One side of a chunk, numbered in the mesher's face order (+X, -X, +Y,
+/// -Y, +Z, -Z).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChunkSide {
+    PosX,
+    NegX,
+    PosY,
+    NegY,
+    PosZ,
+    NegZ,
+}
+
+impl ChunkSide {
+    pub const ALL: [ChunkSide; 6] = [
+        ChunkSide::PosX,
+        ChunkSide::NegX,
+        ChunkSide::PosY,
+        ChunkSide::NegY,
+        ChunkSide::PosZ,
+        ChunkSide::NegZ,
+    ];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Chunk-local coordinates of the block at (`layer`, `row`) in the
+    /// layer of a chunk on this side of another, i.e. the layer touching it.
+    fn touching_block(self, layer: usize, row: usize) -> (usize, usize, usize) {
+        match self {
+            ChunkSide::PosX => (0, row, layer),
+            ChunkSide::NegX => (CS - 1, row, layer),
+            ChunkSide::PosY => (row, 0, layer),
+            ChunkSide::NegY => (row, CS - 1, layer),
+            ChunkSide::PosZ => (row, layer, 0),
+            ChunkSide::NegZ => (row, layer, CS - 1),
+        }
+    }
+}
+
+/// One 32x32 layer of block ids, laid out like the mesher's boundary slabs:
+/// on the ±X sides by `z * 32 + y`, on the ±Y sides by `z * 32 + x` and on
+/// the ±Z sides by `y * 32 + x`.
+pub type EdgeLayer = [u32; CS * CS];
+
+/// The block layers the six chunks around one touch it with, gathered once
+/// so the mesher can cull boundary faces by indexing instead of looking up
+/// neighbouring chunks per block. Sides without a loaded neighbour are air.
+#[derive(Clone)]
+pub struct NeighborEdges {
+    layers: Box<[EdgeLayer; 6]>,
+    present: [bool; 6],
+}
+
+impl NeighborEdges {
+    /// No neighbours: air on every side.
+    pub fn new() -> Self {
+        Self {
+            layers: Box::new([[0; CS * CS]; 6]),
+            present: [false; 6],
+        }
+    }
+
+    /// Edges of the chunks on each side, by [`ChunkSide::index`].
+    pub fn from_neighbors(neighbors: [Option<&Chunk>; 6]) -> Self {
+        let mut edges = Self::new();
+        for side in ChunkSide::ALL {
+            if let Some(chunk) = neighbors[side.index()] {
+                edges.set(side, chunk);
+            }
+        }
+        edges
+    }
+
+    /// Take the layer of `chunk`, the neighbour on `side`, that touches
+    /// this chunk.
+    pub fn set(&mut self, side: ChunkSide, chunk: &Chunk) {
+        let layer = &mut self.layers[side.index()];
+        for l in 0..CS {
+            for row in 0..CS {
+                let (x, y, z) = side.touching_block(l, row);
+                layer[l * CS + row] = chunk.get_block(x, y, z).as_u16() as u32;
+            }
+        }
+        self.present[side.index()] = true;
+    }
+
+    /// The touching layer of the neighbour on `side`, all air if it is
+    /// missing.
+    pub fn get(&self, side: ChunkSide) -> &EdgeLayer {
+        &self.layers[side.index()]
+    }
+
+    /// Whether a neighbour was loaded on `side`.
+    pub fn has(&self, side: ChunkSide) -> bool {
+        self.present[side.index()]
+    }
+
+    /// Block id across the boundary on `side`, at (`layer`, `row`) in the
+    /// layout of [`EdgeLayer`].
+    pub fn block(&self, side: ChunkSide, layer: usize, row: usize) -> u32 {
+        self.layers[side.index()][layer * CS + row]
+    }
+}
+
+impl Default for NeighborEdges {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{Chunk, ChunkPos, World};
+use std::collections::HashMap;
+
+/// Chunks processed per tick by default.
+pub const DEFAULT_CHUNKS_PER_TICK: usize = 8;
+
+/// Position of `pos` in the spiral around `center`: rings of growing
+/// Chebyshev distance, each walked clockwise from its north-west corner.
+/// Smaller keys are nearer the center.
+pub fn spiral_key(center: ChunkPos, pos: ChunkPos) -> (u32, u32) {
+    let (dx, dz) = (pos.x - center.x, pos.z - center.z);
+    let ring = dx.unsigned_abs().max(dz.unsigned_abs());
+    if ring == 0 {
+        return (0, 0);
+    }
+    let r = ring as i32;
+    let side = 2 * ring;
+    let index = if dz == -r {
+        // North edge, west to east
+        (dx + r) as u32
+    } else if dx == r {
+        // East edge, north to south
+        side + (dz + r) as u32
+    } else if dz == r {
+        // South edge, east to west
+        2 * side + (r - dx) as u32
+    } else {
+        // West edge, south to north
+        3 * side + (r - dz) as u32
+    };
+    (ring, index)
+}
+
+/// Every chunk within `radius` rings of `center`, nearest first, in the same
+/// order as [`spiral_key`].
+pub fn spiral(center: ChunkPos, radius: u32) -> Vec<ChunkPos> {
+    let mut positions = vec![center];
+    for ring in 1..=radius as i32 {
+        let at = |dx: i32, dz: i32| ChunkPos {
+            x: center.x + dx,
+            z: center.z + dz,
+        };
+        positions.extend((-ring..ring).map(|dx| at(dx, -ring)));
+        positions.extend((-ring..ring).map(|dz| at(ring, dz)));
+        positions.extend((-ring..ring).map(|dx| at(-dx, ring)));
+        positions.extend((-ring..ring).map(|dz| at(-ring, -dz)));
+    }
+    positions
+}
+
+/// Chunks waiting to be loaded, handed out a few per tick so that crossing a
+/// chunk border spreads decoding, generation, lighting and meshing over
+/// several frames instead of doing it all at once.
+///
+/// `T` is whatever is needed to build the chunk, such as a chunk packet or a
+/// generation seed.
+pub struct ChunkStreamer<T> {
+    budget: usize,
+    pending: HashMap<ChunkPos, T>,
+}
+
+impl<T> ChunkStreamer<T> {
+    /// Hand out at most `budget` chunks per tick.
+    pub fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            pending: HashMap::new(),
+        }
+    }
+
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+    }
+
+    /// Queue a chunk, replacing any work already queued for it.
+    pub fn queue(&mut self, pos: ChunkPos, work: T) {
+        self.pending.insert(pos, work);
+    }
+
+    /// Drop a queued chunk, e.g. because it was unloaded before its turn.
+    pub fn cancel(&mut self, pos: ChunkPos) -> Option<T> {
+        self.pending.remove(&pos)
+    }
+
+    pub fn is_pending(&self, pos: ChunkPos) -> bool {
+        self.pending.contains_key(&pos)
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Take this tick's chunks: up to the budget, nearest to `center` first
+    /// in spiral order. The rest stay queued for later ticks.
+    pub fn next_batch(&mut self, center: ChunkPos) -> Vec<(ChunkPos, T)> {
+        let mut positions: Vec<ChunkPos> = self.pending.keys().copied().collect();
+        positions.sort_unstable_by_key(|&pos| spiral_key(center, pos));
+        positions.truncate(self.budget);
+        positions
+            .into_iter()
+            .filter_map(|pos| Some((pos, self.pending.remove(&pos)?)))
+            .collect()
+    }
+}
+
+impl<T> Default for ChunkStreamer<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_CHUNKS_PER_TICK)
+    }
+}
+
+impl World {
+    /// Build and insert this tick's batch from `streamer` with `load`.
+    /// Returns the positions loaded, nearest first, so the caller can light
+    /// and mesh just those chunks within the same budget.
+    pub fn load_streamed<T>(
+        &mut self,
+        streamer: &mut ChunkStreamer<T>,
+        center: ChunkPos,
+        mut load: impl FnMut(ChunkPos, T) -> Chunk,
+    ) -> Vec<ChunkPos> {
+        streamer
+            .next_batch(center)
+            .into_iter()
+            .map(|(pos, work)| {
+                let chunk = load(pos, work);
+                self.set_chunk(pos, chunk);
+                pos
+            })
+            .collect()
+    }
+}
@@ -1,5 +1,7 @@
-use crate::Chunk;
-use std::collections::HashMap;
+use crate::chunk::CHUNK_SIZE;
+use crate::{Chunk, ChunkSide, NeighborEdges, ProtoChunk, SpilledBlock};
+use ferrum_core::BlockId;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ChunkPos {
@@ -9,12 +11,19 @@ pub struct ChunkPos {
 
 pub struct World {
     chunks: HashMap<ChunkPos, Chunk>,
+    /// Feature blocks waiting for their chunk to be generated.
+    pending: HashMap<ChunkPos, Vec<SpilledBlock>>,
+    /// Chunks changed by [`World::set_block`], or next to a chunk that was
+    /// loaded or unloaded, since the last [`World::take_dirty_chunks`].
+    dirty: HashSet<ChunkPos>,
 }
 
 impl World {
     pub fn new() -> Self {
         Self {
             chunks: HashMap::new(),
+            pending: HashMap::new(),
+            dirty: HashSet::new(),
         }
     }
 
@@ -22,8 +31,11 @@ impl World {
         self.chunks.is_empty()
     }
 
+    /// Insert the chunk at `pos` and mark its loaded neighbours dirty, since
+    /// their boundary faces and light may change with it.
     pub fn set_chunk(&mut self, pos: ChunkPos, chunk: Chunk) {
         self.chunks.insert(pos, chunk);
+        self.mark_neighbours_dirty(pos);
     }
 
     pub fn get_chunk(&self, pos: ChunkPos) -> Option<&Chunk> {
@@ -34,18 +46,158 @@ impl World {
         self.chunks.get_mut(&pos)
     }
 
+    /// Block at a world position. Positions in unloaded chunks or outside the
+    /// chunk height are air.
+    pub fn get_block(&self, x: i32, y: i32, z: i32) -> BlockId {
+        let size = CHUNK_SIZE as i32;
+        if !(0..size).contains(&y) {
+            return BlockId::new(0);
+        }
+        let pos = ChunkPos {
+            x: x.div_euclid(size),
+            z: z.div_euclid(size),
+        };
+        match self.chunks.get(&pos) {
+            Some(chunk) => chunk.get_block(
+                x.rem_euclid(size) as usize,
+                y as usize,
+                z.rem_euclid(size) as usize,
+            ),
+            None => BlockId::new(0),
+        }
+    }
+
+    /// Set the block at a world position and mark its chunk dirty, along
+    /// with the neighbour it touches if it is on a chunk edge. Returns
+    /// `false`, changing nothing, if the position is outside the chunk
+    /// height or its chunk is not loaded.
+    pub fn set_block(&mut self, x: i32, y: i32, z: i32, block: BlockId) -> bool {
+        let size = CHUNK_SIZE as i32;
+        if !(0..size).contains(&y) {
+            return false;
+        }
+        let pos = ChunkPos {
+            x: x.div_euclid(size),
+            z: z.div_euclid(size),
+        };
+        let (local_x, local_z) = (x.rem_euclid(size), z.rem_euclid(size));
+        let Some(chunk) = self.chunks.get_mut(&pos) else {
+            return false;
+        };
+        chunk.set_block(local_x as usize, y as usize, local_z as usize, block);
+
+        self.dirty.insert(pos);
+        let edge = |local: i32| match local {
+            0 => -1,
+            l if l == size - 1 => 1,
+            _ => 0,
+        };
+        let (dx, dz) = (edge(local_x), edge(local_z));
+        for (dx, dz) in [(dx, 0), (0, dz)] {
+            let neighbour = ChunkPos {
+                x: pos.x + dx,
+                z: pos.z + dz,
+            };
+            if neighbour != pos && self.chunks.contains_key(&neighbour) {
+                self.dirty.insert(neighbour);
+            }
+        }
+        true
+    }
+
+    /// Chunks changed since the last call, to be remeshed.
+    pub fn take_dirty_chunks(&mut self) -> Vec<ChunkPos> {
+        self.dirty.drain().collect()
+    }
+
+    /// The edges of the loaded chunks around `pos`, for meshing it. Chunks
+    /// only neighbour each other horizontally, so above and below are air.
+    pub fn neighbor_edges(&self, pos: ChunkPos) -> NeighborEdges {
+        let neighbor = |dx: i32, dz: i32| {
+            self.chunks.get(&ChunkPos {
+                x: pos.x + dx,
+                z: pos.z + dz,
+            })
+        };
+        let mut neighbors = [None; 6];
+        neighbors[ChunkSide::PosX.index()] = neighbor(1, 0);
+        neighbors[ChunkSide::NegX.index()] = neighbor(-1, 0);
+        neighbors[ChunkSide::PosZ.index()] = neighbor(0, 1);
+        neighbors[ChunkSide::NegZ.index()] = neighbor(0, -1);
+        NeighborEdges::from_neighbors(neighbors)
+    }
+
     pub fn has_chunk(&self, pos: ChunkPos) -> bool {
         self.chunks.contains_key(&pos)
     }
 
+    /// Remove the chunk at `pos`, marking its loaded neighbours dirty if it
+    /// was loaded.
     pub fn remove_chunk(&mut self, pos: ChunkPos) -> Option<Chunk> {
-        self.chunks.remove(&pos)
+        let chunk = self.chunks.remove(&pos)?;
+        self.dirty.remove(&pos);
+        self.mark_neighbours_dirty(pos);
+        Some(chunk)
+    }
+
+    /// Mark the loaded chunks sharing a face with `pos` dirty. Chunks only
+    /// neighbour each other horizontally, so there are at most four.
+    fn mark_neighbours_dirty(&mut self, pos: ChunkPos) {
+        for (dx, dz) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            let neighbour = ChunkPos {
+                x: pos.x + dx,
+                z: pos.z + dz,
+            };
+            if self.chunks.contains_key(&neighbour) {
+                self.dirty.insert(neighbour);
+            }
+        }
+    }
+
+    /// Drop every loaded chunk, e.g. when switching dimensions.
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.pending.clear();
+        self.dirty.clear();
     }
 
     pub fn chunk_count(&self) -> usize {
         self.chunks.len()
     }
 
+    /// Generate the chunk at `pos` and insert it. Blocks that neighbours'
+    /// features placed in this chunk are applied on top of what `generate`
+    /// writes. Blocks this chunk's features place in a neighbour go straight
+    /// into it if it is loaded, or wait until it is generated, so a structure
+    /// straddling a chunk edge comes out whole in either generation order.
+    pub fn generate_chunk(&mut self, pos: ChunkPos, generate: impl FnOnce(&mut ProtoChunk)) {
+        let mut proto = ProtoChunk::new(pos);
+        generate(&mut proto);
+        let (mut chunk, spillover) = proto.into_parts();
+
+        for spilled in self.pending.remove(&pos).unwrap_or_default() {
+            let (x, y, z) = spilled.local;
+            chunk.set_block(x as usize, y as usize, z as usize, spilled.block);
+        }
+        for spilled in spillover {
+            let (x, y, z) = spilled.local;
+            match self.chunks.get_mut(&spilled.chunk) {
+                Some(neighbour) => {
+                    neighbour.set_block(x as usize, y as usize, z as usize, spilled.block)
+                }
+                None => self.pending.entry(spilled.chunk).or_default().push(spilled),
+            }
+        }
+
+        self.chunks.insert(pos, chunk);
+        self.mark_neighbours_dirty(pos);
+    }
+
+    /// Number of feature blocks waiting for chunks that are not generated yet.
+    pub fn pending_block_count(&self) -> usize {
+        self.pending.values().map(Vec::len).sum()
+    }
+
     pub fn iter_chunks(&self) -> impl Iterator<Item = (ChunkPos, &Chunk)> + '_ {
         self.chunks.iter().map(|(pos, chunk)| (*pos, chunk))
     }
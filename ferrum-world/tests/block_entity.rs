@@ -0,0 +1,156 @@
+use ferrum_core::BlockId;
+use ferrum_world::{BlockEntityData, BlockInteraction, Chunk, ContainerItem};
+
+fn sign() -> BlockId {
+    BlockId::new(63)
+}
+
+fn chest() -> BlockId {
+    BlockId::new(54)
+}
+
+#[test]
+fn test_store_and_read_sign_text() {
+    let mut chunk = Chunk::new();
+    chunk.set_block(4, 10, 7, sign());
+    chunk.set_block_entity(
+        4,
+        10,
+        7,
+        BlockEntityData::sign(["Welcome", "to", "Ferrum", ""]),
+    );
+
+    match chunk.get_block_entity(4, 10, 7) {
+        Some(BlockEntityData::Sign { lines }) => {
+            assert_eq!(lines[0], "Welcome");
+            assert_eq!(lines[2], "Ferrum");
+            assert_eq!(lines[3], "");
+        }
+        other => panic!("Expected sign data, got {:?}", other),
+    }
+    assert_eq!(chunk.block_entity_count(), 1);
+}
+
+#[test]
+fn test_breaking_block_removes_block_entity() {
+    let mut chunk = Chunk::new();
+    chunk.set_block(4, 10, 7, sign());
+    chunk.set_block_entity(4, 10, 7, BlockEntityData::sign(["a", "b", "c", "d"]));
+
+    chunk.break_block(4, 10, 7);
+
+    assert!(chunk.get_block_entity(4, 10, 7).is_none());
+    assert_eq!(chunk.block_entity_count(), 0);
+}
+
+#[test]
+fn test_replacing_block_removes_block_entity() {
+    let mut chunk = Chunk::new();
+    chunk.set_block(1, 1, 1, chest());
+    chunk.set_block_entity(1, 1, 1, BlockEntityData::Container { items: Vec::new() });
+
+    chunk.set_block(1, 1, 1, BlockId::new(1));
+    assert!(chunk.get_block_entity(1, 1, 1).is_none());
+}
+
+#[test]
+fn test_setting_same_block_keeps_block_entity() {
+    let mut chunk = Chunk::new();
+    chunk.set_block(1, 1, 1, chest());
+    chunk.set_block_entity(1, 1, 1, BlockEntityData::Container { items: Vec::new() });
+
+    chunk.set_block(1, 1, 1, chest());
+    assert!(chunk.get_block_entity(1, 1, 1).is_some());
+}
+
+#[test]
+fn test_block_entity_on_air_is_ignored() {
+    let mut chunk = Chunk::new();
+    chunk.set_block_entity(2, 2, 2, BlockEntityData::sign(["", "", "", ""]));
+    chunk.set_block_entity(40, 2, 2, BlockEntityData::sign(["", "", "", ""]));
+    assert_eq!(chunk.block_entity_count(), 0);
+}
+
+#[test]
+fn test_edit_container_in_place() {
+    let mut chunk = Chunk::new();
+    chunk.set_block(0, 0, 0, chest());
+    chunk.set_block_entity(0, 0, 0, BlockEntityData::Container { items: Vec::new() });
+
+    if let Some(BlockEntityData::Container { items }) = chunk.get_block_entity_mut(0, 0, 0) {
+        items.push(ContainerItem {
+            slot: 3,
+            item_id: 264,
+            count: 5,
+        });
+    }
+
+    assert_eq!(
+        chunk.get_block_entity(0, 0, 0),
+        Some(&BlockEntityData::Container {
+            items: vec![ContainerItem {
+                slot: 3,
+                item_id: 264,
+                count: 5,
+            }],
+        })
+    );
+}
+
+#[test]
+fn test_serialization_roundtrip() {
+    let mut chunk = Chunk::new();
+    chunk.set_block(4, 10, 7, sign());
+    chunk.set_block_entity(4, 10, 7, BlockEntityData::sign(["Hello", "", "world", "!"]));
+    chunk.set_block(31, 31, 31, chest());
+    chunk.set_block_entity(
+        31,
+        31,
+        31,
+        BlockEntityData::Container {
+            items: vec![ContainerItem {
+                slot: 0,
+                item_id: 1,
+                count: 64,
+            }],
+        },
+    );
+    chunk.set_block(0, 5, 0, BlockId::new(61));
+    chunk.set_block_entity(
+        0,
+        5,
+        0,
+        BlockEntityData::Furnace {
+            burn_time: 200,
+            cook_time: 50,
+            cook_time_total: 200,
+        },
+    );
+
+    let mut bytes = Vec::new();
+    chunk.write_to(&mut bytes).unwrap();
+    let restored = Chunk::read_from(&mut bytes.as_slice()).unwrap();
+
+    assert_eq!(restored.get_block(4, 10, 7), sign());
+    assert_eq!(restored.get_block(31, 31, 31), chest());
+    assert_eq!(restored.block_entity_count(), 3);
+    for ((x, y, z), data) in chunk.iter_block_entities() {
+        assert_eq!(
+            restored.get_block_entity(x as usize, y as usize, z as usize),
+            Some(data)
+        );
+    }
+}
+
+#[test]
+fn test_deserialize_truncated_data_fails() {
+    let mut chunk = Chunk::new();
+    chunk.set_block(4, 10, 7, sign());
+    chunk.set_block_entity(4, 10, 7, BlockEntityData::sign(["Hello", "", "", ""]));
+
+    let mut bytes = Vec::new();
+    chunk.write_to(&mut bytes).unwrap();
+    bytes.truncate(bytes.len() - 3);
+
+    assert!(Chunk::read_from(&mut bytes.as_slice()).is_err());
+}
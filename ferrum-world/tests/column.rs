@@ -0,0 +1,75 @@
+use ferrum_core::BlockId;
+use ferrum_world::{Chunk, ChunkColumn, WORLD_HEIGHT, WORLD_MIN_Y};
+
+fn stone() -> BlockId {
+    BlockId::new(1)
+}
+
+#[test]
+fn test_column_covers_full_world_height() {
+    let column = ChunkColumn::new();
+    assert_eq!(column.min_y(), WORLD_MIN_Y);
+    assert_eq!(column.max_y(), WORLD_MIN_Y + WORLD_HEIGHT as i32);
+    assert_eq!(column.section_count(), 12);
+    assert_eq!(column.section_index(-65), None);
+    assert_eq!(column.section_index(320), None);
+}
+
+#[test]
+fn test_blocks_at_bottom_and_top_land_in_their_sections() {
+    let mut column = ChunkColumn::new();
+    column.set_block(3, -64, 5, stone());
+    column.set_block(3, 319, 5, stone());
+
+    assert_eq!(column.section_index(-64), Some(0));
+    assert_eq!(column.section_index(319), Some(11));
+    assert_eq!(column.section(0).unwrap().get_block(3, 0, 5), stone());
+    assert_eq!(column.section(11).unwrap().get_block(3, 31, 5), stone());
+    assert_eq!(column.get_block(3, -64, 5), stone());
+    assert_eq!(column.get_block(3, 319, 5), stone());
+    assert_eq!(column.get_block(3, -63, 5), BlockId::new(0));
+
+    let bottoms: Vec<_> = column.sections().map(|(min_y, _)| min_y).collect();
+    assert_eq!(bottoms, [-64, 288]);
+
+    // Outside the column nothing happens
+    column.set_block(3, 320, 5, stone());
+    column.set_block(3, -65, 5, stone());
+    assert_eq!(column.get_block(3, 320, 5), BlockId::new(0));
+    assert_eq!(column.allocated_sections(), 2);
+}
+
+#[test]
+fn test_empty_sections_are_not_allocated() {
+    let mut column = ChunkColumn::new();
+    assert_eq!(column.allocated_sections(), 0);
+    assert_eq!(column.sections().count(), 0);
+
+    // Writing air never allocates
+    column.set_block(0, 64, 0, BlockId::new(0));
+    assert_eq!(column.allocated_sections(), 0);
+
+    column.set_block(0, 64, 0, stone());
+    assert_eq!(column.allocated_sections(), 1);
+    column.set_block(0, 64, 0, BlockId::new(0));
+    column.release_empty_sections();
+    assert_eq!(column.allocated_sections(), 0);
+
+    column.set_section(4, Chunk::new());
+    assert!(column.section(4).is_none());
+
+    // An empty section costs no more than a pointer
+    assert_eq!(
+        std::mem::size_of::<Option<Box<Chunk>>>(),
+        std::mem::size_of::<usize>()
+    );
+}
+
+#[test]
+fn test_column_with_other_dimension_height() {
+    let mut column = ChunkColumn::with_height(0, 256);
+    assert_eq!(column.section_count(), 8);
+    column.set_block(0, 255, 0, stone());
+    assert_eq!(column.section_index(255), Some(7));
+    assert_eq!(column.sections().next().unwrap().0, 224);
+}
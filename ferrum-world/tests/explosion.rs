@@ -0,0 +1,101 @@
+use ferrum_core::BlockId;
+use ferrum_world::{Chunk, ChunkPos, World};
+use glam::Vec3;
+use std::collections::HashSet;
+
+const STONE: u16 = 1;
+const DIRT: u16 = 2;
+const OBSIDIAN: u16 = 21;
+
+/// One chunk filled with `block`, and its four neighbours empty.
+fn world_of(block: u16) -> World {
+    let mut chunk = Chunk::new();
+    for x in 0..32 {
+        for y in 0..32 {
+            for z in 0..32 {
+                chunk.set_block(x, y, z, BlockId::new(block));
+            }
+        }
+    }
+    let mut world = World::new();
+    world.set_chunk(ChunkPos { x: 0, z: 0 }, chunk);
+    for (x, z) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+        world.set_chunk(ChunkPos { x, z }, Chunk::new());
+    }
+    world
+}
+
+#[test]
+fn test_explosion_in_stone_carves_a_round_hole() {
+    let mut world = world_of(STONE);
+    world.take_dirty_chunks();
+    let explosion = world.explode(Vec3::splat(16.5), 4.0);
+    let destroyed: HashSet<_> = explosion.destroyed.iter().copied().collect();
+
+    // The blast reaches the same distance every way
+    let offsets: HashSet<_> = destroyed
+        .iter()
+        .map(|&(x, y, z)| (x - 16, y - 16, z - 16))
+        .collect();
+    for &(x, y, z) in &offsets {
+        for mirrored in [(-x, y, z), (x, -y, z), (x, y, -z), (z, x, y)] {
+            assert!(offsets.contains(&mirrored), "{mirrored:?} is missing");
+        }
+    }
+
+    // Everything next to the centre is gone, nothing far from it
+    for (x, y, z) in [(0, 0, 0), (1, 0, 0), (0, -1, 0), (0, 0, 1), (1, 1, 0)] {
+        assert!(offsets.contains(&(x, y, z)));
+    }
+    assert!(offsets.iter().all(|&(x, y, z)| x * x + y * y + z * z <= 9));
+    assert!(!offsets.contains(&(2, 2, 2)));
+
+    for &(x, y, z) in &destroyed {
+        assert_eq!(world.get_block(x, y, z), BlockId::new(0));
+    }
+    assert_eq!(world.get_block(16, 16, 20), BlockId::new(STONE));
+    assert_eq!(world.take_dirty_chunks(), vec![ChunkPos { x: 0, z: 0 }]);
+}
+
+#[test]
+fn test_obsidian_survives_a_small_blast() {
+    let mut world = world_of(OBSIDIAN);
+    world.set_block(16, 16, 16, BlockId::new(0));
+
+    let explosion = world.explode(Vec3::splat(16.5), 2.0);
+    assert!(explosion.destroyed.is_empty());
+    assert!(explosion.drops.is_empty());
+    assert_eq!(world.get_block(17, 16, 16), BlockId::new(OBSIDIAN));
+}
+
+#[test]
+fn test_explosion_drops_some_destroyed_blocks() {
+    let mut world = world_of(DIRT);
+    let explosion = world.explode(Vec3::splat(16.5), 4.0);
+
+    assert!(!explosion.drops.is_empty());
+    assert!(explosion.drops.len() < explosion.destroyed.len());
+    for (pos, block) in &explosion.drops {
+        assert!(explosion.destroyed.contains(pos));
+        assert_eq!(*block, BlockId::new(DIRT));
+    }
+
+    // The same blast drops the same blocks
+    let again = world_of(DIRT).explode(Vec3::splat(16.5), 4.0);
+    assert_eq!(again, explosion);
+}
+
+#[test]
+fn test_explosion_on_chunk_edge_marks_neighbour_dirty() {
+    let mut world = world_of(STONE);
+    world.take_dirty_chunks();
+    world.explode(Vec3::new(0.5, 16.5, 16.5), 4.0);
+
+    let mut dirty = world.take_dirty_chunks();
+    dirty.sort_by_key(|pos| (pos.x, pos.z));
+    assert_eq!(
+        dirty,
+        vec![ChunkPos { x: -1, z: 0 }, ChunkPos { x: 0, z: 0 }]
+    );
+    assert!(world.take_dirty_chunks().is_empty());
+}
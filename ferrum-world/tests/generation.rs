@@ -0,0 +1,127 @@
+use ferrum_core::BlockId;
+use ferrum_world::{ChunkPos, ProtoChunk, SpilledBlock, World};
+
+const STONE: u16 = 1;
+const LOG: u16 = 17;
+const LEAVES: u16 = 18;
+
+const WEST: ChunkPos = ChunkPos { x: 0, z: 0 };
+const EAST: ChunkPos = ChunkPos { x: 1, z: 0 };
+
+/// Flat stone floor, plus a tree on the east edge of the west chunk whose
+/// leaves hang over into the east chunk.
+fn generate(proto: &mut ProtoChunk) {
+    for x in 0..32 {
+        for z in 0..32 {
+            proto.set_block(x, 0, z, BlockId::new(STONE));
+        }
+    }
+    if proto.pos() == WEST {
+        place_tree(proto, 31, 1, 10);
+    }
+}
+
+fn place_tree(proto: &mut ProtoChunk, x: i32, y: i32, z: i32) {
+    for dy in 0..5 {
+        proto.set_block(x, y + dy, z, BlockId::new(LOG));
+    }
+    for dx in -2..=2 {
+        for dz in -2..=2 {
+            for dy in 3..5 {
+                if (dx, dz) != (0, 0) {
+                    proto.set_block(x + dx, y + dy, z + dz, BlockId::new(LEAVES));
+                }
+            }
+        }
+    }
+}
+
+fn blocks(world: &World, pos: ChunkPos) -> Vec<BlockId> {
+    let chunk = world.get_chunk(pos).expect("chunk generated");
+    let mut blocks = Vec::new();
+    for x in 0..32 {
+        for y in 0..32 {
+            for z in 0..32 {
+                blocks.push(chunk.get_block(x, y, z));
+            }
+        }
+    }
+    blocks
+}
+
+#[test]
+fn test_spillover_is_recorded_for_neighbour() {
+    let mut proto = ProtoChunk::new(WEST);
+    proto.set_block(33, 4, -1, BlockId::new(LEAVES));
+
+    assert_eq!(
+        proto.spillover(),
+        &[SpilledBlock {
+            chunk: ChunkPos { x: 1, z: -1 },
+            local: (1, 4, 31),
+            block: BlockId::new(LEAVES),
+        }]
+    );
+    assert_eq!(proto.chunk().get_block(1, 4, 31), BlockId::new(0));
+}
+
+#[test]
+fn test_straddling_tree_is_complete_when_west_generates_first() {
+    let mut world = World::new();
+    world.generate_chunk(WEST, generate);
+    assert_eq!(world.pending_block_count(), 20, "overhanging leaves wait");
+
+    world.generate_chunk(EAST, generate);
+    assert_eq!(world.pending_block_count(), 0);
+
+    let east = world.get_chunk(EAST).unwrap();
+    assert_eq!(east.get_block(0, 4, 10).as_u16(), LEAVES);
+    assert_eq!(east.get_block(1, 5, 8).as_u16(), LEAVES);
+    assert_eq!(east.get_block(0, 0, 10).as_u16(), STONE);
+}
+
+#[test]
+fn test_straddling_tree_is_complete_in_either_order() {
+    let mut west_first = World::new();
+    west_first.generate_chunk(WEST, generate);
+    west_first.generate_chunk(EAST, generate);
+
+    let mut east_first = World::new();
+    east_first.generate_chunk(EAST, generate);
+    east_first.generate_chunk(WEST, generate);
+
+    assert_eq!(east_first.pending_block_count(), 0);
+    for pos in [WEST, EAST] {
+        assert!(
+            blocks(&west_first, pos) == blocks(&east_first, pos),
+            "chunk {pos:?} differs between generation orders"
+        );
+    }
+    assert_eq!(
+        east_first
+            .get_chunk(EAST)
+            .unwrap()
+            .get_block(1, 4, 12)
+            .as_u16(),
+        LEAVES
+    );
+    assert_eq!(
+        east_first
+            .get_chunk(WEST)
+            .unwrap()
+            .get_block(31, 3, 10)
+            .as_u16(),
+        LOG
+    );
+}
+
+#[test]
+fn test_clear_drops_pending_blocks() {
+    let mut world = World::new();
+    world.generate_chunk(WEST, generate);
+    assert!(world.pending_block_count() > 0);
+
+    world.clear();
+
+    assert_eq!(world.pending_block_count(), 0);
+}
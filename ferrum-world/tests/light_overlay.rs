@@ -0,0 +1,137 @@
+use ferrum_core::BlockId;
+use ferrum_world::{
+    is_spawnable, light_overlay, overlay_cells, Chunk, ChunkPos, OverlayCell, World,
+};
+use glam::IVec3;
+
+const STONE: u16 = 1;
+
+const ORIGIN: ChunkPos = ChunkPos { x: 0, z: 0 };
+
+/// Light grid with a torch-like falloff around one point.
+struct TorchLight {
+    torch: IVec3,
+}
+
+impl ferrum_world::LightGrid for TorchLight {
+    fn block_light(&self, x: i32, y: i32, z: i32) -> u8 {
+        let distance = (IVec3::new(x, y, z) - self.torch).abs().element_sum();
+        14u8.saturating_sub(distance as u8)
+    }
+}
+
+/// One chunk with a stone floor at y = 0 and a stone pillar at (5, 1..3, 5).
+fn floor_world() -> World {
+    let mut chunk = Chunk::new();
+    for x in 0..32 {
+        for z in 0..32 {
+            chunk.set_block(x, 0, z, BlockId::new(STONE));
+        }
+    }
+    chunk.set_block(5, 1, 5, BlockId::new(STONE));
+    chunk.set_block(5, 2, 5, BlockId::new(STONE));
+
+    let mut world = World::new();
+    world.set_chunk(ORIGIN, chunk);
+    world
+}
+
+fn cell_at(cells: &[OverlayCell], pos: IVec3) -> Option<OverlayCell> {
+    cells.iter().copied().find(|cell| cell.pos == pos)
+}
+
+#[test]
+fn test_overlay_reads_light_from_grid() {
+    let world = floor_world();
+    let light = TorchLight {
+        torch: IVec3::new(10, 1, 10),
+    };
+
+    let cells = light_overlay(&world, &light, ORIGIN, 0);
+
+    // Only the floor surface and the pillar top are standable
+    assert_eq!(cells.len(), 32 * 32);
+    assert!(cells
+        .iter()
+        .all(|cell| cell.pos.y == 1 || cell.pos == IVec3::new(5, 3, 5)));
+
+    assert_eq!(
+        cell_at(&cells, IVec3::new(10, 1, 10)).unwrap().block_light,
+        14
+    );
+    assert_eq!(
+        cell_at(&cells, IVec3::new(13, 1, 10)).unwrap().block_light,
+        11
+    );
+    assert_eq!(
+        cell_at(&cells, IVec3::new(30, 1, 30)).unwrap().block_light,
+        0
+    );
+    assert_eq!(cell_at(&cells, IVec3::new(5, 3, 5)).unwrap().block_light, 2);
+}
+
+#[test]
+fn test_spawn_markers_only_on_dark_open_cells() {
+    let mut world = floor_world();
+    // Roof over (20, 1, 20) leaves only one block of head room
+    world
+        .get_chunk_mut(ORIGIN)
+        .unwrap()
+        .set_block(20, 2, 20, BlockId::new(STONE));
+    let light = |_: i32, _: i32, z: i32| if z < 16 { 7 } else { 0 };
+
+    let cells = light_overlay(&world, &light, ORIGIN, 0);
+
+    for cell in &cells {
+        let expected = cell.block_light == 0 && cell.pos != IVec3::new(20, 1, 20);
+        assert_eq!(cell.spawnable, expected, "cell {:?}", cell.pos);
+    }
+    assert!(cells.iter().any(|cell| cell.spawnable));
+    assert!(!is_spawnable(&world, 20, 1, 20, 0));
+    assert!(!is_spawnable(&world, 3, 1, 3, 1));
+    assert!(is_spawnable(&world, 3, 1, 3, 0));
+    // Nothing to stand on in mid-air
+    assert!(!is_spawnable(&world, 3, 5, 3, 0));
+}
+
+#[test]
+fn test_overlay_limited_to_nearby_chunks() {
+    let mut world = floor_world();
+    let far = ChunkPos { x: 3, z: 0 };
+    let mut chunk = Chunk::new();
+    chunk.set_block(0, 0, 0, BlockId::new(STONE));
+    world.set_chunk(far, chunk);
+    let dark = |_: i32, _: i32, _: i32| 0;
+
+    let near = light_overlay(&world, &dark, ORIGIN, 1);
+    assert_eq!(near.len(), 32 * 32);
+    assert!(near.iter().all(|cell| cell.pos.x < 32));
+
+    let around_far = light_overlay(&world, &dark, far, 0);
+    assert_eq!(around_far.len(), 1);
+    assert_eq!(around_far[0].pos, IVec3::new(96, 1, 0));
+}
+
+#[test]
+fn test_overlay_cells_from_any_block_grid() {
+    // A stone floor below zero, where a world's chunks never reach
+    let blocks = |_: i32, y: i32, _: i32| BlockId::new(if y == -20 { STONE } else { 0 });
+    let light = |x: i32, _: i32, _: i32| if x < 0 { 0 } else { 9 };
+
+    let cells = overlay_cells(
+        &blocks,
+        &light,
+        IVec3::new(-4, -30, -4),
+        IVec3::new(4, 10, 4),
+    );
+
+    assert_eq!(cells.len(), 8 * 8);
+    assert!(cells.iter().all(|cell| cell.pos.y == -19));
+    for cell in &cells {
+        assert_eq!(cell.spawnable, cell.pos.x < 0, "cell {:?}", cell.pos);
+    }
+
+    // Cells below the range are left out even with their floor in it
+    let above = overlay_cells(&blocks, &light, IVec3::new(0, -18, 0), IVec3::new(4, 0, 4));
+    assert!(above.is_empty());
+}
@@ -0,0 +1,90 @@
+use ferrum_core::{properties, BlockId, GLOWSTONE, LAVA};
+use ferrum_world::Chunk;
+
+const SIZE: usize = 32;
+
+fn stone() -> BlockId {
+    BlockId::new(1)
+}
+
+fn sorted_sources(chunk: &Chunk) -> Vec<(u8, u8, u8)> {
+    let mut sources = chunk.light_sources().to_vec();
+    sources.sort();
+    sources
+}
+
+fn scan(chunk: &Chunk) -> Vec<(u8, u8, u8)> {
+    let mut sources = Vec::new();
+    for x in 0..SIZE {
+        for y in 0..SIZE {
+            for z in 0..SIZE {
+                if properties(chunk.get_block(x, y, z)).light_emission > 0 {
+                    sources.push((x as u8, y as u8, z as u8));
+                }
+            }
+        }
+    }
+    sources
+}
+
+#[test]
+fn test_placing_and_removing_glowstone_updates_sources() {
+    let mut chunk = Chunk::new();
+    assert!(chunk.light_sources().is_empty());
+
+    chunk.set_block(3, 4, 5, GLOWSTONE);
+    assert_eq!(chunk.light_sources(), [(3, 4, 5)]);
+
+    // Placing it again or setting a non-emitting block elsewhere changes nothing
+    chunk.set_block(3, 4, 5, GLOWSTONE);
+    chunk.set_block(0, 0, 0, stone());
+    assert_eq!(chunk.light_sources(), [(3, 4, 5)]);
+
+    chunk.set_block(31, 0, 9, LAVA);
+    assert_eq!(sorted_sources(&chunk), [(3, 4, 5), (31, 0, 9)]);
+
+    // Replacing one emitter with another keeps a single entry
+    chunk.set_block(3, 4, 5, LAVA);
+    assert_eq!(sorted_sources(&chunk), [(3, 4, 5), (31, 0, 9)]);
+
+    chunk.set_block(3, 4, 5, BlockId::new(0));
+    assert_eq!(chunk.light_sources(), [(31, 0, 9)]);
+    chunk.set_block(31, 0, 9, stone());
+    assert!(chunk.light_sources().is_empty());
+
+    // Out of bounds edits are ignored
+    chunk.set_block(32, 0, 0, GLOWSTONE);
+    assert!(chunk.light_sources().is_empty());
+}
+
+#[test]
+fn test_sources_match_full_scan() {
+    let mut chunk = Chunk::new();
+    let blocks = [GLOWSTONE, LAVA, stone(), BlockId::new(0)];
+    let mut seed = 0x2545_f491_u32;
+    for _ in 0..500 {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        let (x, y, z) = (
+            seed as usize % SIZE,
+            (seed >> 5) as usize % SIZE,
+            (seed >> 10) as usize % SIZE,
+        );
+        chunk.set_block(x, y, z, blocks[(seed >> 15) as usize % blocks.len()]);
+        assert_eq!(sorted_sources(&chunk), scan(&chunk));
+    }
+    assert!(!chunk.light_sources().is_empty());
+}
+
+#[test]
+fn test_sources_survive_serialization() {
+    let mut chunk = Chunk::new();
+    chunk.set_block(1, 2, 3, GLOWSTONE);
+    chunk.set_block(7, 8, 9, LAVA);
+
+    let mut bytes = Vec::new();
+    chunk.write_to(&mut bytes).unwrap();
+    let loaded = Chunk::read_from(&mut bytes.as_slice()).unwrap();
+    assert_eq!(sorted_sources(&loaded), [(1, 2, 3), (7, 8, 9)]);
+}
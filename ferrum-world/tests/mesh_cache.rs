@@ -0,0 +1,66 @@
+use ferrum_world::{section_hash, SectionMeshCache};
+
+const SECTION: usize = 32 * 32 * 32;
+
+fn uniform(block: u32) -> Vec<u32> {
+    vec![block; SECTION]
+}
+
+/// Stand-in mesher that counts how often it runs.
+fn mesh(voxels: &[u32], runs: &mut u32) -> Vec<u32> {
+    *runs += 1;
+    voxels.iter().take(4).copied().collect()
+}
+
+#[test]
+fn test_identical_sections_mesh_once() {
+    let mut cache = SectionMeshCache::new(8);
+    let mut runs = 0;
+    let first = uniform(1);
+    let second = uniform(1);
+
+    let a = cache
+        .get_or_insert_with(section_hash(&first), || mesh(&first, &mut runs))
+        .clone();
+    let b = cache
+        .get_or_insert_with(section_hash(&second), || mesh(&second, &mut runs))
+        .clone();
+
+    assert_eq!(runs, 1);
+    assert_eq!(a, b);
+    assert_eq!((cache.hits(), cache.misses()), (1, 1));
+}
+
+#[test]
+fn test_different_section_misses() {
+    let mut cache = SectionMeshCache::new(8);
+    let mut runs = 0;
+    let stone = uniform(1);
+    let mut stone_with_hole = uniform(1);
+    stone_with_hole[SECTION / 2] = 0;
+
+    assert_ne!(section_hash(&stone), section_hash(&stone_with_hole));
+    cache.get_or_insert_with(section_hash(&stone), || mesh(&stone, &mut runs));
+    cache.get_or_insert_with(section_hash(&stone_with_hole), || {
+        mesh(&stone_with_hole, &mut runs)
+    });
+
+    assert_eq!(runs, 2);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.misses(), 2);
+}
+
+#[test]
+fn test_least_recently_used_is_evicted() {
+    let mut cache = SectionMeshCache::new(2);
+    cache.get_or_insert_with(1, || "air");
+    cache.get_or_insert_with(2, || "stone");
+    // Touch 1 so 2 is the oldest
+    cache.get_or_insert_with(1, || unreachable!());
+    cache.get_or_insert_with(3, || "dirt");
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains(1));
+    assert!(!cache.contains(2));
+    assert!(cache.contains(3));
+}
@@ -0,0 +1,54 @@
+use ferrum_core::BlockId;
+use ferrum_world::{Chunk, ChunkPos, World};
+
+fn pos(x: i32, z: i32) -> ChunkPos {
+    ChunkPos { x, z }
+}
+
+fn sorted_dirty(world: &mut World) -> Vec<ChunkPos> {
+    let mut dirty = world.take_dirty_chunks();
+    dirty.sort_by_key(|pos| (pos.x, pos.z));
+    dirty
+}
+
+/// A world with chunks east and north of the origin, and a diagonal one that
+/// shares no face with it.
+fn world_around_origin() -> World {
+    let mut world = World::new();
+    for at in [pos(1, 0), pos(0, -1), pos(1, 1)] {
+        world.set_chunk(at, Chunk::new());
+    }
+    world.take_dirty_chunks();
+    world
+}
+
+#[test]
+fn test_loading_a_chunk_marks_loaded_neighbours_dirty() {
+    let mut world = world_around_origin();
+    world.set_chunk(pos(0, 0), Chunk::new());
+
+    // West and south are not loaded, and the diagonal is not a neighbour
+    assert_eq!(sorted_dirty(&mut world), vec![pos(0, -1), pos(1, 0)]);
+
+    // Generated chunks notify their neighbours too
+    world.generate_chunk(pos(2, 0), |_| {});
+    assert_eq!(sorted_dirty(&mut world), vec![pos(1, 0)]);
+}
+
+#[test]
+fn test_unloading_a_chunk_marks_loaded_neighbours_dirty() {
+    let mut world = world_around_origin();
+    world.set_chunk(pos(0, 0), Chunk::new());
+    world.set_block(5, 5, 5, BlockId::new(1));
+    assert!(world.take_dirty_chunks().contains(&pos(0, 0)));
+
+    world.set_block(5, 5, 5, BlockId::new(0));
+    assert!(world.remove_chunk(pos(0, 0)).is_some());
+
+    // The removed chunk itself is no longer waiting to be remeshed
+    assert_eq!(sorted_dirty(&mut world), vec![pos(0, -1), pos(1, 0)]);
+
+    // Removing a chunk that was never loaded changes nothing
+    assert!(world.remove_chunk(pos(-5, -5)).is_none());
+    assert!(world.take_dirty_chunks().is_empty());
+}
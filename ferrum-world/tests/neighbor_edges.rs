@@ -0,0 +1,71 @@
+use ferrum_core::BlockId;
+use ferrum_world::{Chunk, ChunkPos, ChunkSide, NeighborEdges, World};
+
+fn pos(x: i32, z: i32) -> ChunkPos {
+    ChunkPos { x, z }
+}
+
+#[test]
+fn test_edges_capture_the_touching_layer() {
+    let mut east = Chunk::new();
+    east.set_block(0, 5, 7, BlockId::new(3));
+    // One block in from the shared face, not part of the edge
+    east.set_block(1, 6, 7, BlockId::new(4));
+    let mut north = Chunk::new();
+    north.set_block(9, 2, 31, BlockId::new(2));
+
+    let mut world = World::new();
+    world.set_chunk(pos(0, 0), Chunk::new());
+    world.set_chunk(pos(1, 0), east);
+    world.set_chunk(pos(0, -1), north);
+    let edges = world.neighbor_edges(pos(0, 0));
+
+    assert!(edges.has(ChunkSide::PosX));
+    assert_eq!(edges.block(ChunkSide::PosX, 7, 5), 3);
+    assert_eq!(edges.get(ChunkSide::PosX)[7 * 32 + 5], 3);
+    assert_eq!(
+        edges
+            .get(ChunkSide::PosX)
+            .iter()
+            .filter(|&&b| b != 0)
+            .count(),
+        1
+    );
+
+    assert!(edges.has(ChunkSide::NegZ));
+    assert_eq!(edges.block(ChunkSide::NegZ, 2, 9), 2);
+}
+
+#[test]
+fn test_missing_neighbors_are_air() {
+    let mut world = World::new();
+    world.set_chunk(pos(0, 0), Chunk::new());
+    let edges = world.neighbor_edges(pos(0, 0));
+
+    for side in ChunkSide::ALL {
+        assert!(!edges.has(side), "{:?}", side);
+        assert!(edges.get(side).iter().all(|&b| b == 0), "{:?}", side);
+    }
+}
+
+#[test]
+fn test_vertical_sides_are_always_air() {
+    let mut solid = Chunk::new();
+    for x in 0..32 {
+        for y in 0..32 {
+            for z in 0..32 {
+                solid.set_block(x, y, z, BlockId::new(1));
+            }
+        }
+    }
+    let edges = NeighborEdges::from_neighbors([None, None, None, Some(&solid), None, None]);
+    assert!(edges.has(ChunkSide::NegY));
+    assert!(edges.get(ChunkSide::NegY).iter().all(|&b| b == 1));
+
+    let mut world = World::new();
+    world.set_chunk(pos(0, 0), Chunk::new());
+    world.set_chunk(pos(-1, 0), solid);
+    let edges = world.neighbor_edges(pos(0, 0));
+    assert!(edges.has(ChunkSide::NegX));
+    assert!(!edges.has(ChunkSide::PosY) && !edges.has(ChunkSide::NegY));
+}
@@ -0,0 +1,102 @@
+use ferrum_world::{spiral, spiral_key, Chunk, ChunkPos, ChunkStreamer, World};
+
+const CENTER: ChunkPos = ChunkPos { x: 3, z: -2 };
+
+fn ring(pos: ChunkPos) -> u32 {
+    (pos.x - CENTER.x)
+        .unsigned_abs()
+        .max((pos.z - CENTER.z).unsigned_abs())
+}
+
+/// 100 pending chunks in a 10×10 square around `CENTER`.
+fn streamer(budget: usize) -> ChunkStreamer<ChunkPos> {
+    let mut streamer = ChunkStreamer::new(budget);
+    for dx in -5..5 {
+        for dz in -5..5 {
+            let pos = ChunkPos {
+                x: CENTER.x + dx,
+                z: CENTER.z + dz,
+            };
+            streamer.queue(pos, pos);
+        }
+    }
+    streamer
+}
+
+#[test]
+fn test_spiral_matches_key_order() {
+    let positions = spiral(CENTER, 3);
+    assert_eq!(positions.len(), 49);
+    assert_eq!(positions[0], CENTER);
+
+    let keys: Vec<_> = positions
+        .iter()
+        .map(|&pos| spiral_key(CENTER, pos))
+        .collect();
+    assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(positions
+        .windows(2)
+        .all(|pair| ring(pair[0]) <= ring(pair[1])));
+}
+
+#[test]
+fn test_budget_limits_each_tick() {
+    let mut streamer = streamer(8);
+    assert_eq!(streamer.pending_count(), 100);
+
+    for tick in 1..=12 {
+        let batch = streamer.next_batch(CENTER);
+        assert_eq!(batch.len(), 8);
+        assert_eq!(streamer.pending_count(), 100 - 8 * tick);
+        assert!(batch.iter().all(|(pos, work)| pos == work));
+    }
+    assert_eq!(streamer.next_batch(CENTER).len(), 4);
+    assert!(streamer.next_batch(CENTER).is_empty());
+}
+
+#[test]
+fn test_nearest_chunks_first() {
+    let mut streamer = streamer(8);
+    let order = spiral(CENTER, 5);
+
+    let first = streamer.next_batch(CENTER);
+    let first: Vec<_> = first.into_iter().map(|(pos, _)| pos).collect();
+    assert_eq!(first, order[..8]);
+
+    let mut loaded = first;
+    while streamer.pending_count() > 0 {
+        loaded.extend(streamer.next_batch(CENTER).into_iter().map(|(pos, _)| pos));
+    }
+    let expected: Vec<_> = order
+        .into_iter()
+        .filter(|&pos| loaded.contains(&pos))
+        .collect();
+    assert_eq!(loaded, expected);
+}
+
+#[test]
+fn test_moving_center_reorders_pending() {
+    let mut streamer = streamer(1);
+    let corner = ChunkPos {
+        x: CENTER.x - 5,
+        z: CENTER.z - 5,
+    };
+    assert_eq!(streamer.next_batch(corner)[0].0, corner);
+
+    streamer.cancel(CENTER);
+    assert!(!streamer.is_pending(CENTER));
+    assert_ne!(streamer.next_batch(CENTER)[0].0, CENTER);
+}
+
+#[test]
+fn test_world_loads_streamed_batch() {
+    let mut world = World::new();
+    let mut streamer = streamer(8);
+
+    let loaded = world.load_streamed(&mut streamer, CENTER, |_, _| Chunk::new());
+    assert_eq!(loaded.len(), 8);
+    assert_eq!(loaded[0], CENTER);
+    assert_eq!(world.chunk_count(), 8);
+    assert!(loaded.iter().all(|&pos| world.has_chunk(pos)));
+    assert_eq!(streamer.pending_count(), 92);
+}
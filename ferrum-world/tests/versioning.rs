@@ -0,0 +1,49 @@
+use ferrum_core::BlockId;
+use ferrum_world::{Chunk, CHUNK_GENERATION_VERSION};
+
+fn stone_chunk() -> Chunk {
+    let mut chunk = Chunk::new();
+    chunk.set_block(1, 2, 3, BlockId::new(1));
+    chunk
+}
+
+fn regenerated() -> Chunk {
+    let mut chunk = Chunk::new();
+    chunk.set_block(0, 0, 0, BlockId::new(7));
+    chunk
+}
+
+#[test]
+fn test_current_version_is_accepted() {
+    let mut bytes = Vec::new();
+    stone_chunk().write_versioned(&mut bytes).unwrap();
+
+    let loaded = Chunk::read_versioned(&mut bytes.as_slice())
+        .unwrap()
+        .expect("current version should load");
+    assert_eq!(loaded.get_block(1, 2, 3), BlockId::new(1));
+
+    let loaded = Chunk::load_or_generate(&mut bytes.as_slice(), regenerated).unwrap();
+    assert_eq!(loaded.get_block(1, 2, 3), BlockId::new(1));
+    assert_eq!(loaded.get_block(0, 0, 0), BlockId::new(0));
+}
+
+#[test]
+fn test_old_version_is_regenerated() {
+    let mut bytes = (CHUNK_GENERATION_VERSION - 1).to_le_bytes().to_vec();
+    stone_chunk().write_to(&mut bytes).unwrap();
+
+    assert!(Chunk::read_versioned(&mut bytes.as_slice())
+        .unwrap()
+        .is_none());
+
+    let loaded = Chunk::load_or_generate(&mut bytes.as_slice(), regenerated).unwrap();
+    assert_eq!(loaded.get_block(1, 2, 3), BlockId::new(0));
+    assert_eq!(loaded.get_block(0, 0, 0), BlockId::new(7));
+}
+
+#[test]
+fn test_truncated_cache_is_an_error() {
+    let bytes = CHUNK_GENERATION_VERSION.to_le_bytes();
+    assert!(Chunk::load_or_generate(&mut bytes.as_slice(), regenerated).is_err());
+}
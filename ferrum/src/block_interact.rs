@@ -1,21 +1,32 @@
+use crate::hud::HudState;
+use crate::inventory_screen::{InventoryState, HOTBAR_START};
+use crate::network::ReceivedChunks;
 use crate::particles;
+use crate::player_controller::{GameMode, PlayerState};
 use crate::title_screen::GameState;
 use bevy::prelude::*;
+use ferrum_core::BlockState;
+use ferrum_inventory::{drops_of, ItemStack};
+use ferrum_physics::voxel_raycast;
+use ferrum_render::{SwingHand, ViewModelCamera};
 
 pub struct BlockInteractPlugin;
 
 impl Plugin for BlockInteractPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<BlockTarget>().add_systems(
-            Update,
-            (
-                raycast_block,
-                handle_block_break,
-                handle_block_place,
-                update_block_highlight,
-            )
-                .run_if(in_state(GameState::InGame)),
-        );
+        app.init_resource::<BlockTarget>()
+            .add_message::<BlockBroken>()
+            .add_systems(
+                Update,
+                (
+                    raycast_block,
+                    handle_block_break,
+                    collect_block_drops.after(handle_block_break),
+                    handle_block_place,
+                    update_block_highlight,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
     }
 }
 
@@ -27,6 +38,13 @@ pub struct BlockTarget {
     pub is_breaking: bool,
 }
 
+/// The player finished breaking a block.
+#[derive(Message, Debug, Clone, Copy)]
+pub struct BlockBroken {
+    pub pos: IVec3,
+    pub block: BlockState,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Face {
     Top,
@@ -38,6 +56,19 @@ pub enum Face {
 }
 
 impl Face {
+    /// The face with this outward normal, if it is a unit axis vector.
+    fn from_normal(normal: IVec3) -> Option<Self> {
+        match normal.to_array() {
+            [0, 1, 0] => Some(Face::Top),
+            [0, -1, 0] => Some(Face::Bottom),
+            [0, 0, -1] => Some(Face::North),
+            [0, 0, 1] => Some(Face::South),
+            [1, 0, 0] => Some(Face::East),
+            [-1, 0, 0] => Some(Face::West),
+            _ => None,
+        }
+    }
+
     /// Get the normal vector for this face
     fn normal(&self) -> Vec3 {
         match self {
@@ -68,54 +99,40 @@ struct BlockHighlight;
 
 /// Raycast from camera to find targeted block
 fn raycast_block(
-    camera_query: Query<&Transform, With<Camera3d>>,
+    camera_query: Query<&Transform, (With<Camera3d>, Without<ViewModelCamera>)>,
+    received_chunks: Res<ReceivedChunks>,
     mut block_target: ResMut<BlockTarget>,
 ) {
     let Some(camera_transform) = camera_query.iter().next() else {
         return;
     };
 
-    let ray_origin = camera_transform.translation;
-    let ray_direction = *camera_transform.forward();
-
     const MAX_DISTANCE: f32 = 5.0;
-    const STEP_SIZE: f32 = 0.1;
 
-    let mut current_pos = ray_origin;
-    let mut found_block = None;
-    let mut found_face = None;
+    let hit = voxel_raycast(
+        camera_transform.translation,
+        *camera_transform.forward(),
+        MAX_DISTANCE,
+        |(x, y, z)| received_chunks.block_at(x, y, z).as_u16() != 0,
+    );
 
-    for _ in 0..(MAX_DISTANCE / STEP_SIZE) as i32 {
-        current_pos += ray_direction * STEP_SIZE;
-
-        // Simplified ground plane check until proper voxel lookup is available
-        if current_pos.y <= 64.0 && current_pos.y > 63.0 {
-            let block_pos = IVec3::new(
-                current_pos.x.floor() as i32,
-                64,
-                current_pos.z.floor() as i32,
-            );
-
-            found_face = Some(Face::Top);
-            found_block = Some(block_pos);
-            break;
-        }
-    }
-
-    block_target.targeted_block = found_block;
-    block_target.targeted_face = found_face;
+    block_target.targeted_block = hit.map(|hit| hit.cell);
+    block_target.targeted_face = hit.and_then(|hit| Face::from_normal(hit.normal));
 }
 
 /// Handle block breaking with left mouse button
 fn handle_block_break(
     mouse_input: Res<ButtonInput<MouseButton>>,
     time: Res<Time>,
+    received_chunks: Res<ReceivedChunks>,
     mut block_target: ResMut<BlockTarget>,
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut particle_effects: ResMut<particles::ParticleEffects>,
+    mut swings: MessageWriter<SwingHand>,
+    mut broken: MessageWriter<BlockBroken>,
 ) {
     if mouse_input.pressed(MouseButton::Left) {
+        swings.write(SwingHand);
+
         if let Some(block_pos) = block_target.targeted_block {
             block_target.is_breaking = true;
 
@@ -125,24 +142,16 @@ fn handle_block_break(
             if block_target.break_progress >= 1.0 {
                 info!("Broke block at {:?}", block_pos);
 
-                // Spawn particle effects
-                let break_position = Vec3::new(
-                    block_pos.x as f32 + 0.5,
-                    block_pos.y as f32 + 0.5,
-                    block_pos.z as f32 + 0.5,
-                );
-
-                // Use stone/dirt color for now
-                let block_color = Color::srgb(0.5, 0.4, 0.3);
-
+                let block = received_chunks.block_at(block_pos.x, block_pos.y, block_pos.z);
                 particles::spawn_block_break_particles(
-                    &mut commands,
-                    &mut meshes,
-                    &mut materials,
-                    break_position,
-                    block_color,
-                    8, // particle count
+                    &mut particle_effects,
+                    block_pos,
+                    block.as_u16() as u32,
                 );
+                broken.write(BlockBroken {
+                    pos: block_pos,
+                    block: block.into(),
+                });
 
                 // TODO: Send block break packet to server
                 // TODO: Update local world state
@@ -157,9 +166,41 @@ fn handle_block_break(
     }
 }
 
+/// In survival, add what broken blocks drop for the selected hotbar item to
+/// the inventory.
+fn collect_block_drops(
+    mut broken: MessageReader<BlockBroken>,
+    player: Res<PlayerState>,
+    hud: Res<HudState>,
+    mut inventory: ResMut<InventoryState>,
+) {
+    if player.game_mode() != GameMode::Survival {
+        broken.clear();
+        return;
+    }
+    for BlockBroken { block, .. } in broken.read() {
+        let held = inventory
+            .slots
+            .get(HOTBAR_START + hud.selected_slot)
+            .and_then(Option::as_ref)
+            .map(ItemStack::from);
+        for drop in drops_of(*block, held.as_ref()) {
+            let lost = inventory.add_item(drop.item_id, drop.count);
+            if lost > 0 {
+                debug!("Inventory full, lost {} of item {}", lost, drop.item_id);
+            }
+        }
+    }
+}
+
 /// Handle block placing with right mouse button
-fn handle_block_place(mouse_input: Res<ButtonInput<MouseButton>>, block_target: Res<BlockTarget>) {
+fn handle_block_place(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    block_target: Res<BlockTarget>,
+    mut swings: MessageWriter<SwingHand>,
+) {
     if mouse_input.just_pressed(MouseButton::Right) {
+        swings.write(SwingHand);
         if let (Some(block_pos), Some(face)) =
             (block_target.targeted_block, block_target.targeted_face)
         {
@@ -0,0 +1,107 @@
+use crate::network::ReceivedChunks;
+use crate::title_screen::GameState;
+use bevy::prelude::*;
+use ferrum_meshing_cpu::CHUNK_SIZE;
+use ferrum_render::{
+    column_section_voxels, BlockRenderer, ChunkGroupRendering, ChunkGroups, ChunkLods,
+    FirstPersonView, LodMeshJobs,
+};
+use ferrum_world::ChunkPos;
+
+/// Width of the chunk columns a server sends.
+const COLUMN_WIDTH: i32 = 16;
+
+pub struct ChunkLodPlugin;
+
+impl Plugin for ChunkLodPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChunkLods>()
+            .init_resource::<LodMeshJobs<IVec3>>()
+            .add_systems(
+                Update,
+                update_chunk_lods.run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// World position of the chunk group `section`, keyed like `setup_scene`:
+/// `(chunk_x, section index, chunk_z)` of a 16 wide server column whose
+/// sections are 32 tall from `min_y`.
+fn section_origin(section: IVec3, min_y: i32) -> IVec3 {
+    IVec3::new(
+        section.x * COLUMN_WIDTH,
+        section.y * CHUNK_SIZE as i32 + min_y,
+        section.z * COLUMN_WIDTH,
+    )
+}
+
+/// Mesh the received chunk columns at the LOD for their distance to the
+/// camera in the background, replacing the chunk group mesh of each
+/// section as it finishes.
+fn update_chunk_lods(
+    received_chunks: Res<ReceivedChunks>,
+    rendering: Option<Res<ChunkGroupRendering>>,
+    cameras: Query<&GlobalTransform, With<FirstPersonView>>,
+    mut lods: ResMut<ChunkLods>,
+    mut jobs: ResMut<LodMeshJobs<IVec3>>,
+    mut chunk_groups: ResMut<ChunkGroups>,
+) {
+    let Ok(camera) = cameras.single() else {
+        return;
+    };
+
+    let columns = received_chunks
+        .chunks
+        .keys()
+        .map(|&(x, z)| ChunkPos { x, z });
+    let removed = lods.update_columns_async(
+        columns,
+        COLUMN_WIDTH as f32,
+        camera.translation(),
+        |pos| {
+            let Some(column) = received_chunks.column(pos.x, pos.z) else {
+                return Vec::new();
+            };
+            column_section_voxels(&column)
+                .map(|(min_y, voxels)| ((min_y - column.min_y()) / CHUNK_SIZE as i32, voxels))
+                .collect()
+        },
+        &mut jobs,
+    );
+    let sections = received_chunks.dimension_height.div_ceil(CHUNK_SIZE as u32) as i32;
+    for pos in removed {
+        for index in 0..sections {
+            chunk_groups.remove(IVec3::new(pos.x, index, pos.z));
+        }
+    }
+
+    for (section, lod, mesh) in jobs.drain_completed() {
+        debug!(
+            "Meshed section ({}, {}, {}) at {:?}",
+            section.x, section.y, section.z, lod
+        );
+        let pos = ChunkPos {
+            x: section.x,
+            z: section.z,
+        };
+        lods.record_quads(pos, lod, mesh.quads.len() as u32);
+        // Animated and glowing blocks keep the meshes of their own that
+        // setup_scene spawned
+        let mesh = match &rendering {
+            Some(rendering) => {
+                let (static_mesh, _) = BlockRenderer::split_animated(&mesh, &rendering.atlas);
+                BlockRenderer::split_emissive(&static_mesh).0
+            }
+            None => mesh,
+        };
+        if mesh.quads.is_empty() {
+            chunk_groups.remove(section);
+        } else {
+            chunk_groups.insert(
+                section,
+                section_origin(section, received_chunks.min_y),
+                mesh,
+            );
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::network::ServerDisconnected;
+use crate::title_screen::GameState;
+use bevy::prelude::*;
+use bevy::window::{CursorGrabMode, CursorOptions};
+
+pub struct DisconnectScreenPlugin;
+
+impl Plugin for DisconnectScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DisconnectReason>()
+            .add_systems(OnEnter(GameState::Disconnected), setup_disconnect_screen)
+            .add_systems(OnExit(GameState::Disconnected), cleanup_disconnect_screen)
+            .add_systems(
+                Update,
+                (
+                    show_disconnect_screen,
+                    handle_disconnect_screen_input.run_if(in_state(GameState::Disconnected)),
+                ),
+            );
+    }
+}
+
+/// Why the server last closed the connection
+#[derive(Resource, Default)]
+pub struct DisconnectReason(pub String);
+
+#[derive(Component)]
+struct DisconnectScreenUI;
+
+#[derive(Component)]
+struct BackToTitleButton;
+
+fn show_disconnect_screen(
+    mut disconnects: MessageReader<ServerDisconnected>,
+    mut reason: ResMut<DisconnectReason>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    let Some(disconnect) = disconnects.read().last() else {
+        return;
+    };
+    info!("Disconnected: {}", disconnect.reason);
+    reason.0 = disconnect.reason.clone();
+    game_state.set(GameState::Disconnected);
+}
+
+fn setup_disconnect_screen(
+    mut commands: Commands,
+    reason: Res<DisconnectReason>,
+    mut cursor_options: Single<&mut CursorOptions>,
+) {
+    cursor_options.grab_mode = CursorGrabMode::None;
+    cursor_options.visible = true;
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.15, 0.1, 0.08)),
+            DisconnectScreenUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Connection Lost"),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                Node {
+                    margin: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+            ));
+
+            // Kick message from the server
+            parent.spawn((
+                Text::new(reason.0.clone()),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(Justify::Center),
+                Node {
+                    max_width: Val::Percent(60.0),
+                    margin: UiRect::bottom(Val::Px(40.0)),
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(260.0),
+                        height: Val::Px(50.0),
+                        margin: UiRect::all(Val::Px(10.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
+                    BackToTitleButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("Back to Title Screen"),
+                        TextFont {
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                });
+        });
+}
+
+fn cleanup_disconnect_screen(
+    mut commands: Commands,
+    query: Query<Entity, With<DisconnectScreenUI>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn handle_disconnect_screen_input(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<BackToTitleButton>)>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            info!("Returning to title screen...");
+            game_state.set(GameState::TitleScreen);
+        }
+    }
+}
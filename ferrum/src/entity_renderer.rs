@@ -1,5 +1,8 @@
+use bevy::diagnostic::FrameCount;
 use bevy::ecs::hierarchy::ChildSpawnerCommands;
 use bevy::prelude::*;
+pub use ferrum_physics::EntityType;
+use ferrum_render::{ThrottledUpdate, UpdateThrottle, ViewModelCamera};
 use std::collections::HashMap;
 
 /// Plugin that handles rendering of game entities (players, mobs, items)
@@ -8,6 +11,7 @@ pub struct EntityRenderPlugin;
 impl Plugin for EntityRenderPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ServerEntities>()
+            .init_resource::<UpdateThrottle>()
             .add_systems(Startup, spawn_test_entities)
             .add_systems(
                 Update,
@@ -22,21 +26,6 @@ impl Plugin for EntityRenderPlugin {
     }
 }
 
-/// Types of entities that can be rendered
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum EntityType {
-    Player,
-    Zombie,
-    Skeleton,
-    Creeper,
-    Spider,
-    Pig,
-    Cow,
-    Sheep,
-    Chicken,
-    DroppedItem,
-}
-
 /// Component attached to rendered entities
 #[derive(Component)]
 pub struct GameEntity {
@@ -122,6 +111,7 @@ fn spawn_entity(
                 health: entity_data.health,
             },
             EntityRoot,
+            ThrottledUpdate::default(),
         ))
         .with_children(|parent| {
             match entity_type {
@@ -756,25 +746,54 @@ fn spawn_health_bar(
     }
 }
 
-/// System that updates entity positions with smooth interpolation
+/// Position of the main 3D camera, used to throttle updates of far entities.
+type MainCamera<'w, 's> = Query<
+    'w,
+    's,
+    &'static Transform,
+    (
+        With<Camera3d>,
+        Without<ViewModelCamera>,
+        Without<EntityRoot>,
+        Without<HealthBar>,
+    ),
+>;
+
+/// Distance from the camera used to pick an entity's update rate. Without a
+/// camera every entity counts as near.
+fn camera_distance(camera: &MainCamera, position: Vec3) -> f32 {
+    camera
+        .single()
+        .map_or(0.0, |camera| camera.translation.distance(position))
+}
+
+/// System that updates entity positions with smooth interpolation. Far
+/// entities update less often, catching up by the time since their last
+/// update when they do.
 fn update_entity_positions(
-    mut entities: Query<(&mut Transform, &mut GameEntity), With<EntityRoot>>,
+    mut entities: Query<(&mut Transform, &mut GameEntity, &mut ThrottledUpdate), With<EntityRoot>>,
     server_entities: Res<ServerEntities>,
+    throttle: Res<UpdateThrottle>,
+    frame: Res<FrameCount>,
+    camera: MainCamera,
     time: Res<Time>,
 ) {
-    for (mut transform, mut game_entity) in &mut entities {
+    for (mut transform, mut game_entity, mut throttled) in &mut entities {
+        let distance = camera_distance(&camera, transform.translation);
+        let update = throttle.should_update(game_entity.entity_id as u64, distance, frame.0);
+        let Some(delta) = throttled.tick(time.delta_secs(), update) else {
+            continue;
+        };
+
         if let Some(entity_data) = server_entities.entities.get(&game_entity.entity_id) {
             // Smooth interpolation
+            let blend = (10.0 * delta).min(1.0);
             let target_pos = entity_data.position;
-            transform.translation = transform
-                .translation
-                .lerp(target_pos, 10.0 * time.delta_secs());
+            transform.translation = transform.translation.lerp(target_pos, blend);
 
             // Update rotation
             let target_rotation = Quat::from_rotation_y(entity_data.rotation);
-            transform.rotation = transform
-                .rotation
-                .slerp(target_rotation, 10.0 * time.delta_secs());
+            transform.rotation = transform.rotation.slerp(target_rotation, blend);
 
             // Update component data
             game_entity.position = entity_data.position;
@@ -800,14 +819,23 @@ fn despawn_removed_entities(
     }
 }
 
-/// System that animates entities (bobbing, rotation)
+/// System that animates entities (bobbing, rotation), throttled by distance
+/// like position updates
 fn animate_entities(
     mut entities: Query<(&mut Transform, &GameEntity), With<EntityRoot>>,
+    throttle: Res<UpdateThrottle>,
+    frame: Res<FrameCount>,
+    camera: MainCamera,
     time: Res<Time>,
 ) {
     let elapsed = time.elapsed_secs();
 
     for (mut transform, game_entity) in &mut entities {
+        let distance = camera_distance(&camera, transform.translation);
+        if !throttle.should_update(game_entity.entity_id as u64, distance, frame.0) {
+            continue;
+        }
+
         match game_entity.entity_type {
             EntityType::DroppedItem => {
                 // Rotate dropped items
@@ -837,7 +865,7 @@ fn animate_entities(
 fn update_health_bars(
     mut health_bars: Query<(&mut Visibility, &mut Transform, &HealthBar)>,
     entities: Query<(&Transform, &GameEntity), (With<EntityRoot>, Without<HealthBar>)>,
-    camera: Query<&Transform, (With<Camera3d>, Without<EntityRoot>, Without<HealthBar>)>,
+    camera: MainCamera,
 ) {
     let Ok(camera_transform) = camera.single() else {
         return;
@@ -1,8 +1,16 @@
+use crate::inventory_screen::{InventoryState, HOTBAR_START};
+use crate::player_controller::PlayerCollision;
 use crate::title_screen::GameState;
 use bevy::camera::ClearColorConfig;
 use bevy::core_pipeline::core_2d::graph::Core2d;
 use bevy::prelude::*;
 use bevy::render::camera::CameraRenderGraph;
+use ferrum_physics::gravity::fall_damage;
+use ferrum_physics::Axis;
+use ferrum_render::{
+    apply_atlas_swaps, AtlasImageSwapped, HotbarSelection, ItemIconPainter, ViewModelCamera,
+    HOTBAR_SLOTS, ITEM_ICON_SIZE, VIEW_MODEL_CAMERA_ORDER,
+};
 
 pub struct HudPlugin;
 
@@ -12,7 +20,14 @@ impl Plugin for HudPlugin {
             .add_systems(OnEnter(GameState::InGame), setup_hud)
             .add_systems(
                 Update,
-                (update_debug_text, update_hotbar_selection, toggle_debug)
+                (
+                    update_debug_text,
+                    update_hotbar_selection,
+                    toggle_debug,
+                    apply_fall_damage,
+                    sync_hotbar_selection,
+                    update_hotbar_icons.after(apply_atlas_swaps),
+                )
                     .run_if(in_state(GameState::InGame)),
             );
     }
@@ -56,6 +71,10 @@ struct Crosshair;
 #[derive(Component)]
 struct HotbarSlot(usize);
 
+/// Icon of the item in a hotbar slot.
+#[derive(Component)]
+struct HotbarIcon(usize);
+
 #[derive(Component)]
 struct HealthBar;
 
@@ -73,7 +92,7 @@ fn setup_hud(mut commands: Commands) {
         Camera2d,
         HudCamera,
         Camera {
-            order: 1,
+            order: VIEW_MODEL_CAMERA_ORDER + 1,
             clear_color: ClearColorConfig::None,
             ..default()
         },
@@ -176,21 +195,35 @@ fn setup_hud(mut commands: Commands) {
                         })
                         .with_children(|hotbar| {
                             for i in 0..9 {
-                                hotbar.spawn((
-                                    Node {
-                                        width: Val::Px(48.0),
-                                        height: Val::Px(48.0),
-                                        border: UiRect::all(Val::Px(2.0)),
-                                        ..default()
-                                    },
-                                    BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.8)),
-                                    BorderColor::all(if i == 0 {
-                                        Color::WHITE
-                                    } else {
-                                        Color::srgba(0.4, 0.4, 0.4, 0.8)
-                                    }),
-                                    HotbarSlot(i),
-                                ));
+                                hotbar
+                                    .spawn((
+                                        Node {
+                                            width: Val::Px(48.0),
+                                            height: Val::Px(48.0),
+                                            border: UiRect::all(Val::Px(2.0)),
+                                            ..default()
+                                        },
+                                        BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.8)),
+                                        BorderColor::all(if i == 0 {
+                                            Color::WHITE
+                                        } else {
+                                            Color::srgba(0.4, 0.4, 0.4, 0.8)
+                                        }),
+                                        HotbarSlot(i),
+                                    ))
+                                    .with_children(|slot| {
+                                        slot.spawn((
+                                            ImageNode::default(),
+                                            Node {
+                                                width: Val::Px(ITEM_ICON_SIZE as f32),
+                                                height: Val::Px(ITEM_ICON_SIZE as f32),
+                                                margin: UiRect::all(Val::Auto),
+                                                ..default()
+                                            },
+                                            Visibility::Hidden,
+                                            HotbarIcon(i),
+                                        ));
+                                    });
                             }
                         });
                 });
@@ -234,10 +267,77 @@ fn toggle_debug(
     }
 }
 
+/// Mirror the hotbar into the view model's selection so the held item follows
+/// slot changes and inventory edits.
+fn sync_hotbar_selection(
+    hud_state: Res<HudState>,
+    inventory: Res<InventoryState>,
+    mut selection: ResMut<HotbarSelection>,
+) {
+    if !hud_state.is_changed() && !inventory.is_changed() {
+        return;
+    }
+    let hotbar = HotbarSelection {
+        items: std::array::from_fn(|slot| {
+            inventory.slots[HOTBAR_START + slot]
+                .as_ref()
+                .map(|stack| stack.item_id as u32)
+        }),
+        selected: hud_state.selected_slot.min(HOTBAR_SLOTS - 1),
+    };
+    selection.set_if_neq(hotbar);
+}
+
+/// Show the icons of the hotbar's items, retrying block items until the
+/// chunk atlas has loaded and repainting them when it is swapped.
+fn update_hotbar_icons(
+    selection: Res<HotbarSelection>,
+    mut icons: ItemIconPainter,
+    mut atlas_swaps: MessageReader<AtlasImageSwapped>,
+    mut waiting_for_icons: Local<bool>,
+    mut query: Query<(&HotbarIcon, &mut ImageNode, &mut Visibility)>,
+) {
+    let atlas_swapped = atlas_swaps.read().count() > 0;
+    if !selection.is_changed() && !*waiting_for_icons && !atlas_swapped {
+        return;
+    }
+    *waiting_for_icons = false;
+
+    for (HotbarIcon(slot), mut image, mut visibility) in &mut query {
+        let item = selection.items[*slot];
+        match item.and_then(|item_id| icons.icon(item_id)) {
+            Some(icon) => {
+                image.image = icon;
+                *visibility = Visibility::Inherited;
+            }
+            None => {
+                *waiting_for_icons |= item.is_some();
+                *visibility = Visibility::Hidden;
+            }
+        }
+    }
+}
+
+/// Take fall damage from landings reported by the player's physics step.
+fn apply_fall_damage(
+    mut collisions: MessageReader<PlayerCollision>,
+    mut hud_state: ResMut<HudState>,
+) {
+    for PlayerCollision(event) in collisions.read() {
+        if event.axis != Axis::Y {
+            continue;
+        }
+        let damage = fall_damage(event.impact_speed);
+        if damage > 0.0 {
+            hud_state.health = (hud_state.health - damage).max(0.0);
+        }
+    }
+}
+
 fn update_debug_text(
     time: Res<Time>,
     mut hud_state: ResMut<HudState>,
-    camera_query: Query<&Transform, With<Camera3d>>,
+    camera_query: Query<&Transform, (With<Camera3d>, Without<ViewModelCamera>)>,
     mut text_query: Query<&mut Text, With<DebugOverlay>>,
 ) {
     let delta = time.delta_secs();
@@ -1,5 +1,7 @@
 use crate::title_screen::GameState;
 use bevy::prelude::*;
+use ferrum_inventory::items;
+use ferrum_render::{ItemIconPainter, ITEM_ICON_SIZE};
 
 pub struct InventoryPlugin;
 
@@ -19,6 +21,9 @@ impl Plugin for InventoryPlugin {
     }
 }
 
+/// Index of the first hotbar slot in [`InventoryState::slots`].
+pub const HOTBAR_START: usize = 27;
+
 #[derive(Resource)]
 pub struct InventoryState {
     pub is_open: bool,
@@ -35,6 +40,19 @@ pub struct ItemStack {
     pub item_id: u16,
     pub count: u8,
     pub name: String,
+    /// Enchanted to drop broken blocks as themselves.
+    pub silk_touch: bool,
+}
+
+impl From<&ItemStack> for ferrum_inventory::ItemStack {
+    fn from(stack: &ItemStack) -> Self {
+        Self {
+            item_id: stack.item_id,
+            count: stack.count,
+            max_stack_size: items::max_stack_size(stack.item_id),
+            silk_touch: stack.silk_touch,
+        }
+    }
 }
 
 impl Default for InventoryState {
@@ -52,36 +70,79 @@ impl Default for InventoryState {
             item_id: 1,
             count: 64,
             name: "Stone".into(),
+            silk_touch: false,
         });
         state.slots[1] = Some(ItemStack {
             item_id: 4,
             count: 64,
             name: "Cobblestone".into(),
+            silk_touch: false,
         });
         state.slots[2] = Some(ItemStack {
             item_id: 3,
             count: 64,
             name: "Dirt".into(),
+            silk_touch: false,
         });
         state.slots[3] = Some(ItemStack {
             item_id: 17,
             count: 64,
             name: "Oak Log".into(),
+            silk_touch: false,
         });
         state.slots[4] = Some(ItemStack {
             item_id: 264,
             count: 1,
             name: "Diamond Sword".into(),
+            silk_touch: false,
         });
         state.slots[5] = Some(ItemStack {
             item_id: 257,
             count: 1,
             name: "Iron Pickaxe".into(),
+            silk_touch: false,
         });
         state
     }
 }
 
+impl InventoryState {
+    /// Add `count` of an item, topping up matching stacks before filling
+    /// empty slots, hotbar first. Returns how many did not fit.
+    pub fn add_item(&mut self, item_id: u16, mut count: u8) -> u8 {
+        let max_stack = items::max_stack_size(item_id);
+        let order: Vec<usize> = (HOTBAR_START..self.slots.len())
+            .chain(0..HOTBAR_START)
+            .collect();
+
+        for &index in &order {
+            if let Some(stack) = &mut self.slots[index] {
+                if stack.item_id == item_id && stack.count < max_stack {
+                    let added = count.min(max_stack - stack.count);
+                    stack.count += added;
+                    count -= added;
+                }
+            }
+        }
+        for &index in &order {
+            if count == 0 {
+                break;
+            }
+            if self.slots[index].is_none() {
+                let added = count.min(max_stack);
+                self.slots[index] = Some(ItemStack {
+                    item_id,
+                    count: added,
+                    name: items::item_name(item_id).into(),
+                    silk_touch: false,
+                });
+                count -= added;
+            }
+        }
+        count
+    }
+}
+
 #[derive(Component)]
 struct InventoryUI;
 
@@ -103,6 +164,33 @@ enum SlotType {
 #[derive(Component)]
 struct SlotItemDisplay;
 
+#[derive(Component)]
+struct SlotItemIcon;
+
+/// The icon and the count text shown in every slot.
+fn spawn_slot_contents(slot: &mut ChildSpawnerCommands) {
+    slot.spawn((
+        ImageNode::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Px(ITEM_ICON_SIZE as f32),
+            height: Val::Px(ITEM_ICON_SIZE as f32),
+            ..default()
+        },
+        Visibility::Hidden,
+        SlotItemIcon,
+    ));
+    slot.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 10.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.9, 0.9, 0.92)),
+        SlotItemDisplay,
+    ));
+}
+
 fn setup_inventory_screen(mut commands: Commands) {
     commands
         .spawn((
@@ -202,19 +290,7 @@ fn setup_inventory_screen(mut commands: Commands) {
                                                                 index: row * 2 + col,
                                                             },
                                                         ))
-                                                        .with_children(|slot| {
-                                                            slot.spawn((
-                                                                Text::new(""),
-                                                                TextFont {
-                                                                    font_size: 10.0,
-                                                                    ..default()
-                                                                },
-                                                                TextColor(Color::srgb(
-                                                                    0.9, 0.9, 0.92,
-                                                                )),
-                                                                SlotItemDisplay,
-                                                            ));
-                                                        });
+                                                        .with_children(spawn_slot_contents);
                                                 }
                                             });
                                         }
@@ -256,17 +332,7 @@ fn setup_inventory_screen(mut commands: Commands) {
                                                     index: 0,
                                                 },
                                             ))
-                                            .with_children(|slot| {
-                                                slot.spawn((
-                                                    Text::new(""),
-                                                    TextFont {
-                                                        font_size: 10.0,
-                                                        ..default()
-                                                    },
-                                                    TextColor(Color::srgb(0.9, 0.9, 0.92)),
-                                                    SlotItemDisplay,
-                                                ));
-                                            });
+                                            .with_children(spawn_slot_contents);
                                     });
                             });
 
@@ -295,17 +361,7 @@ fn setup_inventory_screen(mut commands: Commands) {
                                                 index: i,
                                             },
                                         ))
-                                        .with_children(|slot| {
-                                            slot.spawn((
-                                                Text::new(""),
-                                                TextFont {
-                                                    font_size: 10.0,
-                                                    ..default()
-                                                },
-                                                TextColor(Color::srgb(0.9, 0.9, 0.92)),
-                                                SlotItemDisplay,
-                                            ));
-                                        });
+                                        .with_children(spawn_slot_contents);
                                 }
                             });
                         });
@@ -347,17 +403,7 @@ fn setup_inventory_screen(mut commands: Commands) {
                                                         index: row * 9 + col,
                                                     },
                                                 ))
-                                                .with_children(|slot| {
-                                                    slot.spawn((
-                                                        Text::new(""),
-                                                        TextFont {
-                                                            font_size: 10.0,
-                                                            ..default()
-                                                        },
-                                                        TextColor(Color::srgb(0.9, 0.9, 0.92)),
-                                                        SlotItemDisplay,
-                                                    ));
-                                                });
+                                                .with_children(spawn_slot_contents);
                                         }
                                     });
                             }
@@ -391,20 +437,10 @@ fn setup_inventory_screen(mut commands: Commands) {
                                         BorderColor::all(Color::srgb(0.18, 0.18, 0.2)),
                                         InventorySlot {
                                             slot_type: SlotType::MainInventory,
-                                            index: 27 + i,
+                                            index: HOTBAR_START + i,
                                         },
                                     ))
-                                    .with_children(|slot| {
-                                        slot.spawn((
-                                            Text::new(""),
-                                            TextFont {
-                                                font_size: 10.0,
-                                                ..default()
-                                            },
-                                            TextColor(Color::srgb(0.9, 0.9, 0.92)),
-                                            SlotItemDisplay,
-                                        ));
-                                    });
+                                    .with_children(spawn_slot_contents);
                             }
                         });
                 });
@@ -476,12 +512,17 @@ fn handle_slot_interaction(
 
 fn update_inventory_display(
     inventory_state: Res<InventoryState>,
+    mut icons: ItemIconPainter,
+    mut waiting_for_icons: Local<bool>,
     slot_query: Query<(&InventorySlot, &Children)>,
     mut text_query: Query<&mut Text, With<SlotItemDisplay>>,
+    mut icon_query: Query<(&mut ImageNode, &mut Visibility), With<SlotItemIcon>>,
 ) {
-    if !inventory_state.is_changed() {
+    // Block icons need the chunk atlas, so keep retrying until it loads
+    if !inventory_state.is_changed() && !*waiting_for_icons {
         return;
     }
+    *waiting_for_icons = false;
 
     for (slot, children) in &slot_query {
         let item = match slot.slot_type {
@@ -491,18 +532,39 @@ fn update_inventory_display(
             SlotType::CraftingResult => &inventory_state.crafting_result,
             SlotType::Offhand => &inventory_state.offhand,
         };
+        let icon = item
+            .as_ref()
+            .and_then(|stack| icons.icon(stack.item_id as u32));
+        *waiting_for_icons |= item.is_some() && icon.is_none();
 
         for child in children.iter() {
             if let Ok(mut text) = text_query.get_mut(child) {
-                if let Some(stack) = item {
-                    let abbrev = stack.name.chars().take(3).collect::<String>();
-                    **text = if stack.count > 1 {
-                        format!("{}\n{}", abbrev.to_uppercase(), stack.count)
-                    } else {
-                        abbrev.to_uppercase()
-                    };
-                } else {
-                    **text = String::new();
+                **text = match item {
+                    Some(stack) if icon.is_some() => {
+                        if stack.count > 1 {
+                            stack.count.to_string()
+                        } else {
+                            String::new()
+                        }
+                    }
+                    Some(stack) => {
+                        let abbrev = stack.name.chars().take(3).collect::<String>();
+                        if stack.count > 1 {
+                            format!("{}\n{}", abbrev.to_uppercase(), stack.count)
+                        } else {
+                            abbrev.to_uppercase()
+                        }
+                    }
+                    None => String::new(),
+                };
+            }
+            if let Ok((mut image, mut visibility)) = icon_query.get_mut(child) {
+                match &icon {
+                    Some(icon) => {
+                        image.image = icon.clone();
+                        *visibility = Visibility::Inherited;
+                    }
+                    None => *visibility = Visibility::Hidden,
                 }
             }
         }
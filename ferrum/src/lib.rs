@@ -1,8 +1,11 @@
 // Library interface for ferrum
 // This allows integration tests to access public modules
 
+pub mod entity_renderer;
+pub mod inventory_screen;
 pub mod network;
 pub mod player_controller;
+pub mod sky;
 pub mod title_screen;
 
 // Re-export commonly used types
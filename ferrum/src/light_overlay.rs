@@ -0,0 +1,181 @@
+use crate::network::ReceivedChunks;
+use crate::player_controller::PlayerCamera;
+use crate::title_screen::GameState;
+use bevy::prelude::*;
+use ferrum_core::properties;
+use ferrum_render::lighting::CHUNK_SIZE;
+use ferrum_render::LightingEngine;
+use ferrum_world::{overlay_cells, Chunk, LightGrid, OverlayCell};
+use std::collections::HashMap;
+
+/// Width of a server chunk column, which fills the x and z corner of each
+/// meshed section.
+const COLUMN_WIDTH: i32 = 16;
+
+/// Server chunks around the player covered by the overlay, in each direction.
+const OVERLAY_RADIUS_CHUNKS: i32 = 1;
+
+/// Blocks above and below the player covered by the overlay, and how far the
+/// player moves vertically before it is rebuilt.
+const OVERLAY_HEIGHT: i32 = 16;
+
+/// Light-level overlay toggled with F7: a colored square on every block a mob
+/// could stand on, and a red cross where hostile mobs can spawn.
+pub struct LightOverlayPlugin;
+
+impl Plugin for LightOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LightOverlay>()
+            .init_resource::<ChunkLighting>()
+            .add_systems(
+                Update,
+                (
+                    toggle_light_overlay,
+                    rebuild_light_overlay,
+                    draw_light_overlay,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct LightOverlay {
+    pub enabled: bool,
+    cells: Vec<OverlayCell>,
+    /// Chunk and vertical slice the cells were built around.
+    center: Option<IVec3>,
+}
+
+/// Block light of the meshed sections, keyed like the chunk groups: server
+/// chunk x and z, and the section's slice up from `min_y`. Sections with no
+/// light-emitting blocks are dark and not stored.
+#[derive(Resource, Default)]
+pub struct ChunkLighting {
+    pub sections: HashMap<IVec3, Box<LightingEngine>>,
+    /// World y of the bottom of the lowest slice.
+    pub min_y: i32,
+}
+
+impl ChunkLighting {
+    /// Light the section meshed at `key` from the blocks in it that give off
+    /// light. Light does not cross into neighbouring sections.
+    pub fn light_section(&mut self, key: IVec3, section: &Chunk) {
+        let mut sources = Vec::new();
+        for x in 0..CHUNK_SIZE {
+            for y in 0..CHUNK_SIZE {
+                for z in 0..CHUNK_SIZE {
+                    let emission = properties(section.get_block(x, y, z)).light_emission;
+                    if emission > 0 {
+                        sources.push((x as u8, y as u8, z as u8, emission));
+                    }
+                }
+            }
+        }
+        if sources.is_empty() {
+            self.sections.remove(&key);
+            return;
+        }
+        let mut light = Box::new(LightingEngine::new());
+        light.propagate_block_light_from(section, &sources);
+        self.sections.insert(key, light);
+    }
+}
+
+impl LightGrid for ChunkLighting {
+    fn block_light(&self, x: i32, y: i32, z: i32) -> u8 {
+        let size = CHUNK_SIZE as i32;
+        let y = y - self.min_y;
+        let key = IVec3::new(
+            x.div_euclid(COLUMN_WIDTH),
+            y.div_euclid(size),
+            z.div_euclid(COLUMN_WIDTH),
+        );
+        self.sections.get(&key).map_or(0, |light| {
+            light.get_block_light(
+                x.rem_euclid(COLUMN_WIDTH) as usize,
+                y.rem_euclid(size) as usize,
+                z.rem_euclid(COLUMN_WIDTH) as usize,
+            )
+        })
+    }
+}
+
+fn toggle_light_overlay(keys: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<LightOverlay>) {
+    if keys.just_pressed(KeyCode::F7) {
+        overlay.enabled = !overlay.enabled;
+        overlay.cells.clear();
+        overlay.center = None;
+    }
+}
+
+/// Rebuild the cells when the player moves to another chunk or slice, or
+/// the world or its light changes.
+fn rebuild_light_overlay(
+    mut overlay: ResMut<LightOverlay>,
+    received_chunks: Res<ReceivedChunks>,
+    lighting: Res<ChunkLighting>,
+    camera: Query<&Transform, With<PlayerCamera>>,
+) {
+    if !overlay.enabled {
+        return;
+    }
+    let Ok(transform) = camera.single() else {
+        return;
+    };
+
+    let block = transform.translation.floor().as_ivec3();
+    let center = IVec3::new(
+        block.x.div_euclid(COLUMN_WIDTH),
+        block.y.div_euclid(OVERLAY_HEIGHT),
+        block.z.div_euclid(COLUMN_WIDTH),
+    );
+    let stale = received_chunks.is_changed() || lighting.is_changed();
+    if overlay.center == Some(center) && !stale {
+        return;
+    }
+    let radius = IVec3::new(OVERLAY_RADIUS_CHUNKS, 1, OVERLAY_RADIUS_CHUNKS);
+    let scale = IVec3::new(COLUMN_WIDTH, OVERLAY_HEIGHT, COLUMN_WIDTH);
+    overlay.cells = overlay_cells(
+        &|x, y, z| received_chunks.block_at(x, y, z),
+        &*lighting,
+        ((center - radius) * scale).to_array().into(),
+        ((center + radius + IVec3::ONE) * scale).to_array().into(),
+    );
+    overlay.center = Some(center);
+}
+
+fn draw_light_overlay(overlay: Res<LightOverlay>, mut gizmos: Gizmos) {
+    if !overlay.enabled {
+        return;
+    }
+    for cell in &overlay.cells {
+        let base = Vec3::new(
+            cell.pos.x as f32,
+            cell.pos.y as f32 + 0.02,
+            cell.pos.z as f32,
+        );
+        let corners = [
+            base + Vec3::new(0.2, 0.0, 0.2),
+            base + Vec3::new(0.8, 0.0, 0.2),
+            base + Vec3::new(0.8, 0.0, 0.8),
+            base + Vec3::new(0.2, 0.0, 0.8),
+        ];
+        let color = light_color(cell.block_light);
+        for i in 0..4 {
+            gizmos.line(corners[i], corners[(i + 1) % 4], color);
+        }
+        if cell.spawnable {
+            let red = Color::srgb(1.0, 0.1, 0.1);
+            gizmos.line(corners[0], corners[2], red);
+            gizmos.line(corners[1], corners[3], red);
+        }
+    }
+}
+
+/// Red in darkness, through yellow, to green at full light.
+fn light_color(level: u8) -> Color {
+    let t = level.min(15) as f32 / 15.0;
+    Color::srgb((1.0 - t) * 2.0, t * 2.0, 0.1)
+}
@@ -0,0 +1,134 @@
+use crate::chat::{ChatMessage, ChatState};
+use bevy::prelude::*;
+use ferrum_subprocess::{
+    PumpkinServer, ServerSupervisor, SupervisorConfig, SupervisorEvent, SupervisorHandle,
+};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// Where the bundled Pumpkin server is built and run from.
+const SERVER_DIR: &str = "./pumpkin-server";
+const SERVER_BINARY: &str = "./pumpkin-server/target/release/pumpkin";
+
+/// Reports the auto-started server's crashes and restarts in chat.
+pub struct LocalServerPlugin;
+
+impl Plugin for LocalServerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, report_server_events);
+    }
+}
+
+/// The auto-started Pumpkin server, kept alive by a [`ServerSupervisor`]
+/// on its own thread. Dropping it stops the server.
+#[derive(Resource)]
+pub struct LocalServer {
+    handle: SupervisorHandle,
+    events: Mutex<UnboundedReceiver<SupervisorEvent>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl LocalServer {
+    /// Start the bundled server under supervision, or `None` if it hasn't
+    /// been built.
+    pub fn start() -> Option<Self> {
+        let binary = PathBuf::from(SERVER_BINARY);
+        if !binary.exists() {
+            warn!("Pumpkin server binary not found at {:?}", binary);
+            return None;
+        }
+        let binary = binary.canonicalize().unwrap_or(binary);
+        let server = PumpkinServer::new(binary).with_working_dir(SERVER_DIR);
+        let (supervisor, events) = ServerSupervisor::new(server, SupervisorConfig::default());
+        let handle = supervisor.handle();
+
+        let thread = thread::spawn(move || {
+            let result = tokio::runtime::Runtime::new()
+                .expect("Failed to create tokio runtime")
+                .block_on(supervisor.run());
+            if let Err(e) = result {
+                warn!("Pumpkin server supervisor stopped: {}", e);
+            }
+        });
+        info!("Pumpkin server started under supervision");
+
+        Some(Self {
+            handle,
+            events: Mutex::new(events),
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for LocalServer {
+    fn drop(&mut self) {
+        info!("Shutting down Pumpkin server...");
+        self.handle.stop();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Chat line for a supervisor event, or `None` for routine ones.
+fn event_message(event: &SupervisorEvent) -> Option<(String, Color)> {
+    let warning = Color::srgb(1.0, 0.85, 0.3);
+    let error = Color::srgb(1.0, 0.35, 0.35);
+    match event {
+        SupervisorEvent::Started | SupervisorEvent::Stopped => None,
+        SupervisorEvent::Crashed {
+            exit_code: Some(code),
+        } => Some((format!("Local server crashed (exit code {})", code), error)),
+        SupervisorEvent::Crashed { exit_code: None } => {
+            Some(("Local server crashed".to_string(), error))
+        }
+        SupervisorEvent::Restarting { attempt, delay } => Some((
+            format!(
+                "Restarting local server in {:.1}s (attempt {})",
+                delay.as_secs_f32(),
+                attempt
+            ),
+            warning,
+        )),
+        SupervisorEvent::Restarted { attempt } => Some((
+            format!("Local server restarted (attempt {})", attempt),
+            warning,
+        )),
+        SupervisorEvent::RestartFailed { attempt, reason } => Some((
+            format!("Local server restart {} failed: {}", attempt, reason),
+            error,
+        )),
+        SupervisorEvent::GaveUp { attempts } => Some((
+            format!(
+                "Local server keeps crashing, gave up after {} restarts",
+                attempts
+            ),
+            error,
+        )),
+    }
+}
+
+fn report_server_events(
+    server: Option<Res<LocalServer>>,
+    mut chat_state: ResMut<ChatState>,
+    time: Res<Time>,
+) {
+    let Some(server) = server else {
+        return;
+    };
+    let Ok(mut events) = server.events.lock() else {
+        return;
+    };
+    while let Ok(event) = events.try_recv() {
+        info!("Pumpkin server: {:?}", event);
+        if let Some((text, color)) = event_message(&event) {
+            chat_state.messages.push(ChatMessage {
+                text,
+                timestamp: time.elapsed_secs_f64(),
+                color,
+            });
+        }
+    }
+}
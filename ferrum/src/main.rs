@@ -1,13 +1,18 @@
 mod block_interact;
 mod chat;
+mod chunk_lod;
 mod death_screen;
+mod disconnect_screen;
 mod entity_renderer;
 mod hud;
 mod inventory_screen;
+mod light_overlay;
+mod local_server;
 mod menu;
 mod network;
 mod particles;
 mod player_controller;
+mod player_save;
 mod screenshot;
 mod settings_screen;
 mod sky;
@@ -16,8 +21,6 @@ mod texture_loader;
 mod title_screen;
 mod weather;
 
-use azalea_block::BlockState;
-use azalea_registry::builtin::BlockKind;
 use bevy::image::ImagePlugin;
 use bevy::pbr::{DistanceFog, FogFalloff};
 use bevy::prelude::*;
@@ -26,21 +29,22 @@ use bevy::render::settings::{Backends, WgpuSettings};
 use bevy::render::RenderPlugin;
 use bevy::window::{CursorGrabMode, CursorOptions};
 use ferrum_config::{Config, ConfigPlugin};
-use ferrum_meshing_cpu::{ChunkMesher, CpuMesher, CHUNK_SIZE, CHUNK_SIZE_CB, CHUNK_SIZE_SQ};
-use ferrum_render::{BlockRenderer, TextureAtlas};
-use network::ReceivedChunks;
-use std::path::PathBuf;
-use std::process::{Child, Command};
-use std::sync::{mpsc, Mutex};
+use ferrum_meshing_cpu::{ChunkMesh, ChunkMesher, CpuMesher, CHUNK_SIZE};
+use ferrum_render::{
+    column_section_voxels, AdaptiveLodPlugin, AntiAliasingPlugin, BlockRenderer, BrightnessPlugin,
+    CameraEffectsPlugin, ChunkBounds, ChunkFadePlugin, ChunkGroupPlugin, ChunkGroupRendering,
+    ChunkGroups, CloudsPlugin, FirstPersonView, FluidOverlayPlugin, FrustumCullingPlugin,
+    ItemIconsPlugin, LightLevelMaterials, MeshUploadPlugin, PendingChunkMesh, ShadowsPlugin,
+    TextureAnimationPlugin, TextureAnimations, TextureAtlas, ViewModelPlugin,
+};
+use ferrum_world::{section_hash, SectionMeshCache};
+use network::chunk_loader::{mc_chunks, process_pending_chunks, ChunkLoaded};
+use network::{ReceivedChunks, ServerDisconnected};
+use std::collections::{HashMap, HashSet};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
-/// Resource to manage the Pumpkin server process lifecycle
-#[derive(Resource)]
-struct PumpkinServerHandle {
-    _child: Option<Child>,
-}
-
 /// Marker resource to track if scene has been set up
 #[derive(Resource)]
 struct SceneSetup {
@@ -51,7 +55,8 @@ struct SceneSetup {
 #[derive(Resource)]
 struct ConnectionState {
     phase: ConnectionPhase,
-    receiver: Option<Mutex<mpsc::Receiver<Option<ReceivedChunks>>>>,
+    /// Chunks on success, `Err` with the reason if the server kicked us
+    receiver: Option<Mutex<mpsc::Receiver<Result<Option<ReceivedChunks>, String>>>>,
 }
 
 enum ConnectionPhase {
@@ -69,16 +74,6 @@ impl Default for ConnectionState {
     }
 }
 
-impl Drop for PumpkinServerHandle {
-    fn drop(&mut self) {
-        if let Some(mut child) = self._child.take() {
-            info!("Shutting down Pumpkin server...");
-            let _ = child.kill();
-            let _ = child.wait();
-        }
-    }
-}
-
 fn main() {
     App::new()
         .add_plugins(
@@ -104,23 +99,44 @@ fn main() {
         .add_plugins(ConfigPlugin {
             config_path: "config.toml".into(),
         })
+        .add_plugins(AntiAliasingPlugin)
+        .add_plugins(BrightnessPlugin)
         .add_plugins(texture_loader::TextureLoaderPlugin)
+        .add_plugins(TextureAnimationPlugin)
+        .add_plugins(MeshUploadPlugin)
+        .add_plugins(ChunkGroupPlugin)
+        .add_plugins(ChunkFadePlugin)
+        .add_plugins(chunk_lod::ChunkLodPlugin)
+        .add_plugins(AdaptiveLodPlugin)
         .add_plugins(title_screen::TitleScreenPlugin)
         .add_plugins(death_screen::DeathScreenPlugin)
+        .add_plugins(disconnect_screen::DisconnectScreenPlugin)
         .add_plugins(settings_screen::SettingsScreenPlugin)
         .add_plugins(player_controller::PlayerControllerPlugin)
+        .add_plugins(player_save::PlayerSavePlugin)
         .add_plugins(hud::HudPlugin)
         .add_plugins(chat::ChatPlugin)
+        .add_plugins(local_server::LocalServerPlugin)
         .add_plugins(menu::MenuPlugin)
         .add_plugins(sky::SkyPlugin)
+        .add_plugins(CloudsPlugin)
+        .add_plugins(ShadowsPlugin)
+        .add_plugins(FluidOverlayPlugin)
         .add_plugins(block_interact::BlockInteractPlugin)
+        .add_plugins(light_overlay::LightOverlayPlugin)
+        .add_plugins(ItemIconsPlugin)
         .add_plugins(inventory_screen::InventoryPlugin)
+        .add_plugins(ViewModelPlugin)
+        .add_plugins(CameraEffectsPlugin)
+        .add_plugins(FrustumCullingPlugin)
         .add_plugins(entity_renderer::EntityRenderPlugin)
         // SoundPlugin disabled: procedural WAV generation triggers rodio UnrecognizedFormat panic
         // TODO: Fix WAV byte generation or switch to .ogg asset files
         // .add_plugins(sounds::SoundPlugin)
         // Network/Multiplayer plugins
         .add_plugins(network::PersistentConnectionPlugin)
+        .add_plugins(network::ChunkLoaderPlugin)
+        .add_plugins(network::DimensionPlugin)
         .add_plugins(network::EntitySyncPlugin)
         .add_plugins(network::PlayerPositionPlugin)
         // Utility plugins
@@ -141,6 +157,12 @@ fn main() {
             (async_connection_system, toggle_cursor)
                 .run_if(in_state(title_screen::GameState::InGame)),
         )
+        .add_systems(
+            Update,
+            mesh_loaded_chunks
+                .after(process_pending_chunks)
+                .run_if(in_state(title_screen::GameState::InGame)),
+        )
         .run();
 }
 
@@ -152,57 +174,43 @@ fn async_connection_system(
     mut commands: Commands,
     config: Res<Config>,
     mut conn_state: ResMut<ConnectionState>,
+    mut disconnects: MessageWriter<ServerDisconnected>,
 ) {
     match conn_state.phase {
         ConnectionPhase::NotStarted => {
-            // Start pumpkin server (non-blocking)
+            // Start pumpkin server (non-blocking), restarted if it crashes
             if config.server.auto_start {
-                let pumpkin_path = PathBuf::from("./pumpkin-server/target/release/pumpkin");
-                if pumpkin_path.exists() {
-                    let abs_path = pumpkin_path.canonicalize().unwrap_or(pumpkin_path.clone());
-                    match Command::new(&abs_path)
-                        .current_dir("./pumpkin-server")
-                        .spawn()
-                    {
-                        Ok(child) => {
-                            info!("Pumpkin server process started (non-blocking)");
-                            commands.insert_resource(PumpkinServerHandle {
-                                _child: Some(child),
-                            });
-                        }
-                        Err(e) => {
-                            warn!("Failed to start Pumpkin server: {}", e);
-                            commands.insert_resource(PumpkinServerHandle { _child: None });
-                        }
-                    }
-                } else {
-                    warn!("Pumpkin server binary not found at {:?}", pumpkin_path);
-                    commands.insert_resource(PumpkinServerHandle { _child: None });
+                if let Some(server) = local_server::LocalServer::start() {
+                    commands.insert_resource(server);
                 }
-            } else {
-                commands.insert_resource(PumpkinServerHandle { _child: None });
             }
 
             // Spawn background connection thread
             let (tx, rx) = mpsc::channel();
             let address = config.server.address.clone();
+            let keepalive_timeout = Duration::from_secs(config.server.keepalive_timeout);
             thread::spawn(move || {
                 // Give Pumpkin server time to start before connecting
                 thread::sleep(Duration::from_secs(3));
                 let result = tokio::runtime::Runtime::new()
                     .expect("Failed to create tokio runtime")
                     .block_on(async {
-                        let connection_future = network::connect_and_play(address.clone());
+                        let connection_future =
+                            network::connect_and_play(address.clone(), keepalive_timeout);
                         match tokio::time::timeout(Duration::from_secs(30), connection_future).await
                         {
-                            Ok(Ok(received_chunks)) => Some(received_chunks),
+                            Ok(Ok(received_chunks)) => Ok(Some(received_chunks)),
+                            Ok(Err(network::ConnectionError::Disconnected(reason))) => {
+                                eprintln!("Disconnected by server: {}", reason);
+                                Err(reason)
+                            }
                             Ok(Err(e)) => {
                                 eprintln!("Connection failed: {}", e);
-                                None
+                                Ok(None)
                             }
                             Err(_) => {
                                 eprintln!("Connection timed out after 30 seconds");
-                                None
+                                Ok(None)
                             }
                         }
                     });
@@ -224,7 +232,7 @@ fn async_connection_system(
             });
 
             match result {
-                Some(Ok(Some(received_chunks))) => {
+                Some(Ok(Ok(Some(received_chunks)))) => {
                     info!(
                         "Background connection succeeded: {} chunks received",
                         received_chunks.chunks.len()
@@ -233,11 +241,16 @@ fn async_connection_system(
                     conn_state.phase = ConnectionPhase::Done;
                     conn_state.receiver = None;
                 }
-                Some(Ok(None)) => {
+                Some(Ok(Ok(None))) => {
                     warn!("Background connection completed without chunks");
                     conn_state.phase = ConnectionPhase::Done;
                     conn_state.receiver = None;
                 }
+                Some(Ok(Err(reason))) => {
+                    disconnects.write(ServerDisconnected { reason });
+                    conn_state.phase = ConnectionPhase::Done;
+                    conn_state.receiver = None;
+                }
                 Some(Err(_)) => {
                     warn!("Connection thread disconnected unexpectedly");
                     conn_state.phase = ConnectionPhase::Done;
@@ -250,112 +263,11 @@ fn async_connection_system(
     }
 }
 
-fn mc_block_state_to_type(state_id: u16) -> u32 {
-    let Ok(block_state) = BlockState::try_from(state_id) else {
-        return 0;
-    };
-    let kind: BlockKind = block_state.into();
-
-    match kind {
-        BlockKind::Air | BlockKind::CaveAir | BlockKind::VoidAir => 0,
-        BlockKind::Stone
-        | BlockKind::Granite
-        | BlockKind::Diorite
-        | BlockKind::Andesite
-        | BlockKind::PolishedGranite
-        | BlockKind::PolishedDiorite
-        | BlockKind::PolishedAndesite => 1,
-        BlockKind::Dirt | BlockKind::CoarseDirt | BlockKind::RootedDirt => 2,
-        BlockKind::GrassBlock | BlockKind::Podzol => 3,
-        BlockKind::Bedrock => 4,
-        BlockKind::Water => 5,
-        BlockKind::Lava => 6,
-        BlockKind::Sand | BlockKind::RedSand => 7,
-        BlockKind::Gravel => 8,
-        BlockKind::GoldOre | BlockKind::DeepslateGoldOre => 9,
-        BlockKind::IronOre | BlockKind::DeepslateIronOre => 10,
-        BlockKind::CoalOre | BlockKind::DeepslateCoalOre => 11,
-        BlockKind::OakLog
-        | BlockKind::SpruceLog
-        | BlockKind::BirchLog
-        | BlockKind::JungleLog
-        | BlockKind::AcaciaLog
-        | BlockKind::DarkOakLog
-        | BlockKind::CherryLog => 12,
-        BlockKind::OakLeaves
-        | BlockKind::SpruceLeaves
-        | BlockKind::BirchLeaves
-        | BlockKind::JungleLeaves
-        | BlockKind::AcaciaLeaves
-        | BlockKind::DarkOakLeaves
-        | BlockKind::CherryLeaves
-        | BlockKind::AzaleaLeaves
-        | BlockKind::FloweringAzaleaLeaves => 13,
-        BlockKind::OakPlanks
-        | BlockKind::SprucePlanks
-        | BlockKind::BirchPlanks
-        | BlockKind::JunglePlanks
-        | BlockKind::AcaciaPlanks
-        | BlockKind::DarkOakPlanks
-        | BlockKind::CherryPlanks => 14,
-        BlockKind::Cobblestone | BlockKind::MossyCobblestone => 15,
-        BlockKind::DiamondOre | BlockKind::DeepslateDiamondOre => 16,
-        BlockKind::Deepslate => 17,
-        BlockKind::Snow | BlockKind::SnowBlock => 18,
-        BlockKind::Ice | BlockKind::PackedIce | BlockKind::BlueIce => 19,
-        BlockKind::Clay => 20,
-        BlockKind::Obsidian => 21,
-        BlockKind::Netherrack => 22,
-        BlockKind::Glowstone => 23,
-        BlockKind::SoulSand => 24,
-        BlockKind::Terracotta => 25,
-        BlockKind::ShortGrass | BlockKind::TallGrass | BlockKind::Fern | BlockKind::LargeFern => 0,
-        _ => {
-            if state_id == 0 {
-                0
-            } else {
-                1
-            }
-        }
-    }
-}
-
-fn block_type_color(block_type: u32) -> [f32; 4] {
-    match block_type {
-        0 => [0.0, 0.0, 0.0, 0.0], // Air: fully transparent
-        _ => [1.0, 1.0, 1.0, 1.0], // All solid blocks: white (passthrough)
-    }
-}
-
-fn convert_server_chunk_to_voxels(
-    chunk_data: &[Vec<Vec<u16>>],
-    y_offset: usize,
-) -> [u32; CHUNK_SIZE_CB] {
-    let mut voxels = [0u32; CHUNK_SIZE_CB];
-
-    for local_y in 0..CHUNK_SIZE.min(32) {
-        let world_y = y_offset + local_y;
-        if world_y >= chunk_data.len() {
-            break;
-        }
-
-        for local_z in 0..16 {
-            for local_x in 0..16 {
-                let state_id = chunk_data[world_y][local_z][local_x];
-                let block_type = mc_block_state_to_type(state_id);
-
-                let idx = local_z * CHUNK_SIZE_SQ + local_y * CHUNK_SIZE + local_x;
-                voxels[idx] = block_type;
-            }
-        }
-    }
-
-    voxels
-}
+/// Server chunk columns whose sections are meshed together in parallel.
+const PARALLEL_MESH_COLUMNS: usize = 4;
 
 fn setup_scene(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     received_chunks: Option<Res<ReceivedChunks>>,
     texture_atlas: Option<Res<texture_loader::BlockTextureAtlas>>,
@@ -363,6 +275,10 @@ fn setup_scene(
     game_state: Res<State<title_screen::GameState>>,
     conn_state: Res<ConnectionState>,
     mut player_state: ResMut<player_controller::PlayerState>,
+    mut texture_animations: ResMut<TextureAnimations>,
+    mut chunk_groups: ResMut<ChunkGroups>,
+    mut chunk_lighting: ResMut<light_overlay::ChunkLighting>,
+    mut light_levels: ResMut<LightLevelMaterials>,
 ) {
     if *game_state.get() != title_screen::GameState::InGame {
         return;
@@ -406,6 +322,7 @@ fn setup_scene(
             pitch: initial_pitch,
             ..default()
         },
+        FirstPersonView,
         DistanceFog {
             color: Color::srgb(0.53, 0.71, 1.0),
             falloff: FogFalloff::Linear {
@@ -422,14 +339,41 @@ fn setup_scene(
     commands.spawn((
         DirectionalLight {
             illuminance: 150000.0,  // Very bright
-            shadows_enabled: false, // Disable shadows for better visibility
+            shadows_enabled: false, // Set from client.shadows by ShadowsPlugin
             ..default()
         },
         Transform::from_rotation(Quat::from_euler(EulerRot::XYZ, -0.5, 0.5, 0.0)),
     ));
 
-    let mesher = CpuMesher::new();
-    let atlas = TextureAtlas::new(16);
+    let mut atlas = TextureAtlas::new(16);
+
+    // Each animated block gets its own material so its UVs can move
+    // independently of the rest of the chunk
+    texture_animations.clear();
+    light_levels.clear();
+    let mut animated_materials = HashMap::new();
+    for (block_type, animation) in &texture_atlas.animations {
+        atlas.set_animation(*block_type, animation.clone());
+        // Lava and other glowing animated blocks stay bright in the dark
+        let light_emission = BlockRenderer::light_emission(*block_type);
+        let material = materials.add(StandardMaterial {
+            alpha_mode: AlphaMode::Opaque,
+            ..BlockRenderer::emissive_material(texture_atlas.atlas_handle.clone(), light_emission)
+        });
+        if light_emission > 0 {
+            light_levels.add(material.clone(), light_emission);
+        }
+        texture_animations.add(material.clone(), animation.clone());
+        animated_materials.insert(*block_type, material);
+    }
+    let mut chunk_assets = ChunkAssets {
+        atlas: Arc::new(atlas),
+        atlas_texture: texture_atlas.atlas_handle.clone(),
+        animated: animated_materials,
+        emissive: HashMap::new(),
+        unregistered: Vec::new(),
+        section_meshes: SectionMeshCache::default(),
+    };
 
     if let Some(chunks) = received_chunks {
         if !chunks.chunks.is_empty() {
@@ -441,44 +385,27 @@ fn setup_scene(
                 alpha_mode: AlphaMode::Opaque,
                 ..default()
             });
-
-            for ((chunk_x, chunk_z), chunk_data) in chunks.chunks.iter() {
-                let height = chunk_data.len();
-                let num_y_slices = (height + 31) / 32;
-
-                for y_slice in 0..num_y_slices {
-                    let y_offset = y_slice * 32;
-                    if y_offset >= height {
-                        break;
-                    }
-
-                    let voxels = convert_server_chunk_to_voxels(chunk_data, y_offset);
-
-                    if voxels.iter().all(|&v| v == 0) {
-                        continue;
-                    }
-
-                    let chunk_mesh = mesher.mesh_chunk(&voxels);
-                    if chunk_mesh.quads.is_empty() {
-                        continue;
-                    }
-
-                    let mut mesh = BlockRenderer::create_mesh(&chunk_mesh, &atlas);
-                    add_vertex_colors(&mut mesh, &chunk_mesh);
-
-                    let world_x = *chunk_x as f32 * 16.0;
-                    let world_y = (y_offset as i32 + chunks.min_y) as f32;
-                    let world_z = *chunk_z as f32 * 16.0;
-
-                    commands.spawn((
-                        Mesh3d(meshes.add(mesh)),
-                        MeshMaterial3d(chunk_material.clone()),
-                        Transform::from_xyz(world_x, world_y, world_z),
-                    ));
-                }
-            }
+            commands.insert_resource(ChunkGroupRendering {
+                atlas: chunk_assets.atlas.clone(),
+                material: chunk_material,
+            });
+            chunk_lighting.sections.clear();
+            chunk_lighting.min_y = chunks.min_y;
+
+            let columns: Vec<_> = chunks.chunks.keys().copied().collect();
+            mesh_columns(
+                &columns,
+                &chunks,
+                &mut commands,
+                &mut materials,
+                &mut chunk_assets,
+                &mut chunk_groups,
+                &mut chunk_lighting,
+            );
 
             info!("Finished rendering server chunks");
+            chunk_assets.register_light_levels(&mut light_levels);
+            commands.insert_resource(chunk_assets);
             scene_setup.done = true;
             return;
         }
@@ -492,42 +419,264 @@ fn setup_scene(
         alpha_mode: AlphaMode::Opaque,
         ..default()
     });
+    commands.insert_resource(ChunkGroupRendering {
+        atlas: chunk_assets.atlas.clone(),
+        material: chunk_material,
+    });
 
+    let mesher = CpuMesher::new();
     for cx in -2..2 {
         for cz in -2..2 {
             let voxels = ferrum_meshing_gpu::terrain_chunk();
-            let chunk_mesh = mesher.mesh_chunk(&voxels);
+            let chunk_mesh = chunk_assets
+                .section_meshes
+                .get_or_insert_with(section_hash(&voxels), || mesher.mesh_chunk(&voxels))
+                .clone();
 
             // Skip empty chunks
             if chunk_mesh.quads.is_empty() {
                 continue;
             }
 
-            let mut mesh = BlockRenderer::create_mesh(&chunk_mesh, &atlas);
-            add_vertex_colors(&mut mesh, &chunk_mesh);
-
-            commands.spawn((
-                Mesh3d(meshes.add(mesh)),
-                MeshMaterial3d(chunk_material.clone()),
-                Transform::from_xyz(cx as f32 * 32.0, 0.0, cz as f32 * 32.0),
-            ));
+            spawn_chunk_meshes(
+                &mut commands,
+                &mut materials,
+                &mut chunk_assets,
+                &mut chunk_groups,
+                IVec3::new(cx, 0, cz),
+                IVec3::new(cx * 32, 0, cz * 32),
+                &chunk_mesh,
+            );
         }
     }
+    chunk_assets.register_light_levels(&mut light_levels);
+    commands.insert_resource(chunk_assets);
     scene_setup.done = true;
 }
 
-fn add_vertex_colors(mesh: &mut Mesh, chunk_mesh: &ferrum_meshing_cpu::ChunkMesh) {
-    let mut colors = Vec::new();
+/// Light and mesh the sections of the server columns at `columns`, and
+/// spawn their meshes.
+fn mesh_columns(
+    columns: &[(i32, i32)],
+    chunks: &ReceivedChunks,
+    commands: &mut Commands,
+    materials: &mut Assets<StandardMaterial>,
+    chunk_assets: &mut ChunkAssets,
+    chunk_groups: &mut ChunkGroups,
+    chunk_lighting: &mut light_overlay::ChunkLighting,
+) {
+    let mesher = CpuMesher::new();
 
-    for quad in &chunk_mesh.quads {
-        let color = block_type_color(quad.block_type);
+    // Mesh a few columns at a time: enough sections to keep every core busy
+    // without holding all of their voxels in memory
+    for batch in columns.chunks(PARALLEL_MESH_COLUMNS) {
+        let mut sections = Vec::new();
+        for (chunk_x, chunk_z) in batch {
+            let Some(column) = chunks.column(*chunk_x, *chunk_z) else {
+                continue;
+            };
+            for (section_y, section) in column.sections() {
+                let y_slice = (section_y - column.min_y()) / CHUNK_SIZE as i32;
+                chunk_lighting.light_section(IVec3::new(*chunk_x, y_slice, *chunk_z), section);
+            }
+            for (section_y, voxels) in column_section_voxels(&column) {
+                let y_slice = (section_y - column.min_y()) / CHUNK_SIZE as i32;
+                let chunk_pos = IVec3::new(*chunk_x, y_slice, *chunk_z);
+                sections.push((chunk_pos, section_hash(&voxels[..]), voxels));
+            }
+        }
 
-        for _ in 0..4 {
-            colors.push(color);
+        // Only sections missing from the cache are meshed, each once
+        let section_meshes = &chunk_assets.section_meshes;
+        let mut queued = HashSet::new();
+        let uncached: Vec<_> = sections
+            .iter()
+            .filter(|(_, hash, _)| !section_meshes.contains(*hash) && queued.insert(*hash))
+            .collect();
+        let voxels: Vec<_> = uncached.iter().map(|(_, _, voxels)| &**voxels).collect();
+        let mut fresh: HashMap<_, _> = uncached
+            .iter()
+            .map(|(_, hash, _)| *hash)
+            .zip(mesher.mesh_chunks_parallel(&voxels))
+            .collect();
+
+        for (chunk_pos, hash, voxels) in &sections {
+            let chunk_mesh = chunk_assets
+                .section_meshes
+                .get_or_insert_with(*hash, || {
+                    fresh
+                        .remove(hash)
+                        .unwrap_or_else(|| mesher.mesh_chunk(voxels))
+                })
+                .clone();
+            if chunk_mesh.quads.is_empty() {
+                continue;
+            }
+
+            let world_x = chunk_pos.x * 16;
+            let world_y = chunk_pos.y * 32 + chunks.min_y;
+            let world_z = chunk_pos.z * 16;
+
+            spawn_chunk_meshes(
+                commands,
+                materials,
+                chunk_assets,
+                chunk_groups,
+                *chunk_pos,
+                IVec3::new(world_x, world_y, world_z),
+                &chunk_mesh,
+            );
         }
     }
+}
 
-    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+/// Light and mesh the server columns of the chunks the chunk loader loaded
+/// this frame, replacing whatever was drawn for them before. Until the scene
+/// is set up there is nothing to replace, and `setup_scene` meshes every
+/// column received by then.
+fn mesh_loaded_chunks(
+    mut commands: Commands,
+    mut loaded: MessageReader<ChunkLoaded>,
+    received_chunks: Option<Res<ReceivedChunks>>,
+    chunk_assets: Option<ResMut<ChunkAssets>>,
+    parts: Query<(Entity, &ChunkPart)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut chunk_groups: ResMut<ChunkGroups>,
+    mut chunk_lighting: ResMut<light_overlay::ChunkLighting>,
+    mut light_levels: ResMut<LightLevelMaterials>,
+) {
+    let mut columns = Vec::new();
+    for ChunkLoaded(pos) in loaded.read() {
+        for column in mc_chunks(*pos) {
+            if !columns.contains(&column) {
+                columns.push(column);
+            }
+        }
+    }
+    let (Some(chunks), Some(mut chunk_assets)) = (received_chunks, chunk_assets) else {
+        return;
+    };
+    columns.retain(|column| chunks.chunks.contains_key(column));
+    if columns.is_empty() {
+        return;
+    }
+
+    for (entity, ChunkPart(chunk)) in &parts {
+        if columns.contains(&(chunk.x, chunk.z)) {
+            commands.entity(entity).despawn();
+        }
+    }
+    let sections = chunks.dimension_height.div_ceil(CHUNK_SIZE as u32) as i32;
+    for &(chunk_x, chunk_z) in &columns {
+        for index in 0..sections {
+            chunk_groups.remove(IVec3::new(chunk_x, index, chunk_z));
+        }
+    }
+
+    mesh_columns(
+        &columns,
+        &chunks,
+        &mut commands,
+        &mut materials,
+        &mut chunk_assets,
+        &mut chunk_groups,
+        &mut chunk_lighting,
+    );
+    chunk_assets.register_light_levels(&mut light_levels);
+}
+
+/// Atlas and materials for the parts of a chunk drawn outside its chunk
+/// group, kept once the scene is set up to mesh chunks loaded later.
+#[derive(Resource)]
+struct ChunkAssets {
+    atlas: Arc<TextureAtlas>,
+    atlas_texture: Handle<Image>,
+    /// One per animated block type, so its UVs can move on their own.
+    animated: HashMap<u32, Handle<StandardMaterial>>,
+    /// One per light emission level, created on first use.
+    emissive: HashMap<u8, Handle<StandardMaterial>>,
+    /// Emission levels of materials created since they were last
+    /// registered with brightness.
+    unregistered: Vec<u8>,
+    /// Identical sections (solid stone, repeated terrain) are meshed once.
+    section_meshes: SectionMeshCache<ChunkMesh>,
+}
+
+/// The chunk group a separately drawn part of a chunk, such as its animated
+/// or glowing blocks, belongs to.
+#[derive(Component)]
+struct ChunkPart(IVec3);
+
+impl ChunkAssets {
+    fn emissive_material(
+        &mut self,
+        light_emission: u8,
+        materials: &mut Assets<StandardMaterial>,
+    ) -> Handle<StandardMaterial> {
+        let atlas_texture = &self.atlas_texture;
+        let unregistered = &mut self.unregistered;
+        self.emissive
+            .entry(light_emission)
+            .or_insert_with(|| {
+                unregistered.push(light_emission);
+                materials.add(BlockRenderer::emissive_material(
+                    atlas_texture.clone(),
+                    light_emission,
+                ))
+            })
+            .clone()
+    }
+
+    /// Have brightness set the glow of the light-emitting block materials
+    /// created since the last call.
+    fn register_light_levels(&mut self, light_levels: &mut LightLevelMaterials) {
+        for light_emission in self.unregistered.drain(..) {
+            light_levels.add(self.emissive[&light_emission].clone(), light_emission);
+        }
+    }
+}
+
+/// Add a chunk's ordinary blocks to its chunk group, drawn with the shared
+/// chunk material, and spawn each animated block type and each level of
+/// light-emitting blocks with its own material. The meshes are built in the
+/// background and appear once uploaded.
+fn spawn_chunk_meshes(
+    commands: &mut Commands,
+    materials: &mut Assets<StandardMaterial>,
+    chunk_assets: &mut ChunkAssets,
+    chunk_groups: &mut ChunkGroups,
+    chunk: IVec3,
+    origin: IVec3,
+    chunk_mesh: &ChunkMesh,
+) {
+    let (static_mesh, animated) = BlockRenderer::split_animated(chunk_mesh, &chunk_assets.atlas);
+    let (unlit, emissive) = BlockRenderer::split_emissive(&static_mesh);
+    chunk_groups.insert(chunk, origin, unlit);
+
+    let mut parts = Vec::new();
+    for (block_type, part) in animated {
+        if let Some(material) = chunk_assets.animated.get(&block_type) {
+            parts.push((material.clone(), part));
+        }
+    }
+    for (light_emission, part) in emissive {
+        let material = chunk_assets.emissive_material(light_emission, materials);
+        parts.push((material, part));
+    }
+
+    let transform = Transform::from_translation(origin.as_vec3());
+    for (material, part) in parts {
+        if part.quads.is_empty() {
+            continue;
+        }
+        commands.spawn((
+            PendingChunkMesh::spawn(part, chunk_assets.atlas.clone(), IVec3::ZERO),
+            MeshMaterial3d(material),
+            transform,
+            ChunkBounds::chunk(origin),
+            ChunkPart(chunk),
+        ));
+    }
 }
 
 fn grab_cursor(mut cursor_options: Single<&mut CursorOptions>) {
@@ -1,27 +1,68 @@
+use azalea_block::BlockState;
+use azalea_registry::builtin::BlockKind;
+use bevy::prelude::*;
+use ferrum_config::Config;
+use ferrum_core::BlockId;
+use ferrum_meshing_cpu::CHUNK_SIZE;
+use ferrum_protocol::codec::read_varint;
 use ferrum_protocol::ChunkDataPacket;
-use ferrum_world::{Chunk, ChunkPos, World};
+use ferrum_render::FirstPersonView;
+use ferrum_world::{Chunk, ChunkPos, ChunkStreamer, CompressedChunk, World, WORLD_MIN_Y};
+use std::ops::RangeInclusive;
 use thiserror::Error;
 
+/// Width, height and depth of a Minecraft chunk section.
+const SECTION_SIZE: usize = 16;
+
+/// Biome cells per section, one per 4x4x4 blocks.
+const SECTION_BIOMES: usize = 64;
+
+/// Bits per entry sent with an indirect palette, and the bits each entry
+/// then takes. Block palettes below 4 bits are still packed at 4.
+const BLOCK_PALETTE_BITS: RangeInclusive<u8> = 4..=8;
+const BIOME_PALETTE_BITS: RangeInclusive<u8> = 1..=3;
+
 #[derive(Debug, Error)]
 pub enum ChunkLoaderError {
-    #[error("Failed to parse chunk data")]
-    ParseError,
+    #[error("Failed to parse chunk data: {0}")]
+    ParseError(String),
 
     #[error("Invalid chunk position: ({x}, {z})")]
     InvalidPosition { x: i32, z: i32 },
 }
 
+#[derive(Resource)]
 pub struct ChunkLoader {
     world: World,
+    /// Packets waiting to be merged, grouped by the chunk of ours they fill.
+    streamer: ChunkStreamer<Vec<ChunkDataPacket>>,
 }
 
 impl ChunkLoader {
     pub fn new() -> Self {
         Self {
             world: World::new(),
+            streamer: ChunkStreamer::default(),
+        }
+    }
+
+    /// Load at most `budget` queued chunks per call to
+    /// [`Self::process_pending`].
+    pub fn with_budget(budget: usize) -> Self {
+        Self {
+            world: World::new(),
+            streamer: ChunkStreamer::new(budget),
         }
     }
 
+    pub fn streamer(&self) -> &ChunkStreamer<Vec<ChunkDataPacket>> {
+        &self.streamer
+    }
+
+    pub fn streamer_mut(&mut self) -> &mut ChunkStreamer<Vec<ChunkDataPacket>> {
+        &mut self.streamer
+    }
+
     pub fn world(&self) -> &World {
         &self.world
     }
@@ -31,20 +72,48 @@ impl ChunkLoader {
     }
 
     pub fn load_chunk(&mut self, packet: &ChunkDataPacket) -> Result<(), ChunkLoaderError> {
-        let pos = ChunkPos {
-            x: packet.x,
-            z: packet.z,
-        };
+        self.load_decoded(decode_chunk(packet)?);
+        Ok(())
+    }
 
-        let chunk = Chunk::new();
+    /// Merge a decoded packet into its chunk, creating the chunk if this is
+    /// the first of its packets to arrive.
+    pub fn load_decoded(&mut self, decoded: DecodedChunk) {
+        let mut chunk = self.world.remove_chunk(decoded.pos).unwrap_or_default();
+        decoded.merge_into(&mut chunk);
+        self.world.set_chunk(decoded.pos, chunk);
+    }
 
-        self.world.set_chunk(pos, chunk);
+    /// Queue a chunk packet to be loaded by a later [`Self::process_pending`]
+    /// instead of right away. Packets for the same chunk of ours are loaded
+    /// together, a resent packet replacing the queued one.
+    pub fn queue_chunk(&mut self, packet: ChunkDataPacket) {
+        let pos = chunk_pos(packet.x, packet.z);
+        let mut packets = self.streamer.cancel(pos).unwrap_or_default();
+        packets.retain(|queued| (queued.x, queued.z) != (packet.x, packet.z));
+        packets.push(packet);
+        self.streamer.queue(pos, packets);
+    }
 
-        Ok(())
+    /// Load this frame's share of queued chunks, nearest to `center` first.
+    /// Returns the chunks loaded so only they get lit and meshed this frame.
+    pub fn process_pending(&mut self, center: ChunkPos) -> Vec<ChunkPos> {
+        let batch = self.streamer.next_batch(center);
+        let mut loaded = Vec::with_capacity(batch.len());
+        for (pos, packets) in batch {
+            for packet in &packets {
+                if let Err(e) = self.load_chunk(packet) {
+                    warn!("Chunk ({}, {}) left unloaded: {}", packet.x, packet.z, e);
+                }
+            }
+            loaded.push(pos);
+        }
+        loaded
     }
 
     pub fn unload_chunk(&mut self, x: i32, z: i32) -> Option<Chunk> {
         let pos = ChunkPos { x, z };
+        self.streamer.cancel(pos);
         self.world.remove_chunk(pos)
     }
 }
@@ -55,9 +124,473 @@ impl Default for ChunkLoader {
     }
 }
 
+/// A queued chunk was loaded into the [`ChunkLoader`]'s world and is ready
+/// to be lit and meshed.
+#[derive(Message, Debug, Clone, Copy)]
+pub struct ChunkLoaded(pub ChunkPos);
+
+/// Loads the chunks queued on the [`ChunkLoader`], `client.chunks_per_frame`
+/// a frame, nearest the camera first.
+pub struct ChunkLoaderPlugin;
+
+impl Plugin for ChunkLoaderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChunkLoader>()
+            .add_message::<ChunkLoaded>()
+            .add_systems(Update, process_pending_chunks);
+    }
+}
+
+/// Load this frame's share of the queued chunks, spiralling out from the
+/// chunk the camera is in.
+pub fn process_pending_chunks(
+    config: Res<Config>,
+    cameras: Query<&GlobalTransform, With<FirstPersonView>>,
+    mut chunk_loader: ResMut<ChunkLoader>,
+    mut chunks_loaded: MessageWriter<ChunkLoaded>,
+) {
+    let budget = config.client.chunks_per_frame as usize;
+    if chunk_loader.streamer().budget() != budget {
+        chunk_loader.streamer_mut().set_budget(budget);
+    }
+    if chunk_loader.streamer().pending_count() == 0 {
+        return;
+    }
+
+    let camera = cameras
+        .iter()
+        .next()
+        .map_or(Vec3::ZERO, GlobalTransform::translation);
+    let center = ChunkPos {
+        x: (camera.x / CHUNK_SIZE as f32).floor() as i32,
+        z: (camera.z / CHUNK_SIZE as f32).floor() as i32,
+    };
+    let loaded = chunk_loader.process_pending(center);
+    debug!(
+        "Loaded {} queued chunks, {} left",
+        loaded.len(),
+        chunk_loader.streamer().pending_count()
+    );
+    chunks_loaded.write_batch(loaded.into_iter().map(ChunkLoaded));
+}
+
+/// The blocks of one chunk packet, placed in the quarter of our chunk it
+/// covers. Our chunks are 32 blocks wide and Minecraft's 16, so two to four
+/// packets are merged into each of ours.
+pub struct DecodedChunk {
+    pub pos: ChunkPos,
+    /// Where the packet's columns start within our chunk.
+    pub x_offset: usize,
+    pub z_offset: usize,
+    /// Air outside the packet's quarter.
+    pub blocks: CompressedChunk,
+}
+
+impl DecodedChunk {
+    /// Overwrite the packet's quarter of `chunk`, leaving the blocks other
+    /// packets filled in as they are.
+    pub fn merge_into(&self, chunk: &mut Chunk) {
+        for x in self.x_offset..self.x_offset + SECTION_SIZE {
+            for y in 0..CHUNK_SIZE {
+                for z in self.z_offset..self.z_offset + SECTION_SIZE {
+                    chunk.set_block(x, y, z, self.blocks.get_block(x, y, z));
+                }
+            }
+        }
+    }
+}
+
+/// Our chunk holding Minecraft chunk `(x, z)`.
+pub fn chunk_pos(x: i32, z: i32) -> ChunkPos {
+    let packets_across = (CHUNK_SIZE / SECTION_SIZE) as i32;
+    ChunkPos {
+        x: x.div_euclid(packets_across),
+        z: z.div_euclid(packets_across),
+    }
+}
+
+/// The Minecraft chunks held by our chunk `pos`.
+pub fn mc_chunks(pos: ChunkPos) -> [(i32, i32); 4] {
+    let packets_across = (CHUNK_SIZE / SECTION_SIZE) as i32;
+    let (x, z) = (pos.x * packets_across, pos.z * packets_across);
+    [(x, z), (x + 1, z), (x, z + 1), (x + 1, z + 1)]
+}
+
+/// Decode the block sections of a chunk packet, assuming the overworld's
+/// height.
+pub fn decode_chunk(packet: &ChunkDataPacket) -> Result<DecodedChunk, ChunkLoaderError> {
+    decode_column(packet.x, packet.z, &packet.chunk_data.data)
+}
+
+/// Decode the data buffer of the packet for Minecraft chunk `(x, z)`. See
+/// [`decode_sections`] for how sections are placed.
+pub fn decode_column(x: i32, z: i32, data: &[u8]) -> Result<DecodedChunk, ChunkLoaderError> {
+    let packets_across = (CHUNK_SIZE / SECTION_SIZE) as i32;
+    let x_offset = x.rem_euclid(packets_across) as usize * SECTION_SIZE;
+    let z_offset = z.rem_euclid(packets_across) as usize * SECTION_SIZE;
+    Ok(DecodedChunk {
+        pos: chunk_pos(x, z),
+        x_offset,
+        z_offset,
+        blocks: decode_sections(data, WORLD_MIN_Y, x_offset, z_offset)?,
+    })
+}
+
+/// Decode the sections of a chunk packet's data buffer, the lowest at world
+/// height `min_y`, into our storage.
+///
+/// Our chunks cover world heights 0 to 32, so the two 16x16x16 sections
+/// there fill x and z from `x_offset` and `z_offset` to 16 past them;
+/// sections outside it are skipped and parsing stops past the top. Block
+/// states are mapped to our block types with [`mc_block_state_to_type`].
+/// Each section is a block count, then paletted containers of block states
+/// and biomes: a single value with no data at 0 bits per entry, a palette
+/// for a few bits and global ids (the direct palette) above that.
+pub fn decode_sections(
+    mut data: &[u8],
+    min_y: i32,
+    x_offset: usize,
+    z_offset: usize,
+) -> Result<CompressedChunk, ChunkLoaderError> {
+    let mut chunk = CompressedChunk::new();
+    let mut section_y = min_y;
+    while !data.is_empty() && section_y < CHUNK_SIZE as i32 {
+        let _block_count = i16::from_be_bytes(take(&mut data)?);
+        let states = read_paletted(&mut data, SECTION_SIZE.pow(3), BLOCK_PALETTE_BITS)?;
+        read_paletted(&mut data, SECTION_BIOMES, BIOME_PALETTE_BITS)?;
+
+        if section_y >= 0 {
+            for (index, &state) in states.iter().enumerate() {
+                let block_type = u16::try_from(state).map_or(0, mc_block_state_to_type);
+                if block_type == 0 {
+                    continue;
+                }
+                // Entries are ordered by y, then z, then x
+                let x = x_offset + index % SECTION_SIZE;
+                let z = z_offset + index / SECTION_SIZE % SECTION_SIZE;
+                let y = section_y as usize + index / (SECTION_SIZE * SECTION_SIZE);
+                chunk.set_block(x, y, z, BlockId::new(block_type as u16));
+            }
+        }
+        section_y += SECTION_SIZE as i32;
+    }
+    Ok(chunk)
+}
+
+/// Read a paletted container of `entries` values and return the global id
+/// of each. `indirect_bits` are the bits per entry that come with a
+/// palette, the lowest also being the fewest bits entries are packed in.
+fn read_paletted(
+    data: &mut &[u8],
+    entries: usize,
+    indirect_bits: RangeInclusive<u8>,
+) -> Result<Vec<u32>, ChunkLoaderError> {
+    let bits = take::<1>(data)?[0];
+    if bits == 0 {
+        return Ok(vec![read_id(data)?; entries]);
+    }
+    let (bits, palette) = if bits <= *indirect_bits.end() {
+        let length = read_id(data)? as usize;
+        let palette = (0..length)
+            .map(|_| read_id(data))
+            .collect::<Result<Vec<_>, _>>()?;
+        (bits.max(*indirect_bits.start()), Some(palette))
+    } else if bits <= 32 {
+        (bits, None)
+    } else {
+        return Err(ChunkLoaderError::ParseError(format!(
+            "{bits} bits per entry"
+        )));
+    };
+
+    // Entries never straddle two longs, so the top bits of each may be unused
+    let per_long = 64 / bits as usize;
+    let mask = (1u64 << bits) - 1;
+    let mut values = Vec::with_capacity(entries);
+    for _ in 0..entries.div_ceil(per_long) {
+        let mut word = u64::from_be_bytes(take(data)?);
+        for _ in 0..per_long.min(entries - values.len()) {
+            let index = (word & mask) as u32;
+            word >>= bits;
+            values.push(match &palette {
+                Some(palette) => *palette.get(index as usize).ok_or_else(|| {
+                    ChunkLoaderError::ParseError(format!("palette index {index} out of range"))
+                })?,
+                None => index,
+            });
+        }
+    }
+    Ok(values)
+}
+
+fn read_id(data: &mut &[u8]) -> Result<u32, ChunkLoaderError> {
+    match read_varint(data) {
+        Ok(Some(id)) if id >= 0 => Ok(id as u32),
+        Ok(Some(id)) => Err(ChunkLoaderError::ParseError(format!("negative id {id}"))),
+        _ => Err(ChunkLoaderError::ParseError("truncated VarInt".into())),
+    }
+}
+
+fn take<const N: usize>(data: &mut &[u8]) -> Result<[u8; N], ChunkLoaderError> {
+    if data.len() < N {
+        return Err(ChunkLoaderError::ParseError(
+            "unexpected end of data".into(),
+        ));
+    }
+    let (bytes, rest) = data.split_at(N);
+    *data = rest;
+    Ok(bytes.try_into().unwrap())
+}
+
+/// Our block type for a Minecraft block state id.
+pub fn mc_block_state_to_type(state_id: u16) -> u32 {
+    let Ok(block_state) = BlockState::try_from(state_id) else {
+        return 0;
+    };
+    let kind: BlockKind = block_state.into();
+
+    match kind {
+        BlockKind::Air | BlockKind::CaveAir | BlockKind::VoidAir => 0,
+        BlockKind::Stone
+        | BlockKind::Granite
+        | BlockKind::Diorite
+        | BlockKind::Andesite
+        | BlockKind::PolishedGranite
+        | BlockKind::PolishedDiorite
+        | BlockKind::PolishedAndesite => 1,
+        BlockKind::Dirt | BlockKind::CoarseDirt | BlockKind::RootedDirt => 2,
+        BlockKind::GrassBlock | BlockKind::Podzol => 3,
+        BlockKind::Bedrock => 4,
+        BlockKind::Water => 5,
+        BlockKind::Lava => 6,
+        BlockKind::Sand | BlockKind::RedSand => 7,
+        BlockKind::Gravel => 8,
+        BlockKind::GoldOre | BlockKind::DeepslateGoldOre => 9,
+        BlockKind::IronOre | BlockKind::DeepslateIronOre => 10,
+        BlockKind::CoalOre | BlockKind::DeepslateCoalOre => 11,
+        BlockKind::OakLog
+        | BlockKind::SpruceLog
+        | BlockKind::BirchLog
+        | BlockKind::JungleLog
+        | BlockKind::AcaciaLog
+        | BlockKind::DarkOakLog
+        | BlockKind::CherryLog => 12,
+        BlockKind::OakLeaves
+        | BlockKind::SpruceLeaves
+        | BlockKind::BirchLeaves
+        | BlockKind::JungleLeaves
+        | BlockKind::AcaciaLeaves
+        | BlockKind::DarkOakLeaves
+        | BlockKind::CherryLeaves
+        | BlockKind::AzaleaLeaves
+        | BlockKind::FloweringAzaleaLeaves => 13,
+        BlockKind::OakPlanks
+        | BlockKind::SprucePlanks
+        | BlockKind::BirchPlanks
+        | BlockKind::JunglePlanks
+        | BlockKind::AcaciaPlanks
+        | BlockKind::DarkOakPlanks
+        | BlockKind::CherryPlanks => 14,
+        BlockKind::Cobblestone | BlockKind::MossyCobblestone => 15,
+        BlockKind::DiamondOre | BlockKind::DeepslateDiamondOre => 16,
+        BlockKind::Deepslate => 17,
+        BlockKind::Snow | BlockKind::SnowBlock => 18,
+        BlockKind::Ice | BlockKind::PackedIce | BlockKind::BlueIce => 19,
+        BlockKind::Clay => 20,
+        BlockKind::Obsidian => 21,
+        BlockKind::Netherrack => 22,
+        BlockKind::Glowstone => 23,
+        BlockKind::SoulSand => 24,
+        BlockKind::Terracotta => 25,
+        BlockKind::ShortGrass | BlockKind::TallGrass | BlockKind::Fern | BlockKind::LargeFern => 0,
+        _ => {
+            if state_id == 0 {
+                0
+            } else {
+                1
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ferrum_protocol::codec::write_varint;
+
+    const STONE_STATE: u32 = 1;
+
+    fn stone() -> BlockId {
+        BlockId::new(1)
+    }
+
+    /// A section with `states` for its blocks and a single biome.
+    fn section(states: Vec<u8>) -> Vec<u8> {
+        let mut section = 4096i16.to_be_bytes().to_vec();
+        section.extend(states);
+        section.extend([0, 0]);
+        section
+    }
+
+    fn single_value(state: u32) -> Vec<u8> {
+        let mut container = vec![0];
+        write_varint(&mut container, state as i32);
+        container
+    }
+
+    /// `values` packed `bits` to an entry, with `palette` in front if given.
+    fn packed(bits: u8, palette: Option<&[u32]>, values: &[u32]) -> Vec<u8> {
+        let mut container = vec![bits];
+        if let Some(palette) = palette {
+            write_varint(&mut container, palette.len() as i32);
+            for &id in palette {
+                write_varint(&mut container, id as i32);
+            }
+        }
+        let per_long = 64 / bits as usize;
+        for entries in values.chunks(per_long) {
+            let word = entries.iter().enumerate().fold(0u64, |word, (i, &value)| {
+                word | ((value as u64) << (i * bits as usize))
+            });
+            container.extend(word.to_be_bytes());
+        }
+        container
+    }
+
+    /// Stone on every other layer of a section, air on the rest.
+    fn striped() -> Vec<u32> {
+        (0..4096).map(|i| ((i / 256) % 2 == 0) as u32).collect()
+    }
+
+    #[test]
+    fn test_decode_single_value_section() {
+        // An air section below the world floor, then an all-stone one from y 0
+        let mut data = section(single_value(0));
+        data.extend(section(single_value(STONE_STATE)));
+        let chunk = decode_sections(&data, -16, 0, 0).unwrap();
+
+        for x in 0..16 {
+            for y in 0..16 {
+                for z in 0..16 {
+                    assert_eq!(chunk.get_block(x, y, z), stone(), "({x}, {y}, {z})");
+                }
+            }
+        }
+        assert_eq!(chunk.get_block(0, 16, 0).as_u16(), 0);
+        assert_eq!(chunk.get_block(16, 0, 0).as_u16(), 0);
+        assert_eq!(chunk.get_block(0, 0, 16).as_u16(), 0);
+        assert_eq!(chunk.count_of(stone()), 4096);
+    }
+
+    #[test]
+    fn test_decode_indirect_and_direct_palettes() {
+        let mut data = section(packed(4, Some(&[0, STONE_STATE]), &striped()));
+        data.extend(section(packed(15, None, &striped())));
+        let chunk = decode_sections(&data, 0, 0, 0).unwrap();
+
+        for y in 0..32 {
+            let expected = if y % 2 == 0 { stone() } else { BlockId::new(0) };
+            assert_eq!(chunk.get_block(3, y, 7), expected, "y {y}");
+        }
+        assert_eq!(chunk.count_of(stone()), 16 * 16 * 16);
+
+        // Two bits on the wire are still packed at four for blocks
+        let mut low_bits = section(packed(4, Some(&[0, STONE_STATE]), &striped()));
+        low_bits[2] = 2;
+        assert_eq!(
+            decode_sections(&low_bits, 0, 0, 0)
+                .unwrap()
+                .count_of(stone()),
+            2048
+        );
+    }
+
+    #[test]
+    fn test_decode_sections_above_the_chunk_are_skipped() {
+        let mut data = Vec::new();
+        for _ in 0..24 {
+            data.extend(section(single_value(STONE_STATE)));
+        }
+        let chunk = decode_sections(&data, WORLD_MIN_Y, 0, 0).unwrap();
+        assert_eq!(chunk.count_of(stone()), 2 * 4096);
+    }
+
+    #[test]
+    fn test_decode_malformed_sections() {
+        let full = section(packed(4, Some(&[0, STONE_STATE]), &striped()));
+        let truncated = &full[..full.len() - 9];
+        assert!(matches!(
+            decode_sections(truncated, 0, 0, 0),
+            Err(ChunkLoaderError::ParseError(_))
+        ));
+
+        // Index 1 with a one-entry palette
+        let bad_index = section(packed(4, Some(&[0]), &striped()));
+        assert!(decode_sections(&bad_index, 0, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_decoded_packets_fill_their_quarter_of_the_chunk() {
+        // Air below the world floor, then stone from y 0 to 16
+        let mut data = Vec::new();
+        for _ in (WORLD_MIN_Y..0).step_by(16) {
+            data.extend(section(single_value(0)));
+        }
+        data.extend(section(single_value(STONE_STATE)));
+
+        let decoded = decode_column(1, 0, &data).unwrap();
+        assert_eq!(decoded.pos, ChunkPos { x: 0, z: 0 });
+        assert_eq!((decoded.x_offset, decoded.z_offset), (16, 0));
+
+        let mut loader = ChunkLoader::new();
+        loader.load_decoded(decoded);
+        let world = loader.world();
+        for y in 0..16 {
+            assert_eq!(world.get_block(16, y, 0), stone(), "y {y}");
+            assert_eq!(world.get_block(31, y, 15), stone(), "y {y}");
+            assert_eq!(world.get_block(15, y, 0).as_u16(), 0, "y {y}");
+            assert_eq!(world.get_block(16, y, 16).as_u16(), 0, "y {y}");
+        }
+
+        // The packet west of it fills the other half without clearing it
+        loader.load_decoded(decode_column(0, 0, &data).unwrap());
+        assert_eq!(loader.world().get_block(0, 0, 0), stone());
+        assert_eq!(loader.world().get_block(16, 0, 0), stone());
+        assert_eq!(loader.world().chunk_count(), 1);
+
+        // Negative packets land in the far quarter of the chunk before 0
+        let decoded = decode_column(-1, -1, &data).unwrap();
+        assert_eq!(decoded.pos, ChunkPos { x: -1, z: -1 });
+        assert_eq!((decoded.x_offset, decoded.z_offset), (16, 16));
+    }
+
+    #[test]
+    fn test_mc_chunks_are_held_by_their_chunk() {
+        for pos in [ChunkPos { x: 0, z: 0 }, ChunkPos { x: -1, z: 3 }] {
+            for (x, z) in mc_chunks(pos) {
+                assert_eq!(chunk_pos(x, z), pos);
+            }
+        }
+    }
+
+    #[test]
+    fn test_budget_follows_the_config() {
+        let mut app = App::new();
+        app.add_plugins(ChunkLoaderPlugin)
+            .insert_resource(Config::from_str("[client]\nchunks_per_frame = 3\n").unwrap());
+        app.update();
+        assert_eq!(app.world().resource::<ChunkLoader>().streamer().budget(), 3);
+
+        app.world_mut()
+            .resource_mut::<Config>()
+            .client
+            .chunks_per_frame = 12;
+        app.update();
+        assert_eq!(
+            app.world().resource::<ChunkLoader>().streamer().budget(),
+            12
+        );
+    }
 
     #[test]
     fn test_chunk_loader_creation() {
@@ -1,7 +1,9 @@
+use azalea_buf::AzaleaWrite;
 use azalea_core::position::ChunkSectionBlockPos;
 use azalea_protocol::connect::{Connection, ConnectionError as AzaleaConnectionError};
 use azalea_protocol::packets::config::{
     s_cookie_response::ServerboundCookieResponse as ConfigServerboundCookieResponse,
+    s_custom_payload::ServerboundCustomPayload as ConfigServerboundCustomPayload,
     s_finish_configuration::ServerboundFinishConfiguration,
     s_keep_alive::ServerboundKeepAlive as ConfigServerboundKeepAlive,
     s_pong::ServerboundPong as ConfigServerboundPong,
@@ -10,6 +12,7 @@ use azalea_protocol::packets::config::{
 use azalea_protocol::packets::game::{
     s_accept_teleportation::ServerboundAcceptTeleportation,
     s_chunk_batch_received::ServerboundChunkBatchReceived,
+    s_custom_payload::ServerboundCustomPayload as GameServerboundCustomPayload,
     s_keep_alive::ServerboundKeepAlive as GameServerboundKeepAlive,
     s_player_loaded::ServerboundPlayerLoaded, ClientboundGamePacket,
 };
@@ -24,6 +27,9 @@ use azalea_protocol::packets::login::{
 use azalea_protocol::packets::{ClientIntention, PROTOCOL_VERSION};
 use azalea_world::chunk_storage::Chunk;
 use bevy::prelude::*;
+use ferrum_core::BlockId;
+use ferrum_protocol::{ChannelRegistry, ConnectionState, Disconnected, CLIENT_BRAND};
+use ferrum_world::ChunkColumn;
 use std::collections::HashMap;
 use std::io::Cursor;
 use std::net::ToSocketAddrs;
@@ -31,6 +37,9 @@ use std::time::{Duration, Instant};
 use thiserror::Error;
 use uuid::Uuid;
 
+use super::chunk_loader::mc_block_state_to_type;
+use super::keepalive::KeepAlive;
+
 #[derive(Debug, Error)]
 pub enum ConnectionError {
     #[error("Failed to connect to server: {0}")]
@@ -59,6 +68,12 @@ pub enum ConnectionError {
 
     #[error("Chunk parse failed: {0}")]
     ChunkParseFailed(String),
+
+    #[error("Disconnected by server: {0}")]
+    Disconnected(String),
+
+    #[error("Timed out waiting for a keepalive from the server")]
+    TimedOut,
 }
 
 /// Storage for received chunk data from the server
@@ -134,6 +149,37 @@ impl ReceivedChunks {
         );
         Ok(())
     }
+
+    /// Our block type at world position `(x, y, z)`. Blocks in chunks not
+    /// received, or above or below the world, are air.
+    pub fn block_at(&self, x: i32, y: i32, z: i32) -> BlockId {
+        let state = self
+            .chunks
+            .get(&(x.div_euclid(16), z.div_euclid(16)))
+            .and_then(|column| column.get(usize::try_from(y - self.min_y).ok()?))
+            .and_then(|layer| layer.get(z.rem_euclid(16) as usize))
+            .and_then(|row| row.get(x.rem_euclid(16) as usize))
+            .copied()
+            .unwrap_or(0);
+        BlockId::new(mc_block_state_to_type(state) as u16)
+    }
+
+    /// The `[y][z][x]` block states received at `(chunk_x, chunk_z)`,
+    /// stacked into a column of sections of our block types. All-air
+    /// sections stay unallocated.
+    pub fn column(&self, chunk_x: i32, chunk_z: i32) -> Option<ChunkColumn> {
+        let chunk_data = self.chunks.get(&(chunk_x, chunk_z))?;
+        let mut column = ChunkColumn::with_height(self.min_y, chunk_data.len() as u32);
+        for (y, layer) in chunk_data.iter().enumerate() {
+            for (z, row) in layer.iter().enumerate() {
+                for (x, &state_id) in row.iter().enumerate() {
+                    let block_type = mc_block_state_to_type(state_id);
+                    column.set_block(x, self.min_y + y as i32, z, BlockId::new(block_type as u16));
+                }
+            }
+        }
+        Some(column)
+    }
 }
 
 impl Default for ReceivedChunks {
@@ -142,8 +188,32 @@ impl Default for ReceivedChunks {
     }
 }
 
-/// Connect to a Minecraft server and play through the full protocol flow
-pub async fn connect_and_play(address: String) -> Result<ReceivedChunks, ConnectionError> {
+/// The reason a server gave when kicking us, read from the disconnect
+/// packet's wire form for the state `protocol` is in, which the kick ends.
+/// A packet that cannot be read closes the connection without a reason.
+pub fn kick_reason(protocol: &mut ConnectionState, packet: &impl AzaleaWrite) -> String {
+    let mut body = Vec::new();
+    let _ = packet.azalea_write(&mut body);
+    match protocol.handle_disconnect(&body) {
+        Ok(Disconnected { reason }) => reason,
+        Err(e) => {
+            warn!("{}", e);
+            protocol.close();
+            "Disconnected by server".to_string()
+        }
+    }
+}
+
+/// Connect to a Minecraft server and play through the full protocol flow,
+/// giving up if the server sends no keepalive for `keepalive_timeout`.
+///
+/// The connection is only kept for the first 5 seconds of the game phase,
+/// so the keepalive timeout covers login, config and that window. A timeout
+/// longer than the window can only fire before the game starts.
+pub async fn connect_and_play(
+    address: String,
+    keepalive_timeout: Duration,
+) -> Result<ReceivedChunks, ConnectionError> {
     info!("Starting connection to {}", address);
 
     // Resolve address
@@ -155,6 +225,7 @@ pub async fn connect_and_play(address: String) -> Result<ReceivedChunks, Connect
 
     // Phase 1: Handshake
     info!("Phase 1: Handshake");
+    let mut protocol = ConnectionState::new();
     let mut conn =
         Connection::<ClientboundHandshakePacket, ServerboundHandshakePacket>::new(&socket_addr)
             .await?;
@@ -171,6 +242,9 @@ pub async fn connect_and_play(address: String) -> Result<ReceivedChunks, Connect
     // Phase 2: Login
     info!("Phase 2: Login");
     let mut conn = conn.login();
+    protocol
+        .transition_to_login()
+        .map_err(|e| ConnectionError::HandshakeFailed(e.to_string()))?;
 
     conn.write(ServerboundHello {
         name: "FerrumBot".to_string(),
@@ -188,6 +262,12 @@ pub async fn connect_and_play(address: String) -> Result<ReceivedChunks, Connect
                         "Setting compression threshold: {}",
                         compression.compression_threshold
                     );
+                    // azalea's connection compresses its own packets, so
+                    // ferrum_protocol's CodecStack is not involved here;
+                    // `protocol` only keeps track of the threshold
+                    protocol
+                        .enable_compression(compression.compression_threshold)
+                        .map_err(|e| ConnectionError::LoginFailed(e.to_string()))?;
                     conn.set_compression_threshold(compression.compression_threshold);
                 }
                 ClientboundLoginPacket::CookieRequest(cookie_req) => {
@@ -210,12 +290,12 @@ pub async fn connect_and_play(address: String) -> Result<ReceivedChunks, Connect
                     break;
                 }
                 ClientboundLoginPacket::LoginDisconnect(disconnect) => {
-                    return Err(ConnectionError::LoginFailed(format!(
-                        "Server disconnected: {:?}",
-                        disconnect.reason
-                    )));
+                    let reason = kick_reason(&mut protocol, &disconnect);
+                    return Err(ConnectionError::Disconnected(reason));
                 }
                 ClientboundLoginPacket::Hello(_) => {
+                    // Encryption is never enabled, so `protocol` is never
+                    // marked encrypted either
                     return Err(ConnectionError::LoginFailed(
                         "Server sent EncryptionRequest (online_mode is enabled). Configure server \
                          with online_mode=false and encryption=false"
@@ -232,12 +312,24 @@ pub async fn connect_and_play(address: String) -> Result<ReceivedChunks, Connect
         }
     }
 
+    debug!(
+        "Login done: compression threshold {:?}, encrypted: {}",
+        protocol.compression_threshold(),
+        protocol.is_encrypted()
+    );
+
     // Phase 3: Config
     info!("Phase 3: Config");
     let mut conn = conn.config();
+    protocol
+        .transition_to_config()
+        .map_err(|e| ConnectionError::LoginFailed(e.to_string()))?;
+    let mut channels = ChannelRegistry::with_brand(CLIENT_BRAND);
+    // Keepalives start with the config phase and carry on through the game
+    let mut keepalive = KeepAlive::new(keepalive_timeout, Instant::now());
 
     loop {
-        match conn.read().await {
+        match keepalive.read(conn.read()).await? {
             Ok(packet) => match packet {
                 ClientboundConfigPacket::SelectKnownPacks(packs) => {
                     info!(
@@ -255,9 +347,12 @@ pub async fn connect_and_play(address: String) -> Result<ReceivedChunks, Connect
                 }
                 ClientboundConfigPacket::KeepAlive(keep_alive) => {
                     debug!("Config KeepAlive: {}", keep_alive.id);
-                    conn.write(ConfigServerboundKeepAlive { id: keep_alive.id })
-                        .await
-                        .map_err(|_| ConnectionError::PacketWriteFailed)?;
+                    keepalive.receive(keep_alive.id, Instant::now());
+                    for id in keepalive.take_replies() {
+                        conn.write(ConfigServerboundKeepAlive { id })
+                            .await
+                            .map_err(|_| ConnectionError::PacketWriteFailed)?;
+                    }
                 }
                 ClientboundConfigPacket::Ping(ping) => {
                     debug!("Config Ping: {}", ping.id);
@@ -272,6 +367,18 @@ pub async fn connect_and_play(address: String) -> Result<ReceivedChunks, Connect
                         .map_err(|_| ConnectionError::PacketWriteFailed)?;
                     break;
                 }
+                ClientboundConfigPacket::CustomPayload(payload) => {
+                    let channel = payload.identifier.to_string();
+                    debug!("Config custom payload on {}", channel);
+                    if let Some(reply) = channels.dispatch(&channel, &payload.data) {
+                        conn.write(ConfigServerboundCustomPayload {
+                            identifier: payload.identifier.clone(),
+                            data: reply.into(),
+                        })
+                        .await
+                        .map_err(|_| ConnectionError::PacketWriteFailed)?;
+                    }
+                }
                 ClientboundConfigPacket::CookieRequest(cookie_req) => {
                     info!("Config cookie request: {:?}", cookie_req.key);
                     conn.write(ConfigServerboundCookieResponse {
@@ -281,6 +388,10 @@ pub async fn connect_and_play(address: String) -> Result<ReceivedChunks, Connect
                     .await
                     .map_err(|_| ConnectionError::PacketWriteFailed)?;
                 }
+                ClientboundConfigPacket::Disconnect(disconnect) => {
+                    let reason = kick_reason(&mut protocol, &disconnect);
+                    return Err(ConnectionError::Disconnected(reason));
+                }
                 _ => {
                     debug!(
                         "Unhandled config packet: {:?}",
@@ -297,6 +408,9 @@ pub async fn connect_and_play(address: String) -> Result<ReceivedChunks, Connect
     // Phase 4: Game
     info!("Phase 4: Game");
     let mut conn = conn.game();
+    protocol
+        .transition_to_play()
+        .map_err(|e| ConnectionError::ConfigFailed(e.to_string()))?;
     let mut received_chunks = ReceivedChunks::new();
     let start_time = Instant::now();
     let collection_duration = Duration::from_secs(5);
@@ -311,7 +425,7 @@ pub async fn connect_and_play(address: String) -> Result<ReceivedChunks, Connect
             break;
         }
 
-        match conn.read().await {
+        match keepalive.read(conn.read()).await? {
             Ok(packet) => match packet {
                 ClientboundGamePacket::Login(login) => {
                     info!(
@@ -352,9 +466,12 @@ pub async fn connect_and_play(address: String) -> Result<ReceivedChunks, Connect
                 }
                 ClientboundGamePacket::KeepAlive(keep_alive) => {
                     debug!("Game KeepAlive: {}", keep_alive.id);
-                    conn.write(GameServerboundKeepAlive { id: keep_alive.id })
-                        .await
-                        .map_err(|_| ConnectionError::PacketWriteFailed)?;
+                    keepalive.receive(keep_alive.id, Instant::now());
+                    for id in keepalive.take_replies() {
+                        conn.write(GameServerboundKeepAlive { id })
+                            .await
+                            .map_err(|_| ConnectionError::PacketWriteFailed)?;
+                    }
                 }
                 ClientboundGamePacket::SetChunkCacheCenter(center) => {
                     info!("SetChunkCacheCenter: ({}, {})", center.x, center.z);
@@ -380,6 +497,22 @@ pub async fn connect_and_play(address: String) -> Result<ReceivedChunks, Connect
                     .await
                     .map_err(|_| ConnectionError::PacketWriteFailed)?;
                 }
+                ClientboundGamePacket::CustomPayload(payload) => {
+                    let channel = payload.identifier.to_string();
+                    debug!("Game custom payload on {}", channel);
+                    if let Some(reply) = channels.dispatch(&channel, &payload.data) {
+                        conn.write(GameServerboundCustomPayload {
+                            identifier: payload.identifier.clone(),
+                            data: reply.into(),
+                        })
+                        .await
+                        .map_err(|_| ConnectionError::PacketWriteFailed)?;
+                    }
+                }
+                ClientboundGamePacket::Disconnect(disconnect) => {
+                    let reason = kick_reason(&mut protocol, &disconnect);
+                    return Err(ConnectionError::Disconnected(reason));
+                }
                 _ => {
                     // Log other packets but don't crash
                     trace!(
@@ -406,3 +539,27 @@ pub async fn connect_and_play(address: String) -> Result<ReceivedChunks, Connect
     );
     Ok(received_chunks)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STONE_STATE: u16 = 1;
+
+    #[test]
+    fn test_block_at_world_coordinates() {
+        let mut received_chunks = ReceivedChunks::new();
+        let mut column = vec![vec![vec![0u16; 16]; 16]; 384];
+        // The block at x -1, y 64, z 17 sits in the far corner of chunk (-1, 1)
+        column[(64 - received_chunks.min_y) as usize][1][15] = STONE_STATE;
+        received_chunks.chunks.insert((-1, 1), column);
+
+        assert_eq!(received_chunks.block_at(-1, 64, 17).as_u16(), 1);
+        assert_eq!(received_chunks.block_at(-1, 65, 17).as_u16(), 0);
+        assert_eq!(received_chunks.block_at(15, 64, 17).as_u16(), 0);
+        // Outside the world and in chunks never received
+        assert_eq!(received_chunks.block_at(-1, -65, 17).as_u16(), 0);
+        assert_eq!(received_chunks.block_at(-1, 320, 17).as_u16(), 0);
+        assert_eq!(received_chunks.block_at(100, 64, 100).as_u16(), 0);
+    }
+}
@@ -0,0 +1,160 @@
+use azalea_protocol::packets::game::c_respawn::ClientboundRespawn;
+use bevy::prelude::*;
+use ferrum_render::{ChunkBounds, ChunkGroups, ChunkLods, LodMeshJobs};
+
+use super::chunk_loader::ChunkLoader;
+use super::connection::ReceivedChunks;
+use super::entity_sync::EntitySync;
+use crate::entity_renderer::ServerEntities;
+use crate::sky::DayNightCycle;
+
+/// World layout and sky behaviour of a dimension.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DimensionSettings {
+    pub name: String,
+    pub min_y: i32,
+    pub height: u32,
+    pub has_skylight: bool,
+    /// Fixed background color for dimensions without a day/night sky.
+    pub fixed_sky_color: Option<Color>,
+}
+
+impl DimensionSettings {
+    pub fn overworld() -> Self {
+        Self {
+            name: "minecraft:overworld".to_string(),
+            min_y: -64,
+            height: 384,
+            has_skylight: true,
+            fixed_sky_color: None,
+        }
+    }
+
+    /// Settings for a vanilla dimension. Unknown dimensions use the overworld
+    /// layout.
+    pub fn for_name(name: &str) -> Self {
+        match name {
+            "minecraft:the_nether" => Self {
+                name: name.to_string(),
+                min_y: 0,
+                height: 256,
+                has_skylight: false,
+                fixed_sky_color: Some(Color::srgb(0.2, 0.03, 0.03)),
+            },
+            "minecraft:the_end" => Self {
+                name: name.to_string(),
+                min_y: 0,
+                height: 256,
+                has_skylight: false,
+                fixed_sky_color: Some(Color::srgb(0.08, 0.06, 0.1)),
+            },
+            _ => Self {
+                name: name.to_string(),
+                ..Self::overworld()
+            },
+        }
+    }
+}
+
+/// The dimension the player is currently in.
+#[derive(Resource, Debug, Clone)]
+pub struct CurrentDimension(pub DimensionSettings);
+
+impl Default for CurrentDimension {
+    fn default() -> Self {
+        Self(DimensionSettings::overworld())
+    }
+}
+
+/// Sent when the server respawns the player, possibly into another dimension.
+#[derive(Message, Debug, Clone)]
+pub struct DimensionChanged(pub DimensionSettings);
+
+impl From<&ClientboundRespawn> for DimensionChanged {
+    fn from(respawn: &ClientboundRespawn) -> Self {
+        Self(DimensionSettings::for_name(
+            &respawn.common.dimension.to_string(),
+        ))
+    }
+}
+
+/// Discard chunks, entities and sky state belonging to the previous dimension.
+/// The player's inventory is left untouched.
+pub fn handle_dimension_change(
+    mut changes: MessageReader<DimensionChanged>,
+    mut current: ResMut<CurrentDimension>,
+    mut received_chunks: Option<ResMut<ReceivedChunks>>,
+    mut chunk_loader: Option<ResMut<ChunkLoader>>,
+    mut entity_sync: Option<ResMut<EntitySync>>,
+    mut server_entities: Option<ResMut<ServerEntities>>,
+    mut day_night: Option<ResMut<DayNightCycle>>,
+) {
+    let Some(DimensionChanged(settings)) = changes.read().last().cloned() else {
+        return;
+    };
+    info!("Entering dimension {}", settings.name);
+
+    if let Some(received_chunks) = received_chunks.as_mut() {
+        received_chunks.chunks.clear();
+        received_chunks.min_y = settings.min_y;
+        received_chunks.dimension_height = settings.height;
+        received_chunks.spawn_position = None;
+    }
+    if let Some(chunk_loader) = chunk_loader.as_mut() {
+        chunk_loader.world_mut().clear();
+        chunk_loader.streamer_mut().clear();
+    }
+    if let Some(entity_sync) = entity_sync.as_mut() {
+        entity_sync.clear();
+    }
+    if let Some(server_entities) = server_entities.as_mut() {
+        server_entities.entities.clear();
+    }
+    if let Some(day_night) = day_night.as_mut() {
+        **day_night = DayNightCycle {
+            fixed_sky_color: settings.fixed_sky_color,
+            ..default()
+        };
+    }
+
+    current.0 = settings;
+}
+
+/// Despawn the previous dimension's chunk meshes and forget the meshes kept
+/// for its chunks, so none of its terrain is drawn in the new one.
+pub fn discard_chunk_meshes(
+    mut commands: Commands,
+    mut changes: MessageReader<DimensionChanged>,
+    chunk_meshes: Query<Entity, With<ChunkBounds>>,
+    mut chunk_groups: Option<ResMut<ChunkGroups>>,
+    mut lods: Option<ResMut<ChunkLods>>,
+    mut lod_jobs: Option<ResMut<LodMeshJobs<IVec3>>>,
+) {
+    if changes.read().count() == 0 {
+        return;
+    }
+
+    for entity in &chunk_meshes {
+        commands.entity(entity).despawn();
+    }
+    if let Some(chunk_groups) = chunk_groups.as_mut() {
+        chunk_groups.clear();
+    }
+    if let Some(lods) = lods.as_mut() {
+        lods.clear();
+    }
+    if let Some(lod_jobs) = lod_jobs.as_mut() {
+        lod_jobs.clear();
+    }
+}
+
+/// Plugin that resets client world state on respawn and dimension change
+pub struct DimensionPlugin;
+
+impl Plugin for DimensionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CurrentDimension>()
+            .add_message::<DimensionChanged>()
+            .add_systems(Update, (handle_dimension_change, discard_chunk_meshes));
+    }
+}
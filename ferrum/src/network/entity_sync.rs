@@ -59,6 +59,11 @@ impl EntitySync {
         self.entities.remove(&entity_id);
     }
 
+    /// Forget every tracked entity, e.g. on dimension change.
+    pub fn clear(&mut self) {
+        self.entities.clear();
+    }
+
     pub fn update_entity_position(&mut self, entity_id: MinecraftEntityId, position: Vec3) {
         if let Some(entity) = self.entities.get_mut(&entity_id) {
             entity.update_position(position);
@@ -0,0 +1,149 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use super::connection::ConnectionError;
+
+/// How long to wait for a keepalive before giving up on the server. Vanilla
+/// servers send one every 15 seconds, so this allows one to go missing.
+pub const DEFAULT_KEEPALIVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Keepalives received from the server: the replies still to send, and
+/// when the last one arrived.
+#[derive(Debug)]
+pub struct KeepAlive {
+    timeout: Duration,
+    last_seen: Instant,
+    replies: VecDeque<u64>,
+}
+
+impl KeepAlive {
+    /// Start the window at `now`, when the connection was made.
+    pub fn new(timeout: Duration, now: Instant) -> Self {
+        Self {
+            timeout,
+            last_seen: now,
+            replies: VecDeque::new(),
+        }
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn last_seen(&self) -> Instant {
+        self.last_seen
+    }
+
+    /// Record keepalive `id` arriving at `now` and queue the same id to be
+    /// sent back.
+    pub fn receive(&mut self, id: u64, now: Instant) {
+        self.last_seen = self.last_seen.max(now);
+        self.replies.push_back(id);
+    }
+
+    /// Replies to send, oldest first.
+    pub fn take_replies(&mut self) -> impl Iterator<Item = u64> + '_ {
+        self.replies.drain(..)
+    }
+
+    /// Time left at `now` before the server counts as gone.
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.timeout
+            .saturating_sub(now.saturating_duration_since(self.last_seen))
+    }
+
+    /// Wait for `read`, failing with [`ConnectionError::TimedOut`] if the
+    /// window runs out first.
+    pub async fn read<T>(&self, read: impl Future<Output = T>) -> Result<T, ConnectionError> {
+        tokio::time::timeout(self.remaining(Instant::now()), read)
+            .await
+            .map_err(|_| ConnectionError::TimedOut)
+    }
+
+    /// Fail with [`ConnectionError::TimedOut`] if no keepalive has arrived
+    /// within the timeout before `now`. Other packets do not count: only
+    /// keepalives show the server still hears us.
+    pub fn check(&self, now: Instant) -> Result<(), ConnectionError> {
+        if now.saturating_duration_since(self.last_seen) > self.timeout {
+            return Err(ConnectionError::TimedOut);
+        }
+        Ok(())
+    }
+}
+
+impl Default for KeepAlive {
+    fn default() -> Self {
+        Self::new(DEFAULT_KEEPALIVE_TIMEOUT, Instant::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replies_echo_ids_in_order() {
+        let start = Instant::now();
+        let mut keepalive = KeepAlive::new(DEFAULT_KEEPALIVE_TIMEOUT, start);
+        keepalive.receive(u64::MAX, start);
+        keepalive.receive(7, start + Duration::from_secs(1));
+
+        let replies: Vec<u64> = keepalive.take_replies().collect();
+        assert_eq!(replies, [u64::MAX, 7]);
+        assert_eq!(keepalive.take_replies().count(), 0);
+        assert_eq!(keepalive.last_seen(), start + Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_regular_keepalives_never_time_out() {
+        let start = Instant::now();
+        let mut keepalive = KeepAlive::new(DEFAULT_KEEPALIVE_TIMEOUT, start);
+
+        // Ten minutes of a server sending one every 15 seconds, checked
+        // every frame in between
+        for second in 0..600u64 {
+            let now = start + Duration::from_secs(second);
+            if second % 15 == 0 {
+                keepalive.receive(second, now);
+            }
+            for frame in 0..60 {
+                let frame_time = now + Duration::from_millis(frame * 16);
+                assert!(keepalive.check(frame_time).is_ok(), "{second}s");
+            }
+        }
+    }
+
+    #[test]
+    fn test_times_out_after_the_window() {
+        let start = Instant::now();
+        let window = Duration::from_secs(20);
+        let mut keepalive = KeepAlive::new(window, start);
+        assert!(keepalive.check(start + window).is_ok());
+        assert!(matches!(
+            keepalive.check(start + window + Duration::from_millis(1)),
+            Err(ConnectionError::TimedOut)
+        ));
+
+        // A late keepalive restarts the window
+        let late = start + Duration::from_secs(25);
+        keepalive.receive(1, late);
+        assert_eq!(keepalive.remaining(late), window);
+        assert!(keepalive.check(late + Duration::from_secs(19)).is_ok());
+
+        // One handled out of order does not move it back
+        keepalive.receive(2, start);
+        assert_eq!(keepalive.last_seen(), late);
+    }
+
+    #[tokio::test]
+    async fn test_read_times_out_without_a_keepalive() {
+        let keepalive = KeepAlive::new(Duration::from_millis(50), Instant::now());
+        assert_eq!(keepalive.read(async { 7 }).await.ok(), Some(7));
+
+        let started = Instant::now();
+        let silent = keepalive.read(std::future::pending::<()>()).await;
+        assert!(matches!(silent, Err(ConnectionError::TimedOut)));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -1,7 +1,9 @@
-use super::{connect_and_play, ConnectionError};
+use super::{connect_and_play, ConnectionError, DEFAULT_KEEPALIVE_TIMEOUT};
 use std::net::SocketAddr;
 
 pub async fn perform_login(address: SocketAddr) -> Result<(), ConnectionError> {
     let address_str = address.to_string();
-    connect_and_play(address_str).await.map(|_| ())
+    connect_and_play(address_str, DEFAULT_KEEPALIVE_TIMEOUT)
+        .await
+        .map(|_| ())
 }
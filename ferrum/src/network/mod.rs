@@ -1,18 +1,22 @@
 pub mod chunk_loader;
 pub mod connection;
+pub mod dimension;
 pub mod entity_sync;
 pub mod handshake;
+pub mod keepalive;
 pub mod login;
 pub mod persistent_connection;
 pub mod player_position;
 
-pub use chunk_loader::{ChunkLoader, ChunkLoaderError};
+pub use chunk_loader::{ChunkLoader, ChunkLoaderError, ChunkLoaderPlugin};
 pub use connection::{connect_and_play, ConnectionError, ReceivedChunks};
+pub use dimension::{CurrentDimension, DimensionChanged, DimensionPlugin, DimensionSettings};
 pub use entity_sync::{EntitySync, EntitySyncPlugin};
 pub use handshake::perform_handshake;
+pub use keepalive::{KeepAlive, DEFAULT_KEEPALIVE_TIMEOUT};
 pub use login::perform_login;
 pub use persistent_connection::{
-    handle_incoming_packets, PersistentConnectionPlugin, ServerConnection,
+    handle_incoming_packets, PersistentConnectionPlugin, ServerConnection, ServerDisconnected,
 };
 pub use player_position::{
     create_position_packet, create_position_rotation_packet, create_status_only_packet,
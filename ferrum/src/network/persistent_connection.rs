@@ -1,29 +1,64 @@
 use azalea_protocol::packets::game::{
     s_chunk_batch_received::ServerboundChunkBatchReceived,
     s_keep_alive::ServerboundKeepAlive as GameServerboundKeepAlive, ClientboundGamePacket,
+    ServerboundGamePacket,
 };
 use bevy::prelude::*;
+use ferrum_protocol::{ChunkDataPacket, ConnectionState};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
-use tokio::time::Instant;
 
-use super::connection::ReceivedChunks;
+use super::chunk_loader::ChunkLoader;
+use super::connection::{kick_reason, ConnectionError, ReceivedChunks};
+use super::dimension::{handle_dimension_change, DimensionChanged};
+use super::keepalive::KeepAlive;
 
 /// Resource holding channels for the persistent server connection
 #[derive(Resource)]
 pub struct ServerConnection {
     pub player_id: i32,
     pub packet_receiver: UnboundedReceiver<ClientboundGamePacket>,
-    pub last_keepalive: Instant,
+    /// Packets for the connection task to write to the server.
+    pub packet_sender: UnboundedSender<ServerboundGamePacket>,
+    /// When the server last sent a keepalive.
+    pub keepalive: KeepAlive,
+    /// Protocol state handed over from the connection, in play until the
+    /// server kicks us.
+    pub protocol: ConnectionState,
 }
 
 impl ServerConnection {
-    pub fn new(player_id: i32, packet_receiver: UnboundedReceiver<ClientboundGamePacket>) -> Self {
+    /// A connection that gives up on the server after `keepalive_timeout`
+    /// without a keepalive.
+    pub fn new(
+        player_id: i32,
+        packet_receiver: UnboundedReceiver<ClientboundGamePacket>,
+        packet_sender: UnboundedSender<ServerboundGamePacket>,
+        protocol: ConnectionState,
+        keepalive_timeout: Duration,
+    ) -> Self {
         Self {
             player_id,
             packet_receiver,
-            last_keepalive: Instant::now(),
+            packet_sender,
+            keepalive: KeepAlive::new(keepalive_timeout, Instant::now()),
+            protocol,
         }
     }
+
+    /// Queue `packet` for the server. Fails once the connection task has
+    /// stopped.
+    fn send(&self, packet: ServerboundGamePacket) -> Result<(), ConnectionError> {
+        self.packet_sender
+            .send(packet)
+            .map_err(|_| ConnectionError::PacketWriteFailed)
+    }
+}
+
+/// Sent when the server closes the connection, with its reason as plain text
+#[derive(Message, Debug, Clone)]
+pub struct ServerDisconnected {
+    pub reason: String,
 }
 
 /// Channel to send outgoing packets to server
@@ -34,8 +69,12 @@ pub struct ServerPacketSender {
 
 /// Bevy system that continuously reads packets from the server
 pub fn handle_incoming_packets(
+    mut commands: Commands,
     mut server_conn: Option<ResMut<ServerConnection>>,
     mut received_chunks: ResMut<ReceivedChunks>,
+    mut chunk_loader: Option<ResMut<ChunkLoader>>,
+    mut dimension_changes: MessageWriter<DimensionChanged>,
+    mut disconnects: MessageWriter<ServerDisconnected>,
 ) {
     let Some(ref mut server_conn) = server_conn else {
         return;
@@ -46,8 +85,15 @@ pub fn handle_incoming_packets(
         match packet {
             ClientboundGamePacket::KeepAlive(keep_alive) => {
                 info!("Received KeepAlive: {}", keep_alive.id);
-                server_conn.last_keepalive = Instant::now();
-                // Send keepalive response via sender (implementation needed)
+                server_conn.keepalive.receive(keep_alive.id, Instant::now());
+                let replies: Vec<u64> = server_conn.keepalive.take_replies().collect();
+                for id in replies {
+                    if let Err(e) = server_conn.send(ServerboundGamePacket::KeepAlive(
+                        GameServerboundKeepAlive { id },
+                    )) {
+                        warn!("Failed to answer KeepAlive {}: {}", id, e);
+                    }
+                }
             }
             ClientboundGamePacket::LevelChunkWithLight(chunk_packet) => {
                 info!("Received chunk at ({}, {})", chunk_packet.x, chunk_packet.z);
@@ -58,14 +104,35 @@ pub fn handle_incoming_packets(
                 ) {
                     warn!("Failed to parse chunk: {}", e);
                 }
+                // Lit and meshed once the chunk loader gets to it
+                if let Some(chunk_loader) = chunk_loader.as_mut() {
+                    chunk_loader.queue_chunk(ChunkDataPacket::clone(&chunk_packet));
+                }
             }
             ClientboundGamePacket::ChunkBatchFinished(batch) => {
                 info!("ChunkBatchFinished: batch_size={}", batch.batch_size);
-                // Send acknowledgment (implementation needed)
+                let ack =
+                    ServerboundGamePacket::ChunkBatchReceived(ServerboundChunkBatchReceived {
+                        desired_chunks_per_tick: 5.0,
+                    });
+                if let Err(e) = server_conn.send(ack) {
+                    warn!("Failed to acknowledge chunk batch: {}", e);
+                }
+            }
+            ClientboundGamePacket::Respawn(respawn) => {
+                info!("Respawn into {}", respawn.common.dimension);
+                dimension_changes.write(DimensionChanged::from(&respawn));
+                // Leave the rest of the queue for next frame so chunks for the
+                // new dimension arrive after the old ones are cleared.
+                break;
             }
             ClientboundGamePacket::Disconnect(disconnect) => {
-                warn!("Server disconnected: {:?}", disconnect.reason);
-                // TODO: Handle disconnection properly
+                let reason = kick_reason(&mut server_conn.protocol, &disconnect);
+                warn!("Server disconnected: {}", reason);
+                disconnects.write(ServerDisconnected { reason });
+                // Nothing more arrives after a disconnect
+                commands.remove_resource::<ServerConnection>();
+                return;
             }
             _ => {
                 trace!(
@@ -75,6 +142,14 @@ pub fn handle_incoming_packets(
             }
         }
     }
+
+    if let Err(e) = server_conn.keepalive.check(Instant::now()) {
+        warn!("{}", e);
+        disconnects.write(ServerDisconnected {
+            reason: e.to_string(),
+        });
+        commands.remove_resource::<ServerConnection>();
+    }
 }
 
 /// Plugin for persistent server connection management
@@ -83,6 +158,10 @@ pub struct PersistentConnectionPlugin;
 impl Plugin for PersistentConnectionPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ReceivedChunks>()
-            .add_systems(Update, handle_incoming_packets);
+            .add_message::<ServerDisconnected>()
+            .add_systems(
+                Update,
+                handle_incoming_packets.before(handle_dimension_change),
+            );
     }
 }
@@ -6,6 +6,7 @@ use azalea_protocol::packets::game::{
     s_move_player_status_only::ServerboundMovePlayerStatusOnly,
 };
 use bevy::prelude::*;
+use ferrum_render::ViewModelCamera;
 use glam::Vec3;
 use std::time::{Duration, Instant};
 
@@ -95,7 +96,7 @@ pub fn create_status_only_packet(on_ground: bool) -> ServerboundMovePlayerStatus
 /// This should run every frame and will throttle updates internally
 pub fn send_player_position_updates(
     mut tracker: ResMut<PlayerPositionTracker>,
-    player_query: Query<&Transform, With<Camera3d>>,
+    player_query: Query<&Transform, (With<Camera3d>, Without<ViewModelCamera>)>,
 ) {
     // Get player transform (using camera as player proxy)
     let Ok(transform) = player_query.single() else {
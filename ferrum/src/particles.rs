@@ -1,4 +1,10 @@
+use crate::network::ReceivedChunks;
+use crate::player_controller::PlayerStep;
+use crate::texture_loader::BlockTextureAtlas;
+use bevy::camera::visibility::NoFrustumCulling;
 use bevy::prelude::*;
+use ferrum_core::LAVA;
+use ferrum_render::{AmbientParticle, ParticleSystem, TextureAtlas, ViewModelCamera};
 use rand::Rng;
 use std::time::Duration;
 
@@ -7,10 +13,42 @@ pub struct ParticlePlugin;
 
 impl Plugin for ParticlePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (update_particles, cleanup_dead_particles));
+        app.init_resource::<ParticleEffects>().add_systems(
+            Update,
+            (
+                update_particles,
+                cleanup_dead_particles,
+                spawn_step_particles,
+                spawn_ambient_particles,
+                spawn_effects_mesh,
+                update_effects_mesh,
+            ),
+        );
+    }
+}
+
+/// Pooled block-break and ambient particles, drawn as a single mesh textured
+/// with the block atlas.
+#[derive(Resource)]
+pub struct ParticleEffects {
+    pub system: ParticleSystem,
+    pub atlas: TextureAtlas,
+    mesh: Option<Handle<Mesh>>,
+}
+
+impl Default for ParticleEffects {
+    fn default() -> Self {
+        Self {
+            system: ParticleSystem::default(),
+            atlas: TextureAtlas::new(16),
+            mesh: None,
+        }
     }
 }
 
+#[derive(Component)]
+struct ParticleEffectsMesh;
+
 /// Component marking an entity as a particle
 #[derive(Component)]
 pub struct Particle {
@@ -29,42 +67,74 @@ impl Particle {
     }
 }
 
-/// Spawn block break particles at a given position
+/// Spawn block break particles for the block whose minimum corner is at
+/// `block_pos`.
 pub fn spawn_block_break_particles(
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &mut ResMut<Assets<StandardMaterial>>,
+    effects: &mut ParticleEffects,
+    block_pos: IVec3,
+    block_type: u32,
+) {
+    let ParticleEffects { system, atlas, .. } = effects;
+    system.spawn_block_break(block_pos.as_vec3(), block_type, atlas);
+}
+
+/// Spawn an ambient particle such as a lava spark.
+pub fn spawn_ambient_particle(
+    effects: &mut ParticleEffects,
+    kind: AmbientParticle,
     position: Vec3,
-    block_color: Color,
-    count: usize,
 ) {
-    let mut rng = rand::thread_rng();
+    effects.system.spawn_ambient(kind, position);
+}
 
-    for _ in 0..count {
-        // Random velocity for each particle
-        let velocity = Vec3::new(
-            rng.gen_range(-2.0..2.0),
-            rng.gen_range(1.0..4.0),
-            rng.gen_range(-2.0..2.0),
-        );
+/// Kick up fragments of the block underfoot on every footstep.
+fn spawn_step_particles(
+    mut steps: MessageReader<PlayerStep>,
+    mut effects: ResMut<ParticleEffects>,
+) {
+    let ParticleEffects { system, atlas, .. } = &mut *effects;
+    for PlayerStep(step) in steps.read() {
+        if step.block_below.as_u16() != 0 {
+            system.spawn_step(step.position, step.block_below.as_u16() as u32, atlas);
+        }
+    }
+}
 
-        // Small random offset from break position
-        let offset = Vec3::new(
-            rng.gen_range(-0.3..0.3),
-            rng.gen_range(-0.3..0.3),
-            rng.gen_range(-0.3..0.3),
-        );
+/// Blocks sampled around the camera each frame for ambient particles, as
+/// vanilla's animate tick does.
+const AMBIENT_SAMPLES: usize = 667;
+/// How far from the camera ambient particles are sampled, in blocks.
+const AMBIENT_RADIUS: i32 = 16;
+/// One in this many sampled lava surfaces throws a spark.
+const LAVA_SPARK_ODDS: u32 = 100;
 
-        commands.spawn((
-            Mesh3d(meshes.add(Cuboid::new(0.1, 0.1, 0.1))),
-            MeshMaterial3d(materials.add(StandardMaterial {
-                base_color: block_color,
-                unlit: false,
-                ..default()
-            })),
-            Transform::from_translation(position + offset),
-            Particle::new(0.5, velocity, 9.8),
-        ));
+/// Throw sparks, trailing smoke, off lava surfaces near the camera.
+fn spawn_ambient_particles(
+    received_chunks: Res<ReceivedChunks>,
+    camera_query: Query<&Transform, (With<Camera3d>, Without<ViewModelCamera>)>,
+    mut effects: ResMut<ParticleEffects>,
+) {
+    let Some(camera) = camera_query.iter().next() else {
+        return;
+    };
+
+    let block_at = |cell: IVec3| received_chunks.block_at(cell.x, cell.y, cell.z);
+    let centre = camera.translation.floor().as_ivec3();
+    let mut rng = rand::thread_rng();
+    for _ in 0..AMBIENT_SAMPLES {
+        let cell = centre
+            + IVec3::new(
+                rng.gen_range(-AMBIENT_RADIUS..=AMBIENT_RADIUS),
+                rng.gen_range(-AMBIENT_RADIUS..=AMBIENT_RADIUS),
+                rng.gen_range(-AMBIENT_RADIUS..=AMBIENT_RADIUS),
+            );
+        let lava_surface = block_at(cell) == LAVA && block_at(cell + IVec3::Y).as_u16() == 0;
+        if !lava_surface || rng.gen_range(0..LAVA_SPARK_ODDS) != 0 {
+            continue;
+        }
+        let position = cell.as_vec3() + Vec3::new(rng.gen(), 1.0, rng.gen());
+        spawn_ambient_particle(&mut effects, AmbientParticle::LavaSpark, position);
+        spawn_ambient_particle(&mut effects, AmbientParticle::Smoke, position);
     }
 }
 
@@ -131,3 +201,58 @@ fn cleanup_dead_particles(mut commands: Commands, particles: Query<(Entity, &Par
         }
     }
 }
+
+/// Create the shared particle mesh once the block atlas has loaded.
+fn spawn_effects_mesh(
+    mut commands: Commands,
+    mut effects: ResMut<ParticleEffects>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    texture_atlas: Option<Res<BlockTextureAtlas>>,
+) {
+    if effects.mesh.is_some() {
+        return;
+    }
+    let Some(texture_atlas) = texture_atlas else {
+        return;
+    };
+
+    let mesh = meshes.add(ParticleSystem::create_mesh());
+    commands.spawn((
+        Mesh3d(mesh.clone()),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color_texture: Some(texture_atlas.atlas_handle.clone()),
+            alpha_mode: AlphaMode::Blend,
+            double_sided: true,
+            cull_mode: None,
+            ..default()
+        })),
+        Transform::IDENTITY,
+        NoFrustumCulling,
+        ParticleEffectsMesh,
+    ));
+    effects.mesh = Some(mesh);
+}
+
+/// Step the pooled particles and rewrite their billboards to face the camera.
+fn update_effects_mesh(
+    time: Res<Time>,
+    mut effects: ResMut<ParticleEffects>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    camera_query: Query<&Transform, (With<Camera3d>, Without<ViewModelCamera>)>,
+) {
+    let Some(camera) = camera_query.iter().next() else {
+        return;
+    };
+
+    effects.system.update(time.delta_secs());
+
+    let Some(handle) = effects.mesh.clone() else {
+        return;
+    };
+    if let Some(mesh) = meshes.get_mut(&handle) {
+        effects
+            .system
+            .write_mesh(mesh, *camera.right(), *camera.up());
+    }
+}
@@ -1,8 +1,10 @@
+use crate::network::ReceivedChunks;
 use crate::title_screen::GameState;
 use bevy::input::mouse::MouseMotion;
 use bevy::prelude::*;
 use ferrum_physics::movement::MovementInput;
-use ferrum_physics::Player;
+use ferrum_physics::{CollisionEvent, Player, StepEvent, StepTracker};
+use ferrum_render::Sprinting;
 
 const EYE_HEIGHT: f32 = 1.62;
 const FEET_TO_GROUND_OFFSET: f32 = 0.5;
@@ -21,6 +23,7 @@ pub struct PlayerState {
     is_flying: bool,
     fly_speed: f32,
     pub ground_level: f32,
+    steps: StepTracker,
 }
 
 impl Default for PlayerState {
@@ -31,6 +34,7 @@ impl Default for PlayerState {
             is_flying: false,
             fly_speed: 20.0,
             ground_level: DEFAULT_GROUND_LEVEL,
+            steps: StepTracker::default(),
         }
     }
 }
@@ -39,9 +43,34 @@ impl PlayerState {
     pub fn set_spawn_position(&mut self, position: Vec3) {
         self.player.set_position(position);
         self.ground_level = position.y - FEET_TO_GROUND_OFFSET;
+        self.steps.reset();
+    }
+
+    pub fn player(&self) -> &Player {
+        &self.player
+    }
+
+    pub fn game_mode(&self) -> GameMode {
+        self.game_mode
+    }
+
+    pub fn set_game_mode(&mut self, game_mode: GameMode) {
+        self.game_mode = game_mode;
+        if game_mode == GameMode::Survival {
+            self.is_flying = false;
+        }
     }
 }
 
+/// Collision resolved by the player's physics step, for landing sounds, fall
+/// damage and other gameplay reactions.
+#[derive(Message, Debug, Clone, Copy)]
+pub struct PlayerCollision(pub CollisionEvent);
+
+/// Footstep taken by the player, for footstep sounds and particles.
+#[derive(Message, Debug, Clone, Copy)]
+pub struct PlayerStep(pub StepEvent);
+
 #[derive(Component)]
 pub struct PlayerCamera {
     pub sensitivity: f32,
@@ -63,20 +92,25 @@ pub struct PlayerControllerPlugin;
 
 impl Plugin for PlayerControllerPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<PlayerState>().add_systems(
-            Update,
-            (
-                toggle_game_mode,
-                camera_look,
-                player_movement,
-                player_jump,
-                player_sprint,
-                player_collision,
-                update_camera_position,
-            )
-                .chain()
-                .run_if(in_state(GameState::InGame)),
-        );
+        app.init_resource::<PlayerState>()
+            .init_resource::<Sprinting>()
+            .add_message::<PlayerCollision>()
+            .add_message::<PlayerStep>()
+            .add_systems(
+                Update,
+                (
+                    toggle_game_mode,
+                    camera_look,
+                    player_movement,
+                    player_jump,
+                    player_sprint,
+                    player_collision,
+                    player_steps,
+                    update_camera_position,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            );
     }
 }
 
@@ -119,6 +153,7 @@ fn camera_look(
 fn player_movement(
     keys: Res<ButtonInput<KeyCode>>,
     time: Res<Time>,
+    sprinting: Res<Sprinting>,
     mut state: ResMut<PlayerState>,
     query: Query<&Transform, With<PlayerCamera>>,
 ) {
@@ -136,7 +171,10 @@ fn player_movement(
             let forward_xz = Vec3::new(forward.x, 0.0, forward.z).normalize_or_zero();
             let right_xz = Vec3::new(right.x, 0.0, right.z).normalize_or_zero();
 
-            let mut input = MovementInput::default();
+            let mut input = MovementInput {
+                sprint: sprinting.0,
+                ..default()
+            };
 
             if keys.pressed(KeyCode::KeyW) {
                 input.forward = true;
@@ -235,35 +273,36 @@ fn player_jump(keys: Res<ButtonInput<KeyCode>>, mut state: ResMut<PlayerState>)
     }
 }
 
-fn player_sprint(keys: Res<ButtonInput<KeyCode>>, mut state: ResMut<PlayerState>) {
-    if state.game_mode != GameMode::Survival {
-        return;
-    }
+fn player_sprint(
+    keys: Res<ButtonInput<KeyCode>>,
+    state: Res<PlayerState>,
+    mut sprinting: ResMut<Sprinting>,
+) {
+    let sprint = state.game_mode == GameMode::Survival
+        && keys.pressed(KeyCode::ControlLeft)
+        && keys.pressed(KeyCode::KeyW);
+    sprinting.set_if_neq(Sprinting(sprint));
 }
 
-fn player_collision(mut state: ResMut<PlayerState>) {
+fn player_collision(
+    mut state: ResMut<PlayerState>,
+    mut collisions: MessageWriter<PlayerCollision>,
+) {
     if state.game_mode != GameMode::Survival {
         return;
     }
 
     // Simple ground plane collision at GROUND_LEVEL
+    let ground_level = state.ground_level;
     let player_pos = state.player.position();
 
-    if player_pos.y <= state.ground_level {
+    if player_pos.y <= ground_level {
         // Player is at or below ground — snap to ground, zero vertical velocity, mark
         // grounded
-        let mut pos = player_pos;
-        pos.y = state.ground_level;
-        state.player.set_position(pos);
-
-        let mut vel = state.player.velocity();
-        if vel.y < 0.0 {
-            vel.y = 0.0;
+        if let Some(landing) = state.player.resolve_ground(ground_level) {
+            collisions.write(PlayerCollision(landing));
         }
-        state.player.set_velocity(vel);
-
-        state.player.set_on_ground(true);
-    } else if player_pos.y <= state.ground_level + 0.05 {
+    } else if player_pos.y <= ground_level + 0.05 {
         // Very close to ground — still grounded (prevents jitter)
         state.player.set_on_ground(true);
     } else {
@@ -271,6 +310,21 @@ fn player_collision(mut state: ResMut<PlayerState>) {
     }
 }
 
+/// Take a footstep every stride the player walks on the ground.
+fn player_steps(
+    mut state: ResMut<PlayerState>,
+    received_chunks: Res<ReceivedChunks>,
+    mut steps: MessageWriter<PlayerStep>,
+) {
+    let state = &mut *state;
+    let step = state.steps.update(&state.player, |pos| {
+        received_chunks.block_at(pos.x, pos.y, pos.z)
+    });
+    if let Some(step) = step {
+        steps.write(PlayerStep(step));
+    }
+}
+
 fn update_camera_position(
     state: Res<PlayerState>,
     mut query: Query<&mut Transform, With<PlayerCamera>>,
@@ -0,0 +1,178 @@
+//! Singleplayer persistence: the player's save is loaded on entering the world
+//! and written back when the game quits.
+
+use crate::hud::HudState;
+use crate::inventory_screen::{InventoryState, ItemStack, HOTBAR_START};
+use crate::player_controller::{GameMode, PlayerCamera, PlayerState};
+use crate::title_screen::GameState;
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use ferrum_core::{PlayerSave, SavedItem, ARMOR_SLOT_START, OFFHAND_SLOT, PLAYER_SAVE_VERSION};
+use std::path::PathBuf;
+
+/// Directory of the world being played, which holds its player save.
+#[derive(Resource, Debug, Clone)]
+pub struct WorldDirectory(pub PathBuf);
+
+impl Default for WorldDirectory {
+    fn default() -> Self {
+        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+        Self(PathBuf::from(home).join(".ferrum/saves/world"))
+    }
+}
+
+pub struct PlayerSavePlugin;
+
+impl Plugin for PlayerSavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldDirectory>()
+            .add_systems(OnEnter(GameState::InGame), load_player_save)
+            .add_systems(Last, save_player_on_exit);
+    }
+}
+
+/// Save slot number of an [`InventoryState::slots`] index. Saves number the
+/// hotbar first, as vanilla does.
+fn save_slot(index: usize) -> i16 {
+    if index >= HOTBAR_START {
+        (index - HOTBAR_START) as i16
+    } else {
+        (index + 9) as i16
+    }
+}
+
+fn inventory_index(slot: i16) -> usize {
+    if slot < 9 {
+        slot as usize + HOTBAR_START
+    } else {
+        slot as usize - 9
+    }
+}
+
+fn saved_item(slot: i16, stack: &ItemStack) -> SavedItem {
+    SavedItem {
+        slot,
+        item_id: stack.item_id,
+        count: stack.count,
+        name: stack.name.clone(),
+    }
+}
+
+fn collect_player_save(
+    player: &PlayerState,
+    hud: &HudState,
+    inventory: &InventoryState,
+    camera: Option<&PlayerCamera>,
+) -> PlayerSave {
+    let mut items = Vec::new();
+    for (index, stack) in inventory.slots.iter().enumerate() {
+        if let Some(stack) = stack {
+            items.push(saved_item(save_slot(index), stack));
+        }
+    }
+    for (index, stack) in inventory.armor.iter().enumerate() {
+        if let Some(stack) = stack {
+            items.push(saved_item(ARMOR_SLOT_START + index as i16, stack));
+        }
+    }
+    if let Some(stack) = &inventory.offhand {
+        items.push(saved_item(OFFHAND_SLOT, stack));
+    }
+
+    let position = player.player().position();
+    PlayerSave {
+        version: PLAYER_SAVE_VERSION,
+        position: [position.x as f64, position.y as f64, position.z as f64],
+        yaw: camera.map_or(0.0, |camera| camera.yaw),
+        pitch: camera.map_or(0.0, |camera| camera.pitch),
+        health: hud.health,
+        hunger: hud.hunger,
+        xp_level: hud.xp_level,
+        xp_progress: hud.xp_progress,
+        game_mode: match player.game_mode() {
+            GameMode::Survival => ferrum_core::GameMode::Survival,
+            GameMode::Creative => ferrum_core::GameMode::Creative,
+        },
+        selected_slot: hud.selected_slot as u8,
+        inventory: items,
+    }
+}
+
+fn load_player_save(
+    world_dir: Res<WorldDirectory>,
+    mut player: ResMut<PlayerState>,
+    mut hud: ResMut<HudState>,
+    mut inventory: ResMut<InventoryState>,
+    mut cameras: Query<&mut PlayerCamera>,
+) {
+    let save = match PlayerSave::load(&world_dir.0) {
+        Ok(Some(save)) => save,
+        Ok(None) => return,
+        Err(err) => {
+            error!("Failed to load player save from {:?}: {}", world_dir.0, err);
+            return;
+        }
+    };
+
+    let [x, y, z] = save.position;
+    player.set_spawn_position(Vec3::new(x as f32, y as f32, z as f32));
+    player.set_game_mode(match save.game_mode {
+        ferrum_core::GameMode::Survival | ferrum_core::GameMode::Adventure => GameMode::Survival,
+        ferrum_core::GameMode::Creative | ferrum_core::GameMode::Spectator => GameMode::Creative,
+    });
+    for mut camera in &mut cameras {
+        camera.yaw = save.yaw;
+        camera.pitch = save.pitch;
+    }
+
+    hud.health = save.health;
+    hud.hunger = save.hunger;
+    hud.xp_level = save.xp_level;
+    hud.xp_progress = save.xp_progress;
+    hud.selected_slot = save.selected_slot as usize;
+
+    inventory.slots = std::array::from_fn(|_| None);
+    inventory.armor = std::array::from_fn(|_| None);
+    inventory.offhand = None;
+    for item in save.inventory {
+        let stack = Some(ItemStack {
+            item_id: item.item_id,
+            count: item.count,
+            name: item.name,
+            silk_touch: false,
+        });
+        let armor = item.slot - ARMOR_SLOT_START;
+        if item.slot == OFFHAND_SLOT {
+            inventory.offhand = stack;
+        } else if (0..inventory.armor.len() as i16).contains(&armor) {
+            inventory.armor[armor as usize] = stack;
+        } else if (0..inventory.slots.len() as i16).contains(&item.slot) {
+            inventory.slots[inventory_index(item.slot)] = stack;
+        } else {
+            warn!("Ignoring saved item in unknown slot {}", item.slot);
+        }
+    }
+
+    info!("Loaded player save from {:?}", world_dir.0);
+}
+
+/// Write the player save when the app is quitting from inside a world.
+fn save_player_on_exit(
+    mut exits: MessageReader<AppExit>,
+    game_state: Res<State<GameState>>,
+    world_dir: Res<WorldDirectory>,
+    player: Res<PlayerState>,
+    hud: Res<HudState>,
+    inventory: Res<InventoryState>,
+    cameras: Query<&PlayerCamera>,
+) {
+    if exits.read().count() == 0 || *game_state.get() != GameState::InGame {
+        return;
+    }
+
+    let save = collect_player_save(&player, &hud, &inventory, cameras.iter().next());
+    match save.save(&world_dir.0) {
+        Ok(()) => info!("Saved player to {:?}", world_dir.0),
+        Err(err) => error!("Failed to save player to {:?}: {}", world_dir.0, err),
+    }
+}
@@ -1,7 +1,10 @@
 use bevy::prelude::*;
+use ferrum_render::{ChunkGroupRendering, ChunkGroups, GltfExport};
 use std::fs;
+use std::path::Path;
 
-/// Plugin for capturing and saving screenshots
+/// Plugin for capturing and saving screenshots, and glTF exports of the
+/// loaded chunks
 pub struct ScreenshotPlugin;
 
 impl Plugin for ScreenshotPlugin {
@@ -11,7 +14,7 @@ impl Plugin for ScreenshotPlugin {
             warn!("Failed to create screenshots directory: {}", e);
         }
 
-        app.add_systems(Update, capture_screenshot);
+        app.add_systems(Update, (capture_screenshot, export_chunks));
     }
 }
 
@@ -27,3 +30,25 @@ fn capture_screenshot(input: Res<ButtonInput<KeyCode>>) {
         // The API location/structure changed in Bevy 0.18, needs investigation
     }
 }
+
+/// System that exports the loaded chunks as a glTF model when F8 is pressed
+fn export_chunks(
+    input: Res<ButtonInput<KeyCode>>,
+    groups: Option<Res<ChunkGroups>>,
+    rendering: Option<Res<ChunkGroupRendering>>,
+) {
+    if !input.just_pressed(KeyCode::F8) {
+        return;
+    }
+    let (Some(groups), Some(rendering)) = (groups, rendering) else {
+        warn!("No chunks loaded to export");
+        return;
+    };
+
+    let now = chrono::Local::now();
+    let filename = format!("screenshots/{}.glb", now.format("%Y-%m-%d_%H.%M.%S"));
+    match groups.export_gltf(&rendering.atlas, Path::new(&filename)) {
+        Ok(()) => info!("Exported the loaded chunks to {}", filename),
+        Err(e) => warn!("Failed to export chunks to {}: {}", filename, e),
+    }
+}
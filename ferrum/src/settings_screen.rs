@@ -6,8 +6,15 @@ pub struct SettingsScreenPlugin;
 
 impl Plugin for SettingsScreenPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<SettingsScreenState>()
-            .add_systems(Update, (toggle_settings_screen, handle_settings_input));
+        app.init_resource::<SettingsScreenState>().add_systems(
+            Update,
+            (
+                toggle_settings_screen,
+                handle_settings_input,
+                handle_setting_steps,
+                handle_fullbright_toggle,
+            ),
+        );
     }
 }
 
@@ -22,13 +29,31 @@ struct SettingsScreenUI;
 #[derive(Component)]
 struct CloseButton;
 
-#[derive(Component)]
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
 enum SettingSlider {
     Fov,
     RenderDistance,
     MasterVolume,
+    Brightness,
+}
+
+/// Button moving a slider setting by `delta`.
+#[derive(Component)]
+struct SettingStep {
+    slider: SettingSlider,
+    delta: f32,
 }
 
+/// Text showing a slider setting's current value.
+#[derive(Component)]
+struct SettingValue(SettingSlider);
+
+#[derive(Component)]
+struct FullbrightToggle;
+
+/// Brightness slider step, as in vanilla's 5% increments.
+const BRIGHTNESS_STEP: f32 = 0.05;
+
 fn toggle_settings_screen(
     mut commands: Commands,
     keyboard: Res<ButtonInput<KeyCode>>,
@@ -114,6 +139,18 @@ fn spawn_settings_screen(commands: &mut Commands, config: &Config) {
                         SettingSlider::RenderDistance,
                     );
 
+                    // Brightness Setting
+                    add_slider_row(
+                        parent,
+                        "Brightness",
+                        &brightness_label(config.client.brightness),
+                        SettingSlider::Brightness,
+                        BRIGHTNESS_STEP,
+                    );
+
+                    // Fullbright Setting
+                    add_toggle_row(parent, "Fullbright", config.client.fullbright);
+
                     // Audio Settings Section
                     add_section_header(parent, "Audio");
 
@@ -208,6 +245,194 @@ fn add_setting_row(
         });
 }
 
+/// Setting row whose value is changed with "-" and "+" buttons.
+fn add_slider_row(
+    parent: &mut ChildSpawnerCommands,
+    label: &str,
+    value: &str,
+    slider: SettingSlider,
+    step: f32,
+) {
+    parent
+        .spawn((Node {
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Row,
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            margin: UiRect::bottom(Val::Px(15.0)),
+            ..default()
+        },))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label.to_string()),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+
+            parent
+                .spawn((Node {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },))
+                .with_children(|parent| {
+                    add_step_button(parent, "-", slider, -step);
+                    parent.spawn((
+                        Text::new(value.to_string()),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.6, 0.8, 1.0)),
+                        SettingValue(slider),
+                    ));
+                    add_step_button(parent, "+", slider, step);
+                });
+        });
+}
+
+fn add_step_button(
+    parent: &mut ChildSpawnerCommands,
+    label: &str,
+    slider: SettingSlider,
+    delta: f32,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(32.0),
+                height: Val::Px(32.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.3, 0.3, 0.35)),
+            SettingStep { slider, delta },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label.to_string()),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+fn add_toggle_row(parent: &mut ChildSpawnerCommands, label: &str, enabled: bool) {
+    parent
+        .spawn((Node {
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Row,
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            margin: UiRect::bottom(Val::Px(15.0)),
+            ..default()
+        },))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label.to_string()),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(80.0),
+                        height: Val::Px(32.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.3, 0.3, 0.35)),
+                    FullbrightToggle,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(on_off(enabled)),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.6, 0.8, 1.0)),
+                    ));
+                });
+        });
+}
+
+fn brightness_label(brightness: f32) -> String {
+    if brightness <= 0.0 {
+        "Moody".to_string()
+    } else if brightness >= 1.0 {
+        "Bright".to_string()
+    } else {
+        format!("{:.0}%", brightness * 100.0)
+    }
+}
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled {
+        "On"
+    } else {
+        "Off"
+    }
+}
+
+fn handle_setting_steps(
+    interaction_query: Query<(&Interaction, &SettingStep), Changed<Interaction>>,
+    mut config: ResMut<Config>,
+    mut values: Query<(&mut Text, &SettingValue)>,
+) {
+    for (interaction, step) in &interaction_query {
+        if *interaction != Interaction::Pressed || step.slider != SettingSlider::Brightness {
+            continue;
+        }
+        let brightness = config.client.brightness + step.delta;
+        // Snap to the step grid so repeated presses don't accumulate error.
+        config.client.brightness =
+            ((brightness / BRIGHTNESS_STEP).round() * BRIGHTNESS_STEP).clamp(0.0, 1.0);
+
+        for (mut text, value) in &mut values {
+            if value.0 == SettingSlider::Brightness {
+                text.0 = brightness_label(config.client.brightness);
+            }
+        }
+    }
+}
+
+fn handle_fullbright_toggle(
+    interaction_query: Query<
+        (&Interaction, &Children),
+        (Changed<Interaction>, With<FullbrightToggle>),
+    >,
+    mut config: ResMut<Config>,
+    mut texts: Query<&mut Text>,
+) {
+    for (interaction, children) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        config.client.fullbright = !config.client.fullbright;
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = on_off(config.client.fullbright).to_string();
+            }
+        }
+    }
+}
+
 fn handle_settings_input(
     mut interaction_query: Query<(&Interaction, Option<&CloseButton>), Changed<Interaction>>,
     mut state: ResMut<SettingsScreenState>,
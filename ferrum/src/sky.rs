@@ -1,6 +1,10 @@
+use crate::network::ReceivedChunks;
+use crate::player_controller::PlayerCamera;
 use crate::title_screen::GameState;
 use bevy::pbr::{DistanceFog, FogFalloff};
 use bevy::prelude::*;
+use ferrum_core::BlockState;
+use ferrum_render::{fluid_at, CameraFluid};
 
 pub struct SkyPlugin;
 
@@ -16,6 +20,7 @@ impl Plugin for SkyPlugin {
                     update_directional_light,
                     update_ambient_light,
                     update_fog,
+                    update_camera_fluid,
                 )
                     .run_if(in_state(GameState::InGame)),
             );
@@ -37,6 +42,9 @@ pub struct DayNightCycle {
 
     /// Ambient light intensity (0.0 to 1.0)
     pub ambient_light: f32,
+
+    /// Constant sky color for dimensions without a day/night cycle
+    pub fixed_sky_color: Option<Color>,
 }
 
 impl Default for DayNightCycle {
@@ -46,6 +54,7 @@ impl Default for DayNightCycle {
             speed: 1.0,   // 1 tick/sec = slow cycle, daylight lasts ~100 real seconds
             sun_angle: 0.0,
             ambient_light: 0.5,
+            fixed_sky_color: None,
         }
     }
 }
@@ -77,6 +86,12 @@ fn update_day_night(time: Res<Time>, mut cycle: ResMut<DayNightCycle>) {
     // Calculate sun angle (0 at sunrise, PI at sunset)
     cycle.sun_angle = (cycle.time / 24000.0) * std::f32::consts::TAU;
 
+    // Dimensions without a sky keep constant lighting
+    if cycle.fixed_sky_color.is_some() {
+        cycle.ambient_light = 0.5;
+        return;
+    }
+
     // Calculate ambient light intensity
     // Brightest at noon (6000), darkest at midnight (18000)
     let time_normalized = cycle.time / 24000.0;
@@ -88,6 +103,11 @@ fn update_day_night(time: Res<Time>, mut cycle: ResMut<DayNightCycle>) {
 
 /// Update sky background color based on time of day
 fn update_sky_color(cycle: Res<DayNightCycle>, mut clear_color: ResMut<ClearColor>) {
+    if let Some(color) = cycle.fixed_sky_color {
+        clear_color.0 = color;
+        return;
+    }
+
     let time = cycle.time;
 
     // Define color palette for different times
@@ -157,8 +177,12 @@ fn update_directional_light(
     }
 }
 
-/// Update ambient light based on time
-fn update_ambient_light(cycle: Res<DayNightCycle>, mut ambient_query: Query<&mut AmbientLight>) {
+/// Update ambient light based on time. Ambient lights on cameras belong to
+/// fullbright and are left alone.
+fn update_ambient_light(
+    cycle: Res<DayNightCycle>,
+    mut ambient_query: Query<&mut AmbientLight, Without<Camera>>,
+) {
     for mut ambient in ambient_query.iter_mut() {
         // Brightness: 200 at night, 1000 at day
         ambient.brightness = 200.0 + cycle.ambient_light * 800.0;
@@ -210,6 +234,20 @@ fn update_fog(
     }
 }
 
+/// Track whether the camera is under water or lava, for the fluid overlay
+fn update_camera_fluid(
+    camera_query: Query<&GlobalTransform, With<PlayerCamera>>,
+    received_chunks: Res<ReceivedChunks>,
+    mut camera_fluid: ResMut<CameraFluid>,
+) {
+    let fluid = camera_query.iter().next().and_then(|camera| {
+        fluid_at(camera.translation(), |cell| {
+            BlockState::from(received_chunks.block_at(cell.x, cell.y, cell.z))
+        })
+    });
+    camera_fluid.set_if_neq(CameraFluid(fluid));
+}
+
 /// Linear interpolation between two colors
 fn lerp_color(a: Color, b: Color, t: f32) -> Color {
     let a_rgb = a.to_srgba();
@@ -1,5 +1,9 @@
+use crate::network::ReceivedChunks;
+use crate::player_controller::{PlayerCollision, PlayerState, PlayerStep};
 use bevy::audio::{AudioPlayer, PlaybackSettings, Volume};
 use bevy::prelude::*;
+use ferrum_core::BlockId;
+use ferrum_physics::{block_below, Axis};
 use std::f32::consts::PI;
 
 pub struct SoundPlugin;
@@ -7,9 +11,7 @@ pub struct SoundPlugin;
 impl Plugin for SoundPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<SoundAssets>()
-            .init_resource::<FootstepTimer>()
             .init_resource::<AmbientTimer>()
-            .init_resource::<LastPlayerPosition>()
             .add_systems(Startup, setup_sounds)
             .add_systems(
                 Update,
@@ -28,20 +30,52 @@ impl Plugin for SoundPlugin {
 struct SoundAssets {
     break_sound: Handle<AudioSource>,
     place_sound: Handle<AudioSource>,
-    step_sound: Handle<AudioSource>,
+    /// Footstep sounds by [`StepMaterial`].
+    step_sounds: Vec<Handle<AudioSource>>,
     ambient_sound: Handle<AudioSource>,
 }
 
-/// Timer for footstep sounds (plays every 0.4 seconds when moving)
-#[derive(Resource)]
-struct FootstepTimer {
-    timer: Timer,
+/// What a footstep sounds like, picked by the block walked on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StepMaterial {
+    Stone,
+    Soil,
+    Sand,
+    Wood,
+    Snow,
 }
 
-impl Default for FootstepTimer {
-    fn default() -> Self {
-        Self {
-            timer: Timer::from_seconds(0.4, TimerMode::Repeating),
+impl StepMaterial {
+    const ALL: [StepMaterial; 5] = [
+        StepMaterial::Stone,
+        StepMaterial::Soil,
+        StepMaterial::Sand,
+        StepMaterial::Wood,
+        StepMaterial::Snow,
+    ];
+
+    fn of(block: BlockId) -> Self {
+        match block.as_u16() {
+            // Grass, dirt, leaves, clay
+            2 | 3 | 13 | 20 => StepMaterial::Soil,
+            // Sand, gravel, soul sand
+            7 | 8 | 24 => StepMaterial::Sand,
+            // Log, planks
+            12 | 14 => StepMaterial::Wood,
+            18 => StepMaterial::Snow,
+            _ => StepMaterial::Stone,
+        }
+    }
+
+    /// Duration in seconds, envelope decay rate and how much of each new
+    /// noise sample is let through, lower being duller.
+    fn tone(self) -> (f32, f32, f32) {
+        match self {
+            StepMaterial::Stone => (0.05, 25.0, 1.0),
+            StepMaterial::Soil => (0.08, 18.0, 0.3),
+            StepMaterial::Sand => (0.12, 12.0, 0.5),
+            StepMaterial::Wood => (0.06, 30.0, 0.15),
+            StepMaterial::Snow => (0.1, 15.0, 0.2),
         }
     }
 }
@@ -60,12 +94,6 @@ impl Default for AmbientTimer {
     }
 }
 
-/// Track last player position to detect movement
-#[derive(Resource, Default)]
-struct LastPlayerPosition {
-    position: Vec3,
-}
-
 // ============================================================================
 // WAV Generation
 // ============================================================================
@@ -182,28 +210,31 @@ fn generate_place_sound() -> Vec<f32> {
     samples
 }
 
-/// Generate footstep sound: very short soft noise burst (0.05s)
-fn generate_step_sound() -> Vec<f32> {
+/// Generate footstep sound: very short soft noise burst, low-passed and
+/// stretched depending on the material
+fn generate_step_sound(material: StepMaterial) -> Vec<f32> {
     const SAMPLE_RATE: u32 = 44100;
-    const DURATION: f32 = 0.05;
-    let num_samples = (SAMPLE_RATE as f32 * DURATION) as usize;
+    let (duration, decay, brightness) = material.tone();
+    let num_samples = (SAMPLE_RATE as f32 * duration) as usize;
     let mut samples = Vec::with_capacity(num_samples);
 
     let mut rng_state: u32 = 0xABCDEF01;
+    let mut filtered = 0.0;
 
     for i in 0..num_samples {
         let t = i as f32 / SAMPLE_RATE as f32;
 
         // Very fast decay
-        let envelope = (-t * 25.0).exp();
+        let envelope = (-t * decay).exp();
 
-        // Noise
+        // Noise, through a one-pole low-pass
         rng_state ^= rng_state << 13;
         rng_state ^= rng_state >> 17;
         rng_state ^= rng_state << 5;
         let noise = (rng_state as f32 / u32::MAX as f32) * 2.0 - 1.0;
+        filtered += (noise - filtered) * brightness;
 
-        samples.push(noise * envelope * 0.3);
+        samples.push(filtered * envelope * 0.3);
     }
 
     samples
@@ -263,12 +294,15 @@ fn setup_sounds(mut commands: Commands, mut audio_assets: ResMut<Assets<AudioSou
     };
     let place_handle = audio_assets.add(place_source);
 
-    let step_samples = generate_step_sound();
-    let step_wav = generate_wav(&step_samples, 44100);
-    let step_source = AudioSource {
-        bytes: step_wav.into(),
-    };
-    let step_handle = audio_assets.add(step_source);
+    let step_handles = StepMaterial::ALL
+        .iter()
+        .map(|&material| {
+            let step_wav = generate_wav(&generate_step_sound(material), 44100);
+            audio_assets.add(AudioSource {
+                bytes: step_wav.into(),
+            })
+        })
+        .collect();
 
     let ambient_samples = generate_ambient_cave();
     let ambient_wav = generate_wav(&ambient_samples, 44100);
@@ -280,7 +314,7 @@ fn setup_sounds(mut commands: Commands, mut audio_assets: ResMut<Assets<AudioSou
     commands.insert_resource(SoundAssets {
         break_sound: break_handle,
         place_sound: place_handle,
-        step_sound: step_handle,
+        step_sounds: step_handles,
         ambient_sound: ambient_handle,
     });
 
@@ -318,40 +352,41 @@ fn play_place_sound(
     }
 }
 
-/// Play footstep sound when player is moving on ground
+/// Landings slower than this are too soft to be heard.
+const MIN_LANDING_SPEED: f32 = 4.0;
+
+/// Play a footstep sound for each step the player takes and one on landing,
+/// sounding like the block underfoot.
 fn play_footstep_sound(
     mut commands: Commands,
-    time: Res<Time>,
-    camera_query: Query<&Transform, With<Camera3d>>,
-    mut footstep_timer: ResMut<FootstepTimer>,
-    mut last_position: ResMut<LastPlayerPosition>,
+    state: Res<PlayerState>,
+    received_chunks: Res<ReceivedChunks>,
+    mut steps: MessageReader<PlayerStep>,
+    mut collisions: MessageReader<PlayerCollision>,
     sound_assets: Res<SoundAssets>,
 ) {
-    let Some(camera_transform) = camera_query.iter().next() else {
-        return;
-    };
-
-    let current_pos = camera_transform.translation;
-
-    let xz_delta = Vec2::new(
-        current_pos.x - last_position.position.x,
-        current_pos.z - last_position.position.z,
-    );
-    let is_moving = xz_delta.length() > 0.1 * time.delta_secs();
+    let mut blocks: Vec<BlockId> = steps
+        .read()
+        .map(|PlayerStep(step)| step.block_below)
+        .collect();
 
-    last_position.position = current_pos;
-
-    if is_moving {
-        footstep_timer.timer.tick(time.delta());
+    let landed = collisions.read().any(|PlayerCollision(event)| {
+        event.axis == Axis::Y && event.impact_speed >= MIN_LANDING_SPEED
+    });
+    if landed {
+        let below = block_below(state.player().position());
+        blocks.push(received_chunks.block_at(below.x, below.y, below.z));
+    }
 
-        if footstep_timer.timer.just_finished() {
-            commands.spawn((
-                AudioPlayer(sound_assets.step_sound.clone()),
-                PlaybackSettings::DESPAWN.with_volume(Volume::Linear(0.3)),
-            ));
-        }
-    } else {
-        footstep_timer.timer.reset();
+    for block in blocks {
+        let material = StepMaterial::of(block) as usize;
+        let Some(sound) = sound_assets.step_sounds.get(material) else {
+            continue;
+        };
+        commands.spawn((
+            AudioPlayer(sound.clone()),
+            PlaybackSettings::DESPAWN.with_volume(Volume::Linear(0.3)),
+        ));
     }
 }
 
@@ -1,66 +1,152 @@
 use bevy::asset::RenderAssetUsages;
 use bevy::prelude::*;
 use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use ferrum_render::{apply_atlas_swaps, AtlasImageSwapped, AtlasSwapPlugin, TextureAnimation};
 use std::path::PathBuf;
 
+const TILE_SIZE: u32 = 16;
+const ATLAS_SIZE: u32 = 256; // 16x16 tiles
+
+/// Animated textures, stored as frames stacked vertically in one image, and
+/// the block type each one belongs to.
+const ANIMATED_TEXTURES: [(&str, u32); 2] = [("water_still.png", 5), ("lava_still.png", 6)];
+
+/// Seconds per frame of water and lava (2 game ticks).
+const ANIMATION_FRAME_TIME: f32 = 0.1;
+
+/// First atlas tile holding the extra frames of animated textures.
+const FIRST_FRAME_TILE: u32 = 32;
+
 #[derive(Resource)]
 pub struct BlockTextureAtlas {
     pub atlas_handle: Handle<Image>,
     pub tile_size: u32,
+    /// Block types with animated textures and their frames in the atlas.
+    pub animations: Vec<(u32, TextureAnimation)>,
+}
+
+/// Texture directories layered over the base textures in
+/// `~/.ferrum/textures`, lowest first: a texture in a later pack replaces
+/// the same file from the ones before it. Changing the list, or just
+/// marking it changed, rebuilds the atlas with the textures now on disk.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ResourcePacks {
+    pub packs: Vec<PathBuf>,
 }
 
 pub struct TextureLoaderPlugin;
 
 impl Plugin for TextureLoaderPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, load_real_textures);
+        app.init_resource::<ResourcePacks>()
+            .add_plugins(AtlasSwapPlugin)
+            .add_systems(Startup, load_real_textures)
+            .add_systems(Update, reload_textures.before(apply_atlas_swaps));
     }
 }
 
-fn load_real_textures(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+/// Texture files and the atlas tile each one goes in. The layout never
+/// changes, so meshes built against one atlas stay valid for a rebuilt one.
+const TEXTURE_FILES: [(&str, u32); 29] = [
+    ("stone.png", 0),
+    ("dirt.png", 1),
+    ("grass_block_top.png", 2),
+    ("grass_block_side.png", 3),
+    ("cobblestone.png", 4),
+    ("oak_planks.png", 5),
+    ("oak_log.png", 6),
+    ("sand.png", 7),
+    ("gravel.png", 8),
+    ("iron_ore.png", 9),
+    ("gold_ore.png", 10),
+    ("diamond_ore.png", 11),
+    ("coal_ore.png", 12),
+    ("water_still.png", 13),
+    ("lava_still.png", 14),
+    ("bedrock.png", 15),
+    ("glass.png", 16),
+    ("oak_leaves.png", 17),
+    ("spruce_log.png", 18),
+    ("birch_log.png", 19),
+    ("netherrack.png", 20),
+    ("soul_sand.png", 21),
+    ("obsidian.png", 22),
+    ("glowstone.png", 23),
+    ("snow.png", 24),
+    ("ice.png", 25),
+    ("clay.png", 26),
+    ("terracotta.png", 27),
+    ("deepslate.png", 28),
+];
+
+fn load_real_textures(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    packs: Res<ResourcePacks>,
+) {
     info!("Loading real Minecraft textures...");
 
+    let (atlas_image, animations) = build_atlas(&texture_sources(&packs));
+    let atlas_handle = images.add(atlas_image);
+
+    commands.insert_resource(BlockTextureAtlas {
+        atlas_handle,
+        tile_size: TILE_SIZE,
+        animations,
+    });
+}
+
+/// Rebuild the atlas when the resource packs change and swap it into the
+/// materials drawing with the old one.
+fn reload_textures(
+    packs: Res<ResourcePacks>,
+    texture_atlas: Option<ResMut<BlockTextureAtlas>>,
+    mut images: ResMut<Assets<Image>>,
+    mut swaps: MessageWriter<AtlasImageSwapped>,
+) {
+    if !packs.is_changed() || packs.is_added() {
+        return;
+    }
+    let Some(mut texture_atlas) = texture_atlas else {
+        return;
+    };
+    info!(
+        "Reloading textures from {} resource packs...",
+        packs.packs.len()
+    );
+
+    let (atlas_image, animations) = build_atlas(&texture_sources(&packs));
+    if animations != texture_atlas.animations {
+        // Animated materials were set up for the old frame tiles
+        warn!("Animated textures changed frame count; restart to animate them correctly");
+    }
+    let new = images.add(atlas_image);
+    let old = std::mem::replace(&mut texture_atlas.atlas_handle, new.clone());
+    texture_atlas.animations = animations;
+    swaps.write(AtlasImageSwapped { old, new });
+}
+
+/// Directories to look for textures in, topmost pack first.
+fn texture_sources(packs: &ResourcePacks) -> Vec<PathBuf> {
     let texture_dir = PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| {
         warn!("HOME env var not set, using /tmp");
         "/tmp".to_string()
     }))
     .join(".ferrum/textures");
 
-    // Load individual textures and create atlas
-    let texture_files = vec![
-        ("stone.png", 0),
-        ("dirt.png", 1),
-        ("grass_block_top.png", 2),
-        ("grass_block_side.png", 3),
-        ("cobblestone.png", 4),
-        ("oak_planks.png", 5),
-        ("oak_log.png", 6),
-        ("sand.png", 7),
-        ("gravel.png", 8),
-        ("iron_ore.png", 9),
-        ("gold_ore.png", 10),
-        ("diamond_ore.png", 11),
-        ("coal_ore.png", 12),
-        ("water_still.png", 13),
-        ("lava_still.png", 14),
-        ("bedrock.png", 15),
-        ("glass.png", 16),
-        ("oak_leaves.png", 17),
-        ("spruce_log.png", 18),
-        ("birch_log.png", 19),
-        ("netherrack.png", 20),
-        ("soul_sand.png", 21),
-        ("obsidian.png", 22),
-        ("glowstone.png", 23),
-        ("snow.png", 24),
-        ("ice.png", 25),
-        ("clay.png", 26),
-        ("terracotta.png", 27),
-        ("deepslate.png", 28),
-    ];
-
-    const TILE_SIZE: u32 = 16;
-    const ATLAS_SIZE: u32 = 256; // 16x16 tiles
+    packs
+        .packs
+        .iter()
+        .rev()
+        .cloned()
+        .chain(std::iter::once(texture_dir))
+        .collect()
+}
+
+/// Pack the textures found in `sources` into an atlas, taking each file
+/// from the first source that has it. Returns the atlas image and the
+/// animations whose extra frames were packed into it.
+fn build_atlas(sources: &[PathBuf]) -> (Image, Vec<(u32, TextureAnimation)>) {
     let mut atlas_data = vec![0u8; (ATLAS_SIZE * ATLAS_SIZE * 4) as usize];
 
     // Fill with default grey
@@ -72,41 +158,57 @@ fn load_real_textures(mut commands: Commands, mut images: ResMut<Assets<Image>>)
     }
 
     let mut loaded_count = 0;
-    for (filename, tile_index) in texture_files {
-        let path = texture_dir.join(filename);
-
-        if let Ok(img_bytes) = std::fs::read(&path) {
+    let mut animations = Vec::new();
+    let mut next_frame_tile = FIRST_FRAME_TILE;
+    for (filename, tile_index) in TEXTURE_FILES {
+        let img_bytes = sources
+            .iter()
+            .find_map(|source| std::fs::read(source.join(filename)).ok());
+
+        if let Some(img_bytes) = img_bytes {
             if let Ok(img) = image::load_from_memory(&img_bytes) {
                 let rgba = img.to_rgba8();
                 let (width, height) = rgba.dimensions();
 
-                if width == TILE_SIZE && height == TILE_SIZE {
-                    // Calculate position in atlas
-                    let tile_x = (tile_index % 16) * TILE_SIZE;
-                    let tile_y = (tile_index / 16) * TILE_SIZE;
-
+                if width == TILE_SIZE && height > 0 && height % TILE_SIZE == 0 {
                     // Get raw byte data from image
                     let raw_data = rgba.as_raw();
+                    let frame_bytes = (TILE_SIZE * TILE_SIZE * 4) as usize;
 
-                    // Copy texture into atlas
-                    for y in 0..TILE_SIZE {
-                        for x in 0..TILE_SIZE {
-                            let src_idx = ((y * TILE_SIZE + x) * 4) as usize;
-                            let dst_x = tile_x + x;
-                            let dst_y = tile_y + y;
-                            let dst_idx = ((dst_y * ATLAS_SIZE + dst_x) * 4) as usize;
+                    // The first frame goes in the texture's own tile
+                    copy_tile(&mut atlas_data, &raw_data[..frame_bytes], tile_index);
+                    loaded_count += 1;
 
-                            atlas_data[dst_idx..dst_idx + 4]
-                                .copy_from_slice(&raw_data[src_idx..src_idx + 4]);
+                    let frame_count = height / TILE_SIZE;
+                    let block_type = ANIMATED_TEXTURES
+                        .iter()
+                        .find(|(name, _)| *name == filename)
+                        .map(|&(_, block_type)| block_type);
+                    if let Some(block_type) = block_type {
+                        let tiles_left = (ATLAS_SIZE / TILE_SIZE).pow(2) - next_frame_tile;
+                        if frame_count > 1 && frame_count - 1 <= tiles_left {
+                            let mut frames = vec![(tile_index % 16, tile_index / 16)];
+                            for frame in raw_data.chunks_exact(frame_bytes).skip(1) {
+                                copy_tile(&mut atlas_data, frame, next_frame_tile);
+                                frames.push((next_frame_tile % 16, next_frame_tile / 16));
+                                next_frame_tile += 1;
+                            }
+                            animations.push((
+                                block_type,
+                                TextureAnimation::new(frames, ANIMATION_FRAME_TIME),
+                            ));
                         }
                     }
-                    loaded_count += 1;
                 }
             }
         }
     }
 
-    info!("Loaded {} real Minecraft textures into atlas", loaded_count);
+    info!(
+        "Loaded {} real Minecraft textures into atlas ({} animated)",
+        loaded_count,
+        animations.len()
+    );
 
     let atlas_image = Image::new(
         Extent3d {
@@ -120,10 +222,24 @@ fn load_real_textures(mut commands: Commands, mut images: ResMut<Assets<Image>>)
         RenderAssetUsages::default(),
     );
 
-    let atlas_handle = images.add(atlas_image);
+    (atlas_image, animations)
+}
 
-    commands.insert_resource(BlockTextureAtlas {
-        atlas_handle,
-        tile_size: TILE_SIZE,
-    });
+/// Copy one tile's RGBA pixels into the atlas at `tile_index`.
+fn copy_tile(atlas_data: &mut [u8], tile: &[u8], tile_index: u32) {
+    // Calculate position in atlas
+    let tile_x = (tile_index % 16) * TILE_SIZE;
+    let tile_y = (tile_index / 16) * TILE_SIZE;
+
+    // Copy texture into atlas
+    for y in 0..TILE_SIZE {
+        for x in 0..TILE_SIZE {
+            let src_idx = ((y * TILE_SIZE + x) * 4) as usize;
+            let dst_x = tile_x + x;
+            let dst_y = tile_y + y;
+            let dst_idx = ((dst_y * ATLAS_SIZE + dst_x) * 4) as usize;
+
+            atlas_data[dst_idx..dst_idx + 4].copy_from_slice(&tile[src_idx..src_idx + 4]);
+        }
+    }
 }
@@ -482,7 +482,7 @@ fn generate_block_textures(mut commands: Commands, mut images: ResMut<Assets<Ima
     }
 
     // Create the atlas image
-    let atlas_image = Image::new(
+    let mut atlas_image = Image::new(
         Extent3d {
             width: atlas_width,
             height: atlas_height,
@@ -494,6 +494,12 @@ fn generate_block_textures(mut commands: Commands, mut images: ResMut<Assets<Ima
         RenderAssetUsages::default(),
     );
 
+    // Mipmaps stop distant blocks shimmering
+    let mips = ferrum_render::TextureAtlas::new(TILE_SIZE)
+        .generate_mipmaps(&atlas_image, TILE_SIZE.ilog2() + 1);
+    atlas_image.texture_descriptor.mip_level_count = mips.len() as u32;
+    atlas_image.data = Some(mips.concat());
+
     let atlas_handle = images.add(atlas_image);
 
     commands.insert_resource(BlockTextureAtlas {
@@ -30,6 +30,7 @@ pub enum GameState {
     Loading,
     InGame,
     Dead,
+    Disconnected,
 }
 
 #[derive(Component)]
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use ferrum_render::ViewModelCamera;
 use rand::Rng;
 
 /// Plugin for weather rendering (rain, snow, thunder)
@@ -54,7 +55,7 @@ pub struct WeatherParticle {
 fn update_rain_particles(
     weather: Res<WeatherState>,
     mut commands: Commands,
-    camera_query: Query<&Transform, With<Camera3d>>,
+    camera_query: Query<&Transform, (With<Camera3d>, Without<ViewModelCamera>)>,
     mut rain_particles: Query<(&mut Transform, &mut WeatherParticle), Without<Camera3d>>,
     time: Res<Time>,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -121,7 +122,7 @@ fn update_rain_particles(
 fn update_snow_particles(
     weather: Res<WeatherState>,
     mut commands: Commands,
-    camera_query: Query<&Transform, With<Camera3d>>,
+    camera_query: Query<&Transform, (With<Camera3d>, Without<ViewModelCamera>)>,
     mut snow_particles: Query<(&mut Transform, &mut WeatherParticle), Without<Camera3d>>,
     time: Res<Time>,
     mut meshes: ResMut<Assets<Mesh>>,
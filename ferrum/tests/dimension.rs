@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+use ferrum::entity_renderer::{EntityData, EntityType, ServerEntities};
+use ferrum::inventory_screen::InventoryState;
+use ferrum::network::{
+    ChunkLoader, CurrentDimension, DimensionChanged, DimensionPlugin, DimensionSettings,
+    ReceivedChunks,
+};
+use ferrum::sky::DayNightCycle;
+use ferrum_meshing_cpu::ChunkMesh;
+use ferrum_render::{ChunkBounds, ChunkGroups};
+use ferrum_world::{Chunk, ChunkPos};
+
+/// App with a few chunks, entities and a customised inventory loaded in the
+/// overworld.
+fn overworld_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins).add_plugins(DimensionPlugin);
+
+    let mut received_chunks = ReceivedChunks::new();
+    received_chunks.chunks.insert((0, 0), Vec::new());
+    received_chunks.chunks.insert((1, -1), Vec::new());
+    received_chunks.spawn_position = Some([0.0, 80.0, 0.0]);
+    app.insert_resource(received_chunks);
+
+    let mut chunk_loader = ChunkLoader::new();
+    chunk_loader
+        .world_mut()
+        .set_chunk(ChunkPos { x: 0, z: 0 }, Chunk::new());
+    app.insert_resource(chunk_loader);
+
+    let mut server_entities = ServerEntities::default();
+    server_entities.entities.insert(
+        7,
+        EntityData {
+            entity_type: EntityType::Zombie,
+            position: Vec3::new(1.0, 65.0, 1.0),
+            rotation: 0.0,
+            health: 20.0,
+        },
+    );
+    app.insert_resource(server_entities);
+
+    let mut inventory = InventoryState::default();
+    inventory.slots[0].as_mut().unwrap().count = 12;
+    app.insert_resource(inventory);
+
+    app.init_resource::<DayNightCycle>();
+    app.update();
+    app
+}
+
+fn enter(app: &mut App, dimension: &str) {
+    app.world_mut()
+        .write_message(DimensionChanged(DimensionSettings::for_name(dimension)));
+    app.update();
+}
+
+#[test]
+fn test_dimension_change_clears_chunks_and_entities() {
+    let mut app = overworld_app();
+    enter(&mut app, "minecraft:the_nether");
+
+    let received_chunks = app.world().resource::<ReceivedChunks>();
+    assert!(received_chunks.chunks.is_empty());
+    assert!(received_chunks.spawn_position.is_none());
+    assert!(app.world().resource::<ChunkLoader>().world().is_empty());
+    assert!(app.world().resource::<ServerEntities>().entities.is_empty());
+}
+
+#[test]
+fn test_dimension_change_despawns_chunk_meshes() {
+    let mut app = overworld_app();
+    let mut chunk_groups = ChunkGroups::default();
+    chunk_groups.insert(IVec3::new(0, 4, 0), IVec3::new(0, 64, 0), ChunkMesh::new());
+    app.insert_resource(chunk_groups);
+    let chunk_mesh = app
+        .world_mut()
+        .spawn(ChunkBounds::chunk(IVec3::new(0, 64, 0)))
+        .id();
+
+    enter(&mut app, "minecraft:the_nether");
+
+    assert!(app.world().get_entity(chunk_mesh).is_err());
+    assert!(app.world().resource::<ChunkGroups>().is_empty());
+}
+
+#[test]
+fn test_dimension_change_keeps_inventory() {
+    let mut app = overworld_app();
+    enter(&mut app, "minecraft:the_end");
+
+    let inventory = app.world().resource::<InventoryState>();
+    let first = inventory.slots[0].as_ref().expect("slot 0 should survive");
+    assert_eq!(first.item_id, 1);
+    assert_eq!(first.count, 12);
+    assert!(inventory.slots[1].is_some());
+}
+
+#[test]
+fn test_dimension_change_applies_new_layout_and_sky() {
+    let mut app = overworld_app();
+    enter(&mut app, "minecraft:the_nether");
+
+    let received_chunks = app.world().resource::<ReceivedChunks>();
+    assert_eq!(received_chunks.min_y, 0);
+    assert_eq!(received_chunks.dimension_height, 256);
+    assert_eq!(
+        app.world().resource::<CurrentDimension>().0.name,
+        "minecraft:the_nether"
+    );
+    assert!(app
+        .world()
+        .resource::<DayNightCycle>()
+        .fixed_sky_color
+        .is_some());
+
+    enter(&mut app, "minecraft:overworld");
+    assert_eq!(app.world().resource::<ReceivedChunks>().min_y, -64);
+    assert!(app
+        .world()
+        .resource::<DayNightCycle>()
+        .fixed_sky_color
+        .is_none());
+}
+
+#[test]
+fn test_unknown_dimension_uses_overworld_layout() {
+    let settings = DimensionSettings::for_name("custom:mining_world");
+    assert_eq!(settings.name, "custom:mining_world");
+    assert_eq!(settings.min_y, -64);
+    assert_eq!(settings.height, 384);
+    assert!(settings.has_skylight);
+}